@@ -0,0 +1,104 @@
+# LUMOS CLI messages (English)
+#
+# English is the fallback for every other locale. Checklist items
+# (checklist-*), categories (category-*), rule names (rule-*) and
+# suggestions (suggestion-*) default to the text produced by lumos-core,
+# so they only need entries in translated catalogs.
+
+## Security analysis
+
+security-report-title = Security Analysis Report
+security-schema = Schema: { $path }
+security-no-issues = ✓ No security issues found!
+security-all-passed = All checks passed. Your schema follows Solana security best practices.
+security-summary = Summary:
+security-critical-count = 🚨 { $count } critical issues
+security-warning-count = ⚠️  { $count } warnings
+security-info-count = ℹ️  { $count } informational
+security-critical-heading = CRITICAL ISSUES
+security-warnings-heading = WARNINGS
+security-info-heading = INFORMATIONAL
+security-recommendations = Recommendations:
+security-fix-critical = Fix all critical issues before deployment
+security-review-warnings = ⚠️  Review and address warnings
+security-see-docs = 📚 See: docs/security/static-analysis.md
+security-location = Location: { $location }
+security-occurrences =
+    { $count ->
+        [one] occurrence
+       *[other] occurrences
+    }
+security-more-members = ... and { $count } more (use --format json for the full list)
+
+## Audit checklist (Markdown)
+
+audit-title = Security Audit Checklist
+audit-generated-from = Generated from:
+audit-date = Date:
+audit-total-checks = Total Checks:
+audit-disclaimer-title = ⚠️ Important Disclaimer
+audit-disclaimer-lead = This automated checklist is a supplementary tool and does NOT replace professional security audits.
+audit-disclaimer-patterns = Generated checklists identify common vulnerability patterns based on schema structure
+audit-disclaimer-limits = They cannot detect logic bugs, business logic flaws, or complex attack vectors
+audit-disclaimer-review = **Always conduct thorough manual code review and professional security audits** before deploying to production
+audit-disclaimer-auditors = Consider engaging professional security auditors for mainnet deployments
+audit-how-to-title = How to Use This Checklist
+audit-how-unchecked = Not checked yet
+audit-how-checked = Verified and compliant
+audit-how-priority = Priority
+audit-how-review = Review each item during your security audit and check the box when verified.
+audit-context = Context
+audit-docs = Docs
+audit-additional-title = Additional Security Considerations
+audit-additional-logic = **Program Logic:** Verify business logic correctness
+audit-additional-errors = **Error Handling:** Ensure all error paths are covered
+audit-additional-testing = **Testing:** Comprehensive test suite including edge cases
+audit-additional-docs = **Documentation:** Code is well-documented
+audit-additional-deps = **Dependencies:** All dependencies are audited and up-to-date
+audit-status-title = Audit Status:
+audit-status-auditor = Auditor
+audit-status-started = Date Started
+audit-status-completed = Date Completed
+audit-status-findings = Findings
+audit-lifecycle-title = Account Lifecycle
+audit-lifecycle-intro = Lifecycle declared in the schema with `#[init]`, `#[init_space]`, `#[close]` and `#[realloc]`. Check the program against it.
+audit-lifecycle-account = Account
+audit-lifecycle-initialized-by = Initialized by
+audit-lifecycle-space = Space (bytes)
+audit-lifecycle-closed-by = Closed by
+audit-lifecycle-resizable = Resizable
+audit-lifecycle-closable = closable
+audit-lifecycle-yes = yes
+
+## HTML report
+
+report-title = LUMOS Report
+report-schema = Schema:
+report-summary = Summary
+report-type-count = { $count } types
+report-finding-counts = { $critical } critical findings, { $warnings } warnings, { $info } informational
+report-checklist-count = { $count } checklist items
+report-lint-count = { $count } lints
+report-filter = Filter { $table }...
+report-yes = yes
+report-no = no
+report-sizes-title = Account Sizes
+report-findings-title = Security Findings
+report-checklist-title = Audit Checklist
+report-lints-title = Lints
+report-col-type = Type
+report-col-account = Account
+report-col-bytes = Bytes
+report-col-rent = Rent (SOL)
+report-col-warnings = Warnings
+report-col-severity = Severity
+report-col-level = Level
+report-col-rule = Rule
+report-col-location = Location
+report-col-message = Message
+report-col-suggestion = Suggestion
+report-col-priority = Priority
+report-col-category = Category
+report-col-check = Check
+report-col-context = Context
+report-col-explanation = Explanation
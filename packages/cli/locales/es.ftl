@@ -0,0 +1,186 @@
+# LUMOS CLI messages (Spanish)
+
+## Security analysis
+
+security-report-title = Informe de análisis de seguridad
+security-schema = Esquema: { $path }
+security-no-issues = ✓ ¡No se encontraron problemas de seguridad!
+security-all-passed = Todas las comprobaciones pasaron. Tu esquema sigue las buenas prácticas de seguridad de Solana.
+security-summary = Resumen:
+security-critical-count = 🚨 { $count } problemas críticos
+security-warning-count = ⚠️  { $count } advertencias
+security-info-count = ℹ️  { $count } informativos
+security-critical-heading = PROBLEMAS CRÍTICOS
+security-warnings-heading = ADVERTENCIAS
+security-info-heading = INFORMATIVOS
+security-recommendations = Recomendaciones:
+security-fix-critical = Corrige todos los problemas críticos antes del despliegue
+security-review-warnings = ⚠️  Revisa y atiende las advertencias
+security-see-docs = 📚 Consulta: docs/security/static-analysis.md
+security-location = Ubicación: { $location }
+security-occurrences =
+    { $count ->
+        [one] ocurrencia
+       *[other] ocurrencias
+    }
+security-more-members = ... y { $count } más (usa --format json para ver la lista completa)
+
+## Audit checklist (Markdown)
+
+audit-title = Lista de verificación de auditoría de seguridad
+audit-generated-from = Generado a partir de:
+audit-date = Fecha:
+audit-total-checks = Total de comprobaciones:
+audit-disclaimer-title = ⚠️ Aviso importante
+audit-disclaimer-lead = Esta lista automatizada es una herramienta complementaria y NO sustituye una auditoría de seguridad profesional.
+audit-disclaimer-patterns = Las listas generadas identifican patrones de vulnerabilidad comunes a partir de la estructura del esquema
+audit-disclaimer-limits = No pueden detectar errores de lógica, fallos en la lógica de negocio ni vectores de ataque complejos
+audit-disclaimer-review = **Realiza siempre una revisión manual exhaustiva del código y auditorías de seguridad profesionales** antes de desplegar en producción
+audit-disclaimer-auditors = Considera contratar auditores de seguridad profesionales para despliegues en mainnet
+audit-how-to-title = Cómo usar esta lista
+audit-how-unchecked = Sin verificar
+audit-how-checked = Verificado y conforme
+audit-how-priority = Prioridad
+audit-how-review = Revisa cada elemento durante la auditoría de seguridad y marca la casilla cuando esté verificado.
+audit-context = Contexto
+audit-docs = Documentación
+audit-additional-title = Consideraciones de seguridad adicionales
+audit-additional-logic = **Lógica del programa:** Verifica que la lógica de negocio sea correcta
+audit-additional-errors = **Manejo de errores:** Asegura que todas las rutas de error estén cubiertas
+audit-additional-testing = **Pruebas:** Conjunto de pruebas completo, incluidos los casos límite
+audit-additional-docs = **Documentación:** El código está bien documentado
+audit-additional-deps = **Dependencias:** Todas las dependencias están auditadas y actualizadas
+audit-status-title = Estado de la auditoría:
+audit-status-auditor = Auditor
+audit-status-started = Fecha de inicio
+audit-status-completed = Fecha de finalización
+audit-status-findings = Hallazgos
+audit-lifecycle-title = Ciclo de vida de las cuentas
+audit-lifecycle-intro = Ciclo de vida declarado en el esquema con `#[init]`, `#[init_space]`, `#[close]` y `#[realloc]`. Comprueba el programa contra él.
+audit-lifecycle-account = Cuenta
+audit-lifecycle-initialized-by = Inicializada por
+audit-lifecycle-space = Espacio (bytes)
+audit-lifecycle-closed-by = Cerrada por
+audit-lifecycle-resizable = Redimensionable
+audit-lifecycle-closable = se puede cerrar
+audit-lifecycle-yes = sí
+
+## HTML report
+
+report-title = Informe de LUMOS
+report-schema = Esquema:
+report-summary = Resumen
+report-type-count = { $count } tipos
+report-finding-counts = { $critical } hallazgos críticos, { $warnings } advertencias, { $info } informativos
+report-checklist-count = { $count } elementos de la lista de verificación
+report-lint-count = { $count } avisos de estilo
+report-filter = Filtrar { $table }...
+report-yes = sí
+report-no = no
+report-sizes-title = Tamaños de cuentas
+report-findings-title = Hallazgos de seguridad
+report-checklist-title = Lista de verificación de auditoría
+report-lints-title = Avisos de estilo
+report-col-type = Tipo
+report-col-account = Cuenta
+report-col-bytes = Bytes
+report-col-rent = Renta (SOL)
+report-col-warnings = Advertencias
+report-col-severity = Severidad
+report-col-level = Nivel
+report-col-rule = Regla
+report-col-location = Ubicación
+report-col-message = Mensaje
+report-col-suggestion = Sugerencia
+report-col-priority = Prioridad
+report-col-category = Categoría
+report-col-check = Comprobación
+report-col-context = Contexto
+report-col-explanation = Explicación
+
+## Checklist categories
+
+category-account-validation = Validación de cuentas
+category-signer-checks = Comprobaciones de firmante
+category-arithmetic-safety = Seguridad aritmética
+category-access-control = Control de acceso
+category-state-transition = Transiciones de estado
+category-data-validation = Validación de datos
+category-rent-exemption = Exención de renta
+category-initialization = Inicialización
+category-account-closure = Cierre de cuentas
+category-confidentiality = Confidencialidad
+
+## Checklist items
+
+checklist-account-ownership = Verifica la propiedad de la cuenta (el programa es dueño de la cuenta)
+    .explanation = Asegura que la cuenta pertenece al programa para evitar ataques en los que se pasa una cuenta propiedad de otro programa.
+checklist-account-discriminator = Valida el discriminador de la cuenta
+    .explanation = El discriminador de 8 bytes de Anchor evita ataques de confusión de tipos. Verifica que se compruebe al deserializar.
+checklist-account-initialized = Comprueba que la cuenta esté inicializada antes de usarla
+    .explanation = Verifica que la cuenta se haya inicializado correctamente y no esté en un estado sin inicializar.
+checklist-rent-exemption = Verifica que la cuenta tenga lamports suficientes para la exención de renta
+    .explanation = Asegura que la cuenta tenga lamports suficientes para seguir exenta de renta y no sea eliminada.
+checklist-authority-signer = Verifica que el campo '{ $field }' requiera firma
+    .explanation = Los campos de autoridad deben validar que la transacción esté firmada con la clave privada correspondiente.
+checklist-authority-access = Asegura que solo '{ $field }' pueda realizar operaciones privilegiadas
+    .explanation = Implementa controles de acceso adecuados para impedir que usuarios no autorizados ejecuten funciones privilegiadas.
+checklist-checked-arithmetic = Verifica que '{ $field }' use operaciones aritméticas comprobadas
+    .explanation = Usa checked_add, checked_sub y checked_mul para evitar desbordamientos de enteros que podrían causar pérdida de fondos.
+checklist-value-bounds = Valida los límites y restricciones de '{ $field }'
+    .explanation = Asegura que el valor esté dentro de rangos aceptables y cumpla las restricciones de la lógica de negocio.
+checklist-owner-matches-signer = Valida que el propietario coincida con el firmante de la transacción en las modificaciones
+    .explanation = Antes de modificar el estado de la cuenta, verifica que el firmante sea el propietario o tenga la autorización adecuada.
+checklist-pubkey-not-default = Verifica que '{ $field }' no sea el programa del sistema ni la clave pública por defecto
+    .explanation = Asegura que los campos PublicKey no tengan valores por defecto (todo ceros) ni direcciones del programa del sistema salvo que sea intencional.
+checklist-vec-length = Valida la longitud de '{ $field }' antes de iterar
+    .explanation = Comprueba la longitud del vector para evitar un uso excesivo de cómputo o accesos fuera de rango.
+checklist-vec-max-size = Asegura que el tamaño máximo de '{ $field }' no supere los límites de la cuenta
+    .explanation = Verifica que el tamaño máximo posible de este vector no haga que la cuenta supere el límite de 10 MB de Solana.
+checklist-optional-none = Gestiona el caso None del campo opcional '{ $field }'
+    .explanation = Asegura que la lógica del programa gestione correctamente el caso en que este campo opcional sea None.
+checklist-sensitive-not-exposed = Mantén el campo sensible '{ $field }' fuera de logs, eventos e índices
+    .explanation = Los datos de las cuentas son públicos on-chain; este campo está marcado con #[sensitive] para réplicas off-chain. Registra e indexa solo copias redactadas (el impl Debug generado y el helper redact() de TypeScript) y nunca lo emitas en logs ni eventos del programa.
+checklist-init-instructions = Verifica que solo las instrucciones declaradas inicialicen la cuenta
+    .explanation = Crea la cuenta con `init` solo en las instrucciones declaradas; `init_if_needed` o una creación manual en otro lugar permite reinicializar una cuenta existente y reiniciar su estado.
+checklist-init-space = Reserva el tamaño del esquema al inicializar la cuenta
+    .explanation = Con poco espacio la serialización falla cuando los campos se llenan; con demasiado se bloquea renta extra en la cuenta. Deriva `space` del esquema (p. ej. `8 + INIT_SPACE`) en lugar de una constante contada a mano.
+checklist-realloc-rent-and-zero = Repón la renta y pon a cero el espacio nuevo al redimensionar la cuenta
+    .explanation = Usa `realloc::payer` para que la cuenta siga exenta de renta al crecer, y `realloc::zero = true` para que los bytes que quedan de una reducción anterior no se lean como datos.
+checklist-close-instructions = Verifica que solo las instrucciones declaradas cierren la cuenta
+    .explanation = Ninguna otra instrucción debe tener una restricción `close` sobre esta cuenta ni vaciar sus lamports.
+checklist-close-refund-destination = Verifica que el cierre reembolse los lamports al destino previsto
+    .explanation = Usa la restricción `close = destination` de Anchor y valida la cuenta de destino; de lo contrario un atacante puede desviar los lamports reembolsados hacia sí mismo.
+checklist-close-revival = Asegura que las cuentas cerradas no puedan revivirse
+    .explanation = Pon a cero los datos de la cuenta y transfiere todos los lamports en la misma instrucción para que la cuenta no pueda recargarse y reutilizarse más adelante en la transacción.
+checklist-close-authority = Verifica que solo '{ $field }' pueda cerrar la cuenta
+    .explanation = Exige la firma de la autoridad de cierre (p. ej. `has_one = close_authority`) para que usuarios arbitrarios no puedan cerrar la cuenta y quedarse con su renta.
+checklist-state-transitions = Verifica que las transiciones de estado sean válidas y atómicas
+    .explanation = Asegura que los cambios de estado sigan los patrones esperados y no puedan dejar la cuenta en un estado inconsistente.
+checklist-reentrancy = Comprueba vulnerabilidades de reentrada
+    .explanation = Si el programa realiza invocaciones entre programas, asegura que no pueda reentrarse en un estado inseguro.
+
+## Security rules
+
+rule-missing-signer = Falta comprobación de firmante
+rule-integer-overflow = Riesgo de desbordamiento de enteros
+rule-missing-owner-validation = Falta validación del propietario
+rule-uninitialized-account = Cuenta sin inicializar
+rule-re-initialization = Riesgo de reinicialización
+rule-unchecked-account-data = Datos de cuenta sin comprobar
+rule-no-discriminator = Sin discriminador
+rule-unchecked-arithmetic = Aritmética sin comprobar
+rule-duplicate-mutable-account = Cuenta mutable duplicada
+rule-unsafe-account-closure = Cierre de cuenta inseguro
+rule-type-confusion = Riesgo de confusión de tipos
+
+## Security suggestions
+
+suggestion-no-discriminator = Añade el atributo #[account] para activar la protección del discriminador de Anchor contra ataques de confusión de tipos
+suggestion-missing-signer = Asegura que este campo requiera validación de firmante en tu programa Anchor. En Anchor, usa el tipo Signer<'info> o añade una comprobación manual del firmante.
+suggestion-missing-owner-validation = Valida que el firmante de la transacción coincida con el campo owner antes de modificar el estado
+suggestion-unchecked-arithmetic = Usa operaciones aritméticas comprobadas (checked_add, checked_sub, checked_mul) para evitar desbordamientos de enteros
+suggestion-integer-overflow = Asegura que las operaciones aritméticas sobre este campo usen aritmética comprobada o saturada
+suggestion-unsafe-account-closure = Cierra la cuenta con la restricción `close = destination` de Anchor (junto con `has_one = close_authority` si existe una autoridad de cierre) para que los lamports vayan a un destino verificado y los datos se pongan a cero, en lugar de depender de un indicador
+suggestion-re-initialization = Añade un campo booleano 'is_initialized' o usa la restricción init de Anchor para evitar la reinicialización
+suggestion-type-confusion = Añade #[account] para obtener el discriminador de 8 bytes de Anchor, o guarda una etiqueta explícita del tipo de cuenta como primer campo y compruébala al deserializar
@@ -0,0 +1,182 @@
+# LUMOS CLI messages (Simplified Chinese)
+
+## Security analysis
+
+security-report-title = 安全分析报告
+security-schema = 模式文件：{ $path }
+security-no-issues = ✓ 未发现安全问题！
+security-all-passed = 所有检查均已通过。你的模式遵循 Solana 安全最佳实践。
+security-summary = 摘要：
+security-critical-count = 🚨 { $count } 个严重问题
+security-warning-count = ⚠️  { $count } 个警告
+security-info-count = ℹ️  { $count } 条提示
+security-critical-heading = 严重问题
+security-warnings-heading = 警告
+security-info-heading = 提示信息
+security-recommendations = 建议：
+security-fix-critical = 部署前修复所有严重问题
+security-review-warnings = ⚠️  检查并处理警告
+security-see-docs = 📚 参见：docs/security/static-analysis.md
+security-location = 位置：{ $location }
+security-occurrences = 处
+security-more-members = ……以及另外 { $count } 处（使用 --format json 查看完整列表）
+
+## Audit checklist (Markdown)
+
+audit-title = 安全审计检查清单
+audit-generated-from = 生成自：
+audit-date = 日期：
+audit-total-checks = 检查总数：
+audit-disclaimer-title = ⚠️ 重要声明
+audit-disclaimer-lead = 此自动生成的检查清单仅为辅助工具，不能替代专业的安全审计。
+audit-disclaimer-patterns = 生成的检查清单基于模式结构识别常见的漏洞模式
+audit-disclaimer-limits = 它无法发现逻辑错误、业务逻辑缺陷或复杂的攻击手段
+audit-disclaimer-review = **部署到生产环境前，务必进行全面的人工代码审查和专业安全审计**
+audit-disclaimer-auditors = 主网部署建议聘请专业安全审计人员
+audit-how-to-title = 如何使用本清单
+audit-how-unchecked = 尚未检查
+audit-how-checked = 已验证并符合要求
+audit-how-priority = 优先级
+audit-how-review = 在安全审计中逐项审查，验证后勾选对应复选框。
+audit-context = 上下文
+audit-docs = 文档
+audit-additional-title = 其他安全注意事项
+audit-additional-logic = **程序逻辑：** 验证业务逻辑的正确性
+audit-additional-errors = **错误处理：** 确保覆盖所有错误路径
+audit-additional-testing = **测试：** 包含边界情况的完整测试套件
+audit-additional-docs = **文档：** 代码有完善的文档
+audit-additional-deps = **依赖：** 所有依赖均已审计并保持最新
+audit-status-title = 审计状态：
+audit-status-auditor = 审计人
+audit-status-started = 开始日期
+audit-status-completed = 完成日期
+audit-status-findings = 发现的问题
+audit-lifecycle-title = 账户生命周期
+audit-lifecycle-intro = 模式中通过 `#[init]`、`#[init_space]`、`#[close]` 和 `#[realloc]` 声明的生命周期。请据此核对程序。
+audit-lifecycle-account = 账户
+audit-lifecycle-initialized-by = 初始化指令
+audit-lifecycle-space = 空间（字节）
+audit-lifecycle-closed-by = 关闭指令
+audit-lifecycle-resizable = 可调整大小
+audit-lifecycle-closable = 可关闭
+audit-lifecycle-yes = 是
+
+## HTML report
+
+report-title = LUMOS 报告
+report-schema = 模式文件：
+report-summary = 摘要
+report-type-count = { $count } 个类型
+report-finding-counts = { $critical } 个严重问题，{ $warnings } 个警告，{ $info } 条提示
+report-checklist-count = { $count } 个检查项
+report-lint-count = { $count } 个规范问题
+report-filter = 筛选{ $table }...
+report-yes = 是
+report-no = 否
+report-sizes-title = 账户大小
+report-findings-title = 安全问题
+report-checklist-title = 审计检查清单
+report-lints-title = 规范检查
+report-col-type = 类型
+report-col-account = 账户
+report-col-bytes = 字节
+report-col-rent = 租金 (SOL)
+report-col-warnings = 警告
+report-col-severity = 严重程度
+report-col-level = 级别
+report-col-rule = 规则
+report-col-location = 位置
+report-col-message = 说明
+report-col-suggestion = 建议
+report-col-priority = 优先级
+report-col-category = 类别
+report-col-check = 检查项
+report-col-context = 上下文
+report-col-explanation = 解释
+
+## Checklist categories
+
+category-account-validation = 账户验证
+category-signer-checks = 签名者检查
+category-arithmetic-safety = 算术安全
+category-access-control = 访问控制
+category-state-transition = 状态转换
+category-data-validation = 数据验证
+category-rent-exemption = 租金豁免
+category-initialization = 初始化
+category-account-closure = 账户关闭
+category-confidentiality = 机密性
+
+## Checklist items
+
+checklist-account-ownership = 验证账户所有权（账户归本程序所有）
+    .explanation = 确保账户归本程序所有，防止攻击者传入由其他程序拥有的账户。
+checklist-account-discriminator = 验证账户鉴别器
+    .explanation = Anchor 的 8 字节鉴别器可防止类型混淆攻击。请确认反序列化时会检查它。
+checklist-account-initialized = 使用前检查账户已初始化
+    .explanation = 确认账户已正确初始化，而不是处于未初始化状态。
+checklist-rent-exemption = 验证账户有足够的 lamports 以豁免租金
+    .explanation = 确保账户有足够的 lamports 保持租金豁免，不会被回收。
+checklist-authority-signer = 验证 '{ $field }' 字段需要签名
+    .explanation = 权限字段必须验证交易由对应的私钥签名。
+checklist-authority-access = 确保只有 '{ $field }' 能执行特权操作
+    .explanation = 实施适当的访问控制，防止未授权用户执行特权功能。
+checklist-checked-arithmetic = 验证 '{ $field }' 使用带检查的算术运算
+    .explanation = 使用 checked_add、checked_sub、checked_mul 防止整数溢出/下溢导致资金损失。
+checklist-value-bounds = 验证 '{ $field }' 的取值范围和约束
+    .explanation = 确保取值在可接受范围内并满足业务逻辑约束。
+checklist-owner-matches-signer = 修改状态时验证所有者与交易签名者一致
+    .explanation = 修改账户状态前，确认签名者是所有者或拥有相应授权。
+checklist-pubkey-not-default = 验证 '{ $field }' 不是系统程序或默认公钥
+    .explanation = 除非有意为之，确保 PublicKey 字段不是默认值（全零）或系统程序地址。
+checklist-vec-length = 遍历前验证 '{ $field }' 的长度
+    .explanation = 检查向量/数组长度，防止计算量过大或越界访问。
+checklist-vec-max-size = 确保 '{ $field }' 的最大长度不会超出账户限制
+    .explanation = 确认该向量的最大可能大小不会使账户超过 Solana 的 10MB 限制。
+checklist-optional-none = 处理可选字段 '{ $field }' 为 None 的情况
+    .explanation = 确保程序逻辑能正确处理该可选字段为 None 的情况。
+checklist-sensitive-not-exposed = 确保敏感字段 '{ $field }' 不出现在日志、事件和索引中
+    .explanation = 账户数据在链上是公开的；该字段标记了 #[sensitive]，用于链下镜像。只记录和索引脱敏后的副本（生成的 Debug 实现和 TypeScript 的 redact() 辅助函数），切勿在程序日志或事件中输出。
+checklist-init-instructions = 验证只有声明的指令会初始化该账户
+    .explanation = 仅在声明的指令中使用 `init` 创建账户；在其他地方使用 `init_if_needed` 或手动创建，会让已有账户被重新初始化并重置其状态。
+checklist-init-space = 初始化账户时按模式大小分配空间
+    .explanation = 空间太小会在字段写满后导致序列化失败；空间太大会在账户中锁定多余的租金。请根据模式推导 `space`（例如 `8 + INIT_SPACE`），而不是手工计算的常量。
+checklist-realloc-rent-and-zero = 调整账户大小时补足租金并将新空间清零
+    .explanation = 使用 `realloc::payer` 让账户在增长时保持免租，并使用 `realloc::zero = true`，避免之前缩小后残留的字节被当作数据读回。
+checklist-close-instructions = 验证只有声明的指令会关闭该账户
+    .explanation = 其他指令不应对该账户带有 `close` 约束，也不应转走其 lamports。
+checklist-close-refund-destination = 验证关闭账户时 lamports 退还到预期的目标账户
+    .explanation = 使用 Anchor 的 `close = destination` 约束并验证目标账户，否则攻击者可以将退还的 lamports 转给自己。
+checklist-close-revival = 确保已关闭的账户无法被复活
+    .explanation = 在同一条指令中清零账户数据并转出全部 lamports，使账户无法在同一交易中被重新充值并复用。
+checklist-close-authority = 验证只有 '{ $field }' 能关闭账户
+    .explanation = 要求关闭权限签名（例如 `has_one = close_authority`），防止任意用户关闭账户并取走租金。
+checklist-state-transitions = 验证状态转换有效且具有原子性
+    .explanation = 确保状态变更遵循预期模式，不会让账户处于不一致的状态。
+checklist-reentrancy = 检查重入漏洞
+    .explanation = 如果程序进行跨程序调用，确保它不会在不安全的状态下被重入。
+
+## Security rules
+
+rule-missing-signer = 缺少签名者检查
+rule-integer-overflow = 整数溢出风险
+rule-missing-owner-validation = 缺少所有者验证
+rule-uninitialized-account = 未初始化的账户
+rule-re-initialization = 重复初始化风险
+rule-unchecked-account-data = 未检查的账户数据
+rule-no-discriminator = 缺少鉴别器
+rule-unchecked-arithmetic = 未检查的算术运算
+rule-duplicate-mutable-account = 重复的可变账户
+rule-unsafe-account-closure = 不安全的账户关闭
+rule-type-confusion = 类型混淆风险
+
+## Security suggestions
+
+suggestion-no-discriminator = 添加 #[account] 属性以启用 Anchor 鉴别器，防止类型混淆攻击
+suggestion-missing-signer = 确保在 Anchor 程序中对该字段进行签名者验证。在 Anchor 中可使用 Signer<'info> 类型或添加手动签名检查。
+suggestion-missing-owner-validation = 在修改状态前，验证交易签名者与 owner 字段一致
+suggestion-unchecked-arithmetic = 使用带检查的算术运算（checked_add、checked_sub、checked_mul）防止整数溢出/下溢
+suggestion-integer-overflow = 确保对该字段的算术运算使用带检查或饱和的运算
+suggestion-unsafe-account-closure = 使用 Anchor 的 `close = destination` 约束关闭账户（若存在关闭权限，同时使用 `has_one = close_authority`），使 lamports 转入经过验证的目标账户并清零数据，而不是依赖标志位
+suggestion-re-initialization = 添加布尔字段 'is_initialized' 或使用 Anchor 的 init 约束防止重复初始化
+suggestion-type-confusion = 添加 #[account] 以获得 Anchor 的 8 字节鉴别器，或在第一个字段存储显式的账户类型标签并在反序列化时检查
@@ -0,0 +1,174 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Built-in benchmark for `lumos bench`
+//!
+//! Each phase runs once cold, paying one-time costs such as page faults and
+//! lazily initialized tables, then repeatedly warm. Allocations are counted
+//! by a global allocator that wraps the system allocator with two relaxed
+//! atomic increments, cheap enough to leave on for every command.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// System allocator that counts allocations and allocated bytes
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn count(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+}
+
+/// Allocation counters since process start
+fn allocations() -> (u64, u64) {
+    (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED_BYTES.load(Ordering::Relaxed),
+    )
+}
+
+/// Timings and allocations of one phase
+pub struct Phase {
+    /// Phase name, e.g. `parse`
+    pub name: &'static str,
+
+    /// First run
+    pub cold: Duration,
+
+    /// Median of the warm runs
+    pub warm_median: Duration,
+
+    /// Fastest warm run
+    pub warm_min: Duration,
+
+    /// Allocations in one warm run
+    pub allocations: u64,
+
+    /// Bytes allocated in one warm run (reallocations count their new size)
+    pub allocated_bytes: u64,
+}
+
+/// Run `work` once cold and `iterations` times warm, returning the last output
+///
+/// `setup` builds each run's input outside the timed section.
+pub fn measure<I, T, E>(
+    name: &'static str,
+    iterations: usize,
+    mut setup: impl FnMut() -> I,
+    mut work: impl FnMut(I) -> Result<T, E>,
+) -> Result<(Phase, T), E> {
+    let mut run = || -> Result<(Duration, u64, u64, T), E> {
+        let input = setup();
+        let (count_before, bytes_before) = allocations();
+        let started = Instant::now();
+        let output = black_box(work(black_box(input))?);
+        let elapsed = started.elapsed();
+        let (count_after, bytes_after) = allocations();
+        Ok((
+            elapsed,
+            count_after - count_before,
+            bytes_after - bytes_before,
+            output,
+        ))
+    };
+
+    let (cold, mut allocations, mut allocated_bytes, mut output) = run()?;
+    let mut warm = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let (elapsed, count, bytes, last) = run()?;
+        warm.push(elapsed);
+        (allocations, allocated_bytes, output) = (count, bytes, last);
+    }
+
+    warm.sort();
+    let phase = Phase {
+        name,
+        cold,
+        warm_median: warm.get(warm.len() / 2).copied().unwrap_or(cold),
+        warm_min: warm.first().copied().unwrap_or(cold),
+        allocations,
+        allocated_bytes,
+    };
+    Ok((phase, output))
+}
+
+/// `850ns`, `312.4µs`, `1.25ms` or `2.40s`
+pub fn format_time(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    }
+}
+
+/// `512 B`, `85.3 KiB` or `4.2 MiB`
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_allocations_of_each_run() {
+        let (phase, output) =
+            measure("collect", 5, || 64, |len| Ok::<_, ()>(vec![0u8; len])).unwrap();
+
+        assert_eq!(output.len(), 64);
+        assert_eq!(phase.name, "collect");
+        assert!(phase.allocations >= 1);
+        assert!(phase.allocated_bytes >= 64);
+        assert!(phase.warm_min <= phase.warm_median);
+    }
+
+    #[test]
+    fn formats_times_and_sizes_by_magnitude() {
+        assert_eq!(format_time(Duration::from_nanos(850)), "850ns");
+        assert_eq!(format_time(Duration::from_nanos(312_400)), "312.4µs");
+        assert_eq!(format_time(Duration::from_micros(1_250)), "1.25ms");
+        assert_eq!(format_time(Duration::from_millis(2_400)), "2.40s");
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(87_347), "85.3 KiB");
+    }
+}
@@ -0,0 +1,288 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Local bug report bundles (`lumos bug-report`)
+//!
+//! Packs what a maintainer needs to reproduce a report into one zip: the
+//! schema and every file it imports, `lumos.toml` and the settings in effect,
+//! versions, and the output of the failing command run again with
+//! `RUST_BACKTRACE=1`. Nothing is sent anywhere; users review the zip and
+//! attach it to an issue themselves.
+//!
+//! Entries are stored uncompressed, which every unzip tool reads, so the zip
+//! is written here instead of pulling in a zip crate.
+
+use anyhow::{Context, Result};
+use lumos_core::parser::parse_lumos_file;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Files of a bug report, in the order they are written
+#[derive(Debug, Default)]
+pub struct Bundle {
+    entries: Vec<(String, Vec<u8>)>,
+}
+
+impl Bundle {
+    /// Add a file at `name`, a `/`-separated path inside the zip
+    pub fn add(&mut self, name: impl Into<String>, contents: impl Into<Vec<u8>>) {
+        self.entries.push((name.into(), contents.into()));
+    }
+
+    /// Names of the files added so far
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// The files as a zip archive with stored (uncompressed) entries
+    pub fn to_zip(&self, modified: chrono::NaiveDateTime) -> Vec<u8> {
+        use chrono::{Datelike, Timelike};
+
+        // MS-DOS time and date, in two-second steps from 1980
+        let time = (modified.hour() << 11) | (modified.minute() << 5) | (modified.second() / 2);
+        let year = (modified.year().max(1980) - 1980) as u32;
+        let date = (year << 9) | (modified.month() << 5) | modified.day();
+        let (time, date) = (time as u16, date as u16);
+
+        let mut zip = Vec::new();
+        let mut central = Vec::new();
+        for (name, contents) in &self.entries {
+            let offset = zip.len() as u32;
+            let crc = crc32(contents);
+            // Shared by the local and central headers: version needed to
+            // extract, UTF-8 names, stored, time, date, CRC and sizes
+            let mut common = Vec::new();
+            put16(&mut common, 10);
+            put16(&mut common, 0x0800);
+            put16(&mut common, 0);
+            put16(&mut common, time);
+            put16(&mut common, date);
+            put32(&mut common, crc);
+            put32(&mut common, contents.len() as u32);
+            put32(&mut common, contents.len() as u32);
+            put16(&mut common, name.len() as u16);
+            put16(&mut common, 0);
+
+            put32(&mut zip, 0x0403_4b50);
+            zip.extend(&common);
+            zip.extend(name.as_bytes());
+            zip.extend(contents);
+
+            put32(&mut central, 0x0201_4b50);
+            put16(&mut central, 20);
+            central.extend(&common);
+            // Comment length, disk, internal and external attributes
+            put16(&mut central, 0);
+            put16(&mut central, 0);
+            put16(&mut central, 0);
+            put32(&mut central, 0);
+            put32(&mut central, offset);
+            central.extend(name.as_bytes());
+        }
+
+        let central_offset = zip.len() as u32;
+        zip.extend(&central);
+        put32(&mut zip, 0x0605_4b50);
+        put16(&mut zip, 0);
+        put16(&mut zip, 0);
+        put16(&mut zip, self.entries.len() as u16);
+        put16(&mut zip, self.entries.len() as u16);
+        put32(&mut zip, central.len() as u32);
+        put32(&mut zip, central_offset);
+        put16(&mut zip, 0);
+        zip
+    }
+}
+
+fn put16(out: &mut Vec<u8>, value: u16) {
+    out.extend(value.to_le_bytes());
+}
+
+fn put32(out: &mut Vec<u8>, value: u32) {
+    out.extend(value.to_le_bytes());
+}
+
+/// CRC-32 (IEEE), as zip entries record it
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// The schema at `path` and every file it imports, with their text
+///
+/// Files that don't parse are included without following their imports;
+/// they are usually what the report is about. Imports that can't be read
+/// are left out.
+pub fn schema_files(path: &Path) -> Result<Vec<(PathBuf, String)>> {
+    let source = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read schema: {}", path.display()))?;
+    let mut files = vec![(path.to_path_buf(), source)];
+    let mut next = 0;
+    while next < files.len() {
+        let (file, source) = &files[next];
+        next += 1;
+        let Ok(ast) = parse_lumos_file(source) else {
+            continue;
+        };
+        let dir = file.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+        for import in ast.imports {
+            let imported = dir.join(&import.path);
+            if files.iter().any(|(seen, _)| same_file(seen, &imported)) {
+                continue;
+            }
+            if let Ok(source) = fs::read_to_string(&imported) {
+                files.push((imported, source));
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Where a schema file goes in the bundle, relative to the schema's directory
+///
+/// Files outside that directory keep only their name, under `external/`.
+pub fn bundle_path(root: &Path, file: &Path) -> String {
+    let relative = file.strip_prefix(root).ok().filter(|relative| {
+        relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    });
+    match relative {
+        Some(relative) => {
+            let parts: Vec<_> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect();
+            format!("schema/{}", parts.join("/"))
+        }
+        None => format!(
+            "schema/external/{}",
+            file.file_name().unwrap_or_default().to_string_lossy()
+        ),
+    }
+}
+
+/// Output of a command run again for the report
+#[derive(Debug)]
+pub struct Trace {
+    /// Exit code, or `None` if the process was killed by a signal
+    pub status: Option<i32>,
+
+    /// Captured standard output
+    pub stdout: String,
+
+    /// Captured standard error
+    pub stderr: String,
+}
+
+/// Run `lumos <args>` again with backtraces on and colors off
+///
+/// A leading `lumos` is dropped, so a command can be pasted as typed.
+/// Standard input is closed, so prompts fail instead of waiting.
+pub fn run_command(args: &[OsString]) -> Result<Trace> {
+    let args = match args.first() {
+        Some(first) if first == "lumos" => &args[1..],
+        _ => args,
+    };
+    let exe = std::env::current_exe().context("Failed to locate the lumos executable")?;
+    let output = Command::new(exe)
+        .args(args)
+        .env("RUST_BACKTRACE", "1")
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run the command")?;
+    Ok(Trace {
+        status: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+/// Version of a tool on the PATH, from its `--version` output
+pub fn tool_version(tool: &str) -> Option<String> {
+    let output = Command::new(tool)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !version.is_empty()).then_some(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_a_stored_zip() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        let mut bundle = Bundle::default();
+        bundle.add("bug-report.md", "# Report\n");
+        bundle.add("schema/vault.lumos", "struct Vault { balance: u64 }\n");
+        let modified = chrono::NaiveDate::from_ymd_opt(2025, 3, 14)
+            .unwrap()
+            .and_hms_opt(15, 9, 26)
+            .unwrap();
+        let zip = bundle.to_zip(modified);
+
+        assert_eq!(&zip[..4], b"PK\x03\x04");
+        assert_eq!(&zip[30..43], b"bug-report.md");
+        assert_eq!(&zip[43..52], b"# Report\n");
+        // End of central directory: two entries, directory right after the files
+        let end = &zip[zip.len() - 22..];
+        assert_eq!(&end[..4], b"PK\x05\x06");
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+        let offset = u32::from_le_bytes([end[16], end[17], end[18], end[19]]) as usize;
+        assert_eq!(&zip[offset..offset + 4], b"PK\x01\x02");
+        assert_eq!(
+            bundle.names().collect::<Vec<_>>(),
+            ["bug-report.md", "schema/vault.lumos"]
+        );
+    }
+
+    #[test]
+    fn collects_imported_schema_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("shared")).unwrap();
+        fs::write(
+            root.join("vault.lumos"),
+            "import \"shared/tokens.lumos\";\nimport \"missing.lumos\";\nstruct Vault { amount: Amount }\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("shared/tokens.lumos"),
+            "import \"../vault.lumos\";\nstruct Amount { raw: u64 }\n",
+        )
+        .unwrap();
+
+        let files = schema_files(&root.join("vault.lumos")).unwrap();
+        let paths: Vec<_> = files
+            .iter()
+            .map(|(file, _)| bundle_path(root, file))
+            .collect();
+        assert_eq!(paths, ["schema/vault.lumos", "schema/shared/tokens.lumos"]);
+        assert_eq!(
+            bundle_path(root, Path::new("/elsewhere/common.lumos")),
+            "schema/external/common.lumos"
+        );
+        assert!(schema_files(&root.join("missing.lumos")).is_err());
+    }
+}
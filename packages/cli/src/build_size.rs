@@ -0,0 +1,111 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Compiled size check for generated Rust (`lumos generate --report-size`)
+//!
+//! The generated module is built in a scratch crate under `.lumos/size-check`
+//! twice: with every `Debug`/`Clone` derive, and as configured in `lumos.toml`
+//! with client features off, as an on-chain build would be. Host release
+//! builds stand in for BPF, so the numbers are for comparing configurations
+//! rather than exact program sizes.
+
+use crate::cache::{ensure_gitignore, STATE_DIR};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Scratch crate name; the library is built as `liblumos_size_check.rlib`
+const CRATE_NAME: &str = "lumos-size-check";
+
+/// Library sizes of the two builds, in bytes
+pub struct SizeReport {
+    /// With every `Debug`/`Clone` derive
+    pub full: u64,
+
+    /// As configured, without client features
+    pub configured: u64,
+}
+
+/// Build both variants of the generated module under `root/.lumos/size-check`
+///
+/// `features` are the Cargo features the configured code gates derives
+/// behind; they are declared but not enabled.
+pub fn measure(
+    root: &Path,
+    full_code: &str,
+    configured_code: &str,
+    features: &[String],
+    uses_anchor: bool,
+) -> Result<SizeReport> {
+    let state_dir = root.join(STATE_DIR);
+    let dir = state_dir.join("size-check");
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    ensure_gitignore(&state_dir)
+        .with_context(|| format!("Failed to write {}", state_dir.join(".gitignore").display()))?;
+
+    // `BorshSchema` derives need borsh's schema feature
+    let borsh = if configured_code.contains("borsh::BorshSchema") {
+        "borsh = { version = \"1.0\", features = [\"unstable__schema\"] }\n"
+    } else if uses_anchor {
+        ""
+    } else {
+        "borsh = \"1.0\"\n"
+    };
+    // Zero-copy structs outside Anchor derive bytemuck's Pod directly
+    let bytemuck = if configured_code.contains("bytemuck::Pod") {
+        "bytemuck = { version = \"1\", features = [\"derive\"] }\n"
+    } else {
+        ""
+    };
+    let dependencies = if uses_anchor {
+        format!("anchor-lang = \"0.30\"\n{}{}", borsh, bytemuck)
+    } else {
+        format!("{}{}solana-program = \"1.18\"\n", borsh, bytemuck)
+    };
+    let features: String = features
+        .iter()
+        .map(|feature| format!("{} = []\n", feature))
+        .collect();
+    let manifest = format!(
+        "[package]\nname = \"{}\"\nversion = \"0.0.0\"\nedition = \"2021\"\npublish = false\n\n\
+         [lib]\npath = \"lib.rs\"\n\n[features]\n{}\n[dependencies]\n{}\n\
+         # Keep the scratch crate out of any enclosing workspace\n[workspace]\n",
+        CRATE_NAME, features, dependencies
+    );
+    fs::write(dir.join("Cargo.toml"), manifest)
+        .with_context(|| format!("Failed to write {}", dir.join("Cargo.toml").display()))?;
+
+    Ok(SizeReport {
+        full: build(&dir, full_code)?,
+        configured: build(&dir, configured_code)?,
+    })
+}
+
+/// Build `code` as the scratch crate's library and return the library size
+fn build(dir: &Path, code: &str) -> Result<u64> {
+    fs::write(dir.join("lib.rs"), code)
+        .with_context(|| format!("Failed to write {}", dir.join("lib.rs").display()))?;
+
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = Command::new(cargo)
+        .args(["build", "--release", "--lib", "--quiet", "--manifest-path"])
+        .arg(dir.join("Cargo.toml"))
+        .output()
+        .context("Failed to run cargo")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Generated code failed to compile in {}:\n{}",
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        );
+    }
+
+    let library = dir
+        .join("target")
+        .join("release")
+        .join(format!("lib{}.rlib", CRATE_NAME.replace('-', "_")));
+    let metadata =
+        fs::metadata(&library).with_context(|| format!("Failed to read {}", library.display()))?;
+    Ok(metadata.len())
+}
@@ -0,0 +1,155 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! On-disk build cache for generated snippets
+//!
+//! Snippets live under `.lumos/cache/<target>/<key>` next to the schema, one
+//! file per type. Entries not used by the latest successful run are pruned,
+//! so the cache tracks the current schema instead of growing without bound.
+//! Cache I/O is best-effort: a failed read or write only costs regeneration.
+
+use lumos_core::generators::cache::SnippetCache;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory holding LUMOS build state, relative to the schema
+pub const STATE_DIR: &str = ".lumos";
+
+/// Snippet cache for one generator target
+pub struct DiskCache {
+    /// Entry directory, or `None` when caching is disabled
+    dir: Option<PathBuf>,
+
+    /// Keys read or written during this run
+    used: HashSet<u64>,
+
+    /// Number of snippets served from the cache
+    hits: usize,
+}
+
+impl DiskCache {
+    /// Open the cache for `target` under `root/.lumos/cache`
+    ///
+    /// Falls back to a disabled cache when the directory cannot be created.
+    pub fn open(root: &Path, target: &str) -> Self {
+        let state_dir = root.join(STATE_DIR);
+        let dir = state_dir.join("cache").join(target);
+
+        let ready = fs::create_dir_all(&dir).is_ok() && ensure_gitignore(&state_dir).is_ok();
+
+        Self {
+            dir: ready.then_some(dir),
+            ..Self::disabled()
+        }
+    }
+
+    /// Cache that never hits and stores nothing
+    pub fn disabled() -> Self {
+        Self {
+            dir: None,
+            used: HashSet::new(),
+            hits: 0,
+        }
+    }
+
+    /// Number of snippets served from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Remove entries not used during this run
+    pub fn prune(&self) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let key = name
+                .to_str()
+                .and_then(|name| u64::from_str_radix(name, 16).ok());
+            if key.map_or(true, |key| !self.used.contains(&key)) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    fn entry_path(&self, key: u64) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{:016x}", key)))
+    }
+}
+
+impl SnippetCache for DiskCache {
+    fn get(&mut self, key: u64) -> Option<String> {
+        let snippet = fs::read_to_string(self.entry_path(key)?).ok()?;
+        self.used.insert(key);
+        self.hits += 1;
+        Some(snippet)
+    }
+
+    fn put(&mut self, key: u64, snippet: &str) {
+        if let Some(path) = self.entry_path(key) {
+            if fs::write(path, snippet).is_ok() {
+                self.used.insert(key);
+            }
+        }
+    }
+}
+
+/// Keep `.lumos/` out of version control
+pub fn ensure_gitignore(state_dir: &Path) -> std::io::Result<()> {
+    let path = state_dir.join(".gitignore");
+    if path.exists() {
+        return Ok(());
+    }
+    fs::write(
+        path,
+        "# Generated by LUMOS; build state is not meant to be committed\n*\n",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumos_core::generators::rust;
+
+    #[test]
+    fn reuses_and_prunes_cached_snippets() {
+        let root = tempfile::tempdir().unwrap();
+        let ir = |source: &str| {
+            lumos_core::transform::transform_to_ir(
+                lumos_core::parser::parse_lumos_file(source).unwrap(),
+            )
+            .unwrap()
+        };
+        let generate = |ir: &[_]| {
+            let mut cache = DiskCache::open(root.path(), "rust");
+            let mut out = Vec::new();
+            rust::generate_cached_to(ir, None, &mut cache, &mut out).unwrap();
+            cache.prune();
+            (String::from_utf8(out).unwrap(), cache.hits())
+        };
+
+        let first = ir("struct A { x: u8 }\nstruct B { y: u16 }");
+        let (code, hits) = generate(&first);
+        assert_eq!(code, rust::generate_module(&first));
+        assert_eq!(hits, 0);
+
+        let edited = ir("struct A { x: u8 }\nstruct B { y: u32 }");
+        let (code, hits) = generate(&edited);
+        assert_eq!(code, rust::generate_module(&edited));
+        assert_eq!(hits, 1);
+
+        let entries = fs::read_dir(root.path().join(".lumos/cache/rust"))
+            .unwrap()
+            .count();
+        assert_eq!(entries, 2);
+        assert!(root.path().join(".lumos/.gitignore").exists());
+    }
+}
@@ -0,0 +1,1143 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! `lumos.toml` validation
+//!
+//! Every setting LUMOS reads is described by [`SCHEMA`], so a misspelled key
+//! or a value of the wrong type is reported with its path, the expected type
+//! and the closest known key, instead of being silently ignored or surfacing
+//! as a raw deserialize error.
+//!
+//! Settings are layered, highest precedence first: CLI flags, `LUMOS_*`
+//! environment variables, `lumos.toml`, then defaults. [`load`] is the single
+//! resolver every subcommand reads its settings through.
+
+use crate::i18n::SUPPORTED_LANGS;
+use anyhow::{Context, Result};
+use colored::*;
+use lumos_core::fuzz_generator::FuzzConfig;
+use lumos_core::generators::rust::RustConfig;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use toml::{Table, Value};
+
+/// Expected shape of a `lumos.toml` value
+enum Spec {
+    /// Any string
+    String,
+
+    /// One of a fixed set of strings
+    OneOf(&'static [&'static str]),
+
+    /// Table with known keys
+    Table(&'static [(&'static str, Spec)]),
+
+    /// Table with arbitrary keys (crate or type names), each matching the spec
+    MapOf(&'static Spec),
+
+    /// Array whose items match the spec
+    ListOf(&'static Spec),
+
+    /// Cargo dependency: a version string or a table
+    Dependency,
+}
+
+const DERIVE_MODE: Spec = Spec::OneOf(&["always", "feature", "skip"]);
+
+/// `[rust]` generator options, also accepted in `[profiles.<name>.rust]`
+const RUST_OPTIONS: &[(&str, Spec)] = &[
+    ("debug-clone", DERIVE_MODE),
+    ("client-feature", Spec::String),
+    ("borsh-schema", DERIVE_MODE),
+    (
+        "types",
+        Spec::MapOf(&Spec::Table(&[("debug-clone", DERIVE_MODE)])),
+    ),
+];
+
+/// `[typescript]` generator options, also accepted in `[profiles.<name>.typescript]`
+const TYPESCRIPT_OPTIONS: &[(&str, Spec)] = &[
+    ("codecs", Spec::OneOf(&["borsh", "kit"])),
+    (
+        "runtime",
+        Spec::OneOf(&["node", "react-native", "deno", "bun"]),
+    ),
+];
+
+const LINT_LEVEL: Spec = Spec::OneOf(&["allow", "warn", "deny"]);
+
+/// Languages `lumos generate` can write
+pub const TARGETS: [&str; 5] = ["rust", "typescript", "python", "jsonschema", "go"];
+
+/// Every section and key LUMOS reads
+const SCHEMA: &[(&str, Spec)] = &[
+    (
+        "output",
+        Spec::Table(&[
+            ("targets", Spec::ListOf(&Spec::OneOf(&TARGETS))),
+            ("directory", Spec::String),
+            ("rust", Spec::String),
+            ("typescript", Spec::String),
+            ("python", Spec::String),
+            ("jsonschema", Spec::String),
+            ("go", Spec::String),
+        ]),
+    ),
+    (
+        "i18n",
+        Spec::Table(&[("lang", Spec::OneOf(&SUPPORTED_LANGS))]),
+    ),
+    (
+        "fuzz",
+        Spec::Table(&[
+            ("crate-name", Spec::String),
+            ("crate-path", Spec::String),
+            ("dependencies", Spec::MapOf(&Spec::Dependency)),
+        ]),
+    ),
+    ("rust", Spec::Table(RUST_OPTIONS)),
+    ("typescript", Spec::Table(TYPESCRIPT_OPTIONS)),
+    (
+        "lint",
+        Spec::Table(&[
+            ("type-name-case", LINT_LEVEL),
+            ("field-name-case", LINT_LEVEL),
+            ("large-vec-in-account", LINT_LEVEL),
+            ("missing-account", LINT_LEVEL),
+        ]),
+    ),
+    (
+        "validation",
+        Spec::Table(&[("passes", Spec::ListOf(&Spec::String))]),
+    ),
+    (
+        "profiles",
+        Spec::MapOf(&Spec::Table(&[
+            ("targets", Spec::ListOf(&Spec::OneOf(&TARGETS))),
+            ("directory", Spec::String),
+            ("rust-file", Spec::String),
+            ("typescript-file", Spec::String),
+            ("python-file", Spec::String),
+            ("jsonschema-file", Spec::String),
+            ("go-file", Spec::String),
+            ("address", Spec::String),
+            ("rust", Spec::Table(RUST_OPTIONS)),
+            ("typescript", Spec::Table(TYPESCRIPT_OPTIONS)),
+        ])),
+    ),
+];
+
+/// A problem with one `lumos.toml` setting
+#[derive(Debug, PartialEq)]
+pub struct ConfigIssue {
+    /// Dotted key path, e.g. `rust.debug-clone`
+    pub key: String,
+
+    /// What is wrong
+    pub message: String,
+
+    /// Suggested fix
+    pub help: Option<String>,
+
+    /// Whether the key is unknown, rather than holding an invalid value
+    pub unknown_key: bool,
+}
+
+/// Parse `content` and check it against the schema
+pub fn check(content: &str) -> Result<Vec<ConfigIssue>, toml::de::Error> {
+    let document: Table = toml::from_str(content)?;
+    let mut issues = Vec::new();
+    check_table(&document, SCHEMA, "", &mut issues);
+    Ok(issues)
+}
+
+/// Read and validate the `lumos.toml` at `path`, if there is one
+///
+/// Invalid values fail with a report of every problem. Unknown keys are only
+/// warned about, once per file, so a setting from a newer release does not
+/// break an older CLI.
+pub fn read(path: &Path) -> Result<Option<String>> {
+    static WARNED: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let issues = check(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let (unknown, invalid): (Vec<_>, Vec<_>) =
+        issues.into_iter().partition(|issue| issue.unknown_key);
+
+    let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let first_read = WARNED
+        .lock()
+        .map(|mut warned| warned.get_or_insert_with(HashSet::new).insert(key))
+        .unwrap_or(true);
+    if first_read {
+        for issue in &unknown {
+            report(issue, false);
+        }
+    }
+
+    if !invalid.is_empty() {
+        for issue in &invalid {
+            report(issue, true);
+        }
+        anyhow::bail!(
+            "Invalid settings in {}: {} problem(s)",
+            path.display(),
+            invalid.len()
+        );
+    }
+
+    Ok(Some(content))
+}
+
+/// Print an issue as an error or a warning
+pub fn report(issue: &ConfigIssue, error: bool) {
+    let label = if error {
+        "error".red().bold()
+    } else {
+        "warning".yellow().bold()
+    };
+    errln!("{}: {}: {}", label, issue.key.bold(), issue.message);
+    if let Some(help) = &issue.help {
+        errln!("  {} {}", "help:".cyan().bold(), help);
+    }
+}
+
+/// Where a setting's value came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// Built-in default
+    Default,
+
+    /// A `lumos.toml` file
+    File(PathBuf),
+
+    /// A `[profiles.<name>]` section selected with `--profile`
+    Profile(String),
+
+    /// A `LUMOS_*` environment variable
+    Env(String),
+
+    /// A command-line flag
+    Flag(&'static str),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Profile(name) => write!(f, "profile {}", name),
+            Source::Env(name) => write!(f, "${}", name),
+            Source::Flag(flag) => write!(f, "{}", flag),
+        }
+    }
+}
+
+/// Settings given as global CLI flags
+#[derive(Debug, Default)]
+pub struct Flags {
+    /// `--lang`
+    pub lang: Option<String>,
+
+    /// `--verbose`: print the resolved settings when they are loaded
+    pub verbose: bool,
+}
+
+static FLAGS: OnceLock<Flags> = OnceLock::new();
+
+/// Record the global flags; call once at startup
+pub fn init(flags: Flags) {
+    let _ = FLAGS.set(flags);
+}
+
+/// `lumos generate` options from `[output]` and a `[profiles.<name>]` section
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    /// Generate Rust
+    pub rust: bool,
+
+    /// Generate TypeScript
+    pub typescript: bool,
+
+    /// Generate Python
+    pub python: bool,
+
+    /// Generate a JSON Schema document
+    pub jsonschema: bool,
+
+    /// Generate Go
+    pub go: bool,
+
+    /// Output directory, relative to the `lumos.toml` that defines it
+    pub directory: Option<PathBuf>,
+
+    /// Rust output file name
+    pub rust_file: String,
+
+    /// TypeScript output file name
+    pub typescript_file: String,
+
+    /// Python output file name
+    pub python_file: String,
+
+    /// JSON Schema output file name
+    pub jsonschema_file: String,
+
+    /// Go output file name
+    pub go_file: String,
+
+    /// Anchor program id
+    pub address: Option<String>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            rust: true,
+            typescript: true,
+            python: false,
+            jsonschema: false,
+            go: false,
+            directory: None,
+            rust_file: "generated.rs".to_string(),
+            typescript_file: "generated.ts".to_string(),
+            python_file: "generated.py".to_string(),
+            jsonschema_file: "generated.schema.json".to_string(),
+            go_file: "generated.go".to_string(),
+            address: None,
+        }
+    }
+}
+
+impl Profile {
+    /// Generate exactly the languages in `targets`, if it is a list
+    fn select(&mut self, targets: Option<&Value>) {
+        if let Some(targets) = targets.and_then(Value::as_array) {
+            let selected = |target: &str| targets.iter().any(|t| t.as_str() == Some(target));
+            self.rust = selected("rust");
+            self.typescript = selected("typescript");
+            self.python = selected("python");
+            self.jsonschema = selected("jsonschema");
+            self.go = selected("go");
+        }
+    }
+}
+
+/// Settings layered from every source, with the source of each value
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    table: Table,
+    sources: BTreeMap<String, Source>,
+}
+
+impl Settings {
+    /// Value at a dotted key, e.g. `rust.debug-clone`
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        let mut parts = key.split('.');
+        let mut value = self.table.get(parts.next()?)?;
+        for part in parts {
+            value = value.get(part)?;
+        }
+        Some(value)
+    }
+
+    /// String value at a dotted key
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    /// The settings as `lumos.toml` content, for the section loaders
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(&self.table).context("Failed to render settings")
+    }
+
+    /// The settings with defaults filled in for every key not set
+    pub fn with_defaults(self) -> Result<Self> {
+        let mut table = defaults()?;
+        let mut sources = BTreeMap::new();
+        flatten(&table, "", &mut |key, _| {
+            sources.insert(key.to_string(), Source::Default);
+        });
+
+        merge(&mut table, self.table);
+        sources.extend(self.sources);
+        Ok(Self { table, sources })
+    }
+
+    /// Every setting as `key = value`, with its source
+    pub fn entries(&self) -> Vec<(String, String, &Source)> {
+        let mut entries = Vec::new();
+        flatten(&self.table, "", &mut |key, value| {
+            let source = self.source_of(key).unwrap_or(&Source::Default);
+            entries.push((key.to_string(), value.to_string(), source));
+        });
+        entries
+    }
+
+    /// Source of the setting at `key`, or of the nearest table above it
+    fn source_of(&self, key: &str) -> Option<&Source> {
+        let mut key = key;
+        loop {
+            if let Some(source) = self.sources.get(key) {
+                return Some(source);
+            }
+            key = &key[..key.rfind('.')?];
+        }
+    }
+
+    /// Layer `file` with the selected `profile`, environment variables and
+    /// `flags`
+    fn layer(
+        file: Option<(&Path, Table)>,
+        profile: Option<&str>,
+        env: impl Fn(&str) -> Option<String>,
+        flags: &Flags,
+    ) -> Result<Self, Vec<ConfigIssue>> {
+        let mut settings = Self::default();
+        let mut issues = Vec::new();
+
+        if let Some((path, table)) = file {
+            flatten(&table, "", &mut |key, _| {
+                settings
+                    .sources
+                    .insert(key.to_string(), Source::File(path.to_path_buf()));
+            });
+            settings.table = table;
+        }
+
+        if let Some(name) = profile {
+            settings.select_profile(name, &mut issues);
+        }
+
+        for (key, spec) in overridable() {
+            let name = env_var(&key);
+            if let Some(value) = env(&name) {
+                settings.set(&key, value, spec, Source::Env(name), &mut issues);
+            }
+        }
+
+        if let Some(lang) = &flags.lang {
+            let spec = &Spec::OneOf(&SUPPORTED_LANGS);
+            settings.set(
+                "i18n.lang",
+                lang.clone(),
+                spec,
+                Source::Flag("--lang"),
+                &mut issues,
+            );
+        }
+
+        if issues.is_empty() {
+            Ok(settings)
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// Apply `[profiles.<name>.rust]` over `[rust]` and
+    /// `[profiles.<name>.typescript]` over `[typescript]`
+    fn select_profile(&mut self, name: &str, issues: &mut Vec<ConfigIssue>) {
+        let profiles = self.get("profiles").and_then(Value::as_table);
+        let Some(profile) = profiles.and_then(|profiles| profiles.get(name)) else {
+            let known: Vec<&str> = profiles
+                .map(|profiles| profiles.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            let help = match closest(name, &known) {
+                Some(known) => format!("did you mean `{}`?", known),
+                None if known.is_empty() => "no [profiles.<name>] sections are defined".to_string(),
+                None => format!("defined profiles: {}", known.join(", ")),
+            };
+            issues.push(ConfigIssue {
+                key: format!("profiles.{}", name),
+                message: "profile not found".to_string(),
+                help: Some(help),
+                unknown_key: false,
+            });
+            return;
+        };
+
+        let sections: Vec<(&str, Table)> = ["rust", "typescript"]
+            .into_iter()
+            .filter_map(|section| Some((section, profile.get(section)?.as_table()?.clone())))
+            .collect();
+        let mut overrides = Table::new();
+        for (section, options) in sections {
+            flatten(&options, section, &mut |key, _| {
+                self.sources
+                    .insert(key.to_string(), Source::Profile(name.to_string()));
+            });
+            overrides.insert(section.to_string(), Value::Table(options));
+        }
+        merge(&mut self.table, overrides);
+    }
+
+    /// `lumos generate` options of `[output]`, with those of the profile
+    /// `name` over them
+    pub fn profile(&self, name: Option<&str>) -> Profile {
+        let mut profile = Profile::default();
+        let output = |key: &str| self.get_str(&format!("output.{}", key)).map(str::to_string);
+        profile.select(self.get("output.targets"));
+        profile.directory = output("directory").map(PathBuf::from);
+        profile.rust_file = output("rust").unwrap_or(profile.rust_file);
+        profile.typescript_file = output("typescript").unwrap_or(profile.typescript_file);
+        profile.python_file = output("python").unwrap_or(profile.python_file);
+        profile.jsonschema_file = output("jsonschema").unwrap_or(profile.jsonschema_file);
+        profile.go_file = output("go").unwrap_or(profile.go_file);
+
+        let Some(section) = name.and_then(|name| self.get(&format!("profiles.{}", name))) else {
+            return profile;
+        };
+        let text = |key: &str| section.get(key).and_then(Value::as_str).map(str::to_string);
+        profile.select(section.get("targets"));
+        profile.directory = text("directory").map(PathBuf::from).or(profile.directory);
+        profile.rust_file = text("rust-file").unwrap_or(profile.rust_file);
+        profile.typescript_file = text("typescript-file").unwrap_or(profile.typescript_file);
+        profile.python_file = text("python-file").unwrap_or(profile.python_file);
+        profile.jsonschema_file = text("jsonschema-file").unwrap_or(profile.jsonschema_file);
+        profile.go_file = text("go-file").unwrap_or(profile.go_file);
+        profile.address = text("address");
+        profile
+    }
+
+    /// Override the string setting at `key`, if `value` matches `spec`
+    fn set(
+        &mut self,
+        key: &str,
+        value: String,
+        spec: &Spec,
+        source: Source,
+        issues: &mut Vec<ConfigIssue>,
+    ) {
+        let value = Value::String(value);
+        let before = issues.len();
+        check_value(&value, spec, key, issues);
+        if issues.len() > before {
+            for issue in &mut issues[before..] {
+                issue.key = format!("{} (from {})", issue.key, source);
+            }
+            return;
+        }
+
+        let (section, field) = key.split_once('.').unwrap_or(("", key));
+        let section = self
+            .table
+            .entry(section.to_string())
+            .or_insert_with(|| Value::Table(Table::new()));
+        if let Value::Table(section) = section {
+            section.insert(field.to_string(), value);
+            self.sources.insert(key.to_string(), source);
+        }
+    }
+}
+
+/// Resolve the settings for the `lumos.toml` at `path`
+///
+/// The file is optional; environment variables and flags apply either way.
+/// Defaults are left to each section's loader. With `--verbose`, the
+/// resolved settings are printed the first time each file is loaded.
+pub fn load(path: &Path) -> Result<Settings> {
+    load_profile(path, None)
+}
+
+/// Resolve the settings for the `lumos.toml` at `path` with a profile
+/// selected
+///
+/// The profile's `rust` and `typescript` options replace those in `[rust]`
+/// and `[typescript]`; environment
+/// variables and flags still take precedence. Unknown profiles are an error.
+pub fn load_profile(path: &Path, profile: Option<&str>) -> Result<Settings> {
+    /// A `lumos.toml` and the profile selected when loading it
+    type Loaded = (PathBuf, Option<String>);
+    static PRINTED: Mutex<Option<HashSet<Loaded>>> = Mutex::new(None);
+
+    let flags = FLAGS.get_or_init(Flags::default);
+    let file = match read(path)? {
+        Some(content) => Some((path, toml::from_str(&content)?)),
+        None => None,
+    };
+
+    let env = |name: &str| std::env::var(name).ok();
+    let settings = match Settings::layer(file, profile, env, flags) {
+        Ok(settings) => settings,
+        Err(issues) => {
+            for issue in &issues {
+                report(issue, true);
+            }
+            anyhow::bail!("Invalid settings: {} problem(s)", issues.len());
+        }
+    };
+
+    if flags.verbose {
+        let key = (normalize(path), profile.map(str::to_string));
+        let first_load = PRINTED
+            .lock()
+            .map(|mut printed| printed.get_or_insert_with(HashSet::new).insert(key))
+            .unwrap_or(true);
+        if first_load {
+            print_settings(path, profile, &settings.clone().with_defaults()?);
+        }
+    }
+
+    Ok(settings)
+}
+
+/// Print resolved settings with their sources to stderr
+fn print_settings(path: &Path, profile: Option<&str>, settings: &Settings) {
+    match profile {
+        Some(profile) => errln!(
+            "{} {} (profile {})",
+            "Settings for".bold(),
+            path.display(),
+            profile
+        ),
+        None => errln!("{} {}", "Settings for".bold(), path.display()),
+    }
+    for (key, value, source) in settings.entries() {
+        errln!(
+            "  {} = {}  {}",
+            key,
+            value,
+            format!("({})", source).dimmed()
+        );
+    }
+}
+
+/// Canonical form of `path`, also for files that do not exist
+fn normalize(path: &Path) -> PathBuf {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let parent = parent
+        .canonicalize()
+        .unwrap_or_else(|_| parent.to_path_buf());
+    match path.file_name() {
+        Some(name) => parent.join(name),
+        None => parent,
+    }
+}
+
+/// Keys that can be set through environment variables: every string setting
+/// directly inside a section
+fn overridable() -> Vec<(String, &'static Spec)> {
+    let mut keys = Vec::new();
+    for (section, spec) in SCHEMA {
+        let Spec::Table(fields) = spec else {
+            continue;
+        };
+        for (field, spec) in fields.iter() {
+            if matches!(spec, Spec::String | Spec::OneOf(_)) {
+                keys.push((format!("{}.{}", section, field), spec));
+            }
+        }
+    }
+    keys
+}
+
+/// Environment variable overriding `key`, e.g. `LUMOS_RUST_DEBUG_CLONE`
+fn env_var(key: &str) -> String {
+    format!("LUMOS_{}", key.replace(['.', '-'], "_").to_uppercase())
+}
+
+/// Default value of every setting
+fn defaults() -> Result<Table> {
+    let fuzz = FuzzConfig::default();
+
+    let mut dependencies = Table::new();
+    for (name, spec) in &fuzz.dependencies {
+        let mut parsed: Table = toml::from_str(&format!("spec = {}", spec))
+            .with_context(|| format!("Invalid default spec for {}", name))?;
+        if let Some(spec) = parsed.remove("spec") {
+            dependencies.insert(name.clone(), spec);
+        }
+    }
+
+    let mut defaults = Table::new();
+    defaults.insert(
+        "output".to_string(),
+        table([
+            ("directory", Value::from(".")),
+            ("rust", Value::from("generated.rs")),
+            ("typescript", Value::from("generated.ts")),
+            ("python", Value::from("generated.py")),
+            ("jsonschema", Value::from("generated.schema.json")),
+            ("go", Value::from("generated.go")),
+        ]),
+    );
+    defaults.insert("i18n".to_string(), table([("lang", Value::from("en"))]));
+    defaults.insert(
+        "fuzz".to_string(),
+        table([
+            ("crate-name", Value::from(FuzzConfig::DEFAULT_CRATE_NAME)),
+            ("crate-path", Value::from(fuzz.crate_path.as_str())),
+            ("dependencies", Value::Table(dependencies)),
+        ]),
+    );
+    defaults.insert(
+        "rust".to_string(),
+        table([
+            ("debug-clone", Value::from("always")),
+            (
+                "client-feature",
+                Value::from(RustConfig::DEFAULT_CLIENT_FEATURE),
+            ),
+        ]),
+    );
+    defaults.insert(
+        "typescript".to_string(),
+        table([
+            ("codecs", Value::from("borsh")),
+            ("runtime", Value::from("node")),
+        ]),
+    );
+
+    Ok(defaults)
+}
+
+/// Call `visit` with the dotted key of every non-table value in `table`
+fn flatten(table: &Table, prefix: &str, visit: &mut dyn FnMut(&str, &Value)) {
+    for (key, value) in table {
+        let key = join(prefix, key);
+        match value {
+            Value::Table(inner) => flatten(inner, &key, visit),
+            value => visit(&key, value),
+        }
+    }
+}
+
+fn table<const N: usize>(entries: [(&str, Value); N]) -> Value {
+    Value::Table(
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+    )
+}
+
+/// Merge `overrides` into `base`, recursing into tables present in both
+fn merge(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overrides)) => merge(base, overrides),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn check_table(
+    table: &Table,
+    fields: &[(&str, Spec)],
+    prefix: &str,
+    issues: &mut Vec<ConfigIssue>,
+) {
+    for (key, value) in table {
+        let path = join(prefix, key);
+        match fields.iter().find(|(name, _)| name == key) {
+            Some((_, spec)) => check_value(value, spec, &path, issues),
+            None => {
+                let known: Vec<&str> = fields.iter().map(|(name, _)| *name).collect();
+                let help = match closest(key, &known) {
+                    Some(name) => format!("did you mean `{}`?", name),
+                    None => format!("known keys: {}", known.join(", ")),
+                };
+                issues.push(ConfigIssue {
+                    key: path,
+                    message: "unknown key".to_string(),
+                    help: Some(help),
+                    unknown_key: true,
+                });
+            }
+        }
+    }
+}
+
+fn check_value(value: &Value, spec: &Spec, path: &str, issues: &mut Vec<ConfigIssue>) {
+    let mut help = None;
+    let expected = match spec {
+        Spec::String if value.is_str() => return,
+        Spec::String => "a string".to_string(),
+        Spec::OneOf(options) => {
+            if let Some(text) = value.as_str() {
+                if options.contains(&text) {
+                    return;
+                }
+                help = closest(text, options).map(|option| format!("did you mean \"{}\"?", option));
+            }
+            let options: Vec<String> = options.iter().map(|o| format!("\"{}\"", o)).collect();
+            format!("one of {}", options.join(", "))
+        }
+        Spec::Table(fields) => match value.as_table() {
+            Some(table) => return check_table(table, fields, path, issues),
+            None => "a table".to_string(),
+        },
+        Spec::MapOf(inner) => match value.as_table() {
+            Some(table) => {
+                for (key, value) in table {
+                    check_value(value, inner, &join(path, key), issues);
+                }
+                return;
+            }
+            None => "a table".to_string(),
+        },
+        Spec::ListOf(inner) => match value.as_array() {
+            Some(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    check_value(item, inner, &format!("{}[{}]", path, index), issues);
+                }
+                return;
+            }
+            None => "an array".to_string(),
+        },
+        Spec::Dependency if value.is_str() || value.is_table() => return,
+        Spec::Dependency => "a version string or a table".to_string(),
+    };
+
+    let found = match value.as_str() {
+        Some(text) => format!("\"{}\"", text),
+        None => value.type_str().to_string(),
+    };
+    issues.push(ConfigIssue {
+        key: path.to_string(),
+        message: format!("expected {}, found {}", expected, found),
+        help,
+        unknown_key: false,
+    });
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// The candidate closest to `input`, if it is a plausible typo or variant
+fn closest<'a>(input: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let limit = (input.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(input, candidate), *candidate))
+        .filter(|(distance, candidate)| {
+            *distance <= limit || input.starts_with(candidate) || candidate.starts_with(input)
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_unknown_keys_and_wrong_types() {
+        let issues = check(
+            r#"
+            [rust]
+            debug-clon = "feature"
+            client-feature = 1
+
+            [rust.types.Order]
+            debug-clone = "alway"
+
+            [fuzz.dependencies]
+            borsh = 1
+
+            [fuzzing]
+            "#,
+        )
+        .unwrap();
+
+        let found: Vec<(&str, &str, Option<&str>)> = issues
+            .iter()
+            .map(|i| (i.key.as_str(), i.message.as_str(), i.help.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "fuzz.dependencies.borsh",
+                    "expected a version string or a table, found integer",
+                    None
+                ),
+                ("fuzzing", "unknown key", Some("did you mean `fuzz`?")),
+                (
+                    "rust.client-feature",
+                    "expected a string, found integer",
+                    None
+                ),
+                (
+                    "rust.debug-clon",
+                    "unknown key",
+                    Some("did you mean `debug-clone`?")
+                ),
+                (
+                    "rust.types.Order.debug-clone",
+                    "expected one of \"always\", \"feature\", \"skip\", found \"alway\"",
+                    Some("did you mean \"always\"?")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_every_documented_setting() {
+        let content = r#"
+            [output]
+            directory = "."
+
+            [i18n]
+            lang = "es"
+
+            [fuzz]
+            crate-name = "vault"
+            crate-path = "../programs/vault"
+
+            [fuzz.dependencies]
+            borsh = { version = "1.5", features = ["derive"] }
+
+            [rust]
+            debug-clone = "feature"
+            client-feature = "client"
+
+            [rust.types.Order]
+            debug-clone = "always"
+
+            [lint]
+            field-name-case = "deny"
+            missing-account = "allow"
+
+            [validation]
+            passes = ["pda-seeds", "./scripts/check.sh"]
+        "#;
+        assert!(check(content).unwrap().is_empty());
+
+        // Every lint rule can be configured
+        let Some((_, Spec::Table(lint))) = SCHEMA.iter().find(|(key, _)| *key == "lint") else {
+            panic!("no [lint] section");
+        };
+        let keys: Vec<_> = lint.iter().map(|(key, _)| *key).collect();
+        let rules: Vec<_> = lumos_core::linter::RULES
+            .iter()
+            .map(|rule| rule.id)
+            .collect();
+        assert_eq!(keys, rules);
+    }
+
+    fn layer(
+        file: &str,
+        profile: Option<&str>,
+        env: &[(&str, &str)],
+        flags: &Flags,
+    ) -> Result<Settings, Vec<ConfigIssue>> {
+        let table: Table = toml::from_str(file).unwrap();
+        Settings::layer(
+            Some((Path::new("lumos.toml"), table)),
+            profile,
+            |name| {
+                env.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            },
+            flags,
+        )
+    }
+
+    #[test]
+    fn resolves_defaults_under_settings() {
+        let settings = layer(
+            "[rust]\ndebug-clone = \"skip\"\n[fuzz.dependencies]\nborsh = \"1.2\"\n",
+            None,
+            &[],
+            &Flags::default(),
+        )
+        .unwrap()
+        .with_defaults()
+        .unwrap();
+
+        assert_eq!(settings.get_str("rust.debug-clone"), Some("skip"));
+        assert_eq!(settings.get_str("rust.client-feature"), Some("client"));
+        assert_eq!(settings.get_str("i18n.lang"), Some("en"));
+        assert_eq!(settings.get_str("fuzz.dependencies.borsh"), Some("1.2"));
+        assert!(settings.get("fuzz.dependencies.libfuzzer-sys").is_some());
+    }
+
+    #[test]
+    fn flags_override_env_override_file() {
+        let flags = Flags {
+            lang: Some("zh".to_string()),
+            verbose: false,
+        };
+        let settings = layer(
+            "[i18n]\nlang = \"en\"\n[rust]\ndebug-clone = \"skip\"\nclient-feature = \"host\"\n",
+            None,
+            &[
+                ("LUMOS_I18N_LANG", "es"),
+                ("LUMOS_RUST_DEBUG_CLONE", "feature"),
+            ],
+            &flags,
+        )
+        .unwrap()
+        .with_defaults()
+        .unwrap();
+
+        let sources: Vec<(String, String)> = settings
+            .entries()
+            .into_iter()
+            .filter(|(key, _, _)| key == "i18n.lang" || key.starts_with("rust."))
+            .map(|(key, value, source)| (format!("{} = {}", key, value), source.to_string()))
+            .collect();
+        assert_eq!(
+            sources,
+            [
+                ("i18n.lang = \"zh\"".to_string(), "--lang".to_string()),
+                (
+                    "rust.client-feature = \"host\"".to_string(),
+                    "lumos.toml".to_string()
+                ),
+                (
+                    "rust.debug-clone = \"feature\"".to_string(),
+                    "$LUMOS_RUST_DEBUG_CLONE".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_environment_values() {
+        let issues = layer(
+            "",
+            None,
+            &[("LUMOS_RUST_DEBUG_CLONE", "never")],
+            &Flags::default(),
+        )
+        .unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].key,
+            "rust.debug-clone (from $LUMOS_RUST_DEBUG_CLONE)"
+        );
+    }
+
+    const PROFILES: &str = r#"
+        [rust]
+        debug-clone = "always"
+        client-feature = "client"
+
+        [profiles.frontend]
+        targets = ["typescript"]
+        directory = "web/src"
+        typescript-file = "accounts.ts"
+
+        [profiles.frontend.typescript]
+        codecs = "kit"
+
+        [profiles.onchain]
+        targets = ["rust"]
+        rust-file = "state.rs"
+        address = "Prog1111111111111111111111111111111111111111"
+
+        [profiles.onchain.rust]
+        debug-clone = "feature"
+    "#;
+
+    #[test]
+    fn selects_profile_options() {
+        let settings = layer(PROFILES, Some("onchain"), &[], &Flags::default()).unwrap();
+        assert_eq!(settings.get_str("rust.debug-clone"), Some("feature"));
+        assert_eq!(settings.get_str("rust.client-feature"), Some("client"));
+        assert_eq!(
+            settings.source_of("rust.debug-clone"),
+            Some(&Source::Profile("onchain".to_string()))
+        );
+
+        let onchain = settings.profile(Some("onchain"));
+        assert!(onchain.rust && !onchain.typescript);
+        assert_eq!(onchain.rust_file, "state.rs");
+        assert_eq!(onchain.directory, None);
+        assert!(onchain.address.is_some());
+
+        let frontend = settings.profile(Some("frontend"));
+        assert!(!frontend.rust && frontend.typescript);
+        assert_eq!(frontend.directory, Some(PathBuf::from("web/src")));
+        assert_eq!(frontend.typescript_file, "accounts.ts");
+        assert_eq!(settings.get_str("typescript.codecs"), None);
+
+        let settings = layer(PROFILES, Some("frontend"), &[], &Flags::default()).unwrap();
+        assert_eq!(settings.get_str("typescript.codecs"), Some("kit"));
+
+        assert_eq!(settings.profile(None), Profile::default());
+    }
+
+    #[test]
+    fn profiles_layer_over_output_settings() {
+        let content = format!(
+            "[output]\ntargets = [\"rust\", \"python\"]\ndirectory = \"gen\"\nrust = \"state.rs\"\n{}",
+            PROFILES
+        );
+        let settings = layer(&content, None, &[], &Flags::default()).unwrap();
+        let output = settings.profile(None);
+        assert!(output.rust && !output.typescript && output.python);
+        assert_eq!(output.directory, Some(PathBuf::from("gen")));
+        assert_eq!(output.rust_file, "state.rs");
+        assert_eq!(output.python_file, "generated.py");
+
+        let frontend = settings.profile(Some("frontend"));
+        assert!(!frontend.rust && frontend.typescript && !frontend.python);
+        assert_eq!(frontend.directory, Some(PathBuf::from("web/src")));
+        assert_eq!(
+            settings.profile(Some("onchain")).directory,
+            Some(PathBuf::from("gen"))
+        );
+
+        let env = [("LUMOS_OUTPUT_TYPESCRIPT", "accounts.ts")];
+        let settings = layer(&content, None, &env, &Flags::default()).unwrap();
+        assert_eq!(settings.profile(None).typescript_file, "accounts.ts");
+    }
+
+    #[test]
+    fn environment_overrides_profile() {
+        let env = [("LUMOS_RUST_DEBUG_CLONE", "skip")];
+        let settings = layer(PROFILES, Some("onchain"), &env, &Flags::default()).unwrap();
+        assert_eq!(settings.get_str("rust.debug-clone"), Some("skip"));
+    }
+
+    #[test]
+    fn rejects_unknown_profiles_and_targets() {
+        let issues = layer(PROFILES, Some("fronted"), &[], &Flags::default()).unwrap_err();
+        assert_eq!(issues[0].key, "profiles.fronted");
+        assert_eq!(issues[0].help.as_deref(), Some("did you mean `frontend`?"));
+
+        let issues = check("[profiles.web]\ntargets = [\"typescript\", \"cobol\"]\n").unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "profiles.web.targets[1]");
+    }
+}
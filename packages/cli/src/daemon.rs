@@ -0,0 +1,482 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! `lumos daemon`: JSON-RPC 2.0 over stdio
+//!
+//! Requests and responses are one JSON object per line. The process stays up
+//! between requests, so editors and build tools skip CLI startup and share
+//! one [`Compiler`] and snippet cache across calls. Schemas are passed
+//! inline as `source` or read from `path`; with `path`, the `lumos.toml`
+//! next to the schema applies as it does for `lumos generate`.
+//!
+//! A panic while handling a request is answered with an internal error
+//! carrying a bug report, and the daemon keeps serving with fresh caches.
+
+use crate::report::{findings_to_json, sizes_to_json};
+use lumos_core::compiler::{Compilation, Compiler};
+use lumos_core::error::LumosError;
+use lumos_core::generators::cache::SnippetCache;
+use lumos_core::generators::rust::{self, RustConfig};
+use lumos_core::generators::typescript::{self, TypeScriptConfig};
+use lumos_core::panic_guard;
+use lumos_core::parser::parse_lumos_file;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// The schema failed to parse or validate
+const SCHEMA_ERROR: i64 = -32000;
+
+/// A file could not be read
+const IO_ERROR: i64 = -32001;
+
+/// Snippets kept per target before the cache is cleared
+const SNIPPET_LIMIT: usize = 4096;
+
+/// Error returned to the client
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<LumosError> for RpcError {
+    fn from(error: LumosError) -> Self {
+        match error {
+            LumosError::Internal(internal) => Self {
+                code: INTERNAL_ERROR,
+                message: format!("internal error in {}: {}", internal.entry, internal.message),
+                data: Some(json!({ "bug_report": internal.bug_report() })),
+            },
+            error => Self::new(SCHEMA_ERROR, error.to_string()),
+        }
+    }
+}
+
+/// In-memory snippet cache, cleared when it grows past [`SNIPPET_LIMIT`]
+#[derive(Default)]
+struct MemoryCache(HashMap<u64, String>);
+
+impl SnippetCache for MemoryCache {
+    fn get(&mut self, key: u64) -> Option<String> {
+        self.0.get(&key).cloned()
+    }
+
+    fn put(&mut self, key: u64, snippet: &str) {
+        if self.0.len() >= SNIPPET_LIMIT {
+            self.0.clear();
+        }
+        self.0.insert(key, snippet.to_string());
+    }
+}
+
+/// A schema named by a request's `source` or `path`
+struct Schema {
+    source: String,
+    path: Option<PathBuf>,
+}
+
+/// State shared by every request
+#[derive(Default)]
+struct Daemon {
+    compiler: Compiler,
+    rust_snippets: MemoryCache,
+    ts_snippets: MemoryCache,
+    requests: u64,
+}
+
+/// Serve requests from `input` until it closes or a `shutdown` request
+pub fn serve(input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut daemon = Daemon::default();
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (response, shutdown) = daemon.handle(&line);
+        if let Some(response) = response {
+            writeln!(output, "{}", response)?;
+            output.flush()?;
+        }
+        if shutdown {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+impl Daemon {
+    /// Handle one line; returns the response (none for notifications) and
+    /// whether to shut down
+    fn handle(&mut self, line: &str) -> (Option<Value>, bool) {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => return (Some(error_response(Value::Null, PARSE_ERROR, e)), false),
+        };
+
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            let id = id.unwrap_or(Value::Null);
+            return (
+                Some(error_response(id, INVALID_REQUEST, "missing method")),
+                false,
+            );
+        };
+        let params = match request.get("params") {
+            None | Some(Value::Null) => Map::new(),
+            Some(Value::Object(params)) => params.clone(),
+            Some(_) => {
+                let id = id.unwrap_or(Value::Null);
+                let response = error_response(id, INVALID_PARAMS, "params must be an object");
+                return (Some(response), false);
+            }
+        };
+
+        self.requests += 1;
+        let shutdown = method == "shutdown";
+        let result = self.guarded_dispatch(method, &params);
+
+        let response = id.map(|id| match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => {
+                let mut response = error_response(id, e.code, e.message);
+                if let Some(data) = e.data {
+                    response["error"]["data"] = data;
+                }
+                response
+            }
+        });
+        (response, shutdown)
+    }
+
+    /// [`Daemon::dispatch`], answering a panic with an internal error and
+    /// dropping the caches it may have left half-updated
+    fn guarded_dispatch(
+        &mut self,
+        method: &str,
+        params: &Map<String, Value>,
+    ) -> Result<Value, RpcError> {
+        // The schema is only hashed for the bug report, so read errors are
+        // left for dispatch to report
+        let source = match schema(params) {
+            Ok(schema) => schema.source,
+            Err(_) => String::new(),
+        };
+        match panic_guard::guarded(method, &source, || Ok(self.dispatch(method, params))) {
+            Ok(result) => result,
+            Err(error) => {
+                self.compiler.clear();
+                self.rust_snippets = MemoryCache::default();
+                self.ts_snippets = MemoryCache::default();
+                Err(error.into())
+            }
+        }
+    }
+
+    fn dispatch(&mut self, method: &str, params: &Map<String, Value>) -> Result<Value, RpcError> {
+        match method {
+            "parse" => {
+                let schema = schema(params)?;
+                let ast = parse_lumos_file(&schema.source)?;
+                serde_json::to_value(ast).map_err(|e| RpcError::new(SCHEMA_ERROR, e.to_string()))
+            }
+            "validate" => {
+                let schema = schema(params)?;
+                Ok(match self.compiler.compile(&schema.source) {
+                    Ok(compilation) => json!({
+                        "valid": true,
+                        "types": compilation.types().iter().map(|t| t.name()).collect::<Vec<_>>(),
+                    }),
+                    Err(e) => json!({ "valid": false, "error": e.to_string() }),
+                })
+            }
+            "generate" => {
+                let schema = schema(params)?;
+                let compilation = self.compiler.compile(&schema.source)?;
+                let address = string_param(params, "address")?;
+                let code = match string_param(params, "target")?.unwrap_or("rust") {
+                    "rust" => self.rust(&schema, &compilation, address)?,
+                    "typescript" => self.typescript(&schema, &compilation)?,
+                    other => {
+                        return Err(RpcError::new(
+                            INVALID_PARAMS,
+                            format!("unknown target '{}' (expected rust or typescript)", other),
+                        ))
+                    }
+                };
+                Ok(json!({ "code": code }))
+            }
+            "check" => {
+                let schema = schema(params)?;
+                let compilation = self.compiler.compile(&schema.source)?;
+                let address = string_param(params, "address")?;
+                let output = PathBuf::from(string_param(params, "output")?.unwrap_or("."));
+
+                let mut stale = Vec::new();
+                let mut missing = Vec::new();
+                let outputs = [
+                    (
+                        output.join("generated.rs"),
+                        self.rust(&schema, &compilation, address)?,
+                    ),
+                    (
+                        output.join("generated.ts"),
+                        self.typescript(&schema, &compilation)?,
+                    ),
+                ];
+                for (path, fresh) in outputs {
+                    match fs::read_to_string(&path) {
+                        Ok(existing) if existing == fresh => {}
+                        Ok(_) => stale.push(path.display().to_string()),
+                        Err(_) => missing.push(path.display().to_string()),
+                    }
+                }
+
+                Ok(json!({
+                    "up_to_date": stale.is_empty() && missing.is_empty(),
+                    "stale": stale,
+                    "missing": missing,
+                }))
+            }
+            "analyze" => {
+                let schema = schema(params)?;
+                let compilation = self.compiler.compile(&schema.source)?;
+                Ok(json!({
+                    "sizes": sizes_to_json(compilation.sizes()),
+                    "findings": findings_to_json(compilation.findings()),
+                }))
+            }
+            "stats" => Ok(json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "requests": self.requests,
+                "compilations": self.compiler.len(),
+                "snippets": self.rust_snippets.0.len() + self.ts_snippets.0.len(),
+            })),
+            "shutdown" => Ok(Value::Null),
+            #[cfg(test)]
+            "panic" => panic!("requested by the test"),
+            other => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method '{}'", other),
+            )),
+        }
+    }
+
+    /// Rust module for a compiled schema, with the schema's `[rust]` settings
+    fn rust(
+        &mut self,
+        schema: &Schema,
+        compilation: &Arc<Compilation>,
+        address: Option<&str>,
+    ) -> Result<String, RpcError> {
+        let config = match &schema.path {
+            Some(path) => crate::load_rust_config(path, None)
+                .map_err(|e| RpcError::new(SCHEMA_ERROR, format!("{:#}", e)))?,
+            None => RustConfig::default(),
+        };
+
+        let mut code = Vec::new();
+        rust::generate_configured_to(
+            compilation.types(),
+            address,
+            &config,
+            &mut self.rust_snippets,
+            &mut code,
+        )
+        .map_err(|e| RpcError::new(IO_ERROR, e.to_string()))?;
+        String::from_utf8(code).map_err(|e| RpcError::new(IO_ERROR, e.to_string()))
+    }
+
+    /// TypeScript module for a compiled schema, with the schema's `[typescript]` settings
+    fn typescript(
+        &mut self,
+        schema: &Schema,
+        compilation: &Arc<Compilation>,
+    ) -> Result<String, RpcError> {
+        let config = match &schema.path {
+            Some(path) => crate::load_typescript_config(path, None)
+                .map_err(|e| RpcError::new(SCHEMA_ERROR, format!("{:#}", e)))?,
+            None => TypeScriptConfig::default(),
+        };
+
+        let mut code = Vec::new();
+        typescript::generate_configured_to(
+            compilation.types(),
+            &config,
+            &mut self.ts_snippets,
+            &mut code,
+        )
+        .map_err(|e| RpcError::new(IO_ERROR, e.to_string()))?;
+        String::from_utf8(code).map_err(|e| RpcError::new(IO_ERROR, e.to_string()))
+    }
+}
+
+/// The schema given as `source`, or read from `path`
+fn schema(params: &Map<String, Value>) -> Result<Schema, RpcError> {
+    if let Some(source) = string_param(params, "source")? {
+        return Ok(Schema {
+            source: source.to_string(),
+            path: None,
+        });
+    }
+
+    let Some(path) = string_param(params, "path")? else {
+        return Err(RpcError::new(INVALID_PARAMS, "expected `source` or `path`"));
+    };
+    let source = fs::read_to_string(path)
+        .map_err(|e| RpcError::new(IO_ERROR, format!("failed to read {}: {}", path, e)))?;
+    Ok(Schema {
+        source,
+        path: Some(Path::new(path).to_path_buf()),
+    })
+}
+
+/// Optional string parameter
+fn string_param<'a>(
+    params: &'a Map<String, Value>,
+    name: &str,
+) -> Result<Option<&'a str>, RpcError> {
+    match params.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(_) => Err(RpcError::new(
+            INVALID_PARAMS,
+            format!("`{}` must be a string", name),
+        )),
+    }
+}
+
+fn error_response(id: Value, code: i64, message: impl ToString) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.to_string() },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT: &str = "#[solana]\\n#[account]\\nstruct Vault { owner: PublicKey, amount: u64 }";
+
+    fn session(requests: &[String]) -> Vec<Value> {
+        let mut output = Vec::new();
+        serve(requests.join("\n").as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn request(id: u64, method: &str, params: &str) -> String {
+        format!(
+            r#"{{"jsonrpc":"2.0","id":{},"method":"{}","params":{}}}"#,
+            id, method, params
+        )
+    }
+
+    #[test]
+    fn serves_requests_and_shares_compilations() {
+        let source = format!(r#"{{"source":"{}"}}"#, VAULT);
+        let responses = session(&[
+            request(1, "validate", &source),
+            request(
+                2,
+                "generate",
+                &format!(r#"{{"source":"{}","target":"typescript"}}"#, VAULT),
+            ),
+            request(3, "analyze", &source),
+            request(4, "stats", "{}"),
+            request(5, "shutdown", "null"),
+            request(6, "stats", "{}"),
+        ]);
+
+        assert_eq!(responses.len(), 5, "nothing is served after shutdown");
+        assert_eq!(responses[0]["result"]["valid"], true);
+        assert_eq!(responses[0]["result"]["types"][0], "Vault");
+        let code = responses[1]["result"]["code"].as_str().unwrap();
+        assert!(code.contains("export interface Vault"));
+        assert_eq!(responses[2]["id"], 3);
+        assert!(responses[2]["result"]["sizes"].is_array());
+        assert_eq!(responses[3]["result"]["compilations"], 1);
+        assert_eq!(responses[4]["result"], Value::Null);
+    }
+
+    #[test]
+    fn reports_protocol_and_schema_errors() {
+        let responses = session(&[
+            "not json".to_string(),
+            request(1, "frobnicate", "{}"),
+            request(2, "generate", "{}"),
+            request(3, "generate", r#"{"source":"struct Broken {"}"#),
+            request(4, "validate", r#"{"source":"struct Broken {"}"#),
+            // Notifications get no response
+            r#"{"jsonrpc":"2.0","method":"stats"}"#.to_string(),
+        ]);
+
+        let codes: Vec<&Value> = responses
+            .iter()
+            .take(4)
+            .map(|r| &r["error"]["code"])
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                &json!(PARSE_ERROR),
+                &json!(METHOD_NOT_FOUND),
+                &json!(INVALID_PARAMS),
+                &json!(SCHEMA_ERROR)
+            ]
+        );
+        assert_eq!(responses[4]["result"]["valid"], false);
+        assert_eq!(responses.len(), 5);
+    }
+
+    #[test]
+    fn answers_panics_with_a_bug_report_and_keeps_serving() {
+        let source = format!(r#"{{"source":"{}"}}"#, VAULT);
+        let responses = session(&[
+            request(1, "validate", &source),
+            request(2, "panic", &source),
+            request(3, "stats", "{}"),
+            request(4, "validate", &source),
+        ]);
+
+        let error = &responses[1]["error"];
+        assert_eq!(error["code"], INTERNAL_ERROR);
+        assert_eq!(
+            error["message"],
+            "internal error in panic: requested by the test"
+        );
+        let report = error["data"]["bug_report"].as_str().unwrap();
+        assert!(report.contains("Entry point: panic"));
+        assert!(report.contains("Schema hash: "));
+        assert_eq!(responses[2]["result"]["compilations"], 0);
+        assert_eq!(responses[3]["result"]["valid"], true);
+    }
+}
@@ -0,0 +1,578 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Live dashboard for watch mode (`lumos generate --watch --dashboard`)
+//!
+//! Instead of scrolling status lines, every regeneration refreshes a set of
+//! panes: the outcome of the last run, how long each stage took, the
+//! diagnostics with the source of the selected one, and the size and
+//! security summaries of the schema's types.
+//!
+//! Collecting a [`Snapshot`] works in every build; drawing it requires the
+//! `dashboard` feature.
+
+// Without the feature only the tests use the snapshot
+#![cfg_attr(not(feature = "dashboard"), allow(dead_code))]
+
+use crate::diagnostic;
+use crate::progress::{self, Stage};
+#[cfg(feature = "dashboard")]
+use crate::watch::SchemaWatcher;
+use anyhow::Result;
+use lumos_core::ast::LumosFile;
+use lumos_core::ir::TypeDefinition;
+use lumos_core::resolver;
+use lumos_core::security_analyzer::{SecurityAnalyzer, Severity};
+use lumos_core::size_calculator::{SizeCalculator, SizeInfo};
+use lumos_core::transform::transform_to_ir;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// Best-practice note
+    Info,
+    /// Worth reviewing
+    Warning,
+    /// Generation failed or a critical finding
+    Error,
+}
+
+impl Level {
+    /// Label shown in the diagnostics pane
+    pub fn label(self) -> &'static str {
+        match self {
+            Level::Info => "info",
+            Level::Warning => "warning",
+            Level::Error => "error",
+        }
+    }
+}
+
+/// A problem shown in the diagnostics pane
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// How serious it is
+    pub level: Level,
+
+    /// One-line description
+    pub message: String,
+
+    /// The schema line it points at, when its location is known
+    pub snippet: Option<String>,
+}
+
+/// Size and rent of an account type
+#[derive(Debug, Clone)]
+pub struct AccountSummary {
+    /// Type name
+    pub name: String,
+
+    /// Serialized size, e.g. `72 bytes` or `>= 40 bytes`
+    pub bytes: String,
+
+    /// Rent-exempt minimum in SOL
+    pub rent_sol: f64,
+}
+
+/// Everything the dashboard shows about one generation run
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Local time the run finished, e.g. `14:03:27`
+    pub finished_at: String,
+
+    /// Why the run failed, or `None` when it succeeded
+    pub error: Option<String>,
+
+    /// Stages the run finished, with their durations
+    pub stages: Vec<Stage>,
+
+    /// Number of type definitions in the schema
+    pub types: usize,
+
+    /// Generation errors, size warnings and security findings
+    pub diagnostics: Vec<Diagnostic>,
+
+    /// Account types with their sizes
+    pub accounts: Vec<AccountSummary>,
+
+    /// Security findings by severity: critical, warning and info
+    pub findings: [usize; 3],
+}
+
+/// Run `generate` for the schema at `schema_path` and summarize the result
+///
+/// Sizes and security findings come from the schema itself, so they are
+/// shown even when writing the generated files failed.
+pub fn capture(schema_path: &Path, generate: impl FnOnce() -> Result<()>) -> Snapshot {
+    progress::record_stages();
+    let generated = generate();
+    let mut snapshot = Snapshot {
+        finished_at: chrono::Local::now().format("%H:%M:%S").to_string(),
+        error: None,
+        stages: progress::take_stages(),
+        types: 0,
+        diagnostics: Vec::new(),
+        accounts: Vec::new(),
+        findings: [0; 3],
+    };
+
+    if let Err(error) = &generated {
+        let message = format!("{:#}", error);
+        snapshot.diagnostics.push(Diagnostic {
+            level: Level::Error,
+            message: message.clone(),
+            snippet: diagnostic::for_error(error),
+        });
+        snapshot.error = Some(message);
+    }
+
+    let Ok(ast) = resolver::load(schema_path) else {
+        return snapshot;
+    };
+    if let Ok(ir) = transform_to_ir(ast.clone()) {
+        summarize(&mut snapshot, &ast, &ir);
+    }
+    snapshot
+}
+
+fn summarize(snapshot: &mut Snapshot, ast: &LumosFile, ir: &[TypeDefinition]) {
+    snapshot.types = ir.len();
+
+    for size in SizeCalculator::new(ir).calculate_all() {
+        for warning in &size.warnings {
+            snapshot.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                message: format!("{}: {}", size.name, warning),
+                snippet: source_snippet(ast, &size.name, None),
+            });
+        }
+        if size.is_account {
+            snapshot.accounts.push(AccountSummary {
+                bytes: match &size.total_bytes {
+                    SizeInfo::Fixed(bytes) => format!("{} bytes", bytes),
+                    SizeInfo::Variable { min, .. } => format!(">= {} bytes", min),
+                },
+                name: size.name.clone(),
+                rent_sol: size.rent_sol,
+            });
+        }
+    }
+
+    for finding in SecurityAnalyzer::new(ir).analyze() {
+        let (level, count) = match finding.severity {
+            Severity::Critical => (Level::Error, 0),
+            Severity::Warning => (Level::Warning, 1),
+            Severity::Info => (Level::Info, 2),
+        };
+        snapshot.findings[count] += 1;
+        snapshot.diagnostics.push(Diagnostic {
+            level,
+            message: format!("{}: {}", finding.location, finding.message),
+            snippet: source_snippet(
+                ast,
+                &finding.location.type_name,
+                finding.location.field_name.as_deref(),
+            ),
+        });
+    }
+}
+
+/// Snippet of the definition of `type_name`, or of its `field` when given
+fn source_snippet(ast: &LumosFile, type_name: &str, field: Option<&str>) -> Option<String> {
+    let location = diagnostic::definition(ast, type_name, field)?;
+    let source = fs::read_to_string(location.file.as_ref()?).ok()?;
+    diagnostic::snippet(&source, &location)
+}
+
+/// Dashboard state across runs
+#[derive(Debug)]
+pub struct Dashboard {
+    snapshot: Snapshot,
+    selected: usize,
+    runs: usize,
+}
+
+impl Dashboard {
+    /// Dashboard showing the first run
+    pub fn new(snapshot: Snapshot) -> Self {
+        Self {
+            snapshot,
+            selected: 0,
+            runs: 1,
+        }
+    }
+
+    /// Show a newer run, keeping the selection where it still exists
+    pub fn update(&mut self, snapshot: Snapshot) {
+        self.snapshot = snapshot;
+        self.selected = self
+            .selected
+            .min(self.snapshot.diagnostics.len().saturating_sub(1));
+        self.runs += 1;
+    }
+
+    /// Select the next diagnostic
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.snapshot.diagnostics.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Select the previous diagnostic
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// The selected diagnostic, if there are any
+    pub fn selected(&self) -> Option<&Diagnostic> {
+        self.snapshot.diagnostics.get(self.selected)
+    }
+}
+
+/// Watch `schema_path`, its imports and its lumos.toml, and show each run of
+/// `generate` on the dashboard
+/// until `q`, `Esc` or `Ctrl+C`
+///
+/// `debounce` is how long to wait for more changes before regenerating.
+#[cfg(feature = "dashboard")]
+pub fn run(
+    schema_path: &Path,
+    debounce: Duration,
+    mut generate: impl FnMut() -> Result<()>,
+) -> Result<()> {
+    use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+
+    let mut watcher = SchemaWatcher::new(schema_path)?;
+
+    // Status lines and escape codes would garble the screen
+    let was_quiet = progress::quiet();
+    progress::init(true);
+    colored::control::set_override(false);
+
+    let mut terminal = ratatui::try_init()?;
+    let result = (|| -> Result<()> {
+        let mut dashboard = Dashboard::new(capture(schema_path, &mut generate));
+        let mut regenerate = false;
+        loop {
+            if regenerate {
+                dashboard.update(capture(schema_path, &mut generate));
+                // Imports may have been added or removed
+                watcher.refresh()?;
+                // A full redraw wipes anything the run printed anyway
+                terminal.clear()?;
+                regenerate = false;
+            }
+            terminal.draw(|frame| render(frame, &dashboard))?;
+
+            if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            return Ok(())
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => dashboard.select_next(),
+                        KeyCode::Up | KeyCode::Char('k') => dashboard.select_previous(),
+                        KeyCode::Char('r') => regenerate = true,
+                        _ => {}
+                    }
+                }
+            }
+
+            if !watcher.changes(Duration::ZERO, debounce)?.is_empty() {
+                regenerate = true;
+            }
+        }
+    })();
+    ratatui::restore();
+    progress::init(was_quiet);
+    result
+}
+
+#[cfg(not(feature = "dashboard"))]
+pub fn run(
+    _schema_path: &Path,
+    _debounce: Duration,
+    _generate: impl FnMut() -> Result<()>,
+) -> Result<()> {
+    anyhow::bail!(
+        "This build of lumos has no dashboard support. Reinstall with `cargo install lumos-cli --features dashboard`"
+    )
+}
+
+#[cfg(feature = "dashboard")]
+fn render(frame: &mut ratatui::Frame, dashboard: &Dashboard) {
+    use ratatui::layout::{Constraint, Layout};
+    use ratatui::style::{Color, Style, Stylize};
+    use ratatui::text::{Line, Span};
+    use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Row, Table, Wrap};
+
+    let snapshot = &dashboard.snapshot;
+    let [status_area, summary_area, diagnostics_area, help_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(6),
+        Constraint::Min(8),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let outcome = match &snapshot.error {
+        None => Span::styled(
+            format!("OK  generated {} type definitions", snapshot.types),
+            Style::new().fg(Color::Green).bold(),
+        ),
+        Some(error) => Span::styled(
+            format!("FAILED  {}", error),
+            Style::new().fg(Color::Red).bold(),
+        ),
+    };
+    let status = Line::from(vec![
+        outcome,
+        Span::raw(format!(
+            "  at {} (run {})",
+            snapshot.finished_at, dashboard.runs
+        ))
+        .dim(),
+    ]);
+    frame.render_widget(
+        Paragraph::new(status).block(Block::bordered().title(" Last generation ")),
+        status_area,
+    );
+
+    let [timings_area, size_area, security_area] = Layout::horizontal([
+        Constraint::Percentage(40),
+        Constraint::Percentage(35),
+        Constraint::Percentage(25),
+    ])
+    .areas(summary_area);
+
+    let timings = snapshot.stages.iter().map(|stage| {
+        Row::new(vec![
+            stage.verb.clone(),
+            stage.message.clone(),
+            progress::format_duration(stage.duration),
+        ])
+    });
+    frame.render_widget(
+        Table::new(
+            timings,
+            [
+                Constraint::Length(11),
+                Constraint::Fill(1),
+                Constraint::Length(8),
+            ],
+        )
+        .block(Block::bordered().title(" Timings ")),
+        timings_area,
+    );
+
+    let sizes = snapshot.accounts.iter().map(|account| {
+        Row::new(vec![
+            account.name.clone(),
+            account.bytes.clone(),
+            format!("{:.5} SOL", account.rent_sol),
+        ])
+    });
+    frame.render_widget(
+        Table::new(
+            sizes,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(12),
+                Constraint::Length(13),
+            ],
+        )
+        .block(Block::bordered().title(" Account sizes ")),
+        size_area,
+    );
+
+    let [critical, warning, info] = snapshot.findings;
+    let security = vec![
+        Line::from(format!("Critical  {}", critical)).fg(match critical {
+            0 => Color::Reset,
+            _ => Color::Red,
+        }),
+        Line::from(format!("Warning   {}", warning)).fg(match warning {
+            0 => Color::Reset,
+            _ => Color::Yellow,
+        }),
+        Line::from(format!("Info      {}", info)),
+    ];
+    frame.render_widget(
+        Paragraph::new(security).block(Block::bordered().title(" Security ")),
+        security_area,
+    );
+
+    let [list_area, source_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .areas(diagnostics_area);
+
+    let items = snapshot.diagnostics.iter().map(|diagnostic| {
+        let color = match diagnostic.level {
+            Level::Error => Color::Red,
+            Level::Warning => Color::Yellow,
+            Level::Info => Color::Blue,
+        };
+        ListItem::new(Line::from(vec![
+            Span::styled(format!("{:<8}", diagnostic.level.label()), color),
+            Span::raw(diagnostic.message.clone()),
+        ]))
+    });
+    let title = format!(" Diagnostics ({}) ", snapshot.diagnostics.len());
+    let mut selection =
+        ListState::default().with_selected(dashboard.selected().map(|_| dashboard.selected));
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().reversed()),
+        list_area,
+        &mut selection,
+    );
+
+    let source = match dashboard.selected() {
+        Some(Diagnostic {
+            snippet: Some(snippet),
+            ..
+        }) => snippet.clone(),
+        Some(_) => "No source location for this diagnostic".to_string(),
+        None => "No diagnostics".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(source)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(" Source ")),
+        source_area,
+    );
+
+    frame.render_widget(
+        Line::from(" Up/Down select   r regenerate   q quit").dim(),
+        help_area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT: &str =
+        "#[solana]\n#[account]\nstruct Vault {\n    authority: PublicKey,\n    balance: u64,\n}\n";
+
+    #[test]
+    fn captures_summaries_and_located_diagnostics() {
+        colored::control::set_override(false);
+        let dir = tempfile::tempdir().unwrap();
+        let schema = dir.path().join("vault.lumos");
+        fs::write(&schema, VAULT).unwrap();
+
+        let snapshot = capture(&schema, || Ok(()));
+        assert!(snapshot.error.is_none());
+        assert_eq!(snapshot.types, 1);
+        assert_eq!(snapshot.accounts.len(), 1);
+        assert_eq!(snapshot.accounts[0].name, "Vault");
+        assert!(snapshot.findings.iter().sum::<usize>() > 0);
+        let located = snapshot
+            .diagnostics
+            .iter()
+            .find_map(|diagnostic| diagnostic.snippet.as_ref())
+            .unwrap();
+        assert!(located.contains("vault.lumos:"));
+
+        // A failed run keeps its error, pointing at the schema
+        fs::write(&schema, "struct Vault {\n    balance: Balance,\n}\n").unwrap();
+        let snapshot = capture(&schema, || {
+            let ast = resolver::load(&schema)?;
+            transform_to_ir(ast)?;
+            Ok(())
+        });
+        assert!(snapshot.error.as_ref().unwrap().contains("Balance"));
+        assert_eq!(snapshot.diagnostics[0].level, Level::Error);
+        assert!(snapshot.diagnostics[0]
+            .snippet
+            .as_ref()
+            .unwrap()
+            .contains("balance: Balance"));
+    }
+
+    #[test]
+    fn keeps_selection_within_diagnostics() {
+        let diagnostic = |message: &str| Diagnostic {
+            level: Level::Warning,
+            message: message.to_string(),
+            snippet: None,
+        };
+        let snapshot = |messages: &[&str]| Snapshot {
+            finished_at: "12:00:00".to_string(),
+            error: None,
+            stages: Vec::new(),
+            types: 1,
+            diagnostics: messages.iter().map(|m| diagnostic(m)).collect(),
+            accounts: Vec::new(),
+            findings: [0; 3],
+        };
+
+        let mut dashboard = Dashboard::new(snapshot(&["a", "b", "c"]));
+        dashboard.select_previous();
+        dashboard.select_next();
+        dashboard.select_next();
+        dashboard.select_next();
+        assert_eq!(dashboard.selected().unwrap().message, "c");
+
+        dashboard.update(snapshot(&["a", "b"]));
+        assert_eq!(dashboard.selected().unwrap().message, "b");
+        dashboard.update(snapshot(&[]));
+        assert!(dashboard.selected().is_none());
+        assert_eq!(dashboard.runs, 3);
+    }
+
+    #[cfg(feature = "dashboard")]
+    #[test]
+    fn renders_every_pane() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let dashboard = Dashboard::new(Snapshot {
+            finished_at: "12:00:00".to_string(),
+            error: None,
+            stages: vec![Stage {
+                verb: "Generating".to_string(),
+                message: "Rust for 1 types".to_string(),
+                duration: Duration::from_millis(12),
+            }],
+            types: 1,
+            diagnostics: vec![Diagnostic {
+                level: Level::Warning,
+                message: "Vault::balance: unchecked".to_string(),
+                snippet: Some(" --> vault.lumos:5:5".to_string()),
+            }],
+            accounts: Vec::new(),
+            findings: [0, 1, 0],
+        });
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|frame| render(frame, &dashboard)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        for text in [
+            "generated 1 type definitions",
+            "Rust for 1 types",
+            "12ms",
+            "Account sizes",
+            "Warning   1",
+            "Vault::balance: unchecked",
+            "vault.lumos:5:5",
+        ] {
+            assert!(screen.contains(text), "missing {:?}", text);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Source snippets for schema errors
+//!
+//! Errors from the core carry the line and column of the problem, and the
+//! file when the schema was loaded from disk. Below the error, the CLI shows
+//! that line with the offending name underlined:
+//!
+//! ```text
+//!   --> vault.lumos:4:12
+//!    |
+//!  4 |     owner: Owner,
+//!    |            ^^^^^
+//! ```
+
+use colored::*;
+use lumos_core::ast::{EnumVariant, Item, LumosFile};
+use lumos_core::error::{LumosError, SourceLocation};
+use std::fs;
+
+/// Snippet for the first located schema error in `error`'s chain, if its file can be read
+pub fn for_error(error: &anyhow::Error) -> Option<String> {
+    let location = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<LumosError>())
+        .and_then(LumosError::location)?;
+    let source = fs::read_to_string(location.file.as_ref()?).ok()?;
+    snippet(&source, location)
+}
+
+/// Where `type_name`, or its `field` when given, is defined
+///
+/// Falls back to the type for fields of enums and fields it doesn't have.
+pub fn definition(ast: &LumosFile, type_name: &str, field: Option<&str>) -> Option<SourceLocation> {
+    let item = ast.items.iter().find(|item| item.name() == type_name)?;
+    let span = match (item, field) {
+        (Item::Struct(def), Some(field)) => def
+            .fields
+            .iter()
+            .find(|candidate| candidate.name == field)
+            .and_then(|field| field.span)
+            .or(def.span),
+        _ => item.span(),
+    };
+    ast.location(type_name, span)
+}
+
+/// Where `field` of the struct variant `variant` of enum `type_name` is defined
+///
+/// Falls back to the enum when the variant or field is missing.
+pub fn variant_definition(
+    ast: &LumosFile,
+    type_name: &str,
+    variant: &str,
+    field: &str,
+) -> Option<SourceLocation> {
+    let span = ast.items.iter().find_map(|item| match item {
+        Item::Enum(def) if def.name == type_name => {
+            def.variants.iter().find_map(|candidate| match candidate {
+                EnumVariant::Struct { name, fields, .. } if name == variant => fields
+                    .iter()
+                    .find(|candidate| candidate.name == field)
+                    .and_then(|field| field.span),
+                _ => None,
+            })
+        }
+        _ => None,
+    });
+    match span {
+        Some(span) => ast.location(type_name, Some(span)),
+        None => definition(ast, type_name, None),
+    }
+}
+
+/// The line of `source` at `location`, with the name starting there underlined
+pub fn snippet(source: &str, location: &SourceLocation) -> Option<String> {
+    let line = source.lines().nth(location.line.checked_sub(1)?)?;
+    let column = location.column.max(1) - 1;
+    if column > line.chars().count() {
+        return None;
+    }
+
+    // Keep tabs so the carets line up with the source
+    let indent: String = line
+        .chars()
+        .take(column)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let width = line
+        .chars()
+        .skip(column)
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .count()
+        .max(1);
+
+    let number = location.line.to_string();
+    let gutter = " ".repeat(number.len());
+    let bar = "|".blue().bold();
+    Some(format!(
+        "{} {} {}\n{} {}\n{} {} {}\n{} {} {}{}",
+        gutter,
+        "-->".blue().bold(),
+        location.format(),
+        gutter,
+        bar,
+        number.blue().bold(),
+        bar,
+        line,
+        gutter,
+        bar,
+        indent,
+        "^".repeat(width).red().bold()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn underlines_name_at_location() {
+        colored::control::set_override(false);
+        let source = "struct Vault {\n\towner: Owner,\n}\n";
+        let location = SourceLocation::new(2, 9).in_file("vault.lumos");
+
+        assert_eq!(
+            snippet(source, &location).unwrap(),
+            "  --> vault.lumos:2:9\n  |\n2 | \towner: Owner,\n  | \t       ^^^^^"
+        );
+        assert_eq!(
+            snippet(source, &SourceLocation::new(3, 1)).unwrap(),
+            "  --> 3:1\n  |\n3 | }\n  | ^"
+        );
+        assert!(snippet(source, &SourceLocation::new(9, 1)).is_none());
+        assert!(snippet(source, &SourceLocation::new(1, 40)).is_none());
+    }
+}
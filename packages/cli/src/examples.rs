@@ -0,0 +1,174 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Example schemas shipped in the binary (`lumos examples`)
+//!
+//! The schemas of the repository's `examples/` directory are compiled in, so
+//! `lumos examples init gaming` scaffolds a realistic project without a
+//! clone or a network connection.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An example schema
+#[derive(Debug)]
+pub struct Example {
+    /// Name used on the command line, e.g. `nft-marketplace`
+    pub name: &'static str,
+
+    /// One-line summary
+    pub description: &'static str,
+
+    /// Schema source
+    pub schema: &'static str,
+}
+
+/// Every shipped example, in the order `lumos examples list` shows them
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "gaming",
+        description: "Player accounts, items and leaderboards of an on-chain game",
+        schema: include_str!("../../../examples/gaming/schema.lumos"),
+    },
+    Example {
+        name: "nft-marketplace",
+        description: "Listings, metadata and purchase receipts of an NFT marketplace",
+        schema: include_str!("../../../examples/nft-marketplace/schema.lumos"),
+    },
+    Example {
+        name: "defi-staking",
+        description: "Staking pools, staker positions and reward tracking",
+        schema: include_str!("../../../examples/defi-staking/schema.lumos"),
+    },
+    Example {
+        name: "dao-governance",
+        description: "DAO configuration, proposals, votes and members",
+        schema: include_str!("../../../examples/dao-governance/schema.lumos"),
+    },
+    Example {
+        name: "token-vesting",
+        description: "Vesting schedules with cliffs and linear release",
+        schema: include_str!("../../../examples/token-vesting/schema.lumos"),
+    },
+    Example {
+        name: "enums",
+        description: "Unit, tuple and struct enum patterns",
+        schema: include_str!("../../../examples/enums/schema.lumos"),
+    },
+];
+
+/// The example called `name`
+pub fn find(name: &str) -> Result<&'static Example> {
+    EXAMPLES
+        .iter()
+        .find(|example| example.name == name)
+        .with_context(|| {
+            let names: Vec<_> = EXAMPLES.iter().map(|example| example.name).collect();
+            format!(
+                "Unknown example '{}'. Available examples: {}",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
+/// Write a project for `example` into `dir`: `schema.lumos`, `lumos.toml`
+/// with `config` and a `README.md`
+///
+/// Existing files are never overwritten; the paths written are returned.
+pub fn scaffold(example: &Example, dir: &Path, config: &str) -> Result<Vec<PathBuf>> {
+    let files = [
+        ("schema.lumos", example.schema.to_string()),
+        ("lumos.toml", config.to_string()),
+        ("README.md", readme(example)),
+    ];
+    if let Some((name, _)) = files.iter().find(|(name, _)| dir.join(name).exists()) {
+        anyhow::bail!(
+            "{} already exists; choose another directory",
+            dir.join(name).display()
+        );
+    }
+
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create project directory: {}", dir.display()))?;
+    let mut written = Vec::new();
+    for (name, contents) in files {
+        let path = dir.join(name);
+        fs::write(&path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn readme(example: &Example) -> String {
+    format!(
+        r#"# LUMOS Example: {name}
+
+{description}.
+
+## Quick Start
+
+1. Generate code (Anchor accounts need your program id):
+   ```bash
+   lumos generate schema.lumos --address <PROGRAM_ID>
+   ```
+2. Use the generated `generated.rs` and `generated.ts` in your project
+3. Edit `schema.lumos` to make it yours
+
+## Documentation
+
+https://github.com/getlumos/lumos
+"#,
+        name = example.name,
+        description = example.description
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumos_core::parser::parse_lumos_file;
+    use lumos_core::transform::transform_to_ir;
+
+    #[test]
+    fn shipped_examples_compile() {
+        for example in EXAMPLES {
+            let ast = parse_lumos_file(example.schema)
+                .unwrap_or_else(|e| panic!("{}: {}", example.name, e));
+            let ir = transform_to_ir(ast).unwrap_or_else(|e| panic!("{}: {}", example.name, e));
+            assert!(!ir.is_empty(), "{}", example.name);
+        }
+        assert_eq!(find("gaming").unwrap().name, "gaming");
+        assert!(find("chess")
+            .unwrap_err()
+            .to_string()
+            .contains("Available examples: gaming, nft-marketplace"));
+    }
+
+    #[test]
+    fn scaffolds_without_overwriting() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("staking");
+        let example = find("defi-staking").unwrap();
+
+        let written = scaffold(example, &project, "[output]\n").unwrap();
+        assert_eq!(written.len(), 3);
+        assert_eq!(
+            fs::read_to_string(project.join("schema.lumos")).unwrap(),
+            example.schema
+        );
+        assert!(fs::read_to_string(project.join("README.md"))
+            .unwrap()
+            .starts_with("# LUMOS Example: defi-staking"));
+
+        fs::write(project.join("schema.lumos"), "struct Mine { x: u8 }").unwrap();
+        let err = scaffold(example, &project, "[output]\n").unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        assert_eq!(
+            fs::read_to_string(project.join("schema.lumos")).unwrap(),
+            "struct Mine { x: u8 }"
+        );
+    }
+}
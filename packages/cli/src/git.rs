@@ -0,0 +1,161 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Baseline files read from git revisions
+//!
+//! `git:<rev>:<path>` names a file as of any revision the repository knows
+//! (a branch, tag, `HEAD~1` or commit id). It is read through libgit2, so CI
+//! needs neither a second checkout nor a `git` binary. `<path>` is relative
+//! to the repository root, or to the current directory when it starts with
+//! `./` or `../`.
+
+use anyhow::{Context, Result};
+use git2::Repository;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Prefix marking a git baseline
+pub const PREFIX: &str = "git:";
+
+/// Split `git:<rev>:<path>` into revision and path
+pub fn parse(spec: &str) -> Option<(&str, &str)> {
+    let (rev, path) = spec.strip_prefix(PREFIX)?.split_once(':')?;
+    (!rev.is_empty() && !path.is_empty()).then_some((rev, path))
+}
+
+/// Read a baseline given as `git:<rev>:<path>` or as a file path
+pub fn read_spec(spec: &str) -> Result<String> {
+    if spec.starts_with(PREFIX) {
+        let (rev, path) = parse(spec).with_context(|| {
+            format!("Invalid git baseline '{}': expected git:<rev>:<path>", spec)
+        })?;
+        return read(rev, path);
+    }
+    fs::read_to_string(spec).with_context(|| format!("Failed to read baseline file: {}", spec))
+}
+
+/// Read `path` as of `rev` in the repository containing the current directory
+pub fn read(rev: &str, path: &str) -> Result<String> {
+    let cwd = std::env::current_dir().context("Failed to determine the current directory")?;
+    read_at(&cwd, rev, path)
+}
+
+/// Read `path` as of `rev` in the repository containing `cwd`
+fn read_at(cwd: &Path, rev: &str, path: &str) -> Result<String> {
+    let repo = Repository::discover(cwd)
+        .with_context(|| format!("No git repository found at {}", cwd.display()))?;
+    let workdir = repo
+        .workdir()
+        .context("Cannot read baselines from a bare repository")?;
+    let relative = repo_path(workdir, cwd, path)?;
+
+    let blob = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_tree())
+        .and_then(|tree| tree.get_path(&relative))
+        .and_then(|entry| entry.to_object(&repo))
+        .and_then(|object| object.peel_to_blob())
+        .with_context(|| format!("Failed to read {} at {}", relative.display(), rev))?;
+
+    String::from_utf8(blob.content().to_vec())
+        .with_context(|| format!("{} at {} is not valid UTF-8", relative.display(), rev))
+}
+
+/// `path` relative to the repository root
+fn repo_path(workdir: &Path, cwd: &Path, path: &str) -> Result<PathBuf> {
+    let given = Path::new(path);
+    let starts_relative = matches!(
+        given.components().next(),
+        Some(Component::CurDir | Component::ParentDir)
+    );
+    if !given.is_absolute() && !starts_relative {
+        return Ok(normalize(given));
+    }
+
+    let workdir = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf());
+    let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    let absolute = normalize(&cwd.join(given));
+    absolute
+        .strip_prefix(&workdir)
+        .map(Path::to_path_buf)
+        .with_context(|| {
+            format!(
+                "{} is outside the repository at {}",
+                absolute.display(),
+                workdir.display()
+            )
+        })
+}
+
+/// Resolve `.` and `..` without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+
+    /// Repository with `schema/v.lumos` committed as `first`, then changed on disk
+    fn repository() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        fs::create_dir(dir.path().join("schema")).unwrap();
+        fs::write(dir.path().join("schema/v.lumos"), "first").unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("schema/v.lumos")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("lumos", "lumos@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "base", &tree, &[])
+            .unwrap();
+
+        fs::write(dir.path().join("schema/v.lumos"), "second").unwrap();
+        dir
+    }
+
+    #[test]
+    fn parses_git_specs() {
+        assert_eq!(
+            parse("git:main:schema.lumos"),
+            Some(("main", "schema.lumos"))
+        );
+        assert_eq!(parse("git:HEAD~1:./a.lumos"), Some(("HEAD~1", "./a.lumos")));
+        assert_eq!(parse("git:main"), None);
+        assert_eq!(parse("main:schema.lumos"), None);
+    }
+
+    #[test]
+    fn reads_committed_content_relative_to_root_or_cwd() {
+        let dir = repository();
+        let nested = dir.path().join("schema");
+
+        assert_eq!(
+            read_at(dir.path(), "HEAD", "schema/v.lumos").unwrap(),
+            "first"
+        );
+        assert_eq!(read_at(&nested, "HEAD", "schema/v.lumos").unwrap(), "first");
+        assert_eq!(read_at(&nested, "HEAD", "./v.lumos").unwrap(), "first");
+        assert_eq!(
+            read_at(&nested, "HEAD", "../schema/v.lumos").unwrap(),
+            "first"
+        );
+
+        let missing = read_at(dir.path(), "HEAD", "missing.lumos").unwrap_err();
+        assert!(missing.to_string().contains("missing.lumos at HEAD"));
+        assert!(read_at(dir.path(), "no-such-branch", "schema/v.lumos").is_err());
+    }
+}
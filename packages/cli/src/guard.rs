@@ -0,0 +1,388 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Breaking-change gate for CI (`lumos guard`)
+//!
+//! Compares a schema against a baseline, usually the same file at another
+//! git revision, and folds four checks into one verdict: the schema diff
+//! ([`lumos_core::schema_diff`]), Borsh layout compatibility, account size
+//! growth and new security findings above a severity threshold.
+
+use crate::git;
+use anyhow::Result;
+use clap::ValueEnum;
+use lumos_core::evolution::check_append_only;
+use lumos_core::ir::TypeDefinition;
+use lumos_core::schema_diff::diff;
+use lumos_core::security_analyzer::{SecurityAnalyzer, Severity};
+use lumos_core::size_calculator::{find_size_regressions, SizeCalculator};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Lowest security severity that fails the guard
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum FailOn {
+    /// Any finding
+    Info,
+    /// Warnings and critical findings
+    Warning,
+    /// Critical findings only
+    #[default]
+    Critical,
+    /// Report findings without failing
+    Never,
+}
+
+impl FailOn {
+    /// Whether a finding of `severity` fails the guard
+    fn blocks(self, severity: &Severity) -> bool {
+        match self {
+            FailOn::Info => true,
+            FailOn::Warning => *severity >= Severity::Warning,
+            FailOn::Critical => *severity == Severity::Critical,
+            FailOn::Never => false,
+        }
+    }
+}
+
+/// Limits applied by the guard
+#[derive(Debug, Clone, Copy)]
+pub struct Thresholds {
+    /// Maximum growth in bytes per account compared to the baseline
+    pub max_growth: usize,
+
+    /// Lowest security severity of a new finding that fails the guard
+    pub fail_on: FailOn,
+
+    /// Run the security analysis in strict mode
+    pub strict: bool,
+}
+
+/// Outcome of one check
+#[derive(Debug)]
+pub struct Check {
+    /// Stable identifier, e.g. `layout`
+    pub name: &'static str,
+
+    /// Whether the check passed
+    pub passed: bool,
+
+    /// One-line summary
+    pub summary: String,
+
+    /// One line per change, violation, regression or finding
+    pub details: Vec<String>,
+}
+
+/// Outcome of every check
+#[derive(Debug)]
+pub struct Verdict {
+    /// Checks in the order they ran
+    pub checks: Vec<Check>,
+}
+
+impl Verdict {
+    /// Whether every check passed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    /// Number of failed checks
+    pub fn failures(&self) -> usize {
+        self.checks.iter().filter(|check| !check.passed).count()
+    }
+
+    /// Render the verdict as structured JSON
+    pub fn to_json(&self, schema: &str, baseline: &str) -> Value {
+        json!({
+            "schema": schema,
+            "baseline": baseline,
+            "passed": self.passed(),
+            "checks": self.checks.iter().map(|check| json!({
+                "name": check.name,
+                "passed": check.passed,
+                "summary": check.summary,
+                "details": check.details,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Run every check of `current` against `baseline`
+pub fn run(
+    baseline: &[TypeDefinition],
+    current: &[TypeDefinition],
+    thresholds: Thresholds,
+) -> Verdict {
+    Verdict {
+        checks: vec![
+            diff_check(baseline, current),
+            layout_check(baseline, current),
+            size_check(baseline, current, thresholds.max_growth),
+            security_check(baseline, current, thresholds.fail_on, thresholds.strict),
+        ],
+    }
+}
+
+/// Read the baseline schema named by `spec`
+///
+/// `spec` is `git:<rev>:<path>`, a file path, `<rev>:<path>`, or a bare
+/// revision meaning the schema's own path at that revision. Returns the text
+/// and a label for the summary.
+pub fn read_baseline(spec: &str, schema_path: &Path) -> Result<(String, String)> {
+    if spec.starts_with(git::PREFIX) || Path::new(spec).is_file() {
+        return Ok((git::read_spec(spec)?, spec.to_string()));
+    }
+
+    let (rev, path) = match spec.split_once(':') {
+        Some((rev, path)) => (rev, path.to_string()),
+        None if schema_path.is_absolute() => (spec, schema_path.display().to_string()),
+        // `./` resolves the path against the current directory rather than
+        // the repository root
+        None => (spec, format!("./{}", schema_path.display())),
+    };
+    let content = git::read(rev, &path)?;
+    Ok((content, format!("{}:{}", rev, path)))
+}
+
+/// Types, fields and variants added, removed or changed, failing on changes
+/// that break existing data or clients
+fn diff_check(baseline: &[TypeDefinition], current: &[TypeDefinition]) -> Check {
+    let changes = diff(baseline, current);
+    let breaking = changes.iter().filter(|change| change.breaking).count();
+
+    let summary = if changes.is_empty() {
+        "No schema changes".to_string()
+    } else if breaking == 0 {
+        format!("{} change(s), none breaking", changes.len())
+    } else {
+        format!("{} change(s), {} breaking", changes.len(), breaking)
+    };
+
+    Check {
+        name: "diff",
+        passed: breaking == 0,
+        summary,
+        details: changes.iter().map(ToString::to_string).collect(),
+    }
+}
+
+/// Struct layouts must only append optional fields
+fn layout_check(baseline: &[TypeDefinition], current: &[TypeDefinition]) -> Check {
+    let violations = check_append_only(baseline, current);
+
+    Check {
+        name: "layout",
+        passed: violations.is_empty(),
+        summary: if violations.is_empty() {
+            "Existing accounts stay readable".to_string()
+        } else {
+            format!(
+                "{} change(s) would leave existing accounts unreadable",
+                violations.len()
+            )
+        },
+        details: violations
+            .iter()
+            .map(|v| format!("{} {}", v.location, v.message))
+            .collect(),
+    }
+}
+
+/// Accounts may not grow past `max_growth` bytes or exceed Solana's limits
+fn size_check(baseline: &[TypeDefinition], current: &[TypeDefinition], max_growth: usize) -> Check {
+    let before: HashMap<String, usize> = SizeCalculator::new(baseline)
+        .calculate_all()
+        .into_iter()
+        .map(|size| (size.name, size.total_bytes.min_bytes()))
+        .collect();
+    let sizes = SizeCalculator::new(current).calculate_all();
+    let regressions = find_size_regressions(&sizes, &before, max_growth);
+
+    let mut details: Vec<String> = regressions
+        .iter()
+        .map(|r| {
+            format!(
+                "{}: {} → {} bytes (+{})",
+                r.name,
+                r.baseline_bytes,
+                r.current_bytes,
+                r.growth()
+            )
+        })
+        .collect();
+    for size in &sizes {
+        for warning in &size.warnings {
+            details.push(format!("{}: {}", size.name, warning));
+        }
+    }
+
+    Check {
+        name: "size",
+        passed: details.is_empty(),
+        summary: match (regressions.len(), details.len() - regressions.len()) {
+            (0, 0) => format!("No account grew more than {} bytes", max_growth),
+            (grown, 0) => format!("{} account(s) grew more than {} bytes", grown, max_growth),
+            (0, warnings) => format!("{} size warning(s)", warnings),
+            (grown, warnings) => format!(
+                "{} account(s) grew more than {} bytes, {} size warning(s)",
+                grown, max_growth, warnings
+            ),
+        },
+        details,
+    }
+}
+
+/// No security finding at or above `fail_on` that the baseline doesn't have
+///
+/// Findings are matched by vulnerability and location, so a finding the
+/// baseline already had is reported in the summary but never fails the guard.
+fn security_check(
+    baseline: &[TypeDefinition],
+    current: &[TypeDefinition],
+    fail_on: FailOn,
+    strict: bool,
+) -> Check {
+    let analyze = |type_defs| {
+        let mut analyzer = SecurityAnalyzer::new(type_defs);
+        if strict {
+            analyzer = analyzer.with_strict_mode();
+        }
+        analyzer.analyze()
+    };
+    let known: HashSet<(&str, String)> = analyze(baseline)
+        .iter()
+        .map(|f| (f.vulnerability.id(), f.location.to_string()))
+        .collect();
+    let findings = analyze(current);
+    let blocking: Vec<_> = findings
+        .iter()
+        .filter(|f| fail_on.blocks(&f.severity))
+        .filter(|f| !known.contains(&(f.vulnerability.id(), f.location.to_string())))
+        .collect();
+
+    Check {
+        name: "security",
+        passed: blocking.is_empty(),
+        summary: match (findings.len(), blocking.len()) {
+            (0, _) => "No security findings".to_string(),
+            (total, 0) => format!(
+                "{} finding(s), none new at or above the failure threshold",
+                total
+            ),
+            (total, blocking) => format!(
+                "{} new of {} finding(s) fail the threshold",
+                blocking, total
+            ),
+        },
+        details: blocking
+            .iter()
+            .map(|f| format!("[{}] {}: {}", f.severity.as_str(), f.location, f.message))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumos_core::parser::parse_lumos_file;
+    use lumos_core::transform::transform_to_ir;
+
+    fn ir(source: &str) -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(source).unwrap()).unwrap()
+    }
+
+    const THRESHOLDS: Thresholds = Thresholds {
+        max_growth: 16,
+        fail_on: FailOn::Critical,
+        strict: false,
+    };
+
+    #[test]
+    fn passes_compatible_evolution() {
+        let baseline = ir(r#"
+            struct Player { wallet: PublicKey, level: u16 }
+            enum Status { Active, Paused }
+        "#);
+        let current = ir(r#"
+            struct Player { wallet: PublicKey, level: u16, nickname: Option<u32> }
+            enum Status { Active, Paused, Closed }
+            struct Guild { name: String }
+        "#);
+
+        let verdict = run(&baseline, &current, THRESHOLDS);
+        assert!(verdict.passed(), "{:?}", verdict);
+        assert_eq!(
+            verdict.checks[0].details,
+            [
+                "+ Player.nickname: Option<u32>",
+                "+ Status::Closed",
+                "+ Guild added"
+            ]
+        );
+    }
+
+    #[test]
+    fn fails_each_check_independently() {
+        let baseline = ir(r#"
+            #[solana]
+            #[account]
+            struct Vault { owner: PublicKey, amount: u64 }
+            enum Status { Active, Paused, Closed }
+            struct Legacy { id: u8 }
+        "#);
+        let current = ir(r#"
+            #[solana]
+            #[account]
+            struct Vault { amount: u64, owner: PublicKey, history: [u8], authority: PublicKey }
+            enum Status { Active, Closed }
+        "#);
+
+        let verdict = run(&baseline, &current, THRESHOLDS);
+        assert!(!verdict.passed());
+
+        let failed: Vec<_> = verdict
+            .checks
+            .iter()
+            .filter(|c| !c.passed)
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(failed, ["diff", "layout", "size", "security"]);
+        assert!(verdict.checks[0]
+            .details
+            .iter()
+            .any(|d| d.starts_with("- Legacy removed")));
+        assert!(verdict.checks[0]
+            .details
+            .iter()
+            .any(|d| d.starts_with("~ Status::Paused replaced by `Closed`")));
+        assert_eq!(verdict.to_json("s", "b")["passed"], false);
+    }
+
+    #[test]
+    fn passes_findings_already_in_the_baseline() {
+        let schema = ir(r#"
+            #[solana]
+            #[account]
+            struct GameItem { owner: PublicKey, power: u64 }
+        "#);
+        let strict = Thresholds {
+            fail_on: FailOn::Info,
+            ..THRESHOLDS
+        };
+
+        let verdict = run(&schema, &schema, strict);
+        assert!(verdict.passed(), "{:?}", verdict);
+        assert!(verdict.checks[3].summary.contains("none new"));
+    }
+
+    #[test]
+    fn severity_threshold_controls_security_failures() {
+        assert!(FailOn::Info.blocks(&Severity::Info));
+        assert!(FailOn::Warning.blocks(&Severity::Critical));
+        assert!(!FailOn::Warning.blocks(&Severity::Info));
+        assert!(!FailOn::Critical.blocks(&Severity::Warning));
+        assert!(!FailOn::Never.blocks(&Severity::Critical));
+    }
+}
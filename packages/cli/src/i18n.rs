@@ -0,0 +1,255 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Localized CLI and report strings
+//!
+//! Messages live in Fluent files under `locales/` and are embedded at build
+//! time. English is the fallback for any message a locale does not define.
+//!
+//! Checklist items, rule names and suggestions are produced in English by
+//! lumos-core. Other locales translate them by check or rule id and fall back
+//! to the core text when no translation exists.
+
+use anyhow::Result;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use lumos_core::audit_generator::{CheckCategory, ChecklistItem};
+use lumos_core::security_analyzer::{SecurityFinding, VulnerabilityType};
+use std::sync::OnceLock;
+
+/// Languages with a bundled message catalog
+pub const SUPPORTED_LANGS: [&str; 3] = ["en", "es", "zh"];
+
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+/// Message catalog for one language, with English fallback
+pub struct Localizer {
+    lang: &'static str,
+    bundle: FluentBundle<FluentResource>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+impl Localizer {
+    /// Load the catalog for `lang` (`es`, `es-MX` and `es_MX` all select `es`)
+    pub fn new(lang: &str) -> Result<Self> {
+        let requested = lang
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        let Some(lang) = SUPPORTED_LANGS.iter().find(|l| **l == requested) else {
+            anyhow::bail!(
+                "Unsupported language '{}' (supported: {})",
+                lang,
+                SUPPORTED_LANGS.join(", ")
+            );
+        };
+
+        Ok(Self {
+            lang,
+            bundle: load_bundle(lang),
+            fallback: load_bundle("en"),
+        })
+    }
+
+    /// Selected language code
+    pub fn lang(&self) -> &'static str {
+        self.lang
+    }
+
+    /// Format a message, falling back to English and then to the id itself
+    pub fn text(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let args = to_fluent_args(args);
+
+        format_message(&self.bundle, id, None, Some(&args))
+            .or_else(|| format_message(&self.fallback, id, None, Some(&args)))
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// Checklist item text and explanation
+    pub fn checklist(&self, item: &ChecklistItem) -> (String, String) {
+        let id = format!("checklist-{}", item.id);
+        let field = item.context.split("::").nth(1).unwrap_or_default();
+        let args = to_fluent_args(&[("field", field.into())]);
+
+        let text = format_message(&self.bundle, &id, None, Some(&args));
+        let explanation = format_message(&self.bundle, &id, Some("explanation"), Some(&args));
+
+        (
+            text.unwrap_or_else(|| item.item.clone()),
+            explanation.unwrap_or_else(|| item.explanation.clone()),
+        )
+    }
+
+    /// Checklist category name
+    pub fn category(&self, category: &CheckCategory) -> String {
+        let id = format!(
+            "category-{}",
+            category.as_str().to_lowercase().replace(' ', "-")
+        );
+        format_message(&self.bundle, &id, None, None).unwrap_or_else(|| category.as_str().into())
+    }
+
+    /// Security rule name
+    pub fn rule(&self, vulnerability: &VulnerabilityType) -> String {
+        let id = format!("rule-{}", vulnerability.id());
+        format_message(&self.bundle, &id, None, None)
+            .unwrap_or_else(|| vulnerability.as_str().into())
+    }
+
+    /// Suggested fix for a security finding
+    pub fn suggestion(&self, finding: &SecurityFinding) -> String {
+        self.suggestion_for(&finding.vulnerability, &finding.suggestion)
+    }
+
+    /// Suggested fix for a rule, given the core's English suggestion
+    pub fn suggestion_for(&self, vulnerability: &VulnerabilityType, english: &str) -> String {
+        let id = format!("suggestion-{}", vulnerability.id());
+        format_message(&self.bundle, &id, None, None).unwrap_or_else(|| english.to_string())
+    }
+}
+
+/// Select the language used by [`tr`] and [`get`] (first call wins)
+pub fn init(lang: &str) -> Result<()> {
+    let localizer = Localizer::new(lang)?;
+    let _ = LOCALIZER.set(localizer);
+    Ok(())
+}
+
+/// The active localizer (English until [`init`] is called)
+pub fn get() -> &'static Localizer {
+    LOCALIZER.get_or_init(|| Localizer::new("en").expect("English catalog is bundled"))
+}
+
+/// Format a message in the active language
+pub fn tr(id: &str) -> String {
+    get().text(id, &[])
+}
+
+/// Format a message with arguments in the active language
+pub fn tr_args(id: &str, args: &[(&str, FluentValue)]) -> String {
+    get().text(id, args)
+}
+
+fn catalog(lang: &str) -> &'static str {
+    match lang {
+        "es" => include_str!("../locales/es.ftl"),
+        "zh" => include_str!("../locales/zh.ftl"),
+        _ => include_str!("../locales/en.ftl"),
+    }
+}
+
+fn load_bundle(lang: &str) -> FluentBundle<FluentResource> {
+    let resource = FluentResource::try_new(catalog(lang).to_string())
+        .unwrap_or_else(|(_, errors)| panic!("invalid {} catalog: {:?}", lang, errors));
+
+    let langid = lang.parse().expect("bundled language ids are valid");
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Terminal and Markdown output must not contain Unicode isolation marks
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .unwrap_or_else(|errors| panic!("duplicate messages in {} catalog: {:?}", lang, errors));
+    bundle
+}
+
+fn to_fluent_args<'a>(args: &[(&'a str, FluentValue<'a>)]) -> FluentArgs<'a> {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    fluent_args
+}
+
+fn format_message(
+    bundle: &FluentBundle<FluentResource>,
+    id: &str,
+    attribute: Option<&str>,
+    args: Option<&FluentArgs>,
+) -> Option<String> {
+    let message = bundle.get_message(id)?;
+    let pattern = match attribute {
+        Some(name) => message.get_attribute(name)?.value(),
+        None => message.value()?,
+    };
+
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    errors.is_empty().then(|| text.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_language_and_falls_back_to_english() {
+        let es = Localizer::new("es-MX").unwrap();
+        assert_eq!(es.lang(), "es");
+        assert_eq!(
+            es.text("security-report-title", &[]),
+            "Informe de análisis de seguridad"
+        );
+
+        let en = Localizer::new("en").unwrap();
+        assert_eq!(
+            en.text("security-report-title", &[]),
+            "Security Analysis Report"
+        );
+        assert_eq!(
+            en.text("security-more-members", &[("count", 3.into())]),
+            "... and 3 more (use --format json for the full list)"
+        );
+        assert_eq!(en.text("no-such-message", &[]), "no-such-message");
+        assert!(Localizer::new("fr").is_err());
+    }
+
+    #[test]
+    fn every_locale_defines_every_english_message() {
+        let en = FluentResource::try_new(catalog("en").to_string()).unwrap();
+        let ids: Vec<_> = en
+            .entries()
+            .filter_map(|entry| match entry {
+                fluent_syntax::ast::Entry::Message(message) => Some(message.id.name),
+                _ => None,
+            })
+            .collect();
+
+        for lang in SUPPORTED_LANGS {
+            let bundle = load_bundle(lang);
+            for id in &ids {
+                assert!(bundle.has_message(id), "{} is missing '{}'", lang, id);
+            }
+        }
+    }
+
+    #[test]
+    fn translates_core_text_by_id() {
+        let source = r#"
+            #[solana]
+            #[account]
+            struct Vault {
+                authority: PublicKey,
+            }
+        "#;
+        let ir = lumos_core::transform::transform_to_ir(
+            lumos_core::parser::parse_lumos_file(source).unwrap(),
+        )
+        .unwrap();
+        let checklist = lumos_core::audit_generator::AuditGenerator::new(&ir).generate();
+        let signer = checklist
+            .iter()
+            .find(|item| item.id == "authority-signer")
+            .unwrap();
+
+        let en = Localizer::new("en").unwrap();
+        assert_eq!(en.checklist(signer).0, signer.item);
+
+        let zh = Localizer::new("zh").unwrap();
+        let (text, explanation) = zh.checklist(signer);
+        assert!(text.contains("'authority'"));
+        assert_ne!(explanation, signer.explanation);
+        assert_eq!(zh.category(&signer.category), "签名者检查");
+    }
+}
@@ -6,17 +6,76 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use colored::*;
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::fs;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
+use cache::DiskCache;
+use i18n::{tr, tr_args};
+use lumos_core::anchor_accounts::AccountsValidator;
 use lumos_core::audit_generator::AuditGenerator;
 use lumos_core::corpus_generator::CorpusGenerator;
-use lumos_core::fuzz_generator::FuzzGenerator;
-use lumos_core::generators::{rust, typescript};
+use lumos_core::criticality::Criticality;
+use lumos_core::determinism::check_deterministic;
+use lumos_core::evolution::check_append_only;
+use lumos_core::formatter::format_source;
+use lumos_core::fuzz_coverage::{CoverageAnalyzer, LlvmCoverageSummary, TypeCoverage};
+use lumos_core::fuzz_generator::{FuzzConfig, FuzzGenerator};
+use lumos_core::generators::cache::NoCache;
+use lumos_core::generators::idl::generate_idl;
+use lumos_core::generators::rust::{ClientDerives, RustConfig};
+use lumos_core::generators::summary::{changed_types, SchemaSummary, TypeChange};
+use lumos_core::generators::typescript::{Runtime, TypeScriptConfig};
+use lumos_core::generators::{
+    arrow, borsh_schema, c_header, docs, go, indexer, json_schema, openapi, python, rust,
+    typescript,
+};
+use lumos_core::idl;
+use lumos_core::ir::TypeDefinition;
+use lumos_core::linter::{self, LintConfig, Linter};
+use lumos_core::mock_generator::{self, MockGenerator};
 use lumos_core::parser::parse_lumos_file;
+use lumos_core::redaction::Redactor;
+use lumos_core::resolver;
+use lumos_core::rust_importer::{import_rust, ImportedSchema};
+use lumos_core::schema_diff;
+use lumos_core::search::Query;
 use lumos_core::security_analyzer::SecurityAnalyzer;
-use lumos_core::size_calculator::SizeCalculator;
+use lumos_core::size_calculator::{find_size_regressions, project_rent, SizeCalculator};
 use lumos_core::transform::transform_to_ir;
+use lumos_core::ts_importer::import_typescript;
+use lumos_core::type_graph::TypeGraph;
+use lumos_core::validation::{self, PassManager};
+use progress::Progress;
+
+#[macro_use]
+mod style;
+
+mod bench;
+mod bug_report;
+mod build_size;
+mod cache;
+mod config;
+mod daemon;
+mod dashboard;
+mod diagnostic;
+mod examples;
+mod git;
+mod guard;
+mod i18n;
+mod passes;
+mod paths;
+mod plugin;
+mod progress;
+mod report;
+mod reproducible;
+mod rpc;
+mod wasm_plugin;
+mod watch;
+mod wizard;
 
 #[derive(Parser)]
 #[command(name = "lumos")]
@@ -24,6 +83,26 @@ use lumos_core::transform::transform_to_ir;
 #[command(version)]
 #[command(author)]
 struct Cli {
+    /// Language for CLI messages and reports: en, es or zh (default: LUMOS_I18N_LANG, lumos.toml, then en)
+    #[arg(long, global = true)]
+    lang: Option<String>,
+
+    /// When to color output (honors NO_COLOR in auto mode)
+    #[arg(long, global = true, value_enum, default_value_t = style::ColorChoice::Auto)]
+    color: style::ColorChoice,
+
+    /// Use plain ASCII instead of emoji and box-drawing characters
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    /// Hide progress bars and status lines (warnings and errors are still shown)
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Print the resolved lumos.toml settings and where each value came from
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -46,6 +125,10 @@ enum Commands {
         #[arg(short, long)]
         watch: bool,
 
+        /// Show a live dashboard of timings, diagnostics, sizes and findings while watching
+        #[arg(long, requires = "watch")]
+        dashboard: bool,
+
         /// Preview changes without writing files
         #[arg(short = 'n', long)]
         dry_run: bool,
@@ -60,12 +143,75 @@ enum Commands {
         /// Anchor program id to embed in generated Anchor code (required when schema uses Anchor)
         #[arg(long = "address")]
         address: Option<String>,
+
+        /// Regenerate every type instead of reusing snippets from .lumos/cache
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Compile the generated Rust and report how much the [rust] settings save
+        #[arg(long, conflicts_with = "watch")]
+        report_size: bool,
+
+        /// Use the targets, output paths and options of [profiles.<NAME>] in lumos.toml
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+
+        /// Language to generate, repeatable; replaces the profile's targets
+        #[arg(long = "target", value_name = "LANG", value_parser = ["rust", "typescript", "python", "jsonschema", "go"])]
+        targets: Vec<String>,
     },
 
     /// Validate schema syntax without generating code
     Validate {
         /// Path to .lumos schema file
         schema: PathBuf,
+
+        /// Reject floats, target-width integers and unordered maps, for byte-stable state hashing
+        #[arg(long)]
+        deterministic: bool,
+
+        /// Only allow appending optional fields, across #[version] layouts and against --previous
+        #[arg(long)]
+        append_only: bool,
+
+        /// Previous release of the schema to compare layouts against (a file or `git:<rev>:<path>`)
+        #[arg(long, value_name = "SCHEMA", requires = "append_only")]
+        previous: Option<String>,
+    },
+
+    /// Check schema style and best practices
+    Lint {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Search fields of every schema, e.g. `lumos grep "type:PublicKey name:*authority*"`
+    Grep {
+        /// Query terms: name:, type:, in:, attr: and is: (a bare pattern matches names)
+        query: String,
+
+        /// Schema files or directories to search recursively
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Format .lumos schemas in place
+    Fmt {
+        /// Schema files to format
+        #[arg(required = true)]
+        schemas: Vec<PathBuf>,
+
+        /// Write nothing; list schemas that are not formatted and fail if there are any
+        #[arg(long)]
+        check: bool,
     },
 
     /// Initialize a new LUMOS project
@@ -74,6 +220,23 @@ enum Commands {
         name: Option<String>,
     },
 
+    /// Create a schema by answering questions about its accounts and fields
+    New {
+        /// Schema file to write
+        #[arg(default_value = "schema.lumos")]
+        schema: PathBuf,
+
+        /// Ask for each type and field, then write the schema and generate code
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// List, print or scaffold the example schemas shipped with LUMOS
+    Examples {
+        #[command(subcommand)]
+        command: ExamplesCommands,
+    },
+
     /// Check if generated code is up-to-date
     Check {
         /// Path to .lumos schema file
@@ -82,6 +245,33 @@ enum Commands {
         /// Output directory (default: current directory)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Expect TypeScript generated for this runtime (node, deno or bun) instead of the
+        /// `[typescript]` setting; deno also runs `deno check` when Deno is installed
+        #[arg(long)]
+        ts_runtime: Option<String>,
+    },
+
+    /// Regenerate in a temporary directory and compare byte-for-byte with the committed files
+    VerifyReproducible {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Directory holding the committed files (default: as `lumos generate`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Anchor program id the files were generated with
+        #[arg(long = "address")]
+        address: Option<String>,
+
+        /// Use the targets, output paths and options of [profiles.<NAME>] in lumos.toml
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+
+        /// Language to verify, repeatable; replaces the profile's targets
+        #[arg(long = "target", value_name = "LANG", value_parser = ["rust", "typescript", "python", "jsonschema", "go"])]
+        targets: Vec<String>,
     },
 
     /// Analyze account sizes and check for Solana limits
@@ -92,1553 +282,6072 @@ enum Commands {
         /// Output format (text or json)
         #[arg(short, long, default_value = "text")]
         format: String,
-    },
 
-    /// Security analysis commands
-    Security {
-        #[command(subcommand)]
-        command: SecurityCommands,
-    },
+        /// Baseline to compare against: sizes written by `check-size --format json`, or a
+        /// .lumos schema; either may be a file or `git:<rev>:<path>`
+        #[arg(long)]
+        baseline: Option<String>,
 
-    /// Audit checklist generation commands
-    Audit {
-        #[command(subcommand)]
-        command: AuditCommands,
-    },
+        /// Maximum allowed growth in bytes per account when comparing to a baseline
+        #[arg(long, default_value_t = 0, requires = "baseline")]
+        max_growth: usize,
 
-    /// Fuzz testing commands
-    Fuzz {
-        #[command(subcommand)]
-        command: FuzzCommands,
+        /// Project total rent for an expected account count, e.g. `UserAccount=100_000`
+        /// (repeatable; prints a rent budget summary instead of the size breakdown)
+        #[arg(long, value_name = "TYPE=COUNT", value_parser = parse_budget_spec)]
+        budget: Vec<(String, u64)>,
     },
-}
 
-#[derive(Subcommand)]
-enum SecurityCommands {
-    /// Analyze schema for common Solana vulnerabilities
-    Analyze {
+    /// Time parsing, transformation and generation of a schema, with allocation counts
+    Bench {
         /// Path to .lumos schema file
         schema: PathBuf,
 
+        /// Warm runs per phase, after one cold run
+        #[arg(short, long, default_value_t = 20)]
+        iterations: usize,
+
         /// Output format (text or json)
         #[arg(short, long, default_value = "text")]
         format: String,
+    },
 
-        /// Enable strict mode (more aggressive warnings)
+    /// Serve parse/validate/generate/check/analyze requests as JSON-RPC over stdio
+    Daemon,
+
+    /// Cross-check an Anchor program's `#[derive(Accounts)]` structs against the schema
+    CheckAccounts {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Anchor program source: a .rs file or a directory such as programs/<name>/src
         #[arg(short, long)]
-        strict: bool,
+        program: PathBuf,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
     },
-}
 
-#[derive(Subcommand)]
-enum AuditCommands {
-    /// Generate security audit checklist from schema
-    Generate {
+    /// Compare the schema against the IDL of a deployed Anchor program
+    VerifyOnchain {
         /// Path to .lumos schema file
         schema: PathBuf,
 
-        /// Output file path (default: SECURITY_AUDIT.md)
-        #[arg(short, long)]
-        output: Option<PathBuf>,
+        /// Program ID whose IDL is fetched with `anchor idl fetch`
+        #[arg(long, required_unless_present = "idl")]
+        program_id: Option<String>,
 
-        /// Output format (markdown or json)
-        #[arg(short, long, default_value = "markdown")]
+        /// RPC URL or cluster moniker (mainnet, devnet, localnet)
+        #[arg(short, long, default_value = "mainnet")]
+        url: String,
+
+        /// Compare against a saved IDL file instead of fetching it
+        #[arg(long, conflicts_with = "program_id")]
+        idl: Option<PathBuf>,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
         format: String,
     },
-}
 
-#[derive(Subcommand)]
-enum FuzzCommands {
-    /// Generate fuzz targets for types
-    Generate {
+    /// Decode instruction data against the schema's instructions and enums
+    DecodeIx {
         /// Path to .lumos schema file
         schema: PathBuf,
 
-        /// Output directory for fuzz targets (default: fuzz/)
+        /// Instruction data, base58-encoded as shown by explorers
         #[arg(short, long)]
-        output: Option<PathBuf>,
+        data: String,
 
-        /// Specific type to generate fuzz target for (optional)
-        #[arg(short, long)]
+        /// Decode as this type instead of trying every instruction and enum
+        #[arg(short = 't', long = "type")]
         type_name: Option<String>,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
     },
 
-    /// Run fuzzing for a specific type
-    Run {
+    /// Decode Anchor events in program logs into a typed timeline
+    DecodeLogs {
         /// Path to .lumos schema file
         schema: PathBuf,
 
-        /// Type to fuzz
-        #[arg(short, long)]
+        /// Program logs, as printed by `solana logs` or copied from an explorer
+        #[arg(long)]
+        file: PathBuf,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Encode a JSON value of a schema type as Borsh bytes
+    Encode {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Schema type of the value
+        #[arg(short = 't', long = "type")]
         type_name: String,
 
-        /// Number of parallel jobs
-        #[arg(short, long, default_value = "1")]
-        jobs: usize,
+        /// JSON file holding the value
+        #[arg(long)]
+        json: PathBuf,
 
-        /// Maximum run time in seconds (optional)
-        #[arg(short, long)]
-        max_time: Option<u64>,
+        /// Output encoding (hex, base64 or base58)
+        #[arg(short, long, default_value = "hex")]
+        encoding: String,
     },
 
-    /// Generate corpus files for fuzzing
-    Corpus {
+    /// Decode Borsh bytes of a schema type as pretty JSON
+    Decode {
         /// Path to .lumos schema file
         schema: PathBuf,
 
-        /// Output directory for corpus (default: fuzz/corpus/)
-        #[arg(short, long)]
-        output: Option<PathBuf>,
+        /// Schema type of the data
+        #[arg(short = 't', long = "type")]
+        type_name: String,
 
-        /// Specific type to generate corpus for (optional)
+        /// Borsh-encoded data
         #[arg(short, long)]
-        type_name: Option<String>,
+        data: String,
+
+        /// Encoding of the data (hex, base64 or base58)
+        #[arg(short, long, default_value = "hex")]
+        encoding: String,
     },
-}
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+    /// Suggest schema changes that improve generated layouts
+    Optimize {
+        /// Path to .lumos schema file
+        schema: PathBuf,
 
-    match cli.command {
-        Commands::Generate {
-            schema,
-            output,
-            watch,
-            dry_run,
-            backup,
-            show_diff,
-            address,
-        } => {
-            if watch {
-                run_watch_mode(&schema, output.as_deref(), address.as_deref())
-            } else {
-                run_generate(
-                    &schema,
-                    output.as_deref(),
-                    dry_run,
-                    backup,
-                    show_diff,
-                    address.as_deref(),
-                )
-            }
-        }
-        Commands::Validate { schema } => run_validate(&schema),
-        Commands::Init { name } => run_init(name.as_deref()),
-        Commands::Check { schema, output } => run_check(&schema, output.as_deref()),
-        Commands::CheckSize { schema, format } => run_check_size(&schema, &format),
-        Commands::Security { command } => match command {
-            SecurityCommands::Analyze {
-                schema,
-                format,
-                strict,
-            } => run_security_analyze(&schema, &format, strict),
-        },
-        Commands::Audit { command } => match command {
-            AuditCommands::Generate {
-                schema,
-                output,
-                format,
-            } => run_audit_generate(&schema, output.as_deref(), &format),
-        },
-        Commands::Fuzz { command } => match command {
-            FuzzCommands::Generate {
-                schema,
-                output,
-                type_name,
-            } => run_fuzz_generate(&schema, output.as_deref(), type_name.as_deref()),
-            FuzzCommands::Run {
-                schema,
-                type_name,
-                jobs,
-                max_time,
-            } => run_fuzz_run(&schema, &type_name, jobs, max_time),
-            FuzzCommands::Corpus {
-                schema,
-                output,
-                type_name,
-            } => run_fuzz_corpus(&schema, output.as_deref(), type_name.as_deref()),
-        },
-    }
-}
+        /// Suggest field orders that remove repr(C) padding and keep filter fields at
+        /// fixed offsets for memcmp
+        #[arg(long)]
+        layout: bool,
 
-/// Generate Rust and TypeScript code from schema
-fn run_generate(
-    schema_path: &Path,
-    output_dir: Option<&Path>,
-    dry_run: bool,
-    backup: bool,
-    show_diff: bool,
-    address: Option<&str>,
-) -> Result<()> {
-    let output_dir = output_dir.unwrap_or_else(|| Path::new("."));
+        /// Rewrite the schema with the suggested orders (a breaking layout change)
+        #[arg(long, requires = "layout")]
+        apply: bool,
 
-    // Validate output directory for security
-    validate_output_path(output_dir)?;
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
 
-    // Dry-run mode header
-    if dry_run {
-        println!(
-            "{}",
-            "🔍 Dry-run mode (no files will be written)\n".cyan().bold()
-        );
-    }
+    /// Run size, security, and audit analyses and write a combined report
+    Report {
+        /// Path to .lumos schema file
+        schema: PathBuf,
 
-    // Read schema file
-    if !dry_run {
-        println!("{:>12} {}", "Reading".cyan().bold(), schema_path.display());
-    }
+        /// Output file path (default: lumos-report.json or lumos-report.html)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
 
-    let content = fs::read_to_string(schema_path)
-        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
+        /// Output format (json or html)
+        #[arg(short, long, default_value = "json")]
+        format: String,
 
-    // Parse schema
-    if !dry_run {
-        println!("{:>12} schema", "Parsing".cyan().bold());
-    }
+        /// Enable strict mode for the security analysis
+        #[arg(short, long)]
+        strict: bool,
+    },
 
-    let ast = parse_lumos_file(&content)
-        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    /// Bundle the schema, settings, versions and a failing command's output into a zip for an issue
+    ///
+    /// Nothing is uploaded: review the zip, then attach it to a GitHub issue.
+    BugReport {
+        /// Path to .lumos schema file
+        schema: PathBuf,
 
-    // Transform to IR
-    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+        /// Output file (default: lumos-bug-report.zip)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
 
-    if ir.is_empty() {
-        eprintln!(
-            "{}: No type definitions found in schema",
-            "warning".yellow().bold()
-        );
-        return Ok(());
-    }
+        /// Rename types, fields, variants and constants, and drop comments
+        #[arg(long)]
+        redact: bool,
 
-    // Generate code
-    if !dry_run {
-        println!("{:>12} code", "Generating".green().bold());
-    }
+        /// Failing command to run again and record, after `--` (e.g. `-- generate schema.lumos`)
+        #[arg(last = true, value_name = "COMMAND")]
+        command: Vec<OsString>,
+    },
 
-    let rust_code = rust::generate_module(&ir);
-    let ts_code = typescript::generate_module(&ir);
+    /// List changes between two schemas and flag those that break Borsh layouts
+    Diff {
+        /// Old schema: a file or `git:<rev>:<path>`
+        #[arg(value_name = "OLD")]
+        old: String,
 
-    // If generated Rust code uses Anchor, require `--address` to be provided.
-    let uses_anchor = rust_code.contains("use anchor_lang::prelude::*");
+        /// New schema file
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
 
-    let mut rust_code = rust_code;
-    if uses_anchor {
-        let addr = if let Some(a) = address {
-            a.to_string()
-        } else {
-            anyhow::bail!("--address is required for Anchor code generation. Run: lumos generate <schema> --address <PROGRAM_ID>");
-        };
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
 
-        // Insert declare_id! after the anchor prelude import if possible
-        let prelude = "use anchor_lang::prelude::*;";
-        if let Some(pos) = rust_code.find(prelude) {
-            if let Some(line_end) = rust_code[pos..].find('\n') {
-                let insert_at = pos + line_end + 1;
-                let decl = format!("\ndeclare_id!(\"{}\");\n\n", addr);
-                rust_code.insert_str(insert_at, &decl);
-            } else {
-                let decl = format!("\n\ndeclare_id!(\"{}\");\n\n", addr);
-                rust_code.push_str(&decl);
-            }
+    /// Fail CI on breaking schema changes, size regressions or security findings
+    Guard {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Baseline schema: `REV:PATH` read with git, a revision (same path), or a file
+        #[arg(short, long, value_name = "REV:PATH")]
+        baseline: String,
+
+        /// Maximum allowed growth in bytes per account compared to the baseline
+        #[arg(long, default_value_t = 0)]
+        max_growth: usize,
+
+        /// Lowest severity of a new security finding that fails the guard
+        #[arg(long, value_enum, default_value_t = guard::FailOn::Critical)]
+        fail_on: guard::FailOn,
+
+        /// Enable strict mode for the security analysis
+        #[arg(short, long)]
+        strict: bool,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Security analysis commands
+    Security {
+        #[command(subcommand)]
+        command: SecurityCommands,
+    },
+
+    /// Audit checklist generation commands
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
+
+    /// Fuzz testing commands
+    Fuzz {
+        #[command(subcommand)]
+        command: FuzzCommands,
+    },
+
+    /// Inspect and validate lumos.toml
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Indexer support commands
+    Indexer {
+        #[command(subcommand)]
+        command: IndexerCommands,
+    },
+
+    /// Query the type dependency graph
+    Graph {
+        #[command(subcommand)]
+        command: GraphCommands,
+    },
+
+    /// Generate seeded mock instances of a type for frontend development
+    Mock {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Type to generate instances of
+        #[arg(short, long = "type")]
+        type_name: String,
+
+        /// Number of instances
+        #[arg(short, long, default_value_t = 10)]
+        count: usize,
+
+        /// Output format: json or ts
+        #[arg(short, long, default_value = "json")]
+        format: String,
+
+        /// Seed for the generated values (same seed produces the same data)
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// Output file (default: print to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Module the TypeScript mocks import their type from
+        #[arg(long, default_value = "./generated")]
+        import_from: String,
+    },
+
+    /// Generate account reference documentation, as one Markdown page or a site
+    Docs {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Generate a documentation site with one page per type
+        #[arg(long)]
+        site: bool,
+
+        /// Site layout: mdbook or docusaurus
+        #[arg(long, default_value = "mdbook", requires = "site")]
+        site_format: String,
+
+        /// Title of the reference
+        #[arg(long, default_value = "Account Reference")]
+        title: String,
+
+        /// Output file, or directory with --site (default: print to stdout, or `book` with --site)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Export an OpenAPI 3.1 document with a component schema per type
+    Openapi {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Encoding of byte arrays: base64 or base58
+        #[arg(long, default_value = "base64")]
+        bytes: String,
+
+        /// API title (default: the schema file name)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// API version
+        #[arg(long, default_value = "1.0.0")]
+        api_version: String,
+
+        /// Output file (default: print to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Generate an Anchor IDL of the schema's instructions, accounts and events
+    Idl {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Program name (default: the schema block's name, or the schema file name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Program version recorded in the IDL metadata (default: the schema block's version, or 0.1.0)
+        #[arg(long)]
+        program_version: Option<String>,
+
+        /// Program ID recorded as the IDL address
+        #[arg(long)]
+        address: Option<String>,
+
+        /// Output file (default: print to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Export Apache Arrow schemas of the account types for Parquet/DuckDB ingestion
+    Arrow {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Output format: json (Arrow JSON schemas) or rust (arrow-schema module)
+        #[arg(short, long, default_value = "json")]
+        format: String,
+
+        /// With --format rust, also generate RecordBatch conversions from the generated types
+        #[arg(long)]
+        conversions: bool,
+
+        /// Module path of the generated Rust types, used by --conversions
+        #[arg(long, default_value = "crate::generated")]
+        types_module: String,
+
+        /// Output file (default: print to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Export BorshSchema containers for Borsh-ecosystem tooling
+    BorshSchema {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Export only this type (default: every #[account] type, or every type if there are none)
+        #[arg(short, long = "type")]
+        type_name: Option<String>,
+
+        /// Container encoding: borsh (as borsh::to_vec writes it) or json
+        #[arg(short, long, default_value = "borsh")]
+        format: String,
+
+        /// Output directory, with one file per type
+        #[arg(short, long, default_value = "borsh-schema")]
+        output: PathBuf,
+    },
+
+    /// Derive a schema from existing source code
+    Import {
+        #[command(subcommand)]
+        command: ImportCommands,
+    },
+
+    /// Run a WASM generator plugin and write the files it returns
+    Plugin {
+        /// Path to the plugin module (.wasm, or .wat text)
+        plugin: PathBuf,
+
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Output directory (default: current directory)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Preview changes without writing files
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Run a `lumos-<name>` plugin from PATH
+    #[command(external_subcommand)]
+    External(Vec<OsString>),
+}
+
+#[derive(Subcommand)]
+enum SecurityCommands {
+    /// Analyze schema for common Solana vulnerabilities
+    Analyze {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Enable strict mode (more aggressive warnings)
+        #[arg(short, long)]
+        strict: bool,
+
+        /// Group identical findings per rule with a count and member list
+        #[arg(short, long)]
+        group: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditCommands {
+    /// Generate security audit checklist from schema
+    Generate {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Output file path (default: SECURITY_AUDIT.md)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Output format (markdown or json)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+    },
+
+    /// Decode sampled on-chain accounts of a program against the schema
+    Sample {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Program that owns the accounts
+        #[arg(long)]
+        program_id: String,
+
+        /// RPC URL or cluster moniker (mainnet, devnet, testnet, localnet)
+        #[arg(short, long, default_value = "mainnet")]
+        url: String,
+
+        /// Accounts to sample per account type
+        #[arg(short = 'n', long, default_value_t = 20)]
+        samples: usize,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Report unknown keys and invalid values
+    Check {
+        /// Path to lumos.toml
+        #[arg(default_value = "lumos.toml")]
+        path: PathBuf,
+    },
+
+    /// Print the settings in effect
+    Print {
+        /// Path to lumos.toml
+        #[arg(default_value = "lumos.toml")]
+        path: PathBuf,
+
+        /// Include defaults for every setting not in the file
+        #[arg(long)]
+        resolved: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum IndexerCommands {
+    /// Generate a Rust module decoding raw account updates into schema types
+    Generate {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Output file (default: indexer.rs)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Rust module the generated schema types are imported from
+        #[arg(long, default_value = "crate::generated")]
+        types_module: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExamplesCommands {
+    /// List the example schemas
+    List,
+
+    /// Print an example schema
+    Show {
+        /// Example name, e.g. gaming
+        name: String,
+    },
+
+    /// Create a project from an example schema
+    Init {
+        /// Example name, e.g. gaming
+        name: String,
+
+        /// Project directory (default: the example name)
+        directory: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GraphCommands {
+    /// Show which types a type uses or is used by, to assess the blast radius of a change
+    Deps {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// List every type using TYPE, directly or through other types
+        #[arg(long, value_name = "TYPE", conflicts_with_all = ["depends_of", "roots"])]
+        who_uses: Option<String>,
+
+        /// List every type TYPE uses, directly or through other types
+        #[arg(long, value_name = "TYPE", conflicts_with = "roots")]
+        depends_of: Option<String>,
+
+        /// List the types no other type uses
+        #[arg(long)]
+        roots: bool,
+
+        /// Output format (text, json or dot)
+        #[arg(short, long, default_value = "text", value_parser = ["text", "json", "dot"])]
+        format: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ImportCommands {
+    /// Import the `#[account]` structs of a Rust source file
+    Rust {
+        /// Rust source file with the program's state structs
+        source: PathBuf,
+
+        /// Output .lumos file (default: print to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Propose a schema from TypeScript Borsh layouts and interfaces
+    #[command(alias = "ts")]
+    Typescript {
+        /// TypeScript source file with `borsh.struct` layouts or interfaces
+        source: PathBuf,
+
+        /// Output .lumos file (default: print to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum FuzzCommands {
+    /// Generate fuzz targets for types
+    Generate {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Output directory for fuzz targets (default: fuzz/)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Specific type to generate fuzz target for (optional)
+        #[arg(short, long)]
+        type_name: Option<String>,
+
+        /// Package name of the crate with the generated types (default: detected)
+        #[arg(long)]
+        crate_name: Option<String>,
+
+        /// Path to that crate, relative to the output directory (default: ..)
+        #[arg(long)]
+        crate_path: Option<String>,
+    },
+
+    /// Run fuzzing for a specific type
+    Run {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Type to fuzz
+        #[arg(short, long)]
+        type_name: String,
+
+        /// Number of parallel jobs
+        #[arg(short, long, default_value = "1")]
+        jobs: usize,
+
+        /// Maximum run time in seconds (optional)
+        #[arg(short, long)]
+        max_time: Option<u64>,
+    },
+
+    /// Generate corpus files for fuzzing
+    Corpus {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Output directory for corpus (default: fuzz/corpus/)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Specific type to generate corpus for (optional)
+        #[arg(short, long)]
+        type_name: Option<String>,
+
+        /// Number of seeded random instances to generate per type
+        #[arg(short, long, default_value_t = 4)]
+        random: usize,
+
+        /// Seed for random instances (same seed produces the same corpus)
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// Merge into existing corpora without overwriting, skipping duplicate content
+        #[arg(long)]
+        sync: bool,
+
+        /// Run `cargo fuzz cmin` on each updated target after syncing
+        #[arg(long, requires = "sync")]
+        minimize: bool,
+    },
+
+    /// Report which schema fields and variants the corpus exercises
+    Coverage {
+        /// Path to .lumos schema file
+        schema: PathBuf,
+
+        /// Specific type to report coverage for (optional)
+        #[arg(short, long)]
+        type_name: Option<String>,
+
+        /// Corpus directory (default: fuzz/corpus/)
+        #[arg(short, long)]
+        corpus: Option<PathBuf>,
+
+        /// Output format: text or json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Only replay the corpus against the schema (skip `cargo fuzz coverage` and llvm-cov)
+        #[arg(long)]
+        schema_only: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            errln!("Error: {:?}", error);
+            if let Some(snippet) = diagnostic::for_error(&error) {
+                errln!("\n{}", snippet);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse();
+    style::init(cli.color, cli.ascii);
+    progress::init(cli.quiet);
+
+    config::init(config::Flags {
+        lang: cli.lang.clone(),
+        verbose: cli.verbose,
+    });
+
+    // `lumos config` reports problems in lumos.toml itself
+    let lang = match &cli.command {
+        Commands::Config { .. } => cli.lang.clone(),
+        _ => config::load(Path::new("lumos.toml"))?
+            .get_str("i18n.lang")
+            .map(str::to_string),
+    }
+    .unwrap_or_else(|| "en".to_string());
+    i18n::init(&lang)?;
+
+    match cli.command {
+        Commands::Generate {
+            schema,
+            output,
+            watch,
+            dashboard,
+            dry_run,
+            backup,
+            show_diff,
+            address,
+            no_cache,
+            report_size,
+            profile,
+            targets,
+        } => {
+            if dashboard {
+                dashboard::run(&schema, watch_debounce(), || {
+                    run_generate(
+                        &schema,
+                        output.as_deref(),
+                        false,
+                        false,
+                        false,
+                        address.as_deref(),
+                        no_cache,
+                        profile.as_deref(),
+                        &targets,
+                    )
+                })
+            } else if watch {
+                run_watch_mode(
+                    &schema,
+                    output.as_deref(),
+                    address.as_deref(),
+                    no_cache,
+                    profile.as_deref(),
+                    &targets,
+                )
+            } else {
+                run_generate(
+                    &schema,
+                    output.as_deref(),
+                    dry_run,
+                    backup,
+                    show_diff,
+                    address.as_deref(),
+                    no_cache,
+                    profile.as_deref(),
+                    &targets,
+                )?;
+                if report_size {
+                    run_report_size(&schema, address.as_deref(), profile.as_deref())?;
+                }
+                Ok(())
+            }
+        }
+        Commands::Validate {
+            schema,
+            deterministic,
+            append_only,
+            previous,
+        } => run_validate(&schema, deterministic, append_only, previous.as_deref()),
+        Commands::Lint { schema, format } => run_lint(&schema, &format),
+        Commands::Grep {
+            query,
+            paths,
+            format,
+        } => run_grep(&query, &paths, &format),
+        Commands::Fmt { schemas, check } => run_fmt(&schemas, check),
+        Commands::Init { name } => run_init(name.as_deref()),
+        Commands::New {
+            schema,
+            interactive,
+        } => run_new(&schema, interactive),
+        Commands::Examples { command } => match command {
+            ExamplesCommands::List => run_examples_list(),
+            ExamplesCommands::Show { name } => run_examples_show(&name),
+            ExamplesCommands::Init { name, directory } => {
+                run_examples_init(&name, directory.as_deref())
+            }
+        },
+        Commands::Check {
+            schema,
+            output,
+            ts_runtime,
+        } => run_check(&schema, output.as_deref(), ts_runtime.as_deref()),
+        Commands::VerifyReproducible {
+            schema,
+            output,
+            address,
+            profile,
+            targets,
+        } => run_verify_reproducible(
+            &schema,
+            output.as_deref(),
+            address.as_deref(),
+            profile.as_deref(),
+            &targets,
+        ),
+        Commands::CheckSize {
+            schema,
+            format,
+            baseline,
+            max_growth,
+            budget,
+        } => run_check_size(&schema, &format, baseline.as_deref(), max_growth, &budget),
+        Commands::Bench {
+            schema,
+            iterations,
+            format,
+        } => run_bench(&schema, iterations, &format),
+        Commands::Daemon => {
+            let stdin = io::stdin();
+            daemon::serve(stdin.lock(), io::stdout().lock())?;
+            Ok(())
+        }
+        Commands::CheckAccounts {
+            schema,
+            program,
+            format,
+        } => run_check_accounts(&schema, &program, &format),
+        Commands::VerifyOnchain {
+            schema,
+            program_id,
+            url,
+            idl,
+            format,
+        } => run_verify_onchain(
+            &schema,
+            program_id.as_deref(),
+            &url,
+            idl.as_deref(),
+            &format,
+        ),
+        Commands::DecodeIx {
+            schema,
+            data,
+            type_name,
+            format,
+        } => run_decode_ix(&schema, &data, type_name.as_deref(), &format),
+        Commands::DecodeLogs {
+            schema,
+            file,
+            format,
+        } => run_decode_logs(&schema, &file, &format),
+        Commands::Encode {
+            schema,
+            type_name,
+            json,
+            encoding,
+        } => run_encode(&schema, &type_name, &json, &encoding),
+        Commands::Decode {
+            schema,
+            type_name,
+            data,
+            encoding,
+        } => run_decode(&schema, &type_name, &data, &encoding),
+        Commands::Optimize {
+            schema,
+            layout,
+            apply,
+            format,
+        } => run_optimize(&schema, layout, apply, &format),
+        Commands::Diff { old, new, format } => run_diff(&old, &new, &format),
+        Commands::Guard {
+            schema,
+            baseline,
+            max_growth,
+            fail_on,
+            strict,
+            format,
+        } => run_guard(
+            &schema,
+            &baseline,
+            guard::Thresholds {
+                max_growth,
+                fail_on,
+                strict,
+            },
+            &format,
+        ),
+        Commands::Security { command } => match command {
+            SecurityCommands::Analyze {
+                schema,
+                format,
+                strict,
+                group,
+            } => run_security_analyze(&schema, &format, strict, group),
+        },
+        Commands::Report {
+            schema,
+            output,
+            format,
+            strict,
+        } => run_report(&schema, output.as_deref(), &format, strict),
+        Commands::BugReport {
+            schema,
+            output,
+            redact,
+            command,
+        } => run_bug_report(&schema, output.as_deref(), redact, &command),
+        Commands::Audit { command } => match command {
+            AuditCommands::Generate {
+                schema,
+                output,
+                format,
+            } => run_audit_generate(&schema, output.as_deref(), &format),
+            AuditCommands::Sample {
+                schema,
+                program_id,
+                url,
+                samples,
+                format,
+            } => run_audit_sample(&schema, &program_id, &url, samples, &format),
+        },
+        Commands::Fuzz { command } => match command {
+            FuzzCommands::Generate {
+                schema,
+                output,
+                type_name,
+                crate_name,
+                crate_path,
+            } => run_fuzz_generate(
+                &schema,
+                output.as_deref(),
+                type_name.as_deref(),
+                crate_name,
+                crate_path,
+            ),
+            FuzzCommands::Run {
+                schema,
+                type_name,
+                jobs,
+                max_time,
+            } => run_fuzz_run(&schema, &type_name, jobs, max_time),
+            FuzzCommands::Corpus {
+                schema,
+                output,
+                type_name,
+                random,
+                seed,
+                sync,
+                minimize,
+            } => run_fuzz_corpus(
+                &schema,
+                output.as_deref(),
+                type_name.as_deref(),
+                random,
+                seed,
+                sync,
+                minimize,
+            ),
+            FuzzCommands::Coverage {
+                schema,
+                type_name,
+                corpus,
+                format,
+                schema_only,
+            } => run_fuzz_coverage(
+                &schema,
+                type_name.as_deref(),
+                corpus.as_deref(),
+                &format,
+                schema_only,
+            ),
+        },
+        Commands::Mock {
+            schema,
+            type_name,
+            count,
+            format,
+            seed,
+            output,
+            import_from,
+        } => run_mock(
+            &schema,
+            &type_name,
+            count,
+            &format,
+            seed,
+            output.as_deref(),
+            &import_from,
+        ),
+        Commands::Docs {
+            schema,
+            site,
+            site_format,
+            title,
+            output,
+        } => run_docs(
+            &schema,
+            site.then_some(site_format.as_str()),
+            &title,
+            output.as_deref(),
+        ),
+        Commands::Openapi {
+            schema,
+            bytes,
+            title,
+            api_version,
+            output,
+        } => run_openapi(
+            &schema,
+            &bytes,
+            title.as_deref(),
+            &api_version,
+            output.as_deref(),
+        ),
+        Commands::Idl {
+            schema,
+            name,
+            program_version,
+            address,
+            output,
+        } => run_idl(
+            &schema,
+            name.as_deref(),
+            program_version.as_deref(),
+            address.as_deref(),
+            output.as_deref(),
+        ),
+        Commands::Arrow {
+            schema,
+            format,
+            conversions,
+            types_module,
+            output,
+        } => run_arrow(
+            &schema,
+            &format,
+            conversions,
+            &types_module,
+            output.as_deref(),
+        ),
+        Commands::BorshSchema {
+            schema,
+            type_name,
+            format,
+            output,
+        } => run_borsh_schema(&schema, type_name.as_deref(), &format, &output),
+        Commands::Config { command } => match command {
+            ConfigCommands::Check { path } => run_config_check(&path),
+            ConfigCommands::Print { path, resolved } => run_config_print(&path, resolved),
+        },
+        Commands::Indexer { command } => match command {
+            IndexerCommands::Generate {
+                schema,
+                output,
+                types_module,
+            } => run_indexer_generate(&schema, output.as_deref(), &types_module),
+        },
+        Commands::Graph { command } => match command {
+            GraphCommands::Deps {
+                schema,
+                who_uses,
+                depends_of,
+                roots,
+                format,
+            } => {
+                let query = match (who_uses, depends_of) {
+                    (Some(name), _) => GraphQuery::WhoUses(name),
+                    (_, Some(name)) => GraphQuery::DependsOf(name),
+                    _ if roots => GraphQuery::Roots,
+                    _ => GraphQuery::All,
+                };
+                run_graph_deps(&schema, &query, &format)
+            }
+        },
+        Commands::Import { command } => match command {
+            ImportCommands::Rust { source, output } => {
+                run_import(&source, output.as_deref(), "rust", import_rust)
+            }
+            ImportCommands::Typescript { source, output } => {
+                run_import(&source, output.as_deref(), "typescript", import_typescript)
+            }
+        },
+        Commands::Plugin {
+            plugin,
+            schema,
+            output,
+            dry_run,
+        } => run_wasm_plugin(&plugin, &schema, output.as_deref(), dry_run),
+        Commands::External(args) => plugin::run_external(&args),
+    }
+}
+
+/// Generate Rust and TypeScript code from schema
+#[allow(clippy::too_many_arguments)]
+fn run_generate(
+    schema_path: &Path,
+    output_dir: Option<&Path>,
+    dry_run: bool,
+    backup: bool,
+    show_diff: bool,
+    address: Option<&str>,
+    no_cache: bool,
+    profile: Option<&str>,
+    targets: &[String],
+) -> Result<()> {
+    // Flags take precedence over the profile
+    let options = load_generate_profile(schema_path, profile, targets)?;
+    let output_dir = output_dir
+        .map(Path::to_path_buf)
+        .or(options.directory.clone())
+        .unwrap_or_else(|| PathBuf::from("."));
+    let address = address.or(options.address.as_deref());
+
+    // Validate output directory for security
+    validate_output_path(&output_dir)?;
+
+    // Dry-run mode header
+    if dry_run {
+        outln!(
+            "{}",
+            "🔍 Dry-run mode (no files will be written)\n".cyan().bold()
+        );
+    }
+
+    let progress = Progress::new().quiet_if(dry_run);
+
+    // Read and parse the schema with its imports
+    let ast = progress.stage("Reading".cyan().bold(), schema_path.display(), || {
+        resolver::load(schema_path)
+            .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))
+    })?;
+
+    let schema = ast.metadata.clone();
+
+    // Transform to IR
+    let ir = progress.stage("Parsing".cyan().bold(), "schema", || {
+        transform_to_ir(ast.clone()).with_context(|| "Failed to transform AST to IR")
+    })?;
+
+    if ir.is_empty() {
+        errln!(
+            "{}: No type definitions found in schema",
+            "warning".yellow().bold()
+        );
+        return Ok(());
+    }
+
+    check_validation_passes(schema_path, &ast, &ir)?;
+
+    // If generated Rust code uses Anchor, require `--address` to be provided.
+    if options.rust && rust::uses_anchor(&ir) && address.is_none() {
+        anyhow::bail!("--address is required for Anchor code generation. Run: lumos generate <schema> --address <PROGRAM_ID>");
+    }
+
+    let rust_config = RustConfig {
+        schema: schema.clone(),
+        ..load_rust_config(schema_path, profile)?
+    };
+    let ts_config = TypeScriptConfig {
+        schema,
+        ..load_typescript_config(schema_path, profile)?
+    };
+    let rust_output = output_dir.join(&options.rust_file);
+    let ts_output = output_dir.join(&options.typescript_file);
+    // `#[zero_copy]` structs also get a C header next to the Rust module
+    let header_output = rust_output.with_extension("h");
+    let header = match options.rust {
+        true => c_header::generate(&ir, &header_guard(&header_output))
+            .with_context(|| "Failed to generate C header")?,
+        false => None,
+    };
+    let python_output = output_dir.join(&options.python_file);
+    let python_code = options.python.then(|| python::generate_module(&ir));
+    let json_schema_output = output_dir.join(&options.jsonschema_file);
+    let json_schema_code = match options.jsonschema {
+        true => {
+            let title = match &rust_config.schema {
+                Some(metadata) => metadata.name.clone(),
+                None => schema_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            };
+            let document =
+                json_schema::generate_document(&ir, &title, openapi::ByteEncoding::Base64);
+            Some(serde_json::to_string_pretty(&document)? + "\n")
+        }
+        false => None,
+    };
+    let go_output = output_dir.join(&options.go_file);
+    let go_code = options
+        .go
+        .then(|| go::generate_module(&ir, &go_package(&go_output)));
+    let outputs: Vec<&Path> = [
+        (options.rust, rust_output.as_path()),
+        (header.is_some(), header_output.as_path()),
+        (options.typescript, ts_output.as_path()),
+        (options.python, python_output.as_path()),
+        (options.jsonschema, json_schema_output.as_path()),
+        (options.go, go_output.as_path()),
+    ]
+    .into_iter()
+    .filter_map(|(selected, output)| selected.then_some(output))
+    .collect();
+    let rust_stage = format!("Rust for {} types", ir.len());
+    let ts_stage = format!("TypeScript for {} types", ir.len());
+
+    // Reuse unchanged types' code from earlier runs (never written in dry-run mode)
+    let cache_root = schema_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let open_cache = |target| match dry_run || no_cache {
+        true => DiskCache::disabled(),
+        false => DiskCache::open(cache_root, target),
+    };
+    let mut rust_cache = open_cache("rust");
+    let mut ts_cache = open_cache("typescript");
+
+    let (rust_written, ts_written) = if dry_run || show_diff {
+        // Previews and diffs compare against existing files, so build the code in memory
+        let rust_code = match options.rust {
+            true => Some(progress.stage("Generating".green().bold(), rust_stage, || {
+                render_module(|out| {
+                    rust::generate_configured_to(&ir, address, &rust_config, &mut rust_cache, out)
+                })
+            })?),
+            false => None,
+        };
+        let ts_code = match options.typescript {
+            true => Some(progress.stage("Generating".green().bold(), ts_stage, || {
+                render_module(|out| {
+                    typescript::generate_configured_to(&ir, &ts_config, &mut ts_cache, out)
+                })
+            })?),
+            false => None,
+        };
+
+        // Dry-run mode: preview only
+        if dry_run {
+            if let Some(rust_code) = &rust_code {
+                preview_file_changes(&rust_output, rust_code, "Rust")?;
+            }
+            if let Some(header) = &header {
+                preview_file_changes(&header_output, header, "C header")?;
+            }
+            if let Some(ts_code) = &ts_code {
+                preview_file_changes(&ts_output, ts_code, "TypeScript")?;
+            }
+            if let Some(python_code) = &python_code {
+                preview_file_changes(&python_output, python_code, "Python")?;
+            }
+            if let Some(json_schema_code) = &json_schema_code {
+                preview_file_changes(&json_schema_output, json_schema_code, "JSON Schema")?;
+            }
+            if let Some(go_code) = &go_code {
+                preview_file_changes(&go_output, go_code, "Go")?;
+            }
+
+            outln!("\n{}", "No files written (dry-run mode).".yellow());
+            outln!("Run without --dry-run to apply changes.");
+            return Ok(());
+        }
+
+        if backup {
+            backup_outputs(&progress, &outputs)?;
+        }
+
+        let mut rust_written = false;
+        if let Some(rust_code) = &rust_code {
+            rust_written = write_with_diff_check(&rust_output, rust_code, show_diff, "Rust")?;
+            report_write(&progress, &rust_output, rust_written);
+        }
+        let mut ts_written = false;
+        if let Some(ts_code) = &ts_code {
+            ts_written = write_with_diff_check(&ts_output, ts_code, show_diff, "TypeScript")?;
+            report_write(&progress, &ts_output, ts_written);
+        }
+        (rust_written, ts_written)
+    } else {
+        if backup {
+            backup_outputs(&progress, &outputs)?;
+        }
+
+        // Stream each module straight to disk, one type at a time
+        if options.rust {
+            progress.stage("Generating".green().bold(), rust_stage, || {
+                stream_module(&rust_output, "Rust", |out| {
+                    rust::generate_configured_to(&ir, address, &rust_config, &mut rust_cache, out)
+                })
+            })?;
+            report_write(&progress, &rust_output, true);
+        }
+        if options.typescript {
+            progress.stage("Generating".green().bold(), ts_stage, || {
+                stream_module(&ts_output, "TypeScript", |out| {
+                    typescript::generate_configured_to(&ir, &ts_config, &mut ts_cache, out)
+                })
+            })?;
+            report_write(&progress, &ts_output, true);
+        }
+        (options.rust, options.typescript)
+    };
+
+    let mut header_written = false;
+    if let Some(header) = &header {
+        header_written = write_with_diff_check(&header_output, header, show_diff, "C header")?;
+        report_write(&progress, &header_output, header_written);
+    }
+    let mut python_written = false;
+    if let Some(python_code) = &python_code {
+        python_written = write_with_diff_check(&python_output, python_code, show_diff, "Python")?;
+        report_write(&progress, &python_output, python_written);
+    }
+    let mut json_schema_written = false;
+    if let Some(json_schema_code) = &json_schema_code {
+        json_schema_written = write_with_diff_check(
+            &json_schema_output,
+            json_schema_code,
+            show_diff,
+            "JSON Schema",
+        )?;
+        report_write(&progress, &json_schema_output, json_schema_written);
+    }
+    let mut go_written = false;
+    if let Some(go_code) = &go_code {
+        go_written = write_with_diff_check(&go_output, go_code, show_diff, "Go")?;
+        report_write(&progress, &go_output, go_written);
+    }
+
+    rust_cache.prune();
+    ts_cache.prune();
+    let cached = rust_cache.hits() + ts_cache.hits();
+    if cached > 0 {
+        progress.status(
+            "Cached".cyan().bold(),
+            format!(
+                "reused {} of {} type snippets",
+                cached,
+                ir.len() * (usize::from(options.rust) + usize::from(options.typescript))
+            ),
+        );
+    }
+
+    // Success summary
+    if rust_written || ts_written || python_written || json_schema_written || go_written {
+        if !progress::quiet() {
+            outln!();
+        }
+        progress.status(
+            "Finished".green().bold(),
+            format!(
+                "generated {} type definitions in {}",
+                ir.len(),
+                progress.elapsed()
+            ),
+        );
+    }
+
+    // Backup restoration hint
+    if backup && (rust_written || ts_written || python_written || json_schema_written || go_written)
+    {
+        outln!("\n{}", "Backups created. Restore with:".dimmed());
+        for (written, output) in [
+            (rust_written, &rust_output),
+            (header_written, &header_output),
+            (ts_written, &ts_output),
+            (python_written, &python_output),
+            (json_schema_written, &json_schema_output),
+            (go_written, &go_output),
+        ] {
+            let backup_path = paths::backup_path(output);
+            if written && backup_path.exists() {
+                outln!(
+                    "  mv {} {}",
+                    backup_path.display().to_string().dimmed(),
+                    output.display().to_string().dimmed()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Go package for a file at `path`, named after its directory
+fn go_package(path: &Path) -> String {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let dir = dir
+        .canonicalize()
+        .or_else(|_| std::env::current_dir().map(|cwd| cwd.join(dir)));
+    match dir.ok().as_deref().and_then(Path::file_name) {
+        Some(name) => go::package_name(&name.to_string_lossy()),
+        None => go::DEFAULT_PACKAGE.to_string(),
+    }
+}
+
+/// Include guard for a C header, `out/game.h` -> `GAME_H`
+fn header_guard(path: &Path) -> String {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut guard: String = stem
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect();
+    if guard.starts_with(|c: char| c.is_ascii_digit()) {
+        guard.insert(0, '_');
+    }
+    guard + "_H"
+}
+
+/// Collect a generated module in memory
+fn render_module(write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> Result<String> {
+    let mut output = Vec::new();
+    write(&mut output)?;
+    Ok(String::from_utf8(output)?)
+}
+
+/// Write a generated module to `path` without holding it in memory
+fn stream_module(
+    path: &Path,
+    label: &str,
+    write: impl FnOnce(&mut io::BufWriter<fs::File>) -> io::Result<()>,
+) -> Result<()> {
+    let file = fs::File::create(paths::long_path(path))
+        .with_context(|| format!("Failed to write {}: {}", label, path.display()))?;
+    let mut out = io::BufWriter::new(file);
+    write(&mut out)
+        .and_then(|_| out.flush())
+        .with_context(|| format!("Failed to write {}: {}", label, path.display()))
+}
+
+/// Back up existing output files before they are overwritten
+fn backup_outputs(progress: &Progress, outputs: &[&Path]) -> Result<()> {
+    progress.status("Backing up".cyan().bold(), "files...");
+    for output in outputs {
+        create_backup_if_exists(output)?;
+    }
+    Ok(())
+}
+
+/// Report whether an output file was written or skipped
+fn report_write(progress: &Progress, path: &Path, written: bool) {
+    if written {
+        progress.status("Wrote".green().bold(), path.display().to_string().bold());
+    } else {
+        progress.status(
+            "Skipped".yellow().bold(),
+            path.display().to_string().dimmed(),
+        );
+    }
+}
+
+/// Preview file changes in dry-run mode
+fn preview_file_changes(path: &Path, new_content: &str, label: &str) -> Result<()> {
+    let new_lines = new_content.lines().count();
+    let new_size = new_content.len();
+
+    outln!(
+        "Would generate: {} ({})",
+        path.display().to_string().bold(),
+        label.cyan()
+    );
+    outln!(
+        "  Size: {} lines ({:.1} KB)",
+        new_lines,
+        new_size as f64 / 1024.0
+    );
+
+    if path.exists() {
+        let old_content = fs::read_to_string(path)?;
+        let old_lines = old_content.lines().count();
+
+        if new_content == old_content {
+            outln!("  {}", "No changes (identical to existing)".dimmed());
+        } else {
+            let added = new_lines.saturating_sub(old_lines);
+            let removed = old_lines.saturating_sub(new_lines);
+
+            if added > 0 {
+                outln!("  {} {} lines", "+".green(), added);
+            }
+            if removed > 0 {
+                outln!("  {} {} lines", "-".red(), removed);
+            }
+            if added == 0 && removed == 0 {
+                outln!("  {} content modified", "~".yellow());
+            }
+        }
+    } else {
+        outln!("  {}", "New file (doesn't exist yet)".green());
+    }
+
+    outln!();
+    Ok(())
+}
+
+/// Create backup of file if it exists
+fn create_backup_if_exists(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let backup_path = paths::backup_path(path);
+
+    fs::copy(paths::long_path(path), paths::long_path(&backup_path))
+        .with_context(|| format!("Failed to create backup: {}", backup_path.display()))?;
+
+    outln!(
+        "  {} → {}",
+        path.display().to_string().dimmed(),
+        backup_path.display().to_string().cyan()
+    );
+
+    Ok(())
+}
+
+/// Write file with optional diff check and confirmation
+fn write_with_diff_check(path: &Path, content: &str, show_diff: bool, label: &str) -> Result<bool> {
+    // If show_diff and file exists, show diff and ask for confirmation
+    if show_diff && path.exists() {
+        let old_content = fs::read_to_string(path)?;
+
+        // If identical, skip
+        if content == old_content {
+            outln!(
+                "{}: {} {}",
+                "Unchanged".dimmed(),
+                path.display().to_string().dimmed(),
+                format!("({})", label).dimmed()
+            );
+            return Ok(false);
+        }
+
+        // Show diff
+        show_diff_and_ask_confirmation(path, &old_content, content, label)?;
+
+        // User declined
+        return Ok(false);
+    }
+
+    // Write file
+    fs::write(paths::long_path(path), content)
+        .with_context(|| format!("Failed to write {}: {}", label, path.display()))?;
+
+    Ok(true)
+}
+
+/// Show diff and ask for user confirmation
+fn show_diff_and_ask_confirmation(
+    path: &Path,
+    old_content: &str,
+    new_content: &str,
+    label: &str,
+) -> Result<()> {
+    outln!("\n{}", "─".repeat(60).dimmed());
+    outln!(
+        "DIFF: {} ({})",
+        path.display().to_string().bold(),
+        label.cyan()
+    );
+    outln!("{}", "─".repeat(60).dimmed());
+    outln!();
+
+    // Simple line-by-line diff
+    let old_lines: Vec<&str> = old_content.lines().collect();
+    let new_lines: Vec<&str> = new_content.lines().collect();
+
+    let mut added = 0;
+    let mut removed = 0;
+    let max_lines = old_lines.len().max(new_lines.len());
+
+    // Show first 20 lines of diff
+    let preview_limit = 20;
+    for i in 0..max_lines.min(preview_limit) {
+        let old_line = old_lines.get(i);
+        let new_line = new_lines.get(i);
+
+        match (old_line, new_line) {
+            (Some(old), Some(new)) if old != new => {
+                outln!("{} {}", "-".red(), old);
+                outln!("{} {}", "+".green(), new);
+                added += 1;
+                removed += 1;
+            }
+            (Some(old), None) => {
+                outln!("{} {}", "-".red(), old);
+                removed += 1;
+            }
+            (None, Some(new)) => {
+                outln!("{} {}", "+".green(), new);
+                added += 1;
+            }
+            (Some(line), Some(_)) => {
+                outln!("  {}", line.dimmed());
+            }
+            _ => {}
+        }
+    }
+
+    if max_lines > preview_limit {
+        outln!(
+            "\n{}",
+            format!("... ({} more lines)", max_lines - preview_limit).dimmed()
+        );
+    }
+
+    outln!();
+    outln!("Summary:");
+    if added > 0 {
+        outln!("  Lines added: {}", added.to_string().green());
+    }
+    if removed > 0 {
+        outln!("  Lines removed: {}", removed.to_string().red());
+    }
+    outln!();
+
+    // Ask for confirmation
+    print!("Apply changes to {}? [y/N] ", path.display());
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    let response = input.trim().to_lowercase();
+    if response == "y" || response == "yes" {
+        fs::write(paths::long_path(path), new_content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        outln!("{:>12} {}", "Applied".green().bold(), path.display());
+        Ok(())
+    } else {
+        outln!("{:>12} {}", "Skipped".yellow().bold(), path.display());
+        Ok(())
+    }
+}
+
+/// Generate mock instances of a type as JSON or a TypeScript module
+fn run_mock(
+    schema_path: &Path,
+    type_name: &str,
+    count: usize,
+    format: &str,
+    seed: u64,
+    output_path: Option<&Path>,
+    import_from: &str,
+) -> Result<()> {
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let Some(values) = MockGenerator::new(&ir)
+        .with_seed(seed)
+        .generate(type_name, count)
+    else {
+        anyhow::bail!(
+            "Type '{}' not found in schema: {}",
+            type_name,
+            schema_path.display()
+        );
+    };
+
+    let rendered = match format {
+        "json" => mock_generator::to_json(&values),
+        "ts" => {
+            let runtime = load_typescript_config(schema_path, None)?.runtime;
+            mock_generator::to_typescript(type_name, &values, import_from, runtime)
+        }
+        other => anyhow::bail!("Unsupported mock format '{}' (expected json or ts)", other),
+    };
+
+    let Some(output_path) = output_path else {
+        print!("{}", rendered);
+        return Ok(());
+    };
+
+    fs::write(paths::long_path(output_path), rendered)
+        .with_context(|| format!("Failed to write mocks to {}", output_path.display()))?;
+    Progress::new().status(
+        "Generated".green().bold(),
+        format!(
+            "{} {} mocks in {}",
+            values.len(),
+            type_name,
+            output_path.display().to_string().bold()
+        ),
+    );
+
+    Ok(())
+}
+
+/// Generate the account update decoder module for indexers
+/// What `lumos graph deps` reports
+enum GraphQuery {
+    /// Types using a type
+    WhoUses(String),
+
+    /// Types a type uses
+    DependsOf(String),
+
+    /// Types no other type uses
+    Roots,
+
+    /// Every type with the types it uses directly
+    All,
+}
+
+/// Query the type dependency graph of a schema
+fn run_graph_deps(schema_path: &Path, query: &GraphQuery, format: &str) -> Result<()> {
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+    let graph = TypeGraph::new(&ir);
+
+    let (name, reached) = match query {
+        GraphQuery::WhoUses(name) => (name.as_str(), graph.users_of(name)),
+        GraphQuery::DependsOf(name) => (name.as_str(), graph.dependencies_of(name)),
+        GraphQuery::Roots | GraphQuery::All => ("", Vec::new()),
+    };
+    if !name.is_empty() && !graph.contains(name) {
+        anyhow::bail!(
+            "Type '{}' not found in schema: {}",
+            name,
+            schema_path.display()
+        );
+    }
+
+    match format {
+        "json" => {
+            let types: Vec<serde_json::Value> = reached
+                .iter()
+                .map(|r| serde_json::json!({ "name": r.name, "depth": r.depth(), "via": r.via }))
+                .collect();
+            let value = match query {
+                GraphQuery::WhoUses(_) => serde_json::json!({ "type": name, "used_by": types }),
+                GraphQuery::DependsOf(_) => {
+                    serde_json::json!({ "type": name, "depends_on": types })
+                }
+                GraphQuery::Roots => serde_json::json!({ "roots": graph.roots() }),
+                GraphQuery::All => {
+                    let types: serde_json::Map<String, serde_json::Value> = graph
+                        .names()
+                        .iter()
+                        .map(|name| (name.to_string(), serde_json::json!(graph.uses(name))))
+                        .collect();
+                    serde_json::json!({ "types": types })
+                }
+            };
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+        "dot" => {
+            let names: Vec<&str> = match query {
+                GraphQuery::WhoUses(_) | GraphQuery::DependsOf(_) => std::iter::once(name)
+                    .chain(reached.iter().map(|r| r.name))
+                    .collect(),
+                GraphQuery::Roots | GraphQuery::All => graph.names().to_vec(),
+            };
+            print!("{}", graph.to_dot(&names));
+        }
+        _ => match query {
+            GraphQuery::WhoUses(_) | GraphQuery::DependsOf(_) => {
+                let relation = match query {
+                    GraphQuery::WhoUses(_) => "used by",
+                    _ => "depends on",
+                };
+                if reached.is_empty() {
+                    outln!("{} is {} no other type", name.bold(), relation);
+                    return Ok(());
+                }
+                outln!("{} is {} {} type(s):", name.bold(), relation, reached.len());
+                let width = reached.iter().map(|r| r.name.len()).max().unwrap_or(0);
+                for r in &reached {
+                    if r.via.is_empty() {
+                        outln!("  {}", r.name);
+                    } else {
+                        let via = format!("(via {})", r.via.join(" -> "));
+                        outln!("  {:<width$}  {}", r.name, via.dimmed(), width = width);
+                    }
+                }
+            }
+            GraphQuery::Roots => {
+                let roots = graph.roots();
+                outln!("{} root type(s):", roots.len());
+                for root in roots {
+                    outln!("  {}", root);
+                }
+            }
+            GraphQuery::All => {
+                let width = graph.names().iter().map(|n| n.len()).max().unwrap_or(0);
+                for name in graph.names() {
+                    let uses = graph.uses(name);
+                    if uses.is_empty() {
+                        outln!("  {}", name);
+                    } else {
+                        outln!("  {:<width$}  -> {}", name, uses.join(", "), width = width);
+                    }
+                }
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn run_indexer_generate(
+    schema_path: &Path,
+    output_path: Option<&Path>,
+    types_module: &str,
+) -> Result<()> {
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let Some(code) = indexer::generate_module(&ir, types_module) else {
+        anyhow::bail!(
+            "No #[account] types to decode in schema: {}",
+            schema_path.display()
+        );
+    };
+
+    let output_path = output_path.unwrap_or_else(|| Path::new("indexer.rs"));
+    fs::write(paths::long_path(output_path), code)
+        .with_context(|| format!("Failed to write decoder to {}", output_path.display()))?;
+    Progress::new().status(
+        "Generated".green().bold(),
+        format!(
+            "account update decoder in {}",
+            output_path.display().to_string().bold()
+        ),
+    );
+
+    Ok(())
+}
+
+/// Write the account reference as a single page, or as a site when `site_format` is given
+fn run_docs(
+    schema_path: &Path,
+    site_format: Option<&str>,
+    title: &str,
+    output_path: Option<&Path>,
+) -> Result<()> {
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let schema = ast.metadata.clone();
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let Some(site_format) = site_format else {
+        let page = docs::generate_markdown(&ir, title, schema.as_ref());
+        let Some(output_path) = output_path else {
+            print!("{}", page);
+            return Ok(());
+        };
+        fs::write(paths::long_path(output_path), page)
+            .with_context(|| format!("Failed to write docs to {}", output_path.display()))?;
+        Progress::new().status(
+            "Generated".green().bold(),
+            format!(
+                "account reference in {}",
+                output_path.display().to_string().bold()
+            ),
+        );
+        return Ok(());
+    };
+
+    let format = match site_format {
+        "mdbook" => docs::SiteFormat::MdBook,
+        "docusaurus" => docs::SiteFormat::Docusaurus,
+        other => anyhow::bail!(
+            "Unsupported site format '{}' (expected mdbook or docusaurus)",
+            other
+        ),
+    };
+
+    let output_dir = output_path.unwrap_or_else(|| Path::new("book"));
+    let files = docs::generate_site(&ir, title, schema.as_ref(), format);
+    for file in &files {
+        let path = output_dir.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(paths::long_path(parent))
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        fs::write(paths::long_path(&path), &file.content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Progress::new().status(
+        "Generated".green().bold(),
+        format!(
+            "{} site with {} pages in {}",
+            site_format,
+            ir.len(),
+            output_dir.display().to_string().bold()
+        ),
+    );
+
+    Ok(())
+}
+
+/// Export the schema's types as OpenAPI component schemas
+fn run_openapi(
+    schema_path: &Path,
+    bytes: &str,
+    title: Option<&str>,
+    api_version: &str,
+    output_path: Option<&Path>,
+) -> Result<()> {
+    let bytes = match bytes {
+        "base64" => openapi::ByteEncoding::Base64,
+        "base58" => openapi::ByteEncoding::Base58,
+        other => anyhow::bail!(
+            "Unsupported byte encoding '{}' (expected base64 or base58)",
+            other
+        ),
+    };
+
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let default_title = schema_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let document =
+        openapi::generate_document(&ir, title.unwrap_or(&default_title), api_version, bytes);
+    let rendered = format!("{}\n", serde_json::to_string_pretty(&document)?);
+
+    let Some(output_path) = output_path else {
+        print!("{}", rendered);
+        return Ok(());
+    };
+    fs::write(paths::long_path(output_path), rendered).with_context(|| {
+        format!(
+            "Failed to write OpenAPI document to {}",
+            output_path.display()
+        )
+    })?;
+    Progress::new().status(
+        "Generated".green().bold(),
+        format!(
+            "{} component schemas in {}",
+            ir.len(),
+            output_path.display().to_string().bold()
+        ),
+    );
+
+    Ok(())
+}
+
+/// Generate the Anchor IDL of the schema's program
+fn run_idl(
+    schema_path: &Path,
+    name: Option<&str>,
+    program_version: Option<&str>,
+    address: Option<&str>,
+    output_path: Option<&Path>,
+) -> Result<()> {
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let schema = ast.metadata.clone().unwrap_or_default();
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let default_name = match schema.name.is_empty() {
+        true => schema_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        false => schema.name,
+    };
+    let program_version = program_version
+        .or(schema.version.as_deref())
+        .unwrap_or("0.1.0");
+    let document = generate_idl(&ir, name.unwrap_or(&default_name), program_version, address);
+    let rendered = format!("{}\n", serde_json::to_string_pretty(&document)?);
+
+    let Some(output_path) = output_path else {
+        print!("{}", rendered);
+        return Ok(());
+    };
+    fs::write(paths::long_path(output_path), rendered)
+        .with_context(|| format!("Failed to write IDL to {}", output_path.display()))?;
+    Progress::new().status(
+        "Generated".green().bold(),
+        format!(
+            "{} instructions in {}",
+            document["instructions"].as_array().map_or(0, Vec::len),
+            output_path.display().to_string().bold()
+        ),
+    );
+
+    Ok(())
+}
+
+/// Export Arrow schemas of the account types as JSON or as a Rust module
+fn run_arrow(
+    schema_path: &Path,
+    format: &str,
+    conversions: bool,
+    types_module: &str,
+    output_path: Option<&Path>,
+) -> Result<()> {
+    if !matches!(format, "json" | "rust") {
+        anyhow::bail!("Unsupported format '{}' (expected json or rust)", format);
+    }
+    if conversions && format != "rust" {
+        anyhow::bail!("--conversions requires --format rust");
+    }
+
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let schemas = arrow::generate_json(&ir);
+    let count = schemas.as_object().map_or(0, |s| s.len());
+    if count == 0 {
+        anyhow::bail!("No structs to export in {}", schema_path.display());
+    }
+    let rendered = match format {
+        "rust" => arrow::generate_module(&ir, types_module, conversions),
+        _ => format!("{}\n", serde_json::to_string_pretty(&schemas)?),
+    };
+
+    let Some(output_path) = output_path else {
+        print!("{}", rendered);
+        return Ok(());
+    };
+    fs::write(paths::long_path(output_path), rendered)
+        .with_context(|| format!("Failed to write Arrow schemas to {}", output_path.display()))?;
+    Progress::new().status(
+        "Generated".green().bold(),
+        format!(
+            "{} Arrow schemas in {}",
+            count,
+            output_path.display().to_string().bold()
+        ),
+    );
+
+    Ok(())
+}
+
+/// Write a Borsh schema container per exported type
+fn run_borsh_schema(
+    schema_path: &Path,
+    type_name: Option<&str>,
+    format: &str,
+    output_dir: &Path,
+) -> Result<()> {
+    if !matches!(format, "borsh" | "json") {
+        anyhow::bail!("Unsupported format '{}' (expected borsh or json)", format);
+    }
+
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let names: Vec<&str> = match type_name {
+        Some(name) => vec![name],
+        None => {
+            let accounts: Vec<&str> = ir
+                .iter()
+                .filter(|t| t.is_account())
+                .map(|t| t.name())
+                .collect();
+            if accounts.is_empty() {
+                ir.iter().map(|t| t.name()).collect()
+            } else {
+                accounts
+            }
+        }
+    };
+
+    fs::create_dir_all(paths::long_path(output_dir))
+        .with_context(|| format!("Failed to create {}", output_dir.display()))?;
+    for name in &names {
+        let container = borsh_schema::container_for(&ir, name)
+            .with_context(|| format!("Failed to build the Borsh schema of '{}'", name))?;
+        let (file, bytes) = match format {
+            "json" => (
+                format!("{}.json", name),
+                format!("{}\n", serde_json::to_string_pretty(&container.to_json())?).into_bytes(),
+            ),
+            _ => (format!("{}.borsh", name), container.to_borsh()),
+        };
+        let path = output_dir.join(file);
+        fs::write(paths::long_path(&path), bytes)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    Progress::new().status(
+        "Generated".green().bold(),
+        format!(
+            "{} Borsh schema container(s) in {}",
+            names.len(),
+            output_dir.display().to_string().bold()
+        ),
+    );
+
+    Ok(())
+}
+
+/// Derive a .lumos schema from existing source with one of the importers
+fn run_import(
+    source_path: &Path,
+    output_path: Option<&Path>,
+    command: &str,
+    import: fn(&str) -> lumos_core::error::Result<ImportedSchema>,
+) -> Result<()> {
+    let content = fs::read_to_string(source_path)
+        .with_context(|| format!("Failed to read source file: {}", source_path.display()))?;
+    let imported =
+        import(&content).with_context(|| format!("Failed to import {}", source_path.display()))?;
+
+    for warning in &imported.warnings {
+        errln!("{}: {}", "warning".yellow().bold(), warning);
+    }
+
+    let file_name = source_path.file_name().map_or_else(
+        || source_path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+    let rendered = format!(
+        "// Imported from {} by `lumos import {}`\n\n{}",
+        file_name, command, imported.source
+    );
+
+    let Some(output_path) = output_path else {
+        print!("{}", rendered);
+        return Ok(());
+    };
+
+    fs::write(paths::long_path(output_path), rendered)
+        .with_context(|| format!("Failed to write schema to {}", output_path.display()))?;
+    Progress::new().status(
+        "Imported".green().bold(),
+        format!(
+            "{} types into {}",
+            imported.types.len(),
+            output_path.display().to_string().bold()
+        ),
+    );
+
+    Ok(())
+}
+
+/// Run a WASM generator plugin on a schema and write the returned files
+fn run_wasm_plugin(
+    plugin_path: &Path,
+    schema_path: &Path,
+    output_dir: Option<&Path>,
+    dry_run: bool,
+) -> Result<()> {
+    let output_dir = output_dir.unwrap_or_else(|| Path::new("."));
+    validate_output_path(output_dir)?;
+
+    if dry_run {
+        outln!(
+            "{}",
+            "🔍 Dry-run mode (no files will be written)\n".cyan().bold()
+        );
+    }
+
+    let progress = Progress::new().quiet_if(dry_run);
+
+    let input = progress.stage("Parsing".cyan().bold(), schema_path.display(), || {
+        plugin::ir_json(schema_path)
+    })?;
+    let files = progress.stage("Running".green().bold(), plugin_path.display(), || {
+        wasm_plugin::generate(plugin_path, &input)
+    })?;
+
+    if files.is_empty() {
+        errln!("{}: Plugin returned no files", "warning".yellow().bold());
+        return Ok(());
+    }
+
+    let label = plugin_path.file_stem().map_or_else(
+        || "plugin".to_string(),
+        |stem| stem.to_string_lossy().into_owned(),
+    );
+
+    if dry_run {
+        for (path, content) in &files {
+            preview_file_changes(&output_dir.join(path), content, &label)?;
+        }
+        outln!("\n{}", "No files written (dry-run mode).".yellow());
+        outln!("Run without --dry-run to apply changes.");
+        return Ok(());
+    }
+
+    for (path, content) in &files {
+        let path = output_dir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(paths::long_path(parent))
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        write_with_diff_check(&path, content, false, &label)?;
+        report_write(&progress, &path, true);
+    }
+
+    if !progress::quiet() {
+        outln!();
+    }
+    progress.status(
+        "Finished".green().bold(),
+        format!("wrote {} files in {}", files.len(), progress.elapsed()),
+    );
+
+    Ok(())
+}
+
+/// Compile the generated Rust with and without the `[rust]` settings and compare sizes
+fn run_report_size(schema_path: &Path, address: Option<&str>, profile: Option<&str>) -> Result<()> {
+    let options = load_generate_profile(schema_path, profile, &[])?;
+    let address = address.or(options.address.as_deref());
+
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+    let config = load_rust_config(schema_path, profile)?;
+
+    let generate = |config: &RustConfig| {
+        render_module(|out| rust::generate_configured_to(&ir, address, config, &mut NoCache, out))
+    };
+    let full_code = generate(&RustConfig::default())?;
+    let configured_code = generate(&config)?;
+
+    let mut features: Vec<String> = [&config.client_derives, &config.borsh_schema]
+        .into_iter()
+        .chain(config.type_overrides.values())
+        .filter_map(|mode| match mode {
+            ClientDerives::Feature(feature) => Some(feature.clone()),
+            _ => None,
+        })
+        .collect();
+    features.sort();
+    features.dedup();
+
+    let root = schema_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let progress = Progress::new();
+    let sizes = progress.stage(
+        "Compiling".cyan().bold(),
+        "generated Rust (release)",
+        || {
+            build_size::measure(
+                root,
+                &full_code,
+                &configured_code,
+                &features,
+                rust::uses_anchor(&ir),
+            )
+        },
+    )?;
+
+    let kib = |bytes: u64| format!("{:.1} KiB", bytes as f64 / 1024.0);
+    let saved = sizes.full.saturating_sub(sizes.configured);
+    progress.status(
+        "Size".green().bold(),
+        format!(
+            "{} with Debug/Clone, {} as configured ({} saved)",
+            kib(sizes.full),
+            kib(sizes.configured).bold(),
+            kib(saved)
+        ),
+    );
+
+    Ok(())
+}
+
+/// Lint a schema with the rule levels of `[lint]` in lumos.toml
+fn run_lint(schema_path: &Path, format: &str) -> Result<()> {
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast.clone()).with_context(|| "Failed to transform AST to IR")?;
+
+    let lints = Linter::new(&ir)
+        .with_config(load_lint_config(schema_path)?)
+        .lint();
+    let denied = lints
+        .iter()
+        .filter(|lint| lint.level == linter::Level::Deny)
+        .count();
+
+    if format == "json" {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report::lints_to_json(&lints))?
+        );
+    } else {
+        outln!("{:>12} {}", "Linting".cyan().bold(), schema_path.display());
+        outln!();
+
+        if lints.is_empty() {
+            outln!("{}", "✓ No lints".green().bold());
+        }
+
+        for lint in &lints {
+            let label = match lint.level {
+                linter::Level::Deny => "error".red().bold(),
+                _ => "warning".yellow().bold(),
+            };
+            outln!("{}[{}]: {}", label, lint.rule, lint.message);
+            let field = lint.location.field_name.as_deref();
+            if let Some(location) = diagnostic::definition(&ast, &lint.location.type_name, field) {
+                outln!("  {} {}", "-->".blue().bold(), location.format());
+            }
+            outln!("  {} {}", "help:".cyan().bold(), lint.suggestion);
+            outln!();
+        }
+
+        if !lints.is_empty() {
+            outln!(
+                "{:>12} {} warning(s), {} error(s)",
+                "Finished".green().bold(),
+                lints.len() - denied,
+                denied
+            );
+        }
+    }
+
+    if denied > 0 {
+        anyhow::bail!(
+            "Schema has {} lint error(s); set the rules to \"warn\" or \"allow\" in [lint] of lumos.toml to accept them",
+            denied
+        );
+    }
+    Ok(())
+}
+
+/// Search the fields of every schema under `paths`
+fn run_grep(query: &str, paths: &[PathBuf], format: &str) -> Result<()> {
+    let query = Query::parse(query).map_err(anyhow::Error::msg)?;
+
+    let mut schemas = Vec::new();
+    for path in paths {
+        lumos_sources(path, &mut schemas)?;
+    }
+    if schemas.is_empty() {
+        anyhow::bail!("No .lumos schemas found");
+    }
+
+    // Imported types are found from every schema importing them; report each once
+    let mut seen = std::collections::HashSet::new();
+    let mut hits = Vec::new();
+    for schema in &schemas {
+        let ir = resolver::load(schema)
+            .map_err(anyhow::Error::from)
+            .and_then(|ast| Ok((transform_to_ir(ast.clone())?, ast)));
+        let (ir, ast) = match ir {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                errln!(
+                    "{}: skipped {}: {}",
+                    "warning".yellow().bold(),
+                    schema.display(),
+                    e
+                );
+                continue;
+            }
+        };
+
+        for hit in query.search(&ir) {
+            let origin = ast.origins.get(&hit.type_name).unwrap_or(schema);
+            let origin = fs::canonicalize(origin).unwrap_or_else(|_| origin.clone());
+            if !seen.insert((origin, hit.path())) {
+                continue;
+            }
+            let location = match &hit.variant {
+                Some(variant) => {
+                    diagnostic::variant_definition(&ast, &hit.type_name, variant, &hit.field_name)
+                }
+                None => diagnostic::definition(&ast, &hit.type_name, Some(&hit.field_name)),
+            };
+            hits.push((location, hit));
+        }
+    }
+
+    if format == "json" {
+        let json: Vec<_> = hits
+            .iter()
+            .map(|(location, hit)| {
+                serde_json::json!({
+                    "file": location.as_ref().and_then(|l| l.file.as_ref()),
+                    "line": location.as_ref().map(|l| l.line),
+                    "column": location.as_ref().map(|l| l.column),
+                    "type": hit.type_name,
+                    "variant": hit.variant,
+                    "field": hit.field_name,
+                    "field_type": hit.type_signature,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json)?);
+    } else {
+        for (location, hit) in &hits {
+            let location = location
+                .as_ref()
+                .map_or_else(|| "?".to_string(), |location| location.format());
+            outln!(
+                "{}  {}: {}",
+                location.blue().bold(),
+                hit.path().bold(),
+                hit.type_signature
+            );
+        }
+        if !hits.is_empty() {
+            outln!();
+        }
+        outln!(
+            "{:>12} {} match(es) in {} schema(s)",
+            "Finished".green().bold(),
+            hits.len(),
+            schemas.len()
+        );
+    }
+
+    // Exit like grep: 1 when nothing matched
+    if hits.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// `.lumos` files at `path`, recursively for directories (skipping build output and dependencies)
+fn lumos_sources(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries: Vec<_> = fs::read_dir(path)
+        .with_context(|| format!("Failed to read directory: {}", path.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    entries.sort();
+    for entry_path in entries {
+        if entry_path.is_dir() {
+            let skipped = entry_path.file_name().is_some_and(|name| {
+                name.to_str().is_some_and(|name| {
+                    name.starts_with('.') || matches!(name, "target" | "node_modules")
+                })
+            });
+            if !skipped {
+                lumos_sources(&entry_path, files)?;
+            }
+        } else if entry_path.extension().is_some_and(|ext| ext == "lumos") {
+            files.push(entry_path);
+        }
+    }
+    Ok(())
+}
+
+/// Validate schema syntax without generating code
+fn run_validate(
+    schema_path: &Path,
+    deterministic: bool,
+    append_only: bool,
+    previous_path: Option<&str>,
+) -> Result<()> {
+    outln!(
+        "{:>12} {}",
+        "Validating".cyan().bold(),
+        schema_path.display()
+    );
+
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+
+    if deterministic {
+        let violations = check_deterministic(&ast);
+        for violation in &violations {
+            errln!(
+                "{}: {} has type {}: {}",
+                "error".red().bold(),
+                violation.location.bold(),
+                violation.type_name,
+                violation.reason
+            );
+            errln!("  {} {}", "help:".cyan().bold(), violation.suggestion);
+        }
+        if !violations.is_empty() {
+            anyhow::bail!(
+                "Schema is not deterministic: {} field(s) use non-byte-stable types",
+                violations.len()
+            );
+        }
+    }
+
+    let ir = transform_to_ir(ast.clone()).with_context(|| "Failed to transform AST to IR")?;
+
+    if append_only {
+        let previous = match previous_path {
+            Some(spec) => {
+                let content = git::read_spec(spec)
+                    .with_context(|| format!("Failed to read previous schema: {}", spec))?;
+                let ast = parse_lumos_file(&content)
+                    .with_context(|| format!("Failed to parse previous schema: {}", spec))?;
+                transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?
+            }
+            None => Vec::new(),
+        };
+
+        let violations = check_append_only(&previous, &ir);
+        for violation in &violations {
+            errln!(
+                "{}: {} {}",
+                "error".red().bold(),
+                violation.location.bold(),
+                violation.message
+            );
+        }
+        if !violations.is_empty() {
+            anyhow::bail!(
+                "Schema breaks the append-only rule: {} change(s) would leave existing accounts unreadable",
+                violations.len()
+            );
+        }
+    }
+
+    check_validation_passes(schema_path, &ast, &ir)?;
+
+    if ir.is_empty() {
+        outln!("{}: No type definitions found", "warning".yellow().bold());
+    } else {
+        outln!(
+            "{:>12} Found {} valid type definitions",
+            "Success".green().bold(),
+            ir.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// `lumos.toml` of new projects
+const PROJECT_CONFIG: &str = r#"# LUMOS Configuration File
+
+[output]
+# Languages to generate: "rust", "typescript", "python", "jsonschema", "go"
+targets = ["rust", "typescript"]
+
+# Output directory for generated files (relative to this file)
+directory = "."
+
+# Rust output file name
+rust = "generated.rs"
+
+# TypeScript output file name
+typescript = "generated.ts"
+"#;
+
+/// Initialize a new LUMOS project
+fn run_init(project_name: Option<&str>) -> Result<()> {
+    let project_dir = if let Some(name) = project_name {
+        outln!("{:>12} project: {}", "Creating".cyan().bold(), name.bold());
+        let dir = PathBuf::from(name);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create project directory: {}", name))?;
+        dir
+    } else {
+        outln!("{:>12} current directory", "Initializing".cyan().bold());
+        PathBuf::from(".")
+    };
+
+    // Create example schema
+    let schema_content = r#"// Example LUMOS schema
+// Edit this file and run: lumos generate schema.lumos
+
+#[solana]
+#[account]
+struct UserAccount {
+    owner: PublicKey,
+    balance: u64,
+    created_at: i64,
+}
+"#;
+
+    let schema_path = project_dir.join("schema.lumos");
+    fs::write(&schema_path, schema_content)
+        .with_context(|| format!("Failed to write schema file: {}", schema_path.display()))?;
+
+    outln!(
+        "{:>12} {}",
+        "Created".green().bold(),
+        schema_path.display().to_string().bold()
+    );
+
+    // Create lumos.toml config
+    let config_path = project_dir.join("lumos.toml");
+    fs::write(&config_path, PROJECT_CONFIG)
+        .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
+
+    outln!(
+        "{:>12} {}",
+        "Created".green().bold(),
+        config_path.display().to_string().bold()
+    );
+
+    // Create README
+    let readme_content = r#"# LUMOS Project
+
+## Quick Start
+
+1. Edit `schema.lumos` to define your data structures
+2. Generate code:
+   ```bash
+   lumos generate schema.lumos
+   ```
+3. Use the generated `generated.rs` and `generated.ts` in your project
+
+## Commands
+
+- `lumos generate schema.lumos` - Generate Rust + TypeScript code
+- `lumos validate schema.lumos` - Validate schema syntax
+- `lumos generate schema.lumos --watch` - Watch for changes
+- `lumos check schema.lumos` - Verify generated code is up-to-date
+
+## Documentation
+
+https://github.com/RECTOR-LABS/lumos
+"#;
+
+    let readme_path = project_dir.join("README.md");
+    fs::write(&readme_path, readme_content)
+        .with_context(|| format!("Failed to write README: {}", readme_path.display()))?;
+
+    outln!(
+        "{:>12} {}",
+        "Created".green().bold(),
+        readme_path.display().to_string().bold()
+    );
+
+    // Success message
+    outln!();
+    outln!("{:>12} project initialized", "Finished".green().bold());
+    outln!();
+    outln!("Next steps:");
+    if let Some(name) = project_name {
+        outln!("  cd {}", name);
+    }
+    outln!("  lumos generate schema.lumos");
+
+    Ok(())
+}
+
+/// Write a schema from the answers of the wizard, then generate code for it
+fn run_new(schema_path: &Path, interactive: bool) -> Result<()> {
+    use std::io::IsTerminal;
+
+    if !interactive {
+        anyhow::bail!(
+            "Run `lumos new --interactive` to be guided through a schema, or `lumos init` for a starter project"
+        );
+    }
+    if !io::stdin().is_terminal() {
+        anyhow::bail!("`lumos new --interactive` needs a terminal to ask questions in");
+    }
+
+    let draft = wizard::ask()?;
+    let source = draft.render()?;
+    if schema_path.exists()
+        && !wizard::confirm(
+            &format!("{} exists. Overwrite it?", schema_path.display()),
+            false,
+        )?
+    {
+        anyhow::bail!("Left {} as it was", schema_path.display());
+    }
+    fs::write(schema_path, &source)
+        .with_context(|| format!("Failed to write schema: {}", schema_path.display()))?;
+    outln!(
+        "{:>12} {}",
+        "Created".green().bold(),
+        schema_path.display().to_string().bold()
+    );
+
+    if !wizard::confirm("Generate Rust and TypeScript now?", true)? {
+        outln!("Next: lumos generate {}", schema_path.display());
+        return Ok(());
+    }
+    let address = match draft.has_accounts() {
+        true => match wizard::ask_address()? {
+            Some(address) => Some(address),
+            None => {
+                outln!(
+                    "Next: lumos generate {} --address <PROGRAM_ID>",
+                    schema_path.display()
+                );
+                return Ok(());
+            }
+        },
+        false => None,
+    };
+    run_generate(
+        schema_path,
+        None,
+        false,
+        false,
+        false,
+        address.as_deref(),
+        false,
+        None,
+        &[],
+    )
+}
+
+/// List the shipped example schemas
+fn run_examples_list() -> Result<()> {
+    let width = examples::EXAMPLES
+        .iter()
+        .map(|example| example.name.len())
+        .max()
+        .unwrap_or(0);
+    for example in examples::EXAMPLES {
+        outln!(
+            "  {:<width$}  {}",
+            example.name.bold(),
+            example.description,
+            width = width
+        );
+    }
+    outln!();
+    outln!("Create a project from one with: lumos examples init <name>");
+    Ok(())
+}
+
+/// Print an example schema
+fn run_examples_show(name: &str) -> Result<()> {
+    let example = examples::find(name)?;
+    print!("{}", example.schema);
+    Ok(())
+}
+
+/// Scaffold a project from an example schema
+fn run_examples_init(name: &str, directory: Option<&Path>) -> Result<()> {
+    let example = examples::find(name)?;
+    let dir = directory.map_or_else(|| PathBuf::from(example.name), Path::to_path_buf);
+    outln!(
+        "{:>12} project from example: {}",
+        "Creating".cyan().bold(),
+        example.name.bold()
+    );
+    for path in examples::scaffold(example, &dir, PROJECT_CONFIG)? {
+        outln!(
+            "{:>12} {}",
+            "Created".green().bold(),
+            path.display().to_string().bold()
+        );
+    }
+
+    outln!();
+    outln!("{:>12} project initialized", "Finished".green().bold());
+    outln!();
+    outln!("Next steps:");
+    outln!("  cd {}", dir.display());
+    outln!("  lumos generate schema.lumos --address <PROGRAM_ID>");
+    Ok(())
+}
+
+/// Check if generated code is up-to-date
+fn run_check(
+    schema_path: &Path,
+    output_dir: Option<&Path>,
+    ts_runtime: Option<&str>,
+) -> Result<()> {
+    // Look where `lumos generate` writes
+    let options = load_generate_profile(schema_path, None, &[])?;
+    let output_dir = output_dir
+        .map(Path::to_path_buf)
+        .or(options.directory)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let mut ts_config = load_typescript_config(schema_path, None)?;
+    if let Some(runtime) = ts_runtime {
+        ts_config.runtime = match runtime {
+            "node" => Runtime::Node,
+            "deno" => Runtime::Deno,
+            "bun" => Runtime::Bun,
+            other => anyhow::bail!(
+                "Unsupported TypeScript runtime '{}' (expected node, deno or bun)",
+                other
+            ),
+        };
+    }
+
+    // Validate output directory
+    validate_output_path(&output_dir)?;
+
+    outln!("{:>12} generated code status", "Checking".cyan().bold());
+
+    // Check if output files exist
+    let rust_output = output_dir.join(&options.rust_file);
+    let ts_output = output_dir.join(&options.typescript_file);
+
+    let rust_exists = rust_output.exists();
+    let ts_exists = ts_output.exists();
+
+    if !rust_exists || !ts_exists {
+        errln!("{}: Generated files not found", "error".red().bold());
+        if !rust_exists {
+            errln!("  Missing: {}", rust_output.display());
+        }
+        if !ts_exists {
+            errln!("  Missing: {}", ts_output.display());
+        }
+        errln!();
+        errln!("Run: lumos generate {}", schema_path.display());
+        std::process::exit(1);
+    }
+
+    // Read and parse schema
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+
+    ts_config.schema = ast.metadata.clone();
+    let rust_config = RustConfig {
+        schema: ast.metadata.clone(),
+        ..load_rust_config(schema_path, None)?
+    };
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    // Generate fresh code
+    let fresh_rust = render_module(|out| {
+        rust::generate_configured_to(&ir, None, &rust_config, &mut NoCache, out)
+    })?;
+    let fresh_ts = render_module(|out| {
+        typescript::generate_configured_to(&ir, &ts_config, &mut NoCache, out)
+    })?;
+
+    // Read existing generated code
+    let existing_rust = fs::read_to_string(&rust_output)
+        .with_context(|| format!("Failed to read {}", rust_output.display()))?;
+
+    let existing_ts = fs::read_to_string(&ts_output)
+        .with_context(|| format!("Failed to read {}", ts_output.display()))?;
+
+    // Compare
+    let rust_match = fresh_rust == existing_rust;
+    let ts_match = fresh_ts == existing_ts;
+
+    if rust_match && ts_match {
+        outln!(
+            "{:>12} generated code is up-to-date",
+            "Success".green().bold()
+        );
+        if ts_config.runtime == Runtime::Deno {
+            deno_check(&ts_output)?;
+        }
+        Ok(())
+    } else {
+        errln!(
+            "{}: Generated code is out-of-date",
+            "warning".yellow().bold()
+        );
+        if !rust_match {
+            errln!("  {}", rust_output.display());
+        }
+        if !ts_match {
+            errln!("  {}", ts_output.display());
+        }
+
+        // The footers name the types the files were generated from
+        let summary = SchemaSummary::new(&ir);
+        let stale = [(rust_match, &existing_rust), (ts_match, &existing_ts)];
+        let mut changes = Vec::new();
+        for (_, code) in stale.iter().filter(|(matches, _)| !matches) {
+            for change in SchemaSummary::parse(code)
+                .map_or_else(Vec::new, |old| changed_types(&old, &summary))
+            {
+                if !changes.contains(&change) {
+                    changes.push(change);
+                }
+            }
+        }
+        if !changes.is_empty() {
+            errln!();
+            errln!("Changed types:");
+            for change in &changes {
+                match change {
+                    TypeChange::Added(name) => errln!("  {} {}", "+".green(), name),
+                    TypeChange::Removed(name) => errln!("  {} {}", "-".red(), name),
+                    TypeChange::Changed(name) => errln!("  {} {}", "~".yellow(), name),
+                }
+            }
+        } else if stale
+            .iter()
+            .any(|(matches, code)| !matches && SchemaSummary::parse(code).is_some())
+        {
+            errln!();
+            errln!("No types changed; generator settings or the LUMOS version differ, or the files were edited");
+        }
+
+        errln!();
+        errln!("Run: lumos generate {}", schema_path.display());
+        std::process::exit(1);
+    }
+}
+
+/// Format schemas in place, or with `check` only report the ones that would change
+fn run_fmt(schemas: &[PathBuf], check: bool) -> Result<()> {
+    let mut unformatted = 0;
+    for path in schemas {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read schema: {}", path.display()))?;
+        let formatted = format_source(&source)
+            .map_err(|error| error.in_file(path))
+            .with_context(|| format!("Failed to parse schema: {}", path.display()))?;
+        if formatted == source {
+            continue;
+        }
+
+        unformatted += 1;
+        if check {
+            outln!(
+                "{:>12} {} (differs from line {})",
+                "Unformatted".red().bold(),
+                path.display(),
+                reproducible::first_difference(&source, &formatted)
+            );
+        } else {
+            fs::write(path, formatted)
+                .with_context(|| format!("Failed to write schema: {}", path.display()))?;
+            outln!("{:>12} {}", "Formatted".green().bold(), path.display());
+        }
+    }
+
+    if check && unformatted > 0 {
+        anyhow::bail!(
+            "{} of {} schemas are not formatted; run `lumos fmt` to format them",
+            unformatted,
+            schemas.len()
+        );
+    }
+    if unformatted == 0 {
+        outln!(
+            "{:>12} {} schema(s) already formatted",
+            "Finished".green().bold(),
+            schemas.len()
+        );
+    }
+    Ok(())
+}
+
+/// Regenerate twice in temporary directories and compare with the committed files
+fn run_verify_reproducible(
+    schema_path: &Path,
+    output_dir: Option<&Path>,
+    address: Option<&str>,
+    profile: Option<&str>,
+    targets: &[String],
+) -> Result<()> {
+    let options = load_generate_profile(schema_path, profile, targets)?;
+    let committed_dir = output_dir
+        .map(Path::to_path_buf)
+        .or(options.directory)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    outln!(
+        "{:>12} {} in temporary directories",
+        "Regenerating".cyan().bold(),
+        schema_path.display()
+    );
+
+    // Two uncached runs: anything that differs between them is nondeterministic
+    let runs = [tempfile::tempdir()?, tempfile::tempdir()?];
+    let was_quiet = progress::quiet();
+    progress::init(true);
+    let generated = runs.iter().try_for_each(|run| {
+        run_generate(
+            schema_path,
+            Some(run.path()),
+            false,
+            false,
+            false,
+            address,
+            true,
+            profile,
+            targets,
+        )
+    });
+    progress::init(was_quiet);
+    generated?;
+
+    let mut files = Vec::new();
+    collect_files(runs[0].path(), runs[0].path(), &mut files)?;
+    files.sort();
+
+    let mut failures = 0;
+    for relative in &files {
+        let fresh = fs::read_to_string(runs[0].path().join(relative))?;
+        let committed_path = committed_dir.join(relative);
+
+        let rerun = fs::read_to_string(runs[1].path().join(relative)).unwrap_or_default();
+        if rerun != fresh {
+            failures += 1;
+            errln!(
+                "{:>12} {}: two runs differ from line {}; the generator is nondeterministic",
+                "Unstable".red().bold(),
+                committed_path.display(),
+                reproducible::first_difference(&fresh, &rerun)
+            );
+            continue;
+        }
+
+        let Ok(committed) = fs::read_to_string(paths::long_path(&committed_path)) else {
+            failures += 1;
+            errln!(
+                "{:>12} {}",
+                "Missing".red().bold(),
+                committed_path.display()
+            );
+            continue;
+        };
+
+        let finding = reproducible::compare(&fresh, &committed);
+        if finding.is_identical() {
+            outln!(
+                "{:>12} {}",
+                "Reproduced".green().bold(),
+                committed_path.display()
+            );
+        } else {
+            failures += 1;
+            errln!(
+                "{:>12} {}: {}",
+                "Differs".red().bold(),
+                committed_path.display(),
+                finding.describe()
+            );
+        }
+    }
+
+    if failures > 0 {
+        anyhow::bail!(
+            "{} of {} generated files are not reproducible from {}",
+            failures,
+            files.len(),
+            schema_path.display()
+        );
+    }
+    outln!(
+        "{:>12} {} files reproduced byte-for-byte",
+        "Success".green().bold(),
+        files.len()
+    );
+    Ok(())
+}
+
+/// Paths of the files under `dir`, relative to `root`
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else {
+            files.push(path.strip_prefix(root)?.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+/// Type-check a generated module with `deno check`, if Deno is installed
+fn deno_check(path: &Path) -> Result<()> {
+    let status = match std::process::Command::new("deno")
+        .arg("check")
+        .arg(path)
+        .status()
+    {
+        Ok(status) => status,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            errln!(
+                "{}: deno not found, skipped `deno check {}`",
+                "warning".yellow().bold(),
+                path.display()
+            );
+            return Ok(());
+        }
+        Err(e) => return Err(e).with_context(|| "Failed to run deno check"),
+    };
+
+    if !status.success() {
+        anyhow::bail!("deno check failed for {}", path.display());
+    }
+    outln!(
+        "{:>12} deno check {}",
+        "Checked".green().bold(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Print a failed regeneration, with the source snippet of schema errors
+fn report_watch_error(error: &anyhow::Error) {
+    errln!("{}: {}", "error".red().bold(), error);
+    if let Some(snippet) = diagnostic::for_error(error) {
+        errln!("{}", snippet);
+    }
+}
+
+/// How long watch mode waits for more changes before regenerating
+///
+/// Configurable via LUMOS_WATCH_DEBOUNCE in milliseconds (default: 100ms)
+fn watch_debounce() -> std::time::Duration {
+    let debounce_ms = std::env::var("LUMOS_WATCH_DEBOUNCE")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|&ms| ms <= 5000) // Max 5 seconds
+        .unwrap_or(100);
+    std::time::Duration::from_millis(debounce_ms)
+}
+
+/// Watch mode: regenerate when the schema, its imports or lumos.toml change
+fn run_watch_mode(
+    schema_path: &Path,
+    output_dir: Option<&Path>,
+    address: Option<&str>,
+    no_cache: bool,
+    profile: Option<&str>,
+    targets: &[String],
+) -> Result<()> {
+    let schema_path = schema_path.to_path_buf();
+    let output_dir_buf = output_dir.map(|p| p.to_path_buf());
+
+    let mut watcher = watch::SchemaWatcher::new(&schema_path)?;
+    let watched = |watcher: &watch::SchemaWatcher| {
+        watcher
+            .files()
+            .iter()
+            .map(|file| file.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    outln!(
+        "{:>12} {} for changes...",
+        "Watching".cyan().bold(),
+        watched(&watcher)
+    );
+    outln!("Press Ctrl+C to stop");
+    outln!();
+
+    // Initial generation (no safety flags in watch mode)
+    if let Err(e) = run_generate(
+        &schema_path,
+        output_dir,
+        false,
+        false,
+        false,
+        address,
+        no_cache,
+        profile,
+        targets,
+    ) {
+        report_watch_error(&e);
+    }
+
+    let debounce = watch_debounce();
+
+    // Watch for changes
+    loop {
+        let changed = watcher.changes(debounce, debounce)?;
+        if changed.is_empty() {
+            continue;
+        }
+
+        outln!();
+        for file in &changed {
+            outln!(
+                "{:>12} change in {}",
+                "Detected".yellow().bold(),
+                file.display()
+            );
+        }
+
+        if let Err(e) = run_generate(
+            &schema_path,
+            output_dir_buf.as_deref(),
+            false,
+            false,
+            false,
+            address,
+            no_cache,
+            profile,
+            targets,
+        ) {
+            report_watch_error(&e);
+        }
+
+        // Imports may have been added or removed
+        let before = watched(&watcher);
+        watcher.refresh()?;
+        let after = watched(&watcher);
+
+        outln!();
+        if after == before {
+            outln!("{:>12} for changes...", "Watching".cyan().bold());
         } else {
-            let decl = format!("declare_id!(\"{}\");\n\n", addr);
-            rust_code = format!("{}{}", decl, rust_code);
+            outln!("{:>12} {} for changes...", "Watching".cyan().bold(), after);
+        }
+    }
+}
+
+/// Cross-check Anchor `Accounts` contexts against the schema's account types
+fn run_check_accounts(schema_path: &Path, program_path: &Path, format: &str) -> Result<()> {
+    use lumos_core::security_analyzer::Severity;
+
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let files = rust_sources(program_path)?;
+    if files.is_empty() {
+        anyhow::bail!("No Rust sources found in {}", program_path.display());
+    }
+    let sources = files
+        .iter()
+        .map(|path| {
+            fs::read_to_string(path)
+                .with_context(|| format!("Failed to read program source: {}", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let sources: Vec<&str> = sources.iter().map(String::as_str).collect();
+
+    let mismatches = AccountsValidator::new(&ir)
+        .validate(&sources)
+        .with_context(|| format!("Failed to parse program: {}", program_path.display()))?;
+
+    if format == "json" {
+        let json_data: Vec<_> = mismatches
+            .iter()
+            .map(|mismatch| {
+                serde_json::json!({
+                    "severity": mismatch.severity.as_str(),
+                    "criticality": Criticality::from(&mismatch.severity),
+                    "kind": mismatch.kind.id(),
+                    "context": mismatch.context,
+                    "field": mismatch.field,
+                    "account_type": mismatch.account_type,
+                    "message": mismatch.message,
+                    "suggestion": mismatch.suggestion,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json_data)?);
+    } else {
+        outln!(
+            "{:>12} {} against {} ({} files)",
+            "Checking".cyan().bold(),
+            program_path.display(),
+            schema_path.display(),
+            files.len()
+        );
+        outln!();
+
+        if mismatches.is_empty() {
+            outln!("{}", "✓ Accounts contexts match the schema".green().bold());
+        }
+
+        for mismatch in &mismatches {
+            let severity = match mismatch.severity {
+                Severity::Critical => mismatch.severity.as_str().red().bold(),
+                Severity::Warning => mismatch.severity.as_str().yellow().bold(),
+                Severity::Info => mismatch.severity.as_str().dimmed().bold(),
+            };
+            let location = match &mismatch.field {
+                Some(field) => format!("{}.{}", mismatch.context, field),
+                None => mismatch.context.clone(),
+            };
+
+            outln!(
+                "{} [{}] {}",
+                mismatch.severity.emoji(),
+                severity,
+                mismatch.kind.as_str().bold()
+            );
+            outln!("   Location: {}", location.cyan());
+            outln!("   {}", mismatch.message);
+            outln!("   💡 {}", mismatch.suggestion.dimmed());
+            outln!();
+        }
+    }
+
+    // Critical mismatches break account loading, so fail like `check`
+    if mismatches
+        .iter()
+        .any(|m| matches!(m.severity, Severity::Critical))
+    {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Diff the schema's exported IDL against a deployed program's IDL
+fn run_verify_onchain(
+    schema_path: &Path,
+    program_id: Option<&str>,
+    url: &str,
+    idl_path: Option<&Path>,
+    format: &str,
+) -> Result<()> {
+    if !matches!(format, "text" | "json") {
+        anyhow::bail!(
+            "Unsupported verify-onchain format '{}' (expected text or json)",
+            format
+        );
+    }
+
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+    let local = idl::export(&ir);
+
+    let (source, deployed) = match (idl_path, program_id) {
+        (Some(path), _) => {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read IDL file: {}", path.display()))?;
+            (path.display().to_string(), content)
+        }
+        (None, Some(program_id)) => (program_id.to_string(), fetch_idl(program_id, url)?),
+        (None, None) => anyhow::bail!("Pass --program-id or --idl"),
+    };
+    let deployed: serde_json::Value = serde_json::from_str(&deployed)
+        .with_context(|| format!("Failed to parse IDL of {}", source))?;
+
+    let drift = idl::diff(&local, &deployed)
+        .with_context(|| format!("Failed to compare against the IDL of {}", source))?;
+
+    if format == "json" {
+        let json_data: Vec<_> = drift
+            .iter()
+            .map(|d| {
+                serde_json::json!({
+                    "kind": d.kind.id(),
+                    "location": d.location,
+                    "message": d.message,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json_data)?);
+    } else {
+        outln!(
+            "{:>12} {} against {}",
+            "Verifying".cyan().bold(),
+            schema_path.display(),
+            source
+        );
+        if local["accounts"].as_array().map_or(true, Vec::is_empty) {
+            errln!(
+                "{}: the schema declares no #[account] types; nothing to compare",
+                "warning".yellow().bold()
+            );
+        }
+        outln!();
+
+        if drift.is_empty() {
+            outln!("{}", "✓ Deployed program matches the schema".green().bold());
+        }
+        for d in &drift {
+            outln!(
+                "{} {} {}",
+                d.kind.as_str().red().bold(),
+                d.location.cyan(),
+                d.message
+            );
+        }
+    }
+
+    // Drift means the deployed program decodes accounts differently
+    if !drift.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Decode instruction data and print every layout it matches
+fn run_decode_ix(
+    schema_path: &Path,
+    data: &str,
+    type_name: Option<&str>,
+    format: &str,
+) -> Result<()> {
+    use lumos_core::encoding::decode_base58;
+    use lumos_core::instruction_decoder::{
+        decode_as, decode_instruction, InstructionCandidate, InstructionEncoding,
+    };
+
+    if !matches!(format, "text" | "json") {
+        anyhow::bail!(
+            "Unsupported decode-ix format '{}' (expected text or json)",
+            format
+        );
+    }
+    let bytes = decode_base58(data).with_context(|| "Instruction data is not base58")?;
+
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let candidates = match type_name {
+        Some(name) => vec![InstructionCandidate {
+            name: name.to_string(),
+            encoding: InstructionEncoding::Borsh,
+            result: decode_as(&ir, name, &bytes),
+        }],
+        None => decode_instruction(&ir, &bytes),
+    };
+    let decoded = candidates.iter().filter(|c| c.result.is_ok()).count();
+
+    if format == "json" {
+        let json_data: Vec<_> = candidates
+            .iter()
+            .map(|c| {
+                serde_json::json!({
+                    "type": c.name,
+                    "encoding": c.encoding.id(),
+                    "value": c.result.as_ref().ok().map(|v| v.to_json()),
+                    "error": c.result.as_ref().err().map(ToString::to_string),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json_data)?);
+    } else {
+        for candidate in &candidates {
+            match &candidate.result {
+                Ok(value) => {
+                    outln!(
+                        "{:>12} {} ({})",
+                        "Decoded".green().bold(),
+                        candidate.name.bold(),
+                        candidate.encoding.as_str()
+                    );
+                    outln!("{}", value);
+                }
+                // Failed candidates only explain why nothing decoded
+                Err(error) if decoded == 0 => errln!(
+                    "{}: not {} ({}): {}",
+                    "error".red().bold(),
+                    candidate.name.bold(),
+                    candidate.encoding.as_str(),
+                    error
+                ),
+                Err(_) => {}
+            }
+        }
+        if decoded > 1 {
+            errln!(
+                "{}: {} layouts match; pass --type to pick one",
+                "warning".yellow().bold(),
+                decoded
+            );
+        }
+    }
+
+    if decoded == 0 {
+        anyhow::bail!(
+            "No instruction or enum in {} decodes these {} bytes{}",
+            schema_path.display(),
+            bytes.len(),
+            if candidates.is_empty() {
+                "; pass --type to decode them as a specific type"
+            } else {
+                ""
+            }
+        );
+    }
+
+    Ok(())
+}
+
+/// Decode the Anchor events in a log file and print them in order
+fn run_decode_logs(schema_path: &Path, file: &Path, format: &str) -> Result<()> {
+    use lumos_core::event_decoder::{decode_logs, EventData};
+
+    if !matches!(format, "text" | "json") {
+        anyhow::bail!(
+            "Unsupported decode-logs format '{}' (expected text or json)",
+            format
+        );
+    }
+
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    if !ir.iter().any(|t| t.is_event()) {
+        anyhow::bail!(
+            "{} declares no #[event] types to decode",
+            schema_path.display()
+        );
+    }
+
+    let logs = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read log file: {}", file.display()))?;
+    let events = decode_logs(&ir, &logs);
+    let failed = events
+        .iter()
+        .filter(|e| matches!(e.data, EventData::Failed { .. }))
+        .count();
+    let unknown = events
+        .iter()
+        .filter(|e| matches!(e.data, EventData::Unknown { .. }))
+        .count();
+
+    if format == "json" {
+        let json_data: Vec<_> = events
+            .iter()
+            .map(|e| {
+                let (event, value, error) = match &e.data {
+                    EventData::Decoded { name, value } => {
+                        (Some(name.as_str()), Some(value.to_json()), None)
+                    }
+                    EventData::Failed { name, error } => {
+                        (Some(name.as_str()), None, Some(error.to_string()))
+                    }
+                    EventData::Unknown { .. } => (None, None, None),
+                };
+                serde_json::json!({
+                    "line": e.line,
+                    "signature": e.signature,
+                    "program": e.program,
+                    "instruction": e.instruction,
+                    "event": event,
+                    "value": value,
+                    "error": error,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json_data)?);
+    } else {
+        let mut signature = None;
+        for event in &events {
+            if event.signature.is_some() && event.signature != signature {
+                signature = event.signature.clone();
+                outln!(
+                    "{:>12} {}",
+                    "Transaction".cyan().bold(),
+                    signature.as_deref().unwrap_or_default()
+                );
+            }
+
+            let context = match (&event.program, &event.instruction) {
+                (Some(program), Some(instruction)) => {
+                    format!(" in {} ({})", instruction, program)
+                }
+                (Some(program), None) => format!(" in {}", program),
+                (None, Some(instruction)) => format!(" in {}", instruction),
+                (None, None) => String::new(),
+            };
+            let location = format!("line {}{}", event.line, context).dimmed();
+
+            match &event.data {
+                EventData::Decoded { name, value } => {
+                    outln!(
+                        "{:>12} {} {}",
+                        "Event".green().bold(),
+                        name.bold(),
+                        location
+                    );
+                    outln!("{}", value);
+                }
+                EventData::Failed { name, error } => errln!(
+                    "{}: {} does not decode ({}): {}",
+                    "error".red().bold(),
+                    name.bold(),
+                    location,
+                    error
+                ),
+                EventData::Unknown { .. } => {}
+            }
+        }
+
+        if unknown > 0 {
+            errln!(
+                "{}: {} `Program data:` line(s) match no #[event] type in the schema",
+                "warning".yellow().bold(),
+                unknown
+            );
+        }
+        if events.is_empty() {
+            errln!(
+                "{}: no `Program data:` lines in {}",
+                "warning".yellow().bold(),
+                file.display()
+            );
+        }
+    }
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Encode a JSON value as Borsh and print it in the chosen encoding
+fn run_encode(schema_path: &Path, type_name: &str, json_path: &Path, encoding: &str) -> Result<()> {
+    use lumos_core::borsh_encoder::BorshEncoder;
+    use lumos_core::encoding::Encoding;
+    use lumos_core::ir::TypeInfo;
+
+    let encoding = Encoding::parse(encoding).with_context(|| {
+        format!(
+            "Unsupported encoding '{}' (expected hex, base64 or base58)",
+            encoding
+        )
+    })?;
+
+    let ir = load_type_ir(schema_path, type_name)?;
+    let input = fs::read_to_string(json_path)
+        .with_context(|| format!("Failed to read JSON file: {}", json_path.display()))?;
+    let json: serde_json::Value = serde_json::from_str(&input)
+        .with_context(|| format!("Failed to parse JSON file: {}", json_path.display()))?;
+
+    let bytes = BorshEncoder::new(&ir)
+        .encode_json(
+            &TypeInfo::UserDefined(type_name.to_string()),
+            &json,
+            type_name,
+        )
+        .with_context(|| format!("{} does not fit {}", json_path.display(), type_name))?;
+
+    println!("{}", encoding.encode(&bytes));
+
+    Ok(())
+}
+
+/// Decode Borsh bytes of a schema type and print them as pretty JSON
+fn run_decode(schema_path: &Path, type_name: &str, data: &str, encoding: &str) -> Result<()> {
+    use lumos_core::borsh_decoder::BorshDecoder;
+    use lumos_core::encoding::Encoding;
+    use lumos_core::ir::TypeInfo;
+
+    let encoding = Encoding::parse(encoding).with_context(|| {
+        format!(
+            "Unsupported encoding '{}' (expected hex, base64 or base58)",
+            encoding
+        )
+    })?;
+    let bytes = encoding
+        .decode(data)
+        .with_context(|| format!("Data is not {}", encoding))?;
+
+    let ir = load_type_ir(schema_path, type_name)?;
+    let (value, end) = BorshDecoder::new(&ir)
+        .decode(
+            &TypeInfo::UserDefined(type_name.to_string()),
+            &bytes,
+            0,
+            type_name,
+        )
+        .with_context(|| format!("Data does not decode as {}", type_name))?;
+    if end < bytes.len() {
+        anyhow::bail!(
+            "{} bytes left over after decoding {} ({} of {} bytes used)",
+            bytes.len() - end,
+            type_name,
+            end,
+            bytes.len()
+        );
+    }
+
+    println!("{}", serde_json::to_string_pretty(&value.to_json())?);
+
+    Ok(())
+}
+
+fn run_optimize(schema_path: &Path, layout: bool, apply: bool, format: &str) -> Result<()> {
+    use lumos_core::layout_optimizer::{reorder_fields, suggest, BREAKING_WARNING};
+
+    if !matches!(format, "text" | "json") {
+        anyhow::bail!(
+            "Unsupported optimize format '{}' (expected text or json)",
+            format
+        );
+    }
+    if !layout {
+        anyhow::bail!("Nothing to optimize; pass --layout to suggest field orders");
+    }
+
+    let content = fs::read_to_string(schema_path)
+        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = lumos_core::transform::transform_for_layout(ast)
+        .with_context(|| "Failed to transform AST to IR")?;
+    let suggestions = suggest(&ir);
+
+    if format == "json" {
+        let json_data: Vec<_> = suggestions
+            .iter()
+            .map(|s| {
+                let filters: Vec<_> = s
+                    .filters
+                    .iter()
+                    .map(|f| {
+                        serde_json::json!({
+                            "field": f.field,
+                            "current_offset": f.current,
+                            "suggested_offset": f.suggested,
+                        })
+                    })
+                    .collect();
+                serde_json::json!({
+                    "name": s.name,
+                    "current": s.current,
+                    "suggested": s.suggested,
+                    "padding": s.padding.map(|(current, suggested)| {
+                        serde_json::json!({ "current": current, "suggested": suggested })
+                    }),
+                    "filters": filters,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "breaking": !suggestions.is_empty(),
+                "warning": BREAKING_WARNING,
+                "applied": apply && !suggestions.is_empty(),
+                "suggestions": json_data,
+            }))?
+        );
+    } else if suggestions.is_empty() {
+        outln!(
+            "{} No field reorderings would remove padding or fix more offsets",
+            "✓".green()
+        );
+    } else {
+        for suggestion in &suggestions {
+            outln!("{:>12} {}", "Reorder".cyan().bold(), suggestion.name.bold());
+            outln!(
+                "{:>12} {}",
+                "current",
+                suggestion.current.join(", ").dimmed()
+            );
+            outln!("{:>12} {}", "suggested", suggestion.suggested.join(", "));
+            if let Some((current, suggested)) = suggestion.padding {
+                outln!(
+                    "{:>12} {} -> {} bytes (repr(C))",
+                    "padding",
+                    current,
+                    suggested
+                );
+            }
+            for filter in &suggestion.filters {
+                let offset = |offset: Option<usize>| {
+                    offset.map_or("variable".to_string(), |offset| offset.to_string())
+                };
+                outln!(
+                    "{:>12} {} at offset {} -> {}",
+                    "filter",
+                    filter.field,
+                    offset(filter.current),
+                    offset(filter.suggested)
+                );
+            }
+        }
+    }
+
+    if suggestions.is_empty() {
+        return Ok(());
+    }
+    errln!("{}: {}", "warning".yellow().bold(), BREAKING_WARNING);
+
+    if !apply {
+        return Ok(());
+    }
+
+    // Apply one struct at a time, so a struct that must be reordered by hand doesn't
+    // hold back the others
+    let mut source = content;
+    let mut reordered = 0;
+    let mut skipped = 0;
+    for suggestion in &suggestions {
+        match reorder_fields(&source, std::slice::from_ref(suggestion)) {
+            Ok(updated) => {
+                source = updated;
+                reordered += 1;
+            }
+            Err(e) => {
+                errln!("{}: {}", "warning".yellow().bold(), e);
+                skipped += 1;
+            }
+        }
+    }
+    if reordered > 0 {
+        fs::write(schema_path, &source)
+            .with_context(|| format!("Failed to write {}", schema_path.display()))?;
+        errln!(
+            "{:>12} {} struct(s) in {}",
+            "Reordered".green().bold(),
+            reordered,
+            schema_path.display()
+        );
+    }
+    if skipped > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Load a schema's IR, checking that it defines `type_name`
+fn load_type_ir(
+    schema_path: &Path,
+    type_name: &str,
+) -> Result<Vec<lumos_core::ir::TypeDefinition>> {
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    if !ir.iter().any(|t| t.name() == type_name) {
+        anyhow::bail!(
+            "Type '{}' not found in schema: {}",
+            type_name,
+            schema_path.display()
+        );
+    }
+
+    Ok(ir)
+}
+
+/// Fetch a program's on-chain IDL through the Anchor CLI
+fn fetch_idl(program_id: &str, url: &str) -> Result<String> {
+    let output = match std::process::Command::new("anchor")
+        .args(["idl", "fetch", program_id, "--provider.cluster", url])
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => anyhow::bail!(
+            "anchor not found; install the Anchor CLI, or save the IDL and pass --idl"
+        ),
+        Err(e) => return Err(e).with_context(|| "Failed to run anchor idl fetch"),
+    };
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "anchor idl fetch failed for {}: {}",
+            program_id,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).with_context(|| "anchor idl fetch printed invalid UTF-8")
+}
+
+/// `.rs` files at `path`, recursively for directories (skipping `target/`)
+fn rust_sources(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    let entries = fs::read_dir(path)
+        .with_context(|| format!("Failed to read program directory: {}", path.display()))?;
+    for entry in entries {
+        let entry_path = entry?.path();
+        if entry_path.is_dir() {
+            if entry_path.file_name().map_or(true, |name| name != "target") {
+                files.extend(rust_sources(&entry_path)?);
+            }
+        } else if entry_path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(entry_path);
         }
     }
+    files.sort();
+    Ok(files)
+}
+
+/// Benchmark each stage of the pipeline on a schema
+fn run_bench(schema_path: &Path, iterations: usize, format: &str) -> Result<()> {
+    if !matches!(format, "text" | "json") {
+        anyhow::bail!(
+            "Unsupported bench format '{}' (expected text or json)",
+            format
+        );
+    }
+    if iterations == 0 {
+        anyhow::bail!("--iterations must be at least 1");
+    }
 
-    let rust_output = output_dir.join("generated.rs");
-    let ts_output = output_dir.join("generated.ts");
+    let content = fs::read_to_string(schema_path)
+        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
+    let rust_config = load_rust_config(schema_path, None)?;
+    let ts_config = load_typescript_config(schema_path, None)?;
 
-    // Dry-run mode: preview only
-    if dry_run {
-        preview_file_changes(&rust_output, &rust_code, "Rust")?;
-        preview_file_changes(&ts_output, &ts_code, "TypeScript")?;
+    let (parse, ast) = bench::measure("parse", iterations, || (), |()| parse_lumos_file(&content))
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let (transform, ir) = bench::measure("transform", iterations, || ast.clone(), transform_to_ir)
+        .with_context(|| "Failed to transform AST to IR")?;
+    let (rust_phase, _) = bench::measure(
+        "generate rust",
+        iterations,
+        || (),
+        |()| {
+            render_module(|out| {
+                rust::generate_configured_to(&ir, None, &rust_config, &mut NoCache, out)
+            })
+        },
+    )?;
+    let (ts_phase, _) = bench::measure(
+        "generate typescript",
+        iterations,
+        || (),
+        |()| {
+            render_module(|out| {
+                typescript::generate_configured_to(&ir, &ts_config, &mut NoCache, out)
+            })
+        },
+    )?;
+    let phases = [parse, transform, rust_phase, ts_phase];
+
+    let build = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+    let platform = format!("{} {}", std::env::consts::OS, std::env::consts::ARCH);
 
-        println!("\n{}", "No files written (dry-run mode).".yellow());
-        println!("Run without --dry-run to apply changes.");
+    if format == "json" {
+        let nanos = |duration: std::time::Duration| duration.as_nanos() as u64;
+        let json = serde_json::json!({
+            "schema": schema_path.display().to_string(),
+            "types": ir.len(),
+            "iterations": iterations,
+            "lumos_version": env!("CARGO_PKG_VERSION"),
+            "platform": platform,
+            "build": build,
+            "phases": phases.iter().map(|phase| serde_json::json!({
+                "name": phase.name,
+                "cold_ns": nanos(phase.cold),
+                "warm_median_ns": nanos(phase.warm_median),
+                "warm_min_ns": nanos(phase.warm_min),
+                "allocations": phase.allocations,
+                "allocated_bytes": phase.allocated_bytes,
+            })).collect::<Vec<_>>(),
+        });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
         return Ok(());
     }
 
-    // Backup mode: create backups
-    if backup {
-        println!("{:>12} files...", "Backing up".cyan().bold());
-        create_backup_if_exists(&rust_output)?;
-        create_backup_if_exists(&ts_output)?;
+    outln!(
+        "{:>12} {} ({} types, {} warm runs per phase)",
+        "Benchmarked".green().bold(),
+        schema_path.display(),
+        ir.len(),
+        iterations
+    );
+    outln!();
+    outln!(
+        "  {:<20} {:>10} {:>12} {:>10} {:>10} {:>11}",
+        "Phase".bold(),
+        "Cold".bold(),
+        "Warm median".bold(),
+        "Warm min".bold(),
+        "Allocs".bold(),
+        "Allocated".bold()
+    );
+    for phase in &phases {
+        outln!(
+            "  {:<20} {:>10} {:>12} {:>10} {:>10} {:>11}",
+            phase.name,
+            bench::format_time(phase.cold),
+            bench::format_time(phase.warm_median),
+            bench::format_time(phase.warm_min),
+            phase.allocations,
+            bench::format_bytes(phase.allocated_bytes)
+        );
     }
+    outln!();
+    outln!(
+        "{}",
+        format!(
+            "lumos {}, {}, {} build",
+            env!("CARGO_PKG_VERSION"),
+            platform,
+            build
+        )
+        .dimmed()
+    );
 
-    // Write Rust file
-    let rust_written = write_with_diff_check(&rust_output, &rust_code, show_diff, "Rust")?;
+    Ok(())
+}
 
-    if rust_written {
-        println!(
-            "{:>12} {}",
-            "Wrote".green().bold(),
-            rust_output.display().to_string().bold()
-        );
-    } else if show_diff {
-        println!(
-            "{:>12} {}",
-            "Skipped".yellow().bold(),
-            rust_output.display().to_string().dimmed()
+/// List the changes from one schema to another, failing on breaking ones
+fn run_diff(old_spec: &str, new_path: &Path, format: &str) -> Result<()> {
+    let content = git::read_spec(old_spec)
+        .with_context(|| format!("Failed to read old schema: {}", old_spec))?;
+    let ast = parse_lumos_file(&content)
+        .with_context(|| format!("Failed to parse old schema: {}", old_spec))?;
+    let old = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let ast = resolver::load(new_path)
+        .with_context(|| format!("Failed to parse schema: {}", new_path.display()))?;
+    let new = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let changes = schema_diff::diff(&old, &new);
+    let breaking = changes.iter().filter(|change| change.breaking).count();
+
+    if format == "json" {
+        let json = serde_json::json!({
+            "old": old_spec,
+            "new": new_path.display().to_string(),
+            "breaking": breaking > 0,
+            "changes": changes,
+        });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else {
+        outln!(
+            "{:>12} {} with {}",
+            "Comparing".cyan().bold(),
+            old_spec.bold(),
+            new_path.display().to_string().bold()
         );
+        if !changes.is_empty() {
+            outln!();
+        }
+        for change in &changes {
+            let line = change.to_string();
+            if change.breaking {
+                outln!("  {}", line.red());
+            } else {
+                outln!("  {}", line);
+            }
+        }
+        outln!();
+        match (changes.len(), breaking) {
+            (0, _) => outln!("{:>12} no schema changes", "Finished".green().bold()),
+            (total, 0) => outln!(
+                "{:>12} {} change(s), none breaking",
+                "Finished".green().bold(),
+                total
+            ),
+            (total, breaking) => outln!(
+                "{:>12} {} change(s), {} breaking",
+                "Breaking".red().bold(),
+                total,
+                breaking
+            ),
+        }
     }
 
-    // Write TypeScript file
-    let ts_written = write_with_diff_check(&ts_output, &ts_code, show_diff, "TypeScript")?;
+    if breaking > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
-    if ts_written {
-        println!(
-            "{:>12} {}",
-            "Wrote".green().bold(),
-            ts_output.display().to_string().bold()
+/// Compare a schema against its baseline and fail on anything that blocks a merge
+fn run_guard(
+    schema_path: &Path,
+    baseline_spec: &str,
+    thresholds: guard::Thresholds,
+    format: &str,
+) -> Result<()> {
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    let current = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let (baseline_content, baseline_label) = guard::read_baseline(baseline_spec, schema_path)?;
+    let ast = parse_lumos_file(&baseline_content)
+        .with_context(|| format!("Failed to parse baseline schema: {}", baseline_label))?;
+    let baseline = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    let verdict = guard::run(&baseline, &current, thresholds);
+    let schema_label = schema_path.display().to_string();
+
+    if format == "json" {
+        outln!(
+            "{}",
+            serde_json::to_string_pretty(&verdict.to_json(&schema_label, &baseline_label))?
         );
-    } else if show_diff {
-        println!(
-            "{:>12} {}",
-            "Skipped".yellow().bold(),
-            ts_output.display().to_string().dimmed()
+    } else {
+        outln!(
+            "{:>12} {} against {}",
+            "Guarding".cyan().bold(),
+            schema_label.bold(),
+            baseline_label.bold()
         );
+        outln!();
+        for check in &verdict.checks {
+            let mark = if check.passed {
+                "✓".green()
+            } else {
+                "✗".red()
+            };
+            outln!("  {} {:<9} {}", mark, check.name.bold(), check.summary);
+            for detail in &check.details {
+                outln!("      {}", detail);
+            }
+        }
+        outln!();
+        if verdict.passed() {
+            outln!(
+                "{:>12} all {} checks",
+                "Passed".green().bold(),
+                verdict.checks.len()
+            );
+        } else {
+            outln!(
+                "{:>12} {} of {} checks",
+                "Failed".red().bold(),
+                verdict.failures(),
+                verdict.checks.len()
+            );
+        }
     }
 
-    // Success summary
-    if rust_written || ts_written {
-        println!(
-            "\n{:>12} generated {} type definitions",
-            "Finished".green().bold(),
-            ir.len()
+    if !verdict.passed() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Check account sizes and detect overflow
+fn run_check_size(
+    schema_path: &Path,
+    format: &str,
+    baseline_path: Option<&str>,
+    max_growth: usize,
+    budget: &[(String, u64)],
+) -> Result<()> {
+    // Read and parse schema
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+
+    if ir.is_empty() {
+        errln!(
+            "{}: No type definitions found in schema",
+            "warning".yellow().bold()
         );
+        return Ok(());
     }
 
-    // Backup restoration hint
-    if backup && (rust_written || ts_written) {
-        println!("\n{}", "Backups created. Restore with:".dimmed());
-        if rust_written && rust_output.with_extension("rs.backup").exists() {
-            println!(
-                "  mv {} {}",
-                rust_output
-                    .with_extension("rs.backup")
-                    .display()
-                    .to_string()
-                    .dimmed(),
-                rust_output.display().to_string().dimmed()
-            );
+    // Calculate sizes
+    let calculator = SizeCalculator::new(&ir);
+    let sizes = calculator.calculate_all();
+
+    if !budget.is_empty() {
+        // Rent budget summary for expected account counts
+        let projections = project_rent(&sizes, budget).map_err(|unknown| {
+            anyhow::anyhow!(
+                "Unknown type(s) in --budget: {} (not defined in {})",
+                unknown.join(", "),
+                schema_path.display()
+            )
+        })?;
+
+        if format == "json" {
+            output_budget_json(&projections)?;
+        } else {
+            output_budget_text(&projections);
         }
-        if ts_written && ts_output.with_extension("ts.backup").exists() {
-            println!(
-                "  mv {} {}",
-                ts_output
-                    .with_extension("ts.backup")
-                    .display()
-                    .to_string()
-                    .dimmed(),
-                ts_output.display().to_string().dimmed()
+    } else if format == "json" {
+        // JSON output for programmatic use
+        output_json(&sizes)?;
+    } else {
+        // Human-readable text output
+        output_text(&sizes)?;
+    }
+
+    // Compare against baseline
+    let mut has_regressions = false;
+    if let Some(baseline_path) = baseline_path {
+        let baseline = load_size_baseline(baseline_path)?;
+        let regressions = find_size_regressions(&sizes, &baseline, max_growth);
+
+        if regressions.is_empty() {
+            errln!(
+                "{} No account grew more than {} bytes compared to {}",
+                "✓".green(),
+                max_growth,
+                baseline_path
+            );
+        } else {
+            errln!();
+            errln!(
+                "{} {} account(s) grew beyond the allowed {} bytes compared to {}:",
+                "error:".red().bold(),
+                regressions.len(),
+                max_growth,
+                baseline_path
             );
+            for regression in &regressions {
+                errln!(
+                    "  {} {}: {} → {} bytes (+{})",
+                    "✗".red(),
+                    regression.name.bold(),
+                    regression.baseline_bytes,
+                    regression.current_bytes,
+                    regression.growth().to_string().red()
+                );
+            }
+            has_regressions = true;
         }
     }
 
+    // Exit with error if any account exceeds limits or regressed
+    let has_errors = sizes.iter().any(|s| !s.warnings.is_empty());
+    if has_errors || has_regressions {
+        std::process::exit(1);
+    }
+
     Ok(())
 }
 
-/// Preview file changes in dry-run mode
-fn preview_file_changes(path: &Path, new_content: &str, label: &str) -> Result<()> {
-    let new_lines = new_content.lines().count();
-    let new_size = new_content.len();
+/// Parse a `--budget` value such as `UserAccount=100_000` or `UserAccount x 100000`
+fn parse_budget_spec(spec: &str) -> std::result::Result<(String, u64), String> {
+    let (name, count) = spec
+        .split_once('=')
+        .or_else(|| spec.split_once(" x "))
+        .ok_or_else(|| format!("expected TYPE=COUNT, got '{}'", spec))?;
 
-    println!(
-        "Would generate: {} ({})",
-        path.display().to_string().bold(),
-        label.cyan()
-    );
-    println!(
-        "  Size: {} lines ({:.1} KB)",
-        new_lines,
-        new_size as f64 / 1024.0
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("missing type name in '{}'", spec));
+    }
+
+    let count = count
+        .trim()
+        .replace('_', "")
+        .parse::<u64>()
+        .map_err(|_| format!("invalid account count in '{}'", spec))?;
+
+    Ok((name.to_string(), count))
+}
+
+/// Output a rent budget summary in human-readable format
+fn output_budget_text(projections: &[lumos_core::size_calculator::RentProjection]) {
+    outln!("{}", "Rent Budget:".bold());
+    outln!();
+
+    for projection in projections {
+        outln!(
+            "  {} x {}: {} bytes each, {} SOL each",
+            projection.name.bold(),
+            projection.count,
+            projection.bytes_each,
+            format!("{:.8}", projection.rent_sol_each).cyan()
+        );
+        outln!(
+            "  {} Total: {} bytes, {} SOL",
+            "└─".dimmed(),
+            projection.total_bytes(),
+            format!("{:.4}", projection.total_rent_sol()).cyan().bold()
+        );
+        outln!();
+    }
+
+    let total_bytes: u64 = projections.iter().map(|p| p.total_bytes()).sum();
+    let total_rent: f64 = projections.iter().map(|p| p.total_rent_sol()).sum();
+    let total_accounts: u64 = projections.iter().map(|p| p.count).sum();
+
+    outln!("{}", "Summary:".bold());
+    outln!("  Total accounts: {}", total_accounts);
+    outln!("  Total storage: {} bytes", total_bytes);
+    outln!(
+        "  Projected rent: {} SOL",
+        format!("{:.4}", total_rent).green().bold()
     );
+}
 
-    if path.exists() {
-        let old_content = fs::read_to_string(path)?;
-        let old_lines = old_content.lines().count();
+/// Output a rent budget summary in JSON format
+fn output_budget_json(projections: &[lumos_core::size_calculator::RentProjection]) -> Result<()> {
+    use serde_json::json;
 
-        if new_content == old_content {
-            println!("  {}", "No changes (identical to existing)".dimmed());
-        } else {
-            let added = new_lines.saturating_sub(old_lines);
-            let removed = old_lines.saturating_sub(new_lines);
+    let json_data = json!({
+        "accounts": projections.iter().map(|projection| {
+            json!({
+                "name": projection.name,
+                "count": projection.count,
+                "bytes_each": projection.bytes_each,
+                "rent_sol_each": projection.rent_sol_each,
+                "total_bytes": projection.total_bytes(),
+                "total_rent_sol": projection.total_rent_sol(),
+            })
+        }).collect::<Vec<_>>(),
+        "total_accounts": projections.iter().map(|p| p.count).sum::<u64>(),
+        "total_bytes": projections.iter().map(|p| p.total_bytes()).sum::<u64>(),
+        "total_rent_sol": projections.iter().map(|p| p.total_rent_sol()).sum::<f64>(),
+    });
 
-            if added > 0 {
-                println!("  {} {} lines", "+".green(), added);
-            }
-            if removed > 0 {
-                println!("  {} {} lines", "-".red(), removed);
-            }
-            if added == 0 && removed == 0 {
-                println!("  {} content modified", "~".yellow());
+    println!("{}", serde_json::to_string_pretty(&json_data)?);
+    Ok(())
+}
+
+/// Load a size baseline written by `check-size --format json`, or computed from a schema
+fn load_size_baseline(spec: &str) -> Result<HashMap<String, usize>> {
+    let content = git::read_spec(spec)?;
+
+    if spec.ends_with(".lumos") {
+        let ast = parse_lumos_file(&content)
+            .with_context(|| format!("Failed to parse baseline schema: {}", spec))?;
+        let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+        return Ok(SizeCalculator::new(&ir)
+            .calculate_all()
+            .into_iter()
+            .map(|size| (size.name, size.total_bytes.min_bytes()))
+            .collect());
+    }
+
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse baseline file: {}", spec))?;
+
+    entries
+        .iter()
+        .map(|entry| {
+            let name = entry["name"].as_str();
+            let bytes = entry["total_bytes"].as_u64();
+            match (name, bytes) {
+                (Some(name), Some(bytes)) => Ok((name.to_string(), bytes as usize)),
+                _ => anyhow::bail!(
+                    "Invalid baseline entry in {}: expected 'name' and 'total_bytes'",
+                    spec
+                ),
             }
+        })
+        .collect()
+}
+
+/// Output sizes in human-readable format
+fn output_text(sizes: &[lumos_core::size_calculator::AccountSize]) -> Result<()> {
+    use lumos_core::size_calculator::SizeInfo;
+
+    outln!("{}", "Account Size Analysis:".bold());
+    outln!();
+
+    for account in sizes {
+        // Account header
+        let status = if account.warnings.is_empty() {
+            "✓".green()
+        } else {
+            "⚠".yellow()
+        };
+
+        let size_str = match &account.total_bytes {
+            SizeInfo::Fixed(bytes) => format!("{} bytes", bytes),
+            SizeInfo::Variable { min, .. } => format!("{}+ bytes (variable)", min),
+        };
+
+        outln!("{} {}: {}", status, account.name.bold(), size_str.cyan());
+
+        // Field breakdown
+        for field in &account.field_breakdown {
+            let field_size = match &field.size {
+                SizeInfo::Fixed(bytes) => format!("{} bytes", bytes),
+                SizeInfo::Variable { min, .. } => format!("{}+ bytes", min),
+            };
+
+            outln!(
+                "  {} {} ({}) - {}",
+                "├─".dimmed(),
+                field.name,
+                field_size.dimmed(),
+                field.description.dimmed()
+            );
+        }
+
+        // Total and rent
+        outln!("  {} Total: {}", "└─".dimmed(), size_str.bold());
+        outln!(
+            "     Rent: {} SOL",
+            format!("{:.8}", account.rent_sol).cyan()
+        );
+
+        // Warnings
+        for warning in &account.warnings {
+            outln!();
+            outln!("  {} {}", "⚠".yellow(), warning.yellow());
         }
+
+        outln!();
+    }
+
+    // Summary
+    let total_accounts = sizes.len();
+    let accounts_with_warnings = sizes.iter().filter(|s| !s.warnings.is_empty()).count();
+
+    outln!("{}", "Summary:".bold());
+    outln!("  Total accounts: {}", total_accounts);
+
+    if accounts_with_warnings > 0 {
+        outln!(
+            "  {} with warnings/errors",
+            accounts_with_warnings.to_string().yellow()
+        );
     } else {
-        println!("  {}", "New file (doesn't exist yet)".green());
+        outln!("  {}", "All accounts within limits ✓".green());
     }
 
-    println!();
     Ok(())
 }
 
-/// Create backup of file if it exists
-fn create_backup_if_exists(path: &Path) -> Result<()> {
-    if !path.exists() {
-        return Ok(());
-    }
-
-    let backup_path = path.with_extension(format!(
-        "{}.backup",
-        path.extension().and_then(|s| s.to_str()).unwrap_or("")
-    ));
+/// Output sizes in JSON format
+fn output_json(sizes: &[lumos_core::size_calculator::AccountSize]) -> Result<()> {
+    let json_data = report::sizes_to_json(sizes);
 
-    fs::copy(path, &backup_path)
-        .with_context(|| format!("Failed to create backup: {}", backup_path.display()))?;
+    println!("{}", serde_json::to_string_pretty(&json_data)?);
+    Ok(())
+}
 
-    println!(
-        "  {} → {}",
-        path.display().to_string().dimmed(),
-        backup_path.display().to_string().cyan()
-    );
+/// Run security analysis on schema
+fn run_security_analyze(schema_path: &Path, format: &str, strict: bool, group: bool) -> Result<()> {
+    // Read and parse schema
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
 
-    Ok(())
-}
+    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
 
-/// Write file with optional diff check and confirmation
-fn write_with_diff_check(path: &Path, content: &str, show_diff: bool, label: &str) -> Result<bool> {
-    // If show_diff and file exists, show diff and ask for confirmation
-    if show_diff && path.exists() {
-        let old_content = fs::read_to_string(path)?;
+    if ir.is_empty() {
+        errln!(
+            "{}: No type definitions found in schema",
+            "warning".yellow().bold()
+        );
+        return Ok(());
+    }
 
-        // If identical, skip
-        if content == old_content {
-            println!(
-                "{}: {} {}",
-                "Unchanged".dimmed(),
-                path.display().to_string().dimmed(),
-                format!("({})", label).dimmed()
-            );
-            return Ok(false);
-        }
+    // Run security analysis
+    let mut analyzer = SecurityAnalyzer::new(&ir);
+    if strict {
+        analyzer = analyzer.with_strict_mode();
+    }
 
-        // Show diff
-        show_diff_and_ask_confirmation(path, &old_content, content, label)?;
+    let findings = analyzer.analyze();
 
-        // User declined
-        return Ok(false);
+    if format == "json" {
+        output_security_json(&findings, group)?;
+    } else {
+        output_security_text(&findings, schema_path, group)?;
     }
 
-    // Write file
-    fs::write(path, content)
-        .with_context(|| format!("Failed to write {}: {}", label, path.display()))?;
+    // Exit with error if any critical findings
+    let has_critical = findings.iter().any(|f| {
+        matches!(
+            f.severity,
+            lumos_core::security_analyzer::Severity::Critical
+        )
+    });
 
-    Ok(true)
+    if has_critical {
+        std::process::exit(1);
+    }
+
+    Ok(())
 }
 
-/// Show diff and ask for user confirmation
-fn show_diff_and_ask_confirmation(
-    path: &Path,
-    old_content: &str,
-    new_content: &str,
-    label: &str,
+/// Output security findings in human-readable format
+fn output_security_text(
+    findings: &[lumos_core::security_analyzer::SecurityFinding],
+    schema_path: &Path,
+    group: bool,
 ) -> Result<()> {
-    use std::io::{self, Write};
+    use lumos_core::security_analyzer::Severity;
 
-    println!("\n{}", "─".repeat(60).dimmed());
-    println!(
-        "DIFF: {} ({})",
-        path.display().to_string().bold(),
-        label.cyan()
+    outln!("{}", tr("security-report-title").bold());
+    outln!(
+        "{}",
+        tr_args(
+            "security-schema",
+            &[(
+                "path",
+                schema_path.display().to_string().cyan().to_string().into()
+            )]
+        )
     );
-    println!("{}", "─".repeat(60).dimmed());
-    println!();
-
-    // Simple line-by-line diff
-    let old_lines: Vec<&str> = old_content.lines().collect();
-    let new_lines: Vec<&str> = new_content.lines().collect();
-
-    let mut added = 0;
-    let mut removed = 0;
-    let max_lines = old_lines.len().max(new_lines.len());
-
-    // Show first 20 lines of diff
-    let preview_limit = 20;
-    for i in 0..max_lines.min(preview_limit) {
-        let old_line = old_lines.get(i);
-        let new_line = new_lines.get(i);
+    outln!();
 
-        match (old_line, new_line) {
-            (Some(old), Some(new)) if old != new => {
-                println!("{} {}", "-".red(), old);
-                println!("{} {}", "+".green(), new);
-                added += 1;
-                removed += 1;
-            }
-            (Some(old), None) => {
-                println!("{} {}", "-".red(), old);
-                removed += 1;
-            }
-            (None, Some(new)) => {
-                println!("{} {}", "+".green(), new);
-                added += 1;
-            }
-            (Some(line), Some(_)) => {
-                println!("  {}", line.dimmed());
-            }
-            _ => {}
-        }
+    if findings.is_empty() {
+        outln!("{}", tr("security-no-issues").green().bold());
+        outln!();
+        outln!("{}", tr("security-all-passed"));
+        return Ok(());
     }
 
-    if max_lines > preview_limit {
-        println!(
-            "\n{}",
-            format!("... ({} more lines)", max_lines - preview_limit).dimmed()
+    // Group by severity
+    let critical: Vec<_> = findings
+        .iter()
+        .filter(|f| matches!(f.severity, Severity::Critical))
+        .collect();
+    let warnings: Vec<_> = findings
+        .iter()
+        .filter(|f| matches!(f.severity, Severity::Warning))
+        .collect();
+    let info: Vec<_> = findings
+        .iter()
+        .filter(|f| matches!(f.severity, Severity::Info))
+        .collect();
+
+    // Summary
+    outln!("{}", tr("security-summary").bold());
+    if !critical.is_empty() {
+        let count = critical.len().to_string().red().bold().to_string();
+        outln!(
+            "  {}",
+            tr_args("security-critical-count", &[("count", count.into())])
         );
     }
-
-    println!();
-    println!("Summary:");
-    if added > 0 {
-        println!("  Lines added: {}", added.to_string().green());
+    if !warnings.is_empty() {
+        let count = warnings.len().to_string().yellow().to_string();
+        outln!(
+            "  {}",
+            tr_args("security-warning-count", &[("count", count.into())])
+        );
     }
-    if removed > 0 {
-        println!("  Lines removed: {}", removed.to_string().red());
+    if !info.is_empty() {
+        outln!(
+            "  {}",
+            tr_args("security-info-count", &[("count", info.len().into())])
+        );
     }
-    println!();
-
-    // Ask for confirmation
-    print!("Apply changes to {}? [y/N] ", path.display());
-    io::stdout().flush()?;
+    outln!();
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
+    // Critical findings
+    if !critical.is_empty() {
+        outln!("{}", tr("security-critical-heading").red().bold());
+        outln!("{}", "═".repeat(60).red());
+        outln!();
 
-    let response = input.trim().to_lowercase();
-    if response == "y" || response == "yes" {
-        fs::write(path, new_content)
-            .with_context(|| format!("Failed to write {}", path.display()))?;
-        println!("{:>12} {}", "Applied".green().bold(), path.display());
-        Ok(())
-    } else {
-        println!("{:>12} {}", "Skipped".yellow().bold(), path.display());
-        Ok(())
+        print_findings(&critical, group);
     }
-}
 
-/// Validate schema syntax without generating code
-fn run_validate(schema_path: &Path) -> Result<()> {
-    println!(
-        "{:>12} {}",
-        "Validating".cyan().bold(),
-        schema_path.display()
-    );
+    // Warnings
+    if !warnings.is_empty() {
+        outln!("{}", tr("security-warnings-heading").yellow().bold());
+        outln!("{}", "═".repeat(60).yellow());
+        outln!();
 
-    let content = fs::read_to_string(schema_path)
-        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
+        print_findings(&warnings, group);
+    }
 
-    let ast = parse_lumos_file(&content)
-        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    // Info
+    if !info.is_empty() {
+        outln!("{}", tr("security-info-heading").dimmed().bold());
+        outln!("{}", "═".repeat(60).dimmed());
+        outln!();
 
-    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+        print_findings(&info, group);
+    }
 
-    if ir.is_empty() {
-        println!("{}: No type definitions found", "warning".yellow().bold());
-    } else {
-        println!(
-            "{:>12} Found {} valid type definitions",
-            "Success".green().bold(),
-            ir.len()
-        );
+    // Footer
+    outln!();
+    outln!("{}", tr("security-recommendations").bold());
+    if !critical.is_empty() {
+        outln!("  {} {}", "🚨".red(), tr("security-fix-critical"));
     }
+    if !warnings.is_empty() {
+        outln!("  {}", tr("security-review-warnings"));
+    }
+    outln!("  {}", tr("security-see-docs"));
 
     Ok(())
 }
 
-/// Initialize a new LUMOS project
-fn run_init(project_name: Option<&str>) -> Result<()> {
-    let project_dir = if let Some(name) = project_name {
-        println!("{:>12} project: {}", "Creating".cyan().bold(), name.bold());
-        let dir = PathBuf::from(name);
-        fs::create_dir_all(&dir)
-            .with_context(|| format!("Failed to create project directory: {}", name))?;
-        dir
-    } else {
-        println!("{:>12} current directory", "Initializing".cyan().bold());
-        PathBuf::from(".")
-    };
+/// Print a severity section, optionally grouped by rule
+fn print_findings(findings: &[&lumos_core::security_analyzer::SecurityFinding], group: bool) {
+    use lumos_core::security_analyzer::group_findings;
 
-    // Create example schema
-    let schema_content = r#"// Example LUMOS schema
-// Edit this file and run: lumos generate schema.lumos
+    if !group {
+        for (i, finding) in findings.iter().enumerate() {
+            print_finding(finding, i + 1);
+        }
+        return;
+    }
 
-#[solana]
-#[account]
-struct UserAccount {
-    owner: PublicKey,
-    balance: u64,
-    created_at: i64,
+    let owned: Vec<_> = findings.iter().map(|f| (*f).clone()).collect();
+    for finding_group in group_findings(&owned) {
+        print_finding_group(&finding_group);
+    }
 }
-"#;
-
-    let schema_path = project_dir.join("schema.lumos");
-    fs::write(&schema_path, schema_content)
-        .with_context(|| format!("Failed to write schema file: {}", schema_path.display()))?;
-
-    println!(
-        "{:>12} {}",
-        "Created".green().bold(),
-        schema_path.display().to_string().bold()
-    );
 
-    // Create lumos.toml config
-    let config_content = r#"# LUMOS Configuration File
+/// Print a group of findings reported by the same rule
+fn print_finding_group(group: &lumos_core::security_analyzer::FindingGroup) {
+    use lumos_core::security_analyzer::Severity;
 
-[output]
-# Output directory for generated files (relative to this file)
-directory = "."
+    const MAX_MEMBERS: usize = 5;
 
-# Rust output file name
-rust = "generated.rs"
+    let severity_str = match group.severity {
+        Severity::Critical => group.severity.as_str().red().bold(),
+        Severity::Warning => group.severity.as_str().yellow().bold(),
+        Severity::Info => group.severity.as_str().dimmed().bold(),
+    };
 
-# TypeScript output file name
-typescript = "generated.ts"
-"#;
+    outln!(
+        "{} [{}] {} ({} {})",
+        group.severity.emoji(),
+        severity_str,
+        i18n::get().rule(&group.vulnerability).bold(),
+        group.count(),
+        tr_args("security-occurrences", &[("count", group.count().into())])
+    );
 
-    let config_path = project_dir.join("lumos.toml");
-    fs::write(&config_path, config_content)
-        .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
+    for member in group.members.iter().take(MAX_MEMBERS) {
+        outln!("   - {}", member.location.to_string().cyan());
+    }
+    if group.count() > MAX_MEMBERS {
+        outln!(
+            "   {}",
+            tr_args(
+                "security-more-members",
+                &[("count", (group.count() - MAX_MEMBERS).into())]
+            )
+        );
+    }
 
-    println!(
-        "{:>12} {}",
-        "Created".green().bold(),
-        config_path.display().to_string().bold()
+    outln!(
+        "   💡 {}",
+        i18n::get()
+            .suggestion_for(&group.vulnerability, &group.suggestion)
+            .dimmed()
     );
 
-    // Create README
-    let readme_content = r#"# LUMOS Project
+    outln!();
+}
 
-## Quick Start
+/// Print a single finding
+fn print_finding(finding: &lumos_core::security_analyzer::SecurityFinding, _index: usize) {
+    use lumos_core::security_analyzer::Severity;
 
-1. Edit `schema.lumos` to define your data structures
-2. Generate code:
-   ```bash
-   lumos generate schema.lumos
-   ```
-3. Use the generated `generated.rs` and `generated.ts` in your project
+    let emoji = finding.severity.emoji();
+    let severity_str = match finding.severity {
+        Severity::Critical => finding.severity.as_str().red().bold(),
+        Severity::Warning => finding.severity.as_str().yellow().bold(),
+        Severity::Info => finding.severity.as_str().dimmed().bold(),
+    };
 
-## Commands
+    outln!(
+        "{} [{}] {}",
+        emoji,
+        severity_str,
+        i18n::get().rule(&finding.vulnerability).bold()
+    );
 
-- `lumos generate schema.lumos` - Generate Rust + TypeScript code
-- `lumos validate schema.lumos` - Validate schema syntax
-- `lumos generate schema.lumos --watch` - Watch for changes
-- `lumos check schema.lumos` - Verify generated code is up-to-date
+    // Location
+    let location = finding.location.to_string().cyan().to_string();
+    outln!(
+        "   {}",
+        tr_args("security-location", &[("location", location.into())])
+    );
 
-## Documentation
+    // Message
+    outln!("   {}", finding.message);
 
-https://github.com/RECTOR-LABS/lumos
-"#;
+    // Suggestion
+    outln!("   💡 {}", i18n::get().suggestion(finding).dimmed());
 
-    let readme_path = project_dir.join("README.md");
-    fs::write(&readme_path, readme_content)
-        .with_context(|| format!("Failed to write README: {}", readme_path.display()))?;
+    outln!();
+}
+
+/// Output security findings in JSON format
+fn output_security_json(
+    findings: &[lumos_core::security_analyzer::SecurityFinding],
+    group: bool,
+) -> Result<()> {
+    use lumos_core::security_analyzer::group_findings;
+    use serde_json::json;
 
-    println!(
-        "{:>12} {}",
-        "Created".green().bold(),
-        readme_path.display().to_string().bold()
-    );
+    if group {
+        let json_data: Vec<_> = group_findings(findings)
+            .iter()
+            .map(|finding_group| {
+                json!({
+                    "severity": finding_group.severity.as_str(),
+                    "criticality": Criticality::from(&finding_group.severity),
+                    "vulnerability_type": finding_group.vulnerability.as_str(),
+                    "count": finding_group.count(),
+                    "suggestion": finding_group.suggestion,
+                    "members": finding_group
+                        .members
+                        .iter()
+                        .map(|finding| {
+                            json!({
+                                "type_name": finding.location.type_name,
+                                "field_name": finding.location.field_name,
+                                "message": finding.message,
+                            })
+                        })
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
 
-    // Success message
-    println!();
-    println!("{:>12} project initialized", "Finished".green().bold());
-    println!();
-    println!("Next steps:");
-    if project_name.is_some() {
-        println!("  cd {}", project_name.unwrap());
+        println!("{}", serde_json::to_string_pretty(&json_data)?);
+        return Ok(());
     }
-    println!("  lumos generate schema.lumos");
 
+    let json_data = report::findings_to_json(findings);
+
+    println!("{}", serde_json::to_string_pretty(&json_data)?);
     Ok(())
 }
 
-/// Check if generated code is up-to-date
-fn run_check(schema_path: &Path, output_dir: Option<&Path>) -> Result<()> {
-    let output_dir = output_dir.unwrap_or_else(|| Path::new("."));
+/// Write a local bug report bundle for the schema and, optionally, a failing command
+fn run_bug_report(
+    schema_path: &Path,
+    output_path: Option<&Path>,
+    redact: bool,
+    command: &[OsString],
+) -> Result<()> {
+    outln!(
+        "{:>12} bug report for {}",
+        "Collecting".cyan().bold(),
+        schema_path.display().to_string().bold()
+    );
 
-    // Validate output directory
-    validate_output_path(output_dir)?;
+    let files = bug_report::schema_files(schema_path)?;
+    let redactor = match redact {
+        true => {
+            let sources: Vec<&str> = files.iter().map(|(_, source)| source.as_str()).collect();
+            Some(Redactor::new(&sources).context(
+                "Can't redact a schema that doesn't parse; rerun without --redact and review the files before sharing them",
+            )?)
+        }
+        false => None,
+    };
+    let scrub = |text: &str| match &redactor {
+        Some(redactor) => redactor.redact_text(text),
+        None => text.to_string(),
+    };
 
-    println!("{:>12} generated code status", "Checking".cyan().bold());
+    let mut bundle = bug_report::Bundle::default();
+    let root = schema_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut schema_entries = Vec::new();
+    for (file, source) in &files {
+        let name = bug_report::bundle_path(root, file);
+        let source = match &redactor {
+            Some(redactor) => redactor
+                .redact(source)
+                .with_context(|| format!("Failed to redact {}", file.display()))?,
+            None => source.clone(),
+        };
+        schema_entries.push(name.clone());
+        bundle.add(name, source);
+    }
 
-    // Check if output files exist
-    let rust_output = output_dir.join("generated.rs");
-    let ts_output = output_dir.join("generated.ts");
+    let config_path = lumos_toml_for(schema_path);
+    if let Some(content) = config::read(&config_path)? {
+        bundle.add("lumos.toml", scrub(&content));
+    }
+    let settings = match config::load(&config_path).and_then(|settings| settings.with_defaults()) {
+        Ok(settings) => settings
+            .entries()
+            .into_iter()
+            .map(|(key, value, source)| format!("{} = {}  # {}\n", key, value, source))
+            .collect(),
+        Err(error) => format!("Settings could not be resolved: {:#}\n", error),
+    };
+    bundle.add("settings.txt", scrub(&settings));
 
-    let rust_exists = rust_output.exists();
-    let ts_exists = ts_output.exists();
+    let mut command_summary = "No command was given; pass one after `--` to record its output, e.g. `lumos bug-report schema.lumos -- generate schema.lumos`.".to_string();
+    if !command.is_empty() {
+        let line = command
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let line = scrub(line.strip_prefix("lumos ").unwrap_or(&line));
+        outln!("{:>12} lumos {}", "Running".cyan().bold(), line);
+        let trace = bug_report::run_command(command)?;
+        let status = match trace.status {
+            Some(code) => format!("exit status {}", code),
+            None => "killed by a signal".to_string(),
+        };
+        bundle.add(
+            "command.txt",
+            format!(
+                "$ lumos {}\n{}\n\n--- stdout ---\n{}\n--- stderr ---\n{}",
+                line,
+                status,
+                scrub(&trace.stdout),
+                scrub(&trace.stderr)
+            ),
+        );
+        command_summary = format!("`lumos {}` ended with {}; see command.txt.", line, status);
+    }
 
-    if !rust_exists || !ts_exists {
-        eprintln!("{}: Generated files not found", "error".red().bold());
-        if !rust_exists {
-            eprintln!("  Missing: {}", rust_output.display());
-        }
-        if !ts_exists {
-            eprintln!("  Missing: {}", ts_output.display());
-        }
-        eprintln!();
-        eprintln!("Run: lumos generate {}", schema_path.display());
-        std::process::exit(1);
+    let mut summary = String::new();
+    summary.push_str("# LUMOS bug report\n\n");
+    summary.push_str(&format!(
+        "Generated by `lumos bug-report` on {}. Nothing in this bundle was uploaded.\n\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    ));
+    summary.push_str("## Versions\n\n");
+    summary.push_str(&format!("- LUMOS: {}\n", env!("CARGO_PKG_VERSION")));
+    summary.push_str(&format!(
+        "- OS: {} ({})\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    ));
+    for tool in ["rustc", "cargo", "node"] {
+        let version = bug_report::tool_version(tool).unwrap_or_else(|| "not found".to_string());
+        summary.push_str(&format!("- {}: {}\n", tool, version));
+    }
+    summary.push_str("\n## Command\n\n");
+    summary.push_str(&command_summary);
+    summary.push_str("\n\n## Schema\n\n");
+    for name in &schema_entries {
+        summary.push_str(&format!("- {}\n", name));
+    }
+    summary.push_str(match redact {
+        true => "\nType, field, variant and constant names were replaced and comments removed (`--redact`); string literals were kept.\n",
+        false => "\nThe schema is included as written. Use `--redact` to replace names and remove comments.\n",
+    });
+    bundle.add("bug-report.md", summary);
+
+    let output_path = output_path.unwrap_or_else(|| Path::new("lumos-bug-report.zip"));
+    fs::write(
+        output_path,
+        bundle.to_zip(chrono::Local::now().naive_local()),
+    )
+    .with_context(|| format!("Failed to write bug report to {}", output_path.display()))?;
+
+    outln!(
+        "{:>12} {}",
+        "Wrote".green().bold(),
+        output_path.display().to_string().bold()
+    );
+    for name in bundle.names() {
+        outln!("             {}", name);
     }
+    outln!();
+    outln!(
+        "Nothing was uploaded. Review the files, then attach the zip to an issue at {}",
+        lumos_core::panic_guard::ISSUES_URL
+    );
+    Ok(())
+}
 
+/// Run all analyses and write a combined report
+fn run_report(
+    schema_path: &Path,
+    output_path: Option<&Path>,
+    format: &str,
+    strict: bool,
+) -> Result<()> {
     // Read and parse schema
-    let content = fs::read_to_string(schema_path)
-        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
-
-    let ast = parse_lumos_file(&content)
+    let ast = resolver::load(schema_path)
         .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
 
     let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
 
-    // Generate fresh code
-    let fresh_rust = rust::generate_module(&ir);
-    let fresh_ts = typescript::generate_module(&ir);
-
-    // Read existing generated code
-    let existing_rust = fs::read_to_string(&rust_output)
-        .with_context(|| format!("Failed to read {}", rust_output.display()))?;
-
-    let existing_ts = fs::read_to_string(&ts_output)
-        .with_context(|| format!("Failed to read {}", ts_output.display()))?;
-
-    // Compare
-    let rust_match = fresh_rust == existing_rust;
-    let ts_match = fresh_ts == existing_ts;
-
-    if rust_match && ts_match {
-        println!(
-            "{:>12} generated code is up-to-date",
-            "Success".green().bold()
-        );
-        Ok(())
-    } else {
-        eprintln!(
-            "{}: Generated code is out-of-date",
+    if ir.is_empty() {
+        errln!(
+            "{}: No type definitions found in schema",
             "warning".yellow().bold()
         );
-        if !rust_match {
-            eprintln!("  {}", rust_output.display());
-        }
-        if !ts_match {
-            eprintln!("  {}", ts_output.display());
-        }
-        eprintln!();
-        eprintln!("Run: lumos generate {}", schema_path.display());
-        std::process::exit(1);
+        return Ok(());
     }
-}
 
-/// Watch mode: regenerate on file changes
-fn run_watch_mode(schema_path: &Path, output_dir: Option<&Path>, address: Option<&str>) -> Result<()> {
-        use notify::{RecursiveMode, Watcher};
-        use std::sync::mpsc::channel;
-        use std::time::Duration;
-        let schema_path = schema_path.to_path_buf();
-    let output_dir_buf = output_dir.map(|p| p.to_path_buf());
+    outln!("{:>12} report...", "Analyzing".cyan().bold());
 
-    println!(
-        "{:>12} {} for changes...",
-        "Watching".cyan().bold(),
-        schema_path.display()
+    let report = report::Report::build(
+        &schema_path.display().to_string(),
+        &ir,
+        strict,
+        load_lint_config(schema_path)?,
     );
-    println!("Press Ctrl+C to stop");
-    println!();
-
-        // Initial generation (no safety flags in watch mode)
-        if let Err(e) = run_generate(&schema_path, output_dir, false, false, false, address) {
-            eprintln!("{}: {}", "error".red().bold(), e);
-        }
-
-    // Set up file watcher
-    let (tx, rx) = channel();
-
-    let mut watcher = notify::recommended_watcher(move |res| {
-        if let Ok(event) = res {
-            let _ = tx.send(event);
-        }
-    })?;
-
-    watcher.watch(&schema_path, RecursiveMode::NonRecursive)?;
-
-    // Get configurable debounce duration (default: 100ms)
-    let debounce_ms = std::env::var("LUMOS_WATCH_DEBOUNCE")
-        .ok()
-        .and_then(|s| s.parse::<u64>().ok())
-        .filter(|&ms| ms <= 5000) // Max 5 seconds
-        .unwrap_or(100);
 
-    // Watch for changes
-    loop {
-        match rx.recv_timeout(Duration::from_millis(debounce_ms)) {
-            Ok(_event) => {
-                // Debounce: wait a bit for multiple rapid changes
-                std::thread::sleep(Duration::from_millis(debounce_ms));
-
-                // Drain any pending events
-                while rx.try_recv().is_ok() {}
-
-                println!();
-                println!("{:>12} change detected", "Detected".yellow().bold());
-
-                if let Err(e) = run_generate(
-                    &schema_path,
-                    output_dir_buf.as_deref(),
-                    false,
-                    false,
-                    false,
-                    address,
-                ) {
-                    eprintln!("{}: {}", "error".red().bold(), e);
-                }
+    let (output, default_output) = match format {
+        "json" => (
+            serde_json::to_string_pretty(&report.to_json())?,
+            "lumos-report.json",
+        ),
+        "html" => (report.to_html(), "lumos-report.html"),
+        other => anyhow::bail!(
+            "Unsupported report format '{}' (expected json or html)",
+            other
+        ),
+    };
 
-                println!();
-                println!("{:>12} for changes...", "Watching".cyan().bold());
-            }
-            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
-                // Normal timeout, continue watching
-            }
-            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
-                break;
-            }
-        }
-    }
+    let output_path = output_path.unwrap_or_else(|| Path::new(default_output));
+    fs::write(output_path, output)
+        .with_context(|| format!("Failed to write report to {}", output_path.display()))?;
 
-        Ok(())
+    outln!(
+        "{:>12} {}",
+        "Generated".green().bold(),
+        output_path.display().to_string().bold()
+    );
+    outln!();
+    outln!("Report includes:");
+    outln!("  ✓ {} account sizes", report.sizes.len());
+    outln!("  ✓ {} security findings", report.findings.len());
+    outln!("  ✓ {} audit checks", report.checklist.len());
+    outln!("  ✓ {} lints", report.lints.len());
+
+    if report.has_blocking_issues() {
+        outln!();
+        outln!(
+            "{} Report contains critical findings or size limit violations",
+            "🚨".red()
+        );
     }
 
-    inner_watch(schema_path, output_dir, address)
+    Ok(())
 }
 
-/// Check account sizes and detect overflow
-fn run_check_size(schema_path: &Path, format: &str) -> Result<()> {
+/// Run audit checklist generation
+fn run_audit_generate(schema_path: &Path, output_path: Option<&Path>, format: &str) -> Result<()> {
     // Read and parse schema
-    let content = fs::read_to_string(schema_path)
-        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
-
-    let ast = parse_lumos_file(&content)
+    let ast = resolver::load(schema_path)
         .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
 
     let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
 
     if ir.is_empty() {
-        eprintln!(
+        errln!(
             "{}: No type definitions found in schema",
             "warning".yellow().bold()
         );
         return Ok(());
     }
 
-    // Calculate sizes
-    let mut calculator = SizeCalculator::new(&ir);
-    let sizes = calculator.calculate_all();
+    // Generate checklist
+    let generator = AuditGenerator::new(&ir);
+    let checklist = generator.generate();
+
+    // Determine output path
+    let output = output_path.unwrap_or_else(|| Path::new("SECURITY_AUDIT.md"));
 
+    // Generate output based on format
     if format == "json" {
-        // JSON output for programmatic use
-        output_json(&sizes)?;
+        generate_audit_json(&checklist, output)?;
     } else {
-        // Human-readable text output
-        output_text(&sizes)?;
+        generate_audit_markdown(&checklist, &ir, schema_path, output)?;
     }
 
-    // Exit with error if any account exceeds limits
-    let has_errors = sizes.iter().any(|s| !s.warnings.is_empty());
-    if has_errors {
-        std::process::exit(1);
+    outln!(
+        "\n{} {}",
+        "Generated:".green().bold(),
+        output.display().to_string().bold()
+    );
+    outln!();
+    outln!("Checklist includes:");
+    outln!("  ✓ {} total checks", checklist.len());
+
+    // Count by category
+    use lumos_core::audit_generator::CheckCategory;
+    let categories = [
+        CheckCategory::AccountValidation,
+        CheckCategory::SignerChecks,
+        CheckCategory::ArithmeticSafety,
+        CheckCategory::AccessControl,
+        CheckCategory::AccountClosure,
+        CheckCategory::Confidentiality,
+    ];
+
+    for category in categories {
+        let count = checklist
+            .iter()
+            .filter(|item| item.category == category)
+            .count();
+        if count > 0 {
+            outln!("  ✓ {} {} checks", count, category.as_str().to_lowercase());
+        }
     }
 
     Ok(())
 }
 
-/// Output sizes in human-readable format
-fn output_text(sizes: &[lumos_core::size_calculator::AccountSize]) -> Result<()> {
-    use lumos_core::size_calculator::SizeInfo;
+/// Generate audit checklist in Markdown format
+fn generate_audit_markdown(
+    checklist: &[lumos_core::audit_generator::ChecklistItem],
+    type_defs: &[TypeDefinition],
+    schema_path: &Path,
+    output_path: &Path,
+) -> Result<()> {
+    use lumos_core::audit_generator::CheckCategory;
 
-    println!("{}", "Account Size Analysis:".bold());
-    println!();
+    let mut content = String::new();
 
-    for account in sizes {
-        // Account header
-        let status = if account.warnings.is_empty() {
-            "✓".green()
-        } else {
-            "⚠".yellow()
-        };
+    // Header
+    content.push_str(&format!("# {}\n\n", tr("audit-title")));
+    content.push_str(&format!(
+        "**{}** `{}`\n",
+        tr("audit-generated-from"),
+        schema_path.display()
+    ));
+    content.push_str(&format!(
+        "**{}** {}\n\n",
+        tr("audit-date"),
+        chrono::Local::now().format("%Y-%m-%d")
+    ));
+    content.push_str(&format!(
+        "**{}** {}\n\n",
+        tr("audit-total-checks"),
+        checklist.len()
+    ));
 
-        let size_str = match &account.total_bytes {
-            SizeInfo::Fixed(bytes) => format!("{} bytes", bytes),
-            SizeInfo::Variable { min, .. } => format!("{}+ bytes (variable)", min),
-        };
+    content.push_str("---\n\n");
+    content.push_str(&format!("## {}\n\n", tr("audit-disclaimer-title")));
+    content.push_str(&format!("**{}**\n\n", tr("audit-disclaimer-lead")));
+    for id in [
+        "audit-disclaimer-patterns",
+        "audit-disclaimer-limits",
+        "audit-disclaimer-review",
+        "audit-disclaimer-auditors",
+    ] {
+        content.push_str(&format!("- {}\n", tr(id)));
+    }
+    content.push('\n');
+    content.push_str("---\n\n");
+    content.push_str(&format!("## {}\n\n", tr("audit-how-to-title")));
+    content.push_str(&format!("- [ ] = {}\n", tr("audit-how-unchecked")));
+    content.push_str(&format!("- [x] = {}\n", tr("audit-how-checked")));
+    content.push_str(&format!(
+        "- {}: 🔴 CRITICAL | 🟡 HIGH | 🟢 MEDIUM | ⚪ LOW\n\n",
+        tr("audit-how-priority")
+    ));
+    content.push_str(&format!("**{}**\n\n", tr("audit-how-review")));
 
-        println!(
-            "{} {}: {}",
-            status,
-            account.name.bold(),
-            size_str.cyan()
-        );
+    content.push_str("---\n\n");
 
-        // Field breakdown
-        for field in &account.field_breakdown {
-            let field_size = match &field.size {
-                SizeInfo::Fixed(bytes) => format!("{} bytes", bytes),
-                SizeInfo::Variable { min, .. } => format!("{}+ bytes", min),
-            };
+    let lifecycle = audit_lifecycle_section(type_defs);
+    if !lifecycle.is_empty() {
+        content.push_str(&lifecycle);
+        content.push_str("---\n\n");
+    }
 
-            println!(
-                "  {} {} ({}) - {}",
-                "├─".dimmed(),
-                field.name,
-                field_size.dimmed(),
-                field.description.dimmed()
-            );
+    // Group by category
+    let mut by_category: HashMap<CheckCategory, Vec<&lumos_core::audit_generator::ChecklistItem>> =
+        HashMap::new();
+
+    for item in checklist {
+        by_category
+            .entry(item.category.clone())
+            .or_default()
+            .push(item);
+    }
+
+    // Output each category
+    let category_order = [
+        CheckCategory::AccountValidation,
+        CheckCategory::SignerChecks,
+        CheckCategory::AccessControl,
+        CheckCategory::ArithmeticSafety,
+        CheckCategory::DataValidation,
+        CheckCategory::StateTransition,
+        CheckCategory::Initialization,
+        CheckCategory::RentExemption,
+        CheckCategory::AccountClosure,
+        CheckCategory::Confidentiality,
+    ];
+
+    let localizer = i18n::get();
+    for category in category_order {
+        if let Some(items) = by_category.get(&category) {
+            content.push_str(&format!(
+                "## {} {}\n\n",
+                category.emoji(),
+                localizer.category(&category)
+            ));
+
+            for item in items {
+                let priority_icon = Criticality::from(&item.priority).emoji();
+                let (text, explanation) = localizer.checklist(item);
+
+                content.push_str(&format!("- [ ] {} **{}**\n", priority_icon, text));
+                content.push_str(&format!(
+                    "  - {}: `{}`\n",
+                    tr("audit-context"),
+                    item.context
+                ));
+                if let Some(docs) = &item.docs {
+                    content.push_str(&format!("  - {}: {}\n", tr("audit-docs"), docs));
+                }
+                content.push_str(&format!("  - {}\n\n", explanation));
+            }
         }
+    }
 
-        // Total and rent
-        println!(
-            "  {} Total: {}",
-            "└─".dimmed(),
-            size_str.bold()
-        );
-        println!("     Rent: {} SOL", format!("{:.8}", account.rent_sol).cyan());
+    // Footer
+    content.push_str("---\n\n");
+    content.push_str(&format!("## {}\n\n", tr("audit-additional-title")));
+    for id in [
+        "audit-additional-logic",
+        "audit-additional-errors",
+        "audit-additional-testing",
+        "audit-additional-docs",
+        "audit-additional-deps",
+    ] {
+        content.push_str(&format!("- [ ] {}\n", tr(id)));
+    }
+    content.push('\n');
+
+    content.push_str("---\n\n");
+    content.push_str(&format!("**{}**\n\n", tr("audit-status-title")));
+    for id in [
+        "audit-status-auditor",
+        "audit-status-started",
+        "audit-status-completed",
+        "audit-status-findings",
+    ] {
+        content.push_str(&format!("- {}: _________________\n", tr(id)));
+    }
+    content.push('\n');
 
-        // Warnings
-        for warning in &account.warnings {
-            println!();
-            println!("  {} {}", "⚠".yellow(), warning.yellow());
-        }
+    fs::write(output_path, content)
+        .with_context(|| format!("Failed to write checklist to {}", output_path.display()))?;
+
+    Ok(())
+}
 
-        println!();
+/// Table of the lifecycle declared on each account, empty if there is none
+fn audit_lifecycle_section(type_defs: &[TypeDefinition]) -> String {
+    let accounts: Vec<_> = type_defs
+        .iter()
+        .filter_map(|type_def| match type_def {
+            TypeDefinition::Struct(s) if !s.metadata.lifecycle.is_empty() => Some(s),
+            _ => None,
+        })
+        .collect();
+    if accounts.is_empty() {
+        return String::new();
     }
 
-    // Summary
-    let total_accounts = sizes.len();
-    let accounts_with_warnings = sizes.iter().filter(|s| !s.warnings.is_empty()).count();
+    let calculator = SizeCalculator::new(type_defs);
+    let instructions = |names: &[String]| {
+        names
+            .iter()
+            .map(|name| format!("`{}`", name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
 
-    println!("{}", "Summary:".bold());
-    println!("  Total accounts: {}", total_accounts);
+    let mut content = format!("## 🔁 {}\n\n", tr("audit-lifecycle-title"));
+    content.push_str(&format!("{}\n\n", tr("audit-lifecycle-intro")));
+    content.push_str(&format!(
+        "| {} | {} | {} | {} | {} |\n",
+        tr("audit-lifecycle-account"),
+        tr("audit-lifecycle-initialized-by"),
+        tr("audit-lifecycle-space"),
+        tr("audit-lifecycle-closed-by"),
+        tr("audit-lifecycle-resizable"),
+    ));
+    content.push_str("|---|---|---|---|---|\n");
+
+    for account in accounts {
+        let lifecycle = &account.metadata.lifecycle;
+        let space = calculator
+            .account_size(&account.name)
+            .filter(|_| lifecycle.init_space)
+            .map(|size| report::describe_size(&size.total_bytes));
+        let closed_by = if !lifecycle.closed_by.is_empty() {
+            instructions(&lifecycle.closed_by)
+        } else if lifecycle.closable {
+            tr("audit-lifecycle-closable")
+        } else {
+            "—".to_string()
+        };
+        let or_dash = |text: String| {
+            if text.is_empty() {
+                "—".to_string()
+            } else {
+                text
+            }
+        };
 
-    if accounts_with_warnings > 0 {
-        println!(
-            "  {} with warnings/errors",
-            accounts_with_warnings.to_string().yellow()
-        );
-    } else {
-        println!("  {}", "All accounts within limits ✓".green());
+        content.push_str(&format!(
+            "| `{}` | {} | {} | {} | {} |\n",
+            account.name,
+            or_dash(instructions(&lifecycle.initialized_by)),
+            or_dash(space.unwrap_or_default()),
+            closed_by,
+            if lifecycle.reallocable {
+                tr("audit-lifecycle-yes")
+            } else {
+                "—".to_string()
+            },
+        ));
     }
+    content.push('\n');
 
-    Ok(())
+    content
 }
 
-/// Output sizes in JSON format
-fn output_json(sizes: &[lumos_core::size_calculator::AccountSize]) -> Result<()> {
-    use lumos_core::size_calculator::SizeInfo;
-    use serde_json::json;
-
-    let json_data: Vec<_> = sizes
-        .iter()
-        .map(|account| {
-            let (total_bytes, is_variable) = match &account.total_bytes {
-                SizeInfo::Fixed(bytes) => (*bytes, false),
-                SizeInfo::Variable { min, .. } => (*min, true),
-            };
+/// Generate audit checklist in JSON format
+fn generate_audit_json(
+    checklist: &[lumos_core::audit_generator::ChecklistItem],
+    output_path: &Path,
+) -> Result<()> {
+    let json_data = report::checklist_to_json(checklist);
 
-            json!({
-                "name": account.name,
-                "total_bytes": total_bytes,
-                "is_variable": is_variable,
-                "is_account": account.is_account,
-                "rent_sol": account.rent_sol,
-                "warnings": account.warnings,
-                "fields": account.field_breakdown.iter().map(|field| {
-                    let (bytes, var) = match &field.size {
-                        SizeInfo::Fixed(b) => (*b, false),
-                        SizeInfo::Variable { min, .. } => (*min, true),
-                    };
-                    json!({
-                        "name": field.name,
-                        "bytes": bytes,
-                        "is_variable": var,
-                        "description": field.description,
-                    })
-                }).collect::<Vec<_>>(),
-            })
-        })
-        .collect();
+    let output = serde_json::to_string_pretty(&json_data)?;
+    fs::write(output_path, output)
+        .with_context(|| format!("Failed to write checklist to {}", output_path.display()))?;
 
-    println!("{}", serde_json::to_string_pretty(&json_data)?);
     Ok(())
 }
 
-/// Run security analysis on schema
-fn run_security_analyze(schema_path: &Path, format: &str, strict: bool) -> Result<()> {
-    // Read and parse schema
-    let content = fs::read_to_string(schema_path)
-        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
+/// Decode a sample of each account type's on-chain accounts
+fn run_audit_sample(
+    schema_path: &Path,
+    program_id: &str,
+    url: &str,
+    samples: usize,
+    format: &str,
+) -> Result<()> {
+    use lumos_core::sampling::{discriminator, AccountSample};
 
-    let ast = parse_lumos_file(&content)
-        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+    if !matches!(format, "text" | "json") {
+        anyhow::bail!(
+            "Unsupported audit sample format '{}' (expected text or json)",
+            format
+        );
+    }
+    if samples == 0 {
+        anyhow::bail!("--samples must be at least 1");
+    }
 
+    let ast = resolver::load(schema_path)
+        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
     let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
 
-    if ir.is_empty() {
-        eprintln!(
-            "{}: No type definitions found in schema",
-            "warning".yellow().bold()
-        );
-        return Ok(());
+    let accounts: Vec<&str> = ir
+        .iter()
+        .filter(|t| t.is_account())
+        .map(|t| t.name())
+        .collect();
+    if accounts.is_empty() {
+        anyhow::bail!("{} declares no #[account] types", schema_path.display());
     }
 
-    // Run security analysis
-    let mut analyzer = SecurityAnalyzer::new(&ir);
-    if strict {
-        analyzer = analyzer.with_strict_mode();
-    }
+    let rpc = rpc::Rpc::new(url);
+    let progress = Progress::new().quiet_if(format == "json");
+    let mut results = Vec::new();
+
+    for name in accounts {
+        let (total, sample) = progress.stage(
+            "Sampled".green().bold(),
+            format!("{} accounts of {}", name, program_id),
+            || -> Result<_> {
+                let mut addresses =
+                    rpc.program_account_addresses(program_id, &discriminator(name))?;
+                addresses.sort();
+                let total = addresses.len();
+
+                // Evenly spaced, so reruns sample the same accounts
+                let picked: Vec<String> = if total <= samples {
+                    addresses
+                } else {
+                    (0..samples)
+                        .map(|i| addresses[i * total / samples].clone())
+                        .collect()
+                };
 
-    let findings = analyzer.analyze();
+                let mut sample = AccountSample::new(&ir, name);
+                for (address, data) in picked.iter().zip(rpc.account_data(&picked)?) {
+                    // Closed between listing and fetching
+                    if let Some(data) = data {
+                        sample.add(address, &data);
+                    }
+                }
+                Ok((total, sample))
+            },
+        )?;
+        results.push((total, sample));
+    }
 
     if format == "json" {
-        output_security_json(&findings)?;
+        let json_data: Vec<_> = results
+            .iter()
+            .map(|(total, sample)| {
+                serde_json::json!({
+                    "account": sample.name,
+                    "total": total,
+                    "sampled": sample.sampled,
+                    "failure_rate": sample.failure_rate(),
+                    "failures": sample.failures.iter().map(|f| serde_json::json!({
+                        "address": f.address,
+                        "path": f.error.path,
+                        "offset": f.error.offset,
+                        "message": f.error.message,
+                    })).collect::<Vec<_>>(),
+                    "trailing": sample.trailing,
+                    "expected_bytes": sample.expected.min_bytes(),
+                    "expected_fixed": sample.expected.is_fixed(),
+                    "sizes": sample.sizes.iter().map(|(size, count)| serde_json::json!({
+                        "bytes": size,
+                        "count": count,
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json_data)?);
     } else {
-        output_security_text(&findings, schema_path)?;
+        for (total, sample) in &results {
+            outln!();
+            outln!(
+                "{} ({} of {} accounts sampled)",
+                sample.name.bold(),
+                sample.sampled,
+                total
+            );
+            if sample.sampled == 0 {
+                continue;
+            }
+
+            let expected = if sample.expected.is_fixed() {
+                format!("{} bytes", sample.expected.min_bytes())
+            } else {
+                format!("at least {} bytes", sample.expected.min_bytes())
+            };
+            if let Some((min, median, max)) = sample.size_range() {
+                outln!(
+                    "   Data size: min {}, median {}, max {} bytes (schema: {})",
+                    min,
+                    median,
+                    max,
+                    expected
+                );
+            }
+
+            let rate = format!("{:.1}%", sample.failure_rate() * 100.0);
+            if sample.failures.is_empty() {
+                outln!("   Decode failures: {}", rate.green());
+            } else {
+                outln!(
+                    "   Decode failures: {} ({} of {})",
+                    rate.red().bold(),
+                    sample.failures.len(),
+                    sample.sampled
+                );
+                for failure in &sample.failures {
+                    outln!("     {} {}", failure.address.cyan(), failure.error);
+                }
+            }
+
+            if !sample.trailing.is_empty() {
+                outln!(
+                    "   {} {} of {} sampled accounts have non-zero bytes after the schema layout",
+                    "⚠".yellow(),
+                    sample.trailing.len(),
+                    sample.sampled
+                );
+                for address in &sample.trailing {
+                    outln!("     {}", address.cyan());
+                }
+            }
+        }
     }
 
-    // Exit with error if any critical findings
-    let has_critical = findings
+    // Accounts the schema cannot decode mean the layout has drifted
+    if results
         .iter()
-        .any(|f| matches!(f.severity, lumos_core::security_analyzer::Severity::Critical));
-
-    if has_critical {
+        .any(|(_, sample)| !sample.failures.is_empty())
+    {
         std::process::exit(1);
     }
 
     Ok(())
 }
 
-/// Output security findings in human-readable format
-fn output_security_text(
-    findings: &[lumos_core::security_analyzer::SecurityFinding],
+/// Generate fuzz targets from schema
+fn run_fuzz_generate(
     schema_path: &Path,
+    output_dir: Option<&Path>,
+    type_name: Option<&str>,
+    crate_name: Option<String>,
+    crate_path: Option<String>,
 ) -> Result<()> {
-    use lumos_core::security_analyzer::Severity;
+    let output_dir = output_dir.unwrap_or_else(|| Path::new("fuzz"));
 
-    println!("{}", "Security Analysis Report".bold());
-    println!("Schema: {}", schema_path.display().to_string().cyan());
-    println!();
+    outln!("{:>12} fuzz targets...", "Generating".cyan().bold());
 
-    if findings.is_empty() {
-        println!("{}", "✓ No security issues found!".green().bold());
-        println!();
-        println!("All checks passed. Your schema follows Solana security best practices.");
-        return Ok(());
+    // Read and parse schema
+    let ast = resolver::load(schema_path)?;
+    let ir = transform_to_ir(ast)?;
+
+    let mut config = load_fuzz_config(schema_path)?;
+    if let Some(path) = crate_path {
+        config.crate_path = path;
+    }
+    if let Some(name) = crate_name {
+        config.crate_name = Some(name);
+    }
+    if config.crate_name.is_none() {
+        detect_fuzz_crate(&mut config, output_dir, schema_path);
     }
 
-    // Group by severity
-    let critical: Vec<_> = findings
-        .iter()
-        .filter(|f| matches!(f.severity, Severity::Critical))
-        .collect();
-    let warnings: Vec<_> = findings
-        .iter()
-        .filter(|f| matches!(f.severity, Severity::Warning))
-        .collect();
-    let info: Vec<_> = findings
-        .iter()
-        .filter(|f| matches!(f.severity, Severity::Info))
-        .collect();
+    outln!(
+        "{:>12} {} ({})",
+        "Using".cyan().bold(),
+        config.crate_name(),
+        output_dir.join(&config.crate_path).display()
+    );
 
-    // Summary
-    println!("{}", "Summary:".bold());
-    if !critical.is_empty() {
-        println!(
-            "  {} {} critical issues",
-            "🚨".to_string(),
-            critical.len().to_string().red().bold()
-        );
+    let progress = Progress::new();
+    let generator = FuzzGenerator::new(&ir).with_config(config);
+
+    // Filter by type if specified
+    let targets: Vec<_> = if let Some(name) = type_name {
+        if !generator.type_exists(name) {
+            anyhow::bail!("Type '{}' not found in schema", name);
+        }
+        generator
+            .generate_all()
+            .into_iter()
+            .filter(|t| t.type_name == name)
+            .collect()
+    } else {
+        generator.generate_all()
+    };
+
+    if targets.is_empty() {
+        outln!("{}", "⚠ No types found in schema".yellow());
+        return Ok(());
     }
-    if !warnings.is_empty() {
-        println!("  ⚠️  {} warnings", warnings.len().to_string().yellow());
+
+    // Create directory structure
+    let fuzz_dir = output_dir;
+    let fuzz_targets_dir = fuzz_dir.join("fuzz_targets");
+
+    fs::create_dir_all(paths::long_path(&fuzz_targets_dir))
+        .with_context(|| format!("Failed to create directory: {}", fuzz_targets_dir.display()))?;
+
+    // Generate Cargo.toml
+    let cargo_toml_path = fuzz_dir.join("Cargo.toml");
+    let cargo_toml = generator.generate_cargo_toml(&targets);
+    fs::write(paths::long_path(&cargo_toml_path), cargo_toml)
+        .with_context(|| format!("Failed to write {}", cargo_toml_path.display()))?;
+
+    progress.status("Created".green().bold(), cargo_toml_path.display());
+
+    // Generate README
+    let readme_path = fuzz_dir.join("README.md");
+    let readme = generator.generate_readme();
+    fs::write(paths::long_path(&readme_path), readme)
+        .with_context(|| format!("Failed to write {}", readme_path.display()))?;
+
+    progress.status("Created".green().bold(), readme_path.display());
+
+    // Generate fuzz targets
+    let bar = progress.bar(targets.len(), "Writing");
+    for target in &targets {
+        let target_path = fuzz_targets_dir.join(format!("{}.rs", target.name));
+        bar.set_message(target.name.clone());
+        fs::write(paths::long_path(&target_path), &target.code)
+            .with_context(|| format!("Failed to write {}", target_path.display()))?;
+
+        bar.suspend(|| {
+            progress.status(
+                "Generated".green().bold(),
+                format!("{} (for {})", target_path.display(), target.type_name),
+            )
+        });
+        bar.inc(1);
     }
-    if !info.is_empty() {
-        println!("  ℹ️  {} informational", info.len());
+    bar.finish_and_clear();
+
+    if progress::quiet() {
+        return Ok(());
     }
-    println!();
 
-    // Critical findings
-    if !critical.is_empty() {
-        println!("{}", "CRITICAL ISSUES".red().bold());
-        println!("{}", "═".repeat(60).red());
-        println!();
+    outln!(
+        "\n{} Generated {} fuzz target{} in {}",
+        "✓".green().bold(),
+        targets.len(),
+        if targets.len() == 1 { "" } else { "s" },
+        progress.elapsed()
+    );
 
-        for (i, finding) in critical.iter().enumerate() {
-            print_finding(finding, i + 1);
-        }
+    outln!("\n{}", "Next steps:".cyan().bold());
+    outln!(
+        "  1. Install cargo-fuzz: {}",
+        "cargo install cargo-fuzz".yellow()
+    );
+    outln!(
+        "  2. Run fuzzing: {}",
+        format!(
+            "cd {} && cargo fuzz run {}",
+            fuzz_dir.display(),
+            targets[0].name
+        )
+        .yellow()
+    );
+
+    Ok(())
+}
+
+/// Run fuzzing for a specific type
+fn run_fuzz_run(
+    schema_path: &Path,
+    type_name: &str,
+    jobs: usize,
+    max_time: Option<u64>,
+) -> Result<()> {
+    outln!(
+        "{:>12} fuzzer for type '{}'",
+        "Running".cyan().bold(),
+        type_name
+    );
+
+    // Read and parse schema to verify type exists
+    let ast = resolver::load(schema_path)?;
+    let ir = transform_to_ir(ast)?;
+
+    let generator = FuzzGenerator::new(&ir);
+
+    if !generator.type_exists(type_name) {
+        anyhow::bail!("Type '{}' not found in schema", type_name);
     }
 
-    // Warnings
-    if !warnings.is_empty() {
-        println!("{}", "WARNINGS".yellow().bold());
-        println!("{}", "═".repeat(60).yellow());
-        println!();
+    // Convert type name to fuzz target name
+    let target_name = format!("fuzz_{}", to_snake_case(type_name));
 
-        for (i, finding) in warnings.iter().enumerate() {
-            print_finding(finding, i + 1);
-        }
+    // Build cargo-fuzz command
+    let mut args = vec!["fuzz", "run", &target_name];
+
+    // Add arguments
+    let mut extra_args = vec![];
+
+    if jobs > 1 {
+        extra_args.push(format!("-jobs={}", jobs));
     }
 
-    // Info
-    if !info.is_empty() {
-        println!("{}", "INFORMATIONAL".dimmed().bold());
-        println!("{}", "═".repeat(60).dimmed());
-        println!();
+    if let Some(time) = max_time {
+        extra_args.push(format!("-max_total_time={}", time));
+    }
 
-        for (i, finding) in info.iter().enumerate() {
-            print_finding(finding, i + 1);
+    if !extra_args.is_empty() {
+        args.push("--");
+        for arg in &extra_args {
+            args.push(arg);
         }
     }
 
-    // Footer
-    println!();
-    println!("{}", "Recommendations:".bold());
-    if !critical.is_empty() {
-        println!(
-            "  {} Fix all critical issues before deployment",
-            "🚨".red()
-        );
-    }
-    if !warnings.is_empty() {
-        println!("  ⚠️  Review and address warnings");
+    outln!(
+        "{:>12} {}",
+        "Executing".cyan().bold(),
+        format!("cargo {}", args.join(" ")).yellow()
+    );
+
+    // Execute cargo-fuzz
+    use std::process::Command;
+
+    let status = Command::new("cargo")
+        .args(&args)
+        .current_dir("fuzz")
+        .status()
+        .with_context(|| "Failed to run cargo-fuzz. Is it installed? (cargo install cargo-fuzz)")?;
+
+    if !status.success() {
+        anyhow::bail!("Fuzzing failed with exit code: {}", status);
     }
-    println!("  📚 See: docs/security/static-analysis.md");
+
+    outln!("{}", "✓ Fuzzing completed".green().bold());
 
     Ok(())
 }
 
-/// Print a single finding
-fn print_finding(finding: &lumos_core::security_analyzer::SecurityFinding, _index: usize) {
-    use lumos_core::security_analyzer::Severity;
+/// Generate corpus files for fuzzing
+fn run_fuzz_corpus(
+    schema_path: &Path,
+    output_dir: Option<&Path>,
+    type_name: Option<&str>,
+    random_count: usize,
+    seed: u64,
+    sync: bool,
+    minimize: bool,
+) -> Result<()> {
+    let output_dir = output_dir.unwrap_or_else(|| Path::new("fuzz/corpus"));
 
-    let emoji = finding.severity.emoji();
-    let severity_str = match finding.severity {
-        Severity::Critical => finding.severity.as_str().red().bold(),
-        Severity::Warning => finding.severity.as_str().yellow().bold(),
-        Severity::Info => finding.severity.as_str().dimmed().bold(),
-    };
+    outln!("{:>12} corpus files...", "Generating".cyan().bold());
 
-    println!(
-        "{} [{}] {}",
-        emoji,
-        severity_str,
-        finding.vulnerability.as_str().bold()
-    );
+    // Read and parse schema
+    let ast = resolver::load(schema_path)?;
+    let ir = transform_to_ir(ast)?;
 
-    // Location
-    let location = if let Some(ref field) = finding.location.field_name {
-        format!("{}::{}", finding.location.type_name, field)
+    let generator = CorpusGenerator::new(&ir).with_random_instances(random_count, seed);
+
+    // Filter by type if specified
+    let corpus_files: Vec<_> = if let Some(name) = type_name {
+        generator
+            .generate_all()
+            .into_iter()
+            .filter(|c| c.type_name == name)
+            .collect()
     } else {
-        finding.location.type_name.clone()
+        generator.generate_all()
     };
-    println!("   Location: {}", location.cyan());
 
-    // Message
-    println!("   {}", finding.message);
-
-    // Suggestion
-    println!("   💡 {}", finding.suggestion.dimmed());
-
-    println!();
-}
-
-/// Output security findings in JSON format
-fn output_security_json(findings: &[lumos_core::security_analyzer::SecurityFinding]) -> Result<()> {
-    use serde_json::json;
+    if corpus_files.is_empty() {
+        outln!("{}", "⚠ No corpus files generated".yellow());
+        return Ok(());
+    }
 
-    let json_data: Vec<_> = findings
-        .iter()
-        .map(|finding| {
-            json!({
-                "severity": finding.severity.as_str(),
-                "vulnerability_type": finding.vulnerability.as_str(),
-                "location": {
-                    "type_name": finding.location.type_name,
-                    "field_name": finding.location.field_name,
-                },
-                "message": finding.message,
-                "suggestion": finding.suggestion,
-            })
-        })
-        .collect();
+    // Create corpus directory structure
+    // Organize by type: fuzz/corpus/{target_name}/...
+    let mut added = 0;
+    let mut duplicates = 0;
+    let mut updated_targets: Vec<String> = Vec::new();
 
-    println!("{}", serde_json::to_string_pretty(&json_data)?);
-    Ok(())
-}
+    for file in &corpus_files {
+        let target_name = format!("fuzz_{}", to_snake_case(&file.type_name));
+        let target_corpus_dir = output_dir.join(&target_name);
 
-/// Run audit checklist generation
-fn run_audit_generate(schema_path: &Path, output_path: Option<&Path>, format: &str) -> Result<()> {
-    // Read and parse schema
-    let content = fs::read_to_string(schema_path)
-        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
+        fs::create_dir_all(&target_corpus_dir).with_context(|| {
+            format!(
+                "Failed to create directory: {}",
+                target_corpus_dir.display()
+            )
+        })?;
 
-    let ast = parse_lumos_file(&content)
-        .with_context(|| format!("Failed to parse schema: {}", schema_path.display()))?;
+        let file_path = if sync {
+            match sync_corpus_file(&target_corpus_dir, &file.name, &file.data)? {
+                Some(path) => path,
+                None => {
+                    duplicates += 1;
+                    continue;
+                }
+            }
+        } else {
+            let file_path = target_corpus_dir.join(&file.name);
+            fs::write(&file_path, &file.data)
+                .with_context(|| format!("Failed to write {}", file_path.display()))?;
+            file_path
+        };
 
-    let ir = transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+        added += 1;
+        if !updated_targets.contains(&target_name) {
+            updated_targets.push(target_name);
+        }
 
-    if ir.is_empty() {
-        eprintln!(
-            "{}: No type definitions found in schema",
-            "warning".yellow().bold()
+        outln!(
+            "{:>12} {} ({} bytes) - {}",
+            "Created".green().bold(),
+            file_path.display(),
+            file.data.len(),
+            file.description
         );
-        return Ok(());
     }
 
-    // Generate checklist
-    let generator = AuditGenerator::new(&ir);
-    let checklist = generator.generate();
-
-    // Determine output path
-    let output = output_path.unwrap_or_else(|| Path::new("SECURITY_AUDIT.md"));
+    if sync {
+        outln!(
+            "\n{} Synced {} new corpus file{}, skipped {} duplicate{}",
+            "✓".green().bold(),
+            added,
+            if added == 1 { "" } else { "s" },
+            duplicates,
+            if duplicates == 1 { "" } else { "s" }
+        );
 
-    // Generate output based on format
-    if format == "json" {
-        generate_audit_json(&checklist, output)?;
+        if minimize {
+            for target_name in &updated_targets {
+                minimize_corpus(target_name, &output_dir.join(target_name))?;
+            }
+        }
     } else {
-        generate_audit_markdown(&checklist, schema_path, output)?;
+        outln!(
+            "\n{} Generated {} corpus file{}",
+            "✓".green().bold(),
+            corpus_files.len(),
+            if corpus_files.len() == 1 { "" } else { "s" }
+        );
     }
 
-    println!(
-        "\n{} {}",
-        "Generated:".green().bold(),
-        output.display().to_string().bold()
-    );
-    println!();
-    println!("Checklist includes:");
-    println!("  ✓ {} total checks", checklist.len());
+    Ok(())
+}
 
-    // Count by category
-    use lumos_core::audit_generator::CheckCategory;
-    let categories = [
-        CheckCategory::AccountValidation,
-        CheckCategory::SignerChecks,
-        CheckCategory::ArithmeticSafety,
-        CheckCategory::AccessControl,
-    ];
+/// Merge a generated corpus file into an existing target corpus
+///
+/// Existing files are never overwritten, since they may be inputs the fuzzer
+/// discovered. Returns `None` when a file with identical content already
+/// exists; if only the name is taken, the content hash is appended to it.
+fn sync_corpus_file(target_dir: &Path, name: &str, data: &[u8]) -> Result<Option<PathBuf>> {
+    let hash = content_hash(data);
+
+    for entry in fs::read_dir(target_dir)
+        .with_context(|| format!("Failed to read directory: {}", target_dir.display()))?
+    {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
 
-    for category in categories {
-        let count = checklist
-            .iter()
-            .filter(|item| item.category == category)
-            .count();
-        if count > 0 {
-            println!("  ✓ {} {} checks", count, category.as_str().to_lowercase());
+        let existing =
+            fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        if existing.len() == data.len() && content_hash(&existing) == hash && existing == data {
+            return Ok(None);
         }
     }
 
-    Ok(())
+    let mut file_path = target_dir.join(name);
+    if file_path.exists() {
+        file_path = target_dir.join(format!("{}_{:016x}", name, hash));
+    }
+
+    fs::write(&file_path, data)
+        .with_context(|| format!("Failed to write {}", file_path.display()))?;
+
+    Ok(Some(file_path))
 }
 
-/// Generate audit checklist in Markdown format
-fn generate_audit_markdown(
-    checklist: &[lumos_core::audit_generator::ChecklistItem],
-    schema_path: &Path,
-    output_path: &Path,
-) -> Result<()> {
-    use lumos_core::audit_generator::CheckCategory;
-    use std::collections::HashMap;
+/// Hash corpus file content for deduplication
+fn content_hash(data: &[u8]) -> u64 {
+    use std::hash::{Hash, Hasher};
 
-    let mut content = String::new();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
 
-    // Header
-    content.push_str("# Security Audit Checklist\n\n");
-    content.push_str(&format!(
-        "**Generated from:** `{}`\n",
-        schema_path.display()
-    ));
-    content.push_str(&format!("**Date:** {}\n\n", chrono::Local::now().format("%Y-%m-%d")));
-    content.push_str(&format!("**Total Checks:** {}\n\n", checklist.len()));
+/// Minimize a target corpus with `cargo fuzz cmin`
+fn minimize_corpus(target_name: &str, corpus_dir: &Path) -> Result<()> {
+    use std::process::Command;
 
-    content.push_str("---\n\n");
-    content.push_str("## ⚠️ Important Disclaimer\n\n");
-    content.push_str("**This automated checklist is a supplementary tool and does NOT replace professional security audits.**\n\n");
-    content.push_str("- Generated checklists identify common vulnerability patterns based on schema structure\n");
-    content.push_str("- They cannot detect logic bugs, business logic flaws, or complex attack vectors\n");
-    content.push_str("- **Always conduct thorough manual code review and professional security audits** before deploying to production\n");
-    content.push_str("- Consider engaging professional security auditors for mainnet deployments\n\n");
-    content.push_str("---\n\n");
-    content.push_str("## How to Use This Checklist\n\n");
-    content.push_str("- [ ] = Not checked yet\n");
-    content.push_str("- [x] = Verified and compliant\n");
-    content.push_str("- Priority: 🔴 CRITICAL | 🟡 HIGH | 🟢 MEDIUM | ⚪ LOW\n\n");
-    content.push_str("**Review each item during your security audit and check the box when verified.**\n\n");
+    let corpus_dir = corpus_dir
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", corpus_dir.display()))?;
+    let corpus_arg = corpus_dir.display().to_string();
+    let args = ["fuzz", "cmin", target_name, corpus_arg.as_str()];
 
-    content.push_str("---\n\n");
+    outln!(
+        "{:>12} {}",
+        "Executing".cyan().bold(),
+        format!("cargo {}", args.join(" ")).yellow()
+    );
 
-    // Group by category
-    let mut by_category: HashMap<CheckCategory, Vec<&lumos_core::audit_generator::ChecklistItem>> =
-        HashMap::new();
+    let status = Command::new("cargo")
+        .args(args)
+        .current_dir("fuzz")
+        .status()
+        .with_context(|| "Failed to run cargo-fuzz. Is it installed? (cargo install cargo-fuzz)")?;
 
-    for item in checklist {
-        by_category
-            .entry(item.category.clone())
-            .or_default()
-            .push(item);
+    if !status.success() {
+        anyhow::bail!("Corpus minimization failed with exit code: {}", status);
     }
 
-    // Output each category
-    let category_order = [
-        CheckCategory::AccountValidation,
-        CheckCategory::SignerChecks,
-        CheckCategory::AccessControl,
-        CheckCategory::ArithmeticSafety,
-        CheckCategory::DataValidation,
-        CheckCategory::StateTransition,
-        CheckCategory::Initialization,
-        CheckCategory::RentExemption,
-    ];
-
-    for category in category_order {
-        if let Some(items) = by_category.get(&category) {
-            content.push_str(&format!(
-                "## {} {}\n\n",
-                category.emoji(),
-                category.as_str()
-            ));
+    Ok(())
+}
 
-            for item in items {
-                let priority_icon = match item.priority {
-                    lumos_core::audit_generator::Priority::Critical => "🔴",
-                    lumos_core::audit_generator::Priority::High => "🟡",
-                    lumos_core::audit_generator::Priority::Medium => "🟢",
-                    lumos_core::audit_generator::Priority::Low => "⚪",
-                };
+/// Validate a lumos.toml and report every problem
+fn run_config_check(path: &Path) -> Result<()> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let issues =
+        config::check(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
 
-                content.push_str(&format!("- [ ] {} **{}**\n", priority_icon, item.item));
-                content.push_str(&format!("  - Context: `{}`\n", item.context));
-                content.push_str(&format!("  - {}\n\n", item.explanation));
-            }
+    if !issues.is_empty() {
+        for issue in &issues {
+            config::report(issue, true);
         }
+        anyhow::bail!("{} problem(s) in {}", issues.len(), path.display());
     }
 
-    // Footer
-    content.push_str("---\n\n");
-    content.push_str("## Additional Security Considerations\n\n");
-    content.push_str("- [ ] **Program Logic:** Verify business logic correctness\n");
-    content.push_str("- [ ] **Error Handling:** Ensure all error paths are covered\n");
-    content.push_str("- [ ] **Testing:** Comprehensive test suite including edge cases\n");
-    content.push_str("- [ ] **Documentation:** Code is well-documented\n");
-    content.push_str("- [ ] **Dependencies:** All dependencies are audited and up-to-date\n\n");
-
-    content.push_str("---\n\n");
-    content.push_str("**Audit Status:**\n\n");
-    content.push_str("- Auditor: _________________\n");
-    content.push_str("- Date Started: _________________\n");
-    content.push_str("- Date Completed: _________________\n");
-    content.push_str("- Findings: _________________\n\n");
+    Progress::new().status(
+        "Checked".green().bold(),
+        format!("{} is valid", path.display()),
+    );
+    Ok(())
+}
 
-    fs::write(output_path, content)
-        .with_context(|| format!("Failed to write checklist to {}", output_path.display()))?;
+/// Print a lumos.toml, or every setting in effect with its source
+fn run_config_print(path: &Path, resolved: bool) -> Result<()> {
+    if resolved {
+        let settings = config::load(path)?.with_defaults()?;
+        for (key, value, source) in settings.entries() {
+            outln!("{} = {}  {}", key, value, format!("# {}", source).dimmed());
+        }
+        return Ok(());
+    }
 
+    let content = config::read(path)?.with_context(|| format!("{} not found", path.display()))?;
+    outln!("{}", content.trim_end());
     Ok(())
 }
 
-/// Generate audit checklist in JSON format
-fn generate_audit_json(
-    checklist: &[lumos_core::audit_generator::ChecklistItem],
-    output_path: &Path,
-) -> Result<()> {
-    use serde_json::json;
+/// Load fuzz settings from the `lumos.toml` next to the schema and `LUMOS_*` variables
+fn load_fuzz_config(schema_path: &Path) -> Result<FuzzConfig> {
+    let config_path = lumos_toml_for(schema_path);
 
-    let json_data: Vec<_> = checklist
-        .iter()
-        .map(|item| {
-            json!({
-                "category": item.category.as_str(),
-                "priority": item.priority.as_str(),
-                "item": item.item,
-                "context": item.context,
-                "explanation": item.explanation,
-                "checked": false,
-            })
-        })
-        .collect();
+    let settings = config::load(&config_path)?;
+    FuzzConfig::from_lumos_toml(&settings.to_toml()?)
+        .with_context(|| format!("Invalid [fuzz] settings in {}", config_path.display()))
+}
 
-    let output = serde_json::to_string_pretty(&json_data)?;
-    fs::write(output_path, output)
-        .with_context(|| format!("Failed to write checklist to {}", output_path.display()))?;
+/// Load `[lint]` rule levels from the `lumos.toml` next to the schema and `LUMOS_*` variables
+fn load_lint_config(schema_path: &Path) -> Result<LintConfig> {
+    let config_path = lumos_toml_for(schema_path);
+    let settings = config::load(&config_path)?;
+    LintConfig::from_lumos_toml(&settings.to_toml()?)
+        .with_context(|| format!("Invalid [lint] settings in {}", config_path.display()))
+}
 
-    Ok(())
+/// Load the validation passes of `[validation]` in the `lumos.toml` next to the schema
+fn load_validation_passes(schema_path: &Path) -> Result<PassManager> {
+    let config_path = lumos_toml_for(schema_path);
+    let settings = config::load(&config_path)?;
+    let passes: Vec<String> = settings
+        .get("validation.passes")
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|pass| pass.as_str().map(str::to_string))
+        .collect();
+    passes::load(&config_path, schema_path, &passes)
 }
 
-/// Generate fuzz targets from schema
-fn run_fuzz_generate(
+/// Run the project's validation passes, failing on errors
+fn check_validation_passes(
     schema_path: &Path,
-    output_dir: Option<&Path>,
-    type_name: Option<&str>,
+    ast: &lumos_core::ast::LumosFile,
+    ir: &[TypeDefinition],
 ) -> Result<()> {
-    let output_dir = output_dir.unwrap_or_else(|| Path::new("fuzz"));
-
-    println!(
-        "{:>12} {}",
-        "Generating".cyan().bold(),
-        "fuzz targets..."
-    );
-
-    // Read and parse schema
-    let source = fs::read_to_string(schema_path)
-        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
-
-    let ast = parse_lumos_file(&source)?;
-    let ir = transform_to_ir(ast)?;
-
-    let generator = FuzzGenerator::new(&ir);
-
-    // Filter by type if specified
-    let targets: Vec<_> = if let Some(name) = type_name {
-        if !generator.type_exists(name) {
-            anyhow::bail!("Type '{}' not found in schema", name);
+    let passes = load_validation_passes(schema_path)?;
+    let diagnostics = passes.run(ir);
+    for diagnostic in diagnostics.iter() {
+        let label = match diagnostic.level {
+            validation::Level::Error => "error".red().bold(),
+            validation::Level::Warning => "warning".yellow().bold(),
+        };
+        errln!("{}[{}]: {}", label, diagnostic.pass, diagnostic.message);
+        let location = diagnostic.location.as_ref().and_then(|location| {
+            diagnostic::definition(ast, &location.type_name, location.field_name.as_deref())
+        });
+        if let Some(location) = location {
+            errln!("  {} {}", "-->".blue().bold(), location.format());
         }
-        generator
-            .generate_all()
-            .into_iter()
-            .filter(|t| t.type_name == name)
-            .collect()
-    } else {
-        generator.generate_all()
-    };
-
-    if targets.is_empty() {
-        println!("{}", "⚠ No types found in schema".yellow());
-        return Ok(());
     }
 
-    // Create directory structure
-    let fuzz_dir = output_dir;
-    let fuzz_targets_dir = fuzz_dir.join("fuzz_targets");
+    let errors = diagnostics.count(validation::Level::Error);
+    if errors > 0 {
+        anyhow::bail!("Schema failed {} check(s) of its validation passes", errors);
+    }
+    Ok(())
+}
 
-    fs::create_dir_all(&fuzz_targets_dir)
-        .with_context(|| format!("Failed to create directory: {}", fuzz_targets_dir.display()))?;
+/// Load `[rust]` settings from the `lumos.toml` next to the schema and `LUMOS_*` variables
+fn load_rust_config(schema_path: &Path, profile: Option<&str>) -> Result<RustConfig> {
+    let config_path = lumos_toml_for(schema_path);
+    let settings = config::load_profile(&config_path, profile)?;
+    RustConfig::from_lumos_toml(&settings.to_toml()?)
+        .with_context(|| format!("Invalid [rust] settings in {}", config_path.display()))
+}
 
-    // Generate Cargo.toml
-    let cargo_toml_path = fuzz_dir.join("Cargo.toml");
-    let cargo_toml = generator.generate_cargo_toml("generated");
-    fs::write(&cargo_toml_path, cargo_toml)
-        .with_context(|| format!("Failed to write {}", cargo_toml_path.display()))?;
+/// Load `[typescript]` settings from the `lumos.toml` next to the schema and `LUMOS_*` variables
+fn load_typescript_config(schema_path: &Path, profile: Option<&str>) -> Result<TypeScriptConfig> {
+    let config_path = lumos_toml_for(schema_path);
+    let settings = config::load_profile(&config_path, profile)?;
+    TypeScriptConfig::from_lumos_toml(&settings.to_toml()?)
+        .with_context(|| format!("Invalid [typescript] settings in {}", config_path.display()))
+}
 
-    println!(
-        "{:>12} {}",
-        "Created".green().bold(),
-        cargo_toml_path.display()
-    );
+/// Load the `lumos generate` options of `[profiles.<name>]`, or the defaults
+///
+/// The profile's output directory is relative to the `lumos.toml` defining it,
+/// and `targets` from `--target` replace the profile's targets.
+fn load_generate_profile(
+    schema_path: &Path,
+    profile: Option<&str>,
+    targets: &[String],
+) -> Result<config::Profile> {
+    let config_path = lumos_toml_for(schema_path);
+    let settings = config::load_profile(&config_path, profile)?;
+
+    let mut options = settings.profile(profile);
+    if !targets.is_empty() {
+        let selected = |target: &str| targets.iter().any(|t| t == target);
+        options.rust = selected("rust");
+        options.typescript = selected("typescript");
+        options.python = selected("python");
+        options.jsonschema = selected("jsonschema");
+        options.go = selected("go");
+    }
+    if !options.rust && !options.typescript && !options.python && !options.jsonschema && !options.go
+    {
+        match profile {
+            Some(profile) => anyhow::bail!(
+                "Profile `{}` in {} selects no targets",
+                profile,
+                config_path.display()
+            ),
+            None => anyhow::bail!("[output] in {} selects no targets", config_path.display()),
+        }
+    }
+    if let (Some(directory), Some(config_dir)) = (&options.directory, config_path.parent()) {
+        options.directory = Some(config_dir.join(directory));
+    }
+    Ok(options)
+}
 
-    // Generate README
-    let readme_path = fuzz_dir.join("README.md");
-    let readme = generator.generate_readme();
-    fs::write(&readme_path, readme)
-        .with_context(|| format!("Failed to write {}", readme_path.display()))?;
+/// The `lumos.toml` next to a schema
+fn lumos_toml_for(schema_path: &Path) -> PathBuf {
+    schema_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("lumos.toml")
+}
 
-    println!(
-        "{:>12} {}",
-        "Created".green().bold(),
-        readme_path.display()
-    );
+/// Fill in the crate name from the Cargo.toml at the configured crate path
+///
+/// If that manifest is a virtual workspace, the member containing the schema
+/// is used instead. Leaves the config untouched when nothing matches.
+fn detect_fuzz_crate(config: &mut FuzzConfig, output_dir: &Path, schema_path: &Path) {
+    let read_manifest = |dir: &Path| -> Option<toml::Value> {
+        let content = fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+        toml::from_str(&content).ok()
+    };
+    let package_name = |manifest: &toml::Value| -> Option<String> {
+        Some(manifest.get("package")?.get("name")?.as_str()?.to_string())
+    };
 
-    // Generate fuzz targets
-    for target in &targets {
-        let target_path = fuzz_targets_dir.join(format!("{}.rs", target.name));
-        fs::write(&target_path, &target.code)
-            .with_context(|| format!("Failed to write {}", target_path.display()))?;
+    let crate_dir = output_dir.join(&config.crate_path);
+    let Some(manifest) = read_manifest(&crate_dir) else {
+        return;
+    };
 
-        println!(
-            "{:>12} {} (for {})",
-            "Generated".green().bold(),
-            target_path.display(),
-            target.type_name
-        );
+    if let Some(name) = package_name(&manifest) {
+        config.crate_name = Some(name);
+        return;
     }
 
-    println!(
-        "\n{} Generated {} fuzz target{}",
-        "✓".green().bold(),
-        targets.len(),
-        if targets.len() == 1 { "" } else { "s" }
-    );
+    let Some(members) = manifest
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+    else {
+        return;
+    };
+    let Ok(schema_path) = schema_path.canonicalize() else {
+        return;
+    };
 
-    println!("\n{}", "Next steps:".cyan().bold());
-    println!("  1. Install cargo-fuzz: {}", "cargo install cargo-fuzz".yellow());
-    println!("  2. Run fuzzing: {}", format!("cd {} && cargo fuzz run {}", fuzz_dir.display(), targets[0].name).yellow());
+    for member in members.iter().filter_map(|m| m.as_str()) {
+        let member_dir = crate_dir.join(member);
+        let contains_schema = member_dir
+            .canonicalize()
+            .is_ok_and(|dir| schema_path.starts_with(dir));
 
-    Ok(())
+        if contains_schema {
+            if let Some(name) = read_manifest(&member_dir).as_ref().and_then(package_name) {
+                config.crate_name = Some(name);
+                config.crate_path = format!("{}/{}", config.crate_path, member);
+            }
+            return;
+        }
+    }
 }
 
-/// Run fuzzing for a specific type
-fn run_fuzz_run(
+/// Report schema-level (and optionally llvm-cov) coverage for fuzz corpora
+fn run_fuzz_coverage(
     schema_path: &Path,
-    type_name: &str,
-    jobs: usize,
-    max_time: Option<u64>,
+    type_name: Option<&str>,
+    corpus_dir: Option<&Path>,
+    format: &str,
+    schema_only: bool,
 ) -> Result<()> {
-    println!(
-        "{:>12} {} for type '{}'",
-        "Running".cyan().bold(),
-        "fuzzer",
-        type_name
-    );
-
-    // Read and parse schema to verify type exists
-    let source = fs::read_to_string(schema_path)
-        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
+    let corpus_dir = corpus_dir.unwrap_or_else(|| Path::new("fuzz/corpus"));
 
-    let ast = parse_lumos_file(&source)?;
+    let ast = resolver::load(schema_path)?;
     let ir = transform_to_ir(ast)?;
 
     let generator = FuzzGenerator::new(&ir);
-
-    if !generator.type_exists(type_name) {
-        anyhow::bail!("Type '{}' not found in schema", type_name);
+    if let Some(name) = type_name {
+        if !generator.type_exists(name) {
+            anyhow::bail!("Type '{}' not found in schema", name);
+        }
     }
 
-    // Convert type name to fuzz target name
-    let target_name = format!("fuzz_{}", to_snake_case(type_name));
+    let targets: Vec<_> = generator
+        .generate_all()
+        .into_iter()
+        .filter(|t| type_name.map_or(true, |name| t.type_name == name))
+        .collect();
 
-    // Build cargo-fuzz command
-    let mut args = vec!["fuzz", "run", &target_name];
+    let analyzer = CoverageAnalyzer::new(&ir);
+    let mut results = Vec::new();
 
-    // Add arguments
-    let mut extra_args = vec![];
+    for target in &targets {
+        let target_corpus = corpus_dir.join(&target.name);
+        let inputs = read_corpus_inputs(&target_corpus)?;
 
-    if jobs > 1 {
-        extra_args.push(format!("-jobs={}", jobs));
+        let llvm = if schema_only {
+            None
+        } else {
+            Some(llvm_coverage_summary(&target.name, &target_corpus)?)
+        };
+
+        if let Some(coverage) = analyzer.analyze(&target.type_name, &inputs) {
+            results.push((target.name.clone(), coverage, llvm));
+        }
     }
 
-    if let Some(time) = max_time {
-        extra_args.push(format!("-max_total_time={}", time));
+    match format {
+        "json" => output_coverage_json(&results)?,
+        _ => output_coverage_text(&results),
     }
 
-    if !extra_args.is_empty() {
-        args.push("--");
-        for arg in &extra_args {
-            args.push(arg);
-        }
+    Ok(())
+}
+
+/// Read every file in a target's corpus directory (missing directory = empty corpus)
+fn read_corpus_inputs(dir: &Path) -> Result<Vec<Vec<u8>>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read corpus directory: {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            fs::read(path)
+                .with_context(|| format!("Failed to read corpus file: {}", path.display()))
+        })
+        .collect()
+}
+
+/// Run `cargo fuzz coverage` for a target and summarize it with llvm-cov
+fn llvm_coverage_summary(target_name: &str, corpus_dir: &Path) -> Result<LlvmCoverageSummary> {
+    use std::process::Command;
+
+    let mut args = vec![
+        "fuzz".to_string(),
+        "coverage".to_string(),
+        target_name.to_string(),
+    ];
+    if let Ok(corpus_dir) = corpus_dir.canonicalize() {
+        args.push(corpus_dir.display().to_string());
     }
 
-    println!(
+    outln!(
         "{:>12} {}",
         "Executing".cyan().bold(),
         format!("cargo {}", args.join(" ")).yellow()
     );
 
-    // Execute cargo-fuzz
-    use std::process::Command;
-
     let status = Command::new("cargo")
         .args(&args)
         .current_dir("fuzz")
@@ -1646,82 +6355,165 @@ fn run_fuzz_run(
         .with_context(|| "Failed to run cargo-fuzz. Is it installed? (cargo install cargo-fuzz)")?;
 
     if !status.success() {
-        anyhow::bail!("Fuzzing failed with exit code: {}", status);
+        anyhow::bail!("Coverage run failed with exit code: {}", status);
     }
 
-    println!("{}", "✓ Fuzzing completed".green().bold());
+    let rustc = Command::new("rustc")
+        .arg("-vV")
+        .output()
+        .with_context(|| "Failed to run rustc")?;
+    let rustc_info = String::from_utf8_lossy(&rustc.stdout);
+    let host = rustc_info
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .ok_or_else(|| anyhow::anyhow!("Could not determine host target triple"))?;
+
+    // Prefer the llvm-cov shipped with rustup's llvm-tools component
+    let sysroot = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .output()
+        .with_context(|| "Failed to run rustc")?;
+    let llvm_cov = PathBuf::from(String::from_utf8_lossy(&sysroot.stdout).trim())
+        .join("lib/rustlib")
+        .join(host)
+        .join("bin/llvm-cov");
+    let llvm_cov = if llvm_cov.exists() {
+        llvm_cov
+    } else {
+        PathBuf::from("llvm-cov")
+    };
 
-    Ok(())
-}
+    let binary = Path::new("fuzz/target")
+        .join(host)
+        .join("coverage")
+        .join(host)
+        .join("release")
+        .join(target_name);
+    let profdata = Path::new("fuzz/coverage")
+        .join(target_name)
+        .join("coverage.profdata");
+
+    let output = Command::new(&llvm_cov)
+        .arg("export")
+        .arg("-summary-only")
+        .arg(format!("-instr-profile={}", profdata.display()))
+        .arg(&binary)
+        .output()
+        .with_context(|| {
+            "Failed to run llvm-cov. Is it installed? (rustup component add llvm-tools-preview)"
+        })?;
 
-/// Generate corpus files for fuzzing
-fn run_fuzz_corpus(
-    schema_path: &Path,
-    output_dir: Option<&Path>,
-    type_name: Option<&str>,
-) -> Result<()> {
-    let output_dir = output_dir.unwrap_or_else(|| Path::new("fuzz/corpus"));
+    if !output.status.success() {
+        anyhow::bail!(
+            "llvm-cov failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
 
-    println!(
-        "{:>12} {}",
-        "Generating".cyan().bold(),
-        "corpus files..."
-    );
+    LlvmCoverageSummary::parse(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| anyhow::anyhow!("Could not parse llvm-cov summary for {}", target_name))
+}
 
-    // Read and parse schema
-    let source = fs::read_to_string(schema_path)
-        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
+/// Output fuzz coverage in text format
+fn output_coverage_text(results: &[(String, TypeCoverage, Option<LlvmCoverageSummary>)]) {
+    outln!("{}", "Fuzz Coverage:".bold());
+    outln!();
 
-    let ast = parse_lumos_file(&source)?;
-    let ir = transform_to_ir(ast)?;
+    for (target_name, coverage, llvm) in results {
+        let percent = format!("{:.1}%", coverage.percent());
+        let percent = if coverage.percent() >= 100.0 {
+            percent.green().bold()
+        } else {
+            percent.yellow().bold()
+        };
 
-    let generator = CorpusGenerator::new(&ir);
+        outln!(
+            "  {} ({}): {} schema coverage, {} input{}, {} decoded",
+            coverage.type_name.bold(),
+            target_name.dimmed(),
+            percent,
+            coverage.inputs,
+            if coverage.inputs == 1 { "" } else { "s" },
+            coverage.decoded
+        );
 
-    // Filter by type if specified
-    let corpus_files: Vec<_> = if let Some(name) = type_name {
-        generator
-            .generate_all()
-            .into_iter()
-            .filter(|c| c.type_name == name)
-            .collect()
-    } else {
-        generator.generate_all()
-    };
+        if let Some(llvm) = llvm {
+            outln!(
+                "  {} Lines: {:.1}%, regions: {:.1}%, functions: {:.1}%",
+                "├─".dimmed(),
+                llvm.lines_percent,
+                llvm.regions_percent,
+                llvm.functions_percent
+            );
+        }
 
-    if corpus_files.is_empty() {
-        println!("{}", "⚠ No corpus files generated".yellow());
-        return Ok(());
+        let untested_variants = coverage.untested_variants();
+        if !untested_variants.is_empty() {
+            outln!(
+                "  {} Untested variants: {}",
+                "├─".dimmed(),
+                untested_variants.join(", ").yellow()
+            );
+        }
+
+        let untested_fields = coverage.untested_fields();
+        if untested_fields.is_empty() {
+            outln!("  {} All fields exercised", "└─".dimmed());
+        } else {
+            outln!(
+                "  {} Untested fields: {}",
+                "└─".dimmed(),
+                untested_fields.join(", ").yellow()
+            );
+        }
+        outln!();
     }
 
-    // Create corpus directory structure
-    // Organize by type: fuzz/corpus/{target_name}/...
-    for file in &corpus_files {
-        let target_name = format!("fuzz_{}", to_snake_case(&file.type_name));
-        let target_corpus_dir = output_dir.join(&target_name);
+    let fully_covered = results
+        .iter()
+        .filter(|(_, coverage, _)| coverage.percent() >= 100.0)
+        .count();
 
-        fs::create_dir_all(&target_corpus_dir).with_context(|| {
-            format!("Failed to create directory: {}", target_corpus_dir.display())
-        })?;
+    outln!("{}", "Summary:".bold());
+    outln!("  Targets: {}", results.len());
+    outln!("  Fully covered: {}", fully_covered);
+}
 
-        let file_path = target_corpus_dir.join(&file.name);
-        fs::write(&file_path, &file.data)
-            .with_context(|| format!("Failed to write {}", file_path.display()))?;
+/// Output fuzz coverage in JSON format
+fn output_coverage_json(
+    results: &[(String, TypeCoverage, Option<LlvmCoverageSummary>)],
+) -> Result<()> {
+    use serde_json::json;
 
-        println!(
-            "{:>12} {} ({} bytes) - {}",
-            "Created".green().bold(),
-            file_path.display(),
-            file.data.len(),
-            file.description
-        );
-    }
+    let entries = |entries: &[lumos_core::fuzz_coverage::CoverageEntry]| {
+        entries
+            .iter()
+            .map(|e| json!({ "name": e.name, "hits": e.hits }))
+            .collect::<Vec<_>>()
+    };
 
-    println!(
-        "\n{} Generated {} corpus file{}",
-        "✓".green().bold(),
-        corpus_files.len(),
-        if corpus_files.len() == 1 { "" } else { "s" }
-    );
+    let json_data = json!({
+        "targets": results.iter().map(|(target_name, coverage, llvm)| {
+            json!({
+                "target": target_name,
+                "type": coverage.type_name,
+                "inputs": coverage.inputs,
+                "decoded": coverage.decoded,
+                "schema_percent": coverage.percent(),
+                "fields": entries(&coverage.fields),
+                "variants": entries(&coverage.variants),
+                "untested_fields": coverage.untested_fields(),
+                "untested_variants": coverage.untested_variants(),
+                "llvm": llvm.map(|llvm| json!({
+                    "lines_percent": llvm.lines_percent,
+                    "regions_percent": llvm.regions_percent,
+                    "functions_percent": llvm.functions_percent,
+                })),
+            })
+        }).collect::<Vec<_>>(),
+    });
+
+    println!("{}", serde_json::to_string_pretty(&json_data)?);
 
     Ok(())
 }
@@ -1780,47 +6572,33 @@ fn to_snake_case(s: &str) -> String {
 /// ```
 fn validate_output_path(path: &Path) -> Result<()> {
     // If path doesn't exist, check parent directory
-    let check_path = if path.exists() {
-        path
-    } else if let Some(parent) = path.parent() {
-        // If parent doesn't exist, we can't validate write permissions
-        if !parent.exists() {
-            anyhow::bail!(
-                "Output directory parent does not exist: {}. Create it first.",
-                parent.display()
-            );
-        }
-        parent
-    } else {
+    let Some(check_path) = paths::output_dir(path) else {
         // No parent means root directory or invalid path
         anyhow::bail!("Invalid output path: {}", path.display());
     };
 
+    // If parent doesn't exist, we can't validate write permissions
+    if !check_path.exists() {
+        anyhow::bail!(
+            "Output directory parent does not exist: {}. Create it first.",
+            check_path.display()
+        );
+    }
+
     // Check if path is absolute or can be canonicalized
-    let canonical = check_path
+    let canonical = paths::long_path(check_path)
         .canonicalize()
         .with_context(|| format!("Cannot resolve output path: {}", path.display()))?;
 
-    // Verify the canonical path is writable
-    // Try to create a temporary file to test write permissions
-    let test_file = canonical.join(".lumos_write_test");
-    match fs::write(&test_file, "") {
-        Ok(_) => {
-            // Clean up test file
-            let _ = fs::remove_file(&test_file);
-            Ok(())
-        }
-        Err(e) => {
-            anyhow::bail!(
-                "Output directory is not writable: {}\nError: {}",
-                canonical.display(),
-                e
-            );
-        }
-    }
+    // Verify the canonical path is writable without writing into it
+    paths::check_writable(&canonical).with_context(|| {
+        format!(
+            "Output directory is not writable: {}",
+            paths::display_path(&canonical)
+        )
+    })
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1851,9 +6629,15 @@ struct Foo { id: u64 }
             false, // backup
             false, // show_diff
             None,  // address
+            true,  // no_cache
+            None,  // profile
+            &[],   // targets
         );
 
-        assert!(res.is_err(), "Expected error when address is not provided in strict mode");
+        assert!(
+            res.is_err(),
+            "Expected error when address is not provided in strict mode"
+        );
     }
 
     #[test]
@@ -1867,10 +6651,13 @@ struct Foo { id: u64 }
         let res = run_generate(
             file.path(),
             Some(Path::new(".")),
-            true,                      // dry_run
-            false,                     // backup
-            false,                     // show_diff
+            true,               // dry_run
+            false,              // backup
+            false,              // show_diff
             Some("5Hj3...xyz"), // address
+            true,               // no_cache
+            None,               // profile
+            &[],                // targets
         );
 
         assert!(res.is_ok(), "Expected success when address provided");
@@ -1896,18 +6683,27 @@ struct Foo { id: u64 }
         let res = run_generate(
             schema_file.path(),
             Some(out),
-            false,                    // dry_run = false -> writes files
-            false,                    // backup
-            false,                    // show_diff
+            false, // dry_run = false -> writes files
+            false, // backup
+            false, // show_diff
             Some("5Hj3SomeValidAddrXyz"),
+            true, // no_cache: keep .lumos/ out of the shared temp dir
+            None, // profile
+            &[],  // targets
         );
 
-        assert!(res.is_ok(), "Generation should succeed when address provided");
+        assert!(
+            res.is_ok(),
+            "Generation should succeed when address provided"
+        );
 
         // Read generated file and verify declare_id! inserted
         let gen_path = out.join("generated.rs");
         let contents = std::fs::read_to_string(&gen_path).expect("read generated.rs");
-        assert!(contents.contains("declare_id!(\"5Hj3SomeValidAddrXyz\")"), "declare_id not found");
+        assert!(
+            contents.contains("declare_id!(\"5Hj3SomeValidAddrXyz\")"),
+            "declare_id not found"
+        );
     }
 
     #[test]
@@ -1934,12 +6730,270 @@ struct Foo { id: u64 }
             false,
             false,
             Some("REPLACE_WITH_YOUR_PROGRAM_ID"),
+            true,
+            None, // profile
+            &[],  // targets
         );
 
-        assert!(res.is_ok(), "Generation should succeed with explicit sentinel address");
+        assert!(
+            res.is_ok(),
+            "Generation should succeed with explicit sentinel address"
+        );
 
         let gen_path = out.join("generated.rs");
         let contents = std::fs::read_to_string(&gen_path).expect("read generated.rs");
-        assert!(contents.contains("declare_id!(\"REPLACE_WITH_YOUR_PROGRAM_ID\")"), "sentinel not found");
+        assert!(
+            contents.contains("declare_id!(\"REPLACE_WITH_YOUR_PROGRAM_ID\")"),
+            "sentinel not found"
+        );
+    }
+
+    #[test]
+    fn generate_target_python_writes_only_python() {
+        use tempfile::tempdir;
+
+        let dir = tempdir().expect("tempdir");
+        let out = dir.path();
+        let schema_file = write_schema("#[solana]\n#[account]\nstruct Foo { id: u64 }\n");
+
+        // Python alone needs no program id
+        let res = run_generate(
+            schema_file.path(),
+            Some(out),
+            false, // dry_run
+            false, // backup
+            false, // show_diff
+            None,  // address
+            true,  // no_cache
+            None,  // profile
+            &["python".to_string()],
+        );
+
+        assert!(res.is_ok(), "Generation failed: {:?}", res.err());
+        let contents =
+            std::fs::read_to_string(out.join("generated.py")).expect("read generated.py");
+        assert!(contents.contains("def decode_account(cls, data: bytes) -> Foo:"));
+        assert!(!out.join("generated.rs").exists());
+        assert!(!out.join("generated.ts").exists());
+    }
+
+    #[test]
+    fn generate_target_jsonschema_writes_a_document() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let out = dir.path();
+        let schema_file = write_schema(
+            "schema { name = \"vaults\" }\n#[solana]\n#[account]\nstruct Foo { id: u64 }\n",
+        );
+
+        let res = run_generate(
+            schema_file.path(),
+            Some(out),
+            false, // dry_run
+            false, // backup
+            false, // show_diff
+            None,  // address
+            true,  // no_cache
+            None,  // profile
+            &["jsonschema".to_string()],
+        );
+
+        assert!(res.is_ok(), "Generation failed: {:?}", res.err());
+        let contents = std::fs::read_to_string(out.join("generated.schema.json"))
+            .expect("read generated.schema.json");
+        let document: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(document["$schema"], json_schema::DIALECT);
+        assert_eq!(document["title"], "vaults");
+        assert_eq!(document["$defs"]["Foo"]["required"][0], "id");
+        assert!(!out.join("generated.rs").exists());
+    }
+
+    #[test]
+    fn generate_target_go_names_the_package_after_the_directory() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let out = dir.path().join("indexer");
+        std::fs::create_dir(&out).unwrap();
+        let schema_file = write_schema("#[solana]\n#[account]\nstruct Foo { id: u64 }\n");
+
+        let res = run_generate(
+            schema_file.path(),
+            Some(&out),
+            false, // dry_run
+            false, // backup
+            false, // show_diff
+            None,  // address
+            true,  // no_cache
+            None,  // profile
+            &["go".to_string()],
+        );
+
+        assert!(res.is_ok(), "Generation failed: {:?}", res.err());
+        let contents =
+            std::fs::read_to_string(out.join("generated.go")).expect("read generated.go");
+        assert!(contents.contains("\npackage indexer\n"));
+        assert!(contents.contains("func DecodeFooAccount(data []byte) (*Foo, error) {"));
+        assert!(!out.join("generated.ts").exists());
+    }
+
+    #[test]
+    fn fmt_check_reports_then_fmt_rewrites() {
+        let schema_file = write_schema("#[account] #[solana]\nstruct Foo { id:u64 }\n");
+        let schemas = [schema_file.path().to_path_buf()];
+
+        assert!(run_fmt(&schemas, true).is_err());
+        let unchanged = std::fs::read_to_string(schema_file.path()).expect("read schema");
+        assert!(unchanged.contains("id:u64"));
+
+        run_fmt(&schemas, false).expect("format schema");
+        let formatted = std::fs::read_to_string(schema_file.path()).expect("read schema");
+        assert_eq!(
+            formatted,
+            "#[solana]\n#[account]\nstruct Foo {\n    id: u64,\n}\n"
+        );
+        assert!(run_fmt(&schemas, true).is_ok());
+    }
+
+    #[test]
+    fn diff_accepts_compatible_changes() {
+        let old = write_schema("struct Player { wallet: PublicKey }\n");
+        let new = write_schema("struct Player { wallet: PublicKey, level: Option<u8> }\n");
+        let old_spec = old.path().display().to_string();
+
+        assert!(run_diff(&old_spec, new.path(), "text").is_ok());
+        assert!(run_diff(&old_spec, new.path(), "json").is_ok());
+        assert!(run_diff("missing.lumos", new.path(), "text").is_err());
+    }
+
+    #[test]
+    fn bug_report_redacts_only_schemas_that_parse() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let output = dir.path().join("report.zip");
+        let schema = write_schema("// internal\nstruct Treasury { reserve: u64 }\n");
+
+        assert!(run_bug_report(schema.path(), Some(&output), true, &[]).is_ok());
+        let zip = fs::read(&output).unwrap();
+        let text = String::from_utf8_lossy(&zip);
+        assert!(text.contains("struct Type1 { field_1: u64 }"));
+        assert!(!text.contains("Treasury") && !text.contains("internal"));
+
+        // Broken schemas are bundled as written, but can't be redacted
+        let broken = write_schema("struct Treasury { reserve: }\n");
+        assert!(run_bug_report(broken.path(), Some(&output), false, &[]).is_ok());
+        assert!(run_bug_report(broken.path(), Some(&output), true, &[]).is_err());
+    }
+
+    #[test]
+    fn grep_searches_schemas_outside_build_output() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let root = dir.path();
+        fs::create_dir_all(root.join("programs/vault")).unwrap();
+        fs::create_dir_all(root.join("target")).unwrap();
+        fs::create_dir_all(root.join(".lumos")).unwrap();
+        let schema = "struct Vault { authority: PublicKey }\n";
+        fs::write(root.join("programs/vault/vault.lumos"), schema).unwrap();
+        fs::write(root.join("target/vault.lumos"), schema).unwrap();
+        fs::write(root.join(".lumos/vault.lumos"), schema).unwrap();
+        fs::write(root.join("notes.txt"), schema).unwrap();
+
+        let mut files = Vec::new();
+        lumos_sources(root, &mut files).unwrap();
+        assert_eq!(files, [root.join("programs/vault/vault.lumos")]);
+
+        assert!(run_grep("type:PublicKey", &[root.to_path_buf()], "text").is_ok());
+        assert!(run_grep("is:vault", &[root.to_path_buf()], "text").is_err());
+    }
+
+    #[test]
+    fn parse_budget_spec_accepts_both_forms() {
+        assert_eq!(
+            parse_budget_spec("UserAccount=100_000"),
+            Ok(("UserAccount".to_string(), 100_000))
+        );
+        assert_eq!(
+            parse_budget_spec("UserAccount x 250"),
+            Ok(("UserAccount".to_string(), 250))
+        );
+        assert!(parse_budget_spec("UserAccount").is_err());
+        assert!(parse_budget_spec("UserAccount=lots").is_err());
+    }
+
+    #[test]
+    fn sync_corpus_file_never_overwrites_and_dedups() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        fs::write(dir.path().join("crash-abc"), [9, 9, 9]).unwrap();
+        fs::write(dir.path().join("player_minimal"), [1, 2, 3]).unwrap();
+
+        // Identical content under another name is a duplicate
+        assert_eq!(
+            sync_corpus_file(dir.path(), "copy", &[9, 9, 9]).unwrap(),
+            None
+        );
+
+        // Taken name with new content gets a hash suffix
+        let path = sync_corpus_file(dir.path(), "player_minimal", &[4, 5])
+            .unwrap()
+            .expect("new file");
+        assert_ne!(path, dir.path().join("player_minimal"));
+        assert_eq!(
+            fs::read(dir.path().join("player_minimal")).unwrap(),
+            [1, 2, 3]
+        );
+        assert_eq!(fs::read(path).unwrap(), [4, 5]);
+    }
+
+    #[test]
+    fn detect_fuzz_crate_reads_package_or_workspace_member() {
+        let root = tempfile::tempdir().expect("temp dir");
+        let program = root.path().join("programs/vault");
+        fs::create_dir_all(&program).unwrap();
+        fs::create_dir_all(root.path().join("fuzz")).unwrap();
+        fs::write(
+            root.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\"programs/vault\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            program.join("Cargo.toml"),
+            "[package]\nname = \"vault-program\"\n",
+        )
+        .unwrap();
+        let schema = program.join("schema.lumos");
+        fs::write(&schema, "").unwrap();
+
+        let fuzz_dir = root.path().join("fuzz");
+        let mut config = FuzzConfig::default();
+        detect_fuzz_crate(&mut config, &fuzz_dir, &schema);
+        assert_eq!(config.crate_name(), "vault-program");
+        assert_eq!(config.crate_path, "../programs/vault");
+
+        // A package manifest at the crate path is used directly
+        let mut config = FuzzConfig {
+            crate_path: "../programs/vault".to_string(),
+            ..FuzzConfig::default()
+        };
+        detect_fuzz_crate(&mut config, &fuzz_dir, &schema);
+        assert_eq!(config.crate_name(), "vault-program");
+        assert_eq!(config.crate_path, "../programs/vault");
+    }
+
+    #[test]
+    fn audit_lifecycle_section_lists_declared_accounts() {
+        let source = r#"
+            #[solana]
+            #[account]
+            #[init(create_vault)]
+            #[init_space]
+            #[close(close_vault)]
+            struct Vault { owner: PublicKey, amount: u64 }
+
+            #[solana]
+            #[account]
+            struct Plain { owner: PublicKey }
+        "#;
+        let ir = transform_to_ir(parse_lumos_file(source).unwrap()).unwrap();
+
+        let section = audit_lifecycle_section(&ir);
+        assert!(section.contains("| `Vault` | `create_vault` | 48 | `close_vault` | — |"));
+        assert!(!section.contains("Plain"));
+        assert!(audit_lifecycle_section(&ir[1..]).is_empty());
     }
 }
@@ -0,0 +1,258 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Project validation passes from `lumos.toml`
+//!
+//! `lumos validate` and `lumos generate` run the passes listed in the
+//! `[validation]` section after the built-in checks, in order:
+//!
+//! ```toml
+//! [validation]
+//! passes = ["pda-seeds", "./scripts/check-versions.py"]
+//! ```
+//!
+//! A bare name runs the `lumos-<name>` executable on `PATH`, like an
+//! external subcommand; anything else is a path relative to `lumos.toml`.
+//! A pass reads the plugin input JSON (see [`crate::plugin`]) on stdin and
+//! writes its findings to stdout:
+//!
+//! ```json
+//! { "diagnostics": [{ "level": "error", "type": "Vault", "field": "owner", "message": "..." }] }
+//! ```
+//!
+//! `level` is `error` (the default) or `warning`; `type` and `field` are
+//! optional. Printing nothing reports nothing, and a pass that exits with a
+//! failure without reporting anything is an error.
+
+use crate::plugin;
+use anyhow::{bail, Context, Result};
+use lumos_core::ir::TypeDefinition;
+use lumos_core::validation::{Diagnostics, Level, Location, PassManager, ValidationPass};
+use serde::Deserialize;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// An executable run as a validation pass
+pub struct ExternalPass {
+    /// Name as listed in `lumos.toml`
+    name: String,
+
+    /// Executable to run
+    program: PathBuf,
+
+    /// Schema the IR comes from, passed on to the executable
+    schema: PathBuf,
+}
+
+/// What a pass writes to stdout
+#[derive(Deserialize)]
+struct PassOutput {
+    #[serde(default)]
+    diagnostics: Vec<Reported>,
+}
+
+/// One diagnostic in a pass's output
+#[derive(Deserialize)]
+struct Reported {
+    #[serde(default)]
+    level: Option<String>,
+
+    #[serde(default, rename = "type")]
+    type_name: Option<String>,
+
+    #[serde(default)]
+    field: Option<String>,
+
+    message: String,
+}
+
+impl ValidationPass for ExternalPass {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, ir: &[TypeDefinition], ctx: &mut Diagnostics) {
+        if let Err(e) = self.check(ir, ctx) {
+            ctx.error(None, format!("{:#}", e));
+        }
+    }
+}
+
+impl ExternalPass {
+    fn check(&self, ir: &[TypeDefinition], ctx: &mut Diagnostics) -> Result<()> {
+        let input = plugin::input_json(&self.schema, ir)?;
+        let mut child = Command::new(&self.program)
+            .env("LUMOS_VERSION", env!("CARGO_PKG_VERSION"))
+            .env("LUMOS_SCHEMA", &self.schema)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run {}", self.program.display()))?;
+
+        // Write from another thread so a pass answering before reading can't deadlock
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = std::thread::spawn(move || match stdin.write_all(input.as_bytes()) {
+            // A pass may exit without reading its input
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e),
+            _ => Ok(()),
+        });
+        let output = child
+            .wait_with_output()
+            .with_context(|| format!("Failed to wait for {}", self.program.display()))?;
+        writer
+            .join()
+            .expect("stdin writer panicked")
+            .context("Failed to write IR to the pass")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let reported = match stdout.trim() {
+            "" => Vec::new(),
+            json => {
+                serde_json::from_str::<PassOutput>(json)
+                    .context("Pass output is not a {\"diagnostics\": [...]} document")?
+                    .diagnostics
+            }
+        };
+        if reported.is_empty() && !output.status.success() {
+            bail!(
+                "{} failed ({}): {}",
+                self.program.display(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        for diagnostic in reported {
+            let level = match diagnostic.level.as_deref() {
+                None => Level::Error,
+                Some(name) => Level::parse(name).with_context(|| {
+                    format!("Unknown level '{}'; use \"error\" or \"warning\"", name)
+                })?,
+            };
+            let location = diagnostic.type_name.map(|type_name| Location {
+                type_name,
+                field_name: diagnostic.field,
+            });
+            ctx.push(level, location, diagnostic.message);
+        }
+        Ok(())
+    }
+}
+
+/// The passes of `[validation]` in `config_path`, for `schema_path`
+pub fn load(config_path: &Path, schema_path: &Path, passes: &[String]) -> Result<PassManager> {
+    let dir = config_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+
+    let mut manager = PassManager::new();
+    for pass in passes {
+        let is_path = pass.contains('/') || pass.contains('\\');
+        let program = match is_path {
+            true => dir.join(pass),
+            false => plugin::find_plugin(pass, &path_var).with_context(|| {
+                format!(
+                    "Validation pass `{}` in {} not found: no `lumos-{}` executable on PATH",
+                    pass,
+                    config_path.display(),
+                    pass
+                )
+            })?,
+        };
+        manager.add(ExternalPass {
+            name: pass.clone(),
+            program,
+            schema: schema_path.to_path_buf(),
+        });
+    }
+    Ok(manager)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use lumos_core::parser::parse_lumos_file;
+    use lumos_core::transform::transform_to_ir;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn write_pass(dir: &Path, name: &str, script: &str) {
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn runs_executables_and_collects_their_diagnostics() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let config = dir.join("lumos.toml");
+        let schema = dir.join("vault.lumos");
+        let ir = transform_to_ir(parse_lumos_file("struct Vault { owner: PublicKey }").unwrap())
+            .unwrap();
+
+        write_pass(
+            dir,
+            "owners.sh",
+            r#"grep -q '"name":"Vault"' && echo '{"diagnostics": [
+                {"level": "warning", "type": "Vault", "field": "owner", "message": "Prefer authority"},
+                {"message": "Missing version"}
+            ]}'"#,
+        );
+        write_pass(dir, "silent.sh", "cat > /dev/null");
+        write_pass(dir, "broken.sh", "echo 'no config' >&2; exit 2");
+        write_pass(
+            dir,
+            "bad-level.sh",
+            r#"echo '{"diagnostics": [{"level": "fatal", "message": "x"}]}'"#,
+        );
+
+        let passes = [
+            "./owners.sh",
+            "./silent.sh",
+            "./broken.sh",
+            "./bad-level.sh",
+        ]
+        .map(String::from);
+        let manager = load(&config, &schema, &passes).unwrap();
+        let diagnostics = manager.run(&ir);
+        let reported: Vec<String> = diagnostics
+            .iter()
+            .map(|d| {
+                let location = d.location.as_ref().map(ToString::to_string);
+                format!(
+                    "{} {} {:?} {}",
+                    d.pass,
+                    d.level.as_str(),
+                    location,
+                    d.message
+                )
+            })
+            .collect();
+
+        assert_eq!(reported.len(), 4, "{:#?}", reported);
+        assert_eq!(
+            reported[0],
+            "./owners.sh warning Some(\"Vault::owner\") Prefer authority"
+        );
+        assert_eq!(reported[1], "./owners.sh error None Missing version");
+        assert!(reported[2].starts_with("./broken.sh error None"));
+        assert!(reported[2].ends_with("no config"), "{}", reported[2]);
+        assert!(
+            reported[3].contains("Unknown level 'fatal'"),
+            "{}",
+            reported[3]
+        );
+
+        let error = load(&config, &schema, &["no-such-pass".to_string()])
+            .err()
+            .unwrap();
+        assert!(error
+            .to_string()
+            .contains("no `lumos-no-such-pass` executable"));
+    }
+}
@@ -0,0 +1,391 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Output path handling
+//!
+//! Windows paths come in several shapes: drive paths (`C:\out`), UNC shares
+//! (`\\server\share\out`) and verbatim paths (`\\?\C:\out`), which is what
+//! `canonicalize` returns. Deep monorepo layouts can also exceed `MAX_PATH`,
+//! which the Win32 file APIs only accept in verbatim form.
+//!
+//! The conversions here work on path strings so they behave the same (and are
+//! tested) on every platform; they are only applied when running on Windows.
+//!
+//! Writability is checked against the file system's access rules
+//! (`faccessat` on Unix, the directory's security descriptor on Windows) so
+//! read-only mounts and file watchers never see a probe file.
+
+use std::borrow::Cow;
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Longest path Windows accepts for a directory without the `\\?\` prefix
+const MAX_DIR_PATH: usize = 248;
+
+/// Backup location for `path`: `.backup` appended to the full file name
+///
+/// `types.rs` becomes `types.rs.backup` and `Makefile` becomes
+/// `Makefile.backup`.
+pub fn backup_path(path: &Path) -> PathBuf {
+    match path.file_name() {
+        Some(name) => {
+            let mut name = name.to_os_string();
+            name.push(".backup");
+            path.with_file_name(name)
+        }
+        None => path.with_extension("backup"),
+    }
+}
+
+/// Directory whose permissions decide whether `path` can be written
+///
+/// The path itself when it exists, otherwise its parent (`.` for a bare
+/// relative name such as `out`). Returns `None` for a root or prefix with no
+/// parent.
+pub fn output_dir(path: &Path) -> Option<&Path> {
+    if path.exists() {
+        return Some(path);
+    }
+    match path.parent() {
+        Some(parent) if parent.as_os_str().is_empty() => Some(Path::new(".")),
+        parent => parent,
+    }
+}
+
+/// Path in a form the file APIs accept even beyond `MAX_PATH`
+///
+/// On Windows, long absolute or relative paths are converted to their `\\?\`
+/// verbatim form. Elsewhere, and for short paths, `path` is returned as is.
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    if !cfg!(windows) || path.as_os_str().len() < MAX_DIR_PATH {
+        return Cow::Borrowed(path);
+    }
+
+    let absolute = match path.is_absolute() {
+        true => Cow::Borrowed(path),
+        false => match env::current_dir() {
+            Ok(cwd) => Cow::Owned(cwd.join(path)),
+            Err(_) => return Cow::Borrowed(path),
+        },
+    };
+
+    match absolute.to_str().and_then(to_verbatim) {
+        Some(verbatim) => Cow::Owned(PathBuf::from(verbatim)),
+        None => Cow::Borrowed(path),
+    }
+}
+
+/// Path for messages, without the `\\?\` prefix `canonicalize` adds on Windows
+pub fn display_path(path: &Path) -> String {
+    let text = path.display().to_string();
+    if !cfg!(windows) {
+        return text;
+    }
+    strip_verbatim(&text).into_owned()
+}
+
+/// Check that files can be created in `dir`
+///
+/// Uses the platform's access check and only falls back to creating (and
+/// immediately removing) a uniquely named probe file when that check is
+/// unavailable, e.g. on file systems that do not support it.
+pub fn check_writable(dir: &Path) -> io::Result<()> {
+    match access_check(dir) {
+        Some(result) => result,
+        None => probe_writable(dir),
+    }
+}
+
+/// Ask the kernel whether the effective user may create files in `dir`
+///
+/// Returns `None` when the answer is unknown and a probe is needed.
+#[cfg(unix)]
+fn access_check(dir: &Path) -> Option<io::Result<()>> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(dir.as_os_str().as_bytes()).ok()?;
+    // SAFETY: `path` is a valid NUL-terminated string for the whole call
+    let status = unsafe {
+        libc::faccessat(
+            libc::AT_FDCWD,
+            path.as_ptr(),
+            libc::W_OK | libc::X_OK,
+            libc::AT_EACCESS,
+        )
+    };
+    if status == 0 {
+        return Some(Ok(()));
+    }
+
+    let error = io::Error::last_os_error();
+    match error.raw_os_error() {
+        Some(libc::EACCES | libc::EPERM | libc::EROFS) => Some(Err(error)),
+        // EINVAL/ENOSYS: AT_EACCESS or faccessat unsupported here
+        _ => None,
+    }
+}
+
+/// Evaluate the directory's DACL against the process token
+///
+/// Returns `None` when the security descriptor cannot be read (e.g. on FAT
+/// volumes or some network shares) and a probe is needed.
+#[cfg(windows)]
+fn access_check(dir: &Path) -> Option<io::Result<()>> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{CloseHandle, GENERIC_WRITE, HANDLE};
+    use windows_sys::Win32::Security::{
+        AccessCheck, DuplicateToken, GetFileSecurityW, MapGenericMask, SecurityImpersonation,
+        DACL_SECURITY_INFORMATION, GENERIC_MAPPING, GROUP_SECURITY_INFORMATION,
+        OWNER_SECURITY_INFORMATION, PRIVILEGE_SET, TOKEN_DUPLICATE, TOKEN_QUERY,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_ALL_ACCESS, FILE_GENERIC_EXECUTE, FILE_GENERIC_READ, FILE_GENERIC_WRITE,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    let path = long_path(dir);
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let info = OWNER_SECURITY_INFORMATION | GROUP_SECURITY_INFORMATION | DACL_SECURITY_INFORMATION;
+
+    // SAFETY: every pointer passed below outlives the call it is passed to,
+    // buffer lengths match their allocations, and handles are closed once
+    unsafe {
+        let mut needed = 0u32;
+        GetFileSecurityW(wide.as_ptr(), info, std::ptr::null_mut(), 0, &mut needed);
+        if needed == 0 {
+            return None;
+        }
+        // u64 elements keep the descriptor pointer-aligned
+        let mut descriptor = vec![0u64; (needed as usize).div_ceil(8)];
+        if GetFileSecurityW(
+            wide.as_ptr(),
+            info,
+            descriptor.as_mut_ptr().cast(),
+            needed,
+            &mut needed,
+        ) == 0
+        {
+            return None;
+        }
+
+        let mut process_token: HANDLE = std::ptr::null_mut();
+        if OpenProcessToken(
+            GetCurrentProcess(),
+            TOKEN_DUPLICATE | TOKEN_QUERY,
+            &mut process_token,
+        ) == 0
+        {
+            return None;
+        }
+        let mut token: HANDLE = std::ptr::null_mut();
+        let duplicated = DuplicateToken(process_token, SecurityImpersonation, &mut token);
+        CloseHandle(process_token);
+        if duplicated == 0 {
+            return None;
+        }
+
+        let mapping = GENERIC_MAPPING {
+            GenericRead: FILE_GENERIC_READ,
+            GenericWrite: FILE_GENERIC_WRITE,
+            GenericExecute: FILE_GENERIC_EXECUTE,
+            GenericAll: FILE_ALL_ACCESS,
+        };
+        let mut desired = GENERIC_WRITE;
+        MapGenericMask(&mut desired, &mapping);
+
+        let mut privileges: PRIVILEGE_SET = std::mem::zeroed();
+        let mut privileges_len = std::mem::size_of::<PRIVILEGE_SET>() as u32;
+        let mut granted = 0u32;
+        let mut allowed = 0;
+        let checked = AccessCheck(
+            descriptor.as_mut_ptr().cast(),
+            token,
+            desired,
+            &mapping,
+            &mut privileges,
+            &mut privileges_len,
+            &mut granted,
+            &mut allowed,
+        );
+        CloseHandle(token);
+
+        if checked == 0 {
+            return None;
+        }
+        if allowed == 0 {
+            return Some(Err(io::Error::from(io::ErrorKind::PermissionDenied)));
+        }
+        Some(Ok(()))
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn access_check(_dir: &Path) -> Option<io::Result<()>> {
+    None
+}
+
+/// Create and remove a uniquely named file in `dir`
+fn probe_writable(dir: &Path) -> io::Result<()> {
+    // Removed when dropped, including on early return
+    tempfile::Builder::new()
+        .prefix(".lumos-write-check-")
+        .tempfile_in(long_path(dir))
+        .map(drop)
+}
+
+/// Convert an absolute Windows path to verbatim (`\\?\`) form
+///
+/// Separators are normalized to `\` and `.`/`..` segments are resolved, since
+/// Windows does neither for verbatim paths. Returns `None` for relative and
+/// drive-relative (`C:out`) paths.
+fn to_verbatim(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return Some(path.to_string());
+    }
+
+    let path = path.replace('/', "\\");
+    let (prefix, rest) = if let Some(unc) = path.strip_prefix(r"\\") {
+        let mut parts = unc.splitn(3, '\\');
+        let server = parts.next().filter(|s| !s.is_empty())?;
+        let share = parts.next().filter(|s| !s.is_empty())?;
+        (
+            format!(r"\\?\UNC\{}\{}", server, share),
+            parts.next().unwrap_or(""),
+        )
+    } else {
+        let drive = path.get(..2).filter(|d| is_drive(d))?;
+        let rest = path[2..].strip_prefix('\\')?;
+        (format!(r"\\?\{}", drive), rest)
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in rest.split('\\') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    Some(format!("{}\\{}", prefix, segments.join("\\")))
+}
+
+/// Remove the verbatim prefix from drive and UNC paths
+///
+/// Other verbatim paths (such as volume GUIDs) have no plain form and are
+/// returned unchanged.
+fn strip_verbatim(path: &str) -> Cow<'_, str> {
+    if let Some(unc) = path.strip_prefix(r"\\?\UNC\") {
+        return Cow::Owned(format!(r"\\{}", unc));
+    }
+    match path.strip_prefix(r"\\?\") {
+        Some(rest) if rest.get(..2).is_some_and(is_drive) => Cow::Borrowed(rest),
+        _ => Cow::Borrowed(path),
+    }
+}
+
+/// Whether `prefix` is a drive designator such as `C:`
+fn is_drive(prefix: &str) -> bool {
+    let bytes = prefix.as_bytes();
+    bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backup_appends_to_file_name() {
+        assert_eq!(
+            backup_path(Path::new("out/generated.rs")),
+            Path::new("out/generated.rs.backup")
+        );
+        assert_eq!(
+            backup_path(Path::new("Makefile")),
+            Path::new("Makefile.backup")
+        );
+        assert_eq!(
+            backup_path(Path::new("v1.2/types.d.ts")),
+            Path::new("v1.2/types.d.ts.backup")
+        );
+    }
+
+    #[test]
+    fn bare_relative_output_checks_current_dir() {
+        assert_eq!(
+            output_dir(Path::new("lumos-missing-output-dir")),
+            Some(Path::new("."))
+        );
+    }
+
+    #[test]
+    fn writability_checks_leave_no_files_behind() {
+        let dir = tempfile::tempdir().unwrap();
+
+        check_writable(dir.path()).unwrap();
+        probe_writable(dir.path()).unwrap();
+
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_directory_is_not_writable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Permission bits do not restrict root
+        // SAFETY: geteuid has no preconditions
+        if unsafe { libc::geteuid() } == 0 {
+            return;
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o555)).unwrap();
+
+        let result = check_writable(dir.path());
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn converts_windows_paths_to_verbatim() {
+        assert_eq!(
+            to_verbatim(r"C:\work\app\..\generated").as_deref(),
+            Some(r"\\?\C:\work\generated")
+        );
+        assert_eq!(
+            to_verbatim("D:/repo/./programs/vault").as_deref(),
+            Some(r"\\?\D:\repo\programs\vault")
+        );
+        assert_eq!(
+            to_verbatim(r"\\build\share\repo\out").as_deref(),
+            Some(r"\\?\UNC\build\share\repo\out")
+        );
+        assert_eq!(
+            to_verbatim(r"\\?\C:\already\verbatim").as_deref(),
+            Some(r"\\?\C:\already\verbatim")
+        );
+        assert_eq!(to_verbatim(r"relative\out"), None);
+        assert_eq!(to_verbatim("C:drive-relative"), None);
+        assert_eq!(to_verbatim(r"\\server-only"), None);
+    }
+
+    #[test]
+    fn strips_verbatim_prefix_for_display() {
+        assert_eq!(strip_verbatim(r"\\?\C:\work\out"), r"C:\work\out");
+        assert_eq!(
+            strip_verbatim(r"\\?\UNC\build\share\out"),
+            r"\\build\share\out"
+        );
+        assert_eq!(
+            strip_verbatim(r"\\?\Volume{1234}\out"),
+            r"\\?\Volume{1234}\out"
+        );
+        assert_eq!(strip_verbatim("/home/dev/out"), "/home/dev/out");
+    }
+}
@@ -0,0 +1,189 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! External subcommands
+//!
+//! Like cargo, `lumos <name> [args]` runs a `lumos-<name>` executable found on
+//! `PATH` when `<name>` is not a built-in command, passing all arguments
+//! through. If one of the arguments is a `.lumos` schema, it is parsed and its
+//! IR is written to the plugin's stdin as JSON:
+//!
+//! ```json
+//! { "lumos_version": "0.1.0", "schema": "schema.lumos", "types": [...] }
+//! ```
+//!
+//! The plugin's exit code becomes the exit code of `lumos`.
+
+use anyhow::{bail, Context, Result};
+use lumos_core::ir::TypeDefinition;
+use lumos_core::resolver;
+use lumos_core::transform::transform_to_ir;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Prefix of plugin executables
+const PREFIX: &str = "lumos-";
+
+/// Run the plugin for an unknown subcommand and exit with its status
+pub fn run_external(args: &[OsString]) -> Result<()> {
+    let Some((name, args)) = args.split_first() else {
+        bail!("No subcommand given");
+    };
+    let name = name.to_string_lossy();
+
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    let Some(plugin) = find_plugin(&name, &path_var) else {
+        bail!(
+            "No such command: `{}`\n\nNo built-in command or `{}{}` executable on PATH matches it; run `lumos --help` for built-in commands",
+            name,
+            PREFIX,
+            name
+        );
+    };
+
+    let code = run_plugin(&plugin, args)?;
+    std::process::exit(code);
+}
+
+/// Locate `lumos-<name>` in the directories of a `PATH`-style list
+pub fn find_plugin(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    let file_name = format!("{}{}{}", PREFIX, name, std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(&file_name))
+        .find(|candidate| is_executable(candidate))
+}
+
+/// Run a plugin, feeding it the IR of the first schema argument, and return its exit code
+pub fn run_plugin(plugin: &Path, args: &[OsString]) -> Result<i32> {
+    let schema = args.iter().map(Path::new).find(|arg| is_schema(arg));
+    let input = schema.map(ir_json).transpose()?;
+
+    let mut command = Command::new(plugin);
+    command
+        .args(args)
+        .env("LUMOS_VERSION", env!("CARGO_PKG_VERSION"))
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        });
+    if let Some(schema) = schema {
+        command.env("LUMOS_SCHEMA", schema);
+    }
+
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Failed to run plugin: {}", plugin.display()))?;
+
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // A plugin may exit without reading its input
+        match stdin.write_all(input.as_bytes()) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+                return Err(e).context("Failed to write IR to plugin");
+            }
+            _ => {}
+        }
+    }
+
+    let status = child
+        .wait()
+        .with_context(|| format!("Failed to wait for plugin: {}", plugin.display()))?;
+    Ok(status.code().unwrap_or(1))
+}
+
+/// Parse a schema and serialize its IR in the plugin input format
+pub fn ir_json(schema: &Path) -> Result<String> {
+    let ast = resolver::load(schema)
+        .with_context(|| format!("Failed to parse schema: {}", schema.display()))?;
+    let types: Vec<TypeDefinition> =
+        transform_to_ir(ast).with_context(|| "Failed to transform AST to IR")?;
+    input_json(schema, &types)
+}
+
+/// Serialize the IR of `schema` in the plugin input format
+pub fn input_json(schema: &Path, types: &[TypeDefinition]) -> Result<String> {
+    let input = serde_json::json!({
+        "lumos_version": env!("CARGO_PKG_VERSION"),
+        "schema": schema,
+        "types": types,
+    });
+    Ok(serde_json::to_string(&input)?)
+}
+
+fn is_schema(arg: &Path) -> bool {
+    arg.extension().is_some_and(|ext| ext == "lumos") && arg.is_file()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn write_plugin(dir: &Path, name: &str, script: &str) -> PathBuf {
+        let path = dir.join(format!("{}{}", PREFIX, name));
+        fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn finds_executable_plugins_on_path() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        fs::write(first.path().join("lumos-docs"), "not executable").unwrap();
+        let plugin = write_plugin(second.path(), "docs", "exit 0");
+
+        let path_var = std::env::join_paths([first.path(), second.path()]).unwrap();
+        assert_eq!(find_plugin("docs", &path_var), Some(plugin));
+        assert_eq!(find_plugin("missing", &path_var), None);
+    }
+
+    #[test]
+    fn pipes_ir_json_to_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema = dir.path().join("game.lumos");
+        fs::write(
+            &schema,
+            "struct Player { name: String, scores: [u64] }\nenum State { Active, Moved(u8) }",
+        )
+        .unwrap();
+        let received = dir.path().join("received.json");
+        let plugin = write_plugin(
+            dir.path(),
+            "capture",
+            &format!("cat > '{}'\nexit 3", received.display()),
+        );
+
+        let code = run_plugin(&plugin, &[schema.clone().into(), "--flag".into()]).unwrap();
+        assert_eq!(code, 3);
+
+        let input: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&received).unwrap()).unwrap();
+        assert_eq!(input["lumos_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(input["schema"], schema.to_str().unwrap());
+
+        let player = &input["types"][0];
+        assert_eq!(player["kind"], "struct");
+        assert_eq!(player["name"], "Player");
+        assert_eq!(player["fields"][1]["type_info"]["kind"], "array");
+        assert_eq!(player["fields"][1]["type_info"]["type"]["type"], "u64");
+        assert_eq!(input["types"][1]["variants"][1]["kind"], "tuple");
+
+        let types: Vec<TypeDefinition> = serde_json::from_value(input["types"].clone()).unwrap();
+        assert_eq!(types.len(), 2);
+    }
+}
@@ -0,0 +1,191 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Progress and timing for long-running commands
+//!
+//! Each stage shows a spinner on stderr while it runs and is then reported as
+//! a status line with its duration. Batches of targets get a progress bar.
+//! Bars are only drawn on a terminal; `--quiet` hides bars and status lines
+//! alike, leaving warnings and errors.
+
+use colored::ColoredString;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Finished stages, while [`record_stages`] is in effect
+static STAGES: Mutex<Option<Vec<Stage>>> = Mutex::new(None);
+
+/// A finished stage and how long it took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage {
+    /// Status verb, e.g. `Generating`
+    pub verb: String,
+
+    /// What the stage worked on, e.g. `Rust for 3 types`
+    pub message: String,
+
+    /// Time the stage took
+    pub duration: Duration,
+}
+
+/// Apply the `--quiet` setting for this process
+pub fn init(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether progress and status output is suppressed
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Start keeping the stages that finish successfully, for [`take_stages`]
+pub fn record_stages() {
+    if let Ok(mut stages) = STAGES.lock() {
+        *stages = Some(Vec::new());
+    }
+}
+
+/// Stages finished since [`record_stages`], which stops recording
+pub fn take_stages() -> Vec<Stage> {
+    STAGES
+        .lock()
+        .ok()
+        .and_then(|mut stages| stages.take())
+        .unwrap_or_default()
+}
+
+/// Progress reporter for one command run
+pub struct Progress {
+    started: Instant,
+    quiet: bool,
+}
+
+impl Progress {
+    /// Start timing a run
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            quiet: quiet(),
+        }
+    }
+
+    /// Also suppress output when `quiet` is true (e.g. in dry-run mode)
+    pub fn quiet_if(mut self, quiet: bool) -> Self {
+        self.quiet |= quiet;
+        self
+    }
+
+    /// Print a `{:>12} message` status line unless quiet
+    pub fn status(&self, verb: ColoredString, message: impl Display) {
+        if !self.quiet {
+            outln!("{:>12} {}", verb, message);
+        }
+    }
+
+    /// Run `work` under a spinner, then report it with its duration
+    pub fn stage<T, E>(
+        &self,
+        verb: ColoredString,
+        message: impl Display,
+        work: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        let message = message.to_string();
+        let spinner = self.spinner(&verb, &message);
+        let started = Instant::now();
+
+        let result = work();
+        spinner.finish_and_clear();
+
+        if result.is_ok() {
+            let duration = started.elapsed();
+            if let Some(stages) = STAGES.lock().as_deref_mut().ok().and_then(Option::as_mut) {
+                let verb: &str = &verb;
+                stages.push(Stage {
+                    verb: verb.to_string(),
+                    message: message.clone(),
+                    duration,
+                });
+            }
+            self.status(verb, format!("{} ({})", message, format_duration(duration)));
+        }
+        result
+    }
+
+    /// Progress bar over `len` targets, hidden when quiet or not on a terminal
+    pub fn bar(&self, len: usize, verb: &str) -> ProgressBar {
+        if self.quiet {
+            return ProgressBar::hidden();
+        }
+
+        let bar = ProgressBar::new(len as u64);
+        let chars = if crate::style::ascii() {
+            "=> "
+        } else {
+            "█▉▊▋▌▍▎▏ "
+        };
+        bar.set_style(
+            ProgressStyle::with_template("{prefix:>12} [{bar:30}] {pos}/{len} {msg}")
+                .expect("valid progress template")
+                .progress_chars(chars),
+        );
+        bar.set_prefix(verb.to_string());
+        bar
+    }
+
+    /// Time since the run started, formatted for status lines
+    pub fn elapsed(&self) -> String {
+        format_duration(self.started.elapsed())
+    }
+
+    fn spinner(&self, verb: &str, message: &str) -> ProgressBar {
+        if self.quiet {
+            return ProgressBar::hidden();
+        }
+
+        let spinner = ProgressBar::new_spinner();
+        let ticks: &[&str] = if crate::style::ascii() {
+            &["-", "\\", "|", "/", " "]
+        } else {
+            &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏", " "]
+        };
+        spinner.set_style(
+            ProgressStyle::with_template("{prefix:>12} {spinner} {msg} {elapsed}")
+                .expect("valid spinner template")
+                .tick_strings(ticks),
+        );
+        spinner.set_prefix(verb.to_string());
+        spinner.set_message(message.to_string());
+        spinner.enable_steady_tick(Duration::from_millis(100));
+        spinner
+    }
+}
+
+/// `850ms`, `1.42s` or `2m 05s`
+pub fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if millis < 1000 {
+        format!("{}ms", millis)
+    } else if millis < 60_000 {
+        format!("{:.2}s", duration.as_secs_f64())
+    } else {
+        let secs = duration.as_secs();
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_durations_by_magnitude() {
+        assert_eq!(format_duration(Duration::from_millis(850)), "850ms");
+        assert_eq!(format_duration(Duration::from_millis(1420)), "1.42s");
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m 05s");
+    }
+}
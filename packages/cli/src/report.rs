@@ -0,0 +1,592 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Combined analysis report (`lumos report`)
+//!
+//! Runs size, security, and audit analyses in one pass and renders the
+//! results as a single JSON or HTML document.
+
+use crate::i18n::{self, tr, tr_args};
+use lumos_core::audit_generator::{AuditGenerator, ChecklistItem};
+use lumos_core::criticality::Criticality;
+use lumos_core::ir::TypeDefinition;
+use lumos_core::linter::{Level, Lint, LintConfig, Linter};
+use lumos_core::security_analyzer::{SecurityAnalyzer, SecurityFinding, Severity};
+use lumos_core::size_calculator::{AccountSize, SizeCalculator, SizeInfo};
+use serde_json::{json, Value};
+
+/// Results of every analysis run against a schema
+pub struct Report {
+    /// Schema the report was generated from
+    pub schema: String,
+
+    /// Number of type definitions in the schema
+    pub type_count: usize,
+
+    /// Account size analysis
+    pub sizes: Vec<AccountSize>,
+
+    /// Security findings
+    pub findings: Vec<SecurityFinding>,
+
+    /// Audit checklist items
+    pub checklist: Vec<ChecklistItem>,
+
+    /// Style and best-practice lints
+    pub lints: Vec<Lint>,
+}
+
+impl Report {
+    /// Run all analyses over the given type definitions, linting with the
+    /// rule levels of `lint`
+    pub fn build(
+        schema: &str,
+        type_defs: &[TypeDefinition],
+        strict: bool,
+        lint: LintConfig,
+    ) -> Self {
+        let sizes = SizeCalculator::new(type_defs).calculate_all();
+
+        let mut analyzer = SecurityAnalyzer::new(type_defs);
+        if strict {
+            analyzer = analyzer.with_strict_mode();
+        }
+        let findings = analyzer.analyze();
+
+        let checklist = AuditGenerator::new(type_defs).generate();
+        let lints = Linter::new(type_defs).with_config(lint).lint();
+
+        Self {
+            schema: schema.to_string(),
+            type_count: type_defs.len(),
+            sizes,
+            findings,
+            checklist,
+            lints,
+        }
+    }
+
+    /// Count security findings with the given severity
+    pub fn count_findings(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+
+    /// Whether the report contains anything that should block a release
+    pub fn has_blocking_issues(&self) -> bool {
+        self.count_findings(Severity::Critical) > 0
+            || self.sizes.iter().any(|s| !s.warnings.is_empty())
+            || self.lints.iter().any(|lint| lint.level == Level::Deny)
+    }
+
+    /// Render the report as structured JSON
+    pub fn to_json(&self) -> Value {
+        json!({
+            "schema": self.schema,
+            "generated_at": chrono::Local::now().format("%Y-%m-%d").to_string(),
+            "lumos_version": env!("CARGO_PKG_VERSION"),
+            "summary": {
+                "types": self.type_count,
+                "accounts": self.sizes.iter().filter(|s| s.is_account).count(),
+                "size_warnings": self.sizes.iter().map(|s| s.warnings.len()).sum::<usize>(),
+                "critical_findings": self.count_findings(Severity::Critical),
+                "warnings": self.count_findings(Severity::Warning),
+                "informational": self.count_findings(Severity::Info),
+                "checklist_items": self.checklist.len(),
+                "lints": self.lints.len(),
+            },
+            "sections": {
+                "sizes": sizes_to_json(&self.sizes),
+                "security": findings_to_json(&self.findings),
+                "audit": checklist_to_json(&self.checklist),
+                "lint": lints_to_json(&self.lints),
+            },
+        })
+    }
+
+    /// Render the report as a self-contained HTML page
+    ///
+    /// Tables can be sorted by clicking a column header and filtered with the
+    /// search box above them. Styles and scripts are inlined so the page works
+    /// offline without the CLI.
+    pub fn to_html(&self) -> String {
+        let localizer = i18n::get();
+        let mut html = String::new();
+
+        html.push_str(&format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n",
+            localizer.lang()
+        ));
+        html.push_str("<meta charset=\"utf-8\">\n");
+        html.push_str(&format!(
+            "<title>{} - {}</title>\n",
+            escape_html(&tr("report-title")),
+            escape_html(&self.schema)
+        ));
+        html.push_str("<style>\n");
+        html.push_str(HTML_STYLE);
+        html.push_str("</style>\n</head>\n<body>\n");
+
+        html.push_str(&format!("<h1>{}</h1>\n", escape_html(&tr("report-title"))));
+        html.push_str(&format!(
+            "<p>{} <code>{}</code></p>\n",
+            escape_html(&tr("report-schema")),
+            escape_html(&self.schema)
+        ));
+
+        // Summary
+        html.push_str(&format!(
+            "<h2>{}</h2>\n<ul>\n",
+            escape_html(&tr("report-summary"))
+        ));
+        let summary = [
+            tr_args("report-type-count", &[("count", self.type_count.into())]),
+            tr_args(
+                "report-finding-counts",
+                &[
+                    ("critical", self.count_findings(Severity::Critical).into()),
+                    ("warnings", self.count_findings(Severity::Warning).into()),
+                    ("info", self.count_findings(Severity::Info).into()),
+                ],
+            ),
+            tr_args(
+                "report-checklist-count",
+                &[("count", self.checklist.len().into())],
+            ),
+            tr_args("report-lint-count", &[("count", self.lints.len().into())]),
+        ];
+        for line in summary {
+            html.push_str(&format!("<li>{}</li>\n", escape_html(&line)));
+        }
+        html.push_str("</ul>\n");
+
+        // Sizes
+        let size_rows: Vec<Vec<HtmlCell>> = self
+            .sizes
+            .iter()
+            .map(|size| {
+                vec![
+                    HtmlCell::text(&size.name),
+                    HtmlCell::text(if size.is_account {
+                        tr("report-yes")
+                    } else {
+                        tr("report-no")
+                    }),
+                    HtmlCell::sorted(
+                        describe_size(&size.total_bytes),
+                        size.total_bytes.min_bytes(),
+                    ),
+                    HtmlCell::text(format!("{:.8}", size.rent_sol)),
+                    HtmlCell::text(size.warnings.join("; ")),
+                ]
+            })
+            .collect();
+        push_html_table(
+            &mut html,
+            &tr("report-sizes-title"),
+            "sizes",
+            &[
+                tr("report-col-type"),
+                tr("report-col-account"),
+                tr("report-col-bytes"),
+                tr("report-col-rent"),
+                tr("report-col-warnings"),
+            ],
+            &size_rows,
+        );
+
+        // Security findings
+        let finding_rows: Vec<Vec<HtmlCell>> = self
+            .findings
+            .iter()
+            .map(|finding| {
+                vec![
+                    HtmlCell::sorted(
+                        finding.severity.as_str(),
+                        Criticality::from(&finding.severity) as usize,
+                    ),
+                    HtmlCell::text(localizer.rule(&finding.vulnerability)),
+                    HtmlCell::text(finding.location.to_string()),
+                    HtmlCell::text(&finding.message),
+                    HtmlCell::text(localizer.suggestion(finding)),
+                ]
+            })
+            .collect();
+        push_html_table(
+            &mut html,
+            &tr("report-findings-title"),
+            "findings",
+            &[
+                tr("report-col-severity"),
+                tr("report-col-rule"),
+                tr("report-col-location"),
+                tr("report-col-message"),
+                tr("report-col-suggestion"),
+            ],
+            &finding_rows,
+        );
+
+        // Audit checklist
+        let checklist_rows: Vec<Vec<HtmlCell>> = self
+            .checklist
+            .iter()
+            .map(|item| {
+                let (text, explanation) = localizer.checklist(item);
+                vec![
+                    HtmlCell::sorted(
+                        item.priority.as_str(),
+                        Criticality::from(&item.priority) as usize,
+                    ),
+                    HtmlCell::text(localizer.category(&item.category)),
+                    HtmlCell::text(text),
+                    HtmlCell::text(match &item.docs {
+                        Some(docs) => format!("{} — {}", item.context, docs),
+                        None => item.context.clone(),
+                    }),
+                    HtmlCell::text(explanation),
+                ]
+            })
+            .collect();
+        push_html_table(
+            &mut html,
+            &tr("report-checklist-title"),
+            "checklist",
+            &[
+                tr("report-col-priority"),
+                tr("report-col-category"),
+                tr("report-col-check"),
+                tr("report-col-context"),
+                tr("report-col-explanation"),
+            ],
+            &checklist_rows,
+        );
+
+        // Lints
+        let lint_rows: Vec<Vec<HtmlCell>> = self
+            .lints
+            .iter()
+            .map(|lint| {
+                vec![
+                    HtmlCell::sorted(lint.level.as_str(), lint.level as usize),
+                    HtmlCell::text(lint.rule),
+                    HtmlCell::text(lint.location.to_string()),
+                    HtmlCell::text(&lint.message),
+                    HtmlCell::text(&lint.suggestion),
+                ]
+            })
+            .collect();
+        push_html_table(
+            &mut html,
+            &tr("report-lints-title"),
+            "lints",
+            &[
+                tr("report-col-level"),
+                tr("report-col-rule"),
+                tr("report-col-location"),
+                tr("report-col-message"),
+                tr("report-col-suggestion"),
+            ],
+            &lint_rows,
+        );
+
+        html.push_str("<script>\n");
+        html.push_str(HTML_SCRIPT);
+        html.push_str("</script>\n");
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// Inline stylesheet for the HTML report
+const HTML_STYLE: &str = r#"body { font-family: sans-serif; margin: 2rem; color: #222; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 2rem; }
+th, td { border: 1px solid #ccc; padding: 0.4rem 0.6rem; text-align: left; vertical-align: top; }
+th { background: #f3f3f3; cursor: pointer; user-select: none; }
+th[data-dir="asc"]::after { content: " \25B2"; }
+th[data-dir="desc"]::after { content: " \25BC"; }
+input.filter { margin-bottom: 0.5rem; padding: 0.3rem; width: 20rem; }
+"#;
+
+/// Inline script adding column sorting and text filtering to report tables
+const HTML_SCRIPT: &str = r#"document.querySelectorAll("input.filter").forEach(function (input) {
+  input.addEventListener("input", function () {
+    var needle = input.value.toLowerCase();
+    var table = document.getElementById(input.dataset.table);
+    table.querySelectorAll("tbody tr").forEach(function (row) {
+      row.style.display = row.textContent.toLowerCase().indexOf(needle) === -1 ? "none" : "";
+    });
+  });
+});
+
+document.querySelectorAll("table.sortable th").forEach(function (th) {
+  th.addEventListener("click", function () {
+    var table = th.closest("table");
+    var column = Array.prototype.indexOf.call(th.parentNode.children, th);
+    var dir = th.dataset.dir === "asc" ? "desc" : "asc";
+    table.querySelectorAll("th").forEach(function (other) { delete other.dataset.dir; });
+    th.dataset.dir = dir;
+
+    var tbody = table.querySelector("tbody");
+    var rows = Array.prototype.slice.call(tbody.rows);
+    rows.sort(function (a, b) {
+      var x = a.cells[column], y = b.cells[column];
+      var xs = x.dataset.sort, ys = y.dataset.sort;
+      var cmp = (xs !== undefined && ys !== undefined)
+        ? Number(xs) - Number(ys)
+        : x.textContent.localeCompare(y.textContent);
+      return dir === "asc" ? cmp : -cmp;
+    });
+    rows.forEach(function (row) { tbody.appendChild(row); });
+  });
+});
+"#;
+
+/// A table cell in the HTML report
+struct HtmlCell {
+    /// Text shown in the cell
+    text: String,
+
+    /// Numeric key used for sorting instead of the text
+    sort_key: Option<usize>,
+}
+
+impl HtmlCell {
+    fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            sort_key: None,
+        }
+    }
+
+    fn sorted(text: impl Into<String>, sort_key: usize) -> Self {
+        Self {
+            text: text.into(),
+            sort_key: Some(sort_key),
+        }
+    }
+}
+
+/// Append a titled, sortable and filterable table to the HTML report
+fn push_html_table(
+    html: &mut String,
+    title: &str,
+    id: &str,
+    headers: &[String],
+    rows: &[Vec<HtmlCell>],
+) {
+    html.push_str(&format!("<h2>{}</h2>\n", escape_html(title)));
+    let placeholder = tr_args("report-filter", &[("table", title.to_lowercase().into())]);
+    html.push_str(&format!(
+        "<input class=\"filter\" type=\"search\" placeholder=\"{}\" data-table=\"{}\">\n",
+        escape_html(&placeholder),
+        id
+    ));
+    html.push_str(&format!("<table class=\"sortable\" id=\"{}\">\n", id));
+
+    html.push_str("<thead><tr>");
+    for header in headers {
+        html.push_str(&format!("<th>{}</th>", escape_html(header)));
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            match cell.sort_key {
+                Some(key) => html.push_str(&format!(
+                    "<td data-sort=\"{}\">{}</td>",
+                    key,
+                    escape_html(&cell.text)
+                )),
+                None => html.push_str(&format!("<td>{}</td>", escape_html(&cell.text))),
+            }
+        }
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</tbody>\n</table>\n");
+}
+
+/// Convert account sizes to JSON
+pub fn sizes_to_json(sizes: &[AccountSize]) -> Value {
+    let json_data: Vec<_> = sizes
+        .iter()
+        .map(|account| {
+            let (total_bytes, is_variable) = match &account.total_bytes {
+                SizeInfo::Fixed(bytes) => (*bytes, false),
+                SizeInfo::Variable { min, .. } => (*min, true),
+            };
+
+            json!({
+                "name": account.name,
+                "total_bytes": total_bytes,
+                "is_variable": is_variable,
+                "is_account": account.is_account,
+                "rent_sol": account.rent_sol,
+                "warnings": account.warnings,
+                "fields": account.field_breakdown.iter().map(|field| {
+                    let (bytes, var) = match &field.size {
+                        SizeInfo::Fixed(b) => (*b, false),
+                        SizeInfo::Variable { min, .. } => (*min, true),
+                    };
+                    json!({
+                        "name": field.name,
+                        "bytes": bytes,
+                        "is_variable": var,
+                        "description": field.description,
+                    })
+                }).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    Value::Array(json_data)
+}
+
+/// Convert security findings to JSON
+pub fn findings_to_json(findings: &[SecurityFinding]) -> Value {
+    let json_data: Vec<_> = findings
+        .iter()
+        .map(|finding| {
+            json!({
+                "severity": finding.severity.as_str(),
+                "criticality": Criticality::from(&finding.severity),
+                "vulnerability_type": finding.vulnerability.as_str(),
+                "location": {
+                    "type_name": finding.location.type_name,
+                    "field_name": finding.location.field_name,
+                },
+                "message": finding.message,
+                "suggestion": finding.suggestion,
+            })
+        })
+        .collect();
+
+    Value::Array(json_data)
+}
+
+/// Convert audit checklist items to JSON
+pub fn checklist_to_json(checklist: &[ChecklistItem]) -> Value {
+    let json_data: Vec<_> = checklist
+        .iter()
+        .map(|item| {
+            json!({
+                "category": item.category.as_str(),
+                "priority": item.priority.as_str(),
+                "criticality": Criticality::from(&item.priority),
+                "item": item.item,
+                "context": item.context,
+                "docs": item.docs,
+                "explanation": item.explanation,
+                "checked": false,
+            })
+        })
+        .collect();
+
+    Value::Array(json_data)
+}
+
+/// Convert lints to JSON
+pub fn lints_to_json(lints: &[Lint]) -> Value {
+    let json_data: Vec<_> = lints
+        .iter()
+        .map(|lint| {
+            json!({
+                "rule": lint.rule,
+                "level": lint.level.as_str(),
+                "location": {
+                    "type_name": lint.location.type_name,
+                    "field_name": lint.location.field_name,
+                },
+                "message": lint.message,
+                "suggestion": lint.suggestion,
+            })
+        })
+        .collect();
+
+    Value::Array(json_data)
+}
+
+/// Describe a size for display
+pub fn describe_size(size: &SizeInfo) -> String {
+    match size {
+        SizeInfo::Fixed(bytes) => bytes.to_string(),
+        SizeInfo::Variable { min, .. } => format!("{}+", min),
+    }
+}
+
+/// Escape text for inclusion in HTML
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumos_core::parser::parse_lumos_file;
+    use lumos_core::transform::transform_to_ir;
+
+    fn build_report(source: &str) -> Report {
+        let ast = parse_lumos_file(source).unwrap();
+        let ir = transform_to_ir(ast).unwrap();
+        Report::build("schema.lumos", &ir, false, LintConfig::default())
+    }
+
+    #[test]
+    fn test_report_json_has_all_sections() {
+        let report = build_report(
+            r#"
+            #[solana]
+            #[account]
+            struct Vault {
+                authority: PublicKey,
+                balance: u64,
+            }
+            "#,
+        );
+
+        let json = report.to_json();
+        assert_eq!(json["summary"]["types"], 1);
+        assert_eq!(json["summary"]["critical_findings"], 1);
+        assert!(json["sections"]["sizes"].is_array());
+        assert!(!json["sections"]["security"].as_array().unwrap().is_empty());
+        assert!(!json["sections"]["audit"].as_array().unwrap().is_empty());
+        assert!(json["sections"]["lint"].as_array().unwrap().is_empty());
+        assert!(report.has_blocking_issues());
+    }
+
+    #[test]
+    fn test_report_html_escapes_content() {
+        let report = build_report(
+            r#"
+            #[solana]
+            #[account]
+            struct Listing {
+                items: [u64],
+            }
+            "#,
+        );
+
+        let html = report.to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h2>Security Findings</h2>"));
+        assert!(html.contains("<table class=\"sortable\" id=\"findings\">"));
+        assert!(html.contains("data-sort=\""));
+        assert!(!html.contains("<script src="));
+        assert!(!html.contains("<link "));
+        assert_eq!(escape_html("<a & 'b'>"), "&lt;a &amp; &#39;b&#39;&gt;");
+    }
+}
@@ -0,0 +1,230 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Reproducibility checks for `lumos verify-reproducible`
+//!
+//! Audited releases need the committed generated files to be exactly what the
+//! schema produces. The command regenerates twice into temporary directories:
+//! files that differ between the two runs are nondeterministic, and each
+//! fresh file is compared byte-for-byte with its committed copy. When they
+//! differ, [`compare`] names the likely cause: line endings rewritten by git,
+//! timestamps added after generation, a schema other than the one pinned in
+//! the footer hash, or an edit.
+
+use lumos_core::generators::summary::SchemaSummary;
+
+/// Placeholder that replaces timestamps before comparing
+const TIMESTAMP: &str = "<timestamp>";
+
+/// Why a committed file does or does not match its regenerated copy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    /// Byte-for-byte identical
+    Identical,
+
+    /// Identical once CRLF line endings are converted to LF
+    LineEndings,
+
+    /// Identical once timestamps are normalized
+    Timestamps,
+
+    /// The footer pins a different schema hash than the current schema's
+    SchemaChanged { committed: String, current: String },
+
+    /// Same schema, different content from this line (1-based) on
+    Differs { line: usize },
+}
+
+impl Finding {
+    /// Whether the committed file is reproducible as is
+    pub fn is_identical(&self) -> bool {
+        *self == Finding::Identical
+    }
+
+    /// One-line explanation for the report
+    pub fn describe(&self) -> String {
+        match self {
+            Finding::Identical => "identical".to_string(),
+            Finding::LineEndings => {
+                "differs only in line endings (CRLF); check core.autocrlf or .gitattributes"
+                    .to_string()
+            }
+            Finding::Timestamps => "differs only in timestamps; \
+                 keep dates out of generated files or strip them before committing"
+                .to_string(),
+            Finding::SchemaChanged { committed, current } => format!(
+                "generated from schema hash {}, the schema now hashes to {}",
+                committed, current
+            ),
+            Finding::Differs { line } => format!(
+                "differs from line {} with the same schema hash; \
+                 generator settings or the LUMOS version differ, or the file was edited",
+                line
+            ),
+        }
+    }
+}
+
+/// Compare a regenerated file with its committed copy
+pub fn compare(fresh: &str, committed: &str) -> Finding {
+    if fresh == committed {
+        return Finding::Identical;
+    }
+
+    let committed_lf = committed.replace("\r\n", "\n");
+    if fresh == committed_lf {
+        return Finding::LineEndings;
+    }
+    if normalize_timestamps(fresh) == normalize_timestamps(&committed_lf) {
+        return Finding::Timestamps;
+    }
+
+    if let (Some(fresh_summary), Some(committed_summary)) = (
+        SchemaSummary::parse(fresh),
+        SchemaSummary::parse(&committed_lf),
+    ) {
+        if fresh_summary.hash != committed_summary.hash {
+            return Finding::SchemaChanged {
+                committed: committed_summary.hash,
+                current: fresh_summary.hash,
+            };
+        }
+    }
+
+    Finding::Differs {
+        line: first_difference(fresh, &committed_lf),
+    }
+}
+
+/// First line (1-based) where `a` and `b` differ
+pub fn first_difference(a: &str, b: &str) -> usize {
+    let mut a_lines = a.lines();
+    let mut b_lines = b.lines();
+    let mut line = 1;
+    while a_lines.next() == b_lines.next() {
+        line += 1;
+    }
+    line
+}
+
+/// Replace `YYYY-MM-DD` dates, with an optional `THH:MM[:SS[.frac]]` or
+/// ` HH:MM[:SS]` time and `Z`/offset suffix, by a placeholder
+pub fn normalize_timestamps(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut normalized = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let boundary = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+        match boundary.then(|| timestamp_len(&bytes[i..])).flatten() {
+            Some(len) => {
+                normalized.push_str(&text[copied..i]);
+                normalized.push_str(TIMESTAMP);
+                i += len;
+                copied = i;
+            }
+            None => i += 1,
+        }
+    }
+    normalized.push_str(&text[copied..]);
+    normalized
+}
+
+/// Length of the timestamp at the start of `bytes`, if there is one
+fn timestamp_len(bytes: &[u8]) -> Option<usize> {
+    // `pattern` uses `d` for a digit; anything else must match exactly
+    let matches = |at: usize, pattern: &str| {
+        let pattern = pattern.as_bytes();
+        bytes.len() >= at + pattern.len()
+            && pattern.iter().enumerate().all(|(i, p)| match p {
+                b'd' => bytes[at + i].is_ascii_digit(),
+                p => bytes[at + i] == *p,
+            })
+    };
+
+    if !matches(0, "dddd-dd-dd") {
+        return None;
+    }
+    let mut len = 10;
+    if matches(len, "Tdd:dd") || matches(len, " dd:dd") {
+        len += 6;
+        if matches(len, ":dd") {
+            len += 3;
+            if matches(len, ".d") {
+                len += 1;
+                while len < bytes.len() && bytes[len].is_ascii_digit() {
+                    len += 1;
+                }
+            }
+        }
+        if matches(len, "Z") {
+            len += 1;
+        } else if matches(len, "+dd:dd") || matches(len, "-dd:dd") {
+            len += 6;
+        }
+    }
+    match bytes.get(len) {
+        Some(next) if next.is_ascii_alphanumeric() => None,
+        _ => Some(len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explains_why_committed_files_differ() {
+        let fresh = "// Auto-generated by LUMOS\nstruct A;\n";
+
+        assert_eq!(compare(fresh, fresh), Finding::Identical);
+        assert_eq!(
+            compare(fresh, &fresh.replace('\n', "\r\n")),
+            Finding::LineEndings
+        );
+        assert_eq!(
+            compare(
+                "// Generated 2025-01-02T10:00:00Z\nstruct A;\n",
+                "// Generated 2025-03-04T11:30:15Z\nstruct A;\n"
+            ),
+            Finding::Timestamps
+        );
+        assert_eq!(
+            compare(fresh, "// Auto-generated by LUMOS\nstruct B;\n"),
+            Finding::Differs { line: 2 }
+        );
+        assert_eq!(
+            compare(fresh, "// Auto-generated by LUMOS\nstruct A;\nstruct B;\n"),
+            Finding::Differs { line: 3 }
+        );
+
+        let footer = |hash| {
+            format!(
+                "// LUMOS schema summary: 0 types, 0 fixed bytes, hash {}\n",
+                hash
+            )
+        };
+        assert_eq!(
+            compare(&footer("bbbb"), &footer("aaaa")),
+            Finding::SchemaChanged {
+                committed: "aaaa".to_string(),
+                current: "bbbb".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn normalizes_dates_and_times() {
+        assert_eq!(
+            normalize_timestamps("on 2025-06-01, at 2025-06-01 12:30:00+02:00."),
+            "on <timestamp>, at <timestamp>."
+        );
+        assert_eq!(
+            normalize_timestamps("2025-06-01T12:30:00.123Z"),
+            "<timestamp>"
+        );
+        // Version numbers and longer digit runs are left alone
+        assert_eq!(normalize_timestamps("v12025-06-011"), "v12025-06-011");
+        assert_eq!(normalize_timestamps("Ü 2025-06-01"), "Ü <timestamp>");
+    }
+}
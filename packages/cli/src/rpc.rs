@@ -0,0 +1,169 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Solana JSON-RPC over `curl`
+//!
+//! Only the handful of read-only calls the audit commands need. Requests go
+//! through the `curl` binary, which every CI image and workstation already
+//! has, rather than an HTTP and TLS stack compiled into the CLI.
+
+use anyhow::{Context, Result};
+use lumos_core::encoding::{decode_base64, encode_base64};
+use serde_json::{json, Value};
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// `getMultipleAccounts` accepts at most this many addresses per request
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// RPC endpoint of a cluster moniker; anything else is used as the URL
+pub fn cluster_url(url: &str) -> &str {
+    match url {
+        "mainnet" | "mainnet-beta" => "https://api.mainnet-beta.solana.com",
+        "devnet" => "https://api.devnet.solana.com",
+        "testnet" => "https://api.testnet.solana.com",
+        "localnet" | "localhost" => "http://127.0.0.1:8899",
+        url => url,
+    }
+}
+
+/// A JSON-RPC endpoint
+pub struct Rpc<'a> {
+    url: &'a str,
+}
+
+impl<'a> Rpc<'a> {
+    /// Client for a URL or cluster moniker
+    pub fn new(url: &'a str) -> Self {
+        Self {
+            url: cluster_url(url),
+        }
+    }
+
+    /// Call `method` and return its `result`
+    pub fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+
+        let mut child = match Command::new("curl")
+            .args(["-sS", "-X", "POST", "-H", "Content-Type: application/json"])
+            .args(["--data-binary", "@-", self.url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                anyhow::bail!("curl not found; it is needed to reach {}", self.url)
+            }
+            Err(e) => return Err(e).with_context(|| "Failed to run curl"),
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(body.to_string().as_bytes())
+                .with_context(|| "Failed to send the RPC request to curl")?;
+        }
+        let output = child
+            .wait_with_output()
+            .with_context(|| "Failed to run curl")?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} request to {} failed: {}",
+                method,
+                self.url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let response: Value = serde_json::from_slice(&output.stdout).with_context(|| {
+            format!(
+                "{} returned a response that is not JSON: {}",
+                method,
+                String::from_utf8_lossy(&output.stdout).trim()
+            )
+        })?;
+
+        if let Some(error) = response.get("error") {
+            anyhow::bail!("{} failed: {}", method, error);
+        }
+        response
+            .get("result")
+            .cloned()
+            .with_context(|| format!("{} returned no result", method))
+    }
+
+    /// Addresses of the program's accounts whose data starts with `prefix`
+    ///
+    /// Data is sliced to zero bytes, so listing large programs stays cheap.
+    pub fn program_account_addresses(
+        &self,
+        program_id: &str,
+        prefix: &[u8],
+    ) -> Result<Vec<String>> {
+        let result = self.call(
+            "getProgramAccounts",
+            json!([program_id, {
+                "encoding": "base64",
+                "dataSlice": { "offset": 0, "length": 0 },
+                "filters": [{ "memcmp": { "offset": 0, "bytes": encode_base64(prefix), "encoding": "base64" } }],
+            }]),
+        )?;
+
+        let accounts = result
+            .as_array()
+            .with_context(|| "getProgramAccounts returned no account list")?;
+        accounts
+            .iter()
+            .map(|account| {
+                account["pubkey"]
+                    .as_str()
+                    .map(str::to_string)
+                    .with_context(|| "getProgramAccounts returned an account without a pubkey")
+            })
+            .collect()
+    }
+
+    /// Data of each address, `None` for accounts that do not exist
+    pub fn account_data(&self, addresses: &[String]) -> Result<Vec<Option<Vec<u8>>>> {
+        let mut data = Vec::with_capacity(addresses.len());
+
+        for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let result = self.call(
+                "getMultipleAccounts",
+                json!([chunk, { "encoding": "base64" }]),
+            )?;
+            let accounts = result["value"]
+                .as_array()
+                .with_context(|| "getMultipleAccounts returned no account list")?;
+
+            for account in accounts {
+                if account.is_null() {
+                    data.push(None);
+                    continue;
+                }
+                let encoded = account["data"][0]
+                    .as_str()
+                    .with_context(|| "getMultipleAccounts returned an account without data")?;
+                data.push(Some(decode_base64(encoded).with_context(|| {
+                    "getMultipleAccounts returned account data that is not base64"
+                })?));
+            }
+        }
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_cluster_monikers() {
+        assert_eq!(cluster_url("devnet"), "https://api.devnet.solana.com");
+        assert_eq!(
+            cluster_url("http://rpc.local:8899"),
+            "http://rpc.local:8899"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Terminal color and glyph controls
+//!
+//! `--color auto` colors output only on a terminal and honors `NO_COLOR`,
+//! `CLICOLOR` and `CLICOLOR_FORCE`. With `--ascii`, or when the terminal is
+//! not using a UTF-8 locale or code page, emoji and box-drawing characters
+//! are replaced by ASCII equivalents.
+
+use clap::ValueEnum;
+use std::borrow::Cow;
+use std::env;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// When to color terminal output
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal, unless NO_COLOR is set
+    #[default]
+    Auto,
+    /// Always color
+    Always,
+    /// Never color
+    Never,
+}
+
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// Apply the color and glyph settings for this process
+pub fn init(color: ColorChoice, ascii: bool) {
+    let enabled = match color {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => auto_color(
+            env::var("NO_COLOR").ok().as_deref(),
+            env::var("CLICOLOR_FORCE").ok().as_deref(),
+            env::var("CLICOLOR").ok().as_deref(),
+            std::io::stdout().is_terminal(),
+        ),
+    };
+    colored::control::set_override(enabled);
+
+    #[cfg(windows)]
+    if enabled {
+        let _ = colored::control::set_virtual_terminal(true);
+    }
+
+    ASCII.store(ascii || !unicode_terminal(), Ordering::Relaxed);
+}
+
+/// Whether emoji and box-drawing characters are replaced by ASCII
+pub fn ascii() -> bool {
+    ASCII.load(Ordering::Relaxed)
+}
+
+/// Replace emoji and box-drawing characters in `text` when in ASCII mode
+pub fn glyphs(text: &str) -> Cow<'_, str> {
+    if !ascii() || text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(to_ascii(text))
+}
+
+/// Print a line of terminal output, replacing glyphs in ASCII mode
+macro_rules! outln {
+    () => {
+        println!()
+    };
+    ($($arg:tt)*) => {
+        println!("{}", $crate::style::glyphs(&format!($($arg)*)))
+    };
+}
+
+/// Print a line to stderr, replacing glyphs in ASCII mode
+macro_rules! errln {
+    () => {
+        eprintln!()
+    };
+    ($($arg:tt)*) => {
+        eprintln!("{}", $crate::style::glyphs(&format!($($arg)*)))
+    };
+}
+
+/// Decide whether `--color auto` colors output
+///
+/// A non-empty `NO_COLOR` always disables color, `CLICOLOR_FORCE` enables
+/// it even when not writing to a terminal, and `CLICOLOR=0` disables it.
+fn auto_color(
+    no_color: Option<&str>,
+    clicolor_force: Option<&str>,
+    clicolor: Option<&str>,
+    is_terminal: bool,
+) -> bool {
+    if no_color.is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    if clicolor_force.is_some_and(|value| !value.is_empty() && value != "0") {
+        return true;
+    }
+    clicolor != Some("0") && is_terminal
+}
+
+/// Whether the terminal can display UTF-8 output
+#[cfg(windows)]
+fn unicode_terminal() -> bool {
+    const CP_UTF8: u32 = 65001;
+    // SAFETY: GetConsoleOutputCP takes no arguments and only reads console state
+    let code_page = unsafe { windows_sys::Win32::System::Console::GetConsoleOutputCP() };
+    // 0 means there is no console (output is redirected), so keep UTF-8
+    code_page == 0 || code_page == CP_UTF8
+}
+
+/// Whether the terminal can display UTF-8 output
+#[cfg(not(windows))]
+fn unicode_terminal() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty());
+    locale.as_deref().map_or(true, utf8_locale)
+}
+
+/// Whether a locale name such as `en_US.UTF-8` selects a UTF-8 charset
+#[cfg(not(windows))]
+fn utf8_locale(locale: &str) -> bool {
+    let locale = locale.to_lowercase();
+    locale.contains("utf-8") || locale.contains("utf8")
+}
+
+fn to_ascii(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match ascii_glyph(c) {
+            Some(replacement) => out.push_str(replacement),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// ASCII stand-in for a glyph used in terminal output
+fn ascii_glyph(c: char) -> Option<&'static str> {
+    let replacement = match c {
+        '✓' | '✅' => "OK",
+        '✗' | '❌' => "X",
+        '⚠' => "!",
+        '🚨' => "!!",
+        'ℹ' => "i",
+        '💡' => "->",
+        '🔍' => ">",
+        '📚' => "*",
+        '🔴' | '🟡' | '🟢' | '⚪' => "*",
+        '→' => "->",
+        '═' => "=",
+        '─' => "-",
+        '├' => "|",
+        '└' => "`",
+        '—' => "-",
+        // Emoji variation selector
+        '\u{FE0F}' => "",
+        _ => return None,
+    };
+    Some(replacement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_color_honors_environment() {
+        assert!(auto_color(None, None, None, true));
+        assert!(!auto_color(None, None, None, false));
+        assert!(!auto_color(Some("1"), None, None, true));
+        assert!(auto_color(Some(""), None, None, true));
+        assert!(auto_color(None, Some("1"), None, false));
+        assert!(!auto_color(Some("1"), Some("1"), None, true));
+        assert!(!auto_color(None, None, Some("0"), true));
+    }
+
+    #[test]
+    fn replaces_glyphs_with_ascii() {
+        assert_eq!(
+            to_ascii("⚠️  3 warnings ├─ a → b └─ ✓ done"),
+            "!  3 warnings |- a -> b `- OK done"
+        );
+        assert_eq!(to_ascii("análisis"), "análisis");
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn detects_utf8_locales() {
+        assert!(utf8_locale("en_US.UTF-8"));
+        assert!(utf8_locale("C.utf8"));
+        assert!(!utf8_locale("C"));
+        assert!(!utf8_locale("en_US.ISO-8859-1"));
+    }
+}
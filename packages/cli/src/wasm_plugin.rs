@@ -0,0 +1,205 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! WASM generator plugins
+//!
+//! A generator compiled to WebAssembly runs inside the CLI with no imports:
+//! it cannot touch the filesystem, network or clock, and it runs under fuel
+//! and memory limits. It receives the same IR JSON as external plugins and
+//! returns a map of files, which LUMOS writes under the output directory.
+//!
+//! A plugin module exports:
+//!
+//! - `memory`
+//! - `lumos_alloc(len: i32) -> i32`: reserve `len` bytes for the input
+//! - `lumos_generate(ptr: i32, len: i32) -> i64`: generate from the input
+//!   JSON at `ptr`, returning `(out_ptr << 32) | out_len` for a UTF-8 JSON
+//!   result in memory
+//!
+//! The result is `{"files": {"<relative path>": "<contents>", ...}}`, or
+//! `{"error": "<message>"}` when generation fails.
+//!
+//! Running plugins requires the `wasm-plugins` feature.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Instructions a plugin may execute, roughly a few seconds of work
+const FUEL: u64 = 10_000_000_000;
+
+/// Largest linear memory a plugin may grow to
+#[cfg_attr(not(feature = "wasm-plugins"), allow(dead_code))]
+const MAX_MEMORY: usize = 256 << 20;
+
+/// Result document returned by `lumos_generate`
+#[derive(Deserialize)]
+struct PluginOutput {
+    #[serde(default)]
+    files: BTreeMap<String, String>,
+
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Run a plugin on the plugin input JSON and return its files, keyed by safe relative path
+pub fn generate(plugin: &Path, input: &str) -> Result<BTreeMap<PathBuf, String>> {
+    let output = run(plugin, input, FUEL)?;
+    parse_output(&output)
+        .with_context(|| format!("Invalid output from plugin: {}", plugin.display()))
+}
+
+/// Validate a plugin's result document
+fn parse_output(output: &[u8]) -> Result<BTreeMap<PathBuf, String>> {
+    let output: PluginOutput =
+        serde_json::from_slice(output).context("Plugin result is not valid JSON")?;
+    if let Some(error) = output.error {
+        bail!("Plugin reported an error: {}", error);
+    }
+
+    output
+        .files
+        .into_iter()
+        .map(|(path, content)| Ok((relative_path(&path)?, content)))
+        .collect()
+}
+
+/// Accept only relative paths that stay inside the output directory
+fn relative_path(path: &str) -> Result<PathBuf> {
+    let mut relative = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => bail!("Plugin output path escapes the output directory: {}", path),
+        }
+    }
+    if relative.as_os_str().is_empty() {
+        bail!("Plugin output path is empty: {:?}", path);
+    }
+    Ok(relative)
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn run(plugin: &Path, input: &str, fuel: u64) -> Result<Vec<u8>> {
+    use wasmtime::{Config, Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+    struct State {
+        limits: StoreLimits,
+    }
+
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::from_file(&engine, plugin)
+        .with_context(|| format!("Failed to load plugin: {}", plugin.display()))?;
+
+    let mut store = Store::new(
+        &engine,
+        State {
+            limits: StoreLimitsBuilder::new().memory_size(MAX_MEMORY).build(),
+        },
+    );
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(fuel)?;
+
+    // No imports are provided, so plugins have no access to the host
+    let instance = Instance::new(&mut store, &module, &[])
+        .context("Failed to instantiate plugin (plugins may not import host functions)")?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .context("Plugin does not export `memory`")?;
+    let alloc = instance.get_typed_func::<i32, i32>(&mut store, "lumos_alloc")?;
+    let generate = instance.get_typed_func::<(i32, i32), i64>(&mut store, "lumos_generate")?;
+
+    let len = i32::try_from(input.len()).context("Plugin input is too large")?;
+    let ptr = alloc
+        .call(&mut store, len)
+        .context("Plugin `lumos_alloc` failed")?;
+    memory
+        .write(&mut store, ptr as u32 as usize, input.as_bytes())
+        .context("Plugin `lumos_alloc` returned an invalid pointer")?;
+
+    let packed = generate
+        .call(&mut store, (ptr, len))
+        .context("Plugin `lumos_generate` failed")? as u64;
+    let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+
+    memory
+        .data(&store)
+        .get(out_ptr..out_ptr.saturating_add(out_len))
+        .map(<[u8]>::to_vec)
+        .context("Plugin `lumos_generate` returned an out-of-bounds result")
+}
+
+#[cfg(not(feature = "wasm-plugins"))]
+fn run(plugin: &Path, _input: &str, _fuel: u64) -> Result<Vec<u8>> {
+    bail!(
+        "Cannot run {}: this build of lumos has no WASM plugin support. Reinstall with `cargo install lumos-cli --features wasm-plugins`",
+        plugin.display()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_paths_outside_output_dir() {
+        assert_eq!(
+            relative_path("./src/types.py").unwrap(),
+            Path::new("src").join("types.py")
+        );
+        assert!(relative_path("../escape.rs").is_err());
+        assert!(relative_path("src/../../escape.rs").is_err());
+        assert!(relative_path("/etc/passwd").is_err());
+        assert!(relative_path("").is_err());
+        assert!(relative_path(".").is_err());
+    }
+
+    #[test]
+    fn parses_files_and_errors() {
+        let files = parse_output(br#"{"files": {"a.txt": "A", "b/c.txt": "C"}}"#).unwrap();
+        assert_eq!(files[Path::new("a.txt")], "A");
+        assert_eq!(files[&Path::new("b").join("c.txt")], "C");
+
+        let error = parse_output(br#"{"error": "unsupported type"}"#).unwrap_err();
+        assert!(error.to_string().contains("unsupported type"));
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    #[test]
+    fn runs_wat_plugin_in_sandbox() {
+        // Echoes a fixed file map; the input is copied to offset 1024
+        let plugin = r#"
+            (module
+              (memory (export "memory") 1)
+              (data (i32.const 0) "{\"files\": {\"out.txt\": \"hello\"}}")
+              (func (export "lumos_alloc") (param i32) (result i32) i32.const 1024)
+              (func (export "lumos_generate") (param i32 i32) (result i64) i64.const 31))
+        "#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("echo.wat");
+        std::fs::write(&path, plugin).unwrap();
+
+        let files = generate(&path, r#"{"types": []}"#).unwrap();
+        assert_eq!(files[Path::new("out.txt")], "hello");
+
+        let spin = dir.path().join("spin.wat");
+        std::fs::write(
+            &spin,
+            plugin.replace("i64.const 31)", "(loop (br 0)) i64.const 0)"),
+        )
+        .unwrap();
+        assert!(run(&spin, "{}", 100_000).is_err());
+
+        let importer = dir.path().join("import.wat");
+        std::fs::write(
+            &importer,
+            r#"(module (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32))))"#,
+        )
+        .unwrap();
+        assert!(generate(&importer, "{}").is_err());
+    }
+}
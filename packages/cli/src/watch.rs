@@ -0,0 +1,207 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Change detection for watch mode (`lumos generate --watch`)
+//!
+//! A schema depends on the files it imports and on the `lumos.toml` next to
+//! it, so all of them are watched. Their directories are watched rather than
+//! the files themselves: editors that save by replacing a file would
+//! otherwise end the watch, and a `lumos.toml` or import that doesn't exist
+//! yet is picked up once it is created. Imports change as the schema is
+//! edited, so [`SchemaWatcher::refresh`] recomputes the set after each run.
+
+use anyhow::Result;
+use lumos_core::resolver;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// Watches a schema and everything it depends on
+pub struct SchemaWatcher {
+    schema_path: PathBuf,
+    watcher: RecommendedWatcher,
+    events: Receiver<notify::Event>,
+
+    /// Directories being watched
+    dirs: HashSet<PathBuf>,
+
+    /// Files the schema depends on, as named in events, to their path for display
+    files: HashMap<PathBuf, PathBuf>,
+}
+
+impl SchemaWatcher {
+    /// Start watching `schema_path`, its imports and its `lumos.toml`
+    pub fn new(schema_path: &Path) -> Result<Self> {
+        let (tx, events) = channel();
+        let watcher = notify::recommended_watcher(move |res| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        })?;
+
+        let mut watcher = Self {
+            schema_path: schema_path.to_path_buf(),
+            watcher,
+            events,
+            dirs: HashSet::new(),
+            files: HashMap::new(),
+        };
+        watcher.refresh()?;
+        Ok(watcher)
+    }
+
+    /// Pick up imports added or removed since the last refresh
+    pub fn refresh(&mut self) -> Result<()> {
+        let files: HashMap<PathBuf, PathBuf> = resolver::dependencies(&self.schema_path)
+            .into_iter()
+            .chain([crate::lumos_toml_for(&self.schema_path)])
+            .map(|file| (event_path(&file), file))
+            .collect();
+
+        let mut dirs = HashSet::new();
+        for dir in files.keys().filter_map(|file| file.parent()) {
+            // Imports of missing directories fail to resolve anyway
+            if !dir.is_dir() || dirs.contains(dir) {
+                continue;
+            }
+            if !self.dirs.contains(dir) {
+                self.watcher.watch(dir, RecursiveMode::NonRecursive)?;
+            }
+            dirs.insert(dir.to_path_buf());
+        }
+        for stale in self.dirs.difference(&dirs) {
+            let _ = self.watcher.unwatch(stale);
+        }
+
+        self.dirs = dirs;
+        self.files = files;
+        Ok(())
+    }
+
+    /// Watched files, schema and imports first, as paths for display
+    pub fn files(&self) -> Vec<&Path> {
+        let config = crate::lumos_toml_for(&self.schema_path);
+        let mut files: Vec<&Path> = self.files.values().map(PathBuf::as_path).collect();
+        files.sort_by_key(|file| (*file == config, *file != self.schema_path, *file));
+        files
+    }
+
+    /// Files changed within `timeout`, after `debounce` to let rapid changes settle
+    ///
+    /// Empty when nothing the schema depends on changed.
+    pub fn changes(&self, timeout: Duration, debounce: Duration) -> Result<Vec<PathBuf>> {
+        let event = match self.events.recv_timeout(timeout) {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => return Ok(Vec::new()),
+            Err(RecvTimeoutError::Disconnected) => anyhow::bail!("File watcher stopped"),
+        };
+
+        let mut changed = self.changed_files(&event);
+        if changed.is_empty() {
+            return Ok(changed);
+        }
+
+        // Debounce: wait a bit for multiple rapid changes
+        std::thread::sleep(debounce);
+        while let Ok(event) = self.events.try_recv() {
+            for file in self.changed_files(&event) {
+                if !changed.contains(&file) {
+                    changed.push(file);
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Watched files `event` touches
+    fn changed_files(&self, event: &notify::Event) -> Vec<PathBuf> {
+        // Reading the schema to regenerate shouldn't trigger another run
+        if event.kind.is_access() {
+            return Vec::new();
+        }
+        let mut changed = Vec::new();
+        for path in &event.paths {
+            if let Some(file) = self.files.get(path) {
+                if !changed.contains(file) {
+                    changed.push(file.clone());
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// `file` as events name it: in its canonical directory, whether or not it exists
+fn event_path(file: &Path) -> PathBuf {
+    let dir = match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    match (dir.canonicalize(), file.file_name()) {
+        (Ok(dir), Some(name)) => dir.join(name),
+        _ => file.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Instant;
+
+    /// Changes seen within a few seconds, for slow file systems
+    fn next_changes(watcher: &SchemaWatcher) -> Vec<PathBuf> {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            let changed = watcher
+                .changes(Duration::from_millis(100), Duration::from_millis(50))
+                .unwrap();
+            if !changed.is_empty() {
+                return changed;
+            }
+        }
+        Vec::new()
+    }
+
+    #[test]
+    fn watches_imports_and_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let schema = dir.join("schema.lumos");
+        let tokens = dir.join("types/tokens.lumos");
+        fs::create_dir_all(dir.join("types")).unwrap();
+        fs::write(&schema, "struct Vault { balance: u64 }\n").unwrap();
+        fs::write(&tokens, "struct Token { raw: u64 }\n").unwrap();
+
+        let mut watcher = SchemaWatcher::new(&schema).unwrap();
+        assert_eq!(watcher.files(), [schema.as_path(), &dir.join("lumos.toml")]);
+
+        // Files that aren't imported yet are ignored
+        fs::write(&tokens, "struct Token { raw: u128 }\n").unwrap();
+        assert!(watcher
+            .changes(Duration::from_millis(300), Duration::ZERO)
+            .unwrap()
+            .is_empty());
+
+        fs::write(
+            &schema,
+            "import \"types/tokens.lumos\";\nstruct Vault { token: Token }\n",
+        )
+        .unwrap();
+        assert_eq!(next_changes(&watcher), [schema.as_path()]);
+        watcher.refresh().unwrap();
+        assert_eq!(
+            watcher.files(),
+            [schema.as_path(), &tokens, &dir.join("lumos.toml")]
+        );
+
+        fs::write(&tokens, "struct Token { raw: u64 }\n").unwrap();
+        assert_eq!(next_changes(&watcher), [tokens.as_path()]);
+
+        // Created after watching started
+        fs::write(dir.join("lumos.toml"), "[rust]\n").unwrap();
+        assert_eq!(next_changes(&watcher), [dir.join("lumos.toml").as_path()]);
+    }
+}
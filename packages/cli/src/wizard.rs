@@ -0,0 +1,414 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Guided schema creation (`lumos new --interactive`)
+//!
+//! Asks for each type, whether it is an Anchor account, and its fields with
+//! their types and bounds, explaining the layout rules as it goes: account
+//! strings and lists need a maximum length so the account can be sized up
+//! front. The answers build a [`Draft`], which renders to a `.lumos` file
+//! that is checked with the real parser before anything is written.
+
+use anyhow::{Context, Result};
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme};
+use dialoguer::{Confirm, Input, Select};
+use lumos_core::formatter::format_source;
+use lumos_core::parser::parse_lumos_file;
+use lumos_core::transform::transform_to_ir;
+
+/// Field types offered by name, with a hint shown next to each
+const FIELD_TYPES: [(&str, &str); 10] = [
+    ("PublicKey", "a wallet, mint or program address"),
+    ("u64", "an amount, e.g. lamports or token units"),
+    ("i64", "a Unix timestamp"),
+    ("u32", "a count or index"),
+    ("u16", "basis points or a small count"),
+    ("u8", "a bump seed or small number"),
+    ("bool", "a flag"),
+    ("String", "text"),
+    ("[PublicKey]", "a list of addresses"),
+    ("[u64]", "a list of amounts"),
+];
+
+/// Integer types a `#[range]` can bound
+const INTEGER_TYPES: [&str; 10] = [
+    "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128",
+];
+
+/// A schema being built from answers
+#[derive(Debug, Default)]
+pub struct Draft {
+    /// Types in the order they were added
+    pub types: Vec<TypeDraft>,
+}
+
+/// A struct of the draft
+#[derive(Debug)]
+pub struct TypeDraft {
+    /// Type name, in PascalCase
+    pub name: String,
+
+    /// Whether it is stored in its own Anchor account
+    pub account: bool,
+
+    /// Fields in layout order
+    pub fields: Vec<FieldDraft>,
+}
+
+/// A field of a drafted struct
+#[derive(Debug)]
+pub struct FieldDraft {
+    /// Field name, in snake_case
+    pub name: String,
+
+    /// Type as written in the schema, e.g. `u64` or `[PublicKey]`
+    pub type_name: String,
+
+    /// Whether the field is `Option<...>`
+    pub optional: bool,
+
+    /// `#[max(n)]` of a string or list
+    pub max_len: Option<u64>,
+
+    /// `#[range(min, max)]` of an integer
+    pub range: Option<(i128, i128)>,
+}
+
+impl Draft {
+    /// The draft as a formatted `.lumos` file
+    ///
+    /// # Errors
+    ///
+    /// Fails if the rendered schema doesn't compile, e.g. because a field
+    /// names a type the draft doesn't define.
+    pub fn render(&self) -> Result<String> {
+        let mut source = String::from("// Created with `lumos new --interactive`\n");
+        for type_draft in &self.types {
+            source.push_str("\n#[solana]\n");
+            if type_draft.account {
+                source.push_str("#[account]\n");
+            }
+            source.push_str(&format!("struct {} {{\n", type_draft.name));
+            for field in &type_draft.fields {
+                if let Some(max) = field.max_len {
+                    source.push_str(&format!("    #[max({})]\n", max));
+                }
+                if let Some((min, max)) = field.range {
+                    source.push_str(&format!("    #[range({}, {})]\n", min, max));
+                }
+                let type_name = match field.optional {
+                    true => format!("Option<{}>", field.type_name),
+                    false => field.type_name.clone(),
+                };
+                source.push_str(&format!("    {}: {},\n", field.name, type_name));
+            }
+            source.push_str("}\n");
+        }
+
+        let ast = parse_lumos_file(&source).context("The drafted schema doesn't parse")?;
+        transform_to_ir(ast).context("The drafted schema isn't valid")?;
+        Ok(format_source(&source)?)
+    }
+
+    /// Whether any drafted type is an Anchor account
+    pub fn has_accounts(&self) -> bool {
+        self.types.iter().any(|type_draft| type_draft.account)
+    }
+}
+
+/// Whether a field of `type_name` is measured by `#[max]`
+fn has_length(type_name: &str) -> bool {
+    type_name == "String" || type_name.starts_with('[')
+}
+
+fn check_name(name: &str, pascal_case: bool) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid = match chars.next() {
+        Some(first) if pascal_case => {
+            first.is_ascii_uppercase() && chars.all(|c| c.is_ascii_alphanumeric())
+        }
+        Some(first) => {
+            (first.is_ascii_lowercase() || first == '_')
+                && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        }
+        None => return Ok(()),
+    };
+    match (valid, pascal_case) {
+        (true, _) => Ok(()),
+        (false, true) => Err("use PascalCase, e.g. PlayerAccount".to_string()),
+        (false, false) => Err("use snake_case, e.g. owner or created_at".to_string()),
+    }
+}
+
+/// Parse `min..max` or `min, max`
+fn parse_range(text: &str) -> Result<(i128, i128), String> {
+    let (min, max) = text
+        .split_once("..")
+        .or_else(|| text.split_once(','))
+        .ok_or("write the bounds as min..max, e.g. 0..10000")?;
+    let bound = |text: &str| {
+        text.trim()
+            .parse::<i128>()
+            .map_err(|_| format!("'{}' is not an integer", text.trim()))
+    };
+    let (min, max) = (bound(min)?, bound(max.trim_start_matches('='))?);
+    match min <= max {
+        true => Ok((min, max)),
+        false => Err(format!("{} is above {}", min, max)),
+    }
+}
+
+/// Prompt style, plain when colors are off (`--color never`, `NO_COLOR`)
+fn theme() -> Box<dyn Theme> {
+    match colored::control::SHOULD_COLORIZE.should_colorize() {
+        true => Box::new(ColorfulTheme::default()),
+        false => Box::new(SimpleTheme),
+    }
+}
+
+/// Ask for a schema until the user is done
+pub fn ask() -> Result<Draft> {
+    let theme = theme();
+    let theme = theme.as_ref();
+    let mut draft = Draft::default();
+    loop {
+        let name: String = Input::with_theme(theme)
+            .with_prompt("Type name")
+            .validate_with(|name: &String| {
+                check_name(name, true)?;
+                match draft.types.iter().any(|t| &t.name == name) {
+                    true => Err(format!("'{}' is already defined", name)),
+                    false => Ok(()),
+                }
+            })
+            .interact_text()?;
+        let account = Confirm::with_theme(theme)
+            .with_prompt(format!(
+                "Is {} stored in its own Anchor account (#[account])?",
+                name
+            ))
+            .default(true)
+            .interact()?;
+
+        let mut fields: Vec<FieldDraft> = Vec::new();
+        loop {
+            let field = ask_field(theme, &draft, account, &fields)?;
+            match field {
+                Some(field) => fields.push(field),
+                None if fields.is_empty() => {
+                    errln!("  {} needs at least one field", name);
+                }
+                None => break,
+            }
+        }
+        draft.types.push(TypeDraft {
+            name,
+            account,
+            fields,
+        });
+
+        let more = Confirm::with_theme(theme)
+            .with_prompt("Add another type?")
+            .default(false)
+            .interact()?;
+        if !more {
+            return Ok(draft);
+        }
+    }
+}
+
+/// Ask a yes/no question
+pub fn confirm(prompt: &str, default: bool) -> Result<bool> {
+    Ok(Confirm::with_theme(theme().as_ref())
+        .with_prompt(prompt)
+        .default(default)
+        .interact()?)
+}
+
+/// Ask for the program id Anchor code is generated for, `None` to skip generation
+pub fn ask_address() -> Result<Option<String>> {
+    let address: String = Input::with_theme(theme().as_ref())
+        .with_prompt("Program id for the Anchor code (empty to generate later)")
+        .allow_empty(true)
+        .interact_text()?;
+    Ok(Some(address.trim().to_string()).filter(|address| !address.is_empty()))
+}
+
+/// Ask for one field, or `None` when the user leaves the name empty
+fn ask_field(
+    theme: &dyn Theme,
+    draft: &Draft,
+    account: bool,
+    fields: &[FieldDraft],
+) -> Result<Option<FieldDraft>> {
+    let name: String = Input::with_theme(theme)
+        .with_prompt("Field name (empty to finish)")
+        .allow_empty(true)
+        .validate_with(|name: &String| {
+            check_name(name, false)?;
+            match fields.iter().any(|field| &field.name == name) {
+                true => Err(format!("'{}' is already a field", name)),
+                false => Ok(()),
+            }
+        })
+        .interact_text()?;
+    if name.is_empty() {
+        return Ok(None);
+    }
+
+    let mut items: Vec<String> = FIELD_TYPES
+        .iter()
+        .map(|(type_name, hint)| format!("{:<12} {}", type_name, hint))
+        .collect();
+    items.extend(
+        draft
+            .types
+            .iter()
+            .map(|t| format!("{:<12} defined above", t.name)),
+    );
+    items.push("Other...".to_string());
+    let choice = Select::with_theme(theme)
+        .with_prompt(format!("Type of {}", name))
+        .items(&items)
+        .default(0)
+        .interact()?;
+    let type_name = match choice {
+        i if i < FIELD_TYPES.len() => FIELD_TYPES[i].0.to_string(),
+        i if i < FIELD_TYPES.len() + draft.types.len() => {
+            draft.types[i - FIELD_TYPES.len()].name.clone()
+        }
+        _ => Input::with_theme(theme)
+            .with_prompt("Type, e.g. u128, [u8; 32] or [String]")
+            .validate_with(|type_name: &String| {
+                parse_lumos_file(&format!("struct Probe {{ field: {} }}", type_name))
+                    .map(|_| ())
+                    .map_err(|_| format!("'{}' is not a LUMOS type", type_name))
+            })
+            .interact_text()?,
+    };
+
+    let optional = Confirm::with_theme(theme)
+        .with_prompt(format!("Can {} be missing (Option)?", name))
+        .default(false)
+        .interact()?;
+
+    let max_len = if has_length(&type_name) {
+        // Anchor sizes accounts when they are created, so they can't grow
+        let prompt = match account {
+            true => format!(
+                "Maximum length of {} (accounts are sized up front, so it's required)",
+                name
+            ),
+            false => format!("Maximum length of {} (empty for none)", name),
+        };
+        let max: String = Input::with_theme(theme)
+            .with_prompt(prompt)
+            .allow_empty(!account)
+            .validate_with(|text: &String| match text.trim() {
+                "" => Ok(()),
+                text => text
+                    .parse::<u64>()
+                    .map(|_| ())
+                    .map_err(|_| "enter a whole number".to_string()),
+            })
+            .interact_text()?;
+        max.trim().parse().ok()
+    } else {
+        None
+    };
+
+    let range = if INTEGER_TYPES.contains(&type_name.as_str()) {
+        let range: String = Input::with_theme(theme)
+            .with_prompt(format!(
+                "Allowed values of {} as min..max (empty for any)",
+                name
+            ))
+            .allow_empty(true)
+            .validate_with(|text: &String| match text.trim() {
+                "" => Ok(()),
+                text => parse_range(text).map(|_| ()),
+            })
+            .interact_text()?;
+        match range.trim() {
+            "" => None,
+            text => parse_range(text).ok(),
+        }
+    } else {
+        None
+    };
+
+    Ok(Some(FieldDraft {
+        name,
+        type_name,
+        optional,
+        max_len,
+        range,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, type_name: &str) -> FieldDraft {
+        FieldDraft {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            optional: false,
+            max_len: None,
+            range: None,
+        }
+    }
+
+    #[test]
+    fn renders_a_valid_schema() {
+        let mut draft = Draft::default();
+        draft.types.push(TypeDraft {
+            name: "Player".to_string(),
+            account: true,
+            fields: vec![
+                field("wallet", "PublicKey"),
+                FieldDraft {
+                    max_len: Some(20),
+                    ..field("username", "String")
+                },
+                FieldDraft {
+                    optional: true,
+                    range: Some((0, 100)),
+                    ..field("level", "u8")
+                },
+                field("stats", "Stats"),
+            ],
+        });
+        draft.types.push(TypeDraft {
+            name: "Stats".to_string(),
+            account: false,
+            fields: vec![field("wins", "u32")],
+        });
+
+        let source = draft.render().unwrap();
+        assert!(draft.has_accounts());
+        assert!(source.contains("#[solana]\n#[account]\nstruct Player {\n    wallet: PublicKey,\n"));
+        assert!(source.contains("    #[max(20)]\n    username: String,\n"));
+        assert!(source.contains("    #[range(0, 100)]\n    level: Option<u8>,\n"));
+        assert!(source.contains("#[solana]\nstruct Stats {\n    wins: u32,\n}"));
+
+        draft.types.pop();
+        let err = draft.render().unwrap_err();
+        assert!(err.to_string().contains("isn't valid"), "{:#}", err);
+    }
+
+    #[test]
+    fn checks_answers() {
+        assert!(check_name("PlayerAccount", true).is_ok());
+        assert!(check_name("player", true).is_err());
+        assert!(check_name("created_at", false).is_ok());
+        assert!(check_name("CreatedAt", false).is_err());
+
+        assert_eq!(parse_range("0..10000"), Ok((0, 10000)));
+        assert_eq!(parse_range("-5, 5"), Ok((-5, 5)));
+        assert_eq!(parse_range("1..=3"), Ok((1, 3)));
+        assert!(parse_range("5..1").unwrap_err().contains("5 is above 1"));
+        assert!(parse_range("ten").is_err());
+    }
+}
@@ -4,7 +4,8 @@
 //! Performance benchmarks for LUMOS core components
 //!
 //! Measures performance of:
-//! - Parser (small, medium, large schemas)
+//! - Parser (small, medium, large schemas), and syn against the
+//!   `fast-parser` feature's hand-written parser
 //! - Transformer (AST → IR)
 //! - Rust generator
 //! - TypeScript generator
@@ -147,6 +148,19 @@ fn bench_parser_large(c: &mut Criterion) {
     });
 }
 
+/// The syn parser against the hand-written one (with `--features fast-parser`)
+fn bench_parser_backends(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parser_backends_large_schema");
+    group.bench_function("syn", |b| {
+        b.iter(|| parser::parse_with_syn(black_box(LARGE_SCHEMA)))
+    });
+    #[cfg(feature = "fast-parser")]
+    group.bench_function("fast", |b| {
+        b.iter(|| lumos_core::fast_parser::parse_lumos_file(black_box(LARGE_SCHEMA)))
+    });
+    group.finish();
+}
+
 // ===== Transform Benchmarks =====
 
 fn bench_transform_small(c: &mut Criterion) {
@@ -261,7 +275,8 @@ criterion_group!(
     parser_benches,
     bench_parser_small,
     bench_parser_medium,
-    bench_parser_large
+    bench_parser_large,
+    bench_parser_backends
 );
 
 criterion_group!(
@@ -0,0 +1,29 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Arbitrary text through both parsers and the formatter
+//!
+//! Nothing may panic, and when both parsers accept the input they must build
+//! the same AST, spans included. Their errors may be worded differently.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use lumos_core::{fast_parser, formatter, parser};
+
+fuzz_target!(|source: &str| {
+    let syn = parser::parse_with_syn(source);
+    let fast = fast_parser::parse_lumos_file(source);
+    if let (Ok(syn), Ok(fast)) = (&syn, &fast) {
+        assert_eq!(
+            format!("{:#?}", syn),
+            format!("{:#?}", fast),
+            "parsers disagree"
+        );
+    }
+
+    // The formatter checks its own output parses to the same schema
+    if syn.is_ok() {
+        let _ = formatter::format_source(source);
+    }
+});
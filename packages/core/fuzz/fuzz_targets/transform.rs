@@ -0,0 +1,27 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Arbitrary text through the pipeline `lumos generate` and the playground run
+//!
+//! Schemas that parse go through transform, and schemas that transform go
+//! through the Rust, TypeScript and Python generators. Errors are fine at
+//! every stage; panics are not.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use lumos_core::generators::{python, rust, typescript};
+use lumos_core::parser::parse_with_syn;
+use lumos_core::transform::transform_to_ir;
+
+fuzz_target!(|source: &str| {
+    let Ok(file) = parse_with_syn(source) else {
+        return;
+    };
+    let Ok(ir) = transform_to_ir(file) else {
+        return;
+    };
+    let _ = rust::generate_module(&ir);
+    let _ = typescript::generate_module(&ir);
+    let _ = python::generate_module(&ir);
+});
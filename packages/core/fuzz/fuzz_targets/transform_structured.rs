@@ -0,0 +1,239 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Well-formed schemas built from structured input
+//!
+//! Random text rarely gets past the parser. Here the input picks items,
+//! attributes and nested types from the schema grammar, and the schema is
+//! printed as `.lumos` source, so most cases reach transform and the
+//! generators. Names come from small pools, which makes references between
+//! types, duplicate names and cycles common.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use lumos_core::formatter::format_source;
+use lumos_core::generators::{python, rust, typescript};
+use lumos_core::parser::parse_lumos_file;
+use lumos_core::transform::transform_to_ir;
+use std::fmt::Write;
+
+const TYPE_NAMES: [&str; 4] = ["Alpha", "Beta", "Gamma", "Delta"];
+const FIELD_NAMES: [&str; 6] = ["owner", "amount", "name", "items", "state", "bump"];
+const CONST_NAMES: [&str; 2] = ["LIMIT", "SIZE"];
+const PRIMITIVES: [&str; 17] = [
+    "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "bool", "String",
+    "PublicKey", "Pubkey", "Signature", "f32", "f64",
+];
+
+/// Deepest nesting of types; deeper types become `u8`
+const MAX_DEPTH: usize = 4;
+
+#[derive(Arbitrary, Debug)]
+struct Schema {
+    items: Vec<Item>,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Item {
+    Struct {
+        name: u8,
+        attributes: Vec<TypeAttribute>,
+        fields: Vec<Field>,
+    },
+    Enum {
+        name: u8,
+        attributes: Vec<TypeAttribute>,
+        variants: Vec<Variant>,
+    },
+    Const {
+        name: u8,
+        value: u64,
+    },
+}
+
+#[derive(Arbitrary, Debug)]
+enum TypeAttribute {
+    Solana,
+    Account,
+    ZeroCopy,
+    Event,
+    Instruction,
+    Version(u8),
+    Deprecated,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Field {
+    name: u8,
+    ty: Type,
+    attributes: Vec<FieldAttribute>,
+}
+
+#[derive(Arbitrary, Debug)]
+enum FieldAttribute {
+    Key,
+    Signer,
+    Max(u16),
+    MaxConst(u8),
+    Since(u8),
+    Sensitive,
+    SkipTypescript,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Type {
+    Primitive(u8),
+    Named(u8),
+    Option(Box<Type>),
+    Vec(Box<Type>),
+    Slice(Box<Type>),
+    Array(Box<Type>, u16),
+    Map(Box<Type>, Box<Type>),
+}
+
+#[derive(Arbitrary, Debug)]
+enum Variant {
+    Unit,
+    Tuple(Vec<Type>),
+    Struct(Vec<Field>),
+}
+
+fn pick<'a>(names: &[&'a str], index: u8) -> &'a str {
+    names[usize::from(index) % names.len()]
+}
+
+impl Schema {
+    fn source(&self) -> String {
+        let mut source = String::new();
+        for item in &self.items {
+            item.write(&mut source);
+        }
+        source
+    }
+}
+
+impl Item {
+    fn write(&self, out: &mut String) {
+        match self {
+            Item::Struct {
+                name,
+                attributes,
+                fields,
+            } => {
+                write_type_attributes(out, attributes);
+                let _ = writeln!(out, "struct {} {{", pick(&TYPE_NAMES, *name));
+                for field in fields {
+                    field.write(out);
+                }
+                out.push_str("}\n");
+            }
+            Item::Enum {
+                name,
+                attributes,
+                variants,
+            } => {
+                write_type_attributes(out, attributes);
+                let _ = writeln!(out, "enum {} {{", pick(&TYPE_NAMES, *name));
+                for (index, variant) in variants.iter().enumerate() {
+                    variant.write(out, index);
+                }
+                out.push_str("}\n");
+            }
+            Item::Const { name, value } => {
+                let _ = writeln!(out, "const {}: u64 = {};", pick(&CONST_NAMES, *name), value);
+            }
+        }
+    }
+}
+
+fn write_type_attributes(out: &mut String, attributes: &[TypeAttribute]) {
+    for attribute in attributes {
+        let _ = match attribute {
+            TypeAttribute::Solana => writeln!(out, "#[solana]"),
+            TypeAttribute::Account => writeln!(out, "#[account]"),
+            TypeAttribute::ZeroCopy => writeln!(out, "#[zero_copy]"),
+            TypeAttribute::Event => writeln!(out, "#[event]"),
+            TypeAttribute::Instruction => writeln!(out, "#[instruction]"),
+            TypeAttribute::Version(version) => writeln!(out, "#[version({})]", version),
+            TypeAttribute::Deprecated => writeln!(out, "#[deprecated = \"old\"]"),
+        };
+    }
+}
+
+impl Field {
+    fn write(&self, out: &mut String) {
+        for attribute in &self.attributes {
+            let _ = match attribute {
+                FieldAttribute::Key => writeln!(out, "    #[key]"),
+                FieldAttribute::Signer => writeln!(out, "    #[signer]"),
+                FieldAttribute::Max(max) => writeln!(out, "    #[max({})]", max),
+                FieldAttribute::MaxConst(name) => {
+                    writeln!(out, "    #[max({})]", pick(&CONST_NAMES, *name))
+                }
+                FieldAttribute::Since(version) => writeln!(out, "    #[since({})]", version),
+                FieldAttribute::Sensitive => writeln!(out, "    #[sensitive]"),
+                FieldAttribute::SkipTypescript => writeln!(out, "    #[skip(typescript)]"),
+            };
+        }
+        let _ = writeln!(
+            out,
+            "    {}: {},",
+            pick(&FIELD_NAMES, self.name),
+            self.ty.source(0)
+        );
+    }
+}
+
+impl Variant {
+    fn write(&self, out: &mut String, index: usize) {
+        let _ = match self {
+            Variant::Unit => writeln!(out, "    V{},", index),
+            Variant::Tuple(types) => {
+                let types: Vec<String> = types.iter().map(|ty| ty.source(0)).collect();
+                writeln!(out, "    V{}({}),", index, types.join(", "))
+            }
+            Variant::Struct(fields) => {
+                let _ = writeln!(out, "    V{} {{", index);
+                for field in fields {
+                    field.write(out);
+                }
+                writeln!(out, "    }},")
+            }
+        };
+    }
+}
+
+impl Type {
+    fn source(&self, depth: usize) -> String {
+        if depth > MAX_DEPTH {
+            return "u8".to_string();
+        }
+        let inner = |ty: &Type| ty.source(depth + 1);
+        match self {
+            Type::Primitive(index) => pick(&PRIMITIVES, *index).to_string(),
+            Type::Named(index) => pick(&TYPE_NAMES, *index).to_string(),
+            Type::Option(ty) => format!("Option<{}>", inner(ty)),
+            Type::Vec(ty) => format!("Vec<{}>", inner(ty)),
+            Type::Slice(ty) => format!("[{}]", inner(ty)),
+            Type::Array(ty, len) => format!("[{}; {}]", inner(ty), len),
+            Type::Map(key, value) => format!("HashMap<{}, {}>", inner(key), inner(value)),
+        }
+    }
+}
+
+fuzz_target!(|schema: Schema| {
+    let source = schema.source();
+    let Ok(file) = parse_lumos_file(&source) else {
+        return;
+    };
+    let _ = format_source(&source);
+
+    let Ok(ir) = transform_to_ir(file) else {
+        return;
+    };
+    let _ = rust::generate_module(&ir);
+    let _ = typescript::generate_module(&ir);
+    let _ = python::generate_module(&ir);
+});
@@ -0,0 +1,19 @@
+#!/bin/bash
+# Seed the parse and transform corpora with the example schemas
+# Usage: bash packages/core/fuzz/seed-corpus.sh
+
+set -e
+
+FUZZ_DIR="$(cd "$(dirname "$0")" && pwd)"
+REPO_ROOT="$(cd "$FUZZ_DIR/../../.." && pwd)"
+
+for target in parse transform; do
+  mkdir -p "$FUZZ_DIR/corpus/$target"
+  find "$REPO_ROOT/examples" "$REPO_ROOT/linguist/samples" -name '*.lumos' | while read -r schema; do
+    # One file per schema, named after its path so examples don't collide
+    name="$(realpath --relative-to="$REPO_ROOT" "$schema" | tr '/' '_')"
+    cp "$schema" "$FUZZ_DIR/corpus/$target/$name"
+  done
+done
+
+echo "Seeded $(ls "$FUZZ_DIR/corpus/parse" | wc -l) schemas into corpus/parse and corpus/transform"
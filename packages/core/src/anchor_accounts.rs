@@ -0,0 +1,873 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Anchor `Accounts` context validation
+//!
+//! Cross-checks the `#[derive(Accounts)]` structs of an existing Anchor
+//! program against the schema's account types, for contexts written by hand
+//! rather than generated from `#[instruction]` structs. For every
+//! `Account<'info, T>` and `AccountLoader<'info, T>` field it checks that:
+//!
+//! - `T` is an `#[account]` type in the schema, under the same name (Anchor
+//!   derives the 8-byte discriminator from the type name)
+//! - the program's own `#[account] struct T` has the schema's field layout
+//! - `space` (or `realloc`) covers the schema size, discriminator included
+//! - the same account type is derived from the same PDA seeds everywhere
+//!
+//! `space` expressions are evaluated when they are built from integer
+//! literals, `const` items in the program source and `T::INIT_SPACE`.
+
+use crate::error::{LumosError, Result};
+use crate::ir::{TypeDefinition, TypeInfo};
+use crate::security_analyzer::Severity;
+use crate::size_calculator::{SizeCalculator, SizeInfo};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use std::collections::{BTreeMap, HashMap};
+
+/// Anchor's account discriminator size in bytes
+const DISCRIMINATOR: usize = 8;
+
+/// Kind of mismatch between the program and the schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MismatchKind {
+    /// Program account type with no counterpart in the schema
+    UnknownAccount,
+
+    /// Schema type used as an account but not marked `#[account]`
+    NotAnAccount,
+
+    /// Account type renamed in the program, so its discriminator differs
+    DiscriminatorMismatch,
+
+    /// Program struct fields differ from the schema
+    LayoutMismatch,
+
+    /// Allocated space is smaller than the schema size
+    SpaceTooSmall,
+
+    /// Allocated space leaves out the 8-byte discriminator
+    MissingDiscriminatorSpace,
+
+    /// Allocated space exceeds a fixed schema size
+    SpaceTooLarge,
+
+    /// The same account type is derived from different seeds
+    InconsistentSeeds,
+}
+
+impl MismatchKind {
+    /// Stable identifier for tooling
+    pub fn id(&self) -> &'static str {
+        match self {
+            MismatchKind::UnknownAccount => "unknown-account",
+            MismatchKind::NotAnAccount => "not-an-account",
+            MismatchKind::DiscriminatorMismatch => "discriminator-mismatch",
+            MismatchKind::LayoutMismatch => "layout-mismatch",
+            MismatchKind::SpaceTooSmall => "space-too-small",
+            MismatchKind::MissingDiscriminatorSpace => "missing-discriminator-space",
+            MismatchKind::SpaceTooLarge => "space-too-large",
+            MismatchKind::InconsistentSeeds => "inconsistent-seeds",
+        }
+    }
+
+    /// Human-readable name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MismatchKind::UnknownAccount => "Unknown Account",
+            MismatchKind::NotAnAccount => "Not An Account",
+            MismatchKind::DiscriminatorMismatch => "Discriminator Mismatch",
+            MismatchKind::LayoutMismatch => "Layout Mismatch",
+            MismatchKind::SpaceTooSmall => "Space Too Small",
+            MismatchKind::MissingDiscriminatorSpace => "Missing Discriminator Space",
+            MismatchKind::SpaceTooLarge => "Space Too Large",
+            MismatchKind::InconsistentSeeds => "Inconsistent Seeds",
+        }
+    }
+}
+
+/// A mismatch between an Anchor program and the schema
+#[derive(Debug, Clone)]
+pub struct AccountsMismatch {
+    /// Severity level
+    pub severity: Severity,
+
+    /// Kind of mismatch
+    pub kind: MismatchKind,
+
+    /// `#[derive(Accounts)]` struct, or the account struct for layout checks
+    pub context: String,
+
+    /// Field of the context (if applicable)
+    pub field: Option<String>,
+
+    /// Account type involved
+    pub account_type: String,
+
+    /// Human-readable message
+    pub message: String,
+
+    /// Suggested fix
+    pub suggestion: String,
+}
+
+/// Validator for Anchor `Accounts` contexts
+pub struct AccountsValidator<'a> {
+    /// All type definitions
+    type_defs: &'a [TypeDefinition],
+
+    /// Schema sizes by type name, discriminator included
+    sizes: HashMap<String, SizeInfo>,
+}
+
+/// Account field of a `#[derive(Accounts)]` struct
+struct ContextField {
+    context: String,
+    name: String,
+    account_type: String,
+    space: Option<syn::Expr>,
+    seeds: Option<syn::Expr>,
+}
+
+/// Items collected from the program source
+#[derive(Default)]
+struct Program {
+    /// `#[account]` structs by name
+    accounts: BTreeMap<String, syn::ItemStruct>,
+
+    /// `const` values by name, and associated consts as `Type::NAME`
+    consts: HashMap<String, syn::Expr>,
+
+    /// Account fields of all `Accounts` contexts
+    fields: Vec<ContextField>,
+}
+
+impl<'a> AccountsValidator<'a> {
+    /// Create a new validator
+    pub fn new(type_defs: &'a [TypeDefinition]) -> Self {
+        let sizes = SizeCalculator::new(type_defs)
+            .calculate_all()
+            .into_iter()
+            .map(|size| (size.name, size.total_bytes))
+            .collect();
+        Self { type_defs, sizes }
+    }
+
+    /// Validate the program's Rust sources (e.g. every file under `src/`)
+    ///
+    /// Returns [`LumosError::SchemaParse`] if a source file is not valid Rust.
+    pub fn validate(&self, sources: &[&str]) -> Result<Vec<AccountsMismatch>> {
+        let mut program = Program::default();
+        for source in sources {
+            let file = syn::parse_file(source).map_err(|e| {
+                LumosError::SchemaParse(format!("Failed to parse Rust source: {}", e), None)
+            })?;
+            program.collect(&file.items);
+        }
+
+        let mut mismatches = Vec::new();
+        for (name, item) in &program.accounts {
+            self.check_layout(name, item, &mut mismatches);
+        }
+        for field in &program.fields {
+            self.check_field(field, &program, &mut mismatches);
+        }
+        self.check_seeds(&program, &mut mismatches);
+
+        Ok(mismatches)
+    }
+
+    fn find(&self, type_name: &str) -> Option<&'a TypeDefinition> {
+        self.type_defs.iter().find(|t| t.name() == type_name)
+    }
+
+    fn is_schema_account(&self, type_def: &TypeDefinition) -> bool {
+        type_def.is_account()
+    }
+
+    /// Compare a program `#[account]` struct with the schema struct of the same name
+    fn check_layout(
+        &self,
+        name: &str,
+        item: &syn::ItemStruct,
+        mismatches: &mut Vec<AccountsMismatch>,
+    ) {
+        let Some(TypeDefinition::Struct(schema)) = self.find(name) else {
+            return;
+        };
+
+        let program_fields = rust_fields(item);
+        let schema_fields: Vec<(String, String)> = schema
+            .fields
+            .iter()
+            .map(|f| (f.name.clone(), schema_signature(&f.type_info)))
+            .collect();
+
+        let difference = program_fields
+            .iter()
+            .zip(&schema_fields)
+            .enumerate()
+            .find(|(_, (program, schema))| program != schema)
+            .map(|(index, (program, schema))| {
+                format!(
+                    "field {} is `{}: {}` in the program but `{}: {}` in the schema",
+                    index + 1,
+                    program.0,
+                    program.1,
+                    schema.0,
+                    schema.1
+                )
+            })
+            .or_else(|| {
+                (program_fields.len() != schema_fields.len()).then(|| {
+                    format!(
+                        "the program has {} fields but the schema has {}",
+                        program_fields.len(),
+                        schema_fields.len()
+                    )
+                })
+            });
+
+        if let Some(difference) = difference {
+            mismatches.push(AccountsMismatch {
+                severity: Severity::Critical,
+                kind: MismatchKind::LayoutMismatch,
+                context: name.to_string(),
+                field: None,
+                account_type: name.to_string(),
+                message: format!(
+                    "Account '{}' does not match the schema: {}",
+                    name, difference
+                ),
+                suggestion: "Update the schema or the program so both declare the same fields in the same order; Borsh layouts depend on field order".to_string(),
+            });
+        }
+    }
+
+    /// Check one `Account<'info, T>` field of an `Accounts` context
+    fn check_field(
+        &self,
+        field: &ContextField,
+        program: &Program,
+        mismatches: &mut Vec<AccountsMismatch>,
+    ) {
+        let account_type = &field.account_type;
+        let mismatch = |severity, kind, message: String, suggestion: String| AccountsMismatch {
+            severity,
+            kind,
+            context: field.context.clone(),
+            field: Some(field.name.clone()),
+            account_type: account_type.clone(),
+            message,
+            suggestion,
+        };
+
+        let Some(type_def) = self.find(account_type) else {
+            // Types defined outside the program (SPL accounts, other crates) are not schema types
+            let Some(item) = program.accounts.get(account_type) else {
+                return;
+            };
+
+            let layout = rust_fields(item);
+            let renamed = self.type_defs.iter().find(|t| match t {
+                TypeDefinition::Struct(s) => {
+                    self.is_schema_account(t)
+                        && s.fields
+                            .iter()
+                            .map(|f| (f.name.clone(), schema_signature(&f.type_info)))
+                            .eq(layout.iter().cloned())
+                }
+                TypeDefinition::Enum(_) => false,
+            });
+
+            mismatches.push(match renamed {
+                Some(schema) => mismatch(
+                    Severity::Critical,
+                    MismatchKind::DiscriminatorMismatch,
+                    format!(
+                        "Account '{}' has the layout of schema account '{}', but Anchor derives discriminators from the type name, so data written as one fails to load as the other",
+                        account_type,
+                        schema.name()
+                    ),
+                    format!(
+                        "Rename the program type to '{}' or the schema type to '{}'",
+                        schema.name(),
+                        account_type
+                    ),
+                ),
+                None => mismatch(
+                    Severity::Warning,
+                    MismatchKind::UnknownAccount,
+                    format!(
+                        "Account '{}' is defined by the program but not in the schema",
+                        account_type
+                    ),
+                    format!(
+                        "Add '{}' to the schema so clients get generated types for it",
+                        account_type
+                    ),
+                ),
+            });
+            return;
+        };
+
+        if !self.is_schema_account(type_def) {
+            mismatches.push(mismatch(
+                Severity::Critical,
+                MismatchKind::NotAnAccount,
+                format!(
+                    "'{}' is used as an account but is not marked #[account] in the schema, so schema sizes and generated code omit the discriminator",
+                    account_type
+                ),
+                format!("Mark '{}' with #[account] in the schema", account_type),
+            ));
+            return;
+        }
+
+        let Some(space) = field
+            .space
+            .as_ref()
+            .and_then(|expr| self.evaluate(expr, program, 0))
+        else {
+            return;
+        };
+        let Some(size) = self.sizes.get(account_type) else {
+            return;
+        };
+
+        let required = size.min_bytes();
+        if space + DISCRIMINATOR == required {
+            mismatches.push(mismatch(
+                Severity::Critical,
+                MismatchKind::MissingDiscriminatorSpace,
+                format!(
+                    "space = {} for '{}' leaves out the 8-byte discriminator (schema size is {} bytes)",
+                    space, account_type, required
+                ),
+                format!("Use space = 8 + {}", space),
+            ));
+        } else if space < required {
+            mismatches.push(mismatch(
+                Severity::Critical,
+                MismatchKind::SpaceTooSmall,
+                format!(
+                    "space = {} for '{}' is smaller than its schema size of {} bytes",
+                    space, account_type, required
+                ),
+                format!("Allocate at least {} bytes", required),
+            ));
+        } else if size.is_fixed() && space > required {
+            mismatches.push(mismatch(
+                Severity::Info,
+                MismatchKind::SpaceTooLarge,
+                format!(
+                    "space = {} for '{}' allocates {} bytes more than its fixed schema size of {} bytes",
+                    space,
+                    account_type,
+                    space - required,
+                    required
+                ),
+                format!(
+                    "Allocate {} bytes unless the extra space is reserved for upgrades",
+                    required
+                ),
+            ));
+        }
+    }
+
+    /// Report account types derived from differently shaped seeds
+    fn check_seeds(&self, program: &Program, mismatches: &mut Vec<AccountsMismatch>) {
+        let mut first_seen: HashMap<&str, (&ContextField, String)> = HashMap::new();
+
+        for field in &program.fields {
+            if self.find(&field.account_type).is_none() {
+                continue;
+            }
+            let Some(seeds) = &field.seeds else {
+                continue;
+            };
+            let shape = seed_shape(seeds);
+
+            let Some((first, first_shape)) = first_seen.get(field.account_type.as_str()) else {
+                first_seen.insert(&field.account_type, (field, shape));
+                continue;
+            };
+            if *first_shape != shape {
+                mismatches.push(AccountsMismatch {
+                    severity: Severity::Warning,
+                    kind: MismatchKind::InconsistentSeeds,
+                    context: field.context.clone(),
+                    field: Some(field.name.clone()),
+                    account_type: field.account_type.clone(),
+                    message: format!(
+                        "'{}' is derived from seeds {} here but from {} in {}.{}",
+                        field.account_type, shape, first_shape, first.context, first.name
+                    ),
+                    suggestion: "Derive each account type from one seed scheme, or split it into separate schema types".to_string(),
+                });
+            }
+        }
+    }
+
+    /// Evaluate a `space` expression, if it only uses integers and known constants
+    fn evaluate(&self, expr: &syn::Expr, program: &Program, depth: usize) -> Option<usize> {
+        if depth > 16 {
+            return None;
+        }
+        match expr {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(int),
+                ..
+            }) => int.base10_parse().ok(),
+            syn::Expr::Paren(inner) => self.evaluate(&inner.expr, program, depth + 1),
+            syn::Expr::Group(inner) => self.evaluate(&inner.expr, program, depth + 1),
+            syn::Expr::Cast(cast) => self.evaluate(&cast.expr, program, depth + 1),
+            syn::Expr::Binary(binary) => {
+                let left = self.evaluate(&binary.left, program, depth + 1)?;
+                let right = self.evaluate(&binary.right, program, depth + 1)?;
+                match binary.op {
+                    syn::BinOp::Add(_) => left.checked_add(right),
+                    syn::BinOp::Sub(_) => left.checked_sub(right),
+                    syn::BinOp::Mul(_) => left.checked_mul(right),
+                    syn::BinOp::Div(_) => left.checked_div(right),
+                    _ => None,
+                }
+            }
+            syn::Expr::Path(path) => {
+                let segments: Vec<String> = path
+                    .path
+                    .segments
+                    .iter()
+                    .map(|s| s.ident.to_string())
+                    .collect();
+                let name = segments[segments.len().saturating_sub(2)..].join("::");
+
+                if let Some(value) = program.consts.get(&name) {
+                    return self.evaluate(value, program, depth + 1);
+                }
+                // `#[derive(InitSpace)]` sizes exclude the discriminator
+                match segments.as_slice() {
+                    [.., type_name, constant] if constant == "INIT_SPACE" => self
+                        .sizes
+                        .get(type_name)
+                        .filter(|size| size.is_fixed())
+                        .map(|size| size.min_bytes().saturating_sub(DISCRIMINATOR)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Program {
+    fn collect(&mut self, items: &[syn::Item]) {
+        for item in items {
+            match item {
+                syn::Item::Struct(item) if has_account_attribute(&item.attrs) => {
+                    self.accounts.insert(item.ident.to_string(), item.clone());
+                }
+                syn::Item::Struct(item) if derives(&item.attrs, "Accounts") => {
+                    self.collect_context(item);
+                }
+                syn::Item::Const(item) => {
+                    self.consts
+                        .insert(item.ident.to_string(), (*item.expr).clone());
+                }
+                syn::Item::Impl(item) => {
+                    let Some(type_name) = type_name(&item.self_ty) else {
+                        continue;
+                    };
+                    for impl_item in &item.items {
+                        if let syn::ImplItem::Const(constant) = impl_item {
+                            self.consts.insert(
+                                format!("{}::{}", type_name, constant.ident),
+                                constant.expr.clone(),
+                            );
+                        }
+                    }
+                }
+                syn::Item::Mod(module) => {
+                    if let Some((_, items)) = &module.content {
+                        self.collect(items);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn collect_context(&mut self, item: &syn::ItemStruct) {
+        for field in &item.fields {
+            let (Some(ident), Some(account_type)) = (&field.ident, account_type(&field.ty)) else {
+                continue;
+            };
+
+            let constraints = field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("account"))
+                .filter_map(|attr| attr.meta.require_list().ok())
+                .flat_map(|list| constraints(list.tokens.clone()))
+                .collect::<Vec<_>>();
+            let value = |keys: &[&str]| {
+                constraints
+                    .iter()
+                    .find(|(key, _)| keys.contains(&key.as_str()))
+                    .and_then(|(_, value)| syn::parse2::<syn::Expr>(value.clone()).ok())
+            };
+
+            self.fields.push(ContextField {
+                context: item.ident.to_string(),
+                name: ident.to_string(),
+                account_type,
+                space: value(&["space", "realloc"]),
+                seeds: value(&["seeds"]),
+            });
+        }
+    }
+}
+
+/// Split `#[account(...)]` arguments into `(key, value)` pairs
+///
+/// Works on tokens rather than expressions, because constraints such as
+/// `has_one = owner @ ErrorCode::Unauthorized` are not valid Rust expressions.
+fn constraints(tokens: TokenStream) -> Vec<(String, TokenStream)> {
+    let mut parts = vec![Vec::new()];
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => parts.push(Vec::new()),
+            _ => parts.last_mut().expect("non-empty").push(token),
+        }
+    }
+
+    parts
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let eq = part
+                .iter()
+                .position(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == '='));
+            let (key, value) = match eq {
+                Some(eq) => (&part[..eq], &part[eq + 1..]),
+                None => (&part[..], &[][..]),
+            };
+            // Custom errors follow `@`
+            let value = value
+                .iter()
+                .take_while(|t| !matches!(t, TokenTree::Punct(p) if p.as_char() == '@'));
+            (
+                key.iter().map(ToString::to_string).collect(),
+                value.cloned().collect(),
+            )
+        })
+        .collect()
+}
+
+/// Seeds with literal parts kept and account-specific parts as `_`
+///
+/// `[b"player", user.key().as_ref()]` and `[b"player", authority.key().as_ref()]`
+/// have the same shape; `[b"profile", user.key().as_ref()]` does not.
+fn seed_shape(seeds: &syn::Expr) -> String {
+    let syn::Expr::Array(array) = seeds else {
+        return seeds.to_token_stream().to_string();
+    };
+
+    let parts: Vec<String> = array
+        .elems
+        .iter()
+        .map(|seed| {
+            let mut expr = seed;
+            while let syn::Expr::MethodCall(call) = expr {
+                expr = &call.receiver;
+            }
+            match expr {
+                syn::Expr::Lit(lit) => lit.to_token_stream().to_string(),
+                _ => "_".to_string(),
+            }
+        })
+        .collect();
+    format!("[{}]", parts.join(", "))
+}
+
+pub(crate) fn has_account_attribute(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("account"))
+}
+
+fn derives(attrs: &[syn::Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .any(|attr| {
+            attr.meta.require_list().is_ok_and(|list| {
+                list.tokens
+                    .clone()
+                    .into_iter()
+                    .any(|t| t.to_string() == name)
+            })
+        })
+}
+
+/// `T` of `Account<'info, T>` or `AccountLoader<'info, T>`, looking through `Box` and `Option`
+fn account_type(ty: &syn::Type) -> Option<String> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let inner = args.args.iter().find_map(|arg| match arg {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })?;
+
+    match segment.ident.to_string().as_str() {
+        "Account" | "AccountLoader" => type_name(inner),
+        "Box" | "Option" => account_type(inner),
+        _ => None,
+    }
+}
+
+/// Last path segment of a type, e.g. `Player` for `state::Player`
+fn type_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// Named fields of a program struct with schema-style type signatures
+fn rust_fields(item: &syn::ItemStruct) -> Vec<(String, String)> {
+    item.fields
+        .iter()
+        .filter_map(|field| {
+            let name = field.ident.as_ref()?.to_string();
+            Some((name, rust_signature(&field.ty)))
+        })
+        .collect()
+}
+
+/// Type signature of a schema type, comparable with [`rust_signature`]
+fn schema_signature(type_info: &TypeInfo) -> String {
+    match type_info {
+        TypeInfo::Primitive(name) if name == "Pubkey" => "PublicKey".to_string(),
+        TypeInfo::Primitive(name) | TypeInfo::UserDefined(name) => name.clone(),
+        TypeInfo::Array(inner) => format!("Vec<{}>", schema_signature(inner)),
+        TypeInfo::FixedArray(inner, len) => format!("[{}; {}]", schema_signature(inner), len),
+        TypeInfo::Map(kind, key, value) => format!(
+            "{}<{}, {}>",
+            kind.as_str(),
+            schema_signature(key),
+            schema_signature(value)
+        ),
+        TypeInfo::Option(inner) => format!("Option<{}>", schema_signature(inner)),
+    }
+}
+
+/// Type signature of a Rust field type, comparable with [`schema_signature`]
+fn rust_signature(ty: &syn::Type) -> String {
+    let fallback = || ty.to_token_stream().to_string();
+    if let syn::Type::Array(array) = ty {
+        return format!(
+            "[{}; {}]",
+            rust_signature(&array.elem),
+            array.len.to_token_stream()
+        );
+    }
+    let syn::Type::Path(path) = ty else {
+        return fallback();
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return fallback();
+    };
+
+    let name = segment.ident.to_string();
+    match &segment.arguments {
+        syn::PathArguments::None if name == "Pubkey" => "PublicKey".to_string(),
+        syn::PathArguments::None => name,
+        syn::PathArguments::AngleBracketed(args) if name == "Vec" || name == "Option" => {
+            match args.args.first() {
+                Some(syn::GenericArgument::Type(inner)) => {
+                    format!("{}<{}>", name, rust_signature(inner))
+                }
+                _ => fallback(),
+            }
+        }
+        syn::PathArguments::AngleBracketed(args) if name == "HashMap" || name == "BTreeMap" => {
+            match args.args.iter().collect::<Vec<_>>()[..] {
+                [syn::GenericArgument::Type(key), syn::GenericArgument::Type(value)] => {
+                    format!(
+                        "{}<{}, {}>",
+                        name,
+                        rust_signature(key),
+                        rust_signature(value)
+                    )
+                }
+                _ => fallback(),
+            }
+        }
+        _ => fallback(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        #[solana]
+        #[account]
+        struct Player {
+            authority: PublicKey,
+            level: u16,
+            gold: u64,
+        }
+
+        #[solana]
+        struct Stats {
+            wins: u32,
+        }
+    "#;
+
+    fn check(program: &str) -> Vec<AccountsMismatch> {
+        let ir = transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap();
+        AccountsValidator::new(&ir).validate(&[program]).unwrap()
+    }
+
+    fn kinds(mismatches: &[AccountsMismatch]) -> Vec<MismatchKind> {
+        mismatches.iter().map(|m| m.kind).collect()
+    }
+
+    #[test]
+    fn accepts_matching_program() {
+        let program = r#"
+            const PLAYER_SIZE: usize = 8 + 32 + 2 + 8;
+
+            #[account]
+            pub struct Player {
+                pub authority: Pubkey,
+                pub level: u16,
+                pub gold: u64,
+            }
+
+            #[derive(Accounts)]
+            pub struct CreatePlayer<'info> {
+                #[account(init, payer = user, space = PLAYER_SIZE, seeds = [b"player", user.key().as_ref()], bump)]
+                pub player: Account<'info, Player>,
+                #[account(mut)]
+                pub user: Signer<'info>,
+                pub token: Account<'info, TokenAccount>,
+            }
+
+            #[derive(Accounts)]
+            pub struct LevelUp<'info> {
+                #[account(mut, seeds = [b"player", authority.key().as_ref()], bump, has_one = authority @ ErrorCode::Unauthorized)]
+                pub player: Box<Account<'info, Player>>,
+                pub authority: Signer<'info>,
+            }
+        "#;
+
+        assert!(check(program).is_empty(), "{:?}", check(program));
+    }
+
+    #[test]
+    fn detects_space_mismatches() {
+        let program = r#"
+            #[derive(Accounts)]
+            pub struct A<'info> {
+                #[account(init, payer = user, space = Player::INIT_SPACE)]
+                pub missing_discriminator: Account<'info, Player>,
+                #[account(init, payer = user, space = 8 + 32)]
+                pub too_small: Account<'info, Player>,
+                #[account(init, payer = user, space = 8 + Player::LEN)]
+                pub too_large: Account<'info, Player>,
+            }
+
+            impl Player {
+                pub const LEN: usize = 100;
+            }
+        "#;
+
+        assert_eq!(
+            kinds(&check(program)),
+            vec![
+                MismatchKind::MissingDiscriminatorSpace,
+                MismatchKind::SpaceTooSmall,
+                MismatchKind::SpaceTooLarge,
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_layout_and_name_mismatches() {
+        let program = r#"
+            #[account]
+            pub struct Player {
+                pub authority: Pubkey,
+                pub level: u32,
+                pub gold: u64,
+            }
+
+            #[account]
+            pub struct Hero {
+                pub authority: Pubkey,
+                pub level: u16,
+                pub gold: u64,
+            }
+
+            #[account]
+            pub struct Guild {
+                pub name: String,
+            }
+
+            #[derive(Accounts)]
+            pub struct Play<'info> {
+                pub hero: Account<'info, Hero>,
+                pub guild: Account<'info, Guild>,
+                pub stats: Account<'info, Stats>,
+            }
+        "#;
+
+        let mismatches = check(program);
+        assert_eq!(
+            kinds(&mismatches),
+            vec![
+                MismatchKind::LayoutMismatch,
+                MismatchKind::DiscriminatorMismatch,
+                MismatchKind::UnknownAccount,
+                MismatchKind::NotAnAccount,
+            ]
+        );
+        assert!(mismatches[0].message.contains("`level: u32`"));
+    }
+
+    #[test]
+    fn detects_inconsistent_seeds() {
+        let program = r#"
+            #[derive(Accounts)]
+            pub struct A<'info> {
+                #[account(seeds = [b"player", user.key().as_ref()], bump)]
+                pub player: Account<'info, Player>,
+            }
+
+            #[derive(Accounts)]
+            pub struct B<'info> {
+                #[account(seeds = [b"profile", user.key().as_ref()], bump)]
+                pub player: Account<'info, Player>,
+            }
+        "#;
+
+        let mismatches = check(program);
+        assert_eq!(kinds(&mismatches), vec![MismatchKind::InconsistentSeeds]);
+        assert_eq!(mismatches[0].context, "B");
+    }
+
+    #[test]
+    fn rejects_invalid_rust() {
+        let ir = transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap();
+        assert!(AccountsValidator::new(&ir).validate(&["struct {"]).is_err());
+    }
+}
@@ -7,16 +7,90 @@
 //! The AST is a direct representation of the parsed syntax,
 //! before transformation into the IR.
 
+use crate::error::SourceLocation;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// A complete LUMOS file (can contain multiple items)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LumosFile {
+    /// The `schema { ... }` block at the top of the file, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<SchemaMetadata>,
+
+    /// Files imported with `import "other.lumos";`, in source order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<Import>,
+
     /// All items (structs and enums) in this file
     pub items: Vec<Item>,
+
+    /// File each item was read from, by item name, when loaded with
+    /// [`crate::resolver`]
+    #[serde(skip)]
+    pub origins: HashMap<String, PathBuf>,
+}
+
+impl LumosFile {
+    /// Location of `span`, in the file defining the item `item_name`
+    pub fn location(&self, item_name: &str, span: Option<Span>) -> Option<SourceLocation> {
+        span.map(|span| span.location(self.origins.get(item_name).map(PathBuf::as_path)))
+    }
+}
+
+/// An `import "other.lumos";` statement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Import {
+    /// Path of the imported file, relative to the importing one
+    pub path: String,
+
+    /// Line of the statement (1-indexed)
+    pub line: usize,
+}
+
+/// The `schema { name = "staking", version = "1.2.0", ... }` block
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SchemaMetadata {
+    /// Schema name (e.g., "staking")
+    pub name: String,
+
+    /// Schema version (e.g., "1.2.0")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// One-line description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// SPDX license expression (e.g., "MIT OR Apache-2.0")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+
+    /// Authors, conventionally `Name <email>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+}
+
+impl SchemaMetadata {
+    /// Lines describing the schema in the comment header of generated files
+    pub fn header_lines(&self) -> Vec<String> {
+        let mut lines = vec![match &self.version {
+            Some(version) => format!("Schema: {} {}", self.name, version),
+            None => format!("Schema: {}", self.name),
+        }];
+        lines.extend(self.description.clone());
+        if !self.authors.is_empty() {
+            lines.push(format!("Authors: {}", self.authors.join(", ")));
+        }
+        if let Some(license) = &self.license {
+            lines.push(format!("SPDX-License-Identifier: {}", license));
+        }
+        lines
+    }
 }
 
-/// An item in a LUMOS file (struct or enum)
+/// An item in a LUMOS file (struct, enum or constant)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Item {
     /// Struct definition
@@ -24,6 +98,26 @@ pub enum Item {
 
     /// Enum definition
     Enum(EnumDef),
+
+    /// Constant definition
+    Const(ConstDef),
+}
+
+/// A constant definition (e.g., `const MAX_NAME_LEN: u32 = 28;`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstDef {
+    /// Constant name (e.g., "MAX_NAME_LEN")
+    pub name: String,
+
+    /// Declared unsigned integer type (e.g., "u32")
+    pub type_name: String,
+
+    /// Value expression as written (e.g., "MAX_SEED_LEN * 2"), folded during transform
+    pub value: String,
+
+    /// Span information for error reporting
+    #[serde(skip)]
+    pub span: Option<Span>,
 }
 
 /// A struct definition
@@ -40,7 +134,7 @@ pub struct StructDef {
 
     /// Span information for error reporting
     #[serde(skip)]
-    pub span: Option<proc_macro2::Span>,
+    pub span: Option<Span>,
 }
 
 /// An enum definition
@@ -57,7 +151,7 @@ pub struct EnumDef {
 
     /// Span information for error reporting
     #[serde(skip)]
-    pub span: Option<proc_macro2::Span>,
+    pub span: Option<Span>,
 }
 
 /// An enum variant
@@ -67,7 +161,7 @@ pub enum EnumVariant {
     Unit {
         name: String,
         #[serde(skip)]
-        span: Option<proc_macro2::Span>,
+        span: Option<Span>,
     },
 
     /// Tuple variant (e.g., `PlayerJoined(PublicKey)`)
@@ -75,7 +169,7 @@ pub enum EnumVariant {
         name: String,
         types: Vec<TypeSpec>,
         #[serde(skip)]
-        span: Option<proc_macro2::Span>,
+        span: Option<Span>,
     },
 
     /// Struct variant (e.g., `Initialize { authority: PublicKey }`)
@@ -83,7 +177,7 @@ pub enum EnumVariant {
         name: String,
         fields: Vec<FieldDef>,
         #[serde(skip)]
-        span: Option<proc_macro2::Span>,
+        span: Option<Span>,
     },
 }
 
@@ -104,7 +198,7 @@ pub struct FieldDef {
 
     /// Span information for error reporting
     #[serde(skip)]
-    pub span: Option<proc_macro2::Span>,
+    pub span: Option<Span>,
 }
 
 /// Type specification
@@ -116,8 +210,19 @@ pub enum TypeSpec {
     /// Array type (e.g., `Vec<PublicKey>` in Rust)
     Array(Box<TypeSpec>),
 
+    /// Fixed-size array type (e.g., `[u8; 32]`)
+    FixedArray(Box<TypeSpec>, usize),
+
+    /// Map type as written (e.g., `HashMap<PublicKey, u64>`): collection name, key, value
+    Map(String, Box<TypeSpec>, Box<TypeSpec>),
+
     /// User-defined type (e.g., Address, CustomStruct)
     UserDefined(String),
+
+    /// Optional type nested in another one (e.g., the `Option<u32>` of `Vec<Option<u32>>`)
+    ///
+    /// A field's own `Option` is recorded in [`FieldDef::optional`] instead.
+    Option(Box<TypeSpec>),
 }
 
 /// Attribute (e.g., @solana, @account, @key, @max(100))
@@ -131,11 +236,19 @@ pub struct Attribute {
 
     /// Span information for error reporting
     #[serde(skip)]
-    pub span: Option<proc_macro2::Span>,
+    pub span: Option<Span>,
+}
+
+// Spans only locate the attribute in the source, so they do not take part in hashing
+impl std::hash::Hash for Attribute {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.value.hash(state);
+    }
 }
 
 /// Attribute value
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub enum AttributeValue {
     /// String value
     String(String),
@@ -147,6 +260,40 @@ pub enum AttributeValue {
     Bool(bool),
 }
 
+/// Start of a parsed item in the source, for error reporting
+///
+/// Unlike `proc_macro2::Span`, this is plain data, so the AST and the IR
+/// built from it can be shared across threads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// 1-based line
+    pub line: usize,
+
+    /// 0-based column, in characters
+    pub column: usize,
+}
+
+impl Span {
+    /// The 1-based source location of the span, in `file` when known
+    pub fn location(self, file: Option<&Path>) -> SourceLocation {
+        let location = SourceLocation::new(self.line, self.column + 1);
+        match file {
+            Some(file) => location.in_file(file),
+            None => location,
+        }
+    }
+}
+
+impl From<proc_macro2::Span> for Span {
+    fn from(span: proc_macro2::Span) -> Self {
+        let start = span.start();
+        Self {
+            line: start.line,
+            column: start.column,
+        }
+    }
+}
+
 impl StructDef {
     /// Check if struct has a specific attribute
     pub fn has_attribute(&self, name: &str) -> bool {
@@ -194,6 +341,35 @@ impl EnumVariant {
             EnumVariant::Struct { name, .. } => name,
         }
     }
+
+    /// Get the span of the variant name
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            EnumVariant::Unit { span, .. }
+            | EnumVariant::Tuple { span, .. }
+            | EnumVariant::Struct { span, .. } => *span,
+        }
+    }
+}
+
+impl Item {
+    /// Get the item name
+    pub fn name(&self) -> &str {
+        match self {
+            Item::Struct(s) => &s.name,
+            Item::Enum(e) => &e.name,
+            Item::Const(c) => &c.name,
+        }
+    }
+
+    /// Get the span of the item name
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Item::Struct(s) => s.span,
+            Item::Enum(e) => e.span,
+            Item::Const(c) => c.span,
+        }
+    }
 }
 
 impl FieldDef {
@@ -238,7 +414,12 @@ impl TypeSpec {
         match self {
             TypeSpec::Primitive(name) => name.clone(),
             TypeSpec::Array(inner) => format!("[{}]", inner.as_string()),
+            TypeSpec::FixedArray(inner, len) => format!("[{}; {}]", inner.as_string(), len),
+            TypeSpec::Map(kind, key, value) => {
+                format!("{}<{}, {}>", kind, key.as_string(), value.as_string())
+            }
             TypeSpec::UserDefined(name) => name.clone(),
+            TypeSpec::Option(inner) => format!("Option<{}>", inner.as_string()),
         }
     }
 }
@@ -7,10 +7,14 @@
 //! for manual code review and security audits.
 
 use crate::ir::{StructDefinition, TypeDefinition, TypeInfo};
+use crate::size_calculator::{SizeCalculator, SizeInfo};
 
 /// A single checklist item
 #[derive(Debug, Clone)]
 pub struct ChecklistItem {
+    /// Stable identifier of the check (e.g. "account-ownership")
+    pub id: &'static str,
+
     /// Category of the check
     pub category: CheckCategory,
 
@@ -23,6 +27,9 @@ pub struct ChecklistItem {
     /// Context (which account/field this applies to)
     pub context: String,
 
+    /// Doc comment of the field this applies to, if any
+    pub docs: Option<String>,
+
     /// Detailed explanation
     pub explanation: String,
 }
@@ -38,6 +45,8 @@ pub enum CheckCategory {
     DataValidation,
     RentExemption,
     Initialization,
+    AccountClosure,
+    Confidentiality,
 }
 
 /// Priority level for checklist items
@@ -86,39 +95,47 @@ impl<'a> AuditGenerator<'a> {
     fn generate_struct_checks(&self, struct_def: &StructDefinition) -> Vec<ChecklistItem> {
         let mut items = Vec::new();
 
-        let is_account = struct_def.metadata.attributes.contains(&"account".to_string());
+        let is_account = struct_def.metadata.is_account();
 
         // Account validation checks
         if is_account {
             items.push(ChecklistItem {
+                id: "account-ownership",
                 category: CheckCategory::AccountValidation,
                 priority: Priority::Critical,
                 item: "Verify account ownership (program owns the account)".to_string(),
                 context: struct_def.name.clone(),
+                docs: None,
                 explanation: "Ensure the account is owned by the program to prevent attacks where an attacker passes an account owned by a different program.".to_string(),
             });
 
             items.push(ChecklistItem {
+                id: "account-discriminator",
                 category: CheckCategory::AccountValidation,
                 priority: Priority::Critical,
                 item: "Validate account discriminator".to_string(),
                 context: struct_def.name.clone(),
+                docs: None,
                 explanation: "Anchor's 8-byte discriminator prevents type confusion attacks. Verify it's checked on deserialization.".to_string(),
             });
 
             items.push(ChecklistItem {
+                id: "account-initialized",
                 category: CheckCategory::Initialization,
                 priority: Priority::High,
                 item: "Check account is initialized before use".to_string(),
                 context: struct_def.name.clone(),
+                docs: None,
                 explanation: "Verify the account has been properly initialized and is not in an uninitialized state.".to_string(),
             });
 
             items.push(ChecklistItem {
+                id: "rent-exemption",
                 category: CheckCategory::RentExemption,
                 priority: Priority::Medium,
                 item: "Verify account has sufficient lamports for rent exemption".to_string(),
                 context: struct_def.name.clone(),
+                docs: None,
                 explanation: "Ensure the account has enough lamports to remain rent-exempt and won't be garbage collected.".to_string(),
             });
         }
@@ -128,18 +145,22 @@ impl<'a> AuditGenerator<'a> {
             // Signer checks for authority fields
             if self.is_authority_field(&field.name) {
                 items.push(ChecklistItem {
+                    id: "authority-signer",
                     category: CheckCategory::SignerChecks,
                     priority: Priority::Critical,
                     item: format!("Verify '{}' field requires signer", field.name),
                     context: format!("{}::{}", struct_def.name, field.name),
+                    docs: field.doc_comment(),
                     explanation: "Authority fields must validate that the transaction is signed by the corresponding private key.".to_string(),
                 });
 
                 items.push(ChecklistItem {
+                    id: "authority-access",
                     category: CheckCategory::AccessControl,
                     priority: Priority::Critical,
                     item: format!("Ensure only '{}' can perform privileged operations", field.name),
                     context: format!("{}::{}", struct_def.name, field.name),
+                    docs: field.doc_comment(),
                     explanation: "Implement proper access control checks to prevent unauthorized users from executing privileged functions.".to_string(),
                 });
             }
@@ -147,18 +168,22 @@ impl<'a> AuditGenerator<'a> {
             // Arithmetic safety for numeric fields
             if self.is_arithmetic_field(&field.name, &field.type_info) {
                 items.push(ChecklistItem {
+                    id: "checked-arithmetic",
                     category: CheckCategory::ArithmeticSafety,
                     priority: Priority::High,
                     item: format!("Verify '{}' uses checked arithmetic operations", field.name),
                     context: format!("{}::{}", struct_def.name, field.name),
+                    docs: field.doc_comment(),
                     explanation: "Use checked_add, checked_sub, checked_mul to prevent integer overflow/underflow vulnerabilities that could lead to loss of funds.".to_string(),
                 });
 
                 items.push(ChecklistItem {
+                    id: "value-bounds",
                     category: CheckCategory::DataValidation,
                     priority: Priority::Medium,
                     item: format!("Validate '{}' bounds and constraints", field.name),
                     context: format!("{}::{}", struct_def.name, field.name),
+                    docs: field.doc_comment(),
                     explanation: "Ensure the value is within acceptable ranges and meets business logic constraints.".to_string(),
                 });
             }
@@ -166,71 +191,217 @@ impl<'a> AuditGenerator<'a> {
             // Owner validation
             if field.name == "owner" {
                 items.push(ChecklistItem {
+                    id: "owner-matches-signer",
                     category: CheckCategory::AccessControl,
                     priority: Priority::Critical,
                     item: "Validate owner matches transaction signer for mutations".to_string(),
                     context: format!("{}::{}", struct_def.name, field.name),
+                    docs: field.doc_comment(),
                     explanation: "Before modifying account state, verify that the signer is the owner or has proper authorization.".to_string(),
                 });
             }
 
             // PublicKey validation
-            if matches!(field.type_info, TypeInfo::Primitive(ref t) if t == "PublicKey" || t == "Pubkey") {
+            if matches!(field.type_info, TypeInfo::Primitive(ref t) if t == "PublicKey" || t == "Pubkey")
+            {
                 items.push(ChecklistItem {
+                    id: "pubkey-not-default",
                     category: CheckCategory::DataValidation,
                     priority: Priority::Medium,
                     item: format!("Verify '{}' is not system program or default pubkey", field.name),
                     context: format!("{}::{}", struct_def.name, field.name),
+                    docs: field.doc_comment(),
                     explanation: "Ensure PublicKey fields are not set to default values (all zeros) or system program addresses unless intentional.".to_string(),
                 });
             }
 
-            // Vec/Array bounds checking
-            if matches!(field.type_info, TypeInfo::Array(_)) {
+            // Vec/Array/map bounds checking
+            if matches!(field.type_info, TypeInfo::Array(_) | TypeInfo::Map(..)) {
                 items.push(ChecklistItem {
+                    id: "vec-length",
                     category: CheckCategory::DataValidation,
                     priority: Priority::High,
                     item: format!("Validate '{}' length before iteration", field.name),
                     context: format!("{}::{}", struct_def.name, field.name),
+                    docs: field.doc_comment(),
                     explanation: "Check vector/array length to prevent excessive compute usage or out-of-bounds access.".to_string(),
                 });
 
                 items.push(ChecklistItem {
+                    id: "vec-max-size",
                     category: CheckCategory::ArithmeticSafety,
                     priority: Priority::Medium,
                     item: format!("Ensure '{}' max size doesn't exceed account limits", field.name),
                     context: format!("{}::{}", struct_def.name, field.name),
+                    docs: field.doc_comment(),
                     explanation: "Verify that the maximum possible size of this vector won't cause the account to exceed Solana's 10MB limit.".to_string(),
                 });
             }
 
+            // Sensitive data handling
+            if field.is_sensitive() {
+                items.push(ChecklistItem {
+                    id: "sensitive-not-exposed",
+                    category: CheckCategory::Confidentiality,
+                    priority: Priority::High,
+                    item: format!("Keep sensitive '{}' out of logs, events and indexes", field.name),
+                    context: format!("{}::{}", struct_def.name, field.name),
+                    docs: field.doc_comment(),
+                    explanation: "Account data is public on-chain; this field is marked #[sensitive] for off-chain mirrors. Log and index only redacted copies (the generated Debug impl and the TypeScript redact() helper), and never emit it in program logs or events.".to_string(),
+                });
+            }
+
             // Option type handling
             if matches!(field.type_info, TypeInfo::Option(_)) {
                 items.push(ChecklistItem {
+                    id: "optional-none",
                     category: CheckCategory::DataValidation,
                     priority: Priority::Medium,
                     item: format!("Handle None case for optional '{}' field", field.name),
                     context: format!("{}::{}", struct_def.name, field.name),
+                    docs: field.doc_comment(),
                     explanation: "Ensure program logic properly handles the case when this optional field is None.".to_string(),
                 });
             }
         }
 
+        let lifecycle = &struct_def.metadata.lifecycle;
+
+        // Declared lifecycle: creation and resizing
+        if !lifecycle.initialized_by.is_empty() {
+            items.push(ChecklistItem {
+                id: "init-instructions",
+                category: CheckCategory::Initialization,
+                priority: Priority::Critical,
+                item: format!(
+                    "Verify only {} may initialize '{}'",
+                    lifecycle.initialized_by.join(", "),
+                    struct_def.name
+                ),
+                context: struct_def.name.clone(),
+                docs: None,
+                explanation: "Create the account with `init` in the declared instructions only; `init_if_needed` or manual creation elsewhere lets an existing account be re-initialized and its state reset.".to_string(),
+            });
+        }
+
+        if lifecycle.init_space {
+            let item = match SizeCalculator::new(self.type_defs)
+                .account_size(&struct_def.name)
+                .map(|size| size.total_bytes.clone())
+            {
+                Some(SizeInfo::Fixed(bytes)) => format!(
+                    "Allocate {} bytes (including the 8-byte discriminator) when initializing '{}'",
+                    bytes, struct_def.name
+                ),
+                Some(SizeInfo::Variable { min, .. }) => format!(
+                    "Allocate at least {} bytes plus the maximum length of variable fields when initializing '{}'",
+                    min, struct_def.name
+                ),
+                None => format!("Allocate the schema size when initializing '{}'", struct_def.name),
+            };
+            items.push(ChecklistItem {
+                id: "init-space",
+                category: CheckCategory::Initialization,
+                priority: Priority::High,
+                item,
+                context: struct_def.name.clone(),
+                docs: None,
+                explanation: "Too little space makes serialization fail once fields fill up; too much locks extra rent in the account. Derive `space` from the schema (e.g. `8 + INIT_SPACE`) rather than a hand-counted constant.".to_string(),
+            });
+        }
+
+        if lifecycle.reallocable {
+            items.push(ChecklistItem {
+                id: "realloc-rent-and-zero",
+                category: CheckCategory::RentExemption,
+                priority: Priority::High,
+                item: format!("Top up rent and zero new space when reallocating '{}'", struct_def.name),
+                context: struct_def.name.clone(),
+                docs: None,
+                explanation: "Use `realloc::payer` so the account stays rent-exempt as it grows, and `realloc::zero = true` so bytes left by an earlier shrink aren't read back as data.".to_string(),
+            });
+        }
+
+        // Account closure checks
+        if is_account
+            && (lifecycle.closable
+                || struct_def
+                    .fields
+                    .iter()
+                    .any(|f| self.is_closable_field(&f.name)))
+        {
+            if !lifecycle.closed_by.is_empty() {
+                items.push(ChecklistItem {
+                    id: "close-instructions",
+                    category: CheckCategory::AccountClosure,
+                    priority: Priority::High,
+                    item: format!(
+                        "Verify only {} may close '{}'",
+                        lifecycle.closed_by.join(", "),
+                        struct_def.name
+                    ),
+                    context: struct_def.name.clone(),
+                    docs: None,
+                    explanation: "No other instruction should carry a `close` constraint on this account or drain its lamports.".to_string(),
+                });
+            }
+
+            items.push(ChecklistItem {
+                id: "close-refund-destination",
+                category: CheckCategory::AccountClosure,
+                priority: Priority::Critical,
+                item: "Verify closing refunds lamports to the intended destination".to_string(),
+                context: struct_def.name.clone(),
+                docs: None,
+                explanation: "Use Anchor's `close = destination` constraint and validate the destination account, otherwise an attacker can redirect the refunded lamports to themselves.".to_string(),
+            });
+
+            items.push(ChecklistItem {
+                id: "close-revival",
+                category: CheckCategory::AccountClosure,
+                priority: Priority::High,
+                item: "Ensure closed accounts cannot be revived".to_string(),
+                context: struct_def.name.clone(),
+                docs: None,
+                explanation: "Zero the account data and transfer all lamports in the same instruction so the account can't be refunded and reused later in the transaction.".to_string(),
+            });
+
+            if let Some(field) = struct_def
+                .fields
+                .iter()
+                .find(|f| f.name.to_lowercase() == "close_authority")
+            {
+                items.push(ChecklistItem {
+                    id: "close-authority",
+                    category: CheckCategory::AccountClosure,
+                    priority: Priority::Critical,
+                    item: format!("Verify only '{}' can close the account", field.name),
+                    context: format!("{}::{}", struct_def.name, field.name),
+                    docs: field.doc_comment(),
+                    explanation: "Require the close authority to sign (e.g. `has_one = close_authority`) so arbitrary users can't close the account and drain its rent.".to_string(),
+                });
+            }
+        }
+
         // State transition checks
         if is_account {
             items.push(ChecklistItem {
+                id: "state-transitions",
                 category: CheckCategory::StateTransition,
                 priority: Priority::High,
                 item: "Verify state transitions are valid and atomic".to_string(),
                 context: struct_def.name.clone(),
+                docs: None,
                 explanation: "Ensure state changes follow expected patterns and can't leave the account in an inconsistent state.".to_string(),
             });
 
             items.push(ChecklistItem {
+                id: "reentrancy",
                 category: CheckCategory::StateTransition,
                 priority: Priority::Medium,
                 item: "Check for reentrancy vulnerabilities".to_string(),
                 context: struct_def.name.clone(),
+                docs: None,
                 explanation: "If the program makes cross-program invocations, ensure it can't be re-entered in an unsafe state.".to_string(),
             });
         }
@@ -238,6 +409,14 @@ impl<'a> AuditGenerator<'a> {
         items
     }
 
+    /// Check if a field name suggests the account can be closed
+    fn is_closable_field(&self, field_name: &str) -> bool {
+        let closable_keywords = ["closed", "is_closed", "close_authority", "closer"];
+
+        let lower = field_name.to_lowercase();
+        closable_keywords.contains(&lower.as_str())
+    }
+
     /// Check if a field name suggests it's an authority/signer
     fn is_authority_field(&self, field_name: &str) -> bool {
         let authority_keywords = [
@@ -282,21 +461,14 @@ impl<'a> AuditGenerator<'a> {
     /// Check if a field is used for arithmetic operations
     fn is_arithmetic_field(&self, field_name: &str, type_info: &TypeInfo) -> bool {
         let arithmetic_keywords = [
-            "balance",
-            "amount",
-            "supply",
-            "total",
-            "count",
-            "price",
-            "value",
-            "reward",
-            "stake",
-            "fee",
-            "lamport",
+            "balance", "amount", "supply", "total", "count", "price", "value", "reward", "stake",
+            "fee", "lamport",
         ];
 
         let lower = field_name.to_lowercase();
-        let is_arithmetic_name = arithmetic_keywords.iter().any(|keyword| lower.contains(keyword));
+        let is_arithmetic_name = arithmetic_keywords
+            .iter()
+            .any(|keyword| lower.contains(keyword));
 
         let is_numeric = matches!(type_info, TypeInfo::Primitive(ref t) if
             t == "u64" || t == "u128" || t == "i64" || t == "i128" ||
@@ -319,6 +491,8 @@ impl CheckCategory {
             CheckCategory::DataValidation => "Data Validation",
             CheckCategory::RentExemption => "Rent Exemption",
             CheckCategory::Initialization => "Initialization",
+            CheckCategory::AccountClosure => "Account Closure",
+            CheckCategory::Confidentiality => "Confidentiality",
         }
     }
 
@@ -333,6 +507,8 @@ impl CheckCategory {
             CheckCategory::DataValidation => "✅",
             CheckCategory::RentExemption => "💰",
             CheckCategory::Initialization => "🎬",
+            CheckCategory::AccountClosure => "🗑️",
+            CheckCategory::Confidentiality => "🔒",
         }
     }
 }
@@ -352,7 +528,7 @@ impl Priority {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{FieldDefinition, Metadata};
+    use crate::ir::{FieldDefinition, Metadata, TypeRole};
 
     #[test]
     fn test_generates_account_validation_checks() {
@@ -362,16 +538,23 @@ mod tests {
             metadata: Metadata {
                 solana: true,
                 attributes: vec!["account".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         })];
 
         let generator = AuditGenerator::new(&type_defs);
         let checklist = generator.generate();
 
-        assert!(checklist.iter().any(|item|
-            matches!(item.category, CheckCategory::AccountValidation) &&
-            item.item.contains("ownership")
-        ));
+        assert!(checklist.iter().any(|item| matches!(
+            item.category,
+            CheckCategory::AccountValidation
+        ) && item.item.contains("ownership")));
     }
 
     #[test]
@@ -382,20 +565,28 @@ mod tests {
                 name: "authority".to_string(),
                 type_info: TypeInfo::Primitive("PublicKey".to_string()),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata {
                 solana: true,
                 attributes: vec!["account".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         })];
 
         let generator = AuditGenerator::new(&type_defs);
         let checklist = generator.generate();
 
-        assert!(checklist.iter().any(|item|
-            matches!(item.category, CheckCategory::SignerChecks) &&
-            matches!(item.priority, Priority::Critical)
-        ));
+        assert!(checklist
+            .iter()
+            .any(|item| matches!(item.category, CheckCategory::SignerChecks)
+                && matches!(item.priority, Priority::Critical)));
     }
 
     #[test]
@@ -406,6 +597,7 @@ mod tests {
                 name: "balance".to_string(),
                 type_info: TypeInfo::Primitive("u64".to_string()),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata::default(),
         })];
@@ -413,10 +605,10 @@ mod tests {
         let generator = AuditGenerator::new(&type_defs);
         let checklist = generator.generate();
 
-        assert!(checklist.iter().any(|item|
-            matches!(item.category, CheckCategory::ArithmeticSafety) &&
-            item.item.contains("checked arithmetic")
-        ));
+        assert!(checklist.iter().any(|item| matches!(
+            item.category,
+            CheckCategory::ArithmeticSafety
+        ) && item.item.contains("checked arithmetic")));
     }
 
     #[test]
@@ -428,16 +620,25 @@ mod tests {
                     name: "authority".to_string(),
                     type_info: TypeInfo::Primitive("PublicKey".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
                 FieldDefinition {
                     name: "balance".to_string(),
                     type_info: TypeInfo::Primitive("u64".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
             ],
             metadata: Metadata {
                 solana: true,
                 attributes: vec!["account".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         })];
 
@@ -449,4 +650,140 @@ mod tests {
             assert!(checklist[i - 1].priority <= checklist[i].priority);
         }
     }
+
+    #[test]
+    fn test_generates_account_closure_checks() {
+        let type_defs = vec![TypeDefinition::Struct(StructDefinition {
+            name: "Escrow".to_string(),
+            fields: vec![FieldDefinition {
+                name: "close_authority".to_string(),
+                type_info: TypeInfo::Primitive("PublicKey".to_string()),
+                optional: false,
+                attributes: vec![],
+            }],
+            metadata: Metadata {
+                solana: true,
+                attributes: vec!["account".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
+            },
+        })];
+
+        let generator = AuditGenerator::new(&type_defs);
+        let checklist = generator.generate();
+
+        let closure: Vec<_> = checklist
+            .iter()
+            .filter(|item| item.category == CheckCategory::AccountClosure)
+            .collect();
+
+        assert_eq!(closure.len(), 3);
+        assert!(closure
+            .iter()
+            .any(|item| item.item.contains("refunds lamports")));
+    }
+
+    #[test]
+    fn test_includes_field_docs_in_context() {
+        let source = r#"
+            #[solana]
+            #[account]
+            struct Vault {
+                /// Signer allowed to withdraw
+                authority: PublicKey,
+                balance: u64,
+            }
+        "#;
+        let type_defs =
+            crate::transform::transform_to_ir(crate::parser::parse_lumos_file(source).unwrap())
+                .unwrap();
+
+        let checklist = AuditGenerator::new(&type_defs).generate();
+
+        let authority = checklist
+            .iter()
+            .find(|item| item.context == "Vault::authority")
+            .unwrap();
+        assert_eq!(
+            authority.docs.as_deref(),
+            Some("Signer allowed to withdraw")
+        );
+
+        let balance = checklist
+            .iter()
+            .find(|item| item.context == "Vault::balance")
+            .unwrap();
+        assert!(balance.docs.is_none());
+    }
+
+    #[test]
+    fn flags_sensitive_fields() {
+        let source = r#"
+            #[solana]
+            struct Member {
+                wallet: PublicKey,
+                #[sensitive]
+                email: String,
+            }
+        "#;
+        let type_defs =
+            crate::transform::transform_to_ir(crate::parser::parse_lumos_file(source).unwrap())
+                .unwrap();
+
+        let checklist = AuditGenerator::new(&type_defs).generate();
+
+        let flagged: Vec<_> = checklist
+            .iter()
+            .filter(|item| item.category == CheckCategory::Confidentiality)
+            .collect();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].id, "sensitive-not-exposed");
+        assert_eq!(flagged[0].context, "Member::email");
+    }
+
+    #[test]
+    fn generates_checks_for_declared_lifecycle() {
+        let source = r#"
+            #[solana]
+            #[account]
+            #[init(create_vault)]
+            #[init_space]
+            #[close(close_vault)]
+            #[realloc]
+            struct Vault {
+                owner: PublicKey,
+                amount: u64,
+            }
+        "#;
+        let type_defs =
+            crate::transform::transform_to_ir(crate::parser::parse_lumos_file(source).unwrap())
+                .unwrap();
+
+        let checklist = AuditGenerator::new(&type_defs).generate();
+        let item = |id: &str| checklist.iter().find(|item| item.id == id).unwrap();
+
+        assert_eq!(
+            item("init-instructions").item,
+            "Verify only create_vault may initialize 'Vault'"
+        );
+        assert_eq!(
+            item("init-space").item,
+            "Allocate 48 bytes (including the 8-byte discriminator) when initializing 'Vault'"
+        );
+        assert_eq!(
+            item("close-instructions").item,
+            "Verify only close_vault may close 'Vault'"
+        );
+        assert_eq!(
+            item("realloc-rent-and-zero").category,
+            CheckCategory::RentExemption
+        );
+        // Declaring #[close] enables the general closure checks too
+        assert!(checklist.iter().any(|item| item.id == "close-revival"));
+    }
 }
@@ -0,0 +1,645 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Borsh decoding of raw bytes with the schema
+//!
+//! Decodes data the way the generated Rust would, without generating or
+//! compiling anything, for tools that inspect bytes from the chain: account
+//! sampling and the instruction decoder. Every error names the value being
+//! decoded and its byte offset.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::borsh_decoder::BorshDecoder;
+//! use lumos_core::ir::TypeInfo;
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "struct Deposit { amount: u64, memo: Option<String> }",
+//! )?)?;
+//!
+//! let data = [5, 0, 0, 0, 0, 0, 0, 0, 1, 2, 0, 0, 0, b'h', b'i'];
+//! let (value, end) = BorshDecoder::new(&ir)
+//!     .decode(&TypeInfo::UserDefined("Deposit".into()), &data, 0, "Deposit")?;
+//! assert_eq!(end, data.len());
+//! assert_eq!(value.to_string(), "{\n  amount: 5\n  memo: Some(\"hi\")\n}");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::encoding::encode_base58;
+use crate::ir::{EnumVariantDefinition, FieldDefinition, TypeDefinition, TypeInfo};
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Where and why data failed to decode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    /// Byte offset into the data
+    pub offset: usize,
+
+    /// Value being decoded, e.g. `Vault.tags[2]`
+    pub path: String,
+
+    /// What was wrong
+    pub message: String,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}: {}", self.path, self.offset, self.message)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A decoded value, in schema order
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedValue {
+    /// `bool`
+    Bool(bool),
+
+    /// Unsigned integers
+    Unsigned(u128),
+
+    /// Signed integers
+    Signed(i128),
+
+    /// `f32` and `f64`
+    Float(f64),
+
+    /// `String`, and `Signature` (generated as a base58 `String`)
+    String(String),
+
+    /// `PublicKey`, base58-encoded
+    PublicKey(String),
+
+    /// `Option<T>`
+    Option(Option<Box<DecodedValue>>),
+
+    /// `[T]`
+    Vec(Vec<DecodedValue>),
+
+    /// `HashMap<K, V>` and `BTreeMap<K, V>` entries, in encoded order
+    Map(Vec<(DecodedValue, DecodedValue)>),
+
+    /// Struct fields, or the fields of a tuple variant named `0`, `1`, ...
+    Struct(Vec<(String, DecodedValue)>),
+
+    /// Enum variant and its fields
+    Variant {
+        name: String,
+        fields: Vec<(String, DecodedValue)>,
+    },
+}
+
+impl DecodedValue {
+    /// JSON form; integers wider than 64 bits become strings
+    ///
+    /// Maps become objects keyed by [`DecodedValue::key_string`].
+    pub fn to_json(&self) -> Value {
+        let fields = |fields: &[(String, DecodedValue)]| -> Value {
+            Value::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.to_json()))
+                    .collect(),
+            )
+        };
+
+        match self {
+            DecodedValue::Bool(value) => json!(value),
+            DecodedValue::Unsigned(value) => match u64::try_from(*value) {
+                Ok(value) => json!(value),
+                Err(_) => json!(value.to_string()),
+            },
+            DecodedValue::Signed(value) => match i64::try_from(*value) {
+                Ok(value) => json!(value),
+                Err(_) => json!(value.to_string()),
+            },
+            DecodedValue::Float(value) => json!(value),
+            DecodedValue::String(value) | DecodedValue::PublicKey(value) => json!(value),
+            DecodedValue::Option(value) => value.as_ref().map_or(Value::Null, |v| v.to_json()),
+            DecodedValue::Vec(items) => Value::Array(items.iter().map(Self::to_json).collect()),
+            DecodedValue::Map(entries) => Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.key_string(), value.to_json()))
+                    .collect(),
+            ),
+            DecodedValue::Struct(values) => fields(values),
+            DecodedValue::Variant {
+                name,
+                fields: values,
+            } if values.is_empty() => json!(name),
+            DecodedValue::Variant {
+                name,
+                fields: values,
+            } => json!({ name: fields(values) }),
+        }
+    }
+
+    /// Map key as a JSON object key: integers in decimal, `true`/`false`,
+    /// strings and base58 public keys as they are
+    pub fn key_string(&self) -> String {
+        match self {
+            DecodedValue::String(value) | DecodedValue::PublicKey(value) => value.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    fn is_scalar(&self) -> bool {
+        match self {
+            DecodedValue::Struct(_) | DecodedValue::Vec(_) | DecodedValue::Map(_) => false,
+            DecodedValue::Variant { fields, .. } => fields.is_empty(),
+            DecodedValue::Option(value) => value.as_ref().map_or(true, |v| v.is_scalar()),
+            _ => true,
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let pad = "  ".repeat(indent);
+        let write_fields = |f: &mut fmt::Formatter<'_>, fields: &[(String, DecodedValue)]| {
+            writeln!(f, "{{")?;
+            for (name, value) in fields {
+                write!(f, "{}  {}: ", pad, name)?;
+                value.write(f, indent + 1)?;
+                writeln!(f)?;
+            }
+            write!(f, "{}}}", pad)
+        };
+
+        match self {
+            DecodedValue::Bool(value) => write!(f, "{}", value),
+            DecodedValue::Unsigned(value) => write!(f, "{}", value),
+            DecodedValue::Signed(value) => write!(f, "{}", value),
+            DecodedValue::Float(value) => write!(f, "{}", value),
+            DecodedValue::String(value) => write!(f, "{:?}", value),
+            DecodedValue::PublicKey(value) => write!(f, "{}", value),
+            DecodedValue::Option(None) => write!(f, "None"),
+            DecodedValue::Option(Some(value)) => {
+                write!(f, "Some(")?;
+                value.write(f, indent)?;
+                write!(f, ")")
+            }
+            DecodedValue::Vec(items) if items.iter().all(Self::is_scalar) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    item.write(f, indent)?;
+                }
+                write!(f, "]")
+            }
+            DecodedValue::Vec(items) => {
+                writeln!(f, "[")?;
+                for item in items {
+                    write!(f, "{}  ", pad)?;
+                    item.write(f, indent + 1)?;
+                    writeln!(f, ",")?;
+                }
+                write!(f, "{}]", pad)
+            }
+            DecodedValue::Map(entries) if entries.is_empty() => write!(f, "{{}}"),
+            DecodedValue::Map(entries) => {
+                writeln!(f, "{{")?;
+                for (key, value) in entries {
+                    write!(f, "{}  ", pad)?;
+                    key.write(f, indent + 1)?;
+                    write!(f, ": ")?;
+                    value.write(f, indent + 1)?;
+                    writeln!(f, ",")?;
+                }
+                write!(f, "{}}}", pad)
+            }
+            DecodedValue::Struct(fields) if fields.is_empty() => write!(f, "{{}}"),
+            DecodedValue::Struct(fields) => write_fields(f, fields),
+            DecodedValue::Variant { name, fields } if fields.is_empty() => write!(f, "{}", name),
+            DecodedValue::Variant { name, fields } => {
+                write!(f, "{} ", name)?;
+                write_fields(f, fields)
+            }
+        }
+    }
+}
+
+/// Multi-line, indented rendering for terminals
+impl fmt::Display for DecodedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Decoder for data laid out as the schema's types
+#[derive(Debug, Clone, Copy)]
+pub struct BorshDecoder<'a> {
+    type_defs: &'a [TypeDefinition],
+}
+
+impl<'a> BorshDecoder<'a> {
+    /// Create a decoder for the schema's types
+    pub fn new(type_defs: &'a [TypeDefinition]) -> Self {
+        Self { type_defs }
+    }
+
+    /// Decode a `type_info` value starting at `offset`, returning it and where it ends
+    ///
+    /// `path` names the value in errors.
+    pub fn decode(
+        &self,
+        type_info: &TypeInfo,
+        data: &[u8],
+        offset: usize,
+        path: &str,
+    ) -> Result<(DecodedValue, usize), DecodeError> {
+        let mut reader = Reader { data, offset };
+        let value = self.decode_type(&mut reader, type_info, path)?;
+        Ok((value, reader.offset))
+    }
+
+    fn decode_type(
+        &self,
+        reader: &mut Reader,
+        type_info: &TypeInfo,
+        path: &str,
+    ) -> Result<DecodedValue, DecodeError> {
+        match type_info {
+            TypeInfo::Primitive(name) => decode_primitive(reader, name, path),
+            TypeInfo::UserDefined(name) => {
+                let type_def = self
+                    .type_defs
+                    .iter()
+                    .find(|t| t.name() == name)
+                    .ok_or_else(|| reader.error(path, format!("unknown type `{}`", name)))?;
+
+                match type_def {
+                    TypeDefinition::Struct(s) => self
+                        .decode_fields(reader, s.data_fields(), path)
+                        .map(DecodedValue::Struct),
+                    TypeDefinition::Enum(e) => {
+                        let tag = reader.take(1, path)?[0] as usize;
+                        let variant = e.variants.get(tag).ok_or_else(|| DecodeError {
+                            offset: reader.offset - 1,
+                            path: path.to_string(),
+                            message: format!(
+                                "variant tag {} is out of range ({} variants)",
+                                tag,
+                                e.variants.len()
+                            ),
+                        })?;
+                        let fields = match variant {
+                            EnumVariantDefinition::Unit { .. } => Vec::new(),
+                            EnumVariantDefinition::Tuple { name, types } => types
+                                .iter()
+                                .enumerate()
+                                .map(|(index, type_info)| {
+                                    let path = format!("{}::{}.{}", path, name, index);
+                                    Ok((
+                                        index.to_string(),
+                                        self.decode_type(reader, type_info, &path)?,
+                                    ))
+                                })
+                                .collect::<Result<_, DecodeError>>()?,
+                            EnumVariantDefinition::Struct { name, fields } => {
+                                let path = format!("{}::{}", path, name);
+                                self.decode_fields(reader, fields, &path)?
+                            }
+                        };
+                        Ok(DecodedValue::Variant {
+                            name: variant.name().to_string(),
+                            fields,
+                        })
+                    }
+                }
+            }
+            TypeInfo::Array(inner) => {
+                let len = u32::from_le_bytes(reader.array(path)?) as usize;
+                // Every element takes at least a byte, except empty structs
+                let message = if self.may_be_empty(inner) {
+                    (len > MAX_EMPTY_ELEMENTS).then(|| {
+                        format!(
+                            "length {} exceeds {} empty elements",
+                            len, MAX_EMPTY_ELEMENTS
+                        )
+                    })
+                } else {
+                    (len > reader.remaining()).then(|| {
+                        format!(
+                            "length {} exceeds the {} bytes left",
+                            len,
+                            reader.remaining()
+                        )
+                    })
+                };
+                if let Some(message) = message {
+                    return Err(reader.error(path, message));
+                }
+                (0..len)
+                    .map(|index| self.decode_type(reader, inner, &format!("{}[{}]", path, index)))
+                    .collect::<Result<_, _>>()
+                    .map(DecodedValue::Vec)
+            }
+            TypeInfo::Map(_, key, value) => {
+                let len = u32::from_le_bytes(reader.array(path)?) as usize;
+                // Keys are primitives, so every entry takes at least a byte
+                if len > reader.remaining() {
+                    let message = format!(
+                        "length {} exceeds the {} bytes left",
+                        len,
+                        reader.remaining()
+                    );
+                    return Err(reader.error(path, message));
+                }
+                (0..len)
+                    .map(|index| {
+                        let key =
+                            self.decode_type(reader, key, &format!("{}.key[{}]", path, index))?;
+                        let path = format!("{}[{}]", path, key.key_string());
+                        Ok((key, self.decode_type(reader, value, &path)?))
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(DecodedValue::Map)
+            }
+            TypeInfo::FixedArray(inner, len) => (0..*len)
+                .map(|index| self.decode_type(reader, inner, &format!("{}[{}]", path, index)))
+                .collect::<Result<_, _>>()
+                .map(DecodedValue::Vec),
+            TypeInfo::Option(inner) => match reader.take(1, path)?[0] {
+                0 => Ok(DecodedValue::Option(None)),
+                1 => Ok(DecodedValue::Option(Some(Box::new(
+                    self.decode_type(reader, inner, path)?,
+                )))),
+                tag => Err(DecodeError {
+                    offset: reader.offset - 1,
+                    path: path.to_string(),
+                    message: format!("option tag {} is not 0 or 1", tag),
+                }),
+            },
+        }
+    }
+
+    /// Whether a value of this type can take zero bytes (empty structs)
+    fn may_be_empty(&self, type_info: &TypeInfo) -> bool {
+        match type_info {
+            TypeInfo::UserDefined(name) => self.type_defs.iter().any(|t| match t {
+                TypeDefinition::Struct(s) if s.name == *name => {
+                    s.data_fields().all(|f| self.may_be_empty(&f.type_info))
+                }
+                _ => false,
+            }),
+            _ => false,
+        }
+    }
+
+    fn decode_fields(
+        &self,
+        reader: &mut Reader,
+        fields: impl IntoIterator<Item = &'a FieldDefinition>,
+        path: &str,
+    ) -> Result<Vec<(String, DecodedValue)>, DecodeError> {
+        fields
+            .into_iter()
+            .map(|field| {
+                let path = format!("{}.{}", path, field.name);
+                if let Some(codec) = field.codec("rust") {
+                    return Err(reader.error(
+                        &path,
+                        format!("layout is owned by custom codec `{}`", codec),
+                    ));
+                }
+                Ok((
+                    field.name.clone(),
+                    self.decode_type(reader, &field.type_info, &path)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+fn decode_primitive(
+    reader: &mut Reader,
+    name: &str,
+    path: &str,
+) -> Result<DecodedValue, DecodeError> {
+    macro_rules! int {
+        ($ty:ty, $variant:ident) => {{
+            let value = <$ty>::from_le_bytes(reader.array(path)?);
+            DecodedValue::$variant(value.into())
+        }};
+    }
+
+    Ok(match name {
+        "u8" => int!(u8, Unsigned),
+        "u16" => int!(u16, Unsigned),
+        "u32" => int!(u32, Unsigned),
+        "u64" => int!(u64, Unsigned),
+        "u128" => int!(u128, Unsigned),
+        "i8" => int!(i8, Signed),
+        "i16" => int!(i16, Signed),
+        "i32" => int!(i32, Signed),
+        "i64" => int!(i64, Signed),
+        "i128" => int!(i128, Signed),
+        "f32" => DecodedValue::Float(f32::from_le_bytes(reader.array(path)?).into()),
+        "f64" => DecodedValue::Float(f64::from_le_bytes(reader.array(path)?)),
+        "PublicKey" | "Pubkey" => {
+            DecodedValue::PublicKey(encode_base58(&reader.array::<32>(path)?))
+        }
+        "bool" => match reader.take(1, path)?[0] {
+            0 => DecodedValue::Bool(false),
+            1 => DecodedValue::Bool(true),
+            value => {
+                return Err(DecodeError {
+                    offset: reader.offset - 1,
+                    path: path.to_string(),
+                    message: format!("bool byte {} is not 0 or 1", value),
+                })
+            }
+        },
+        // Signatures are generated as base58 `String`s
+        "String" | "Signature" => {
+            let len = u32::from_le_bytes(reader.array(path)?) as usize;
+            let start = reader.offset;
+            let bytes = reader.take(len, path)?;
+            match std::str::from_utf8(bytes) {
+                Ok(value) => DecodedValue::String(value.to_string()),
+                Err(_) => {
+                    reader.offset = start;
+                    return Err(reader.error(path, "string is not valid UTF-8".to_string()));
+                }
+            }
+        }
+        other => {
+            return Err(reader.error(path, format!("`{}` has no Borsh encoding", other)));
+        }
+    })
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.offset)
+    }
+
+    fn take(&mut self, len: usize, path: &str) -> Result<&'a [u8], DecodeError> {
+        if len > self.remaining() {
+            return Err(self.error(
+                path,
+                format!("needs {} bytes but only {} are left", len, self.remaining()),
+            ));
+        }
+        let bytes = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self, path: &str) -> Result<[u8; N], DecodeError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N, path)?);
+        Ok(array)
+    }
+
+    fn error(&self, path: &str, message: String) -> DecodeError {
+        DecodeError {
+            offset: self.offset,
+            path: path.to_string(),
+            message,
+        }
+    }
+}
+
+/// Most elements read for a vector of empty structs
+const MAX_EMPTY_ELEMENTS: usize = 1 << 16;
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256, enough for Anchor discriminators without pulling in a hashing crate
+pub(crate) fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let mut message = bytes.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((bytes.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA256_K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0; 32];
+    for (chunk, word) in digest.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn decode(source: &str, name: &str, data: &[u8]) -> Result<(DecodedValue, usize), DecodeError> {
+        let ir = transform_to_ir(parse_lumos_file(source).unwrap()).unwrap();
+        BorshDecoder::new(&ir).decode(&TypeInfo::UserDefined(name.to_string()), data, 0, name)
+    }
+
+    #[test]
+    fn decodes_nested_values() {
+        let source = r#"
+            struct Order { owner: PublicKey, side: Side, fills: [Fill], price: i64, big: u128 }
+            struct Fill { size: u32, maker: bool }
+            enum Side { Bid, Ask(u8) }
+        "#;
+        let mut data = vec![0; 32];
+        data.extend_from_slice(&[1, 9]);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&[3, 0, 0, 0, 1]);
+        data.extend_from_slice(&(-2i64).to_le_bytes());
+        data.extend_from_slice(&u128::MAX.to_le_bytes());
+
+        let (value, end) = decode(source, "Order", &data).unwrap();
+        assert_eq!(end, data.len());
+        assert_eq!(
+            value.to_string(),
+            "{\n  owner: 11111111111111111111111111111111\n  side: Ask {\n    0: 9\n  }\n  fills: [\n    {\n      size: 3\n      maker: true\n    },\n  ]\n  price: -2\n  big: 340282366920938463463374607431768211455\n}"
+        );
+        assert_eq!(
+            value.to_json(),
+            json!({
+                "owner": "11111111111111111111111111111111",
+                "side": { "Ask": { "0": 9 } },
+                "fills": [{ "size": 3, "maker": true }],
+                "price": -2,
+                "big": "340282366920938463463374607431768211455",
+            })
+        );
+    }
+
+    #[test]
+    fn reports_invalid_data() {
+        let err = decode("struct Flag { on: bool }", "Flag", &[2]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Flag.on at byte 0: bool byte 2 is not 0 or 1"
+        );
+
+        let err = decode("struct Tags { tags: [u64] }", "Tags", &[255, 255, 0, 0]).unwrap_err();
+        assert_eq!(err.message, "length 65535 exceeds the 0 bytes left");
+    }
+}
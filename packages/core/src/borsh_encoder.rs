@@ -0,0 +1,658 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Borsh encoding of JSON values with the schema
+//!
+//! The inverse of [`borsh_decoder`](crate::borsh_decoder): JSON in the shape
+//! [`DecodedValue::to_json`] produces is checked against the schema, turned
+//! into a [`DecodedValue`] and written as the bytes the generated Rust would
+//! serialize. Test fixtures and support scripts can round-trip values
+//! without compiling anything.
+//!
+//! JSON shapes:
+//!
+//! - integers are numbers, or strings for values wider than 64 bits
+//! - `PublicKey` is a base58 string
+//! - `Option<T>` is `null` or the value
+//! - maps are objects; integer and `bool` keys are written as strings
+//! - structs are objects with every field
+//! - enum variants are `"Name"`, or `{ "Name": { fields } }` with tuple
+//!   fields named `"0"`, `"1"`, ...
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::borsh_encoder::BorshEncoder;
+//! use lumos_core::ir::TypeInfo;
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "struct Deposit { amount: u64, memo: Option<String> }",
+//! )?)?;
+//!
+//! let json = serde_json::json!({ "amount": 5, "memo": "hi" });
+//! let bytes = BorshEncoder::new(&ir)
+//!     .encode_json(&TypeInfo::UserDefined("Deposit".into()), &json, "Deposit")?;
+//! assert_eq!(bytes, [5, 0, 0, 0, 0, 0, 0, 0, 1, 2, 0, 0, 0, b'h', b'i']);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::borsh_decoder::DecodedValue;
+use crate::encoding::decode_pubkey;
+use crate::ir::{EnumVariantDefinition, FieldDefinition, TypeDefinition, TypeInfo};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Where and why a value failed to encode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodeError {
+    /// Value being encoded, e.g. `Vault.tags[2]`
+    pub path: String,
+
+    /// What was wrong
+    pub message: String,
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// Encoder for values of the schema's types
+#[derive(Debug, Clone, Copy)]
+pub struct BorshEncoder<'a> {
+    type_defs: &'a [TypeDefinition],
+}
+
+impl<'a> BorshEncoder<'a> {
+    /// Create an encoder for the schema's types
+    pub fn new(type_defs: &'a [TypeDefinition]) -> Self {
+        Self { type_defs }
+    }
+
+    /// Check `json` against `type_info` and encode it
+    ///
+    /// `path` names the value in errors.
+    pub fn encode_json(
+        &self,
+        type_info: &TypeInfo,
+        json: &Value,
+        path: &str,
+    ) -> Result<Vec<u8>, EncodeError> {
+        let value = self.from_json(type_info, json, path)?;
+        self.encode(type_info, &value, path)
+    }
+
+    /// Parse `json` as a value of `type_info`
+    pub fn from_json(
+        &self,
+        type_info: &TypeInfo,
+        json: &Value,
+        path: &str,
+    ) -> Result<DecodedValue, EncodeError> {
+        match type_info {
+            TypeInfo::Primitive(name) => primitive_from_json(name, json, path),
+            TypeInfo::UserDefined(name) => match self.type_def(name, path)? {
+                TypeDefinition::Struct(s) => self
+                    .fields_from_json(&named(&s.fields), json, path)
+                    .map(DecodedValue::Struct),
+                TypeDefinition::Enum(e) => {
+                    let (variant_name, body) = match json {
+                        Value::String(name) => (name.as_str(), None),
+                        Value::Object(map) if map.len() == 1 => {
+                            let (name, body) = map.iter().next().unwrap();
+                            (name.as_str(), Some(body))
+                        }
+                        _ => {
+                            return Err(error(path, format!("expected a variant of `{}`", e.name)))
+                        }
+                    };
+                    let variant = e
+                        .variants
+                        .iter()
+                        .find(|v| v.name() == variant_name)
+                        .ok_or_else(|| {
+                            error(
+                                path,
+                                format!("`{}` has no variant `{}`", e.name, variant_name),
+                            )
+                        })?;
+
+                    let path = format!("{}::{}", path, variant_name);
+                    let fields = match (variant, body) {
+                        (EnumVariantDefinition::Unit { .. }, None) => Vec::new(),
+                        (EnumVariantDefinition::Unit { .. }, Some(_)) => {
+                            return Err(error(&path, "unit variant takes no fields".to_string()))
+                        }
+                        (_, None) => {
+                            return Err(error(&path, "variant fields are missing".to_string()))
+                        }
+                        (EnumVariantDefinition::Tuple { types, .. }, Some(body)) => {
+                            let fields: Vec<_> = types
+                                .iter()
+                                .enumerate()
+                                .map(|(index, type_info)| (index.to_string(), type_info))
+                                .collect();
+                            self.fields_from_json(&fields, body, &path)?
+                        }
+                        (EnumVariantDefinition::Struct { fields, .. }, Some(body)) => {
+                            self.fields_from_json(&named(fields), body, &path)?
+                        }
+                    };
+                    Ok(DecodedValue::Variant {
+                        name: variant_name.to_string(),
+                        fields,
+                    })
+                }
+            },
+            TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => match json {
+                Value::Array(items) => {
+                    if let TypeInfo::FixedArray(_, len) = type_info {
+                        if items.len() != *len {
+                            return Err(error(
+                                path,
+                                format!("expected {} elements, found {}", len, items.len()),
+                            ));
+                        }
+                    }
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, item)| {
+                            self.from_json(inner, item, &format!("{}[{}]", path, index))
+                        })
+                        .collect::<Result<_, _>>()
+                        .map(DecodedValue::Vec)
+                }
+                _ => Err(error(path, "expected an array".to_string())),
+            },
+            TypeInfo::Map(_, key, value) => match json {
+                Value::Object(map) => map
+                    .iter()
+                    .map(|(name, json)| {
+                        let path = format!("{}[{}]", path, name);
+                        let key_json = match (key.as_ref(), name.as_str()) {
+                            (TypeInfo::Primitive(p), "true" | "false") if p == "bool" => {
+                                Value::Bool(name == "true")
+                            }
+                            _ => Value::String(name.clone()),
+                        };
+                        Ok((
+                            self.from_json(key, &key_json, &path)?,
+                            self.from_json(value, json, &path)?,
+                        ))
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(DecodedValue::Map),
+                _ => Err(error(path, "expected an object".to_string())),
+            },
+            TypeInfo::Option(inner) => match json {
+                Value::Null => Ok(DecodedValue::Option(None)),
+                json => Ok(DecodedValue::Option(Some(Box::new(
+                    self.from_json(inner, json, path)?,
+                )))),
+            },
+        }
+    }
+
+    /// Encode a value of `type_info`
+    pub fn encode(
+        &self,
+        type_info: &TypeInfo,
+        value: &DecodedValue,
+        path: &str,
+    ) -> Result<Vec<u8>, EncodeError> {
+        let mut out = Vec::new();
+        self.write(&mut out, type_info, value, path)?;
+        Ok(out)
+    }
+
+    fn write(
+        &self,
+        out: &mut Vec<u8>,
+        type_info: &TypeInfo,
+        value: &DecodedValue,
+        path: &str,
+    ) -> Result<(), EncodeError> {
+        match (type_info, value) {
+            (TypeInfo::Primitive(name), value) => write_primitive(out, name, value, path),
+            (TypeInfo::UserDefined(name), value) => match (self.type_def(name, path)?, value) {
+                (TypeDefinition::Struct(s), DecodedValue::Struct(values)) => {
+                    self.write_fields(out, &s.fields, values, path)
+                }
+                (TypeDefinition::Enum(e), DecodedValue::Variant { name, fields }) => {
+                    let (tag, variant) = e
+                        .variants
+                        .iter()
+                        .enumerate()
+                        .find(|(_, v)| v.name() == name)
+                        .ok_or_else(|| {
+                            error(path, format!("`{}` has no variant `{}`", e.name, name))
+                        })?;
+                    out.push(tag as u8);
+
+                    let path = format!("{}::{}", path, name);
+                    match variant {
+                        EnumVariantDefinition::Unit { .. } => Ok(()),
+                        EnumVariantDefinition::Tuple { types, .. } => {
+                            if types.len() != fields.len() {
+                                return Err(error(
+                                    &path,
+                                    format!("expected {} fields", types.len()),
+                                ));
+                            }
+                            for (index, (type_info, (_, value))) in
+                                types.iter().zip(fields).enumerate()
+                            {
+                                self.write(out, type_info, value, &format!("{}.{}", path, index))?;
+                            }
+                            Ok(())
+                        }
+                        EnumVariantDefinition::Struct {
+                            fields: definitions,
+                            ..
+                        } => self.write_fields(out, definitions, fields, &path),
+                    }
+                }
+                (type_def, _) => Err(mismatch(type_def.name(), path)),
+            },
+            (TypeInfo::Array(inner), DecodedValue::Vec(items)) => {
+                let len = u32::try_from(items.len())
+                    .map_err(|_| error(path, "array is too long for Borsh".to_string()))?;
+                out.extend_from_slice(&len.to_le_bytes());
+                for (index, item) in items.iter().enumerate() {
+                    self.write(out, inner, item, &format!("{}[{}]", path, index))?;
+                }
+                Ok(())
+            }
+            (TypeInfo::FixedArray(inner, len), DecodedValue::Vec(items)) => {
+                if items.len() != *len {
+                    return Err(error(
+                        path,
+                        format!("expected {} elements, found {}", len, items.len()),
+                    ));
+                }
+                for (index, item) in items.iter().enumerate() {
+                    self.write(out, inner, item, &format!("{}[{}]", path, index))?;
+                }
+                Ok(())
+            }
+            (TypeInfo::Map(_, key, value), DecodedValue::Map(entries)) => {
+                let len = u32::try_from(entries.len())
+                    .map_err(|_| error(path, "map is too long for Borsh".to_string()))?;
+                out.extend_from_slice(&len.to_le_bytes());
+
+                // Borsh writes entries in key order, for HashMap too
+                let mut sorted: Vec<_> = entries.iter().collect();
+                sorted.sort_by(|(a, _), (b, _)| compare_keys(a, b));
+                for pair in sorted.windows(2) {
+                    if compare_keys(&pair[0].0, &pair[1].0).is_eq() {
+                        return Err(error(
+                            path,
+                            format!("duplicate key `{}`", pair[0].0.key_string()),
+                        ));
+                    }
+                }
+                for (entry_key, entry_value) in sorted {
+                    let path = format!("{}[{}]", path, entry_key.key_string());
+                    self.write(out, key, entry_key, &path)?;
+                    self.write(out, value, entry_value, &path)?;
+                }
+                Ok(())
+            }
+            (TypeInfo::Option(_), DecodedValue::Option(None)) => {
+                out.push(0);
+                Ok(())
+            }
+            (TypeInfo::Option(inner), DecodedValue::Option(Some(value))) => {
+                out.push(1);
+                self.write(out, inner, value, path)
+            }
+            (TypeInfo::Array(_) | TypeInfo::FixedArray(..), _) => Err(mismatch("array", path)),
+            (TypeInfo::Map(..), _) => Err(mismatch("map", path)),
+            (TypeInfo::Option(_), _) => Err(mismatch("option", path)),
+        }
+    }
+
+    fn type_def(&self, name: &str, path: &str) -> Result<&'a TypeDefinition, EncodeError> {
+        self.type_defs
+            .iter()
+            .find(|t| t.name() == name)
+            .ok_or_else(|| error(path, format!("unknown type `{}`", name)))
+    }
+
+    fn fields_from_json(
+        &self,
+        fields: &[(String, &TypeInfo)],
+        json: &Value,
+        path: &str,
+    ) -> Result<Vec<(String, DecodedValue)>, EncodeError> {
+        let Value::Object(map) = json else {
+            return Err(error(path, "expected an object".to_string()));
+        };
+        if let Some(unknown) = map.keys().find(|k| !fields.iter().any(|(n, _)| n == *k)) {
+            return Err(error(path, format!("unknown field `{}`", unknown)));
+        }
+
+        fields
+            .iter()
+            .map(|(name, type_info)| {
+                let path = format!("{}.{}", path, name);
+                let json = map
+                    .get(name)
+                    .ok_or_else(|| error(&path, "field is missing".to_string()))?;
+                Ok((name.clone(), self.from_json(type_info, json, &path)?))
+            })
+            .collect()
+    }
+
+    fn write_fields(
+        &self,
+        out: &mut Vec<u8>,
+        fields: &[FieldDefinition],
+        values: &[(String, DecodedValue)],
+        path: &str,
+    ) -> Result<(), EncodeError> {
+        for field in fields {
+            let path = format!("{}.{}", path, field.name);
+            if let Some(codec) = field.codec("rust") {
+                return Err(error(
+                    &path,
+                    format!("layout is owned by custom codec `{}`", codec),
+                ));
+            }
+            let (_, value) = values
+                .iter()
+                .find(|(name, _)| *name == field.name)
+                .ok_or_else(|| error(&path, "field is missing".to_string()))?;
+            self.write(out, &field.type_info, value, &path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Order of map keys as the Rust key types compare, public keys by their bytes
+fn compare_keys(a: &DecodedValue, b: &DecodedValue) -> Ordering {
+    match (a, b) {
+        (DecodedValue::Unsigned(a), DecodedValue::Unsigned(b)) => a.cmp(b),
+        (DecodedValue::Signed(a), DecodedValue::Signed(b)) => a.cmp(b),
+        (DecodedValue::Bool(a), DecodedValue::Bool(b)) => a.cmp(b),
+        (DecodedValue::PublicKey(a), DecodedValue::PublicKey(b)) => {
+            decode_pubkey(a).ok().cmp(&decode_pubkey(b).ok())
+        }
+        (DecodedValue::String(a), DecodedValue::String(b)) => a.cmp(b),
+        _ => Ordering::Equal,
+    }
+}
+
+fn primitive_from_json(name: &str, json: &Value, path: &str) -> Result<DecodedValue, EncodeError> {
+    macro_rules! int {
+        ($ty:ty, $variant:ident, $as:ident) => {{
+            let value = match json {
+                Value::Number(n) => n.$as().map(|v| v as i128),
+                Value::String(s) => s.parse::<i128>().ok(),
+                _ => None,
+            };
+            let value = value
+                .and_then(|v| <$ty>::try_from(v).ok())
+                .or_else(|| json.as_str().and_then(|s| s.parse::<$ty>().ok()))
+                .ok_or_else(|| error(path, format!("expected a {}", stringify!($ty))))?;
+            DecodedValue::$variant(value.into())
+        }};
+    }
+
+    Ok(match name {
+        "u8" => int!(u8, Unsigned, as_u64),
+        "u16" => int!(u16, Unsigned, as_u64),
+        "u32" => int!(u32, Unsigned, as_u64),
+        "u64" => int!(u64, Unsigned, as_u64),
+        "u128" => int!(u128, Unsigned, as_u64),
+        "i8" => int!(i8, Signed, as_i64),
+        "i16" => int!(i16, Signed, as_i64),
+        "i32" => int!(i32, Signed, as_i64),
+        "i64" => int!(i64, Signed, as_i64),
+        "i128" => int!(i128, Signed, as_i64),
+        "f32" | "f64" => DecodedValue::Float(
+            json.as_f64()
+                .ok_or_else(|| error(path, "expected a number".to_string()))?,
+        ),
+        "bool" => DecodedValue::Bool(
+            json.as_bool()
+                .ok_or_else(|| error(path, "expected true or false".to_string()))?,
+        ),
+        "PublicKey" | "Pubkey" => {
+            let key = json
+                .as_str()
+                .ok_or_else(|| error(path, "expected a base58 public key".to_string()))?;
+            decode_pubkey(key).map_err(|e| error(path, format!("`{}` is {}", key, e)))?;
+            DecodedValue::PublicKey(key.to_string())
+        }
+        "String" | "Signature" => DecodedValue::String(
+            json.as_str()
+                .ok_or_else(|| error(path, "expected a string".to_string()))?
+                .to_string(),
+        ),
+        other => return Err(error(path, format!("`{}` has no Borsh encoding", other))),
+    })
+}
+
+fn write_primitive(
+    out: &mut Vec<u8>,
+    name: &str,
+    value: &DecodedValue,
+    path: &str,
+) -> Result<(), EncodeError> {
+    macro_rules! int {
+        ($ty:ty, $variant:ident) => {{
+            let DecodedValue::$variant(value) = value else {
+                return Err(mismatch(name, path));
+            };
+            let value = <$ty>::try_from(*value)
+                .map_err(|_| error(path, format!("{} is out of range for {}", value, name)))?;
+            out.extend_from_slice(&value.to_le_bytes());
+        }};
+    }
+
+    match name {
+        "u8" => int!(u8, Unsigned),
+        "u16" => int!(u16, Unsigned),
+        "u32" => int!(u32, Unsigned),
+        "u64" => int!(u64, Unsigned),
+        "u128" => int!(u128, Unsigned),
+        "i8" => int!(i8, Signed),
+        "i16" => int!(i16, Signed),
+        "i32" => int!(i32, Signed),
+        "i64" => int!(i64, Signed),
+        "i128" => int!(i128, Signed),
+        "f32" | "f64" => {
+            let DecodedValue::Float(value) = value else {
+                return Err(mismatch(name, path));
+            };
+            if name == "f32" {
+                out.extend_from_slice(&(*value as f32).to_le_bytes());
+            } else {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        "bool" => {
+            let DecodedValue::Bool(value) = value else {
+                return Err(mismatch(name, path));
+            };
+            out.push(*value as u8);
+        }
+        "PublicKey" | "Pubkey" => {
+            let DecodedValue::PublicKey(key) = value else {
+                return Err(mismatch(name, path));
+            };
+            let bytes = decode_pubkey(key).map_err(|e| error(path, e.to_string()))?;
+            out.extend_from_slice(&bytes);
+        }
+        "String" | "Signature" => {
+            let DecodedValue::String(value) = value else {
+                return Err(mismatch(name, path));
+            };
+            let len = u32::try_from(value.len())
+                .map_err(|_| error(path, "string is too long for Borsh".to_string()))?;
+            out.extend_from_slice(&len.to_le_bytes());
+            out.extend_from_slice(value.as_bytes());
+        }
+        other => return Err(error(path, format!("`{}` has no Borsh encoding", other))),
+    }
+    Ok(())
+}
+
+fn named(fields: &[FieldDefinition]) -> Vec<(String, &TypeInfo)> {
+    fields
+        .iter()
+        .map(|f| (f.name.clone(), &f.type_info))
+        .collect()
+}
+
+fn error(path: &str, message: String) -> EncodeError {
+    EncodeError {
+        path: path.to_string(),
+        message,
+    }
+}
+
+fn mismatch(expected: &str, path: &str) -> EncodeError {
+    error(path, format!("value is not a `{}`", expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::borsh_decoder::BorshDecoder;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+    use serde_json::json;
+
+    const SOURCE: &str = r#"
+        struct Order { owner: PublicKey, side: Side, fills: [Fill], price: i64, big: u128, note: Option<String> }
+        struct Fill { size: u32, maker: bool }
+        enum Side { Bid, Ask(u8), Limit { price: u64 } }
+    "#;
+
+    fn encode(json: &Value) -> Result<Vec<u8>, EncodeError> {
+        let ir = transform_to_ir(parse_lumos_file(SOURCE).unwrap()).unwrap();
+        BorshEncoder::new(&ir).encode_json(&TypeInfo::UserDefined("Order".into()), json, "Order")
+    }
+
+    fn order() -> Value {
+        json!({
+            "owner": "11111111111111111111111111111111",
+            "side": { "Ask": { "0": 9 } },
+            "fills": [{ "size": 3, "maker": true }],
+            "price": -2,
+            "big": u128::MAX.to_string(),
+            "note": null,
+        })
+    }
+
+    #[test]
+    fn encodes_the_json_the_decoder_produces() {
+        let bytes = encode(&order()).unwrap();
+
+        let mut expected = vec![0; 32];
+        expected.extend_from_slice(&[1, 9]);
+        expected.extend_from_slice(&1u32.to_le_bytes());
+        expected.extend_from_slice(&[3, 0, 0, 0, 1]);
+        expected.extend_from_slice(&(-2i64).to_le_bytes());
+        expected.extend_from_slice(&u128::MAX.to_le_bytes());
+        expected.push(0);
+        assert_eq!(bytes, expected);
+
+        let ir = transform_to_ir(parse_lumos_file(SOURCE).unwrap()).unwrap();
+        let (value, _) = BorshDecoder::new(&ir)
+            .decode(&TypeInfo::UserDefined("Order".into()), &bytes, 0, "Order")
+            .unwrap();
+        assert_eq!(value.to_json(), order());
+    }
+
+    #[test]
+    fn reports_where_values_do_not_fit() {
+        let with = |pointer: &str, value: Value| {
+            let mut json = order();
+            *json.pointer_mut(pointer).unwrap() = value;
+            encode(&json).unwrap_err().to_string()
+        };
+
+        assert_eq!(
+            with("/fills/0/size", json!(-1)),
+            "Order.fills[0].size: expected a u32"
+        );
+        assert_eq!(
+            with("/side", json!("Sell")),
+            "Order.side: `Side` has no variant `Sell`"
+        );
+        assert_eq!(
+            with("/side", json!({ "Limit": {} })),
+            "Order.side::Limit.price: field is missing"
+        );
+        assert_eq!(
+            with("/owner", json!("not-a-key")),
+            "Order.owner: `not-a-key` is invalid base58: '-' at position 3 is not a base58 character"
+        );
+
+        let mut json = order();
+        json["extra"] = json!(1);
+        assert_eq!(encode(&json).unwrap_err().message, "unknown field `extra`");
+    }
+
+    #[test]
+    fn encodes_fixed_arrays_without_a_length_prefix() {
+        let ir =
+            transform_to_ir(parse_lumos_file("struct Seed { bytes: [u8; 3] }").unwrap()).unwrap();
+        let seed = TypeInfo::UserDefined("Seed".into());
+        let encoder = BorshEncoder::new(&ir);
+
+        let bytes = encoder
+            .encode_json(&seed, &json!({ "bytes": [1, 2, 3] }), "Seed")
+            .unwrap();
+        assert_eq!(bytes, [1, 2, 3]);
+        let (value, end) = BorshDecoder::new(&ir)
+            .decode(&seed, &bytes, 0, "Seed")
+            .unwrap();
+        assert_eq!((value.to_json(), end), (json!({ "bytes": [1, 2, 3] }), 3));
+
+        let err = encoder
+            .encode_json(&seed, &json!({ "bytes": [1, 2] }), "Seed")
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Seed.bytes: expected 3 elements, found 2");
+    }
+
+    #[test]
+    fn encodes_maps_in_key_order() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                "struct Book { levels: BTreeMap<u16, bool>, flags: HashMap<bool, u8> }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let book = TypeInfo::UserDefined("Book".into());
+        let json = json!({ "levels": { "10": true, "2": false }, "flags": { "true": 1 } });
+
+        let bytes = BorshEncoder::new(&ir)
+            .encode_json(&book, &json, "Book")
+            .unwrap();
+        assert_eq!(bytes, [2, 0, 0, 0, 2, 0, 0, 10, 0, 1, 1, 0, 0, 0, 1, 1]);
+        let (value, _) = BorshDecoder::new(&ir)
+            .decode(&book, &bytes, 0, "Book")
+            .unwrap();
+        assert_eq!(value.to_json(), json);
+
+        let err = BorshEncoder::new(&ir)
+            .encode_json(
+                &book,
+                &json!({ "levels": { "1": true, "01": false }, "flags": {} }),
+                "Book",
+            )
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Book.levels: duplicate key `1`");
+    }
+}
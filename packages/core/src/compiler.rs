@@ -0,0 +1,260 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Shared compiler for long-running hosts
+//!
+//! Language servers and playground backends see the same schema text many
+//! times. A [`Compiler`] interns each successfully compiled source, so
+//! repeated requests share one [`Compilation`], whose sizes, findings and
+//! generated code are computed on first use. Every method takes `&self` and
+//! the compiler is `Send + Sync`, so one instance can serve all requests
+//! (e.g. behind an `Arc`).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::compiler::Compiler;
+//! use std::sync::Arc;
+//!
+//! let compiler = Compiler::new();
+//! let source = "#[solana]\n#[account]\nstruct Vault { owner: PublicKey, amount: u64 }";
+//!
+//! let first = compiler.compile(source)?;
+//! let second = compiler.compile(source)?;
+//! assert!(Arc::ptr_eq(&first, &second));
+//! assert_eq!(first.sizes()[0].total_bytes.min_bytes(), 8 + 32 + 8);
+//! # Ok::<(), lumos_core::error::LumosError>(())
+//! ```
+
+use crate::error::Result;
+use crate::generators::{rust, typescript};
+use crate::ir::TypeDefinition;
+use crate::panic_guard::guarded;
+use crate::parser::parse_lumos_file;
+use crate::security_analyzer::{SecurityAnalyzer, SecurityFinding};
+use crate::size_calculator::{AccountSize, SizeCalculator};
+use crate::transform::transform_to_ir;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Compiled schema with lazily computed analyses and generated code
+#[derive(Debug)]
+pub struct Compilation {
+    types: Vec<TypeDefinition>,
+    sizes: OnceLock<Vec<AccountSize>>,
+    findings: OnceLock<Vec<SecurityFinding>>,
+    rust: OnceLock<String>,
+    typescript: OnceLock<String>,
+}
+
+impl Compilation {
+    fn new(types: Vec<TypeDefinition>) -> Self {
+        Self {
+            types,
+            sizes: OnceLock::new(),
+            findings: OnceLock::new(),
+            rust: OnceLock::new(),
+            typescript: OnceLock::new(),
+        }
+    }
+
+    /// Type definitions (IR)
+    pub fn types(&self) -> &[TypeDefinition] {
+        &self.types
+    }
+
+    /// Size of every type
+    pub fn sizes(&self) -> &[AccountSize] {
+        self.sizes
+            .get_or_init(|| SizeCalculator::new(&self.types).calculate_all())
+    }
+
+    /// Security findings in the default (non-strict) mode
+    pub fn findings(&self) -> &[SecurityFinding] {
+        self.findings
+            .get_or_init(|| SecurityAnalyzer::new(&self.types).analyze())
+    }
+
+    /// Generated Rust module, without a program id
+    pub fn rust(&self) -> &str {
+        self.rust.get_or_init(|| rust::generate_module(&self.types))
+    }
+
+    /// Generated TypeScript module
+    pub fn typescript(&self) -> &str {
+        self.typescript
+            .get_or_init(|| typescript::generate_module(&self.types))
+    }
+}
+
+/// Interned compilations, oldest first in `order`
+#[derive(Default)]
+struct Interned {
+    compilations: HashMap<Arc<str>, Arc<Compilation>>,
+    order: VecDeque<Arc<str>>,
+}
+
+/// Thread-safe compiler that reuses compilations of identical sources
+pub struct Compiler {
+    capacity: usize,
+    interned: Mutex<Interned>,
+}
+
+impl Compiler {
+    /// Sources kept by [`Compiler::new`]
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    /// Create a compiler keeping the [`DEFAULT_CAPACITY`](Self::DEFAULT_CAPACITY)
+    /// most recently compiled sources
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    /// Create a compiler keeping up to `capacity` sources
+    ///
+    /// When full, the source compiled least recently is evicted; callers
+    /// still holding its [`Compilation`] keep it alive.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            interned: Mutex::new(Interned::default()),
+        }
+    }
+
+    /// Compile `source`, or return the compilation of an identical source
+    ///
+    /// Failed compilations are not kept, so fixing a schema never returns a
+    /// stale error. A panic while compiling is returned as
+    /// [`crate::error::LumosError::Internal`].
+    pub fn compile(&self, source: &str) -> Result<Arc<Compilation>> {
+        if let Some(compilation) = self.lookup(source) {
+            return Ok(compilation);
+        }
+
+        // Compile without holding the lock; a concurrent compile of the same
+        // source produces an equal result, and the first one interned wins
+        let types = guarded("compile", source, || {
+            transform_to_ir(parse_lumos_file(source)?)
+        })?;
+        let compilation = Arc::new(Compilation::new(types));
+
+        let mut interned = self.lock();
+        if let Some(existing) = interned.compilations.get(source) {
+            return Ok(Arc::clone(existing));
+        }
+        let key: Arc<str> = Arc::from(source);
+        interned
+            .compilations
+            .insert(Arc::clone(&key), Arc::clone(&compilation));
+        interned.order.push_back(key);
+        while interned.order.len() > self.capacity {
+            if let Some(evicted) = interned.order.pop_front() {
+                interned.compilations.remove(&evicted);
+            }
+        }
+
+        Ok(compilation)
+    }
+
+    /// Number of interned sources
+    pub fn len(&self) -> usize {
+        self.lock().compilations.len()
+    }
+
+    /// Whether no sources are interned
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop every interned compilation
+    pub fn clear(&self) {
+        *self.lock() = Interned::default();
+    }
+
+    /// Interned compilation of `source`, marked as most recently used
+    fn lookup(&self, source: &str) -> Option<Arc<Compilation>> {
+        let mut interned = self.lock();
+        let compilation = Arc::clone(interned.compilations.get(source)?);
+        if let Some(position) = interned.order.iter().position(|key| &**key == source) {
+            if let Some(key) = interned.order.remove(position) {
+                interned.order.push_back(key);
+            }
+        }
+        Some(compilation)
+    }
+
+    /// The interned state, recovering it if another thread panicked
+    fn lock(&self) -> std::sync::MutexGuard<'_, Interned> {
+        self.interned
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT: &str = "#[solana]\n#[account]\nstruct Vault { owner: PublicKey, amount: u64 }";
+
+    #[test]
+    fn compiler_is_shareable_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Compiler>();
+        assert_send_sync::<Compilation>();
+
+        let compiler = Arc::new(Compiler::new());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let compiler = Arc::clone(&compiler);
+                std::thread::spawn(move || compiler.compile(VAULT).unwrap().sizes()[0].clone())
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap().total_bytes.min_bytes(), 48);
+        }
+        assert_eq!(compiler.len(), 1);
+    }
+
+    #[test]
+    fn reuses_compilations_and_their_outputs() {
+        let compiler = Compiler::new();
+        let first = compiler.compile(VAULT).unwrap();
+        let second = compiler.compile(VAULT).unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(std::ptr::eq(first.rust(), second.rust()));
+        assert!(first.typescript().contains("export interface Vault"));
+        assert!(first.findings().len() == second.findings().len());
+    }
+
+    #[test]
+    fn evicts_least_recently_used_sources() {
+        let compiler = Compiler::with_capacity(2);
+        let a = compiler.compile("struct A { x: u8 }").unwrap();
+        compiler.compile("struct B { x: u8 }").unwrap();
+        compiler.compile("struct A { x: u8 }").unwrap();
+        compiler.compile("struct C { x: u8 }").unwrap();
+
+        assert_eq!(compiler.len(), 2);
+        assert!(Arc::ptr_eq(
+            &a,
+            &compiler.compile("struct A { x: u8 }").unwrap()
+        ));
+        assert_eq!(a.types()[0].name(), "A");
+    }
+
+    #[test]
+    fn does_not_keep_failed_compilations() {
+        let compiler = Compiler::new();
+        assert!(compiler.compile("struct Broken {").is_err());
+        assert!(compiler.is_empty());
+    }
+}
@@ -0,0 +1,362 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Constant folding
+//!
+//! Schemas can declare unsigned integer constants and use them, with simple
+//! arithmetic, wherever an attribute takes a number:
+//!
+//! ```text
+//! const MAX_NAME_LEN: u32 = 28;
+//!
+//! struct Profile {
+//!     #[max(MAX_NAME_LEN + 4)]
+//!     display_name: String,
+//! }
+//! ```
+//!
+//! Expressions may use integer literals, other constants (in any order),
+//! parentheses and `+ - * / % << >>`. Everything is folded to a plain integer
+//! during transform, so the IR and the generators only ever see literals.
+//! Overflow, division by zero, cycles and undefined names are errors.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::ast::{Attribute, AttributeValue, ConstDef, EnumVariant, Item, LumosFile};
+use crate::error::{LumosError, Result};
+
+/// Attributes whose argument is a number, and so may be an expression
+pub const NUMERIC_ATTRIBUTES: [&str; 4] = ["max", "min", "version", "since"];
+
+/// Evaluate every `const` item in the file
+pub fn evaluate(file: &LumosFile) -> Result<HashMap<String, u64>> {
+    let mut defs: HashMap<&str, &ConstDef> = HashMap::new();
+    for item in &file.items {
+        if let Item::Const(def) = item {
+            if defs.insert(&def.name, def).is_some() {
+                return Err(LumosError::TypeValidation(
+                    format!("const '{}' is defined more than once", def.name),
+                    file.location(&def.name, def.span),
+                ));
+            }
+        }
+    }
+
+    let mut folder = Folder {
+        file,
+        defs,
+        values: HashMap::new(),
+        stack: Vec::new(),
+    };
+    for item in &file.items {
+        if let Item::Const(def) = item {
+            folder.constant(&def.name)?;
+        }
+    }
+    Ok(folder.values)
+}
+
+/// Fold the constants and every numeric attribute expression into integers
+///
+/// `const` items stay in the file; attribute values such as
+/// `#[max(MAX_NAME_LEN + 4)]` become [`AttributeValue::Integer`].
+pub fn fold(file: &mut LumosFile) -> Result<()> {
+    let constants = evaluate(file)?;
+
+    for item in &mut file.items {
+        let origin = file.origins.get(item.name()).map(PathBuf::as_path);
+        let fold = |attributes: &mut [Attribute], location: &str| {
+            fold_attributes(attributes, &constants, location, origin)
+        };
+        match item {
+            Item::Struct(s) => {
+                fold(&mut s.attributes, &s.name)?;
+                for field in &mut s.fields {
+                    fold(&mut field.attributes, &format!("{}.{}", s.name, field.name))?;
+                }
+            }
+            Item::Enum(e) => {
+                fold(&mut e.attributes, &e.name)?;
+                for variant in &mut e.variants {
+                    if let EnumVariant::Struct { name, fields, .. } = variant {
+                        for field in fields {
+                            let location = format!("{}::{}.{}", e.name, name, field.name);
+                            fold(&mut field.attributes, &location)?;
+                        }
+                    }
+                }
+            }
+            Item::Const(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Fold the numeric attributes on `location`, defined in `file`
+fn fold_attributes(
+    attributes: &mut [Attribute],
+    constants: &HashMap<String, u64>,
+    location: &str,
+    file: Option<&Path>,
+) -> Result<()> {
+    for attr in attributes {
+        let error = |attr: &Attribute, reason: String| {
+            LumosError::TypeValidation(
+                format!("#[{}] on '{}': {}", attr.name, location, reason),
+                attr.span.map(|span| span.location(file)),
+            )
+        };
+        if attr.name == "range" {
+            if let Some(AttributeValue::String(source)) = &attr.value {
+                let (min, max) = eval_range(source, &mut |name| {
+                    constants
+                        .get(name)
+                        .copied()
+                        .ok_or_else(|| format!("undefined constant '{}'", name))
+                })
+                .map_err(|reason| error(attr, reason))?;
+                attr.value = Some(AttributeValue::String(format!("{}, {}", min, max)));
+            }
+            continue;
+        }
+        if !NUMERIC_ATTRIBUTES.contains(&attr.name.as_str()) {
+            continue;
+        }
+        if let Some(AttributeValue::String(source)) = &attr.value {
+            let value = eval_source(source, &mut |name| {
+                constants
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| format!("undefined constant '{}'", name))
+            })
+            .map_err(|reason| error(attr, reason))?;
+            attr.value = Some(AttributeValue::Integer(value));
+        }
+    }
+    Ok(())
+}
+
+struct Folder<'a> {
+    file: &'a LumosFile,
+    defs: HashMap<&'a str, &'a ConstDef>,
+    values: HashMap<String, u64>,
+    /// Constants being evaluated, to report cycles
+    stack: Vec<String>,
+}
+
+impl Folder<'_> {
+    fn constant(&mut self, name: &str) -> Result<u64> {
+        if let Some(value) = self.values.get(name) {
+            return Ok(*value);
+        }
+        let def = self.defs[name];
+        if self.stack.iter().any(|n| n == name) {
+            self.stack.push(name.to_string());
+            return Err(LumosError::TypeValidation(
+                format!(
+                    "const '{}' refers to itself: {}",
+                    name,
+                    self.stack.join(" -> ")
+                ),
+                self.file.location(name, def.span),
+            ));
+        }
+
+        self.stack.push(name.to_string());
+        let mut failure = None;
+        let result = eval_source(&def.value, &mut |reference| {
+            if !self.defs.contains_key(reference) {
+                return Err(format!("undefined constant '{}'", reference));
+            }
+            self.constant(reference).map_err(|err| {
+                let message = err.to_string();
+                failure = Some(err);
+                message
+            })
+        });
+        self.stack.pop();
+
+        let value = match (result, failure) {
+            (_, Some(err)) => return Err(err),
+            (Err(reason), None) => {
+                return Err(LumosError::TypeValidation(
+                    format!("const '{}': {}", name, reason),
+                    self.file.location(name, def.span),
+                ))
+            }
+            (Ok(value), None) => value,
+        };
+
+        let max = match def.type_name.as_str() {
+            "u8" => u8::MAX as u64,
+            "u16" => u16::MAX as u64,
+            "u32" => u32::MAX as u64,
+            _ => u64::MAX,
+        };
+        if value > max {
+            return Err(LumosError::TypeValidation(
+                format!("const '{}' = {} overflows {}", name, value, def.type_name),
+                self.file.location(name, def.span),
+            ));
+        }
+
+        self.values.insert(name.to_string(), value);
+        Ok(value)
+    }
+}
+
+fn eval_source(
+    source: &str,
+    lookup: &mut dyn FnMut(&str) -> std::result::Result<u64, String>,
+) -> std::result::Result<u64, String> {
+    let expr = syn::parse_str::<syn::Expr>(source)
+        .map_err(|_| format!("'{}' is not an integer expression", source))?;
+    eval(&expr, lookup)
+}
+
+/// Evaluate the `min, max` bounds of `#[range]`, which may be negated
+fn eval_range(
+    source: &str,
+    lookup: &mut dyn FnMut(&str) -> std::result::Result<u64, String>,
+) -> std::result::Result<(i128, i128), String> {
+    let expected = || format!("expected #[range(min, max)], found '{}'", source);
+    let tuple =
+        syn::parse_str::<syn::ExprTuple>(&format!("({})", source)).map_err(|_| expected())?;
+    let bounds = tuple
+        .elems
+        .iter()
+        .map(|bound| match bound {
+            syn::Expr::Unary(syn::ExprUnary {
+                op: syn::UnOp::Neg(_),
+                expr,
+                ..
+            }) => eval(expr, lookup).map(|value| -i128::from(value)),
+            bound => eval(bound, lookup).map(i128::from),
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    match bounds[..] {
+        [min, max] => Ok((min, max)),
+        _ => Err(expected()),
+    }
+}
+
+fn eval(
+    expr: &syn::Expr,
+    lookup: &mut dyn FnMut(&str) -> std::result::Result<u64, String>,
+) -> std::result::Result<u64, String> {
+    use syn::{BinOp, Expr, Lit};
+
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => int
+                .base10_parse::<u64>()
+                .map_err(|_| format!("{} doesn't fit in u64", int)),
+            _ => Err("only integer literals are allowed".to_string()),
+        },
+        Expr::Path(path) => match path.path.get_ident() {
+            Some(ident) => lookup(&ident.to_string()),
+            None => Err("constants must be plain names".to_string()),
+        },
+        Expr::Paren(paren) => eval(&paren.expr, lookup),
+        Expr::Group(group) => eval(&group.expr, lookup),
+        Expr::Binary(binary) => {
+            let left = eval(&binary.left, lookup)?;
+            let right = eval(&binary.right, lookup)?;
+            let (result, symbol) = match binary.op {
+                BinOp::Add(_) => (left.checked_add(right), "+"),
+                BinOp::Sub(_) => (left.checked_sub(right), "-"),
+                BinOp::Mul(_) => (left.checked_mul(right), "*"),
+                BinOp::Div(_) => (left.checked_div(right), "/"),
+                BinOp::Rem(_) => (left.checked_rem(right), "%"),
+                BinOp::Shl(_) => (
+                    u32::try_from(right)
+                        .ok()
+                        .and_then(|shift| left.checked_shl(shift))
+                        .filter(|value| value >> right == left),
+                    "<<",
+                ),
+                BinOp::Shr(_) => (
+                    u32::try_from(right)
+                        .ok()
+                        .and_then(|shift| left.checked_shr(shift)),
+                    ">>",
+                ),
+                _ => return Err("only + - * / % << >> are allowed".to_string()),
+            };
+            result.ok_or_else(|| match symbol {
+                "/" | "%" if right == 0 => format!("{} {} {} divides by zero", left, symbol, right),
+                "-" => format!("{} - {} is negative", left, right),
+                _ => format!("{} {} {} overflows u64", left, symbol, right),
+            })
+        }
+        _ => Err("only integers, constants, parentheses and arithmetic are allowed".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+
+    fn folded(source: &str) -> Result<LumosFile> {
+        let mut file = parse_lumos_file(source)?;
+        fold(&mut file)?;
+        Ok(file)
+    }
+
+    fn max_of(file: &LumosFile) -> Option<u64> {
+        file.items.iter().find_map(|item| match item {
+            Item::Struct(s) => s.fields[0].max_length(),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn folds_constants_into_numeric_attributes() {
+        let file = folded(
+            "struct Profile { #[max(MAX_NAME_LEN + 4)] name: String }\n\
+             const MAX_NAME_LEN: u32 = BASE * 2;\n\
+             const BASE: u8 = (10 + 4);",
+        )
+        .unwrap();
+        assert_eq!(max_of(&file), Some(32));
+
+        let file = folded("struct Seeds { #[max(32 * 2)] seed: String }").unwrap();
+        assert_eq!(max_of(&file), Some(64));
+
+        let file =
+            folded("const LIMIT: u16 = 500;\nstruct Fee { #[range(-LIMIT, LIMIT * 2)] bps: i32 }")
+                .unwrap();
+        let Item::Struct(fee) = &file.items[1] else {
+            unreachable!()
+        };
+        assert!(matches!(
+            &fee.fields[0].get_attribute("range").unwrap().value,
+            Some(AttributeValue::String(range)) if range == "-500, 1000"
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_expressions() {
+        let err = |source: &str| folded(source).unwrap_err().to_string();
+
+        assert!(err("struct A { #[max(LEN)] name: String }")
+            .contains("#[max] on 'A.name': undefined constant 'LEN'"));
+        assert!(err("const BIG: u8 = 200 + 100;\nstruct A { x: u8 }")
+            .contains("const 'BIG' = 300 overflows u8"));
+        assert!(
+            err("struct A { #[max(18446744073709551615 + 1)] name: String }")
+                .contains("overflows u64")
+        );
+        assert!(err("struct A { #[max(4 / (2 - 2))] name: String }").contains("divides by zero"));
+        assert!(
+            err("const A: u32 = B;\nconst B: u32 = A + 1;\nstruct S { x: u8 }")
+                .contains("refers to itself: A -> B -> A")
+        );
+        assert!(parse_lumos_file("const A: i32 = 1;\nstruct S { x: u8 }")
+            .unwrap_err()
+            .to_string()
+            .contains("unsigned integer type"));
+    }
+}
@@ -6,9 +6,10 @@
 //! Generates initial corpus files with valid serialized instances
 //! to seed the fuzzer with known-good inputs.
 
+use crate::encoding::encode_base58;
+use crate::fuzz_generator::DISPATCH_TYPE_NAME;
 use crate::ir::{
-    EnumDefinition, EnumVariantDefinition, StructDefinition, TypeDefinition,
-    TypeInfo,
+    EnumDefinition, EnumVariantDefinition, StructDefinition, TypeDefinition, TypeInfo,
 };
 
 /// Corpus file entry
@@ -31,12 +32,32 @@ pub struct CorpusFile {
 pub struct CorpusGenerator<'a> {
     /// All type definitions
     type_defs: &'a [TypeDefinition],
+
+    /// Number of seeded random instances to generate per type
+    random_count: usize,
+
+    /// Seed for random instances
+    seed: u64,
 }
 
 impl<'a> CorpusGenerator<'a> {
     /// Create a new corpus generator
     pub fn new(type_defs: &'a [TypeDefinition]) -> Self {
-        Self { type_defs }
+        Self {
+            type_defs,
+            random_count: 0,
+            seed: 0,
+        }
+    }
+
+    /// Also generate `count` pseudo-random instances per type from `seed`
+    ///
+    /// The same seed always produces the same bytes, and each type gets its
+    /// own stream so filtering by type doesn't change the output.
+    pub fn with_random_instances(mut self, count: usize, seed: u64) -> Self {
+        self.random_count = count;
+        self.seed = seed;
+        self
     }
 
     /// Generate corpus files for all types
@@ -54,6 +75,59 @@ impl<'a> CorpusGenerator<'a> {
             }
         }
 
+        files.extend(self.generate_dispatch_corpus());
+
+        files
+    }
+
+    /// Generate corpus files for the instruction dispatcher target
+    ///
+    /// Each entry is the instruction tag byte followed by minimal arguments.
+    /// Instruction enums get one entry per variant.
+    fn generate_dispatch_corpus(&self) -> Vec<CorpusFile> {
+        let mut files = Vec::new();
+
+        let instructions = self.type_defs.iter().filter(|t| t.is_instruction());
+        for (tag, type_def) in instructions.enumerate() {
+            let payloads: Vec<(String, Vec<u8>)> = match type_def {
+                TypeDefinition::Struct(s) => {
+                    let mut args = Vec::new();
+                    for field in &s.fields {
+                        args.extend(self.serialize_minimal_value(&field.type_info, field.optional));
+                    }
+                    vec![(to_snake_case(&s.name), args)]
+                }
+                TypeDefinition::Enum(e) => e
+                    .variants
+                    .iter()
+                    .enumerate()
+                    .map(|(index, variant)| {
+                        let file = self.generate_enum_variant_corpus(e, variant, index);
+                        (
+                            format!(
+                                "{}_{}",
+                                to_snake_case(&e.name),
+                                to_snake_case(variant.name())
+                            ),
+                            file.data,
+                        )
+                    })
+                    .collect(),
+            };
+
+            for (name, args) in payloads {
+                let mut data = vec![tag as u8];
+                data.extend(args);
+
+                files.push(CorpusFile {
+                    name: format!("dispatch_{}", name),
+                    type_name: DISPATCH_TYPE_NAME.to_string(),
+                    data,
+                    description: format!("Instruction {} with minimal arguments", type_def.name()),
+                });
+            }
+        }
+
         files
     }
 
@@ -72,19 +146,46 @@ impl<'a> CorpusGenerator<'a> {
         // Generate edge cases
         files.extend(self.generate_struct_edge_cases(struct_def));
 
+        // Generate seeded random instances
+        let mut rng = SeededRng::for_type(self.seed, &struct_def.name);
+        for index in 0..self.random_count {
+            files.push(self.generate_random_struct(struct_def, index, &mut rng));
+        }
+
         files
     }
 
+    /// Generate a seeded random struct instance with plausible values
+    fn generate_random_struct(
+        &self,
+        struct_def: &StructDefinition,
+        index: usize,
+        rng: &mut SeededRng,
+    ) -> CorpusFile {
+        let mut data = Vec::new();
+
+        if struct_def.metadata.is_account() {
+            data.extend_from_slice(&[0u8; 8]);
+        }
+
+        for field in &struct_def.fields {
+            data.extend(self.serialize_random_value(&field.type_info, &field.name, rng, 0));
+        }
+
+        CorpusFile {
+            name: format!("{}_random_{}", to_snake_case(&struct_def.name), index),
+            type_name: struct_def.name.clone(),
+            data,
+            description: format!("Random instance #{} (seed {})", index, self.seed),
+        }
+    }
+
     /// Generate minimal struct corpus (zero values)
     fn generate_minimal_struct(&self, struct_def: &StructDefinition) -> CorpusFile {
         let mut data = Vec::new();
 
         // Add Anchor discriminator if it's an account
-        if struct_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-        {
+        if struct_def.metadata.is_account() {
             // 8-byte discriminator (zeros for corpus)
             data.extend_from_slice(&[0u8; 8]);
         }
@@ -107,11 +208,7 @@ impl<'a> CorpusGenerator<'a> {
         let mut data = Vec::new();
 
         // Add Anchor discriminator if it's an account
-        if struct_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-        {
+        if struct_def.metadata.is_account() {
             data.extend_from_slice(&[0u8; 8]);
         }
 
@@ -161,11 +258,7 @@ impl<'a> CorpusGenerator<'a> {
     fn generate_optional_none_case(&self, struct_def: &StructDefinition) -> CorpusFile {
         let mut data = Vec::new();
 
-        if struct_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-        {
+        if struct_def.metadata.is_account() {
             data.extend_from_slice(&[0u8; 8]);
         }
 
@@ -190,11 +283,7 @@ impl<'a> CorpusGenerator<'a> {
     fn generate_optional_some_case(&self, struct_def: &StructDefinition) -> CorpusFile {
         let mut data = Vec::new();
 
-        if struct_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-        {
+        if struct_def.metadata.is_account() {
             data.extend_from_slice(&[0u8; 8]);
         }
 
@@ -220,11 +309,7 @@ impl<'a> CorpusGenerator<'a> {
     fn generate_empty_vec_case(&self, struct_def: &StructDefinition) -> CorpusFile {
         let mut data = Vec::new();
 
-        if struct_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-        {
+        if struct_def.metadata.is_account() {
             data.extend_from_slice(&[0u8; 8]);
         }
 
@@ -249,11 +334,7 @@ impl<'a> CorpusGenerator<'a> {
     fn generate_single_elem_vec_case(&self, struct_def: &StructDefinition) -> CorpusFile {
         let mut data = Vec::new();
 
-        if struct_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-        {
+        if struct_def.metadata.is_account() {
             data.extend_from_slice(&[0u8; 8]);
         }
 
@@ -285,6 +366,17 @@ impl<'a> CorpusGenerator<'a> {
             files.push(self.generate_enum_variant_corpus(enum_def, variant, index));
         }
 
+        // Generate seeded random instances
+        let mut rng = SeededRng::for_type(self.seed, &enum_def.name);
+        for index in 0..self.random_count {
+            files.push(CorpusFile {
+                name: format!("{}_random_{}", to_snake_case(&enum_def.name), index),
+                type_name: enum_def.name.clone(),
+                data: self.serialize_random_enum(enum_def, &mut rng, 0),
+                description: format!("Random instance #{} (seed {})", index, self.seed),
+            });
+        }
+
         files
     }
 
@@ -295,50 +387,61 @@ impl<'a> CorpusGenerator<'a> {
         variant: &EnumVariantDefinition,
         discriminant: usize,
     ) -> CorpusFile {
-        let mut data = Vec::new();
+        let data = self.serialize_variant(variant, discriminant, |type_info| {
+            self.serialize_minimal_value(type_info, false)
+        });
 
-        // Borsh encodes enum discriminant as u32 (little-endian)
-        data.extend_from_slice(&(discriminant as u32).to_le_bytes());
+        CorpusFile {
+            name: format!(
+                "{}_{}_variant",
+                to_snake_case(&enum_def.name),
+                to_snake_case(variant.name())
+            ),
+            type_name: enum_def.name.clone(),
+            data,
+            description: format!("Enum variant: {}", variant.name()),
+        }
+    }
+
+    /// Serialize an enum variant, its payload values produced by `value`
+    fn serialize_variant(
+        &self,
+        variant: &EnumVariantDefinition,
+        discriminant: usize,
+        value: impl Fn(&TypeInfo) -> Vec<u8>,
+    ) -> Vec<u8> {
+        // Borsh encodes the enum discriminant as a single u8
+        let mut data = vec![discriminant as u8];
 
-        // Encode variant data
         match variant {
-            EnumVariantDefinition::Unit { .. } => {
-                // Unit variant has no data
-            }
+            EnumVariantDefinition::Unit { .. } => {}
             EnumVariantDefinition::Tuple { types, .. } => {
-                // Tuple variant: serialize each field
                 for field in types {
-                    data.extend(self.serialize_minimal_value(field, false));
+                    data.extend(value(field));
                 }
             }
             EnumVariantDefinition::Struct { fields, .. } => {
-                // Struct variant: serialize each field
                 for field in fields {
-                    data.extend(self.serialize_minimal_value(&field.type_info, field.optional));
+                    data.extend(value(&field.type_info));
                 }
             }
         }
 
-        CorpusFile {
-            name: format!(
-                "{}_{}_variant",
-                to_snake_case(&enum_def.name),
-                to_snake_case(&variant.name())
-            ),
-            type_name: enum_def.name.clone(),
-            data,
-            description: format!("Enum variant: {}", variant.name()),
-        }
+        data
     }
 
     /// Serialize a minimal value for a given type
     fn serialize_minimal_value(&self, type_info: &TypeInfo, _optional: bool) -> Vec<u8> {
         match type_info {
             TypeInfo::Primitive(name) => self.serialize_minimal_primitive(name),
-            TypeInfo::Array(_) => {
-                // Empty vec (length = 0)
+            TypeInfo::Array(_) | TypeInfo::Map(..) => {
+                // Empty vec or map (length = 0)
                 vec![0, 0, 0, 0]
             }
+            TypeInfo::FixedArray(inner, len) => {
+                // Every element, minimal
+                self.serialize_minimal_value(inner, false).repeat(*len)
+            }
             TypeInfo::Option(_) => {
                 // None
                 vec![0]
@@ -351,14 +454,19 @@ impl<'a> CorpusGenerator<'a> {
                             let mut data = Vec::new();
                             // Serialize each field with minimal values
                             for field in &s.fields {
-                                data.extend(self.serialize_minimal_value(&field.type_info, field.optional));
+                                data.extend(
+                                    self.serialize_minimal_value(&field.type_info, field.optional),
+                                );
                             }
                             data
                         }
-                        TypeDefinition::Enum(_) => {
-                            // Minimal enum is first variant (discriminant = 0 in u32)
-                            vec![0, 0, 0, 0]
-                        }
+                        TypeDefinition::Enum(e) => match e.variants.first() {
+                            // Minimal enum is the first variant with minimal values
+                            Some(variant) => self.serialize_variant(variant, 0, |type_info| {
+                                self.serialize_minimal_value(type_info, false)
+                            }),
+                            None => vec![],
+                        },
                     }
                 } else {
                     // Unknown type - return empty bytes as fallback
@@ -380,6 +488,17 @@ impl<'a> CorpusGenerator<'a> {
                 }
                 data
             }
+            TypeInfo::Map(_, key, value) => {
+                // One entry, maximal; more would need distinct keys
+                let mut data = vec![1, 0, 0, 0]; // length = 1
+                data.extend(self.serialize_maximal_value(key, false));
+                data.extend(self.serialize_maximal_value(value, false));
+                data
+            }
+            TypeInfo::FixedArray(inner, len) => {
+                // Every element, maximal
+                self.serialize_maximal_value(inner, false).repeat(*len)
+            }
             TypeInfo::Option(inner) => {
                 // Some(max_value)
                 let mut data = vec![1]; // Some
@@ -394,15 +513,19 @@ impl<'a> CorpusGenerator<'a> {
                             let mut data = Vec::new();
                             // Serialize each field with maximal values
                             for field in &s.fields {
-                                data.extend(self.serialize_maximal_value(&field.type_info, field.optional));
+                                data.extend(
+                                    self.serialize_maximal_value(&field.type_info, field.optional),
+                                );
                             }
                             data
                         }
-                        TypeDefinition::Enum(_) => {
-                            // Maximal enum is first variant with max values (discriminant = 0 in u32)
-                            // For simplicity, just use discriminant 0 like minimal
-                            vec![0, 0, 0, 0]
-                        }
+                        TypeDefinition::Enum(e) => match e.variants.first() {
+                            // Maximal enum is the first variant with maximal values
+                            Some(variant) => self.serialize_variant(variant, 0, |type_info| {
+                                self.serialize_maximal_value(type_info, false)
+                            }),
+                            None => vec![],
+                        },
                     }
                 } else {
                     // Unknown type - return empty bytes as fallback
@@ -412,6 +535,167 @@ impl<'a> CorpusGenerator<'a> {
         }
     }
 
+    /// Serialize a random value with a plausible shape for a given type
+    ///
+    /// `field_name` is used as a hint, e.g. fields that look like timestamps
+    /// get realistic Unix times. `depth` bounds recursion through
+    /// self-referencing user-defined types.
+    fn serialize_random_value(
+        &self,
+        type_info: &TypeInfo,
+        field_name: &str,
+        rng: &mut SeededRng,
+        depth: usize,
+    ) -> Vec<u8> {
+        const MAX_DEPTH: usize = 4;
+
+        match type_info {
+            TypeInfo::Primitive(name) => self.serialize_random_primitive(name, field_name, rng),
+            TypeInfo::Array(inner) => {
+                let len = if depth >= MAX_DEPTH {
+                    0
+                } else {
+                    rng.range(1, 5) as u32
+                };
+                let mut data = len.to_le_bytes().to_vec();
+                for _ in 0..len {
+                    data.extend(self.serialize_random_value(inner, field_name, rng, depth + 1));
+                }
+                data
+            }
+            TypeInfo::FixedArray(inner, len) => (0..*len)
+                .flat_map(|_| self.serialize_random_value(inner, field_name, rng, depth + 1))
+                .collect(),
+            TypeInfo::Map(_, key, value) => {
+                // At most one entry, so keys never repeat
+                if depth >= MAX_DEPTH || rng.next_u64() % 2 == 0 {
+                    vec![0, 0, 0, 0]
+                } else {
+                    let mut data = vec![1, 0, 0, 0];
+                    data.extend(self.serialize_random_value(key, field_name, rng, depth + 1));
+                    data.extend(self.serialize_random_value(value, field_name, rng, depth + 1));
+                    data
+                }
+            }
+            TypeInfo::Option(inner) => {
+                if depth >= MAX_DEPTH || rng.next_u64() % 2 == 0 {
+                    vec![0]
+                } else {
+                    let mut data = vec![1];
+                    data.extend(self.serialize_random_value(inner, field_name, rng, depth + 1));
+                    data
+                }
+            }
+            TypeInfo::UserDefined(type_name) => {
+                match self.type_defs.iter().find(|t| t.name() == type_name) {
+                    Some(TypeDefinition::Struct(s)) => {
+                        let mut data = Vec::new();
+                        for field in &s.fields {
+                            data.extend(self.serialize_random_value(
+                                &field.type_info,
+                                &field.name,
+                                rng,
+                                depth + 1,
+                            ));
+                        }
+                        data
+                    }
+                    Some(TypeDefinition::Enum(e)) => self.serialize_random_enum(e, rng, depth + 1),
+                    // Unknown type - return empty bytes as fallback
+                    None => vec![],
+                }
+            }
+        }
+    }
+
+    /// Serialize a random variant of an enum
+    fn serialize_random_enum(
+        &self,
+        enum_def: &EnumDefinition,
+        rng: &mut SeededRng,
+        depth: usize,
+    ) -> Vec<u8> {
+        if enum_def.variants.is_empty() {
+            return vec![];
+        }
+
+        let discriminant = rng.range(0, enum_def.variants.len() as u64) as usize;
+        let mut data = vec![discriminant as u8];
+
+        match &enum_def.variants[discriminant] {
+            EnumVariantDefinition::Unit { .. } => {}
+            EnumVariantDefinition::Tuple { name, types } => {
+                for field in types {
+                    data.extend(self.serialize_random_value(field, name, rng, depth));
+                }
+            }
+            EnumVariantDefinition::Struct { fields, .. } => {
+                for field in fields {
+                    data.extend(self.serialize_random_value(
+                        &field.type_info,
+                        &field.name,
+                        rng,
+                        depth,
+                    ));
+                }
+            }
+        }
+
+        data
+    }
+
+    /// Serialize a random primitive value
+    fn serialize_random_primitive(
+        &self,
+        type_name: &str,
+        field_name: &str,
+        rng: &mut SeededRng,
+    ) -> Vec<u8> {
+        match type_name {
+            "bool" => vec![(rng.next_u64() % 2) as u8],
+            "i64" | "u64" if looks_like_timestamp(field_name) => rng
+                .range(TIMESTAMP_RANGE.0, TIMESTAMP_RANGE.1)
+                .to_le_bytes()
+                .to_vec(),
+            "u8" => vec![rng.mid_range(8) as u8],
+            "i8" => vec![rng.signed_mid_range(8) as i8 as u8],
+            "u16" => (rng.mid_range(16) as u16).to_le_bytes().to_vec(),
+            "i16" => (rng.signed_mid_range(16) as i16).to_le_bytes().to_vec(),
+            "u32" => (rng.mid_range(32) as u32).to_le_bytes().to_vec(),
+            "i32" => (rng.signed_mid_range(32) as i32).to_le_bytes().to_vec(),
+            "u64" => (rng.mid_range(64) as u64).to_le_bytes().to_vec(),
+            "i64" => (rng.signed_mid_range(64) as i64).to_le_bytes().to_vec(),
+            "u128" => rng.mid_range(128).to_le_bytes().to_vec(),
+            "i128" => rng.signed_mid_range(128).to_le_bytes().to_vec(),
+            "f32" => ((rng.range(0, 1_000_000) as f32) / 1000.0)
+                .to_le_bytes()
+                .to_vec(),
+            "f64" => ((rng.range(0, 1_000_000) as f64) / 1000.0)
+                .to_le_bytes()
+                .to_vec(),
+            // Any 32 bytes form a valid pubkey; avoid the all-zero default key
+            "Pubkey" | "PublicKey" => {
+                let mut key = rng.bytes(32);
+                key[0] |= 1;
+                key
+            }
+            // Generated types hold signatures as base58 `String`s
+            "Signature" => serialize_signature(&rng.bytes(64)),
+            "String" => {
+                const ALPHABET: &[u8] =
+                    b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+                let len = rng.range(1, 33) as usize;
+                let text: Vec<u8> = (0..len)
+                    .map(|_| ALPHABET[rng.range(0, ALPHABET.len() as u64) as usize])
+                    .collect();
+                let mut data = (text.len() as u32).to_le_bytes().to_vec();
+                data.extend(text);
+                data
+            }
+            _ => vec![],
+        }
+    }
+
     /// Serialize minimal primitive value
     fn serialize_minimal_primitive(&self, type_name: &str) -> Vec<u8> {
         match type_name {
@@ -422,7 +706,7 @@ impl<'a> CorpusGenerator<'a> {
             "u64" | "i64" | "f64" => vec![0, 0, 0, 0, 0, 0, 0, 0],
             "u128" | "i128" => vec![0; 16],
             "Pubkey" | "PublicKey" => vec![0; 32],
-            "Signature" => vec![0; 64],
+            "Signature" => serialize_signature(&[0; 64]),
             "String" => {
                 // Empty string: length 0
                 vec![0, 0, 0, 0]
@@ -460,7 +744,7 @@ impl<'a> CorpusGenerator<'a> {
                 max.to_le_bytes().to_vec()
             }
             "Pubkey" | "PublicKey" => vec![255; 32],
-            "Signature" => vec![255; 64],
+            "Signature" => serialize_signature(&[255; 64]),
             "String" => {
                 // String with 100 'A' characters
                 let s = "A".repeat(100);
@@ -473,6 +757,80 @@ impl<'a> CorpusGenerator<'a> {
     }
 }
 
+/// Borsh encoding of a signature as the base58 `String` generated types hold
+fn serialize_signature(bytes: &[u8]) -> Vec<u8> {
+    let text = encode_base58(bytes);
+    let mut data = (text.len() as u32).to_le_bytes().to_vec();
+    data.extend(text.as_bytes());
+    data
+}
+
+/// Unix timestamps between 2020-09 and 2030-03
+pub(crate) const TIMESTAMP_RANGE: (u64, u64) = (1_600_000_000, 1_900_000_000);
+
+/// Whether a field name suggests a Unix timestamp (`created_at`, `deadline`, ...)
+pub(crate) fn looks_like_timestamp(field_name: &str) -> bool {
+    let lower = field_name.to_lowercase();
+    lower.contains("time")
+        || lower.ends_with("_at")
+        || lower.contains("date")
+        || lower.contains("deadline")
+}
+
+/// Small deterministic PRNG (SplitMix64) so corpus output is reproducible
+pub(crate) struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    /// Create a generator for one type, mixing the type name into the seed
+    pub(crate) fn for_type(seed: u64, type_name: &str) -> Self {
+        // FNV-1a hash of the type name
+        let hash = type_name.bytes().fold(0xcbf2_9ce4_8422_2325u64, |acc, b| {
+            (acc ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+        Self { state: seed ^ hash }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Random value in `low..high`
+    pub(crate) fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next_u64() % (high - low).max(1)
+    }
+
+    /// Random unsigned value in the middle half of a `bits`-wide integer
+    fn mid_range(&mut self, bits: u32) -> u128 {
+        let max = if bits == 128 {
+            u128::MAX
+        } else {
+            (1u128 << bits) - 1
+        };
+        let wide = ((self.next_u64() as u128) << 64) | self.next_u64() as u128;
+        max / 4 + wide % (max / 2)
+    }
+
+    /// Random signed value whose magnitude is in the middle half of the positive range
+    fn signed_mid_range(&mut self, bits: u32) -> i128 {
+        let magnitude = (self.mid_range(bits - 1)) as i128;
+        if self.next_u64() % 2 == 0 {
+            magnitude
+        } else {
+            -magnitude
+        }
+    }
+
+    pub(crate) fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next_u64() as u8).collect()
+    }
+}
+
 /// Convert PascalCase to snake_case
 fn to_snake_case(s: &str) -> String {
     let mut result = String::new();
@@ -497,7 +855,7 @@ fn to_snake_case(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{FieldDefinition, Metadata};
+    use crate::ir::{FieldDefinition, Metadata, TypeRole};
 
     #[test]
     fn test_generates_minimal_struct_corpus() {
@@ -507,6 +865,7 @@ mod tests {
                 name: "value".to_string(),
                 type_info: TypeInfo::Primitive("u32".to_string()),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata::default(),
         })];
@@ -515,10 +874,7 @@ mod tests {
         let corpus = generator.generate_all();
 
         assert!(!corpus.is_empty());
-        let minimal = corpus
-            .iter()
-            .find(|c| c.name.contains("minimal"))
-            .unwrap();
+        let minimal = corpus.iter().find(|c| c.name.contains("minimal")).unwrap();
 
         // u32 minimal value: 4 bytes of zeros
         assert_eq!(minimal.data, vec![0, 0, 0, 0]);
@@ -532,20 +888,25 @@ mod tests {
                 name: "value".to_string(),
                 type_info: TypeInfo::Primitive("u8".to_string()),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata {
                 solana: true,
                 attributes: vec!["account".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         })];
 
         let generator = CorpusGenerator::new(&type_defs);
         let corpus = generator.generate_all();
 
-        let minimal = corpus
-            .iter()
-            .find(|c| c.name.contains("minimal"))
-            .unwrap();
+        let minimal = corpus.iter().find(|c| c.name.contains("minimal")).unwrap();
 
         // Should have 8-byte discriminator + 1 byte for u8 field
         assert_eq!(minimal.data.len(), 9);
@@ -560,6 +921,7 @@ mod tests {
                 name: "maybe_value".to_string(),
                 type_info: TypeInfo::Option(Box::new(TypeInfo::Primitive("u32".to_string()))),
                 optional: true,
+                attributes: vec![],
             }],
             metadata: Metadata::default(),
         })];
@@ -592,6 +954,7 @@ mod tests {
                 name: "items".to_string(),
                 type_info: TypeInfo::Array(Box::new(TypeInfo::Primitive("u8".to_string()))),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata::default(),
         })];
@@ -621,7 +984,7 @@ mod tests {
             name: "SimpleEnum".to_string(),
             variants: vec![
                 EnumVariantDefinition::Unit {
-                    name: "Variant1".to_string()
+                    name: "Variant1".to_string(),
                 },
                 EnumVariantDefinition::Tuple {
                     name: "Variant2".to_string(),
@@ -636,11 +999,155 @@ mod tests {
 
         assert_eq!(corpus.len(), 2); // One per variant
 
-        // Variant1: discriminant 0 (u32)
-        assert_eq!(corpus[0].data, vec![0, 0, 0, 0]);
+        // Variant1: discriminant 0 (u8)
+        assert_eq!(corpus[0].data, vec![0]);
 
         // Variant2: discriminant 1 + u32 value
-        assert_eq!(corpus[1].data.len(), 8);
-        assert_eq!(&corpus[1].data[0..4], &[1, 0, 0, 0]); // discriminant
+        assert_eq!(corpus[1].data, vec![1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_corpus_decodes_as_borsh() {
+        use borsh::BorshDeserialize;
+
+        #[derive(BorshDeserialize)]
+        #[allow(dead_code)]
+        enum Side {
+            Bid(u32),
+            Ask { price: u64 },
+        }
+
+        #[derive(BorshDeserialize)]
+        #[allow(dead_code)]
+        struct Fill {
+            sig: String,
+            side: Side,
+            sides: Vec<Side>,
+        }
+
+        let type_defs = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "struct Fill { sig: Signature, side: Side, sides: [Side] }\n\
+                 enum Side { Bid(u32), Ask { price: u64 } }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let corpus = CorpusGenerator::new(&type_defs)
+            .with_random_instances(8, 42)
+            .generate_all();
+
+        for file in corpus.iter().filter(|c| c.type_name == "Fill") {
+            let fill = Fill::try_from_slice(&file.data)
+                .unwrap_or_else(|e| panic!("{} does not decode: {}", file.name, e));
+            assert!(!fill.sig.is_empty());
+        }
+        for file in corpus.iter().filter(|c| c.type_name == "Side") {
+            Side::try_from_slice(&file.data)
+                .unwrap_or_else(|e| panic!("{} does not decode: {}", file.name, e));
+        }
+
+        let random = corpus.iter().find(|c| c.name == "fill_random_0").unwrap();
+        let fill = Fill::try_from_slice(&random.data).unwrap();
+        assert!((86..=88).contains(&fill.sig.len()));
+        assert!(fill.sig.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn test_random_instances_are_seeded() {
+        let type_defs = vec![TypeDefinition::Struct(StructDefinition {
+            name: "Listing".to_string(),
+            fields: vec![
+                FieldDefinition {
+                    name: "seller".to_string(),
+                    type_info: TypeInfo::Primitive("PublicKey".to_string()),
+                    optional: false,
+                    attributes: vec![],
+                },
+                FieldDefinition {
+                    name: "created_at".to_string(),
+                    type_info: TypeInfo::Primitive("i64".to_string()),
+                    optional: false,
+                    attributes: vec![],
+                },
+            ],
+            metadata: Metadata::default(),
+        })];
+
+        let random = |seed| -> Vec<CorpusFile> {
+            CorpusGenerator::new(&type_defs)
+                .with_random_instances(3, seed)
+                .generate_all()
+                .into_iter()
+                .filter(|c| c.name.contains("_random_"))
+                .collect()
+        };
+
+        let first = random(7);
+        assert_eq!(first.len(), 3);
+        assert_eq!(first[0].name, "listing_random_0");
+        assert_eq!(first[0].data.len(), 40);
+
+        // Same seed, same bytes; different seed, different bytes
+        assert_eq!(first[0].data, random(7)[0].data);
+        assert_ne!(first[0].data, random(8)[0].data);
+
+        // Pubkey is not the default key and the timestamp is realistic
+        assert_ne!(&first[0].data[..32], &[0u8; 32]);
+        let timestamp = i64::from_le_bytes(first[0].data[32..40].try_into().unwrap());
+        assert!((1_600_000_000..1_900_000_000).contains(&timestamp));
+    }
+
+    #[test]
+    fn test_generates_dispatch_corpus_per_instruction() {
+        let type_defs = vec![
+            TypeDefinition::Struct(StructDefinition {
+                name: "Deposit".to_string(),
+                fields: vec![FieldDefinition {
+                    name: "amount".to_string(),
+                    type_info: TypeInfo::Primitive("u64".to_string()),
+                    optional: false,
+                    attributes: vec![],
+                }],
+                metadata: Metadata {
+                    solana: true,
+                    attributes: vec!["instruction".to_string()],
+                    role: TypeRole::Instruction,
+                    zero_copy: false,
+                    version: None,
+                    lifecycle: Default::default(),
+                    skip: Vec::new(),
+                    docs: Vec::new(),
+                    test_vectors: Vec::new(),
+                },
+            }),
+            TypeDefinition::Struct(StructDefinition {
+                name: "Close".to_string(),
+                fields: vec![],
+                metadata: Metadata {
+                    solana: true,
+                    attributes: vec!["instruction".to_string()],
+                    role: TypeRole::Instruction,
+                    zero_copy: false,
+                    version: None,
+                    lifecycle: Default::default(),
+                    skip: Vec::new(),
+                    docs: Vec::new(),
+                    test_vectors: Vec::new(),
+                },
+            }),
+        ];
+
+        let corpus = CorpusGenerator::new(&type_defs).generate_all();
+        let dispatch: Vec<_> = corpus
+            .iter()
+            .filter(|c| c.type_name == DISPATCH_TYPE_NAME)
+            .collect();
+
+        assert_eq!(dispatch.len(), 2);
+        assert_eq!(dispatch[0].name, "dispatch_deposit");
+        assert_eq!(dispatch[0].data, vec![0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(dispatch[1].data, vec![1]);
     }
 }
@@ -0,0 +1,160 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Shared criticality model for analysis outputs
+//!
+//! Security findings use [`Severity`] and audit checklist items use
+//! [`Priority`]. Both map onto [`Criticality`], which gives tooling a single
+//! ordered scale with a stable JSON representation.
+
+use crate::audit_generator::Priority;
+use crate::security_analyzer::Severity;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How urgently an analysis result needs attention
+///
+/// Ordered from least to most critical, so `Criticality::Critical` is the maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Criticality {
+    /// Best practice or informational note
+    Low,
+
+    /// Should be reviewed
+    Medium,
+
+    /// Should be fixed before release
+    High,
+
+    /// Must be fixed before deployment
+    Critical,
+}
+
+impl Criticality {
+    /// All levels, most critical first
+    pub const ALL: [Criticality; 4] = [
+        Criticality::Critical,
+        Criticality::High,
+        Criticality::Medium,
+        Criticality::Low,
+    ];
+
+    /// Get string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Criticality::Low => "LOW",
+            Criticality::Medium => "MEDIUM",
+            Criticality::High => "HIGH",
+            Criticality::Critical => "CRITICAL",
+        }
+    }
+
+    /// Get emoji representation
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Criticality::Low => "⚪",
+            Criticality::Medium => "🟢",
+            Criticality::High => "🟡",
+            Criticality::Critical => "🔴",
+        }
+    }
+
+    /// Parse from a string (case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "LOW" | "INFO" => Some(Criticality::Low),
+            "MEDIUM" | "WARNING" => Some(Criticality::Medium),
+            "HIGH" => Some(Criticality::High),
+            "CRITICAL" => Some(Criticality::Critical),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Criticality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&Severity> for Criticality {
+    fn from(severity: &Severity) -> Self {
+        match severity {
+            Severity::Info => Criticality::Low,
+            Severity::Warning => Criticality::Medium,
+            Severity::Critical => Criticality::Critical,
+        }
+    }
+}
+
+impl From<&Priority> for Criticality {
+    fn from(priority: &Priority) -> Self {
+        match priority {
+            Priority::Low => Criticality::Low,
+            Priority::Medium => Criticality::Medium,
+            Priority::High => Criticality::High,
+            Priority::Critical => Criticality::Critical,
+        }
+    }
+}
+
+impl From<Criticality> for Priority {
+    fn from(criticality: Criticality) -> Self {
+        match criticality {
+            Criticality::Low => Priority::Low,
+            Criticality::Medium => Priority::Medium,
+            Criticality::High => Priority::High,
+            Criticality::Critical => Priority::Critical,
+        }
+    }
+}
+
+impl From<Criticality> for Severity {
+    fn from(criticality: Criticality) -> Self {
+        match criticality {
+            Criticality::Low => Severity::Info,
+            Criticality::Medium | Criticality::High => Severity::Warning,
+            Criticality::Critical => Severity::Critical,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ordering_matches_urgency() {
+        assert!(Criticality::Critical > Criticality::High);
+        assert!(Criticality::High > Criticality::Medium);
+        assert!(Criticality::Medium > Criticality::Low);
+        assert_eq!(Criticality::ALL.iter().max(), Some(&Criticality::Critical));
+    }
+
+    #[test]
+    fn test_severity_and_priority_agree() {
+        assert_eq!(
+            Criticality::from(&Severity::Critical),
+            Criticality::from(&Priority::Critical)
+        );
+        assert_eq!(
+            Criticality::from(&Severity::Info),
+            Criticality::from(&Priority::Low)
+        );
+
+        for level in Criticality::ALL {
+            assert_eq!(Criticality::from(&Priority::from(level)), level);
+        }
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let json = serde_json::to_string(&Criticality::High).unwrap();
+        assert_eq!(json, "\"HIGH\"");
+
+        let parsed: Criticality = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, Criticality::High);
+        assert_eq!(Criticality::parse("warning"), Some(Criticality::Medium));
+    }
+}
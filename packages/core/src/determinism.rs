@@ -0,0 +1,150 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Deterministic schema profile
+//!
+//! Teams that hash account state need the same value to serialize to the same
+//! bytes on every platform. This check rejects types that break that:
+//! floating point, target-width integers, and unordered collections.
+//!
+//! It runs on the AST rather than the IR, so types the schema language does
+//! not otherwise accept (`usize`, `HashMap`) get a specific explanation
+//! instead of an "undefined type" error.
+
+use crate::ast::{EnumVariant, Item, LumosFile, TypeSpec};
+
+/// A field whose type is not byte-stable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeterminismViolation {
+    /// Location, e.g. `Player.score` or `Event::Moved.0`
+    pub location: String,
+
+    /// Offending type name
+    pub type_name: String,
+
+    /// Why the type is rejected
+    pub reason: &'static str,
+
+    /// Suggested replacement
+    pub suggestion: &'static str,
+}
+
+/// Check every type in a schema against the deterministic profile
+pub fn check_deterministic(file: &LumosFile) -> Vec<DeterminismViolation> {
+    let mut violations = Vec::new();
+
+    for item in &file.items {
+        match item {
+            Item::Struct(struct_def) => {
+                for field in &struct_def.fields {
+                    let location = format!("{}.{}", struct_def.name, field.name);
+                    check_type(&field.type_spec, &location, &mut violations);
+                }
+            }
+            Item::Enum(enum_def) => {
+                for variant in &enum_def.variants {
+                    let prefix = format!("{}::{}", enum_def.name, variant.name());
+                    match variant {
+                        EnumVariant::Unit { .. } => {}
+                        EnumVariant::Tuple { types, .. } => {
+                            for (index, type_spec) in types.iter().enumerate() {
+                                let location = format!("{}.{}", prefix, index);
+                                check_type(type_spec, &location, &mut violations);
+                            }
+                        }
+                        EnumVariant::Struct { fields, .. } => {
+                            for field in fields {
+                                let location = format!("{}.{}", prefix, field.name);
+                                check_type(&field.type_spec, &location, &mut violations);
+                            }
+                        }
+                    }
+                }
+            }
+            Item::Const(_) => {}
+        }
+    }
+
+    violations
+}
+
+fn check_type(type_spec: &TypeSpec, location: &str, violations: &mut Vec<DeterminismViolation>) {
+    let name = match type_spec {
+        TypeSpec::Array(inner) | TypeSpec::FixedArray(inner, _) | TypeSpec::Option(inner) => {
+            return check_type(inner, location, violations)
+        }
+        // `BTreeMap` iterates in key order; `HashMap` is reported below
+        TypeSpec::Map(kind, key, value) => {
+            check_type(key, location, violations);
+            check_type(value, location, violations);
+            kind
+        }
+        TypeSpec::Primitive(name) | TypeSpec::UserDefined(name) => name,
+    };
+
+    let (reason, suggestion) = match name.as_str() {
+        "f32" | "f64" => (
+            "floating-point arithmetic can round differently across platforms, and NaN has many encodings",
+            "Use a fixed-point integer, e.g. u64 scaled by 10^decimals",
+        ),
+        "usize" | "isize" => (
+            "its width depends on the target (64-bit on-chain, 32-bit in wasm32 clients)",
+            "Use an explicit width such as u32 or u64",
+        ),
+        "HashMap" | "HashSet" | "Map" | "Set" => (
+            "iteration order is unspecified, so the serialized bytes can differ between runs",
+            "Use an array of entries sorted by key",
+        ),
+        _ => return,
+    };
+
+    violations.push(DeterminismViolation {
+        location: location.to_string(),
+        type_name: name.clone(),
+        reason,
+        suggestion,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+
+    #[test]
+    fn accepts_byte_stable_types() {
+        let file = parse_lumos_file(
+            "struct Vault { owner: PublicKey, amount: u64, history: [i64], memo: Option<String> }",
+        )
+        .unwrap();
+        assert!(check_deterministic(&file).is_empty());
+    }
+
+    #[test]
+    fn rejects_platform_dependent_types() {
+        let file = parse_lumos_file(
+            r#"
+            struct Pool { price: f64, count: usize, weights: [f32], owners: HashMap<PublicKey, u64> }
+            enum Event { Moved(u8, isize), Priced { at: Option<f32> } }
+            "#,
+        )
+        .unwrap();
+
+        let violations = check_deterministic(&file);
+        let found: Vec<(&str, &str)> = violations
+            .iter()
+            .map(|v| (v.location.as_str(), v.type_name.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("Pool.price", "f64"),
+                ("Pool.count", "usize"),
+                ("Pool.weights", "f32"),
+                ("Pool.owners", "HashMap"),
+                ("Event::Moved.1", "isize"),
+                ("Event::Priced.at", "f32"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,376 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Hex, base64 and base58 text encodings of raw bytes
+//!
+//! Solana tools show the same bytes in different encodings: explorers print
+//! instruction data and addresses in base58, RPC returns account data and
+//! `Program data:` logs in base64, and fixtures are often written in hex.
+//! Decoding is strict and errors point at the offending character, so a
+//! truncated copy-paste fails loudly instead of decoding to the wrong bytes.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::encoding::Encoding;
+//!
+//! let encoding = Encoding::parse("base58").unwrap();
+//! assert_eq!(encoding.decode("StV1DL6CwTryKyV")?, b"hello world");
+//! assert_eq!(Encoding::Hex.encode(b"hi"), "6869");
+//!
+//! let err = Encoding::Base64.decode("Zm9v!").unwrap_err();
+//! assert_eq!(err.to_string(), "invalid base64: '!' at position 4 is not a base64 character");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::fmt;
+
+/// A text encoding of bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Lowercase hex, optionally `0x`-prefixed when decoding
+    Hex,
+
+    /// Standard padded base64
+    Base64,
+
+    /// Base58 (Bitcoin alphabet)
+    Base58,
+}
+
+impl Encoding {
+    /// Every encoding, in display order
+    pub const ALL: [Encoding; 3] = [Encoding::Hex, Encoding::Base64, Encoding::Base58];
+
+    /// Stable identifier, as accepted by `--encoding`
+    pub fn id(&self) -> &'static str {
+        match self {
+            Encoding::Hex => "hex",
+            Encoding::Base64 => "base64",
+            Encoding::Base58 => "base58",
+        }
+    }
+
+    /// Parse an identifier returned by [`Encoding::id`]
+    pub fn parse(value: &str) -> Option<Self> {
+        Encoding::ALL.into_iter().find(|e| e.id() == value)
+    }
+
+    /// Encode `bytes` as text
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        match self {
+            Encoding::Hex => encode_hex(bytes),
+            Encoding::Base64 => encode_base64(bytes),
+            Encoding::Base58 => encode_base58(bytes),
+        }
+    }
+
+    /// Decode `text`, ignoring surrounding whitespace
+    pub fn decode(&self, text: &str) -> Result<Vec<u8>, EncodingError> {
+        match self {
+            Encoding::Hex => decode_hex(text),
+            Encoding::Base64 => decode_base64(text),
+            Encoding::Base58 => decode_base58(text),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// Why text failed to decode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodingError {
+    /// Encoding the text was decoded as
+    pub encoding: Encoding,
+
+    /// Byte offset of the problem in the trimmed text, if it has one
+    pub position: Option<usize>,
+
+    /// What was wrong
+    pub message: String,
+}
+
+impl EncodingError {
+    fn new(encoding: Encoding, position: Option<usize>, message: String) -> Self {
+        Self {
+            encoding,
+            position,
+            message,
+        }
+    }
+
+    fn character(encoding: Encoding, position: usize, ch: char) -> Self {
+        Self::new(
+            encoding,
+            Some(position),
+            format!(
+                "{:?} at position {} is not a {} character",
+                ch, position, encoding
+            ),
+        )
+    }
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {}: {}", self.encoding, self.message)
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// Lowercase hex
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|b| {
+            [
+                HEX[(b >> 4) as usize] as char,
+                HEX[(b & 0xf) as usize] as char,
+            ]
+        })
+        .collect()
+}
+
+/// Decode hex in either case, with an optional `0x` prefix
+pub fn decode_hex(text: &str) -> Result<Vec<u8>, EncodingError> {
+    let text = text.trim();
+    let (prefix, digits) = match text.strip_prefix("0x") {
+        Some(digits) => (2, digits),
+        None => (0, text),
+    };
+
+    let values = digits
+        .char_indices()
+        .map(|(i, ch)| {
+            ch.to_digit(16)
+                .map(|d| d as u8)
+                .ok_or_else(|| EncodingError::character(Encoding::Hex, prefix + i, ch))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if values.len() % 2 != 0 {
+        return Err(EncodingError::new(
+            Encoding::Hex,
+            None,
+            format!("odd number of digits ({})", values.len()),
+        ));
+    }
+
+    Ok(values
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
+
+const BASE58: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Base58 (Bitcoin alphabet), as used for Solana addresses and instruction data
+pub fn encode_base58(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in &bytes[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    std::iter::repeat('1')
+        .take(zeros)
+        .chain(digits.iter().rev().map(|&d| BASE58[d as usize] as char))
+        .collect()
+}
+
+/// Decode base58 text
+pub fn decode_base58(text: &str) -> Result<Vec<u8>, EncodingError> {
+    let text = text.trim();
+    let zeros = text.bytes().take_while(|&b| b == b'1').count();
+    let mut bytes: Vec<u8> = Vec::new();
+    for (i, ch) in text.char_indices().skip(zeros) {
+        let mut carry = BASE58
+            .iter()
+            .position(|&c| c as char == ch)
+            .ok_or_else(|| EncodingError::character(Encoding::Base58, i, ch))?
+            as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut decoded = vec![0; zeros];
+    decoded.extend(bytes.iter().rev());
+    Ok(decoded)
+}
+
+/// Decode a base58 public key, which must be exactly 32 bytes
+pub fn decode_pubkey(text: &str) -> Result<[u8; 32], EncodingError> {
+    let bytes = decode_base58(text)?;
+    bytes.as_slice().try_into().map_err(|_| {
+        EncodingError::new(
+            Encoding::Base58,
+            None,
+            format!("a public key is 32 bytes, not {}", bytes.len()),
+        )
+    })
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard padded base64, as used for account data and `Program data:` logs
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decode standard base64; padding may be left off but not misplaced
+pub fn decode_base64(text: &str) -> Result<Vec<u8>, EncodingError> {
+    let text = text.trim();
+    let data = text.trim_end_matches('=');
+    let padding = text.len() - data.len();
+
+    let mut bytes = Vec::with_capacity(data.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for (i, ch) in data.char_indices() {
+        let value = BASE64
+            .iter()
+            .position(|&c| c as char == ch)
+            .ok_or_else(|| EncodingError::character(Encoding::Base64, i, ch))?;
+        buffer = (buffer << 6 | value as u32) & 0xffff;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    let length_error = |message: String| Err(EncodingError::new(Encoding::Base64, None, message));
+    if data.len() % 4 == 1 {
+        return length_error("the last group has a single character".to_string());
+    }
+    if padding > 0 && (padding > 2 || text.len() % 4 != 0) {
+        return length_error(format!(
+            "{} padding characters don't fit the length",
+            padding
+        ));
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_hex() {
+        assert_eq!(encode_hex(&[0x00, 0xab, 0x10]), "00ab10");
+        assert_eq!(decode_hex("00AB10").unwrap(), [0x00, 0xab, 0x10]);
+        assert_eq!(decode_hex(" 0x00ab10\n").unwrap(), [0x00, 0xab, 0x10]);
+        assert!(decode_hex("").unwrap().is_empty());
+
+        assert_eq!(
+            decode_hex("0x0g").unwrap_err().to_string(),
+            "invalid hex: 'g' at position 3 is not a hex character"
+        );
+        assert_eq!(
+            decode_hex("abc").unwrap_err().message,
+            "odd number of digits (3)"
+        );
+    }
+
+    #[test]
+    fn round_trips_base58() {
+        for (bytes, text) in [
+            (&[][..], ""),
+            (&[0, 0, 1][..], "112"),
+            (&[0x61][..], "2g"),
+            (b"hello world", "StV1DL6CwTryKyV"),
+        ] {
+            assert_eq!(encode_base58(bytes), text);
+            assert_eq!(decode_base58(text).unwrap(), bytes);
+        }
+
+        let err = decode_base58("StV1l").unwrap_err();
+        assert_eq!(err.position, Some(4));
+        assert_eq!(
+            err.to_string(),
+            "invalid base58: 'l' at position 4 is not a base58 character"
+        );
+    }
+
+    #[test]
+    fn decodes_public_keys() {
+        assert_eq!(
+            decode_pubkey("11111111111111111111111111111111").unwrap(),
+            [0; 32]
+        );
+        assert_eq!(
+            decode_pubkey("StV1DL6CwTryKyV").unwrap_err().message,
+            "a public key is 32 bytes, not 11"
+        );
+    }
+
+    #[test]
+    fn round_trips_base64() {
+        for (bytes, encoded) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foobar", "Zm9vYmFy"),
+            (&[0xff, 0x00, 0xfe, 0x10][..], "/wD+EA=="),
+        ] {
+            assert_eq!(encode_base64(bytes), encoded);
+            assert_eq!(decode_base64(encoded).unwrap(), bytes);
+        }
+        assert_eq!(decode_base64("Zm8").unwrap(), b"fo");
+
+        assert_eq!(decode_base64("Zm9v!").unwrap_err().position, Some(4));
+        assert_eq!(
+            decode_base64("Zm=9v").unwrap_err().to_string(),
+            "invalid base64: '=' at position 2 is not a base64 character"
+        );
+        assert!(decode_base64("Zm9vY").is_err());
+        assert!(decode_base64("Zm8==").is_err());
+    }
+
+    #[test]
+    fn parses_encoding_ids() {
+        for encoding in Encoding::ALL {
+            assert_eq!(Encoding::parse(encoding.id()), Some(encoding));
+        }
+        assert_eq!(Encoding::parse("utf8"), None);
+    }
+}
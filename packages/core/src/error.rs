@@ -3,6 +3,7 @@
 
 //! Error types for LUMOS core
 
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Source location information for error reporting
@@ -12,17 +13,34 @@ pub struct SourceLocation {
     pub line: usize,
     /// Column number (1-indexed)
     pub column: usize,
+    /// File the location is in, when the schema was loaded from disk
+    pub file: Option<PathBuf>,
 }
 
 impl SourceLocation {
     /// Create a new source location
     pub fn new(line: usize, column: usize) -> Self {
-        Self { line, column }
+        Self {
+            line,
+            column,
+            file: None,
+        }
     }
 
-    /// Format location as "line:column"
+    /// The same location, in `file`
+    pub fn in_file(self, file: impl Into<PathBuf>) -> Self {
+        Self {
+            file: Some(file.into()),
+            ..self
+        }
+    }
+
+    /// Format location as "line:column", prefixed with the file when known
     pub fn format(&self) -> String {
-        format!("{}:{}", self.line, self.column)
+        match &self.file {
+            Some(file) => format!("{}:{}:{}", file.display(), self.line, self.column),
+            None => format!("{}:{}", self.line, self.column),
+        }
     }
 }
 
@@ -30,7 +48,7 @@ impl SourceLocation {
 #[derive(Error, Debug)]
 pub enum LumosError {
     /// Schema parsing error with optional source location
-    #[error("{}{}", .0, .1.as_ref().map(|loc| format!(" at line {}, column {}", loc.line, loc.column)).unwrap_or_default())]
+    #[error("{}{}", .0, .1.as_ref().map(|loc| format!(" at line {}, column {}{}", loc.line, loc.column, loc.file.as_ref().map(|file| format!(" of {}", file.display())).unwrap_or_default())).unwrap_or_default())]
     SchemaParse(String, Option<SourceLocation>),
 
     /// Code generation error
@@ -48,6 +66,49 @@ pub enum LumosError {
     /// TOML deserialization error
     #[error("TOML error: {0}")]
     Toml(#[from] toml::de::Error),
+
+    /// A bug: an entry point panicked (see [`crate::panic_guard`])
+    #[error("internal error in {}: {}", .0.entry, .0.message)]
+    Internal(Box<crate::panic_guard::InternalError>),
+}
+
+impl LumosError {
+    /// Where in the schema the error is, if known
+    pub fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            LumosError::SchemaParse(_, location) | LumosError::TypeValidation(_, location) => {
+                location.as_ref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Place the error's location, if it has one without a file, in `file`
+    pub fn in_file(mut self, file: &Path) -> Self {
+        if let LumosError::SchemaParse(_, Some(location))
+        | LumosError::TypeValidation(_, Some(location)) = &mut self
+        {
+            location.file.get_or_insert_with(|| file.to_path_buf());
+        }
+        self
+    }
+
+    /// Locate the error at `fallback` when it has no location yet, or in
+    /// `fallback`'s file when its location has none
+    pub fn or_located(mut self, fallback: Option<SourceLocation>) -> Self {
+        if let LumosError::SchemaParse(_, location) | LumosError::TypeValidation(_, location) =
+            &mut self
+        {
+            match location {
+                Some(location) if location.file.is_none() => {
+                    location.file = fallback.and_then(|fallback| fallback.file);
+                }
+                Some(_) => {}
+                None => *location = fallback,
+            }
+        }
+        self
+    }
 }
 
 /// Result type for LUMOS operations
@@ -0,0 +1,257 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Anchor event decoding from program logs
+//!
+//! Anchor's `emit!` writes an event as a `Program data: <base64>` log line:
+//! the 8-byte discriminator `sha256("event:<Name>")[..8]` followed by the
+//! Borsh-encoded `#[event]` struct. Scanning a log dump for those lines
+//! turns it into a typed timeline.
+//!
+//! Logs are accepted as printed by `solana logs`, copied from an explorer,
+//! or as a JSON array of log messages; quotes and trailing commas around a
+//! line are ignored. Each event records the transaction signature, the
+//! program that was executing and the instruction it announced
+//! (`Program log: Instruction: <Name>`), when the logs show them.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::event_decoder::{decode_logs, EventData};
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "#[solana]\n#[event]\nstruct Ping { seq: u8 }",
+//! )?)?;
+//!
+//! let logs = "Program Ping111 invoke [1]\nProgram data: rxJrlQt0HEQH\nProgram Ping111 success";
+//! let events = decode_logs(&ir, logs);
+//! assert_eq!(events[0].program.as_deref(), Some("Ping111"));
+//! assert!(matches!(&events[0].data, EventData::Decoded { name, .. } if name == "Ping"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::borsh_decoder::{BorshDecoder, DecodeError, DecodedValue};
+use crate::encoding::decode_base64;
+use crate::ir::{anchor_discriminator, TypeDefinition, TypeInfo};
+
+/// Anchor event discriminator size in bytes
+const DISCRIMINATOR: usize = 8;
+
+/// Anchor discriminator of the event type `name`
+pub fn event_discriminator(name: &str) -> [u8; 8] {
+    anchor_discriminator("event", name)
+}
+
+/// What a `Program data:` line contained
+#[derive(Debug, Clone)]
+pub enum EventData {
+    /// An `#[event]` type, decoded
+    Decoded { name: String, value: DecodedValue },
+
+    /// An `#[event]` discriminator whose data does not fit the type
+    Failed { name: String, error: DecodeError },
+
+    /// Data that is no schema event, e.g. from another program
+    Unknown { bytes: Vec<u8> },
+}
+
+/// An event found in the logs
+#[derive(Debug, Clone)]
+pub struct LogEvent {
+    /// 1-based line number in the logs
+    pub line: usize,
+
+    /// Signature of the enclosing transaction, if the logs show it
+    pub signature: Option<String>,
+
+    /// Program executing when the event was emitted
+    pub program: Option<String>,
+
+    /// Instruction the program announced
+    pub instruction: Option<String>,
+
+    /// Decoded event
+    pub data: EventData,
+}
+
+/// Every `Program data:` line in `logs`, in order
+pub fn decode_logs(type_defs: &[TypeDefinition], logs: &str) -> Vec<LogEvent> {
+    let decoder = BorshDecoder::new(type_defs);
+    let events: Vec<(&str, [u8; 8])> = type_defs
+        .iter()
+        .filter(|t| t.is_event())
+        .filter_map(|t| Some((t.name(), t.discriminator()?)))
+        .collect();
+
+    let mut signature = None;
+    let mut programs: Vec<String> = Vec::new();
+    let mut instruction = None;
+    let mut found = Vec::new();
+
+    for (index, raw) in logs.lines().enumerate() {
+        let line = raw.trim().trim_end_matches(',').trim_matches('"').trim();
+
+        if let Some(rest) = line.strip_prefix("Signature:") {
+            signature = Some(rest.trim().to_string());
+            programs.clear();
+            instruction = None;
+        } else if let Some(rest) = line.strip_prefix("Program log: Instruction: ") {
+            instruction = Some(rest.trim().to_string());
+        } else if let Some(data) = line.strip_prefix("Program data: ") {
+            let Ok(bytes) = decode_base64(data) else {
+                continue;
+            };
+            found.push(LogEvent {
+                line: index + 1,
+                signature: signature.clone(),
+                program: programs.last().cloned(),
+                instruction: instruction.clone(),
+                data: decode_event(&decoder, &events, bytes),
+            });
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => {
+                    programs.push(program.to_string());
+                    if programs.len() == 1 {
+                        instruction = None;
+                    }
+                }
+                (Some(_), Some("success" | "failed:")) => {
+                    programs.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    found
+}
+
+fn decode_event(decoder: &BorshDecoder, events: &[(&str, [u8; 8])], bytes: Vec<u8>) -> EventData {
+    let Some((name, _)) = events.iter().find(|(_, d)| bytes.starts_with(d)) else {
+        return EventData::Unknown { bytes };
+    };
+
+    let type_info = TypeInfo::UserDefined(name.to_string());
+    let result = decoder
+        .decode(&type_info, &bytes, DISCRIMINATOR, name)
+        .and_then(|(value, end)| match bytes.len() - end {
+            0 => Ok(value),
+            left => Err(DecodeError {
+                offset: end,
+                path: name.to_string(),
+                message: format!("{} bytes left over after the event", left),
+            }),
+        });
+
+    match result {
+        Ok(value) => EventData::Decoded {
+            name: name.to_string(),
+            value,
+        },
+        Err(error) => EventData::Failed {
+            name: name.to_string(),
+            error,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::encode_base64;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn ir() -> Vec<TypeDefinition> {
+        transform_to_ir(
+            parse_lumos_file(
+                r#"
+                #[solana]
+                #[event]
+                struct Deposited { amount: u64 }
+
+                #[solana]
+                #[event]
+                struct Closed {}
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn data(name: &str, payload: &[u8]) -> String {
+        let mut bytes = event_discriminator(name).to_vec();
+        bytes.extend_from_slice(payload);
+        format!("Program data: {}", encode_base64(&bytes))
+    }
+
+    #[test]
+    fn builds_a_timeline_with_context() {
+        let logs = [
+            "Signature: 5xSig".to_string(),
+            "Program Vault111 invoke [1]".to_string(),
+            "Program log: Instruction: Deposit".to_string(),
+            "Program Token111 invoke [2]".to_string(),
+            "Program Token111 success".to_string(),
+            data("Deposited", &9u64.to_le_bytes()),
+            "Program Vault111 success".to_string(),
+            format!("  \"{}\",", data("Closed", &[])),
+        ]
+        .join("\n");
+
+        let events = decode_logs(&ir(), &logs);
+        assert_eq!(events.len(), 2);
+
+        assert_eq!(events[0].line, 6);
+        assert_eq!(events[0].signature.as_deref(), Some("5xSig"));
+        assert_eq!(events[0].program.as_deref(), Some("Vault111"));
+        assert_eq!(events[0].instruction.as_deref(), Some("Deposit"));
+        match &events[0].data {
+            EventData::Decoded { name, value } => {
+                assert_eq!(name, "Deposited");
+                assert_eq!(value.to_string(), "{\n  amount: 9\n}");
+            }
+            other => panic!("expected a decoded event, got {:?}", other),
+        }
+
+        assert_eq!(events[1].program, None);
+        match &events[1].data {
+            EventData::Decoded { name, value } => {
+                assert_eq!(name, "Closed");
+                assert_eq!(value.to_string(), "{}");
+            }
+            other => panic!("expected a decoded event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_unknown_and_malformed_events() {
+        let logs = format!(
+            "{}\n{}\nProgram data: AAEC",
+            data("Deposited", &[1, 2]),
+            data("Deposited", &[0; 10]),
+        );
+
+        let events = decode_logs(&ir(), &logs);
+        match &events[0].data {
+            EventData::Failed { error, .. } => {
+                assert_eq!(
+                    error.to_string(),
+                    "Deposited.amount at byte 8: needs 8 bytes but only 2 are left"
+                )
+            }
+            other => panic!("expected a failed event, got {:?}", other),
+        }
+        match &events[1].data {
+            EventData::Failed { error, .. } => {
+                assert_eq!(error.message, "2 bytes left over after the event")
+            }
+            other => panic!("expected a failed event, got {:?}", other),
+        }
+        assert!(matches!(&events[2].data, EventData::Unknown { bytes } if bytes == &[0, 1, 2]));
+    }
+}
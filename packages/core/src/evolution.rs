@@ -0,0 +1,184 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Trailing-append evolution check
+//!
+//! Borsh data carries no field names or tags, so an account written under an
+//! older layout only stays readable when the new layout keeps every existing
+//! field in place and appends new fields at the end. Requiring appended
+//! fields to be optional lets readers treat missing or zeroed trailing bytes
+//! as `None`, the common forward-compatible pattern for Solana accounts.
+//!
+//! Layouts are compared between two schema files, and between consecutive
+//! `#[version(n)]` layouts of the same struct.
+
+use crate::ir::{StructDefinition, TypeDefinition, TypeInfo};
+
+/// A layout change that breaks the trailing-append rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvolutionViolation {
+    /// Location, e.g. `Player.score` or `Player (version 2).level`
+    pub location: String,
+
+    /// What changed
+    pub message: String,
+}
+
+/// Check `current` against the `previous` schema and its own version history
+///
+/// Types that only exist on one side are not compared; adding or removing a
+/// type does not change how existing accounts of other types decode.
+pub fn check_append_only(
+    previous: &[TypeDefinition],
+    current: &[TypeDefinition],
+) -> Vec<EvolutionViolation> {
+    let mut violations = Vec::new();
+
+    for type_def in current {
+        let TypeDefinition::Struct(struct_def) = type_def else {
+            continue;
+        };
+
+        let old = previous.iter().find_map(|t| match t {
+            TypeDefinition::Struct(s) if s.name == struct_def.name => Some(s),
+            _ => None,
+        });
+        if let Some(old) = old {
+            compare(old, struct_def, &struct_def.name, &mut violations);
+        }
+
+        let Some(version) = struct_def.metadata.version else {
+            continue;
+        };
+        for from in 1..version {
+            let label = format!("{} (version {})", struct_def.name, from + 1);
+            compare(
+                &struct_def.at_version(from),
+                &struct_def.at_version(from + 1),
+                &label,
+                &mut violations,
+            );
+        }
+    }
+
+    violations
+}
+
+/// Compare two layouts of the same struct; `label` names the newer one
+fn compare(
+    old: &StructDefinition,
+    new: &StructDefinition,
+    label: &str,
+    violations: &mut Vec<EvolutionViolation>,
+) {
+    for (index, old_field) in old.fields.iter().enumerate() {
+        let location = format!("{}.{}", label, old_field.name);
+        let message = match new.fields.get(index) {
+            None => "was removed; existing fields must stay in place".to_string(),
+            Some(new_field) if new_field.name != old_field.name => format!(
+                "was replaced by `{}` at position {}; existing fields must stay in place",
+                new_field.name, index
+            ),
+            Some(new_field) => {
+                let (old_type, new_type) = (
+                    signature(&old_field.type_info),
+                    signature(&new_field.type_info),
+                );
+                if old_type == new_type {
+                    continue;
+                }
+                format!("changed type from {} to {}", old_type, new_type)
+            }
+        };
+        violations.push(EvolutionViolation { location, message });
+    }
+
+    for field in new.fields.iter().skip(old.fields.len()) {
+        if !matches!(field.type_info, TypeInfo::Option(_)) {
+            violations.push(EvolutionViolation {
+                location: format!("{}.{}", label, field.name),
+                message: format!(
+                    "was appended as {}; appended fields must be optional",
+                    signature(&field.type_info)
+                ),
+            });
+        }
+    }
+}
+
+/// Type in schema notation, e.g. `[u8]` or `Option<PublicKey>`
+pub fn signature(type_info: &TypeInfo) -> String {
+    match type_info {
+        TypeInfo::Primitive(name) | TypeInfo::UserDefined(name) => name.clone(),
+        TypeInfo::Array(inner) => format!("[{}]", signature(inner)),
+        TypeInfo::FixedArray(inner, len) => format!("[{}; {}]", signature(inner), len),
+        TypeInfo::Map(kind, key, value) => {
+            format!(
+                "{}<{}, {}>",
+                kind.as_str(),
+                signature(key),
+                signature(value)
+            )
+        }
+        TypeInfo::Option(inner) => format!("Option<{}>", signature(inner)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn ir(source: &str) -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn accepts_appended_optional_fields() {
+        let previous = ir("struct Player { wallet: PublicKey, level: u16 }");
+        let current = ir(r#"
+            struct Player { wallet: PublicKey, level: u16, nickname: Option<String> }
+            struct Guild { name: String }
+        "#);
+        assert!(check_append_only(&previous, &current).is_empty());
+    }
+
+    #[test]
+    fn rejects_layout_breaking_changes() {
+        let previous = ir("struct Player { wallet: PublicKey, level: u16, score: u64 }");
+        let current = ir("struct Player { wallet: PublicKey, level: u32, xp: u64, badges: [u8] }");
+
+        let found: Vec<String> = check_append_only(&previous, &current)
+            .into_iter()
+            .map(|v| format!("{} {}", v.location, v.message))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                "Player.level changed type from u16 to u32",
+                "Player.score was replaced by `xp` at position 2; existing fields must stay in place",
+                "Player.badges was appended as [u8]; appended fields must be optional",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_version_history() {
+        let current = ir(r#"
+            #[solana]
+            #[version(3)]
+            struct Player {
+                wallet: PublicKey,
+                #[since(2)]
+                nickname: Option<String>,
+                #[since(3)]
+                level: u16,
+            }
+        "#);
+
+        let violations = check_append_only(&[], &current);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].location, "Player (version 3).level");
+    }
+}
@@ -0,0 +1,1260 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Hand-written `.lumos` parser (`fast-parser` feature)
+//!
+//! [`crate::parser::parse_with_syn`] parses a schema as a Rust file: `syn`
+//! builds a full Rust syntax tree, which is then converted to the AST. This
+//! parser reads the `.lumos` subset directly, with a lexer and a recursive
+//! descent over its tokens, and skips the syntax tree.
+//!
+//! Its output is the same AST, spans included, and it reports the same
+//! errors in the same order: syntax errors first, then the first schema
+//! error in source order. Attribute arguments and `const` values are
+//! rendered the way `proc_macro2` prints tokens, so `#[account(mut)]` and
+//! `const A: u8 = B * 2;` carry the same strings on both paths. Items
+//! schemas don't use (functions, `impl` blocks, `use` declarations) are
+//! skipped without being checked.
+//!
+//! With the feature enabled, [`crate::parser::parse_lumos_file`] uses this
+//! parser. The tests compare both parsers on the example schemas and on
+//! every syntax form the syn path supports.
+
+use crate::ast::{
+    Attribute, AttributeValue, ConstDef, EnumDef, EnumVariant, FieldDef, Item, LumosFile,
+    SchemaMetadata, Span, StructDef, TypeSpec,
+};
+use crate::error::{LumosError, Result};
+use crate::lexer::{closing, syntax_error, Kind, Lexer, Token};
+use crate::parser::{extract_imports, lumos_file, parse_attribute_value, CONST_TYPES, SCHEMA_KEYS};
+use std::ops::Range;
+
+/// Items that are skipped, as `syn` parses and the schema ignores them
+const SKIPPED_ITEMS: [&str; 12] = [
+    "fn",
+    "impl",
+    "use",
+    "mod",
+    "type",
+    "static",
+    "trait",
+    "extern",
+    "unsafe",
+    "async",
+    "union",
+    "macro_rules",
+];
+
+/// Keywords `syn` doesn't accept as identifiers
+const KEYWORDS: [&str; 52] = [
+    "_", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "Self", "self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Keywords that may start or continue a path
+const PATH_KEYWORDS: [&str; 4] = ["super", "self", "Self", "crate"];
+
+/// Suffixes of integer literals
+const INT_SUFFIXES: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// Parse a `.lumos` file into an Abstract Syntax Tree
+///
+/// Same as [`crate::parser::parse_with_syn`], without building a Rust syntax
+/// tree.
+pub fn parse_lumos_file(input: &str) -> Result<LumosFile> {
+    let (imports, input) = extract_imports(input)?;
+    let tokens = Lexer::new(input.trim_start_matches('\u{feff}')).tokenize()?;
+
+    let mut parser = Parser::new(tokens);
+    let (metadata, items) = parser.file()?;
+    if let Some(error) = parser.deferred {
+        return Err(error);
+    }
+    lumos_file(metadata, imports, items)
+}
+
+/// The value of a string literal, or `None` for other literals
+fn string_value(literal: &str) -> Option<String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = raw[hashes..].strip_prefix('"')?;
+        return Some(body[..body.rfind('"')?].to_string());
+    }
+
+    let body = literal.strip_prefix('"')?;
+    let body = &body[..body.rfind('"')?];
+    let mut value = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            '0' => value.push('\0'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                value.push(char::from(u8::from_str_radix(&hex, 16).ok()?));
+            }
+            'u' => {
+                let hex: String = chars
+                    .by_ref()
+                    .skip_while(|c| *c == '{')
+                    .take_while(|c| *c != '}')
+                    .collect();
+                value.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            // A line continuation skips the line break and the indentation after it
+            '\n' => {
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+            }
+            other => value.push(other),
+        }
+    }
+    Some(value)
+}
+
+/// The value of an integer literal such as `32`, `0x20` or `32_usize`
+fn int_value(literal: &str) -> Option<usize> {
+    let digits = literal.replace('_', "");
+    let (radix, digits) = match digits.get(..2) {
+        Some("0x") => (16, &digits[2..]),
+        Some("0o") => (8, &digits[2..]),
+        Some("0b") => (2, &digits[2..]),
+        _ => (10, &digits[..]),
+    };
+    let end = digits
+        .find(|c: char| !c.is_digit(radix))
+        .unwrap_or(digits.len());
+    let (value, suffix) = digits.split_at(end);
+    if !suffix.is_empty() && !INT_SUFFIXES.contains(&suffix) {
+        return None;
+    }
+    usize::from_str_radix(value, radix).ok()
+}
+
+/// A type as written, before it is checked and converted to a [`TypeSpec`]
+enum RawType {
+    /// A path such as `u64` or `Option<T>`: the last segment and its generic arguments
+    Path {
+        name: String,
+        args: Vec<Option<RawType>>,
+        ident: bool,
+    },
+
+    /// `[T]`
+    Slice(Box<RawType>),
+
+    /// `[T; N]`, with `N` when it is an integer literal
+    Array(Box<RawType>, Option<usize>),
+
+    /// Any other type syntax, as written
+    Unsupported(String),
+}
+
+impl RawType {
+    /// The type and whether it is optional, with the syn path's errors
+    fn convert(&self) -> Result<(TypeSpec, bool)> {
+        match self {
+            RawType::Path { name, args, .. } => {
+                match (name.as_str(), &args[..]) {
+                    ("Option", [Some(inner), ..]) => return Ok((inner.convert_nested()?, true)),
+                    ("Vec", [_, _, ..]) => {
+                        return Err(LumosError::SchemaParse(
+                            format!("Vec takes a single element type, found {}", args.len()),
+                            None,
+                        ))
+                    }
+                    ("Vec", [Some(inner)]) => {
+                        return Ok((TypeSpec::Array(Box::new(inner.convert_nested()?)), false))
+                    }
+                    ("Vec", _) => {
+                        return Err(LumosError::SchemaParse(
+                            "Vec needs an element type, e.g. Vec<u64>".to_string(),
+                            None,
+                        ))
+                    }
+                    ("HashMap" | "BTreeMap", [Some(key), Some(value)]) => {
+                        return Ok((
+                            TypeSpec::Map(
+                                name.clone(),
+                                Box::new(key.convert_nested()?),
+                                Box::new(value.convert_nested()?),
+                            ),
+                            false,
+                        ))
+                    }
+                    ("HashMap" | "BTreeMap", _) => {
+                        return Err(LumosError::SchemaParse(
+                            format!(
+                                "{} needs key and value types, e.g. {}<PublicKey, u64>",
+                                name, name
+                            ),
+                            None,
+                        ))
+                    }
+                    _ => {}
+                }
+                Ok((TypeSpec::Primitive(name.clone()), false))
+            }
+            RawType::Array(elem, len) => {
+                let inner = elem.convert_nested()?;
+                let len = len.filter(|&len| len > 0).ok_or_else(|| {
+                    LumosError::SchemaParse(
+                        "Fixed array length must be a positive integer literal, e.g. [u8; 32]"
+                            .to_string(),
+                        None,
+                    )
+                })?;
+                Ok((TypeSpec::FixedArray(Box::new(inner), len), false))
+            }
+            RawType::Slice(elem) => Ok((TypeSpec::Array(Box::new(elem.convert_nested()?)), false)),
+            RawType::Unsupported(text) => Err(LumosError::SchemaParse(
+                format!("Unsupported type: {}", text),
+                None,
+            )),
+        }
+    }
+
+    /// A type nested in another one, keeping its `Option` as [`TypeSpec::Option`]
+    fn convert_nested(&self) -> Result<TypeSpec> {
+        let (type_spec, optional) = self.convert()?;
+        if optional {
+            Ok(TypeSpec::Option(Box::new(type_spec)))
+        } else {
+            Ok(type_spec)
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+
+    /// First schema error, reported once the whole file has parsed
+    deferred: Option<LumosError>,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            deferred: None,
+        }
+    }
+
+    fn peek(&self) -> Option<&Kind> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, ahead: usize) -> Option<&Kind> {
+        self.tokens.get(self.pos + ahead).map(|token| &token.kind)
+    }
+
+    fn span(&self) -> Span {
+        match self.tokens.get(self.pos).or(self.tokens.last()) {
+            Some(token) => token.span,
+            None => Span { line: 1, column: 0 },
+        }
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> LumosError {
+        match self.pos < self.tokens.len() {
+            true => syntax_error(self.span(), message),
+            false => syntax_error(self.span(), "unexpected end of input"),
+        }
+    }
+
+    fn defer(&mut self, error: LumosError) {
+        self.deferred.get_or_insert(error);
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        matches!(self.peek(), Some(Kind::Punct(p, _)) if *p == c)
+    }
+
+    fn is_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Kind::Ident(ident)) if ident == name)
+    }
+
+    fn is_path_separator(&self) -> bool {
+        matches!(self.peek(), Some(Kind::Punct(':', true)))
+            && matches!(self.peek_at(1), Some(Kind::Punct(':', _)))
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        let found = self.is_punct(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<()> {
+        match self.eat_punct(c) {
+            true => Ok(()),
+            false => Err(self.error(format!("expected `{}`", c))),
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<(String, Span)> {
+        match self.peek() {
+            Some(Kind::Ident(name)) if KEYWORDS.contains(&name.as_str()) => {
+                Err(self.error(format!("expected identifier, found keyword `{}`", name)))
+            }
+            Some(Kind::Ident(name)) => {
+                let ident = (name.clone(), self.span());
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => Err(self.error("expected identifier")),
+        }
+    }
+
+    /// Step into the `open` group here, returning the index of its closing token
+    fn enter(&mut self, open: char) -> Result<usize> {
+        match self.peek() {
+            Some(Kind::Open(c, close)) if *c == open => {
+                let close = *close;
+                self.pos += 1;
+                Ok(close)
+            }
+            _ => Err(self.error(format!("expected `{}`", open))),
+        }
+    }
+
+    /// Step over one token, or a whole group
+    fn skip_tree(&mut self) {
+        self.pos = match self.peek() {
+            Some(Kind::Open(_, close)) => close + 1,
+            _ => self.pos + 1,
+        };
+    }
+
+    /// Step over tokens up to `stop` (or the end of the group) outside nested groups
+    fn skip_until(&mut self, stop: char, end: usize) {
+        while self.pos < end && !self.is_punct(stop) {
+            self.skip_tree();
+        }
+    }
+
+    fn file(&mut self) -> Result<(Option<SchemaMetadata>, Vec<Item>)> {
+        // `#![...]` and `//!` at the top of the file are allowed and ignored
+        loop {
+            match self.peek() {
+                Some(Kind::Doc(_, true)) => self.pos += 1,
+                Some(Kind::Punct('#', _))
+                    if matches!(self.peek_at(1), Some(Kind::Punct('!', _))) =>
+                {
+                    self.pos += 2;
+                    let close = self.enter('[')?;
+                    self.pos = close + 1;
+                }
+                _ => break,
+            }
+        }
+
+        let metadata =
+            match self.is_ident("schema") && matches!(self.peek_at(1), Some(Kind::Open('{', _))) {
+                true => Some(self.schema_block()?),
+                false => None,
+            };
+
+        let mut items = Vec::new();
+        while self.pos < self.tokens.len() {
+            if let Some(item) = self.item()? {
+                items.push(item);
+            }
+        }
+        Ok((metadata, items))
+    }
+
+    /// Parse `schema { name = "...", version = "...", authors = ["..."], ... }`
+    fn schema_block(&mut self) -> Result<SchemaMetadata> {
+        let keyword = self.span();
+        self.pos += 1;
+        let close = self.enter('{')?;
+
+        // All entries parse before any is checked, as with syn
+        let mut entries = Vec::new();
+        while self.pos < close {
+            let path_span = self.span();
+            let (segments, ident) = self.path()?;
+            self.expect_punct('=')?;
+            let start = self.pos;
+            self.skip_until(',', close);
+            if self.pos == start {
+                return Err(self.error("expected an expression"));
+            }
+            entries.push((segments, ident, path_span, start..self.pos));
+            if self.pos < close {
+                self.expect_punct(',')?;
+            }
+        }
+        self.pos = close + 1;
+
+        let mut metadata = SchemaMetadata::default();
+        let mut seen = Vec::new();
+        for (segments, ident, path_span, value) in entries {
+            let key = segments
+                .last()
+                .filter(|key| ident && SCHEMA_KEYS.contains(&key.as_str()))
+                .ok_or_else(|| {
+                    syntax_error(
+                        path_span,
+                        format!(
+                            "unknown schema key (expected one of {})",
+                            SCHEMA_KEYS.join(", ")
+                        ),
+                    )
+                })?
+                .clone();
+            if seen.contains(&key) {
+                return Err(syntax_error(
+                    path_span,
+                    format!("duplicate schema key `{}`", key),
+                ));
+            }
+            match key.as_str() {
+                "name" => metadata.name = self.string_literal(value)?,
+                "version" => metadata.version = Some(self.string_literal(value)?),
+                "description" => metadata.description = Some(self.string_literal(value)?),
+                "license" => metadata.license = Some(self.string_literal(value)?),
+                _ => {
+                    let list = match self.tokens[value.start].kind {
+                        Kind::Open('[', list_close) if list_close + 1 == value.end => {
+                            Some(list_close)
+                        }
+                        _ => None,
+                    };
+                    let Some(list_close) = list else {
+                        return Err(syntax_error(
+                            self.tokens[value.start].span,
+                            "`authors` must be a list of strings",
+                        ));
+                    };
+                    self.pos = value.start + 1;
+                    let mut authors = Vec::new();
+                    while self.pos < list_close {
+                        let start = self.pos;
+                        self.skip_until(',', list_close);
+                        authors.push(self.string_literal(start..self.pos)?);
+                        self.eat_punct(',');
+                    }
+                    self.pos = close + 1;
+                    metadata.authors = authors;
+                }
+            }
+            seen.push(key);
+        }
+
+        if metadata.name.is_empty() {
+            return Err(syntax_error(
+                keyword,
+                "schema block needs a non-empty `name`",
+            ));
+        }
+        Ok(metadata)
+    }
+
+    fn string_literal(&self, tokens: Range<usize>) -> Result<String> {
+        let value = match &self.tokens[tokens.clone()] {
+            [Token {
+                kind: Kind::Literal(literal),
+                ..
+            }] => string_value(literal),
+            _ => None,
+        };
+        value.ok_or_else(|| {
+            syntax_error(self.tokens[tokens.start].span, "expected a string literal")
+        })
+    }
+
+    /// A path such as `a::b`: its segments, and whether it is a single identifier
+    fn path(&mut self) -> Result<(Vec<String>, bool)> {
+        let leading = self.is_path_separator();
+        if leading {
+            self.pos += 2;
+        }
+        let mut segments = vec![self.path_segment()?];
+        while self.is_path_separator() && matches!(self.peek_at(2), Some(Kind::Ident(_))) {
+            self.pos += 2;
+            segments.push(self.path_segment()?);
+        }
+        let ident = !leading && segments.len() == 1;
+        Ok((segments, ident))
+    }
+
+    /// An identifier, or one of the [`PATH_KEYWORDS`]
+    fn path_segment(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(Kind::Ident(name)) if PATH_KEYWORDS.contains(&name.as_str()) => self.any_ident(),
+            _ => Ok(self.expect_ident()?.0),
+        }
+    }
+
+    /// An identifier or keyword, as in lifetimes such as `'static`
+    fn any_ident(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(Kind::Ident(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.error("expected identifier")),
+        }
+    }
+
+    /// `pub`, `pub(crate)`, `pub(super)`, `pub(self)` or `pub(in path)`
+    fn visibility(&mut self) {
+        if !self.is_ident("pub") {
+            return;
+        }
+        self.pos += 1;
+        if let Some(Kind::Open('(', _)) = self.peek() {
+            let restricted = matches!(
+                self.peek_at(1),
+                Some(Kind::Ident(name)) if ["crate", "self", "super", "in"].contains(&name.as_str())
+            );
+            if restricted {
+                self.skip_tree();
+            }
+        }
+    }
+
+    /// Outer attributes and doc comments before an item, field or variant
+    ///
+    /// Nothing is recorded when `record` is false, as for enum variants,
+    /// whose attributes the schema ignores.
+    fn attributes(&mut self, record: bool) -> Result<Vec<Attribute>> {
+        let mut attributes = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Kind::Doc(text, false)) => {
+                    attributes.push(Attribute {
+                        name: "doc".to_string(),
+                        value: Some(AttributeValue::String(text.trim().to_string())),
+                        span: Some(self.span()),
+                    });
+                    self.pos += 1;
+                }
+                Some(Kind::Doc(_, true)) => {
+                    return Err(
+                        self.error("inner doc comments are only allowed at the top of the file")
+                    )
+                }
+                Some(Kind::Punct('#', _)) => {
+                    if matches!(self.peek_at(1), Some(Kind::Punct('!', _))) {
+                        return Err(
+                            self.error("inner attributes are only allowed at the top of the file")
+                        );
+                    }
+                    self.pos += 1;
+                    let close = self.enter('[')?;
+                    let attribute = self.attribute(close, record)?;
+                    attributes.extend(attribute);
+                    self.pos = close + 1;
+                }
+                _ => break,
+            }
+        }
+        if !record {
+            attributes.clear();
+        }
+        Ok(attributes)
+    }
+
+    /// The contents of `#[...]`, up to the closing bracket at `close`
+    fn attribute(&mut self, close: usize, record: bool) -> Result<Option<Attribute>> {
+        let span = self.span();
+        let (segments, ident) = self.path()?;
+        let name = segments.last().cloned().unwrap_or_default();
+
+        match self.peek() {
+            // #[solana]
+            _ if self.pos == close => Ok(ident.then_some(Attribute {
+                name,
+                value: None,
+                span: Some(span),
+            })),
+
+            // #[max(100)]
+            Some(Kind::Open(_, group_close)) if group_close + 1 == close => {
+                if !ident {
+                    if record {
+                        self.defer(LumosError::SchemaParse(
+                            "Invalid attribute".to_string(),
+                            None,
+                        ));
+                    }
+                    return Ok(None);
+                }
+                let tokens = self.render(self.pos + 1..*group_close);
+                Ok(Some(Attribute {
+                    name,
+                    value: Some(parse_attribute_value(&tokens)?),
+                    span: Some(span),
+                }))
+            }
+
+            // #[doc = "text"]; other name-value attributes are ignored
+            Some(Kind::Punct('=', _)) if self.pos + 1 < close => {
+                let value = match &self.tokens[self.pos + 1..close] {
+                    [Token {
+                        kind: Kind::Literal(literal),
+                        span,
+                        ..
+                    }] if ident && name == "doc" => string_value(literal).map(|text| (text, *span)),
+                    _ => None,
+                };
+                Ok(value.map(|(text, span)| Attribute {
+                    name,
+                    value: Some(AttributeValue::String(text.trim().to_string())),
+                    span: Some(span),
+                }))
+            }
+
+            _ => Err(self.error("expected `(`, `=` or `]` in attribute")),
+        }
+    }
+
+    fn item(&mut self) -> Result<Option<Item>> {
+        let attributes = self.attributes(true)?;
+        self.visibility();
+
+        let keyword = match self.peek() {
+            Some(Kind::Ident(keyword)) => keyword.clone(),
+            _ => return Err(self.error("expected item")),
+        };
+        match keyword.as_str() {
+            "struct" => Ok(Some(Item::Struct(self.structure(attributes)?))),
+            "enum" => Ok(Some(Item::Enum(self.enumeration(attributes)?))),
+            "const" if matches!(self.peek_at(1), Some(Kind::Ident(name)) if !SKIPPED_ITEMS.contains(&name.as_str())) => {
+                Ok(Some(Item::Const(self.constant()?)))
+            }
+            "use" | "type" | "static" => {
+                self.skip_until(';', self.tokens.len());
+                self.expect_punct(';')?;
+                Ok(None)
+            }
+            keyword
+                if SKIPPED_ITEMS.contains(&keyword)
+                    || keyword == "const"
+                    || matches!(self.peek_at(1), Some(Kind::Punct('!', _))) =>
+            {
+                // Up to the item's body, or its `;` when it has none
+                loop {
+                    match self.peek() {
+                        Some(Kind::Punct(';', _)) => {
+                            self.pos += 1;
+                            return Ok(None);
+                        }
+                        Some(Kind::Open('{', close)) => {
+                            self.pos = close + 1;
+                            return Ok(None);
+                        }
+                        Some(_) => self.skip_tree(),
+                        None => return Err(self.error("expected item")),
+                    }
+                }
+            }
+            other => Err(self.error(format!("expected item, found `{}`", other))),
+        }
+    }
+
+    /// `<...>` after a type name, which the schema ignores
+    fn skip_generics(&mut self) {
+        if !self.is_punct('<') {
+            return;
+        }
+        let mut depth = 0;
+        loop {
+            match self.peek() {
+                Some(Kind::Punct('<', _)) => depth += 1,
+                Some(Kind::Punct('>', _)) => depth -= 1,
+                None => return,
+                _ => {}
+            }
+            self.skip_tree();
+            if depth == 0 {
+                return;
+            }
+        }
+    }
+
+    /// A `where` clause, up to the body or `;`
+    fn skip_where_clause(&mut self) {
+        if self.is_ident("where") {
+            while !matches!(
+                self.peek(),
+                None | Some(Kind::Open('{', _)) | Some(Kind::Punct(';', _))
+            ) {
+                self.skip_tree();
+            }
+        }
+    }
+
+    fn structure(&mut self, attributes: Vec<Attribute>) -> Result<StructDef> {
+        self.pos += 1;
+        let (name, span) = self.expect_ident()?;
+        self.skip_generics();
+        self.skip_where_clause();
+
+        let (fields, named) = match self.peek() {
+            Some(Kind::Open('{', _)) => (self.fields()?, true),
+            // Tuple and unit structs
+            Some(Kind::Open('(', _)) => {
+                self.skip_tree();
+                self.skip_where_clause();
+                self.expect_punct(';')?;
+                (Vec::new(), false)
+            }
+            _ => {
+                self.expect_punct(';')?;
+                (Vec::new(), false)
+            }
+        };
+        if !named {
+            self.defer(LumosError::SchemaParse(
+                format!("Struct '{}' must have named fields", name),
+                None,
+            ));
+        }
+
+        Ok(StructDef {
+            name,
+            attributes,
+            fields,
+            span: Some(span),
+        })
+    }
+
+    /// `{ name: Type, ... }`
+    fn fields(&mut self) -> Result<Vec<FieldDef>> {
+        let close = self.enter('{')?;
+        let mut fields = Vec::new();
+        while self.pos < close {
+            let attributes = self.attributes(true)?;
+            self.visibility();
+            // syn takes `_` as a field name, though not as other names
+            let span = self.span();
+            let name = match self.peek() {
+                Some(Kind::Ident(name)) if name == "_" => self.any_ident()?,
+                _ => self.expect_ident()?.0,
+            };
+            self.expect_punct(':')?;
+            let (type_spec, optional) = self.field_type()?;
+            fields.push(FieldDef {
+                name,
+                type_spec,
+                optional,
+                attributes,
+                span: Some(span),
+            });
+            if self.pos < close {
+                self.expect_punct(',')?;
+            }
+        }
+        self.pos = close + 1;
+        Ok(fields)
+    }
+
+    /// A field's type; schema errors are deferred and a placeholder returned
+    fn field_type(&mut self) -> Result<(TypeSpec, bool)> {
+        let raw = self.raw_type()?;
+        Ok(raw.convert().unwrap_or_else(|error| {
+            self.defer(error);
+            (TypeSpec::Primitive(String::new()), false)
+        }))
+    }
+
+    fn enumeration(&mut self, attributes: Vec<Attribute>) -> Result<EnumDef> {
+        self.pos += 1;
+        let (name, span) = self.expect_ident()?;
+        self.skip_generics();
+        self.skip_where_clause();
+
+        let close = self.enter('{')?;
+        let mut variants = Vec::new();
+        while self.pos < close {
+            self.attributes(false)?;
+            self.visibility();
+            let (name, span) = self.expect_ident()?;
+            let span = Some(span);
+            let variant = match self.peek() {
+                Some(Kind::Open('{', _)) => EnumVariant::Struct {
+                    name,
+                    fields: self.fields()?,
+                    span,
+                },
+                Some(Kind::Open('(', _)) => {
+                    let types_close = self.enter('(')?;
+                    let mut types = Vec::new();
+                    while self.pos < types_close {
+                        self.attributes(false)?;
+                        self.visibility();
+                        types.push(self.field_type()?.0);
+                        if self.pos < types_close {
+                            self.expect_punct(',')?;
+                        }
+                    }
+                    self.pos = types_close + 1;
+                    EnumVariant::Tuple { name, types, span }
+                }
+                _ => EnumVariant::Unit { name, span },
+            };
+            // Explicit discriminants are ignored
+            if self.eat_punct('=') {
+                self.skip_until(',', close);
+            }
+            variants.push(variant);
+            if self.pos < close {
+                self.expect_punct(',')?;
+            }
+        }
+        self.pos = close + 1;
+
+        if variants.is_empty() {
+            self.defer(LumosError::SchemaParse(
+                format!("Enum '{}' must have at least one variant", name),
+                None,
+            ));
+        }
+
+        Ok(EnumDef {
+            name,
+            attributes,
+            variants,
+            span: Some(span),
+        })
+    }
+
+    /// `const NAME: u32 = VALUE;`
+    fn constant(&mut self) -> Result<ConstDef> {
+        self.pos += 1;
+        let (name, span) = self.expect_ident()?;
+        self.expect_punct(':')?;
+        let type_name = match self.raw_type()? {
+            RawType::Path {
+                name, ident: true, ..
+            } if CONST_TYPES.contains(&name.as_str()) => name,
+            _ => String::new(),
+        };
+        self.expect_punct('=')?;
+        let start = self.pos;
+        self.skip_until(';', self.tokens.len());
+        if self.pos == start {
+            return Err(self.error("expected an expression"));
+        }
+        let value = self.render(start..self.pos);
+        self.expect_punct(';')?;
+
+        if type_name.is_empty() {
+            self.defer(LumosError::SchemaParse(
+                format!(
+                    "const '{}' must have an unsigned integer type (u8, u16, u32 or u64)",
+                    name
+                ),
+                None,
+            ));
+        }
+        Ok(ConstDef {
+            name,
+            type_name,
+            value,
+            span: Some(span),
+        })
+    }
+
+    fn raw_type(&mut self) -> Result<RawType> {
+        let start = self.pos;
+        match self.peek() {
+            // [T] or [T; N]
+            Some(Kind::Open('[', close)) => {
+                let close = *close;
+                self.pos += 1;
+                let elem = Box::new(self.raw_type()?);
+                if self.pos == close {
+                    self.pos += 1;
+                    return Ok(RawType::Slice(elem));
+                }
+                self.expect_punct(';')?;
+                if self.pos == close {
+                    return Err(self.error("expected an expression"));
+                }
+                let len = match &self.tokens[self.pos..close] {
+                    [Token {
+                        kind: Kind::Literal(literal),
+                        ..
+                    }] => int_value(literal),
+                    _ => None,
+                };
+                self.pos = close + 1;
+                Ok(RawType::Array(elem, len))
+            }
+            Some(Kind::Ident(name)) if name == "_" => {
+                self.pos += 1;
+                Ok(RawType::Unsupported(self.render(start..self.pos)))
+            }
+            Some(Kind::Ident(_)) | Some(Kind::Punct(':', true)) => self.path_type(),
+            // Tuples, references and pointers parse, but the schema rejects them
+            Some(Kind::Open('(', _)) => {
+                self.skip_tree();
+                Ok(RawType::Unsupported(self.render(start..self.pos)))
+            }
+            Some(Kind::Punct('&' | '*', _)) => {
+                self.pos += 1;
+                if self.eat_punct('\'') {
+                    self.any_ident()?;
+                }
+                if self.is_ident("mut") || self.is_ident("const") {
+                    self.pos += 1;
+                }
+                self.raw_type()?;
+                Ok(RawType::Unsupported(self.render(start..self.pos)))
+            }
+            Some(Kind::Punct('!', _)) => {
+                self.pos += 1;
+                Ok(RawType::Unsupported(self.render(start..self.pos)))
+            }
+            _ => Err(self.error("expected type")),
+        }
+    }
+
+    /// `Name`, `a::b::Name` or `Name<Args>`
+    fn path_type(&mut self) -> Result<RawType> {
+        let leading = self.is_path_separator();
+        if leading {
+            self.pos += 2;
+        }
+        let mut segments = 0;
+        loop {
+            let name = self.path_segment()?;
+            segments += 1;
+            if self.is_path_separator() && matches!(self.peek_at(2), Some(Kind::Punct('<', _))) {
+                self.pos += 2;
+            }
+            let generic = self.is_punct('<');
+            let args = match generic {
+                true => self.generic_args()?,
+                false => Vec::new(),
+            };
+
+            if self.is_path_separator() && matches!(self.peek_at(2), Some(Kind::Ident(_))) {
+                self.pos += 2;
+                continue;
+            }
+            let ident = !leading && segments == 1 && !generic;
+            return Ok(RawType::Path { name, args, ident });
+        }
+    }
+
+    /// `<A, B>`: types, or `None` for lifetimes and constants
+    fn generic_args(&mut self) -> Result<Vec<Option<RawType>>> {
+        self.pos += 1;
+        let mut args = Vec::new();
+        while !self.eat_punct('>') {
+            if self.eat_punct('\'') {
+                self.any_ident()?;
+                args.push(None);
+            } else if matches!(self.peek(), Some(Kind::Literal(_) | Kind::Open('{', _))) {
+                self.skip_tree();
+                args.push(None);
+            } else {
+                args.push(Some(self.raw_type()?));
+            }
+            if !self.is_punct('>') {
+                self.expect_punct(',')?;
+            }
+        }
+        Ok(args)
+    }
+
+    /// Tokens printed the way `proc_macro2` prints a token stream
+    fn render(&self, tokens: Range<usize>) -> String {
+        let mut text = String::new();
+        self.render_into(tokens, &mut text);
+        text
+    }
+
+    fn render_into(&self, tokens: Range<usize>, text: &mut String) {
+        let mut i = tokens.start;
+        let mut joint = true;
+        while i < tokens.end {
+            if !joint {
+                text.push(' ');
+            }
+            joint = false;
+            match &self.tokens[i].kind {
+                Kind::Open(open, close) => {
+                    text.push(*open);
+                    if *open == '{' {
+                        text.push(' ');
+                    }
+                    self.render_into(i + 1..*close, text);
+                    if *open == '{' && *close > i + 1 {
+                        text.push(' ');
+                    }
+                    text.push(closing(*open));
+                    i = *close;
+                }
+                Kind::Punct(c, is_joint) => {
+                    text.push(*c);
+                    joint = *is_joint;
+                }
+                Kind::Ident(word) | Kind::Literal(word) => text.push_str(word),
+                Kind::Doc(comment, inner) => {
+                    text.push('#');
+                    if *inner {
+                        text.push_str(" !");
+                    }
+                    text.push_str(&format!(" [doc = {:?}]", comment));
+                }
+                Kind::Close(_) | Kind::Comment(_) => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_with_syn;
+    use std::fs;
+    use std::path::Path;
+
+    /// Every syntax form the syn path reads
+    const KITCHEN_SINK: &str = r##"
+        //! Schema-level docs
+        #![allow(dead_code)]
+
+        schema {
+            name = "kitchen-sink",
+            version = "1.0.0",
+            description = "Every \"form\" \u{263A}\x21",
+            license = r#"MIT OR Apache-2.0"#,
+            authors = ["Ada <ada@example.com>", "Grace",],
+        }
+
+        const MAX_LEN: u32 = 32;
+        pub const SEEDS: u64 = (MAX_LEN * 2) << 1;
+        const MASK: u8 = 0x1F_u8 & !0;
+
+        /// A vault
+        /** Block doc */
+        #[solana]
+        #[account]
+        #[version(2)]
+        #[deprecated("use V2")]
+        pub struct Vault<T> where T: Clone {
+            /// Owner
+            #[key]
+            pub owner: PublicKey,
+            #[max(MAX_LEN)]
+            name: String,
+            #[account(mut, seeds = [b"vault", owner.key().as_ref()], bump)]
+            vault: solana::Vault,
+            #[doc = "explicit doc"]
+            r#type: u8,
+            optional: Option<Option<u64>>,
+            list: Vec<[u8; 0x20]>,
+            ballots: Vec<Option<u32>>,
+            slots: [Option<u8>; 3],
+            tally: BTreeMap<Option<u8>, Option<u64>>,
+            slice: [PublicKey],
+            grid: [[u16; 4_usize]; 2],
+            map: HashMap<PublicKey, Vec<u64>>,
+            tree: ::std::collections::BTreeMap<u8, String>,
+            generic: Wrapper<'static, u8>,
+            /* comment */ after_comment: bool, // trailing
+            #[some::path] pathed: u8,
+            #[range(0..=100)] bounded: i64,
+            #[literals(1.5e-3, 'c', b'x', "s", -7)] floats: f64,
+            #[config { a: 1 }] braced: u8,
+            pub(crate) restricted: Option<Vec<u8>>,
+        }
+
+        #[solana]
+        pub(crate) enum Event {
+            /// Unit
+            #[deprecated]
+            Started,
+            Moved(PublicKey, Option<u64>),
+            Named { from: PublicKey, #[max(10)] memo: String },
+            Explicit = 5,
+            #[ignored::path(1)] Last
+        }
+
+        struct Unit {}
+
+        fn ignored() -> u8 { 1 }
+        impl Vault { fn x(&self) {} }
+        use std::collections::{HashMap, BTreeMap};
+        type Alias = u8;
+        mod inner;
+        macro_rules! m { () => {} }
+        m!(x);
+        static S: [u8; 2] = [1, 2];
+        const fn helper() {}
+    "##;
+
+    /// Both parsers give the same AST, spans included, or the same error
+    fn assert_same(source: &str) {
+        let fast = parse_lumos_file(source).map(|file| format!("{:#?}", file));
+        let syn = parse_with_syn(source).map(|file| format!("{:#?}", file));
+        match (fast, syn) {
+            (Ok(fast), Ok(syn)) => assert_eq!(fast, syn, "in:\n{}", source),
+            (Err(fast), Err(syn)) => {
+                assert_eq!(fast.to_string(), syn.to_string(), "in:\n{}", source)
+            }
+            (fast, syn) => panic!("fast: {:?}\nsyn: {:?}\nin:\n{}", fast, syn, source),
+        }
+    }
+
+    #[test]
+    fn matches_syn_on_example_schemas() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let mut schemas = Vec::new();
+        for dir in ["examples", "linguist/samples"] {
+            for entry in fs::read_dir(root.join(dir)).unwrap() {
+                let path = entry.unwrap().path();
+                let schema = match path.is_dir() {
+                    true => path.join("schema.lumos"),
+                    false => path,
+                };
+                if schema.extension().is_some_and(|ext| ext == "lumos") && schema.exists() {
+                    schemas.push(schema);
+                }
+            }
+        }
+        assert!(schemas.len() >= 6, "{:?}", schemas);
+
+        for schema in schemas {
+            assert_same(&fs::read_to_string(schema).unwrap());
+        }
+    }
+
+    #[test]
+    fn matches_syn_on_every_syntax_form() {
+        assert_same(KITCHEN_SINK);
+        assert_same(&KITCHEN_SINK.replace('\n', "\r\n"));
+        assert_same(&format!("\u{feff}{}", KITCHEN_SINK));
+        assert_same(&format!("import \"a.lumos\";\n{}", KITCHEN_SINK));
+        assert_same("import \"a.lumos\";\nimport \"b.lumos\"; // more\n");
+        assert_same("struct A { x: u8 } struct B { y: A, }");
+    }
+
+    #[test]
+    fn reports_schema_errors_like_syn() {
+        for source in [
+            "struct A(u8);",
+            "struct A;",
+            "enum E {}",
+            "const A: i32 = 1;\nstruct B {}",
+            "const A: Option<u8> = 1;\nstruct B {}",
+            "struct S { x: Vec }",
+            "struct S { x: Vec<> }",
+            "struct S { x: Vec<u8, u16> }",
+            "struct S { x: HashMap<u64> }",
+            "struct S { x: [u8; N] }",
+            "struct S { x: [u8; 0] }",
+            "struct S { x: [u8; 1.0] }",
+            "#[a::b(1)] struct S {}",
+            "struct S { #[a::b(1)] x: u8 }",
+            // The first schema error in source order
+            "struct A(u8);\nstruct B { x: Vec }",
+            "enum E { A(Vec) }\nstruct S;",
+            "const ONLY: u8 = 1;",
+            "struct A { x: u8 }\nimport \"a.lumos\"\n",
+            "schema {\n    name = \"a\",\n    owner = \"b\",\n}\nstruct A {}",
+            "schema { version = \"1\" }\nstruct A {}",
+            "schema { name = \"a\", name = \"b\" }\nstruct A {}",
+            "schema { name = 1 }\nstruct A {}",
+            "schema { name = \"a\" + \"b\" }\nstruct A {}",
+            "schema { name = \"a\", authors = \"b\" }\nstruct A {}",
+            "schema { name = \"a\", authors = [\"b\", 1] }\nstruct A {}",
+            "schema { name = \"a\", a::b = \"c\" }\nstruct A {}",
+        ] {
+            assert_same(source);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_syntax() {
+        for source in [
+            "struct A { x: u8 y: u8 }",
+            "struct A { x: }",
+            "struct A {",
+            "struct A { x: u8 } }",
+            "struct A { x: u8 ]",
+            "struct A { x: (u8, u16) }",
+            "struct A { x: &u8 }",
+            "struct A { x: u8, /// dangling\n }",
+            "struct A { #! [inner] x: u8 }",
+            "struct A { x: u8 }\nschema { name = \"a\" }",
+            "struct A { x: \"unterminated }",
+            "struct A { x: u8 } /* open",
+            "enum",
+            "banana A {}",
+            "struct enum { x: u8 }",
+            "struct A { type: u8 }",
+            "struct A { x: Option<fn> }",
+            "enum A { Self }",
+        ] {
+            assert!(parse_lumos_file(source).is_err(), "{}", source);
+            assert!(parse_with_syn(source).is_err(), "{}", source);
+        }
+
+        // Keywords are names only where syn allows them
+        assert_same("struct A { r#type: u8 }");
+        assert_same("struct A { x: crate::B, y: self::C }");
+        assert_same("schema { type = \"a\" }\nstruct A { x: u8 }");
+        assert_same("struct A { x: Wrapper<'static, u8> }");
+        assert_same("struct A { _: u8 }");
+        assert_same("struct A {\n    x: u8,\n    match: u8,\n}");
+
+        let error = parse_lumos_file("struct A {\n    x: u8\n    y: u8,\n}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to parse .lumos file: expected `,` at line 3, column 5"
+        );
+    }
+
+    #[test]
+    fn renders_tokens_like_proc_macro2() {
+        for source in [
+            "a::b<'c>",
+            "x+=1;-y",
+            "f(a,b)[0]{}{ x }",
+            "1.5e-3 0x1F_u8 1..2 1.max(2)",
+            "a/ /b // c",
+            "/// doc\nx /*! inner */",
+            "r#type b\"x\" br#\"y\"# 'a' '\\'' c",
+        ] {
+            let tokens = Lexer::new(source).tokenize().unwrap();
+            let len = tokens.len();
+            let expected = source.parse::<proc_macro2::TokenStream>().unwrap();
+            assert_eq!(Parser::new(tokens).render(0..len), expected.to_string());
+        }
+
+        assert_eq!(
+            string_value(r#""a\"b\n\u{263A}""#).unwrap(),
+            "a\"b\n\u{263A}"
+        );
+        assert_eq!(string_value("r##\"a\"#b\"##").unwrap(), "a\"#b");
+        assert_eq!(int_value("0b1_0u16"), Some(2));
+        assert_eq!(int_value("32f32"), None);
+    }
+}
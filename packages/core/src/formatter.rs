@@ -0,0 +1,847 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Canonical formatting of `.lumos` files (`lumos fmt`)
+//!
+//! The schema is parsed first, so only valid schemas are formatted. It is
+//! then printed again from its tokens, which keeps comments and the
+//! spelling the author chose (`Vec<T>` or `[T]`, `0x20` or `32`):
+//!
+//! - Four-space indentation, one field, variant or schema key per line,
+//!   each followed by a comma
+//! - Doc comments first, then attributes in [`ATTRIBUTE_ORDER`]; unknown
+//!   attributes follow in source order
+//! - Types, attribute arguments and values spaced the way `rustfmt` spaces
+//!   them: `Option<Vec<u8>>`, `[u8; 32]`, `#[account(mut, seeds = [b"a"])]`
+//! - A blank line around structs, enums and the schema block, and wherever
+//!   the source had one; runs of blank lines become one
+//!
+//! Comments on their own line stay before the code that follows them, and
+//! comments at the end of a line stay at the end of that line. Formatting is
+//! idempotent: formatting the output again leaves it unchanged.
+
+use crate::error::{LumosError, Result, SourceLocation};
+use crate::lexer::{closing, Kind, Lexer, Token};
+use crate::parser;
+use std::ops::Range;
+
+/// Canonical order of attributes on a type or field
+pub const ATTRIBUTE_ORDER: [&str; 23] = [
+    "solana",
+    "account",
+    "event",
+    "instruction",
+    "zero_copy",
+    "version",
+    "state_hash",
+    "display",
+    "init",
+    "init_space",
+    "close",
+    "realloc",
+    "key",
+    "signer",
+    "min",
+    "max",
+    "range",
+    "since",
+    "sensitive",
+    "codec",
+    "skip",
+    "test_vector",
+    "deprecated",
+];
+
+/// Operators of more than one character, longest first
+const OPERATORS: [&str; 25] = [
+    "<<=", ">>=", "...", "..=", "::", "->", "=>", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=",
+    "*=", "/=", "%=", "^=", "&=", "|=", "<<", ">>", "..", "**",
+];
+
+/// Indentation of one nesting level
+const INDENT: &str = "    ";
+
+/// Format a `.lumos` source
+///
+/// Returns the parse error for schemas that don't parse.
+pub fn format_source(source: &str) -> Result<String> {
+    let original = meaning(source)?;
+
+    let source = source.trim_start_matches('\u{feff}');
+    let tokens = Lexer::new(source).with_comments().tokenize()?;
+    let mut formatter = Formatter {
+        chars: source.chars().collect(),
+        tokens,
+        pos: 0,
+        out: String::new(),
+        indent: 0,
+    };
+    formatter.file()?;
+
+    // A formatter bug must not change the schema it was asked to tidy
+    if meaning(&formatter.out).ok() != Some(original) {
+        return Err(LumosError::SchemaParse(
+            "formatting would change what the schema means; the schema is left as is".to_string(),
+            None,
+        ));
+    }
+    Ok(formatter.out)
+}
+
+/// The parsed schema as JSON, without what formatting may change: the
+/// lines of imports and the order of attributes
+fn meaning(source: &str) -> Result<serde_json::Value> {
+    let file = parser::parse_lumos_file(source)?;
+    let mut value = serde_json::to_value(file).map_err(|error| {
+        LumosError::SchemaParse(format!("failed to serialize the schema: {}", error), None)
+    })?;
+    if let Some(imports) = value.get_mut("imports").and_then(|v| v.as_array_mut()) {
+        for import in imports
+            .iter_mut()
+            .filter_map(|import| import.as_object_mut())
+        {
+            import.remove("line");
+        }
+    }
+    sort_attributes(&mut value);
+    Ok(value)
+}
+
+fn sort_attributes(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if let (true, Some(attributes)) = (key == "attributes", value.as_array_mut()) {
+                    attributes.sort_by_cached_key(|attribute| attribute.to_string());
+                }
+                sort_attributes(value);
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(sort_attributes),
+        _ => {}
+    }
+}
+
+/// Whether formatting would change `source`
+pub fn is_formatted(source: &str) -> Result<bool> {
+    Ok(format_source(source)? == source)
+}
+
+/// How types and expressions are spaced
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    /// `<` and `>` are generic brackets: `Vec<Option<u8>>`
+    Type,
+
+    /// `<` and `>` are operators: `1 << 4`
+    Expr,
+}
+
+/// What a `{ ... }` body holds
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Body {
+    Fields,
+    Variants,
+    SchemaKeys,
+}
+
+/// A piece of an inline rendering, with operators merged
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Word(String),
+    Op(String),
+    Group(char, String),
+    Comma,
+    Semi,
+}
+
+/// A doc comment or attribute, with the comments that go with it
+struct AttributeLines {
+    rank: usize,
+    lines: Vec<String>,
+}
+
+struct Formatter {
+    chars: Vec<char>,
+    tokens: Vec<Token>,
+    pos: usize,
+    out: String,
+    indent: usize,
+}
+
+impl Formatter {
+    fn kind(&self, i: usize) -> Option<&Kind> {
+        self.tokens.get(i).map(|token| &token.kind)
+    }
+
+    fn is_punct(&self, i: usize, c: char) -> bool {
+        matches!(self.kind(i), Some(Kind::Punct(p, _)) if *p == c)
+    }
+
+    fn is_ident(&self, i: usize, name: &str) -> bool {
+        matches!(self.kind(i), Some(Kind::Ident(word)) if word == name)
+    }
+
+    /// Index of the closing delimiter of the group opened at `i`
+    fn partner(&self, i: usize) -> usize {
+        match self.kind(i) {
+            Some(Kind::Open(_, close)) => *close,
+            _ => i,
+        }
+    }
+
+    /// The source text of tokens `first..=last`
+    fn verbatim(&self, first: usize, last: usize) -> String {
+        let range = self.tokens[first].range.start..self.tokens[last].range.end;
+        self.chars[range].iter().collect()
+    }
+
+    /// Whether the source has a blank line before token `i`
+    fn blank_before(&self, i: usize) -> bool {
+        i > 0 && i < self.tokens.len() && self.tokens[i].span.line > self.tokens[i - 1].end_line + 1
+    }
+
+    /// Write one line at the current indentation
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.indent {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(text.trim_end());
+        self.out.push('\n');
+    }
+
+    /// A comment that ends the line of the last consumed token
+    fn trailing_comment(&mut self) -> Option<String> {
+        let comment = match (self.kind(self.pos), self.pos.checked_sub(1)) {
+            (Some(Kind::Comment(text)), Some(last))
+                if self.tokens[self.pos].span.line == self.tokens[last].end_line =>
+            {
+                text.clone()
+            }
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(comment)
+    }
+
+    /// `text`, followed by the trailing comment if there is one
+    fn with_trailing_comment(&mut self, text: String) -> String {
+        match self.trailing_comment() {
+            Some(comment) => format!("{} {}", text, comment),
+            None => text,
+        }
+    }
+
+    /// Index of the first `c` at the top level of `range`, or its end
+    fn find_punct(&self, c: char, range: Range<usize>) -> usize {
+        let mut i = range.start;
+        while i < range.end && !self.is_punct(i, c) {
+            i = self.partner(i) + 1;
+        }
+        i.min(range.end)
+    }
+
+    fn file(&mut self) -> Result<()> {
+        let mut previous_block = None;
+        while self.pos < self.tokens.len() {
+            let blank = self.blank_before(self.pos);
+            let before = std::mem::take(&mut self.out);
+            let block = self.entry()?;
+            let entry = std::mem::replace(&mut self.out, before);
+
+            if let Some(previous_block) = previous_block {
+                if blank || block || previous_block {
+                    self.out.push('\n');
+                }
+            }
+            self.out.push_str(&entry);
+            previous_block = Some(block);
+        }
+        Ok(())
+    }
+
+    /// One top-level entry; returns whether it is a block (struct, enum,
+    /// schema block or other item with a body)
+    fn entry(&mut self) -> Result<bool> {
+        let start = self.pos;
+        match self.kind(start) {
+            Some(Kind::Comment(_)) => {
+                self.comments(usize::MAX)?;
+                if self.pos >= self.tokens.len() || self.blank_before(self.pos) {
+                    return Ok(false);
+                }
+                return self.entry();
+            }
+            Some(Kind::Doc(_, true)) => {
+                let doc = self.verbatim(start, start);
+                self.pos += 1;
+                let doc = self.with_trailing_comment(doc);
+                self.line(&doc);
+                return Ok(false);
+            }
+            Some(Kind::Punct('#', _)) if self.is_punct(start + 1, '!') => {
+                let close = self.partner(start + 2);
+                let attribute = format!("#![{}]", self.inline(start + 3..close, Mode::Expr)?);
+                self.pos = close + 1;
+                let attribute = self.with_trailing_comment(attribute);
+                self.line(&attribute);
+                return Ok(false);
+            }
+            _ if self.at_import() => {
+                let import = self.import()?;
+                self.line(&import);
+                return Ok(false);
+            }
+            Some(Kind::Ident(word))
+                if word == "schema" && matches!(self.kind(start + 1), Some(Kind::Open('{', _))) =>
+            {
+                self.pos += 1;
+                let resume = self.partner(self.pos) + 1;
+                self.body("schema".to_string(), Body::SchemaKeys, "", resume)?;
+                return Ok(true);
+            }
+            _ => {}
+        }
+        self.item()
+    }
+
+    /// Comments on their own lines before `end`, keeping a blank line
+    /// between them where the source had one
+    ///
+    /// Import lines count as comments here: the parser takes them out
+    /// wherever they are, so they stay where they were written.
+    fn comments(&mut self, end: usize) -> Result<()> {
+        let mut first = true;
+        while self.pos < end {
+            let blank = !first && self.blank_before(self.pos);
+            let line = match self.kind(self.pos) {
+                Some(Kind::Comment(comment)) => {
+                    let comment = comment.clone();
+                    self.pos += 1;
+                    comment
+                }
+                _ if self.at_import() => self.import()?,
+                _ => break,
+            };
+            if blank {
+                self.out.push('\n');
+            }
+            first = false;
+            self.line(&line);
+        }
+        Ok(())
+    }
+
+    /// Whether an `import "file.lumos";` line starts here
+    fn at_import(&self) -> bool {
+        if !self.is_ident(self.pos, "import")
+            || !matches!(self.kind(self.pos + 1), Some(Kind::Literal(path)) if path.starts_with('"'))
+        {
+            return false;
+        }
+        match self.pos.checked_sub(1) {
+            Some(previous) => self.tokens[previous].end_line < self.tokens[self.pos].span.line,
+            None => true,
+        }
+    }
+
+    /// The import line at `self.pos`
+    fn import(&mut self) -> Result<String> {
+        let end = self.find_punct(';', self.pos..self.tokens.len());
+        let import = format!("{};", self.inline(self.pos..end, Mode::Expr)?);
+        self.pos = end + 1;
+        Ok(self.with_trailing_comment(import))
+    }
+
+    /// Doc comments and attributes before an item, field or variant,
+    /// written in canonical order
+    fn attributes(&mut self) -> Result<()> {
+        let mut attributes: Vec<AttributeLines> = Vec::new();
+        let mut comments: Vec<String> = Vec::new();
+        loop {
+            let start = self.pos;
+            let (rank, text) = match self.kind(start) {
+                Some(Kind::Comment(comment)) => {
+                    comments.push(comment.clone());
+                    self.pos += 1;
+                    continue;
+                }
+                _ if self.at_import() => {
+                    let import = self.import()?;
+                    comments.push(import);
+                    continue;
+                }
+                Some(Kind::Doc(_, false)) => {
+                    self.pos += 1;
+                    (0, self.verbatim(start, start))
+                }
+                Some(Kind::Punct('#', _))
+                    if matches!(self.kind(start + 1), Some(Kind::Open('[', _))) =>
+                {
+                    let close = self.partner(start + 1);
+                    let rank = match self.kind(start + 2) {
+                        Some(Kind::Ident(name)) if name == "doc" => 0,
+                        Some(Kind::Ident(name)) => ATTRIBUTE_ORDER
+                            .iter()
+                            .position(|known| known == name)
+                            .map_or(ATTRIBUTE_ORDER.len() + 1, |index| index + 1),
+                        _ => ATTRIBUTE_ORDER.len() + 1,
+                    };
+                    self.pos = close + 1;
+                    (
+                        rank,
+                        format!("#[{}]", self.inline(start + 2..close, Mode::Expr)?),
+                    )
+                }
+                _ => break,
+            };
+            let mut lines = std::mem::take(&mut comments);
+            lines.push(self.with_trailing_comment(text));
+            attributes.push(AttributeLines { rank, lines });
+        }
+
+        attributes.sort_by_key(|attribute| attribute.rank);
+        for line in attributes.iter().flat_map(|attribute| &attribute.lines) {
+            self.line(line);
+        }
+        for comment in comments {
+            self.line(&comment);
+        }
+        Ok(())
+    }
+
+    /// A struct, enum, const or other top-level item
+    fn item(&mut self) -> Result<bool> {
+        self.attributes()?;
+        let start = self.pos;
+        let end = self.tokens.len();
+
+        let mut keyword = start;
+        if self.is_ident(keyword, "pub") {
+            keyword += 1;
+            if matches!(self.kind(keyword), Some(Kind::Open('(', _))) {
+                keyword = self.partner(keyword) + 1;
+            }
+        }
+        let body = match self.kind(keyword) {
+            Some(Kind::Ident(word)) if word == "struct" => Some(Body::Fields),
+            Some(Kind::Ident(word)) if word == "enum" => Some(Body::Variants),
+            _ => None,
+        };
+        let brace = (keyword..end)
+            .find(|&i| {
+                matches!(
+                    self.kind(i),
+                    Some(Kind::Open(..)) | Some(Kind::Punct(';', _))
+                )
+            })
+            .filter(|&i| matches!(self.kind(i), Some(Kind::Open('{', _))));
+
+        if let (Some(body), Some(brace)) = (body, brace) {
+            let head = self.inline(start..brace, Mode::Type)?;
+            self.pos = brace;
+            self.body(head, body, "", self.partner(brace) + 1)?;
+            return Ok(true);
+        }
+
+        if self.is_ident(keyword, "const") {
+            let semi = self.find_punct(';', start..end);
+            let constant = format!("{};", self.inline(start..semi, Mode::Expr)?);
+            self.pos = semi + 1;
+            let constant = self.with_trailing_comment(constant);
+            self.line(&constant);
+            return Ok(false);
+        }
+
+        // Items schemas ignore are kept as written
+        let mut last = start;
+        let mut block = false;
+        while last < end {
+            match self.kind(last) {
+                Some(Kind::Punct(';', _)) => break,
+                Some(Kind::Open('{', close)) => {
+                    last = *close;
+                    block = true;
+                    if !self.is_punct(last + 1, ';') {
+                        break;
+                    }
+                }
+                Some(Kind::Open(_, close)) => last = *close,
+                _ => {}
+            }
+            last += 1;
+        }
+        let last = last.min(end - 1);
+        let item = self.verbatim(start, last);
+        self.pos = last + 1;
+        let item = self.with_trailing_comment(item);
+        self.line(&item);
+        Ok(block)
+    }
+
+    /// A `{ ... }` body at `self.pos`, written after `head`; `suffix`
+    /// follows the closing brace, and the tokens before `resume` are part of
+    /// the line it ends
+    fn body(&mut self, head: String, body: Body, suffix: &str, resume: usize) -> Result<()> {
+        let close = self.partner(self.pos);
+        self.pos += 1;
+        if self.pos == close {
+            self.pos = resume;
+            let line = self.with_trailing_comment(format!("{} {{}}{}", head, suffix));
+            self.line(&line);
+            return Ok(());
+        }
+
+        let head = self.with_trailing_comment(format!("{} {{", head));
+        self.line(&head);
+        self.indent += 1;
+        let mut first = true;
+        while self.pos < close {
+            if !first && self.blank_before(self.pos) {
+                self.out.push('\n');
+            }
+            first = false;
+            self.member(body, close)?;
+        }
+        self.indent -= 1;
+
+        self.pos = resume;
+        let end = self.with_trailing_comment(format!("}}{}", suffix));
+        self.line(&end);
+        Ok(())
+    }
+
+    /// A field, variant or schema key, with its comments and attributes
+    fn member(&mut self, body: Body, close: usize) -> Result<()> {
+        let first = self.pos;
+        self.comments(close)?;
+        if self.pos == close {
+            return Ok(());
+        }
+        if self.pos > first && self.blank_before(self.pos) {
+            self.out.push('\n');
+        }
+        self.attributes()?;
+
+        let start = self.pos;
+        let end = self.member_end(start..close, body == Body::Fields);
+        let resume = (end + 1).min(close);
+        let group = match self.kind(start + 1) {
+            Some(Kind::Open(open, _)) if start + 1 < end => Some(*open),
+            _ => None,
+        };
+        if body == Body::Variants && group == Some('{') {
+            let name = self.inline(start..start + 1, Mode::Type)?;
+            let rest = self.inline(self.partner(start + 1) + 1..end, Mode::Expr)?;
+            let suffix = match rest.is_empty() {
+                true => ",".to_string(),
+                false => format!(" {},", rest),
+            };
+            self.pos = start + 1;
+            return self.body(name, Body::Fields, &suffix, resume);
+        }
+
+        let mode = match body {
+            Body::Fields => Mode::Type,
+            Body::Variants if group == Some('(') => Mode::Type,
+            _ => Mode::Expr,
+        };
+        let text = format!("{},", self.inline(start..end, mode)?);
+        self.pos = resume;
+        let text = self.with_trailing_comment(text);
+        self.line(&text);
+        Ok(())
+    }
+
+    /// Index of the comma ending the member that starts `range`, or the
+    /// range's end; with `generics`, commas between `<` and `>` don't count
+    fn member_end(&self, range: Range<usize>, generics: bool) -> usize {
+        let mut depth = 0usize;
+        let mut i = range.start;
+        while i < range.end {
+            match self.kind(i) {
+                Some(Kind::Punct(',', _)) if depth == 0 => return i,
+                Some(Kind::Punct('<', _)) if generics => depth += 1,
+                Some(Kind::Punct('>', _)) if generics && !self.is_punct(i.wrapping_sub(1), '-') => {
+                    depth = depth.saturating_sub(1)
+                }
+                _ => {}
+            }
+            i = self.partner(i) + 1;
+        }
+        range.end
+    }
+
+    /// A comment where the formatter can't keep it
+    fn unsupported(&self, i: usize) -> LumosError {
+        let span = self.tokens[i].span;
+        LumosError::SchemaParse(
+            "cannot format a comment inside a type, attribute or value; \
+             move it onto its own line"
+                .to_string(),
+            Some(SourceLocation::new(span.line, span.column + 1)),
+        )
+    }
+
+    /// Tokens in `range` on one line, canonically spaced
+    fn inline(&self, range: Range<usize>, mode: Mode) -> Result<String> {
+        let pieces = self.pieces(range, mode)?;
+        let mut text = String::new();
+        let mut joined = false;
+        for (i, piece) in pieces.iter().enumerate() {
+            let previous = i.checked_sub(1).map(|i| &pieces[i]);
+            let operand = matches!(previous, Some(Piece::Word(_) | Piece::Group(..)));
+            let (unary, macro_call) = match piece {
+                Piece::Op(op) => (
+                    matches!(op.as_str(), "-" | "!" | "&" | "*") && !operand,
+                    op == "!" && operand && matches!(pieces.get(i + 1), Some(Piece::Group(..))),
+                ),
+                _ => (false, false),
+            };
+            if let Some(previous) = previous {
+                if !joined && !macro_call && space_between(previous, piece, mode) {
+                    text.push(' ');
+                }
+            }
+            // Unary operators and the bang of `vec![...]` join what follows
+            joined = unary || macro_call;
+
+            match piece {
+                Piece::Word(word) | Piece::Op(word) => text.push_str(word),
+                Piece::Group(open, inner) => {
+                    text.push(*open);
+                    if *open == '{' && !inner.is_empty() {
+                        text.push_str(&format!(" {} ", inner));
+                    } else {
+                        text.push_str(inner);
+                    }
+                    text.push(closing(*open));
+                }
+                Piece::Comma => text.push(','),
+                Piece::Semi => text.push(';'),
+            }
+        }
+        Ok(text)
+    }
+
+    /// Tokens in `range` as pieces, with multi-character operators merged
+    fn pieces(&self, range: Range<usize>, mode: Mode) -> Result<Vec<Piece>> {
+        let mut pieces = Vec::new();
+        let mut i = range.start;
+        while i < range.end {
+            match &self.tokens[i].kind {
+                Kind::Ident(word) | Kind::Literal(word) => pieces.push(Piece::Word(word.clone())),
+                Kind::Open(open, close) => {
+                    let mut inner = i + 1..*close;
+                    // Trailing commas go, except in a one-element tuple
+                    let commas = (inner.clone()).filter(|&j| self.is_punct(j, ',')).count();
+                    if inner.end > inner.start
+                        && self.is_punct(inner.end - 1, ',')
+                        && !(*open == '(' && commas == 1)
+                    {
+                        inner.end -= 1;
+                    }
+                    pieces.push(Piece::Group(*open, self.inline(inner, mode)?));
+                    i = *close;
+                }
+                Kind::Punct('\'', _) if matches!(self.kind(i + 1), Some(Kind::Ident(_))) => {
+                    let Some(Kind::Ident(name)) = self.kind(i + 1) else {
+                        unreachable!("checked above");
+                    };
+                    pieces.push(Piece::Word(format!("'{}", name)));
+                    i += 1;
+                }
+                Kind::Punct(..) => {
+                    let mut run = String::new();
+                    let mut j = i;
+                    while let Some(Kind::Punct(c, joint)) = self.kind(j) {
+                        run.push(*c);
+                        j += 1;
+                        if !joint || j >= range.end {
+                            break;
+                        }
+                    }
+                    let op = OPERATORS
+                        .iter()
+                        .filter(|op| mode == Mode::Expr || !op.contains(['<', '>']) || **op == "->")
+                        .find(|op| run.starts_with(**op))
+                        .map_or_else(|| run[..1].to_string(), |op| op.to_string());
+                    i += op.chars().count() - 1;
+                    pieces.push(match op.as_str() {
+                        "," => Piece::Comma,
+                        ";" => Piece::Semi,
+                        _ => Piece::Op(op),
+                    });
+                }
+                Kind::Doc(..) | Kind::Comment(_) => return Err(self.unsupported(i)),
+                Kind::Close(_) => {}
+            }
+            i += 1;
+        }
+        Ok(pieces)
+    }
+}
+
+/// Whether a space separates two adjacent pieces
+fn space_between(previous: &Piece, piece: &Piece, mode: Mode) -> bool {
+    let is_op =
+        |piece: &Piece, ops: &[&str]| matches!(piece, Piece::Op(op) if ops.contains(&op.as_str()));
+    let tight = ["::", ".", "..", "..="];
+
+    if matches!(piece, Piece::Comma | Piece::Semi) || is_op(piece, &["?", ":"]) {
+        return false;
+    }
+    if matches!(previous, Piece::Comma | Piece::Semi) || is_op(previous, &[":"]) {
+        return true;
+    }
+    if is_op(previous, &tight) || is_op(piece, &tight) {
+        return false;
+    }
+    if mode == Mode::Type && (is_op(piece, &["<", ">"]) || is_op(previous, &["<"])) {
+        return false;
+    }
+    match (previous, piece) {
+        (_, Piece::Op(_)) | (Piece::Op(_), _) => true,
+        (Piece::Word(_) | Piece::Group(..), Piece::Group('(' | '[', _)) => false,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    const MESSY: &str = r#"//! Vault schema
+import "common/tokens.lumos";
+schema { name="vault" , authors = [ "a" , "b", ], }
+// Limits
+const MAX_NAME:u32=32*2;
+const SHIFTED : u64 = 1<<4 ;
+
+
+/// The vault
+#[account]   // anchor
+#[solana]
+#[custom( a , b )]
+pub struct Vault{
+  owner : PublicKey, // who
+  #[max(MAX_NAME)] name:String,
+
+
+  balances:HashMap< String ,Vec<u64> >,
+  data : [ u8 ; 32 ],
+  list: [PublicKey],
+  #[account(mut,seeds=[b"vault",owner.key().as_ref()],bump)]
+  other: Option<Vec<Option<u8>>>}
+#[solana] enum State { Active=1, Paused(u8,PublicKey,), Closed{ at:i64, by: PublicKey } , // closed
+  /// Legacy
+  Old }
+"#;
+
+    const FORMATTED: &str = r#"//! Vault schema
+import "common/tokens.lumos";
+
+schema {
+    name = "vault",
+    authors = ["a", "b"],
+}
+
+// Limits
+const MAX_NAME: u32 = 32 * 2;
+const SHIFTED: u64 = 1 << 4;
+
+/// The vault
+#[solana]
+#[account] // anchor
+#[custom(a, b)]
+pub struct Vault {
+    owner: PublicKey, // who
+    #[max(MAX_NAME)]
+    name: String,
+
+    balances: HashMap<String, Vec<u64>>,
+    data: [u8; 32],
+    list: [PublicKey],
+    #[account(mut, seeds = [b"vault", owner.key().as_ref()], bump)]
+    other: Option<Vec<Option<u8>>>,
+}
+
+#[solana]
+enum State {
+    Active = 1,
+    Paused(u8, PublicKey),
+    Closed {
+        at: i64,
+        by: PublicKey,
+    }, // closed
+    /// Legacy
+    Old,
+}
+"#;
+
+    #[test]
+    fn formats_to_canonical_layout() {
+        assert_eq!(format_source(MESSY).unwrap(), FORMATTED);
+        assert!(is_formatted(FORMATTED).unwrap());
+        assert!(!is_formatted(MESSY).unwrap());
+
+        // CRLF sources and a byte order mark come out the same
+        let crlf = format!("\u{feff}{}", MESSY.replace('\n', "\r\n"));
+        assert_eq!(format_source(&crlf).unwrap(), FORMATTED);
+    }
+
+    #[test]
+    fn keeps_example_schemas_meaning() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        for dir in ["examples", "linguist/samples"] {
+            for entry in fs::read_dir(root.join(dir)).unwrap() {
+                let path = entry.unwrap().path();
+                let schema = match path.is_dir() {
+                    true => path.join("schema.lumos"),
+                    false => path,
+                };
+                if !schema.extension().is_some_and(|ext| ext == "lumos") || !schema.exists() {
+                    continue;
+                }
+
+                let source = fs::read_to_string(&schema).unwrap();
+                let formatted = format_source(&source).unwrap();
+                assert_eq!(
+                    format_source(&formatted).unwrap(),
+                    formatted,
+                    "not idempotent on {}",
+                    schema.display()
+                );
+
+                // Spans move; everything serialized stays the same
+                let ast = |source: &str| {
+                    serde_json::to_string(&parser::parse_lumos_file(source).unwrap()).unwrap()
+                };
+                assert_eq!(ast(&source), ast(&formatted), "{}", schema.display());
+            }
+        }
+    }
+
+    #[test]
+    fn orders_attributes_canonically() {
+        let source = "#[derive(Debug)]\n#[zero_copy]\n/// Docs\n#[solana]\n#[account]\nstruct A {\n    #[since(2)]\n    #[max(4)]\n    #[key]\n    id: String,\n}\n";
+        let expected = "/// Docs\n#[solana]\n#[account]\n#[zero_copy]\n#[derive(Debug)]\nstruct A {\n    #[key]\n    #[max(4)]\n    #[since(2)]\n    id: String,\n}\n";
+        assert_eq!(format_source(source).unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_what_it_cannot_format() {
+        let error = format_source("struct A { x: u8 y: u8 }").unwrap_err();
+        assert!(error.to_string().contains("parse"), "{}", error);
+
+        let error = format_source("struct A {\n    x: Option</* note */ u8>,\n}\n").unwrap_err();
+        assert!(
+            error.to_string().contains("move it onto its own line"),
+            "{}",
+            error
+        );
+        assert!(error.to_string().contains("line 2"), "{}", error);
+    }
+}
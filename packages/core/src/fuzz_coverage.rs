@@ -0,0 +1,581 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Schema-level coverage for fuzz corpora
+//!
+//! Replays corpus inputs through a Borsh decoder driven by the schema IR and
+//! records which fields and enum variants were decoded. A field counts as
+//! exercised when it decoded with a value present (`Some` for options, a
+//! non-empty vec for arrays), since that is the path that runs its element
+//! decoder. Only inputs that decode completely, with no bytes left over,
+//! count towards coverage. This complements the line/region totals reported by llvm-cov,
+//! which cannot be tied back to individual schema fields.
+
+use crate::fuzz_generator::DISPATCH_TYPE_NAME;
+use crate::ir::{EnumVariantDefinition, FieldDefinition, TypeDefinition, TypeInfo};
+use serde_json::Value;
+
+/// Maximum nesting depth followed while decoding (guards recursive types)
+const MAX_DEPTH: usize = 32;
+
+/// Coverage of a single field or variant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageEntry {
+    /// Field name, `Variant.field` for enum payloads, or variant name
+    pub name: String,
+
+    /// Number of inputs that exercised this entry
+    pub hits: usize,
+}
+
+/// Schema-level coverage for one fuzz target
+#[derive(Debug, Clone)]
+pub struct TypeCoverage {
+    /// Type the target decodes (or [`DISPATCH_TYPE_NAME`])
+    pub type_name: String,
+
+    /// Number of corpus inputs replayed
+    pub inputs: usize,
+
+    /// Number of inputs that decoded completely
+    pub decoded: usize,
+
+    /// Field coverage, in schema order
+    pub fields: Vec<CoverageEntry>,
+
+    /// Variant coverage, in schema order (enums and the dispatcher only)
+    pub variants: Vec<CoverageEntry>,
+}
+
+impl TypeCoverage {
+    /// Fields no input exercised
+    pub fn untested_fields(&self) -> Vec<&str> {
+        untested(&self.fields)
+    }
+
+    /// Variants no input exercised
+    pub fn untested_variants(&self) -> Vec<&str> {
+        untested(&self.variants)
+    }
+
+    /// Percentage of fields and variants exercised at least once
+    pub fn percent(&self) -> f64 {
+        let total = self.fields.len() + self.variants.len();
+        if total == 0 {
+            return 100.0;
+        }
+
+        let covered = self
+            .fields
+            .iter()
+            .chain(&self.variants)
+            .filter(|e| e.hits > 0)
+            .count();
+
+        covered as f64 * 100.0 / total as f64
+    }
+}
+
+/// Add a hit to every entry whose field carried a value
+fn record(entries: &mut [CoverageEntry], present: &[bool]) {
+    for (entry, &present) in entries.iter_mut().zip(present) {
+        if present {
+            entry.hits += 1;
+        }
+    }
+}
+
+fn untested(entries: &[CoverageEntry]) -> Vec<&str> {
+    entries
+        .iter()
+        .filter(|e| e.hits == 0)
+        .map(|e| e.name.as_str())
+        .collect()
+}
+
+/// Line, region and function totals from `llvm-cov export -summary-only`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LlvmCoverageSummary {
+    /// Percentage of lines executed
+    pub lines_percent: f64,
+
+    /// Percentage of regions executed
+    pub regions_percent: f64,
+
+    /// Percentage of functions executed
+    pub functions_percent: f64,
+}
+
+impl LlvmCoverageSummary {
+    /// Parse the JSON emitted by `llvm-cov export -summary-only`
+    pub fn parse(json: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(json).ok()?;
+        let totals = value.get("data")?.get(0)?.get("totals")?;
+        let percent = |key: &str| totals.get(key)?.get("percent")?.as_f64();
+
+        Some(Self {
+            lines_percent: percent("lines")?,
+            regions_percent: percent("regions")?,
+            functions_percent: percent("functions")?,
+        })
+    }
+}
+
+/// One decodable alternative: an enum variant or a dispatched instruction
+struct Alternative<'a> {
+    name: &'a str,
+    payload: Vec<(String, TypeInfo)>,
+}
+
+/// Replays corpus inputs against the schema
+pub struct CoverageAnalyzer<'a> {
+    /// All type definitions
+    type_defs: &'a [TypeDefinition],
+}
+
+impl<'a> CoverageAnalyzer<'a> {
+    /// Create a new coverage analyzer
+    pub fn new(type_defs: &'a [TypeDefinition]) -> Self {
+        Self { type_defs }
+    }
+
+    /// Replay `inputs` against the decode path of `type_name`
+    ///
+    /// Returns `None` if the type does not exist in the schema.
+    pub fn analyze(&self, type_name: &str, inputs: &[Vec<u8>]) -> Option<TypeCoverage> {
+        let mut coverage = TypeCoverage {
+            type_name: type_name.to_string(),
+            inputs: inputs.len(),
+            decoded: 0,
+            fields: Vec::new(),
+            variants: Vec::new(),
+        };
+
+        if type_name == DISPATCH_TYPE_NAME {
+            let alternatives = self.dispatch_alternatives();
+            if alternatives.is_empty() {
+                return None;
+            }
+            self.analyze_alternatives(&alternatives, inputs, &mut coverage);
+            return Some(coverage);
+        }
+
+        match self.find(type_name)? {
+            TypeDefinition::Struct(s) => {
+                let payload: Vec<_> = s.fields.iter().map(field_entry).collect();
+                coverage.fields = payload
+                    .iter()
+                    .map(|(name, _)| CoverageEntry {
+                        name: name.clone(),
+                        hits: 0,
+                    })
+                    .collect();
+
+                // Account corpora carry the 8-byte Anchor discriminator
+                let is_account = s.metadata.is_account();
+
+                for input in inputs {
+                    let mut data = input.as_slice();
+                    if is_account && take(&mut data, 8).is_none() {
+                        continue;
+                    }
+                    if let Some(present) = self.decode_complete(&payload, &mut data) {
+                        record(&mut coverage.fields, &present);
+                        coverage.decoded += 1;
+                    }
+                }
+            }
+            TypeDefinition::Enum(e) => {
+                let alternatives: Vec<_> = e.variants.iter().map(variant_alternative).collect();
+                self.analyze_alternatives(&alternatives, inputs, &mut coverage);
+            }
+        }
+
+        Some(coverage)
+    }
+
+    /// Instructions in dispatch order, mirroring the generated dispatcher
+    fn dispatch_alternatives(&self) -> Vec<Alternative<'a>> {
+        self.type_defs
+            .iter()
+            .filter(|t| t.is_instruction())
+            .map(|t| match t {
+                TypeDefinition::Struct(s) => Alternative {
+                    name: &s.name,
+                    payload: s.fields.iter().map(field_entry).collect(),
+                },
+                TypeDefinition::Enum(e) => Alternative {
+                    name: &e.name,
+                    payload: vec![(e.name.clone(), TypeInfo::UserDefined(e.name.clone()))],
+                },
+            })
+            .collect()
+    }
+
+    /// Decode a tag byte followed by the selected alternative's payload
+    fn analyze_alternatives(
+        &self,
+        alternatives: &[Alternative],
+        inputs: &[Vec<u8>],
+        coverage: &mut TypeCoverage,
+    ) {
+        coverage.variants = alternatives
+            .iter()
+            .map(|a| CoverageEntry {
+                name: a.name.to_string(),
+                hits: 0,
+            })
+            .collect();
+
+        // Field entries are flattened as `Alternative.field`; an instruction
+        // enum's single payload entry keeps its own name
+        let mut offsets = Vec::with_capacity(alternatives.len());
+        for alternative in alternatives {
+            offsets.push(coverage.fields.len());
+            for (name, _) in &alternative.payload {
+                let name = if name == alternative.name {
+                    name.clone()
+                } else {
+                    format!("{}.{}", alternative.name, name)
+                };
+                coverage.fields.push(CoverageEntry { name, hits: 0 });
+            }
+        }
+
+        for input in inputs {
+            let Some((&tag, rest)) = input.split_first() else {
+                continue;
+            };
+            let Some(alternative) = alternatives.get(tag as usize) else {
+                continue;
+            };
+
+            let start = offsets[tag as usize];
+            let end = start + alternative.payload.len();
+            let mut data = rest;
+            if let Some(present) = self.decode_complete(&alternative.payload, &mut data) {
+                record(&mut coverage.fields[start..end], &present);
+                coverage.variants[tag as usize].hits += 1;
+                coverage.decoded += 1;
+            }
+        }
+    }
+
+    /// Decode a sequence of fields that must consume all of `data`
+    ///
+    /// Returns whether each field carried a value, or `None` if the input
+    /// is invalid or has bytes left over.
+    fn decode_complete(
+        &self,
+        payload: &[(String, TypeInfo)],
+        data: &mut &[u8],
+    ) -> Option<Vec<bool>> {
+        let present = payload
+            .iter()
+            .map(|(_, type_info)| self.decode(type_info, data, 0))
+            .collect::<Option<Vec<_>>>()?;
+        data.is_empty().then_some(present)
+    }
+
+    /// Decode one value, returning whether it carried a value
+    ///
+    /// `None` means the input is not a valid encoding of `type_info`.
+    fn decode(&self, type_info: &TypeInfo, data: &mut &[u8], depth: usize) -> Option<bool> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        match type_info {
+            TypeInfo::Primitive(name) => decode_primitive(name, data).map(|_| true),
+            TypeInfo::Option(inner) => match take(data, 1)?[0] {
+                0 => Some(false),
+                1 => self.decode(inner, data, depth + 1).map(|_| true),
+                _ => None,
+            },
+            TypeInfo::Array(inner) => {
+                let len = read_u32(data)? as usize;
+                // Every element needs at least one byte for the types we decode
+                if len > data.len() {
+                    return None;
+                }
+                for _ in 0..len {
+                    self.decode(inner, data, depth + 1)?;
+                }
+                Some(len > 0)
+            }
+            TypeInfo::Map(_, key, value) => {
+                let len = read_u32(data)? as usize;
+                // Keys are primitives, so every entry needs at least one byte
+                if len > data.len() {
+                    return None;
+                }
+                for _ in 0..len {
+                    self.decode(key, data, depth + 1)?;
+                    self.decode(value, data, depth + 1)?;
+                }
+                Some(len > 0)
+            }
+            TypeInfo::FixedArray(inner, len) => {
+                for _ in 0..*len {
+                    self.decode(inner, data, depth + 1)?;
+                }
+                Some(true)
+            }
+            TypeInfo::UserDefined(name) => {
+                match self.find(name)? {
+                    TypeDefinition::Struct(s) => {
+                        for field in &s.fields {
+                            let (_, field_type) = field_entry(field);
+                            self.decode(&field_type, data, depth + 1)?;
+                        }
+                    }
+                    TypeDefinition::Enum(e) => {
+                        let tag = take(data, 1)?[0] as usize;
+                        let variant = variant_alternative(e.variants.get(tag)?);
+                        for (_, field_type) in &variant.payload {
+                            self.decode(field_type, data, depth + 1)?;
+                        }
+                    }
+                }
+                Some(true)
+            }
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<&'a TypeDefinition> {
+        self.type_defs.iter().find(|t| t.name() == name)
+    }
+}
+
+/// Field name and its wire type (optional fields are Borsh `Option`s)
+fn field_entry(field: &FieldDefinition) -> (String, TypeInfo) {
+    let type_info = if field.optional && !matches!(field.type_info, TypeInfo::Option(_)) {
+        TypeInfo::Option(Box::new(field.type_info.clone()))
+    } else {
+        field.type_info.clone()
+    };
+    (field.name.clone(), type_info)
+}
+
+fn variant_alternative(variant: &EnumVariantDefinition) -> Alternative<'_> {
+    match variant {
+        EnumVariantDefinition::Unit { name } => Alternative {
+            name,
+            payload: Vec::new(),
+        },
+        EnumVariantDefinition::Tuple { name, types } => Alternative {
+            name,
+            payload: types
+                .iter()
+                .enumerate()
+                .map(|(i, t)| (i.to_string(), t.clone()))
+                .collect(),
+        },
+        EnumVariantDefinition::Struct { name, fields } => Alternative {
+            name,
+            payload: fields.iter().map(field_entry).collect(),
+        },
+    }
+}
+
+fn take<'d>(data: &mut &'d [u8], len: usize) -> Option<&'d [u8]> {
+    if data.len() < len {
+        return None;
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Some(head)
+}
+
+fn read_u32(data: &mut &[u8]) -> Option<u32> {
+    let bytes = take(data, 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Decode a primitive with Borsh's validation rules
+fn decode_primitive(name: &str, data: &mut &[u8]) -> Option<()> {
+    match name {
+        "bool" => (take(data, 1)?[0] <= 1).then_some(()),
+        "u8" | "i8" => take(data, 1).map(|_| ()),
+        "u16" | "i16" => take(data, 2).map(|_| ()),
+        "u32" | "i32" => take(data, 4).map(|_| ()),
+        "u64" | "i64" => take(data, 8).map(|_| ()),
+        "u128" | "i128" => take(data, 16).map(|_| ()),
+        // Borsh rejects NaN floats
+        "f32" => {
+            let bytes = take(data, 4)?;
+            (!f32::from_le_bytes(bytes.try_into().ok()?).is_nan()).then_some(())
+        }
+        "f64" => {
+            let bytes = take(data, 8)?;
+            (!f64::from_le_bytes(bytes.try_into().ok()?).is_nan()).then_some(())
+        }
+        "Pubkey" | "PublicKey" => take(data, 32).map(|_| ()),
+        // Signatures are generated as base58 `String`s
+        "String" | "string" | "Signature" => {
+            let len = read_u32(data)? as usize;
+            std::str::from_utf8(take(data, len)?).ok().map(|_| ())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn type_defs(source: &str) -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_struct_field_coverage() {
+        let defs = type_defs(
+            r#"
+            #[solana]
+            struct Vault {
+                amount: u64,
+                memo: Option<String>,
+                owners: [PublicKey],
+            }
+            "#,
+        );
+        let analyzer = CoverageAnalyzer::new(&defs);
+
+        // amount = 1, memo = Some("a"), owners = []
+        let mut input = 1u64.to_le_bytes().to_vec();
+        input.extend([1, 1, 0, 0, 0, b'a']);
+        input.extend([0, 0, 0, 0]);
+
+        let coverage = analyzer.analyze("Vault", &[input, vec![1, 2, 3]]).unwrap();
+
+        assert_eq!(coverage.inputs, 2);
+        assert_eq!(coverage.decoded, 1);
+        assert_eq!(coverage.untested_fields(), vec!["owners"]);
+        assert!(analyzer.analyze("Missing", &[]).is_none());
+    }
+
+    #[test]
+    fn test_incomplete_inputs_add_no_hits() {
+        let defs = type_defs(
+            r#"
+            #[solana]
+            struct Vault {
+                amount: u64,
+                memo: Option<String>,
+            }
+
+            #[solana]
+            enum Action {
+                Idle,
+                Move { x: i32, y: i32 },
+            }
+            "#,
+        );
+        let analyzer = CoverageAnalyzer::new(&defs);
+
+        // amount = 1, memo = Some("ab") cut short, then with a trailing byte
+        let mut input = 1u64.to_le_bytes().to_vec();
+        input.extend([1, 2, 0, 0, 0, b'a']);
+        let mut trailing = input.clone();
+        trailing.extend([b'b', 0]);
+
+        let coverage = analyzer.analyze("Vault", &[input, trailing]).unwrap();
+        assert_eq!(coverage.decoded, 0);
+        assert_eq!(coverage.untested_fields(), vec!["amount", "memo"]);
+        assert_eq!(coverage.percent(), 0.0);
+
+        // Move with y cut short; Idle with a trailing byte
+        let coverage = analyzer
+            .analyze("Action", &[vec![1, 0, 0, 0, 0, 0], vec![0, 0]])
+            .unwrap();
+        assert_eq!(coverage.decoded, 0);
+        assert_eq!(coverage.untested_variants(), vec!["Idle", "Move"]);
+        assert_eq!(coverage.untested_fields(), vec!["Move.x", "Move.y"]);
+    }
+
+    #[test]
+    fn test_signature_decodes_as_string() {
+        let defs = type_defs("#[solana]\nstruct Receipt { sig: Signature, amount: u64 }");
+        let analyzer = CoverageAnalyzer::new(&defs);
+
+        // sig = "ab", amount = 7
+        let mut input = vec![2, 0, 0, 0, b'a', b'b'];
+        input.extend(7u64.to_le_bytes());
+
+        let coverage = analyzer.analyze("Receipt", &[input]).unwrap();
+
+        assert_eq!(coverage.decoded, 1);
+        assert!(coverage.untested_fields().is_empty());
+    }
+
+    #[test]
+    fn test_enum_variant_coverage() {
+        let defs = type_defs(
+            r#"
+            #[solana]
+            enum Action {
+                Idle,
+                Move { x: i32, y: i32 },
+                Say(String),
+            }
+            "#,
+        );
+        let analyzer = CoverageAnalyzer::new(&defs);
+
+        let mut moved = vec![1];
+        moved.extend([0; 8]);
+        let coverage = analyzer
+            .analyze("Action", &[vec![0], moved, vec![9]])
+            .unwrap();
+
+        assert_eq!(coverage.decoded, 2);
+        assert_eq!(coverage.untested_variants(), vec!["Say"]);
+        assert_eq!(coverage.untested_fields(), vec!["Say.0"]);
+        assert!(coverage.percent() > 50.0 && coverage.percent() < 100.0);
+    }
+
+    #[test]
+    fn test_dispatch_coverage() {
+        let defs = type_defs(
+            r#"
+            #[solana]
+            #[instruction]
+            struct Deposit {
+                amount: u64,
+            }
+
+            #[solana]
+            #[instruction]
+            struct Withdraw {
+                amount: u64,
+            }
+            "#,
+        );
+        let analyzer = CoverageAnalyzer::new(&defs);
+
+        let mut deposit = vec![0];
+        deposit.extend(5u64.to_le_bytes());
+        let coverage = analyzer.analyze(DISPATCH_TYPE_NAME, &[deposit]).unwrap();
+
+        assert_eq!(coverage.untested_variants(), vec!["Withdraw"]);
+        assert_eq!(coverage.untested_fields(), vec!["Withdraw.amount"]);
+    }
+
+    #[test]
+    fn test_parse_llvm_cov_summary() {
+        let json = r#"{"data":[{"totals":{
+            "lines":{"count":10,"covered":5,"percent":50.0},
+            "regions":{"count":4,"covered":1,"percent":25.0},
+            "functions":{"count":2,"covered":2,"percent":100.0}
+        }}]}"#;
+
+        let summary = LlvmCoverageSummary::parse(json).unwrap();
+        assert_eq!(summary.lines_percent, 50.0);
+        assert_eq!(summary.regions_percent, 25.0);
+        assert_eq!(summary.functions_percent, 100.0);
+        assert!(LlvmCoverageSummary::parse("{}").is_none());
+    }
+}
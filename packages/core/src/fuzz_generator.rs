@@ -10,11 +10,123 @@
 //! - Size limits
 
 use crate::ir::{EnumDefinition, StructDefinition, TypeDefinition, TypeInfo};
+use std::collections::BTreeMap;
+
+/// Type name used for the instruction dispatcher target and its corpus
+pub const DISPATCH_TYPE_NAME: &str = "InstructionDispatch";
 
 /// Fuzz target generator
 pub struct FuzzGenerator<'a> {
     /// All type definitions
     type_defs: &'a [TypeDefinition],
+
+    /// Dependency settings for the generated Cargo.toml
+    config: FuzzConfig,
+}
+
+/// Dependency settings for the generated fuzz project
+///
+/// Read from the `[fuzz]` section of `lumos.toml`:
+///
+/// ```toml
+/// [fuzz]
+/// crate-name = "my-program"
+/// crate-path = "../programs/my-program"
+///
+/// [fuzz.dependencies]
+/// anchor-lang = "0.31"
+/// borsh = { version = "1.5", features = ["derive"] }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzConfig {
+    /// Package name of the crate containing the generated types
+    ///
+    /// `None` falls back to [`FuzzConfig::DEFAULT_CRATE_NAME`].
+    pub crate_name: Option<String>,
+
+    /// Path to the crate containing the generated types, relative to the fuzz directory
+    pub crate_path: String,
+
+    /// Dependency specs keyed by crate name, as inline TOML values
+    pub dependencies: BTreeMap<String, String>,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        let dependencies = [
+            ("libfuzzer-sys", "\"0.4\""),
+            ("borsh", "{ version = \"1.5\", features = [\"derive\"] }"),
+            ("anchor-lang", "\"0.30\""),
+        ]
+        .into_iter()
+        .map(|(name, spec)| (name.to_string(), spec.to_string()))
+        .collect();
+
+        Self {
+            crate_name: None,
+            crate_path: "..".to_string(),
+            dependencies,
+        }
+    }
+}
+
+impl FuzzConfig {
+    /// Crate name used when none is configured or detected
+    pub const DEFAULT_CRATE_NAME: &'static str = "generated";
+
+    /// Package name of the crate containing the generated types
+    pub fn crate_name(&self) -> &str {
+        self.crate_name
+            .as_deref()
+            .unwrap_or(Self::DEFAULT_CRATE_NAME)
+    }
+
+    /// Crate name as written in Rust paths (`my-program` → `my_program`)
+    pub fn crate_ident(&self) -> String {
+        self.crate_name().replace('-', "_")
+    }
+
+    /// Read the `[fuzz]` section of a `lumos.toml`
+    ///
+    /// Missing keys keep their defaults; configured dependencies are merged
+    /// over the default set.
+    pub fn from_lumos_toml(input: &str) -> Result<Self, toml::de::Error> {
+        use serde::de::Error;
+
+        let mut config = Self::default();
+        let value: toml::Value = toml::from_str(input)?;
+
+        let Some(fuzz) = value.get("fuzz") else {
+            return Ok(config);
+        };
+
+        if let Some(name) = fuzz.get("crate-name") {
+            config.crate_name = Some(
+                name.as_str()
+                    .ok_or_else(|| toml::de::Error::custom("fuzz.crate-name must be a string"))?
+                    .to_string(),
+            );
+        }
+
+        if let Some(path) = fuzz.get("crate-path") {
+            config.crate_path = path
+                .as_str()
+                .ok_or_else(|| toml::de::Error::custom("fuzz.crate-path must be a string"))?
+                .to_string();
+        }
+
+        if let Some(dependencies) = fuzz.get("dependencies") {
+            let dependencies = dependencies
+                .as_table()
+                .ok_or_else(|| toml::de::Error::custom("fuzz.dependencies must be a table"))?;
+
+            for (name, spec) in dependencies {
+                config.dependencies.insert(name.clone(), spec.to_string());
+            }
+        }
+
+        Ok(config)
+    }
 }
 
 /// Generated fuzz target
@@ -36,7 +148,16 @@ pub struct FuzzTarget {
 impl<'a> FuzzGenerator<'a> {
     /// Create a new fuzz generator
     pub fn new(type_defs: &'a [TypeDefinition]) -> Self {
-        Self { type_defs }
+        Self {
+            type_defs,
+            config: FuzzConfig::default(),
+        }
+    }
+
+    /// Use dependency settings from `lumos.toml`
+    pub fn with_config(mut self, config: FuzzConfig) -> Self {
+        self.config = config;
+        self
     }
 
     /// Generate all fuzz targets
@@ -54,18 +175,86 @@ impl<'a> FuzzGenerator<'a> {
             }
         }
 
+        if let Some(dispatch) = self.generate_dispatch_target() {
+            targets.push(dispatch);
+        }
+
         targets
     }
 
+    /// Generate a dispatcher target covering every `#[instruction]` type
+    ///
+    /// The first input byte selects the instruction (in schema order) and the
+    /// rest is decoded as its Borsh-encoded arguments, mirroring how a program
+    /// entrypoint decodes instruction data.
+    fn generate_dispatch_target(&self) -> Option<FuzzTarget> {
+        let instructions: Vec<&str> = self
+            .type_defs
+            .iter()
+            .filter(|t| t.is_instruction())
+            .map(|t| t.name())
+            .collect();
+
+        if instructions.is_empty() {
+            return None;
+        }
+
+        let mut code = String::new();
+
+        code.push_str("#![no_main]\n");
+        code.push_str("use libfuzzer_sys::fuzz_target;\n");
+        code.push_str("use borsh::{BorshSerialize, BorshDeserialize};\n\n");
+        code.push_str(&format!(
+            "use {}::{{{}}};\n\n",
+            self.config.crate_ident(),
+            instructions.join(", ")
+        ));
+
+        code.push_str("fuzz_target!(|data: &[u8]| {\n");
+        code.push_str("    // First byte selects the instruction, the rest are its arguments\n");
+        code.push_str("    let Some((&tag, args)) = data.split_first() else {\n");
+        code.push_str("        return;\n");
+        code.push_str("    };\n\n");
+        code.push_str("    match tag {\n");
+
+        for (tag, name) in instructions.iter().enumerate() {
+            code.push_str(&format!("        {} => {{\n", tag));
+            code.push_str(&format!(
+                "            if let Ok(instruction) = {}::try_from_slice(args) {{\n",
+                name
+            ));
+            code.push_str("                // Round-trip: serialize → deserialize\n");
+            code.push_str("                let serialized = instruction.try_to_vec().expect(\"serialization should succeed\");\n");
+            code.push_str(&format!(
+                "                {}::try_from_slice(&serialized)\n",
+                name
+            ));
+            code.push_str(
+                "                    .expect(\"round-trip deserialization should succeed\");\n",
+            );
+            code.push_str("            }\n");
+            code.push_str("        }\n");
+        }
+
+        code.push_str("        // Unknown instructions must be rejected by the entrypoint\n");
+        code.push_str("        _ => {}\n");
+        code.push_str("    }\n");
+        code.push_str("});\n");
+
+        Some(FuzzTarget {
+            name: format!("fuzz_{}", to_snake_case(DISPATCH_TYPE_NAME)),
+            type_name: DISPATCH_TYPE_NAME.to_string(),
+            code,
+            needs_partial_eq: false,
+        })
+    }
+
     /// Generate fuzz target for a struct
     fn generate_struct_target(&self, struct_def: &StructDefinition) -> FuzzTarget {
         let type_name = &struct_def.name;
         let target_name = format!("fuzz_{}", to_snake_case(type_name));
 
-        let is_account = struct_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string());
+        let is_account = struct_def.metadata.is_account();
 
         let needs_partial_eq = self.needs_partial_eq_derive(struct_def);
 
@@ -83,7 +272,11 @@ impl<'a> FuzzGenerator<'a> {
         if is_account {
             code.push_str("use anchor_lang::prelude::*;\n");
         }
-        code.push_str(&format!("use generated::{};\n\n", type_name));
+        code.push_str(&format!(
+            "use {}::{};\n\n",
+            self.config.crate_ident(),
+            type_name
+        ));
 
         // Fuzz target implementation
         code.push_str("fuzz_target!(|data: &[u8]| {\n");
@@ -122,7 +315,7 @@ impl<'a> FuzzGenerator<'a> {
                 ));
                 code.push_str(&format!("        let _ = instance.{};\n", field_name));
             }
-            code.push_str("\n");
+            code.push('\n');
         }
 
         // Account-specific checks
@@ -155,7 +348,11 @@ impl<'a> FuzzGenerator<'a> {
         code.push_str("use libfuzzer_sys::fuzz_target;\n");
         code.push_str("use borsh::{BorshSerialize, BorshDeserialize};\n\n");
 
-        code.push_str(&format!("use generated::{};\n\n", type_name));
+        code.push_str(&format!(
+            "use {}::{};\n\n",
+            self.config.crate_ident(),
+            type_name
+        ));
 
         // Fuzz target implementation
         code.push_str("fuzz_target!(|data: &[u8]| {\n");
@@ -230,7 +427,11 @@ impl<'a> FuzzGenerator<'a> {
     }
 
     /// Generate Cargo.toml for fuzz targets
-    pub fn generate_cargo_toml(&self, crate_name: &str) -> String {
+    ///
+    /// Lists one `[[bin]]` per target. `anchor-lang` is only included when the
+    /// schema has `#[account]` types.
+    pub fn generate_cargo_toml(&self, targets: &[FuzzTarget]) -> String {
+        let crate_name = self.config.crate_name();
         let mut toml = String::new();
 
         toml.push_str("[package]\n");
@@ -242,21 +443,32 @@ impl<'a> FuzzGenerator<'a> {
         toml.push_str("[package.metadata]\n");
         toml.push_str("cargo-fuzz = true\n\n");
 
+        let has_accounts = self.type_defs.iter().any(|t| t.is_account());
+
         toml.push_str("[dependencies]\n");
-        toml.push_str("libfuzzer-sys = \"0.4\"\n");
-        toml.push_str("borsh = { version = \"1.5\", features = [\"derive\"] }\n");
-        toml.push_str("anchor-lang = \"0.30\"\n");
-        toml.push_str(&format!("generated = {{ path = \"..\" }}\n\n"));
+        for (name, spec) in &self.config.dependencies {
+            if name == "anchor-lang" && !has_accounts {
+                continue;
+            }
+            toml.push_str(&format!("{} = {}\n", name, spec));
+        }
+        toml.push_str(&format!(
+            "{} = {{ path = \"{}\" }}\n\n",
+            crate_name, self.config.crate_path
+        ));
 
         toml.push_str("# Prevent this from interfering with workspaces\n");
         toml.push_str("[workspace]\n");
-        toml.push_str("members = [\"fuzz_targets\"]\n\n");
-
-        toml.push_str("[[bin]]\n");
-        toml.push_str("name = \"fuzz_target_1\"\n");
-        toml.push_str("path = \"fuzz_targets/fuzz_target_1.rs\"\n");
-        toml.push_str("test = false\n");
-        toml.push_str("doc = false\n");
+        toml.push_str("members = [\".\"]\n");
+
+        for target in targets {
+            toml.push_str("\n[[bin]]\n");
+            toml.push_str(&format!("name = \"{}\"\n", target.name));
+            toml.push_str(&format!("path = \"fuzz_targets/{}.rs\"\n", target.name));
+            toml.push_str("test = false\n");
+            toml.push_str("doc = false\n");
+            toml.push_str("bench = false\n");
+        }
 
         toml
     }
@@ -287,12 +499,16 @@ impl<'a> FuzzGenerator<'a> {
         readme.push_str("- **Round-trip integrity**: Serialize → Deserialize → Compare\n");
         readme.push_str("- **Size limits**: Ensure data fits within Solana's 10MB limit\n");
         readme.push_str("- **Discriminator validation**: For Anchor accounts\n");
-        readme.push_str("- **Arithmetic bounds**: For balance/amount fields\n\n");
+        readme.push_str("- **Arithmetic bounds**: For balance/amount fields\n");
+        readme.push_str(
+            "- **Instruction dispatch**: `fuzz_instruction_dispatch` decodes a tag byte plus arguments\n\n",
+        );
         readme.push_str("## Corpus\n\n");
         readme.push_str("Fuzzing corpus files are stored in `corpus/` directory.\n");
         readme.push_str("These provide seed inputs for the fuzzer.\n\n");
         readme.push_str("## Artifacts\n\n");
-        readme.push_str("Crash artifacts are saved to `artifacts/` directory when failures occur.\n");
+        readme
+            .push_str("Crash artifacts are saved to `artifacts/` directory when failures occur.\n");
 
         readme
     }
@@ -309,7 +525,13 @@ impl<'a> FuzzGenerator<'a> {
     }
 
     /// Check if a type name exists
+    ///
+    /// [`DISPATCH_TYPE_NAME`] exists when the schema has `#[instruction]` types.
     pub fn type_exists(&self, type_name: &str) -> bool {
+        if type_name == DISPATCH_TYPE_NAME {
+            return self.type_defs.iter().any(|t| t.is_instruction());
+        }
+
         self.type_defs.iter().any(|type_def| match type_def {
             TypeDefinition::Struct(s) => s.name == type_name,
             TypeDefinition::Enum(e) => e.name == type_name,
@@ -348,7 +570,7 @@ fn to_snake_case(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{FieldDefinition, Metadata};
+    use crate::ir::{FieldDefinition, Metadata, TypeRole};
 
     #[test]
     fn test_generates_struct_fuzz_target() {
@@ -359,16 +581,25 @@ mod tests {
                     name: "wallet".to_string(),
                     type_info: TypeInfo::Primitive("PublicKey".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
                 FieldDefinition {
                     name: "balance".to_string(),
                     type_info: TypeInfo::Primitive("u64".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
             ],
             metadata: Metadata {
                 solana: true,
                 attributes: vec!["account".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         })];
 
@@ -411,14 +642,72 @@ mod tests {
     #[test]
     fn test_generates_cargo_toml() {
         let type_defs = vec![];
-        let generator = FuzzGenerator::new(&type_defs);
-        let cargo_toml = generator.generate_cargo_toml("my-project");
+        let config = FuzzConfig {
+            crate_name: Some("my-project".to_string()),
+            ..FuzzConfig::default()
+        };
+        let generator = FuzzGenerator::new(&type_defs).with_config(config);
+        let cargo_toml = generator.generate_cargo_toml(&[]);
 
         assert!(cargo_toml.contains("name = \"my-project-fuzz\""));
         assert!(cargo_toml.contains("libfuzzer-sys"));
         assert!(cargo_toml.contains("borsh"));
     }
 
+    #[test]
+    fn test_cargo_toml_lists_targets_and_uses_config() {
+        let type_defs = vec![TypeDefinition::Struct(StructDefinition {
+            name: "Vault".to_string(),
+            fields: vec![],
+            metadata: Metadata::default(),
+        })];
+
+        let config = FuzzConfig::from_lumos_toml(
+            r#"
+            [fuzz]
+            crate-name = "vault-program"
+            crate-path = "../programs/vault"
+
+            [fuzz.dependencies]
+            borsh = "1.2"
+            "#,
+        )
+        .unwrap();
+
+        let generator = FuzzGenerator::new(&type_defs).with_config(config);
+        let targets = generator.generate_all();
+        let cargo_toml = generator.generate_cargo_toml(&targets);
+
+        assert!(cargo_toml.contains("name = \"fuzz_vault\"\npath = \"fuzz_targets/fuzz_vault.rs\""));
+        assert!(!cargo_toml.contains("fuzz_target_1"));
+        assert!(cargo_toml.contains("members = [\".\"]"));
+        assert!(cargo_toml.contains("borsh = \"1.2\""));
+        assert!(cargo_toml.contains("vault-program = { path = \"../programs/vault\" }"));
+        assert!(targets[0].code.contains("use vault_program::Vault;"));
+        // No #[account] types, so no Anchor dependency
+        assert!(!cargo_toml.contains("anchor-lang"));
+
+        let parsed: toml::Value = toml::from_str(&cargo_toml).unwrap();
+        assert_eq!(parsed["bin"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_fuzz_config_inline_table_dependency() {
+        let config = FuzzConfig::from_lumos_toml(
+            r#"
+            [fuzz.dependencies]
+            anchor-lang = { version = "0.31", features = ["init-if-needed"] }
+            "#,
+        )
+        .unwrap();
+
+        let spec: toml::Value =
+            toml::from_str(&format!("dep = {}", config.dependencies["anchor-lang"])).unwrap();
+        assert_eq!(spec["dep"]["version"].as_str(), Some("0.31"));
+        assert_eq!(config.crate_path, "..");
+        assert!(FuzzConfig::from_lumos_toml("[fuzz]\ncrate-path = 1").is_err());
+    }
+
     #[test]
     fn test_get_type_names() {
         let type_defs = vec![
@@ -453,4 +742,55 @@ mod tests {
         assert!(generator.type_exists("PlayerAccount"));
         assert!(!generator.type_exists("NonExistent"));
     }
+
+    #[test]
+    fn test_generates_instruction_dispatch_target() {
+        let instruction = |name: &str| {
+            TypeDefinition::Struct(StructDefinition {
+                name: name.to_string(),
+                fields: vec![FieldDefinition {
+                    name: "amount".to_string(),
+                    type_info: TypeInfo::Primitive("u64".to_string()),
+                    optional: false,
+                    attributes: vec![],
+                }],
+                metadata: Metadata {
+                    solana: true,
+                    attributes: vec!["instruction".to_string()],
+                    role: TypeRole::Instruction,
+                    zero_copy: false,
+                    version: None,
+                    lifecycle: Default::default(),
+                    skip: Vec::new(),
+                    docs: Vec::new(),
+                    test_vectors: Vec::new(),
+                },
+            })
+        };
+        let type_defs = vec![instruction("Deposit"), instruction("Withdraw")];
+
+        let generator = FuzzGenerator::new(&type_defs);
+        let targets = generator.generate_all();
+
+        let dispatch = targets
+            .iter()
+            .find(|t| t.type_name == DISPATCH_TYPE_NAME)
+            .expect("dispatch target");
+        assert_eq!(dispatch.name, "fuzz_instruction_dispatch");
+        assert!(dispatch
+            .code
+            .contains("use generated::{Deposit, Withdraw};"));
+        assert!(dispatch.code.contains("0 => {"));
+        assert!(dispatch.code.contains("Withdraw::try_from_slice(args)"));
+        assert!(generator.type_exists(DISPATCH_TYPE_NAME));
+
+        let plain = vec![TypeDefinition::Struct(StructDefinition {
+            name: "Plain".to_string(),
+            fields: vec![],
+            metadata: Metadata::default(),
+        })];
+        let plain_generator = FuzzGenerator::new(&plain);
+        assert_eq!(plain_generator.generate_all().len(), 1);
+        assert!(!plain_generator.type_exists(DISPATCH_TYPE_NAME));
+    }
 }
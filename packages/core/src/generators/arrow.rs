@@ -0,0 +1,900 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Apache Arrow schemas for analytics pipelines
+//!
+//! Describes each account type as an Arrow schema, so data teams can load
+//! decoded accounts into Parquet or DuckDB with their real column types.
+//! [`generate_json`] emits the schemas in Arrow's JSON schema representation;
+//! [`generate_module`] emits a Rust module with `<type>_schema()` functions
+//! built on `arrow-schema` and, optionally, `<type>_batch()` functions that
+//! turn rows of the generated Rust types into a `RecordBatch`.
+//!
+//! Account types are the `#[account]` structs, or every struct when the
+//! schema has none. Columns follow the fields; nested structs become Arrow
+//! structs and vectors become lists:
+//!
+//! - Integers keep their width and signedness; `u128`/`i128` become
+//!   `Decimal256(39, 0)`, which holds every value of both
+//! - `PublicKey` and `Signature` become base58 `Utf8`, `[u8]` becomes `Binary`
+//! - `Option<T>` becomes a nullable `T`
+//! - `HashMap<K, V>` and `BTreeMap<K, V>` become Arrow maps of `key`/`value` entries
+//! - Unit-only enums become `Utf8` variant names; other enums become a struct
+//!   with a `kind` column and a nullable struct per data-carrying variant,
+//!   named after the variant in snake case, with tuple fields named `field0`,
+//!   `field1`, and so on
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::arrow;
+//! use lumos_core::{parser, transform};
+//!
+//! let source = r#"
+//!     #[solana]
+//!     #[account]
+//!     struct Vault { owner: PublicKey, amount: u64, memo: Option<String> }
+//! "#;
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(source)?)?;
+//! let code = arrow::generate_module(&ir, "crate::generated", true);
+//! assert!(code.contains("pub fn vault_schema() -> Schema"));
+//! assert!(code.contains("pub fn vault_batch(rows: &[Vault]) -> Result<RecordBatch, ArrowError>"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::ir::{
+    EnumDefinition, EnumVariantDefinition, StructDefinition, TypeDefinition, TypeInfo,
+};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+
+/// Precision of the decimal holding `u128` and `i128` values
+const WIDE_INTEGER_PRECISION: u8 = 39;
+
+/// Items imported from `arrow_array`, when the module uses them
+const ARRAY_ITEMS: &[&str] = &[
+    "ArrayRef",
+    "BinaryArray",
+    "BooleanArray",
+    "Decimal256Array",
+    "Float32Array",
+    "Float64Array",
+    "Int16Array",
+    "Int32Array",
+    "Int64Array",
+    "Int8Array",
+    "ListArray",
+    "MapArray",
+    "NullArray",
+    "RecordBatch",
+    "StringArray",
+    "StructArray",
+    "UInt16Array",
+    "UInt32Array",
+    "UInt64Array",
+    "UInt8Array",
+];
+
+/// Items imported from `arrow_buffer`, when the module uses them
+const BUFFER_ITEMS: &[&str] = &["i256", "NullBuffer", "OffsetBuffer"];
+
+/// Items imported from `arrow_schema`, when the module uses them
+const SCHEMA_ITEMS: &[&str] = &["ArrowError", "DataType", "Field", "Fields", "Schema"];
+
+/// Arrow schemas of the account types, keyed by type name, in Arrow's JSON representation
+pub fn generate_json(type_defs: &[TypeDefinition]) -> Value {
+    let generator = ArrowGenerator { type_defs };
+    let schemas: Map<String, Value> = generator
+        .accounts()
+        .into_iter()
+        .map(|account| {
+            let fields: Vec<Value> = account
+                .fields
+                .iter()
+                .map(|field| generator.json_field(&field.name, &field.type_info))
+                .collect();
+            (account.name.clone(), json!({ "fields": fields }))
+        })
+        .collect();
+    Value::Object(schemas)
+}
+
+/// Rust module with a schema function per account type, and batch conversions if `conversions`
+///
+/// The module imports the generated Rust types from `types_module`.
+pub fn generate_module(
+    type_defs: &[TypeDefinition],
+    types_module: &str,
+    conversions: bool,
+) -> String {
+    let generator = ArrowGenerator { type_defs };
+    let accounts = generator.accounts();
+    let nested = generator.nested_types(&accounts);
+
+    let mut body = String::new();
+    for account in &accounts {
+        let snake = to_snake_case(&account.name);
+        body.push_str(&format!("/// Arrow schema of `{}` rows\n", account.name));
+        body.push_str(&format!("pub fn {}_schema() -> Schema {{\n", snake));
+        body.push_str(&format!("    Schema::new({}_fields())\n", snake));
+        body.push_str("}\n\n");
+        body.push_str(&generator.fields_fn(&TypeDefinition::Struct((*account).clone())));
+    }
+    for type_def in &nested {
+        if !matches!(type_def, TypeDefinition::Enum(e) if e.is_unit_only()) {
+            body.push_str(&generator.fields_fn(type_def));
+        }
+    }
+
+    if conversions {
+        for account in &accounts {
+            let snake = to_snake_case(&account.name);
+            body.push_str(&format!(
+                "/// `{}` rows as a record batch with the columns of [`{}_schema`]\n",
+                account.name, snake
+            ));
+            body.push_str(&format!(
+                "pub fn {}_batch(rows: &[{}]) -> Result<RecordBatch, ArrowError> {{\n",
+                snake, account.name
+            ));
+            body.push_str(&format!(
+                "    let items: Vec<Option<&{}>> = rows.iter().map(Some).collect();\n",
+                account.name
+            ));
+            body.push_str(&format!("    let array = {}_array(&items)?;\n", snake));
+            body.push_str("    Ok(RecordBatch::from(array.as_struct().clone()))\n");
+            body.push_str("}\n\n");
+            body.push_str(&generator.array_fn(&TypeDefinition::Struct((*account).clone())));
+        }
+        for type_def in &nested {
+            body.push_str(&generator.array_fn(type_def));
+        }
+    }
+
+    let mut output = String::new();
+    output.push_str("// Auto-generated by LUMOS\n");
+    output.push_str("// DO NOT EDIT - Changes will be overwritten\n\n");
+    output.push_str(
+        "//! Apache Arrow schemas of the account types, for Parquet and DuckDB ingestion\n\n",
+    );
+    if conversions {
+        output.push_str("use arrow_array::cast::AsArray;\n");
+        output.push_str(&use_line("arrow_array", ARRAY_ITEMS, &body));
+        output.push_str(&use_line("arrow_buffer", BUFFER_ITEMS, &body));
+    }
+    output.push_str(&use_line("arrow_schema", SCHEMA_ITEMS, &body));
+    if uses(&body, "Arc") {
+        output.push_str("use std::sync::Arc;\n");
+    }
+    if conversions {
+        output.push_str(&format!("use {}::*;\n", types_module));
+    }
+    output.push('\n');
+    output.push_str(body.trim_end());
+    output.push('\n');
+    output
+}
+
+struct ArrowGenerator<'a> {
+    type_defs: &'a [TypeDefinition],
+}
+
+impl<'a> ArrowGenerator<'a> {
+    fn find(&self, name: &str) -> Option<&'a TypeDefinition> {
+        self.type_defs.iter().find(|t| t.name() == name)
+    }
+
+    /// `#[account]` structs, or every struct when there are none
+    fn accounts(&self) -> Vec<&'a StructDefinition> {
+        let structs: Vec<&StructDefinition> = self
+            .type_defs
+            .iter()
+            .filter_map(|t| match t {
+                TypeDefinition::Struct(s) => Some(s),
+                TypeDefinition::Enum(_) => None,
+            })
+            .collect();
+        let accounts: Vec<&StructDefinition> = structs
+            .iter()
+            .copied()
+            .filter(|s| s.metadata.is_account())
+            .collect();
+        if accounts.is_empty() {
+            structs
+        } else {
+            accounts
+        }
+    }
+
+    /// Types reachable from the accounts' fields, other than the accounts themselves
+    fn nested_types(&self, accounts: &[&StructDefinition]) -> Vec<&'a TypeDefinition> {
+        let mut found = BTreeSet::new();
+        let mut pending: Vec<&TypeInfo> = accounts
+            .iter()
+            .flat_map(|account| account.fields.iter().map(|f| &f.type_info))
+            .collect();
+        while let Some(type_info) = pending.pop() {
+            let Some(name) = user_type(type_info) else {
+                continue;
+            };
+            if !found.insert(name.to_string()) {
+                continue;
+            }
+            match self.find(name) {
+                Some(TypeDefinition::Struct(s)) => {
+                    pending.extend(s.fields.iter().map(|f| &f.type_info));
+                }
+                Some(TypeDefinition::Enum(e)) => {
+                    pending.extend(variant_fields(e).into_iter().flat_map(|(_, fields)| {
+                        fields.into_iter().map(|(_, type_info)| type_info)
+                    }));
+                }
+                None => {}
+            }
+        }
+
+        self.type_defs
+            .iter()
+            .filter(|t| found.contains(t.name()))
+            .filter(|t| !accounts.iter().any(|a| a.name == t.name()))
+            .collect()
+    }
+
+    /// `pub fn <type>_fields() -> Fields`
+    fn fields_fn(&self, type_def: &TypeDefinition) -> String {
+        let snake = to_snake_case(type_def.name());
+        let fields = match type_def {
+            TypeDefinition::Struct(s) => {
+                let fields: Vec<(String, &TypeInfo)> = s
+                    .fields
+                    .iter()
+                    .map(|f| (f.name.clone(), &f.type_info))
+                    .collect();
+                self.fields_code(&fields)
+            }
+            TypeDefinition::Enum(e) => {
+                let mut columns = vec!["Field::new(\"kind\", DataType::Utf8, false)".to_string()];
+                for (variant, fields) in variant_fields(e) {
+                    columns.push(format!(
+                        "Field::new(\"{}\", DataType::Struct({}), true)",
+                        to_snake_case(variant.name()),
+                        self.fields_code(&fields)
+                    ));
+                }
+                format!(
+                    "Fields::from(vec![\n        {},\n    ])",
+                    columns.join(",\n        ")
+                )
+            }
+        };
+
+        format!(
+            "/// Columns of `{}`\npub fn {}_fields() -> Fields {{\n    {}\n}}\n\n",
+            type_def.name(),
+            snake,
+            fields
+        )
+    }
+
+    fn fields_code(&self, fields: &[(String, &TypeInfo)]) -> String {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(name, type_info)| self.field_code(name, type_info))
+            .collect();
+        format!("Fields::from(vec![{}])", fields.join(", "))
+    }
+
+    fn field_code(&self, name: &str, type_info: &TypeInfo) -> String {
+        format!(
+            "Field::new(\"{}\", {}, {})",
+            name,
+            self.data_type(type_info),
+            is_nullable(type_info)
+        )
+    }
+
+    fn data_type(&self, type_info: &TypeInfo) -> String {
+        match type_info {
+            TypeInfo::Primitive(name) => match name.as_str() {
+                "bool" => "DataType::Boolean".to_string(),
+                "u8" => "DataType::UInt8".to_string(),
+                "u16" => "DataType::UInt16".to_string(),
+                "u32" => "DataType::UInt32".to_string(),
+                "u64" => "DataType::UInt64".to_string(),
+                "i8" => "DataType::Int8".to_string(),
+                "i16" => "DataType::Int16".to_string(),
+                "i32" => "DataType::Int32".to_string(),
+                "i64" => "DataType::Int64".to_string(),
+                "u128" | "i128" => format!("DataType::Decimal256({}, 0)", WIDE_INTEGER_PRECISION),
+                "f32" => "DataType::Float32".to_string(),
+                "f64" => "DataType::Float64".to_string(),
+                "String" | "Pubkey" | "PublicKey" | "Signature" => "DataType::Utf8".to_string(),
+                _ => "DataType::Null".to_string(),
+            },
+            TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) if is_byte(inner) => {
+                "DataType::Binary".to_string()
+            }
+            TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => format!(
+                "DataType::List(Arc::new({}))",
+                self.field_code("item", inner)
+            ),
+            TypeInfo::Map(_, key, value) => format!(
+                "DataType::Map(Arc::new(Field::new(\"entries\", DataType::Struct({}), false)), false)",
+                self.entry_fields(key, value)
+            ),
+            TypeInfo::Option(inner) => self.data_type(inner),
+            TypeInfo::UserDefined(name) => match self.find(name) {
+                Some(TypeDefinition::Enum(e)) if e.is_unit_only() => "DataType::Utf8".to_string(),
+                Some(_) => format!("DataType::Struct({}_fields())", to_snake_case(name)),
+                None => "DataType::Null".to_string(),
+            },
+        }
+    }
+
+    /// `Fields` of a map's `entries` struct
+    fn entry_fields(&self, key: &TypeInfo, value: &TypeInfo) -> String {
+        format!(
+            "Fields::from(vec![{}, {}])",
+            self.field_code("key", key),
+            self.field_code("value", value)
+        )
+    }
+
+    /// `fn <type>_array(items) -> Result<ArrayRef, ArrowError>`, plus `<enum>_kind` for enums
+    fn array_fn(&self, type_def: &TypeDefinition) -> String {
+        let name = type_def.name();
+        let snake = to_snake_case(name);
+        let mut out = String::new();
+
+        if let TypeDefinition::Enum(e) = type_def {
+            out.push_str(&format!(
+                "fn {}_kind(value: &{}) -> &'static str {{\n",
+                snake, name
+            ));
+            out.push_str("    match value {\n");
+            for variant in &e.variants {
+                let pattern = match variant {
+                    EnumVariantDefinition::Unit { .. } => "",
+                    EnumVariantDefinition::Tuple { .. } => "(..)",
+                    EnumVariantDefinition::Struct { .. } => " { .. }",
+                };
+                out.push_str(&format!(
+                    "        {}::{}{} => \"{}\",\n",
+                    name,
+                    variant.name(),
+                    pattern,
+                    variant.name()
+                ));
+            }
+            out.push_str("    }\n");
+            out.push_str("}\n\n");
+        }
+
+        out.push_str(&format!(
+            "fn {}_array(items: &[Option<&{}>]) -> Result<ArrayRef, ArrowError> {{\n",
+            snake, name
+        ));
+        match type_def {
+            TypeDefinition::Enum(e) if e.is_unit_only() => {
+                out.push_str(&format!(
+                    "    Ok(Arc::new(items.iter().map(|v| v.map({}_kind)).collect::<StringArray>()))\n",
+                    snake
+                ));
+            }
+            TypeDefinition::Struct(s) => {
+                let columns: Vec<String> = s
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        format!(
+                            "{{\n    let items: Vec<Option<&_>> = items.iter().map(|v| v.map(|v| &v.{})).collect();\n{}\n}}",
+                            field.name,
+                            indent(&self.array_expr(&field.type_info), 1)
+                        )
+                    })
+                    .collect();
+                out.push_str(&struct_array(
+                    &columns,
+                    &format!("{}_fields()", snake),
+                    "items",
+                ));
+            }
+            TypeDefinition::Enum(e) => {
+                let mut columns = vec![format!(
+                    "Arc::new(items.iter().map(|v| v.map({}_kind)).collect::<StringArray>()) as ArrayRef",
+                    snake
+                )];
+                for (variant, fields) in variant_fields(e) {
+                    let tuple = matches!(variant, EnumVariantDefinition::Tuple { .. });
+                    let payload: Vec<String> = fields
+                        .iter()
+                        .enumerate()
+                        .map(|(index, (field, type_info))| {
+                            let pattern = if tuple {
+                                format!("{}::{}({}value, ..)", name, variant.name(), "_, ".repeat(index))
+                            } else {
+                                format!("{}::{} {{ {}: value, .. }}", name, variant.name(), field)
+                            };
+                            format!(
+                                "{{\n    let items: Vec<Option<&_>> = variant\n        .iter()\n        .map(|v| match v {{\n            Some({}) => Some(value),\n            _ => None,\n        }})\n        .collect();\n{}\n}}",
+                                pattern,
+                                indent(&self.array_expr(type_info), 1)
+                            )
+                        })
+                        .collect();
+                    let pattern = if tuple { "(..)" } else { " { .. }" };
+                    columns.push(format!(
+                        "{{\n    let variant: Vec<Option<&{}>> = items\n        .iter()\n        .map(|v| v.filter(|v| matches!(v, {}::{}{})))\n        .collect();\n{}\n}}",
+                        name,
+                        name,
+                        variant.name(),
+                        pattern,
+                        indent(
+                            struct_array(&payload, &self.fields_code(&fields), "variant")
+                                .trim_end()
+                                .replace("Ok(Arc::new(", "Arc::new(")
+                                .replace(")?))", ")?) as ArrayRef")
+                                .as_str(),
+                            0
+                        )
+                    ));
+                }
+                out.push_str(&struct_array(
+                    &columns,
+                    &format!("{}_fields()", snake),
+                    "items",
+                ));
+            }
+        }
+        out.push_str("}\n\n");
+        out
+    }
+
+    /// Expression turning `items: Vec<Option<&T>>` into an `ArrayRef`
+    fn array_expr(&self, type_info: &TypeInfo) -> String {
+        match type_info {
+            TypeInfo::Primitive(name) => primitive_array(name),
+            TypeInfo::Option(inner) => format!(
+                "{{\n    let items: Vec<Option<&_>> = items.iter().map(|v| v.and_then(|o| o.as_ref())).collect();\n{}\n}}",
+                indent(&self.array_expr(inner), 1)
+            ),
+            TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) if is_byte(inner) => {
+                "Arc::new(items.iter().map(|v| v.map(|b| b.as_slice())).collect::<BinaryArray>()) as ArrayRef"
+                    .to_string()
+            }
+            TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => format!(
+                concat!(
+                    "{{\n",
+                    "    let mut offsets = vec![0i32];\n",
+                    "    let mut children: Vec<Option<&_>> = Vec::new();\n",
+                    "    for v in &items {{\n",
+                    "        if let Some(list) = v {{\n",
+                    "            children.extend(list.iter().map(Some));\n",
+                    "        }}\n",
+                    "        offsets.push(children.len() as i32);\n",
+                    "    }}\n",
+                    "    let nulls = NullBuffer::from(items.iter().map(Option::is_some).collect::<Vec<_>>());\n",
+                    "    let items = children;\n",
+                    "    let values = {};\n",
+                    "    Arc::new(ListArray::try_new(\n",
+                    "        Arc::new({}),\n",
+                    "        OffsetBuffer::new(offsets.into()),\n",
+                    "        values,\n",
+                    "        Some(nulls),\n",
+                    "    )?) as ArrayRef\n",
+                    "}}"
+                ),
+                indent(&self.array_expr(inner), 1).trim_start(),
+                self.field_code("item", inner)
+            ),
+            TypeInfo::Map(_, key, value) => format!(
+                concat!(
+                    "{{\n",
+                    "    let mut offsets = vec![0i32];\n",
+                    "    let mut keys: Vec<Option<&_>> = Vec::new();\n",
+                    "    let mut values: Vec<Option<&_>> = Vec::new();\n",
+                    "    for v in &items {{\n",
+                    "        if let Some(map) = v {{\n",
+                    "            for (key, value) in map.iter() {{\n",
+                    "                keys.push(Some(key));\n",
+                    "                values.push(Some(value));\n",
+                    "            }}\n",
+                    "        }}\n",
+                    "        offsets.push(keys.len() as i32);\n",
+                    "    }}\n",
+                    "    let nulls = NullBuffer::from(items.iter().map(Option::is_some).collect::<Vec<_>>());\n",
+                    "    let key_column = {{\n",
+                    "        let items = keys;\n",
+                    "        {}\n",
+                    "    }};\n",
+                    "    let value_column = {{\n",
+                    "        let items = values;\n",
+                    "        {}\n",
+                    "    }};\n",
+                    "    let fields = {};\n",
+                    "    let entries = StructArray::try_new(fields.clone(), vec![key_column, value_column], None)?;\n",
+                    "    Arc::new(MapArray::try_new(\n",
+                    "        Arc::new(Field::new(\"entries\", DataType::Struct(fields), false)),\n",
+                    "        OffsetBuffer::new(offsets.into()),\n",
+                    "        entries,\n",
+                    "        Some(nulls),\n",
+                    "        false,\n",
+                    "    )?) as ArrayRef\n",
+                    "}}"
+                ),
+                indent(&self.array_expr(key), 2).trim_start(),
+                indent(&self.array_expr(value), 2).trim_start(),
+                self.entry_fields(key, value)
+            ),
+            TypeInfo::UserDefined(name) => match self.find(name) {
+                Some(_) => format!("{}_array(&items)?", to_snake_case(name)),
+                None => "Arc::new(NullArray::new(items.len())) as ArrayRef".to_string(),
+            },
+        }
+    }
+
+    /// Arrow field in the JSON schema representation
+    fn json_field(&self, name: &str, type_info: &TypeInfo) -> Value {
+        let (data_type, children) = self.json_type(type_info);
+        json!({
+            "name": name,
+            "nullable": is_nullable(type_info),
+            "type": data_type,
+            "children": children,
+        })
+    }
+
+    fn json_type(&self, type_info: &TypeInfo) -> (Value, Vec<Value>) {
+        let leaf = |data_type: Value| (data_type, Vec::new());
+        match type_info {
+            TypeInfo::Primitive(name) => leaf(match name.as_str() {
+                "bool" => json!({ "name": "bool" }),
+                "u8" | "u16" | "u32" | "u64" | "i8" | "i16" | "i32" | "i64" => json!({
+                    "name": "int",
+                    "isSigned": name.starts_with('i'),
+                    "bitWidth": name[1..].parse::<u32>().unwrap_or(64),
+                }),
+                "u128" | "i128" => json!({
+                    "name": "decimal",
+                    "precision": WIDE_INTEGER_PRECISION,
+                    "scale": 0,
+                    "bitWidth": 256,
+                }),
+                "f32" => json!({ "name": "floatingpoint", "precision": "SINGLE" }),
+                "f64" => json!({ "name": "floatingpoint", "precision": "DOUBLE" }),
+                "String" | "Pubkey" | "PublicKey" | "Signature" => json!({ "name": "utf8" }),
+                _ => json!({ "name": "null" }),
+            }),
+            TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) if is_byte(inner) => {
+                leaf(json!({ "name": "binary" }))
+            }
+            TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => (
+                json!({ "name": "list" }),
+                vec![self.json_field("item", inner)],
+            ),
+            TypeInfo::Map(_, key, value) => (
+                json!({ "name": "map", "keysSorted": false }),
+                vec![json!({
+                    "name": "entries",
+                    "nullable": false,
+                    "type": { "name": "struct" },
+                    "children": [self.json_field("key", key), self.json_field("value", value)],
+                })],
+            ),
+            TypeInfo::Option(inner) => self.json_type(inner),
+            TypeInfo::UserDefined(name) => match self.find(name) {
+                Some(TypeDefinition::Struct(s)) => (
+                    json!({ "name": "struct" }),
+                    s.fields
+                        .iter()
+                        .map(|f| self.json_field(&f.name, &f.type_info))
+                        .collect(),
+                ),
+                Some(TypeDefinition::Enum(e)) if e.is_unit_only() => {
+                    leaf(json!({ "name": "utf8" }))
+                }
+                Some(TypeDefinition::Enum(e)) => {
+                    let mut children = vec![json!({
+                        "name": "kind",
+                        "nullable": false,
+                        "type": { "name": "utf8" },
+                        "children": [],
+                    })];
+                    for (variant, fields) in variant_fields(e) {
+                        children.push(json!({
+                            "name": to_snake_case(variant.name()),
+                            "nullable": true,
+                            "type": { "name": "struct" },
+                            "children": fields
+                                .iter()
+                                .map(|(name, type_info)| self.json_field(name, type_info))
+                                .collect::<Vec<_>>(),
+                        }));
+                    }
+                    (json!({ "name": "struct" }), children)
+                }
+                None => leaf(json!({ "name": "null" })),
+            },
+        }
+    }
+}
+
+/// Data-carrying variants with their fields; tuple fields are `field0`, `field1`, ...
+fn variant_fields(
+    enum_def: &EnumDefinition,
+) -> Vec<(&EnumVariantDefinition, Vec<(String, &TypeInfo)>)> {
+    enum_def
+        .variants
+        .iter()
+        .filter_map(|variant| {
+            let fields: Vec<(String, &TypeInfo)> = match variant {
+                EnumVariantDefinition::Unit { .. } => return None,
+                EnumVariantDefinition::Tuple { types, .. } => types
+                    .iter()
+                    .enumerate()
+                    .map(|(index, type_info)| (format!("field{}", index), type_info))
+                    .collect(),
+                EnumVariantDefinition::Struct { fields, .. } => fields
+                    .iter()
+                    .map(|f| (f.name.clone(), &f.type_info))
+                    .collect(),
+            };
+            Some((variant, fields))
+        })
+        .collect()
+}
+
+/// Body of an array function building a `StructArray` from `columns`
+fn struct_array(columns: &[String], fields: &str, items: &str) -> String {
+    let mut out = String::from("    let columns: Vec<ArrayRef> = vec![\n");
+    for column in columns {
+        out.push_str(&indent(column, 2));
+        out.push_str(",\n");
+    }
+    out.push_str("    ];\n");
+    out.push_str(&format!(
+        "    let nulls = NullBuffer::from({}.iter().map(Option::is_some).collect::<Vec<_>>());\n",
+        items
+    ));
+    out.push_str(&format!(
+        "    Ok(Arc::new(StructArray::try_new({}, columns, Some(nulls))?))\n",
+        fields
+    ));
+    out
+}
+
+/// Expression turning `items: Vec<Option<&T>>` of a primitive into an `ArrayRef`
+fn primitive_array(name: &str) -> String {
+    let array = match name {
+        "bool" => "BooleanArray",
+        "u8" => "UInt8Array",
+        "u16" => "UInt16Array",
+        "u32" => "UInt32Array",
+        "u64" => "UInt64Array",
+        "i8" => "Int8Array",
+        "i16" => "Int16Array",
+        "i32" => "Int32Array",
+        "i64" => "Int64Array",
+        "f32" => "Float32Array",
+        "f64" => "Float64Array",
+        "u128" | "i128" => {
+            let convert = if name == "u128" {
+                "i256::from_parts(*n, 0)"
+            } else {
+                "i256::from_i128(*n)"
+            };
+            return format!(
+                "Arc::new(\n    items\n        .iter()\n        .map(|v| v.map(|n| {}))\n        .collect::<Decimal256Array>()\n        .with_precision_and_scale({}, 0)?,\n) as ArrayRef",
+                convert, WIDE_INTEGER_PRECISION
+            );
+        }
+        "String" | "Signature" => {
+            return "Arc::new(items.iter().map(|v| v.map(|s| s.as_str())).collect::<StringArray>()) as ArrayRef"
+                .to_string()
+        }
+        "Pubkey" | "PublicKey" => {
+            return "Arc::new(items.iter().map(|v| v.map(|k| k.to_string())).collect::<StringArray>()) as ArrayRef"
+                .to_string()
+        }
+        _ => return "Arc::new(NullArray::new(items.len())) as ArrayRef".to_string(),
+    };
+    format!(
+        "Arc::new(items.iter().map(|v| v.copied()).collect::<{}>()) as ArrayRef",
+        array
+    )
+}
+
+fn is_nullable(type_info: &TypeInfo) -> bool {
+    matches!(type_info, TypeInfo::Option(_))
+}
+
+fn is_byte(type_info: &TypeInfo) -> bool {
+    matches!(type_info, TypeInfo::Primitive(name) if name == "u8")
+}
+
+fn user_type(type_info: &TypeInfo) -> Option<&str> {
+    match type_info {
+        TypeInfo::Primitive(_) => None,
+        TypeInfo::UserDefined(name) => Some(name),
+        TypeInfo::Array(inner)
+        | TypeInfo::FixedArray(inner, _)
+        | TypeInfo::Option(inner)
+        | TypeInfo::Map(_, _, inner) => user_type(inner),
+    }
+}
+
+/// `use krate::{..};` for the `items` that `body` mentions
+fn use_line(krate: &str, items: &[&str], body: &str) -> String {
+    let used: Vec<&str> = items
+        .iter()
+        .copied()
+        .filter(|item| uses(body, item))
+        .collect();
+    match used.as_slice() {
+        [] => String::new(),
+        [item] => format!("use {}::{};\n", krate, item),
+        _ => format!("use {}::{{{}}};\n", krate, used.join(", ")),
+    }
+}
+
+/// Whether `code` mentions the identifier `name`
+fn uses(code: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    code.match_indices(name).any(|(start, _)| {
+        let before = code[..start].chars().next_back();
+        let after = code[start + name.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+/// Indent every non-empty line by `levels` times four spaces
+fn indent(code: &str, levels: usize) -> String {
+    let prefix = "    ".repeat(levels);
+    code.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `GameAccount` → `game_account`
+fn to_snake_case(s: &str) -> String {
+    let mut result = String::new();
+    for (i, ch) in s.chars().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            result.push('_');
+        }
+        result.push(ch.to_ascii_lowercase());
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        #[solana]
+        #[account]
+        struct GameAccount {
+            owner: PublicKey,
+            score: u64,
+            prize: u128,
+            memo: [u8],
+            moves: [Move],
+            nickname: Option<String>,
+            status: Status,
+            state: GameState,
+        }
+
+        struct Move { x: u8, y: i16 }
+
+        enum Status { Active, Paused }
+
+        enum GameState { Waiting, Playing(u8, u32), Finished { winner: PublicKey, note: Option<String> } }
+
+        struct Unused { x: u8 }
+    "#;
+
+    fn ir() -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn generates_json_schemas_for_accounts() {
+        let schemas = generate_json(&ir());
+        assert_eq!(schemas.as_object().unwrap().len(), 1);
+
+        let fields = schemas["GameAccount"]["fields"].as_array().unwrap();
+        let field = |name: &str| fields.iter().find(|f| f["name"] == name).unwrap();
+
+        assert_eq!(field("owner")["type"], json!({ "name": "utf8" }));
+        assert_eq!(
+            field("score")["type"],
+            json!({ "name": "int", "isSigned": false, "bitWidth": 64 })
+        );
+        assert_eq!(field("prize")["type"]["bitWidth"], 256);
+        assert_eq!(field("memo")["type"]["name"], "binary");
+        assert_eq!(
+            field("moves")["children"][0]["children"][1]["type"]["isSigned"],
+            true
+        );
+        assert_eq!(field("nickname")["nullable"], true);
+        assert_eq!(field("status")["type"]["name"], "utf8");
+
+        let state = field("state")["children"].as_array().unwrap();
+        let names: Vec<_> = state.iter().map(|c| c["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["kind", "playing", "finished"]);
+        assert_eq!(state[1]["children"][1]["nullable"], false);
+        assert_eq!(state[2]["children"][1]["nullable"], true);
+    }
+
+    #[test]
+    fn generates_schema_functions_for_reachable_types() {
+        let code = generate_module(&ir(), "crate::generated", false);
+
+        assert!(code.contains("use arrow_schema::{DataType, Field, Fields, Schema};\n"));
+        assert!(code.contains("use std::sync::Arc;\n"));
+        assert!(!code.contains("crate::generated"));
+        assert!(code.contains(
+            "pub fn game_account_schema() -> Schema {\n    Schema::new(game_account_fields())\n}"
+        ));
+        assert!(code.contains("Field::new(\"prize\", DataType::Decimal256(39, 0), false)"));
+        assert!(code.contains("Field::new(\"memo\", DataType::Binary, false)"));
+        assert!(code.contains(
+            "Field::new(\"moves\", DataType::List(Arc::new(Field::new(\"item\", DataType::Struct(move_fields()), false))), false)"
+        ));
+        assert!(code.contains("Field::new(\"nickname\", DataType::Utf8, true)"));
+        assert!(code.contains("Field::new(\"status\", DataType::Utf8, false)"));
+        assert!(code.contains(
+            "Field::new(\"finished\", DataType::Struct(Fields::from(vec![Field::new(\"winner\", DataType::Utf8, false), Field::new(\"note\", DataType::Utf8, true)])), true)"
+        ));
+        assert!(code.contains("pub fn move_fields() -> Fields"));
+        assert!(!code.contains("status_fields"));
+        assert!(!code.contains("unused"));
+    }
+
+    #[test]
+    fn generates_batch_conversions() {
+        let code = generate_module(&ir(), "crate::generated", true);
+
+        assert!(code.contains("use arrow_array::cast::AsArray;\n"));
+        assert!(code.contains("use arrow_buffer::{i256, NullBuffer, OffsetBuffer};\n"));
+        assert!(code.contains("use crate::generated::*;\n"));
+        assert!(code.contains(
+            "pub fn game_account_batch(rows: &[GameAccount]) -> Result<RecordBatch, ArrowError> {"
+        ));
+        assert!(code.contains(
+            "let items: Vec<Option<&_>> = items.iter().map(|v| v.map(|v| &v.score)).collect();"
+        ));
+        assert!(code.contains(".map(|v| v.map(|n| i256::from_parts(*n, 0)))"));
+        assert!(code
+            .contains("fn move_array(items: &[Option<&Move>]) -> Result<ArrayRef, ArrowError> {"));
+        assert!(code.contains(
+            "Ok(Arc::new(items.iter().map(|v| v.map(status_kind)).collect::<StringArray>()))"
+        ));
+        assert!(code.contains("GameState::Playing(..) => \"Playing\","));
+        assert!(code.contains("Some(GameState::Playing(_, value, ..)) => Some(value),"));
+        assert!(code.contains("Some(GameState::Finished { winner: value, .. }) => Some(value),"));
+        assert!(code.contains(".map(|v| v.filter(|v| matches!(v, GameState::Finished { .. })))"));
+        assert!(!code.contains("Ok(Arc::new(StructArray::try_new(Fields::from"));
+
+        let opens = code.matches('{').count();
+        assert_eq!(opens, code.matches('}').count());
+    }
+
+    #[test]
+    fn detects_identifiers() {
+        assert!(uses("let x: ArrayRef = y;", "ArrayRef"));
+        assert!(!uses("StringArrayRef", "ArrayRef"));
+        assert!(!uses("Int8Arrays", "Int8Array"));
+    }
+}
@@ -0,0 +1,512 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Borsh schema containers
+//!
+//! Builds the `BorshSchemaContainer` that `borsh::schema_container_of::<T>()`
+//! returns for the generated Rust type `T` when it derives `BorshSchema`
+//! (see `borsh-schema` in the `[rust]` section of `lumos.toml`), without
+//! compiling anything. [`SchemaContainer::to_borsh`] serializes it exactly as
+//! `borsh::to_vec` does, so tooling that reads Borsh schemas can load the
+//! exported file directly.
+//!
+//! Declarations follow `borsh` 1.x: primitives keep their Rust names,
+//! `String` and `Vec<T>` are length-prefixed sequences, `Option<T>` is an enum
+//! of `None` and `Some`, `Pubkey` is a struct around `[u8; 32]`, and enum
+//! variants are structs named `<Enum>__<Variant>`. Account containers describe
+//! the data after Anchor's 8-byte discriminator.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::borsh_schema;
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "struct Vault { owner: PublicKey, amount: u64 }",
+//! )?)?;
+//!
+//! let container = borsh_schema::container_for(&ir, "Vault")?;
+//! assert_eq!(container.declaration, "Vault");
+//! assert!(container.definitions.contains_key("Pubkey"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::{LumosError, Result};
+use crate::ir::{EnumVariantDefinition, TypeDefinition, TypeInfo};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// Length prefix width of `String` and `Vec<T>`
+const LENGTH_WIDTH: u8 = 4;
+
+/// How a declared type is laid out, mirroring `borsh::schema::Definition`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Definition {
+    /// Fixed-size value of this many bytes
+    Primitive(u8),
+
+    /// Elements prefixed by a `length_width`-byte count, or exactly `min` elements if zero
+    Sequence {
+        length_width: u8,
+        length_range: (u64, u64),
+        elements: String,
+    },
+
+    /// Elements in order, with no prefix
+    Tuple { elements: Vec<String> },
+
+    /// Variants as `(discriminant, name, declaration)`, after a `tag_width`-byte tag
+    Enum {
+        tag_width: u8,
+        variants: Vec<(i64, String, String)>,
+    },
+
+    /// Fields in order
+    Struct { fields: Fields },
+}
+
+/// Fields of a struct definition, mirroring `borsh::schema::Fields`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fields {
+    /// `(name, declaration)` pairs
+    Named(Vec<(String, String)>),
+
+    /// Declarations of tuple fields
+    Unnamed(Vec<String>),
+
+    /// No fields
+    Empty,
+}
+
+/// Declaration of a type and every definition needed to decode it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaContainer {
+    /// Declaration of the root type
+    pub declaration: String,
+
+    /// Definitions by declaration
+    pub definitions: BTreeMap<String, Definition>,
+}
+
+impl SchemaContainer {
+    /// The container in Borsh, as `borsh::to_vec(&BorshSchemaContainer)` writes it
+    pub fn to_borsh(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_string(&mut out, &self.declaration);
+        write_len(&mut out, self.definitions.len());
+        for (declaration, definition) in &self.definitions {
+            write_string(&mut out, declaration);
+            match definition {
+                Definition::Primitive(size) => {
+                    out.push(0);
+                    out.push(*size);
+                }
+                Definition::Sequence {
+                    length_width,
+                    length_range: (min, max),
+                    elements,
+                } => {
+                    out.push(1);
+                    out.push(*length_width);
+                    out.extend_from_slice(&min.to_le_bytes());
+                    out.extend_from_slice(&max.to_le_bytes());
+                    write_string(&mut out, elements);
+                }
+                Definition::Tuple { elements } => {
+                    out.push(2);
+                    write_len(&mut out, elements.len());
+                    for declaration in elements {
+                        write_string(&mut out, declaration);
+                    }
+                }
+                Definition::Enum {
+                    tag_width,
+                    variants,
+                } => {
+                    out.push(3);
+                    out.push(*tag_width);
+                    write_len(&mut out, variants.len());
+                    for (discriminant, name, declaration) in variants {
+                        out.extend_from_slice(&discriminant.to_le_bytes());
+                        write_string(&mut out, name);
+                        write_string(&mut out, declaration);
+                    }
+                }
+                Definition::Struct { fields } => {
+                    out.push(4);
+                    match fields {
+                        Fields::Named(fields) => {
+                            out.push(0);
+                            write_len(&mut out, fields.len());
+                            for (name, declaration) in fields {
+                                write_string(&mut out, name);
+                                write_string(&mut out, declaration);
+                            }
+                        }
+                        Fields::Unnamed(fields) => {
+                            out.push(1);
+                            write_len(&mut out, fields.len());
+                            for declaration in fields {
+                                write_string(&mut out, declaration);
+                            }
+                        }
+                        Fields::Empty => out.push(2),
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// The container as JSON, with definitions tagged like `borsh`'s enum variants
+    pub fn to_json(&self) -> Value {
+        let definitions: serde_json::Map<String, Value> = self
+            .definitions
+            .iter()
+            .map(|(declaration, definition)| {
+                let definition = match definition {
+                    Definition::Primitive(size) => json!({ "Primitive": size }),
+                    Definition::Sequence {
+                        length_width,
+                        length_range: (min, max),
+                        elements,
+                    } => json!({ "Sequence": {
+                        "length_width": length_width,
+                        "length_range": [min, max],
+                        "elements": elements,
+                    } }),
+                    Definition::Tuple { elements } => json!({ "Tuple": { "elements": elements } }),
+                    Definition::Enum {
+                        tag_width,
+                        variants,
+                    } => json!({ "Enum": { "tag_width": tag_width, "variants": variants } }),
+                    Definition::Struct { fields } => {
+                        let fields = match fields {
+                            Fields::Named(fields) => json!({ "NamedFields": fields }),
+                            Fields::Unnamed(fields) => json!({ "UnnamedFields": fields }),
+                            Fields::Empty => json!("Empty"),
+                        };
+                        json!({ "Struct": { "fields": fields } })
+                    }
+                };
+                (declaration.clone(), definition)
+            })
+            .collect();
+
+        json!({ "declaration": self.declaration, "definitions": definitions })
+    }
+}
+
+/// Build the schema container of the type named `type_name`
+pub fn container_for(type_defs: &[TypeDefinition], type_name: &str) -> Result<SchemaContainer> {
+    let mut builder = Builder {
+        type_defs,
+        definitions: BTreeMap::new(),
+    };
+    let declaration = builder.declare(&TypeInfo::UserDefined(type_name.to_string()))?;
+    Ok(SchemaContainer {
+        declaration,
+        definitions: builder.definitions,
+    })
+}
+
+struct Builder<'a> {
+    type_defs: &'a [TypeDefinition],
+    definitions: BTreeMap<String, Definition>,
+}
+
+impl Builder<'_> {
+    /// Declaration of `type_info`, adding its definitions
+    fn declare(&mut self, type_info: &TypeInfo) -> Result<String> {
+        match type_info {
+            TypeInfo::Primitive(name) => self.declare_primitive(name),
+            TypeInfo::Array(inner) => {
+                let elements = self.declare(inner)?;
+                let declaration = format!("Vec<{}>", elements);
+                self.define(&declaration, sequence(LENGTH_WIDTH, elements));
+                Ok(declaration)
+            }
+            TypeInfo::FixedArray(inner, len) => {
+                let elements = self.declare(inner)?;
+                let declaration = format!("[{}; {}]", elements, len);
+                self.define(
+                    &declaration,
+                    Definition::Sequence {
+                        length_width: 0,
+                        length_range: (*len as u64, *len as u64),
+                        elements,
+                    },
+                );
+                Ok(declaration)
+            }
+            TypeInfo::Map(kind, key, value) => {
+                let key = self.declare(key)?;
+                let value = self.declare(value)?;
+                let entry = format!("({}, {})", key, value);
+                self.define(
+                    &entry,
+                    Definition::Tuple {
+                        elements: vec![key.clone(), value.clone()],
+                    },
+                );
+                let declaration = format!("{}<{}, {}>", kind.as_str(), key, value);
+                self.define(&declaration, sequence(LENGTH_WIDTH, entry));
+                Ok(declaration)
+            }
+            TypeInfo::Option(inner) => {
+                let some = self.declare(inner)?;
+                self.define("()", Definition::Primitive(0));
+                let declaration = format!("Option<{}>", some);
+                self.define(
+                    &declaration,
+                    Definition::Enum {
+                        tag_width: 1,
+                        variants: vec![
+                            (0, "None".to_string(), "()".to_string()),
+                            (1, "Some".to_string(), some),
+                        ],
+                    },
+                );
+                Ok(declaration)
+            }
+            TypeInfo::UserDefined(name) => {
+                if !self.definitions.contains_key(name) {
+                    self.define_user_type(name)?;
+                }
+                Ok(name.clone())
+            }
+        }
+    }
+
+    fn declare_primitive(&mut self, name: &str) -> Result<String> {
+        let size = match name {
+            "bool" | "u8" | "i8" => 1,
+            "u16" | "i16" => 2,
+            "u32" | "i32" | "f32" => 4,
+            "u64" | "i64" | "f64" => 8,
+            "u128" | "i128" => 16,
+            // Signatures are generated as base58 strings
+            "String" | "Signature" => {
+                self.define("u8", Definition::Primitive(1));
+                self.define("String", sequence(LENGTH_WIDTH, "u8".to_string()));
+                return Ok("String".to_string());
+            }
+            "Pubkey" | "PublicKey" => {
+                self.define("u8", Definition::Primitive(1));
+                let bytes = "[u8; 32]".to_string();
+                self.define(
+                    &bytes,
+                    Definition::Sequence {
+                        length_width: 0,
+                        length_range: (32, 32),
+                        elements: "u8".to_string(),
+                    },
+                );
+                self.define(
+                    "Pubkey",
+                    Definition::Struct {
+                        fields: Fields::Unnamed(vec![bytes]),
+                    },
+                );
+                return Ok("Pubkey".to_string());
+            }
+            other => {
+                return Err(LumosError::CodeGen(format!(
+                    "'{}' has no Borsh schema",
+                    other
+                )))
+            }
+        };
+        self.define(name, Definition::Primitive(size));
+        Ok(name.to_string())
+    }
+
+    fn define_user_type(&mut self, name: &str) -> Result<()> {
+        let type_def = self
+            .type_defs
+            .iter()
+            .find(|t| t.name() == name)
+            .ok_or_else(|| LumosError::CodeGen(format!("Unknown type '{}'", name)))?;
+
+        // Placeholder so recursive references stop here
+        self.define(
+            name,
+            Definition::Struct {
+                fields: Fields::Empty,
+            },
+        );
+        let definition = match type_def {
+            TypeDefinition::Struct(s) => Definition::Struct {
+                fields: self.named_fields(s.fields.iter().map(|f| (&f.name, &f.type_info)))?,
+            },
+            TypeDefinition::Enum(e) => {
+                let mut variants = Vec::new();
+                for (index, variant) in e.variants.iter().enumerate() {
+                    let fields = match variant {
+                        EnumVariantDefinition::Unit { .. } => Fields::Empty,
+                        EnumVariantDefinition::Tuple { types, .. } => Fields::Unnamed(
+                            types
+                                .iter()
+                                .map(|t| self.declare(t))
+                                .collect::<Result<_>>()?,
+                        ),
+                        EnumVariantDefinition::Struct { fields, .. } => {
+                            self.named_fields(fields.iter().map(|f| (&f.name, &f.type_info)))?
+                        }
+                    };
+                    let declaration = format!("{}__{}", name, variant.name());
+                    self.define(&declaration, Definition::Struct { fields });
+                    variants.push((index as i64, variant.name().to_string(), declaration));
+                }
+                Definition::Enum {
+                    tag_width: 1,
+                    variants,
+                }
+            }
+        };
+        self.definitions.insert(name.to_string(), definition);
+        Ok(())
+    }
+
+    fn named_fields<'f>(
+        &mut self,
+        fields: impl Iterator<Item = (&'f String, &'f TypeInfo)>,
+    ) -> Result<Fields> {
+        let fields = fields
+            .map(|(name, type_info)| Ok((name.clone(), self.declare(type_info)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Fields::Named(fields))
+    }
+
+    fn define(&mut self, declaration: &str, definition: Definition) {
+        self.definitions
+            .entry(declaration.to_string())
+            .or_insert(definition);
+    }
+}
+
+fn sequence(length_width: u8, elements: String) -> Definition {
+    Definition::Sequence {
+        length_width,
+        length_range: (0, u32::MAX as u64),
+        elements,
+    }
+}
+
+fn write_len(out: &mut Vec<u8>, len: usize) {
+    out.extend_from_slice(&(len as u32).to_le_bytes());
+}
+
+fn write_string(out: &mut Vec<u8>, value: &str) {
+    write_len(out, value.len());
+    out.extend_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+    use borsh::BorshSchema;
+
+    const SCHEMA: &str = r#"
+        #[solana]
+        #[account]
+        struct Player {
+            name: String,
+            level: u16,
+            score: u128,
+            items: [Item],
+            guild: Option<u32>,
+            status: Status,
+            flag: bool,
+            allies: HashMap<u32, Item>,
+            stakes: BTreeMap<String, u64>,
+        }
+
+        #[solana]
+        struct Item { id: u64, weight: f32 }
+
+        #[solana]
+        enum Status { Idle, Fighting(i64, u8), Trading { partner: String } }
+    "#;
+
+    // The Rust types LUMOS generates for SCHEMA, deriving `BorshSchema`
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    struct Player {
+        name: String,
+        level: u16,
+        score: u128,
+        items: Vec<Item>,
+        guild: Option<u32>,
+        status: Status,
+        flag: bool,
+        allies: std::collections::HashMap<u32, Item>,
+        stakes: std::collections::BTreeMap<String, u64>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    struct Item {
+        id: u64,
+        weight: f32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    enum Status {
+        Idle,
+        Fighting(i64, u8),
+        Trading { partner: String },
+    }
+
+    fn ir() -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn matches_borsh_derived_container() {
+        let container = container_for(&ir(), "Player").unwrap();
+        let expected = borsh::to_vec(&borsh::schema_container_of::<Player>()).unwrap();
+        assert_eq!(container.to_borsh(), expected);
+    }
+
+    #[test]
+    fn describes_public_keys_and_variants() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                "struct Vault { owner: PublicKey, status: Status }\nenum Status { Open }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let container = container_for(&ir, "Vault").unwrap();
+
+        assert_eq!(
+            container.definitions["Pubkey"],
+            Definition::Struct {
+                fields: Fields::Unnamed(vec!["[u8; 32]".to_string()])
+            }
+        );
+        assert_eq!(
+            container.definitions["Status__Open"],
+            Definition::Struct {
+                fields: Fields::Empty
+            }
+        );
+
+        let json = container.to_json();
+        assert_eq!(json["declaration"], "Vault");
+        assert_eq!(
+            json["definitions"]["[u8; 32]"]["Sequence"]["length_range"],
+            json!([32, 32])
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_types() {
+        assert!(container_for(&ir(), "Missing").is_err());
+    }
+}
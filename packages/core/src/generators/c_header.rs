@@ -0,0 +1,203 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! C headers for `#[zero_copy]` structs
+//!
+//! Native clients (C/C++ bots, market makers) map zero-copy account data
+//! directly onto these structs instead of decoding Borsh. Every struct gets
+//! `sizeof`/`offsetof` static assertions, so a compiler that lays it out
+//! differently from the generated Rust fails the build instead of reading
+//! garbage. `u128`/`i128` and `PublicKey` have no portable C type and become
+//! byte arrays; accounts also get a `<NAME>_DATA_OFFSET` define for the
+//! 8-byte Anchor discriminator that precedes their data.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::c_header;
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "#[zero_copy]\nstruct Level { price: u64, size: u64 }",
+//! )?)?;
+//!
+//! let header = c_header::generate(&ir, "ORDERBOOK_H")?.unwrap();
+//! assert!(header.contains("typedef struct Level {"));
+//! assert!(header.contains("    uint64_t price; /* offset 0 */"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::Result;
+use crate::ir::{TypeDefinition, TypeInfo};
+use crate::zero_copy::{self, Layout};
+
+/// Size of the Anchor discriminator in front of account data
+const DISCRIMINATOR_SIZE: usize = 8;
+
+/// Generate a header declaring every `#[zero_copy]` struct, or `None` if there are none
+///
+/// `guard` is the include guard macro, e.g. `GAME_STATE_H`.
+pub fn generate(type_defs: &[TypeDefinition], guard: &str) -> Result<Option<String>> {
+    let layouts = zero_copy::ordered_layouts(type_defs)?;
+    if layouts.is_empty() {
+        return Ok(None);
+    }
+
+    let mut output = String::new();
+    output.push_str("/* Auto-generated by LUMOS */\n");
+    output.push_str("/* DO NOT EDIT - Changes will be overwritten */\n\n");
+    output.push_str(&format!("#ifndef {guard}\n#define {guard}\n\n"));
+    output.push_str("#include <stddef.h>\n#include <stdint.h>\n\n");
+    output.push_str(
+        "#ifndef LUMOS_STATIC_ASSERT\n\
+         #ifdef __cplusplus\n\
+         #define LUMOS_STATIC_ASSERT(cond, msg) static_assert(cond, msg)\n\
+         #else\n\
+         #define LUMOS_STATIC_ASSERT(cond, msg) _Static_assert(cond, msg)\n\
+         #endif\n\
+         #endif\n\n",
+    );
+    output.push_str(
+        "#if defined(__BYTE_ORDER__) && __BYTE_ORDER__ != __ORDER_LITTLE_ENDIAN__\n\
+         #error \"LUMOS zero-copy layouts are little-endian\"\n\
+         #endif\n",
+    );
+
+    for layout in &layouts {
+        output.push('\n');
+        output.push_str(&generate_struct(type_defs, layout));
+    }
+
+    output.push_str(&format!("\n#endif /* {guard} */\n"));
+    Ok(Some(output))
+}
+
+fn generate_struct(type_defs: &[TypeDefinition], layout: &Layout) -> String {
+    let name = &layout.name;
+    let mut output = String::new();
+
+    if is_account(type_defs, name) {
+        output.push_str(&format!(
+            "/* Account data starts after the {DISCRIMINATOR_SIZE}-byte Anchor discriminator */\n"
+        ));
+        output.push_str(&format!(
+            "#define {}_DATA_OFFSET {}\n\n",
+            to_screaming_snake_case(name),
+            DISCRIMINATOR_SIZE
+        ));
+    }
+
+    output.push_str(&format!("typedef struct {name} {{\n"));
+    for field in &layout.fields {
+        output.push_str(&format!(
+            "    {}; /* offset {} */\n",
+            c_declaration(&field.type_info, &field.name),
+            field.offset
+        ));
+    }
+    output.push_str(&format!("}} {name};\n\n"));
+
+    output.push_str(&format!(
+        "LUMOS_STATIC_ASSERT(sizeof({name}) == {}, \"{name} size\");\n",
+        layout.size
+    ));
+    for field in &layout.fields {
+        output.push_str(&format!(
+            "LUMOS_STATIC_ASSERT(offsetof({name}, {field}) == {offset}, \"{name}.{field} offset\");\n",
+            field = field.name,
+            offset = field.offset
+        ));
+    }
+
+    output
+}
+
+/// C declaration of a field; validated layouts only contain Pod types
+fn c_declaration(type_info: &TypeInfo, field: &str) -> String {
+    match type_info {
+        TypeInfo::Primitive(name) => match name.as_str() {
+            "u8" => format!("uint8_t {field}"),
+            "i8" => format!("int8_t {field}"),
+            "u16" => format!("uint16_t {field}"),
+            "i16" => format!("int16_t {field}"),
+            "u32" => format!("uint32_t {field}"),
+            "i32" => format!("int32_t {field}"),
+            "u64" => format!("uint64_t {field}"),
+            "i64" => format!("int64_t {field}"),
+            "f32" => format!("float {field}"),
+            "f64" => format!("double {field}"),
+            "u128" | "i128" => format!("uint8_t {field}[16]"),
+            _ => format!("uint8_t {field}[32]"),
+        },
+        TypeInfo::UserDefined(name) => format!("{name} {field}"),
+        TypeInfo::FixedArray(inner, len) => c_declaration(inner, &format!("{field}[{len}]")),
+        TypeInfo::Array(_) | TypeInfo::Option(_) | TypeInfo::Map(..) => {
+            unreachable!("zero_copy::layout rejects vectors, maps and options")
+        }
+    }
+}
+
+fn is_account(type_defs: &[TypeDefinition], name: &str) -> bool {
+    type_defs
+        .iter()
+        .find(|t| t.name() == name)
+        .is_some_and(|t| t.is_account())
+}
+
+/// `OrderBook` -> `ORDER_BOOK`
+fn to_screaming_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            result.push('_');
+        }
+        result.push(ch.to_ascii_uppercase());
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn ir(source: &str) -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn declares_structs_with_layout_asserts() {
+        let ir = ir(r#"
+            #[zero_copy]
+            struct Level { price: u64, size: u64 }
+
+            #[solana]
+            #[account]
+            #[zero_copy]
+            struct OrderBook { total: u128, market: PublicKey, best: Level, fee_bps: i16, bump: u8, side: u8, tick: u32, seq: u64 }
+        "#);
+
+        let header = generate(&ir, "BOOK_H").unwrap().unwrap();
+        assert!(header.contains("#ifndef BOOK_H\n#define BOOK_H\n"));
+        assert!(header.ends_with("#endif /* BOOK_H */\n"));
+        assert!(header.find("typedef struct Level {") < header.find("typedef struct OrderBook {"));
+        assert!(header.contains("#define ORDER_BOOK_DATA_OFFSET 8\n"));
+        assert!(!header.contains("LEVEL_DATA_OFFSET"));
+        assert!(header.contains(
+            "typedef struct OrderBook {\n    uint8_t total[16]; /* offset 0 */\n    uint8_t market[32]; /* offset 16 */\n    Level best; /* offset 48 */\n    int16_t fee_bps; /* offset 64 */\n    uint8_t bump; /* offset 66 */\n    uint8_t side; /* offset 67 */\n    uint32_t tick; /* offset 68 */\n    uint64_t seq; /* offset 72 */\n} OrderBook;\n"
+        ));
+        assert!(
+            header.contains("LUMOS_STATIC_ASSERT(sizeof(OrderBook) == 80, \"OrderBook size\");")
+        );
+        assert!(header.contains(
+            "LUMOS_STATIC_ASSERT(offsetof(OrderBook, tick) == 68, \"OrderBook.tick offset\");"
+        ));
+    }
+
+    #[test]
+    fn skips_schemas_without_zero_copy_types() {
+        let ir = ir("struct Vault { owner: PublicKey, amount: u64 }");
+        assert!(generate(&ir, "VAULT_H").unwrap().is_none());
+    }
+}
@@ -0,0 +1,159 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Per-type snippet cache
+//!
+//! The generators produce each type's code independently of the other types,
+//! so a snippet can be reused whenever the type's IR, the generation context
+//! and the LUMOS version are unchanged. [`snippet_key`] derives a key from
+//! those inputs with a hash that is stable across Rust releases, which lets
+//! callers persist snippets between runs (e.g. on disk in watch mode).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::cache::SnippetCache;
+//! use lumos_core::generators::rust;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Default)]
+//! struct MemoryCache(HashMap<u64, String>);
+//!
+//! impl SnippetCache for MemoryCache {
+//!     fn get(&mut self, key: u64) -> Option<String> {
+//!         self.0.get(&key).cloned()
+//!     }
+//!
+//!     fn put(&mut self, key: u64, snippet: &str) {
+//!         self.0.insert(key, snippet.to_string());
+//!     }
+//! }
+//!
+//! let ir = lumos_core::transform::transform_to_ir(
+//!     lumos_core::parser::parse_lumos_file("struct Player { level: u16 }")?,
+//! )?;
+//!
+//! let mut cache = MemoryCache::default();
+//! let mut first = Vec::new();
+//! rust::generate_cached_to(&ir, None, &mut cache, &mut first)?;
+//! let mut second = Vec::new();
+//! rust::generate_cached_to(&ir, None, &mut cache, &mut second)?;
+//!
+//! assert_eq!(first, second);
+//! assert_eq!(cache.0.len(), 1);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::ir::TypeDefinition;
+use std::hash::{Hash, Hasher};
+
+/// Store of generated per-type snippets, keyed by [`snippet_key`]
+pub trait SnippetCache {
+    /// Previously generated snippet for `key`
+    fn get(&mut self, key: u64) -> Option<String>;
+
+    /// Remember the snippet generated for `key`
+    fn put(&mut self, key: u64, snippet: &str);
+}
+
+/// Cache that never hits, used by the uncached entry points
+pub struct NoCache;
+
+impl SnippetCache for NoCache {
+    fn get(&mut self, _key: u64) -> Option<String> {
+        None
+    }
+
+    fn put(&mut self, _key: u64, _snippet: &str) {}
+}
+
+/// Cache key for one type's snippet
+///
+/// `target` names the generator (`"rust"`, `"typescript"`) and `context`
+/// carries any module-level input the snippet depends on, such as whether
+/// the module uses Anchor.
+pub fn snippet_key(target: &str, context: impl Hash, type_def: &TypeDefinition) -> u64 {
+    let mut hasher = StableHasher::default();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    target.hash(&mut hasher);
+    context.hash(&mut hasher);
+    type_def.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Look up a snippet, generating and storing it on a miss
+pub(crate) fn cached_snippet(
+    cache: &mut dyn SnippetCache,
+    key: u64,
+    generate: impl FnOnce() -> String,
+) -> String {
+    if let Some(snippet) = cache.get(key) {
+        return snippet;
+    }
+    let snippet = generate();
+    cache.put(key, &snippet);
+    snippet
+}
+
+/// 64-bit FNV-1a; unlike `DefaultHasher`, its output never changes between
+/// Rust releases, so keys stay valid for caches persisted across runs
+pub(crate) struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn ir(source: &str) -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn key_depends_on_type_target_and_context() {
+        let player = &ir("struct Player { level: u16 }")[0];
+        let moved = &ir("\n\n   struct Player { level: u16 }")[0];
+        let changed = &ir("struct Player { level: u32 }")[0];
+
+        let key = snippet_key("rust", false, player);
+        assert_eq!(key, snippet_key("rust", false, moved));
+        assert_ne!(key, snippet_key("rust", false, changed));
+        assert_ne!(key, snippet_key("rust", true, player));
+        assert_ne!(key, snippet_key("typescript", false, player));
+    }
+
+    #[test]
+    fn attribute_spans_do_not_change_keys() {
+        let first = &ir("struct Vault {\n    #[max(32)]\n    name: String,\n}")[0];
+        let second = &ir("struct Vault { #[max(32)] name: String }")[0];
+        let other = &ir("struct Vault { #[max(64)] name: String }")[0];
+
+        assert_eq!(
+            snippet_key("rust", (), first),
+            snippet_key("rust", (), second)
+        );
+        assert_ne!(
+            snippet_key("rust", (), first),
+            snippet_key("rust", (), other)
+        );
+    }
+}
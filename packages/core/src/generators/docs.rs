@@ -0,0 +1,853 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Account reference documentation
+//!
+//! Renders a schema as Markdown with one section per type: its kind and
+//! attributes, size and rent, a field or variant table that links to the
+//! types it references, and an example instance as JSON and as the Borsh
+//! bytes stored on-chain (the `#[test_vector]` values, when declared). [`generate_site`] lays the sections out as an
+//! mdBook or a Docusaurus content folder, one page per type, so protocols
+//! can publish their account reference straight from the schema;
+//! [`generate_markdown`] renders the same content as a single page. The
+//! schema's `schema { ... }` block, when given, introduces the overview.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::docs::{self, SiteFormat};
+//! use lumos_core::{parser, transform};
+//!
+//! let source = r#"
+//!     #[solana]
+//!     #[account]
+//!     struct Vault { owner: PublicKey, stats: Stats }
+//!
+//!     struct Stats { deposits: u64 }
+//! "#;
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(source)?)?;
+//! let files = docs::generate_site(&ir, "Vault Program", None, SiteFormat::MdBook);
+//!
+//! let vault = files.iter().find(|f| f.path == "src/types/vault.md").unwrap();
+//! assert!(vault.content.contains("[`Stats`](./stats.md)"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::ast::SchemaMetadata;
+use crate::borsh_decoder::DecodedValue;
+use crate::ir::{EnumVariantDefinition, TypeDefinition, TypeInfo};
+use crate::mock_generator::{self, MockGenerator};
+use crate::size_calculator::{AccountSize, SizeCalculator, SizeInfo};
+use crate::test_vector;
+use serde_json::Value;
+
+/// Seed of the example instances, fixed so regenerated docs don't churn
+const EXAMPLE_SEED: u64 = 0;
+
+/// Bytes per line of a hex dump
+const HEX_LINE: usize = 16;
+
+/// Documentation site layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiteFormat {
+    /// `book.toml` and a `src/` folder with `SUMMARY.md`
+    MdBook,
+
+    /// Content folder for a Docusaurus `docs/` directory
+    Docusaurus,
+}
+
+/// A file of a generated site
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiteFile {
+    /// Path relative to the output directory, with `/` separators
+    pub path: String,
+
+    /// File content
+    pub content: String,
+}
+
+/// Generate a documentation site with one page per type
+pub fn generate_site(
+    type_defs: &[TypeDefinition],
+    title: &str,
+    schema: Option<&SchemaMetadata>,
+    format: SiteFormat,
+) -> Vec<SiteFile> {
+    let reference = Reference::new(type_defs);
+    let about = schema.map(about).unwrap_or_default();
+    let page_link = |name: &str| format!("./{}.md", page_name(name));
+    let overview_link = |name: &str| format!("./types/{}.md", page_name(name));
+
+    let mut files = Vec::new();
+    match format {
+        SiteFormat::MdBook => {
+            files.push(SiteFile {
+                path: "book.toml".to_string(),
+                content: format!("[book]\ntitle = {}\nsrc = \"src\"\n", toml_string(title)),
+            });
+            files.push(SiteFile {
+                path: "src/SUMMARY.md".to_string(),
+                content: reference.summary(),
+            });
+            files.push(SiteFile {
+                path: "src/README.md".to_string(),
+                content: format!(
+                    "# {}\n\n{}{}",
+                    title,
+                    about,
+                    reference.overview(&overview_link)
+                ),
+            });
+            for type_def in type_defs {
+                files.push(SiteFile {
+                    path: format!("src/types/{}.md", page_name(type_def.name())),
+                    content: format!(
+                        "# {}\n\n{}",
+                        type_def.name(),
+                        reference.describe(type_def, 2, &page_link)
+                    ),
+                });
+            }
+        }
+        SiteFormat::Docusaurus => {
+            files.push(SiteFile {
+                path: "index.md".to_string(),
+                content: format!(
+                    "---\ntitle: {}\nsidebar_position: 1\n---\n\n{}{}",
+                    yaml_string(title),
+                    about,
+                    reference.overview(&overview_link)
+                ),
+            });
+            files.push(SiteFile {
+                path: "types/_category_.json".to_string(),
+                content: "{\n  \"label\": \"Types\",\n  \"position\": 2\n}\n".to_string(),
+            });
+            for (index, type_def) in type_defs.iter().enumerate() {
+                files.push(SiteFile {
+                    path: format!("types/{}.md", page_name(type_def.name())),
+                    content: format!(
+                        "---\ntitle: {}\nsidebar_position: {}\n---\n\n{}",
+                        type_def.name(),
+                        index + 1,
+                        reference.describe(type_def, 2, &page_link)
+                    ),
+                });
+            }
+        }
+    }
+    files
+}
+
+/// Generate the reference as a single Markdown page
+pub fn generate_markdown(
+    type_defs: &[TypeDefinition],
+    title: &str,
+    schema: Option<&SchemaMetadata>,
+) -> String {
+    let reference = Reference::new(type_defs);
+    let anchor = |name: &str| format!("#{}", name.to_lowercase());
+
+    let mut out = format!(
+        "# {}\n\n{}{}",
+        title,
+        schema.map(about).unwrap_or_default(),
+        reference.overview(&anchor)
+    );
+    for type_def in type_defs {
+        out.push_str(&format!(
+            "\n## {}\n\n{}",
+            type_def.name(),
+            reference.describe(type_def, 3, &anchor)
+        ));
+    }
+    out
+}
+
+/// Schema with the analyses every page draws on
+struct Reference<'a> {
+    type_defs: &'a [TypeDefinition],
+    sizes: Vec<AccountSize>,
+    mocks: MockGenerator<'a>,
+}
+
+impl<'a> Reference<'a> {
+    fn new(type_defs: &'a [TypeDefinition]) -> Self {
+        Self {
+            type_defs,
+            sizes: SizeCalculator::new(type_defs).calculate_all(),
+            mocks: MockGenerator::new(type_defs).with_seed(EXAMPLE_SEED),
+        }
+    }
+
+    fn size(&self, name: &str) -> Option<&AccountSize> {
+        self.sizes.iter().find(|size| size.name == name)
+    }
+
+    /// mdBook table of contents, grouped by kind
+    fn summary(&self) -> String {
+        let mut out = String::from("# Summary\n\n[Overview](README.md)\n");
+        for group in KINDS {
+            let members: Vec<&TypeDefinition> = self
+                .type_defs
+                .iter()
+                .filter(|t| group_of(t) == group)
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n# {}\n\n", group));
+            for type_def in members {
+                out.push_str(&format!(
+                    "- [{}](types/{}.md)\n",
+                    type_def.name(),
+                    page_name(type_def.name())
+                ));
+            }
+        }
+        out
+    }
+
+    /// Table of every type with its kind, size and rent
+    fn overview(&self, link: &dyn Fn(&str) -> String) -> String {
+        if self.type_defs.is_empty() {
+            return "The schema defines no types.\n".to_string();
+        }
+
+        let mut out = String::from("| Type | Kind | Size (bytes) | Rent-exempt minimum |\n");
+        out.push_str("|------|------|--------------|---------------------|\n");
+        for type_def in self.type_defs {
+            let size = self.size(type_def.name());
+            out.push_str(&format!(
+                "| [`{}`]({}) | {} | {} | {} |\n",
+                type_def.name(),
+                link(type_def.name()),
+                kind(type_def),
+                size.map(|s| describe_size(&s.total_bytes))
+                    .unwrap_or_default(),
+                match size {
+                    Some(size) if is_account(type_def) => format!("{:.8} SOL", size.rent_sol),
+                    _ => "-".to_string(),
+                }
+            ));
+        }
+        out
+    }
+
+    /// Everything below a type's title; subsections use `level` hashes
+    fn describe(
+        &self,
+        type_def: &TypeDefinition,
+        level: usize,
+        link: &dyn Fn(&str) -> String,
+    ) -> String {
+        let heading = "#".repeat(level);
+        let mut out = format!("**Kind:** {}", kind(type_def));
+
+        let attributes = attributes(type_def);
+        if !attributes.is_empty() {
+            out.push_str(&format!("  \n**Attributes:** {}", attributes.join(" ")));
+        }
+        if let Some(size) = self.size(type_def.name()) {
+            out.push_str(&format!("  \n**Size:** {}", size_sentence(type_def, size)));
+            if is_account(type_def) {
+                out.push_str(&format!(
+                    "  \n**Rent-exempt minimum:** {:.8} SOL",
+                    size.rent_sol
+                ));
+            }
+        }
+        out.push('\n');
+
+        match type_def {
+            TypeDefinition::Struct(s) => {
+                out.push_str(&format!("\n{} Fields\n\n", heading));
+                out.push_str("| Field | Type | Size (bytes) | Description |\n");
+                out.push_str("|-------|------|--------------|-------------|\n");
+                let breakdown = self
+                    .size(&s.name)
+                    .map(|size| size.field_breakdown.as_slice())
+                    .unwrap_or_default();
+                if is_account(type_def) {
+                    out.push_str(
+                        "| *discriminator* | `[u8; 8]` | 8 | Anchor account discriminator |\n",
+                    );
+                }
+                for field in &s.fields {
+                    let size = breakdown
+                        .iter()
+                        .find(|f| f.name == field.name)
+                        .map(|f| describe_size(&f.size))
+                        .unwrap_or_default();
+                    let mut description = field
+                        .doc_comment()
+                        .map(|doc| escape(&doc))
+                        .unwrap_or_default();
+                    if field.is_sensitive() {
+                        description = format!("**Sensitive**, redacted from logs. {}", description)
+                            .trim_end()
+                            .to_string();
+                    }
+                    out.push_str(&format!(
+                        "| `{}` | {} | {} | {} |\n",
+                        field.name,
+                        type_cell(&field.type_info, link),
+                        size,
+                        description
+                    ));
+                }
+
+                let lifecycle = &s.metadata.lifecycle;
+                if !lifecycle.is_empty() {
+                    out.push_str(&format!("\n{} Lifecycle\n\n", heading));
+                    if !lifecycle.initialized_by.is_empty() {
+                        out.push_str(&format!(
+                            "- Initialized by {}\n",
+                            code_list(&lifecycle.initialized_by)
+                        ));
+                    }
+                    if lifecycle.closable {
+                        out.push_str(&match lifecycle.closed_by.is_empty() {
+                            true => "- Closable\n".to_string(),
+                            false => format!("- Closed by {}\n", code_list(&lifecycle.closed_by)),
+                        });
+                    }
+                    if lifecycle.reallocable {
+                        out.push_str("- Resizable with `realloc`\n");
+                    }
+                }
+            }
+            TypeDefinition::Enum(e) => {
+                out.push_str(&format!("\n{} Variants\n\n", heading));
+                out.push_str("| Index | Variant | Fields |\n");
+                out.push_str("|-------|---------|--------|\n");
+                for (index, variant) in e.variants.iter().enumerate() {
+                    let fields = match variant {
+                        EnumVariantDefinition::Unit { .. } => "-".to_string(),
+                        EnumVariantDefinition::Tuple { types, .. } => types
+                            .iter()
+                            .map(|t| type_cell(t, link))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        EnumVariantDefinition::Struct { fields, .. } => fields
+                            .iter()
+                            .map(|f| format!("`{}`: {}", f.name, type_cell(&f.type_info, link)))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    };
+                    out.push_str(&format!(
+                        "| {} | `{}` | {} |\n",
+                        index,
+                        variant.name(),
+                        fields
+                    ));
+                }
+            }
+        }
+
+        let referrers = self.referenced_by(type_def.name());
+        if !referrers.is_empty() {
+            out.push_str(&format!("\n{} Referenced by\n\n", heading));
+            for (owner, member) in referrers {
+                out.push_str(&format!("- [`{}`]({}) `{}`\n", owner, link(owner), member));
+            }
+        }
+
+        // Values declared with #[test_vector], which the generated tests
+        // check, or else a mock instance
+        let vectors = match type_def {
+            // Checked when the schema was transformed
+            TypeDefinition::Struct(s) => {
+                test_vector::test_vectors(self.type_defs, s).unwrap_or_default()
+            }
+            TypeDefinition::Enum(_) => Vec::new(),
+        };
+        let examples: Vec<(String, Vec<u8>)> = match vectors.is_empty() {
+            true => self
+                .mocks
+                .generate(type_def.name(), 1)
+                .unwrap_or_default()
+                .iter()
+                .map(|value| {
+                    (
+                        mock_generator::instance_to_json(value),
+                        self.mocks.to_borsh(type_def.name(), value),
+                    )
+                })
+                .collect(),
+            false => vectors
+                .into_iter()
+                .map(|vector| {
+                    let mut json = String::new();
+                    write_json(&vector.value, 0, &mut json);
+                    json.push('\n');
+                    (json, vector.bytes)
+                })
+                .collect(),
+        };
+
+        if !examples.is_empty() {
+            let title = if examples.len() > 1 {
+                "Examples"
+            } else {
+                "Example"
+            };
+            out.push_str(&format!("\n{} {}\n", heading, title));
+            for (json, bytes) in &examples {
+                out.push_str("\n```json\n");
+                out.push_str(json);
+                out.push_str("```\n\n");
+                out.push_str("Borsh encoding:\n\n```text\n");
+                out.push_str(&hex_dump(bytes));
+                out.push_str("```\n");
+            }
+            if is_account(type_def) {
+                out.push_str(&format!(
+                    "\nOn-chain, the account data starts with the 8-byte discriminator, the first 8 bytes of `sha256(\"account:{}\")`, followed by these bytes.\n",
+                    type_def.name()
+                ));
+            }
+        }
+
+        out
+    }
+
+    /// `(type, member)` pairs that mention `name`, e.g. `("Vault", "stats")`
+    fn referenced_by(&self, name: &str) -> Vec<(&'a str, String)> {
+        let mut referrers = Vec::new();
+        for type_def in self.type_defs {
+            match type_def {
+                TypeDefinition::Struct(s) => {
+                    for field in &s.fields {
+                        if mentions(&field.type_info, name) {
+                            referrers.push((s.name.as_str(), field.name.clone()));
+                        }
+                    }
+                }
+                TypeDefinition::Enum(e) => {
+                    for variant in &e.variants {
+                        let found = match variant {
+                            EnumVariantDefinition::Unit { .. } => false,
+                            EnumVariantDefinition::Tuple { types, .. } => {
+                                types.iter().any(|t| mentions(t, name))
+                            }
+                            EnumVariantDefinition::Struct { fields, .. } => {
+                                fields.iter().any(|f| mentions(&f.type_info, name))
+                            }
+                        };
+                        if found {
+                            referrers.push((e.name.as_str(), variant.name().to_string()));
+                        }
+                    }
+                }
+            }
+        }
+        referrers
+    }
+}
+
+/// Groups in the mdBook summary, in order
+const KINDS: [&str; 3] = ["Accounts", "Instructions", "Types"];
+
+/// The description, version, license and authors of the schema
+fn about(schema: &SchemaMetadata) -> String {
+    let mut out = String::new();
+    if let Some(description) = &schema.description {
+        out.push_str(&format!("{}\n\n", escape(description)));
+    }
+    let mut facts = vec![match &schema.version {
+        Some(version) => format!("**Schema:** `{}` {}", schema.name, version),
+        None => format!("**Schema:** `{}`", schema.name),
+    }];
+    if let Some(license) = &schema.license {
+        facts.push(format!("**License:** {}", escape(license)));
+    }
+    if !schema.authors.is_empty() {
+        facts.push(format!(
+            "**Authors:** {}",
+            escape(&schema.authors.join(", "))
+        ));
+    }
+    out.push_str(&format!("{}\n\n", facts.join(" · ")));
+    out
+}
+
+fn group_of(type_def: &TypeDefinition) -> &'static str {
+    if is_account(type_def) {
+        "Accounts"
+    } else if type_def.is_instruction() {
+        "Instructions"
+    } else {
+        "Types"
+    }
+}
+
+fn is_account(type_def: &TypeDefinition) -> bool {
+    matches!(type_def, TypeDefinition::Struct(_)) && type_def.is_account()
+}
+
+fn kind(type_def: &TypeDefinition) -> &'static str {
+    match type_def {
+        _ if is_account(type_def) => "Account",
+        _ if type_def.is_instruction() => "Instruction",
+        TypeDefinition::Struct(_) => "Struct",
+        TypeDefinition::Enum(_) => "Enum",
+    }
+}
+
+/// Type-level attributes in schema notation
+fn attributes(type_def: &TypeDefinition) -> Vec<String> {
+    let metadata = type_def.metadata();
+    let mut attributes = Vec::new();
+    if metadata.solana {
+        attributes.push("`#[solana]`".to_string());
+    }
+    for name in &metadata.attributes {
+        // `solana` is listed from its flag, `version` with its number
+        if name == "solana" || name == "version" {
+            continue;
+        }
+        attributes.push(format!("`#[{}]`", name));
+    }
+    if let Some(version) = metadata.version {
+        attributes.push(format!("`#[version({})]`", version));
+    }
+    attributes
+}
+
+fn size_sentence(type_def: &TypeDefinition, size: &AccountSize) -> String {
+    let discriminator = if is_account(type_def) {
+        ", including the 8-byte discriminator"
+    } else {
+        ""
+    };
+    match &size.total_bytes {
+        SizeInfo::Fixed(bytes) => format!("{} bytes{}", bytes, discriminator),
+        SizeInfo::Variable { min, .. } => format!("at least {} bytes{}", min, discriminator),
+    }
+}
+
+/// `32` for fixed sizes, `4+` for variable ones
+fn describe_size(size: &SizeInfo) -> String {
+    match size {
+        SizeInfo::Fixed(bytes) => bytes.to_string(),
+        SizeInfo::Variable { min, .. } => format!("{}+", min),
+    }
+}
+
+/// Type in schema notation, linked to the page of the user type it contains
+fn type_cell(type_info: &TypeInfo, link: &dyn Fn(&str) -> String) -> String {
+    let code = format!("`{}`", signature(type_info));
+    match user_type(type_info) {
+        Some(name) => format!("[{}]({})", code, link(name)),
+        None => code,
+    }
+}
+
+fn signature(type_info: &TypeInfo) -> String {
+    match type_info {
+        TypeInfo::Primitive(name) | TypeInfo::UserDefined(name) => name.clone(),
+        TypeInfo::Array(inner) => format!("[{}]", signature(inner)),
+        TypeInfo::FixedArray(inner, len) => format!("[{}; {}]", signature(inner), len),
+        TypeInfo::Map(kind, key, value) => {
+            format!(
+                "{}<{}, {}>",
+                kind.as_str(),
+                signature(key),
+                signature(value)
+            )
+        }
+        TypeInfo::Option(inner) => format!("Option<{}>", signature(inner)),
+    }
+}
+
+fn user_type(type_info: &TypeInfo) -> Option<&str> {
+    match type_info {
+        TypeInfo::Primitive(_) => None,
+        TypeInfo::UserDefined(name) => Some(name),
+        TypeInfo::Array(inner)
+        | TypeInfo::FixedArray(inner, _)
+        | TypeInfo::Option(inner)
+        | TypeInfo::Map(_, _, inner) => user_type(inner),
+    }
+}
+
+fn mentions(type_info: &TypeInfo, name: &str) -> bool {
+    user_type(type_info) == Some(name)
+}
+
+fn code_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("`{}`", name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Pretty-printed JSON of `value`, keeping fields in declaration order
+fn write_json(value: &DecodedValue, indent: usize, out: &mut String) {
+    let object = |fields: &[(String, DecodedValue)], indent: usize, out: &mut String| {
+        if fields.is_empty() {
+            out.push_str("{}");
+            return;
+        }
+        out.push_str("{\n");
+        for (index, (name, value)) in fields.iter().enumerate() {
+            out.push_str(&"  ".repeat(indent + 1));
+            out.push_str(&format!("{}: ", Value::from(name.as_str())));
+            write_json(value, indent + 1, out);
+            out.push_str(if index + 1 < fields.len() {
+                ",\n"
+            } else {
+                "\n"
+            });
+        }
+        out.push_str(&"  ".repeat(indent));
+        out.push('}');
+    };
+
+    match value {
+        DecodedValue::Struct(fields) => object(fields, indent, out),
+        DecodedValue::Variant { name, fields } if !fields.is_empty() => {
+            object(
+                &[(name.clone(), DecodedValue::Struct(fields.clone()))],
+                indent,
+                out,
+            );
+        }
+        DecodedValue::Option(Some(value)) => write_json(value, indent, out),
+        DecodedValue::Vec(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                write_json(item, indent, out);
+            }
+            out.push(']');
+        }
+        value => out.push_str(&value.to_json().to_string()),
+    }
+}
+
+/// Offset and up to 16 bytes per line
+fn hex_dump(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "(empty)\n".to_string();
+    }
+    bytes
+        .chunks(HEX_LINE)
+        .enumerate()
+        .map(|(line, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("{:04x}  {}\n", line * HEX_LINE, hex.join(" "))
+        })
+        .collect()
+}
+
+/// Escape doc text for a table cell that both CommonMark and MDX accept
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '|' => escaped.push_str("\\|"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '{' => escaped.push_str("&#123;"),
+            '}' => escaped.push_str("&#125;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// `UserAccount` → `user_account`
+fn page_name(name: &str) -> String {
+    let mut result = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            result.push('_');
+        }
+        result.push(ch.to_ascii_lowercase());
+    }
+    result
+}
+
+fn toml_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn yaml_string(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        #[solana]
+        #[account]
+        struct GameAccount {
+            /// Player who owns the game
+            owner: PublicKey,
+            state: GameState,
+            history: [Move],
+        }
+
+        struct Move { x: u8, y: u8 }
+
+        enum GameState { Waiting, Playing(u8), Finished { winner: Option<PublicKey> } }
+    "#;
+
+    fn ir() -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap()
+    }
+
+    fn file<'f>(files: &'f [SiteFile], path: &str) -> &'f str {
+        &files
+            .iter()
+            .find(|f| f.path == path)
+            .unwrap_or_else(|| panic!("{} not generated", path))
+            .content
+    }
+
+    #[test]
+    fn generates_mdbook_with_a_page_per_type() {
+        let files = generate_site(&ir(), "Game \"Docs\"", None, SiteFormat::MdBook);
+
+        assert_eq!(
+            file(&files, "book.toml"),
+            "[book]\ntitle = \"Game \\\"Docs\\\"\"\nsrc = \"src\"\n"
+        );
+        let summary = file(&files, "src/SUMMARY.md");
+        assert!(summary.contains("# Accounts\n\n- [GameAccount](types/game_account.md)\n"));
+        assert!(summary.contains("- [GameState](types/game_state.md)"));
+
+        let game = file(&files, "src/types/game_account.md");
+        assert!(game.starts_with(
+            "# GameAccount\n\n**Kind:** Account  \n**Attributes:** `#[solana]` `#[account]`  \n"
+        ));
+        assert!(game.contains("| `owner` | `PublicKey` | 32 | Player who owns the game |"));
+        assert!(game.contains("| `history` | [`[Move]`](./move.md) | 4+ |"));
+        assert!(game.contains("**Size:** at least 81 bytes, including the 8-byte discriminator"));
+        assert!(game.contains("```json\n{\n  \"owner\": \""));
+        assert!(game.contains("```text\n0000  "));
+        assert!(game.contains("sha256(\"account:GameAccount\")"));
+
+        let state = file(&files, "src/types/game_state.md");
+        assert!(state.contains("| 2 | `Finished` | `winner`: `Option<PublicKey>` |"));
+        assert!(state.contains("- [`GameAccount`](./game_account.md) `state`"));
+
+        let overview = file(&files, "src/README.md");
+        assert!(overview.contains("| [`Move`](./types/move.md) | Struct | 2 | - |"));
+    }
+
+    #[test]
+    fn generates_docusaurus_content_folder() {
+        let files = generate_site(&ir(), "Game's Docs", None, SiteFormat::Docusaurus);
+
+        assert!(file(&files, "index.md")
+            .starts_with("---\ntitle: 'Game''s Docs'\nsidebar_position: 1\n---"));
+        assert!(file(&files, "types/_category_.json").contains("\"label\": \"Types\""));
+        assert!(
+            file(&files, "types/move.md").starts_with("---\ntitle: Move\nsidebar_position: 2\n---")
+        );
+    }
+
+    #[test]
+    fn generates_single_page_with_anchors() {
+        let page = generate_markdown(&ir(), "Game", None);
+
+        assert!(page.starts_with("# Game\n\n| Type |"));
+        assert!(page.contains("\n## Move\n"));
+        assert!(page.contains("\n### Fields\n"));
+        assert!(page.contains("[`GameState`](#gamestate)"));
+    }
+
+    #[test]
+    fn shows_test_vectors_as_examples() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                r#"
+                #[test_vector(x = 1, tags = [2, 3], state = {"Playing": {"0": 4}})]
+                #[test_vector(x = 5, tags = [], state = "Waiting")]
+                struct Board { x: u8, tags: Vec<u8>, state: GameState }
+
+                enum GameState { Waiting, Playing(u8) }
+            "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let page = generate_markdown(&ir, "Game", None);
+        assert!(page.contains(
+            "\n### Examples\n\n```json\n{\n  \"x\": 1,\n  \"tags\": [2, 3],\n  \"state\": {\n    \"Playing\": {\n      \"0\": 4\n    }\n  }\n}\n```\n\nBorsh encoding:\n\n```text\n0000  01 02 00 00 00 02 03 01 04\n```\n"
+        ));
+        assert!(page.contains("\"state\": \"Waiting\"\n}\n```\n\nBorsh encoding:\n\n```text\n0000  05 00 00 00 00 00\n```\n"));
+        // Types without vectors keep their mock example
+        assert!(page.contains("\n### Example\n\n```json\n"));
+    }
+
+    #[test]
+    fn marks_sensitive_fields() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                r#"
+                struct Profile {
+                    /// Contact address
+                    #[sensitive]
+                    email: String,
+                    #[sensitive]
+                    pin: u32,
+                    age: u8,
+                }
+            "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let page = generate_markdown(&ir, "Profiles", None);
+        assert!(page.contains(
+            "| `email` | `String` | 4+ | **Sensitive**, redacted from logs. Contact address |\n\
+             | `pin` | `u32` | 4 | **Sensitive**, redacted from logs. |\n\
+             | `age` | `u8` | 1 |  |\n"
+        ));
+
+        let files = generate_site(&ir, "Profiles", None, SiteFormat::MdBook);
+        assert!(file(&files, "src/types/profile.md")
+            .contains("| `pin` | `u32` | 4 | **Sensitive**, redacted from logs. |"));
+    }
+
+    #[test]
+    fn introduces_overview_with_schema_block() {
+        let schema = SchemaMetadata {
+            name: "game".to_string(),
+            version: Some("1.2.0".to_string()),
+            description: Some("Tic-tac-toe accounts".to_string()),
+            license: Some("MIT".to_string()),
+            authors: vec!["Ada <ada@example.com>".to_string()],
+        };
+        let page = generate_markdown(&ir(), "Game", Some(&schema));
+
+        assert!(page.starts_with(
+            "# Game\n\nTic-tac-toe accounts\n\n**Schema:** `game` 1.2.0 · **License:** MIT · \
+             **Authors:** Ada &lt;ada@example.com&gt;\n\n| Type |"
+        ));
+    }
+
+    #[test]
+    fn escapes_table_cells_and_dumps_hex() {
+        assert_eq!(escape("a | <b> {c}"), "a \\| &lt;b&gt; &#123;c&#125;");
+        assert_eq!(
+            hex_dump(&(0..18).collect::<Vec<u8>>()),
+            "0000  00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f\n0010  10 11\n"
+        );
+    }
+}
@@ -0,0 +1,265 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Anchor IDL generator
+//!
+//! Emits a program IDL in the Anchor 0.30 format, so explorers and frontends
+//! can decode accounts, events and instructions without the generated
+//! TypeScript:
+//!
+//! - `instructions` come from `#[solana]` `#[instruction]` structs: the
+//!   handler is the snake_case struct name, `#[signer]` and `#[account(...)]`
+//!   fields are its accounts, and the remaining fields its arguments
+//! - `accounts` and `events` list the `#[account]` and `#[event]` types with
+//!   their discriminators
+//! - `types` defines those accounts and events and every type they, or
+//!   instruction arguments, refer to
+//!
+//! [`crate::idl::export`] produces the accounts-and-types subset compared
+//! against deployed programs.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::idl;
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "#[solana]\n#[account]\nstruct Vault { balance: u64 }\n\
+//!      #[solana]\n#[instruction]\nstruct Deposit { #[account(mut)] vault: Vault, #[signer] owner: PublicKey, amount: u64 }",
+//! )?)?;
+//!
+//! let document = idl::generate_idl(&ir, "vault", "0.1.0", None);
+//! let deposit = &document["instructions"][0];
+//! assert_eq!(deposit["name"], "deposit");
+//! assert_eq!(deposit["accounts"][0]["writable"], true);
+//! assert_eq!(deposit["args"][0]["type"], "u64");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::idl::{export_type, idl_type};
+use crate::instruction_decoder::{instruction_discriminator, to_snake_case};
+use crate::ir::{FieldDefinition, StructDefinition, TypeDefinition};
+use crate::type_graph::TypeGraph;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+
+/// Version of the IDL specification the generated document follows
+pub const IDL_SPEC: &str = "0.1.0";
+
+/// `#[account(...)]` constraints that make Anchor treat the account as writable
+const WRITABLE_CONSTRAINTS: [&str; 4] = ["mut", "init", "init_if_needed", "zero"];
+
+/// Generate the IDL of program `name` at `version`
+///
+/// `address` is the program ID; the `address` field is left out without one.
+pub fn generate_idl(
+    type_defs: &[TypeDefinition],
+    name: &str,
+    version: &str,
+    address: Option<&str>,
+) -> Value {
+    let solana: Vec<&TypeDefinition> = type_defs.iter().filter(|t| t.is_solana()).collect();
+    let instructions: Vec<&StructDefinition> = solana
+        .iter()
+        .filter_map(|t| match t {
+            TypeDefinition::Struct(s) if s.metadata.is_instruction() => Some(s),
+            _ => None,
+        })
+        .collect();
+
+    // Accounts, events and instructions, then everything they use; the
+    // account fields of instructions only name account types
+    let roots: Vec<&str> = solana
+        .iter()
+        .filter(|t| t.is_account() || t.is_event() || t.is_instruction())
+        .map(|t| t.name())
+        .collect();
+    let graph = TypeGraph::new(type_defs);
+    let mut used: BTreeSet<&str> = roots.iter().copied().collect();
+    for root in &roots {
+        used.extend(
+            graph
+                .dependencies_of(root)
+                .iter()
+                .map(|reached| reached.name),
+        );
+    }
+
+    let mut document = Map::new();
+    if let Some(address) = address {
+        document.insert("address".to_string(), json!(address));
+    }
+    document.insert(
+        "metadata".to_string(),
+        json!({ "name": to_snake_case(name), "version": version, "spec": IDL_SPEC }),
+    );
+    document.insert(
+        "instructions".to_string(),
+        Value::Array(instructions.iter().copied().map(instruction).collect()),
+    );
+    for (key, selected) in [
+        (
+            "accounts",
+            TypeDefinition::is_account as fn(&TypeDefinition) -> bool,
+        ),
+        ("events", TypeDefinition::is_event),
+    ] {
+        let entries: Vec<Value> = solana
+            .iter()
+            .filter(|t| selected(t))
+            .map(|t| json!({ "name": t.name(), "discriminator": t.discriminator() }))
+            .collect();
+        document.insert(key.to_string(), Value::Array(entries));
+    }
+    document.insert(
+        "types".to_string(),
+        Value::Array(
+            type_defs
+                .iter()
+                .filter(|t| used.contains(t.name()) && !t.is_instruction())
+                .map(export_type)
+                .collect(),
+        ),
+    );
+
+    Value::Object(document)
+}
+
+fn instruction(struct_def: &StructDefinition) -> Value {
+    let accounts: Vec<Value> = struct_def
+        .instruction_accounts()
+        .map(|field| {
+            let mut account = Map::new();
+            account.insert("name".to_string(), json!(field.name));
+            if is_writable(field) {
+                account.insert("writable".to_string(), json!(true));
+            }
+            if field.has_attribute("signer") {
+                account.insert("signer".to_string(), json!(true));
+            }
+            Value::Object(account)
+        })
+        .collect();
+    let args: Vec<Value> = struct_def
+        .data_fields()
+        .map(|field| json!({ "name": field.name, "type": idl_type(&field.type_info) }))
+        .collect();
+
+    json!({
+        "name": to_snake_case(&struct_def.name),
+        "discriminator": instruction_discriminator(&struct_def.name),
+        "accounts": accounts,
+        "args": args,
+    })
+}
+
+fn is_writable(field: &FieldDefinition) -> bool {
+    field.attribute_args("account").iter().any(|arg| {
+        let key = arg.split('=').next().unwrap_or("").trim();
+        WRITABLE_CONSTRAINTS.contains(&key)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        #[solana]
+        #[account]
+        struct Vault {
+            owner: PublicKey,
+            limits: Limits,
+        }
+
+        struct Limits {
+            daily: u64,
+        }
+
+        enum Memo {
+            Text(String),
+        }
+
+        struct Unused {
+            value: u8,
+        }
+
+        #[solana]
+        #[event]
+        struct Deposited {
+            amount: u64,
+        }
+
+        #[solana]
+        #[instruction]
+        struct DepositFunds {
+            #[account(mut, seeds = [b"vault"])]
+            vault: Vault,
+            #[signer]
+            owner: PublicKey,
+            #[account]
+            system_program: PublicKey,
+            amount: u64,
+            memo: Option<Memo>,
+        }
+    "#;
+
+    fn generate(address: Option<&str>) -> Value {
+        let ir = transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap();
+        generate_idl(&ir, "VaultProgram", "1.2.0", address)
+    }
+
+    #[test]
+    fn generates_program_idl() {
+        let document = generate(Some("Vau1t11111111111111111111111111111111111111"));
+
+        assert_eq!(
+            document["address"],
+            "Vau1t11111111111111111111111111111111111111"
+        );
+        assert_eq!(
+            document["metadata"],
+            json!({ "name": "vault_program", "version": "1.2.0", "spec": IDL_SPEC })
+        );
+
+        let deposit = &document["instructions"][0];
+        assert_eq!(deposit["name"], "deposit_funds");
+        assert_eq!(
+            deposit["discriminator"],
+            json!(instruction_discriminator("DepositFunds"))
+        );
+        assert_eq!(
+            deposit["accounts"],
+            json!([
+                { "name": "vault", "writable": true },
+                { "name": "owner", "signer": true },
+                { "name": "system_program" },
+            ])
+        );
+        assert_eq!(
+            deposit["args"],
+            json!([
+                { "name": "amount", "type": "u64" },
+                { "name": "memo", "type": { "option": { "defined": { "name": "Memo" } } } },
+            ])
+        );
+
+        assert_eq!(document["accounts"][0]["name"], "Vault");
+        assert_eq!(document["events"][0]["name"], "Deposited");
+        let types: Vec<&str> = document["types"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["Vault", "Limits", "Memo", "Deposited"]);
+    }
+
+    #[test]
+    fn omits_address_without_program_id() {
+        assert!(generate(None).get("address").is_none());
+    }
+}
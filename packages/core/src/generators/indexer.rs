@@ -0,0 +1,205 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Account update decoder for indexers
+//!
+//! Generates a Rust module that turns raw account updates (pubkey, data,
+//! slot), as delivered by a Geyser plugin or an RPC subscription, into a typed
+//! `DecodedAccount` enum over every `#[account]` type in the schema. Accounts
+//! are recognized by their Anchor discriminator, so the module works on data
+//! exactly as stored on-chain.
+//!
+//! The module expects the types generated by [`crate::generators::rust`] to be
+//! importable from `types_module`, including the program `ID` from
+//! `declare_id!`.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::indexer;
+//! use lumos_core::{parser, transform};
+//!
+//! let source = r#"
+//!     #[solana]
+//!     #[account]
+//!     struct Player {
+//!         wallet: PublicKey,
+//!         level: u16,
+//!     }
+//! "#;
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(source)?)?;
+//! let code = indexer::generate_module(&ir, "crate::generated").unwrap();
+//! assert!(code.contains("pub enum DecodedAccount"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::ir::TypeDefinition;
+
+/// Generate the decoder module, or `None` if the schema has no `#[account]` types
+pub fn generate_module(type_defs: &[TypeDefinition], types_module: &str) -> Option<String> {
+    let accounts: Vec<&str> = type_defs
+        .iter()
+        .filter(|t| {
+            let metadata = t.metadata();
+            metadata.solana && metadata.is_account()
+        })
+        .map(TypeDefinition::name)
+        .collect();
+    if accounts.is_empty() {
+        return None;
+    }
+
+    let mut output = String::new();
+    output.push_str("// Auto-generated by LUMOS\n");
+    output.push_str("// DO NOT EDIT - Changes will be overwritten\n\n");
+    output.push_str("//! Decoder for raw account updates, for Geyser plugins and indexers\n\n");
+    output.push_str("use anchor_lang::prelude::*;\n");
+    output.push_str("use anchor_lang::Discriminator;\n");
+    output.push_str(&format!("use {}::*;\n\n", types_module));
+
+    // Decoded account enum
+    // Anchor's #[account] derives Clone but not Debug
+    output.push_str("/// A schema account decoded from raw account data\n");
+    output.push_str("#[derive(Clone)]\n");
+    output.push_str("pub enum DecodedAccount {\n");
+    for name in &accounts {
+        output.push_str(&format!("    {}({}),\n", name, name));
+    }
+    output.push_str("}\n\n");
+
+    output.push_str("impl DecodedAccount {\n");
+    output.push_str("    /// Name of the account type\n");
+    output.push_str("    pub fn type_name(&self) -> &'static str {\n");
+    output.push_str("        match self {\n");
+    for name in &accounts {
+        output.push_str(&format!(
+            "            DecodedAccount::{}(_) => \"{}\",\n",
+            name, name
+        ));
+    }
+    output.push_str("        }\n");
+    output.push_str("    }\n");
+    output.push_str("}\n\n");
+
+    // Update envelope
+    output.push_str("/// An account update with its decoded contents\n");
+    output.push_str("#[derive(Clone)]\n");
+    output.push_str("pub struct AccountUpdate {\n");
+    output.push_str("    pub pubkey: Pubkey,\n");
+    output.push_str("    pub slot: u64,\n");
+    output.push_str("    pub account: DecodedAccount,\n");
+    output.push_str("}\n\n");
+
+    // Errors
+    output.push_str("/// Why account data could not be decoded\n");
+    output.push_str("#[derive(Debug)]\n");
+    output.push_str("pub enum DecodeError {\n");
+    output.push_str("    /// Data does not start with the discriminator of a schema account\n");
+    output.push_str("    UnknownDiscriminator,\n");
+    output.push_str("    /// The discriminator matched, but the rest is not valid for the type\n");
+    output.push_str("    InvalidData(&'static str, std::io::Error),\n");
+    output.push_str("}\n\n");
+
+    output.push_str("impl std::fmt::Display for DecodeError {\n");
+    output.push_str("    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n");
+    output.push_str("        match self {\n");
+    output.push_str(
+        "            DecodeError::UnknownDiscriminator => write!(f, \"unknown account discriminator\"),\n",
+    );
+    output.push_str(
+        "            DecodeError::InvalidData(name, e) => write!(f, \"invalid {} data: {}\", name, e),\n",
+    );
+    output.push_str("        }\n");
+    output.push_str("    }\n");
+    output.push_str("}\n\n");
+    output.push_str("impl std::error::Error for DecodeError {}\n\n");
+
+    // Entry points
+    output.push_str(
+        "/// Whether an account belongs to this program; filter updates with it before decoding\n",
+    );
+    output.push_str("pub fn is_program_account(owner: &Pubkey) -> bool {\n");
+    output.push_str("    *owner == ID\n");
+    output.push_str("}\n\n");
+
+    output.push_str("/// Decode raw account data, discriminator included\n");
+    output.push_str(
+        "pub fn decode_account(data: &[u8]) -> std::result::Result<DecodedAccount, DecodeError> {\n",
+    );
+    for name in &accounts {
+        output.push_str(&format!(
+            "    if let Some(mut body) = data.strip_prefix({}::DISCRIMINATOR.as_ref()) {{\n",
+            name
+        ));
+        output.push_str(&format!(
+            "        return {}::deserialize(&mut body)\n",
+            name
+        ));
+        output.push_str(&format!("            .map(DecodedAccount::{})\n", name));
+        output.push_str(&format!(
+            "            .map_err(|e| DecodeError::InvalidData(\"{}\", e));\n",
+            name
+        ));
+        output.push_str("    }\n");
+    }
+    output.push_str("    Err(DecodeError::UnknownDiscriminator)\n");
+    output.push_str("}\n\n");
+
+    output.push_str("/// Decode a raw account update\n");
+    output.push_str("pub fn decode_update(\n");
+    output.push_str("    pubkey: Pubkey,\n");
+    output.push_str("    data: &[u8],\n");
+    output.push_str("    slot: u64,\n");
+    output.push_str(") -> std::result::Result<AccountUpdate, DecodeError> {\n");
+    output.push_str("    Ok(AccountUpdate {\n");
+    output.push_str("        pubkey,\n");
+    output.push_str("        slot,\n");
+    output.push_str("        account: decode_account(data)?,\n");
+    output.push_str("    })\n");
+    output.push_str("}\n");
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn ir(source: &str) -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn decodes_every_account_type() {
+        let type_defs = ir(r#"
+            #[solana]
+            #[account]
+            struct Player { wallet: PublicKey }
+
+            #[solana]
+            struct Stats { wins: u32 }
+
+            #[solana]
+            #[account]
+            struct Match { players: [PublicKey], stats: Stats }
+        "#);
+
+        let code = generate_module(&type_defs, "crate::state").unwrap();
+        assert!(code.contains("use crate::state::*;"));
+        assert!(code.contains("    Player(Player),\n    Match(Match),\n}"));
+        assert!(!code.contains("Stats(Stats)"));
+        assert!(code.contains("data.strip_prefix(Match::DISCRIMINATOR.as_ref())"));
+        assert!(code.contains(".map_err(|e| DecodeError::InvalidData(\"Player\", e));"));
+        assert!(code.contains("pub fn decode_update("));
+        assert!(code.contains("#[derive(Clone)]\npub enum DecodedAccount {"));
+    }
+
+    #[test]
+    fn requires_account_types() {
+        let type_defs = ir("#[solana] struct Stats { wins: u32 }");
+        assert!(generate_module(&type_defs, "crate::generated").is_none());
+    }
+}
@@ -0,0 +1,495 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! JSON Schema (draft 2020-12) documents
+//!
+//! For off-chain services that accept payloads shaped like on-chain
+//! accounts and want to validate them with any JSON Schema validator.
+//! [`generate_document`] bundles every type under `$defs`, so a payload is
+//! checked against e.g. `generated.schema.json#/$defs/Vault`;
+//! [`generate_type_document`] makes a standalone document for one type.
+//!
+//! Values have the JSON shape of the generated TypeScript types, of
+//! `lumos mock` and of [`super::openapi`]: enums are `{ kind, ... }` objects
+//! with tuple fields named `field0`, `field1`, and so on, 128-bit integers
+//! are decimal strings, public keys and signatures are base58 strings, and
+//! byte arrays (`[u8]`) are base64 or base58 strings. Optional fields may be
+//! `null` or absent.
+//!
+//! Unlike OpenAPI there are no custom `format`s, which strict validators
+//! reject; integer ranges, base58 alphabets and decimal strings are spelled
+//! out with `minimum`, `maximum` and `pattern` instead. `#[min]`, `#[max]`
+//! and `#[range]` bounds carry over, though string lengths are counted in
+//! characters rather than the UTF-8 bytes `validate()` counts.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::json_schema;
+//! use lumos_core::generators::openapi::ByteEncoding;
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "struct Vault { owner: PublicKey, #[range(1, 100)] fee_bps: u16 }",
+//! )?)?;
+//!
+//! let document = json_schema::generate_document(&ir, "Vault", ByteEncoding::Base64);
+//! assert_eq!(document["$schema"], json_schema::DIALECT);
+//! assert_eq!(document["$defs"]["Vault"]["properties"]["fee_bps"]["maximum"], 100);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use super::openapi::ByteEncoding;
+use crate::ir::{Constraints, EnumDefinition, EnumVariantDefinition, FieldDefinition};
+use crate::ir::{StructDefinition, TypeDefinition, TypeInfo};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+
+/// `$schema` of the generated documents
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Characters of a base58 string, as used for Solana keys
+const BASE58_PATTERN: &str = "^[1-9A-HJ-NP-Za-km-z]+$";
+
+/// Generate a document with every type under `$defs`
+pub fn generate_document(type_defs: &[TypeDefinition], title: &str, bytes: ByteEncoding) -> Value {
+    json!({
+        "$schema": DIALECT,
+        "title": title,
+        "$defs": definitions(type_defs, |_| true, bytes),
+    })
+}
+
+/// Generate a standalone document validating values of the type `name`
+///
+/// The types it refers to, directly or through other types, are included
+/// under `$defs`. Returns `None` if there is no such type.
+pub fn generate_type_document(
+    type_defs: &[TypeDefinition],
+    name: &str,
+    bytes: ByteEncoding,
+) -> Option<Value> {
+    let type_def = type_defs.iter().find(|type_def| type_def.name() == name)?;
+
+    let mut referenced = BTreeSet::new();
+    let mut pending = vec![type_def];
+    while let Some(current) = pending.pop() {
+        for used in used_types(current) {
+            if used != name && referenced.insert(used.clone()) {
+                pending.extend(type_defs.iter().find(|type_def| type_def.name() == used));
+            }
+        }
+    }
+
+    let Value::Object(schema) = type_schema_of(type_def, bytes) else {
+        unreachable!("type schemas are objects");
+    };
+    let mut document = Map::new();
+    document.insert("$schema".to_string(), json!(DIALECT));
+    document.insert("title".to_string(), json!(name));
+    document.extend(schema);
+    if !referenced.is_empty() {
+        document.insert(
+            "$defs".to_string(),
+            definitions(type_defs, |name| referenced.contains(name), bytes),
+        );
+    }
+    Some(Value::Object(document))
+}
+
+/// The `$defs` object of the types `include` selects, keyed by type name
+fn definitions(
+    type_defs: &[TypeDefinition],
+    include: impl Fn(&str) -> bool,
+    bytes: ByteEncoding,
+) -> Value {
+    let defs: Map<String, Value> = type_defs
+        .iter()
+        .filter(|type_def| include(type_def.name()))
+        .map(|type_def| (type_def.name().to_string(), type_schema_of(type_def, bytes)))
+        .collect();
+    Value::Object(defs)
+}
+
+fn type_schema_of(type_def: &TypeDefinition, bytes: ByteEncoding) -> Value {
+    let mut schema = match type_def {
+        TypeDefinition::Struct(s) => struct_schema(s, bytes),
+        TypeDefinition::Enum(e) => enum_schema(e, bytes),
+    };
+    let docs = &type_def.metadata().docs;
+    if !docs.is_empty() {
+        schema["description"] = json!(docs.join(" "));
+    }
+    schema
+}
+
+/// Names of the types `type_def`'s fields refer to
+fn used_types(type_def: &TypeDefinition) -> Vec<String> {
+    fn collect(type_info: &TypeInfo, names: &mut Vec<String>) {
+        match type_info {
+            TypeInfo::Primitive(_) => {}
+            TypeInfo::UserDefined(name) => names.push(name.clone()),
+            TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) | TypeInfo::Option(inner) => {
+                collect(inner, names)
+            }
+            TypeInfo::Map(_, key, value) => {
+                collect(key, names);
+                collect(value, names);
+            }
+        }
+    }
+
+    let mut names = Vec::new();
+    match type_def {
+        TypeDefinition::Struct(s) => {
+            for field in &s.fields {
+                collect(&field.type_info, &mut names);
+            }
+        }
+        TypeDefinition::Enum(e) => {
+            for variant in &e.variants {
+                match variant {
+                    EnumVariantDefinition::Unit { .. } => {}
+                    EnumVariantDefinition::Tuple { types, .. } => {
+                        for type_info in types {
+                            collect(type_info, &mut names);
+                        }
+                    }
+                    EnumVariantDefinition::Struct { fields, .. } => {
+                        for field in fields {
+                            collect(&field.type_info, &mut names);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    names
+}
+
+fn struct_schema(struct_def: &StructDefinition, bytes: ByteEncoding) -> Value {
+    let mut schema = object_schema(&struct_def.fields, bytes);
+    if struct_def.metadata.is_account() {
+        schema.insert(
+            "description".to_string(),
+            json!(format!(
+                "`{}` account data, without the 8-byte Anchor discriminator",
+                struct_def.name
+            )),
+        );
+    }
+    Value::Object(schema)
+}
+
+/// `{ kind, ... }` object per variant
+fn enum_schema(enum_def: &EnumDefinition, bytes: ByteEncoding) -> Value {
+    let variants: Vec<Value> = enum_def
+        .variants
+        .iter()
+        .map(|variant| {
+            let mut schema = match variant {
+                EnumVariantDefinition::Unit { .. } => object_schema(&[], bytes),
+                EnumVariantDefinition::Tuple { types, .. } => {
+                    let fields: Vec<FieldDefinition> = types
+                        .iter()
+                        .enumerate()
+                        .map(|(index, type_info)| FieldDefinition {
+                            name: format!("field{}", index),
+                            type_info: type_info.clone(),
+                            optional: false,
+                            attributes: Vec::new(),
+                        })
+                        .collect();
+                    object_schema(&fields, bytes)
+                }
+                EnumVariantDefinition::Struct { fields, .. } => object_schema(fields, bytes),
+            };
+
+            let mut properties = Map::new();
+            properties.insert("kind".to_string(), json!({ "const": variant.name() }));
+            if let Some(Value::Object(fields)) = schema.remove("properties") {
+                properties.extend(fields);
+            }
+            let mut required = vec![json!("kind")];
+            if let Some(Value::Array(fields)) = schema.remove("required") {
+                required.extend(fields);
+            }
+
+            json!({
+                "type": "object",
+                "title": variant.name(),
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false,
+            })
+        })
+        .collect();
+
+    json!({ "oneOf": variants })
+}
+
+fn object_schema(fields: &[FieldDefinition], bytes: ByteEncoding) -> Map<String, Value> {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in fields {
+        let mut schema = field_schema(field, bytes);
+        if let Some(doc) = field.doc_comment() {
+            schema["description"] = json!(doc);
+        }
+        if !matches!(field.type_info, TypeInfo::Option(_)) {
+            required.push(json!(field.name));
+        }
+        properties.insert(field.name.clone(), schema);
+    }
+
+    let mut schema = Map::new();
+    schema.insert("type".to_string(), json!("object"));
+    schema.insert("properties".to_string(), Value::Object(properties));
+    schema.insert("required".to_string(), Value::Array(required));
+    schema.insert("additionalProperties".to_string(), json!(false));
+    schema
+}
+
+/// Schema of a field's values, with its `#[min]`, `#[max]` and `#[range]` bounds
+fn field_schema(field: &FieldDefinition, bytes: ByteEncoding) -> Value {
+    let constraints = field.constraints();
+    match &field.type_info {
+        TypeInfo::Option(inner) => {
+            json!({ "oneOf": [bounded(inner, &constraints, bytes), { "type": "null" }] })
+        }
+        type_info => bounded(type_info, &constraints, bytes),
+    }
+}
+
+fn bounded(type_info: &TypeInfo, constraints: &Constraints, bytes: ByteEncoding) -> Value {
+    let mut schema = type_schema(type_info, bytes);
+    let (min_key, max_key) = match schema["type"].as_str() {
+        Some("string") if matches!(type_info, TypeInfo::Primitive(t) if t == "String") => {
+            ("minLength", "maxLength")
+        }
+        Some("array") => ("minItems", "maxItems"),
+        _ => ("", ""),
+    };
+    if !min_key.is_empty() {
+        if let Some(min) = constraints.min_len {
+            schema[min_key] = json!(min);
+        }
+        if let Some(max) = constraints.max_len {
+            schema[max_key] = json!(max);
+        }
+    }
+    // 128-bit integers are strings, which have no range
+    if let (Some((min, max)), Some("integer")) = (constraints.range, schema["type"].as_str()) {
+        schema["minimum"] = integer_value(min);
+        schema["maximum"] = integer_value(max);
+    }
+    schema
+}
+
+/// JSON Schema of a value of `type_info`
+fn type_schema(type_info: &TypeInfo, bytes: ByteEncoding) -> Value {
+    match type_info {
+        TypeInfo::Primitive(name) => primitive_schema(name),
+        TypeInfo::Array(inner) if is_byte(inner) => byte_string(bytes, None),
+        TypeInfo::FixedArray(inner, len) if is_byte(inner) => byte_string(bytes, Some(*len)),
+        TypeInfo::Array(inner) => json!({ "type": "array", "items": type_schema(inner, bytes) }),
+        TypeInfo::FixedArray(inner, len) => json!({
+            "type": "array",
+            "items": type_schema(inner, bytes),
+            "minItems": len,
+            "maxItems": len,
+        }),
+        // JSON object keys are strings: decimal integers, `true`/`false` or base58 keys
+        TypeInfo::Map(_, key, value) => {
+            let mut schema = json!({
+                "type": "object",
+                "additionalProperties": type_schema(value, bytes),
+            });
+            if let Some(pattern) = key_pattern(key) {
+                schema["propertyNames"] = json!({ "pattern": pattern });
+            }
+            schema
+        }
+        TypeInfo::Option(inner) => {
+            json!({ "oneOf": [type_schema(inner, bytes), { "type": "null" }] })
+        }
+        TypeInfo::UserDefined(name) => json!({ "$ref": format!("#/$defs/{}", name) }),
+    }
+}
+
+fn is_byte(type_info: &TypeInfo) -> bool {
+    matches!(type_info, TypeInfo::Primitive(t) if t == "u8")
+}
+
+/// Bytes as a string, exactly as long as `len` bytes encode to when the length is fixed
+fn byte_string(bytes: ByteEncoding, len: Option<usize>) -> Value {
+    match bytes {
+        ByteEncoding::Base64 => {
+            let mut schema = json!({ "type": "string", "contentEncoding": "base64" });
+            if let Some(len) = len {
+                let encoded = len.div_ceil(3) * 4;
+                schema["minLength"] = json!(encoded);
+                schema["maxLength"] = json!(encoded);
+            }
+            schema
+        }
+        ByteEncoding::Base58 => json!({ "type": "string", "pattern": BASE58_PATTERN }),
+    }
+}
+
+/// Pattern map keys of `key` type have as JSON object keys
+fn key_pattern(key: &TypeInfo) -> Option<&'static str> {
+    match key {
+        TypeInfo::Primitive(name) => match name.as_str() {
+            "u8" | "u16" | "u32" | "u64" | "u128" => Some("^[0-9]+$"),
+            "i8" | "i16" | "i32" | "i64" | "i128" => Some("^-?[0-9]+$"),
+            "bool" => Some("^(true|false)$"),
+            "Pubkey" | "PublicKey" | "Signature" => Some(BASE58_PATTERN),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn primitive_schema(name: &str) -> Value {
+    match name {
+        "bool" => json!({ "type": "boolean" }),
+        "u8" => integer(u8::MIN.into(), u8::MAX.into()),
+        "i8" => integer(i8::MIN.into(), i8::MAX.into()),
+        "u16" => integer(u16::MIN.into(), u16::MAX.into()),
+        "i16" => integer(i16::MIN.into(), i16::MAX.into()),
+        "u32" => integer(u32::MIN.into(), u32::MAX.into()),
+        "i32" => integer(i32::MIN.into(), i32::MAX.into()),
+        "u64" => integer(u64::MIN.into(), u64::MAX.into()),
+        "i64" => integer(i64::MIN.into(), i64::MAX.into()),
+        "u128" => json!({ "type": "string", "pattern": "^[0-9]+$" }),
+        "i128" => json!({ "type": "string", "pattern": "^-?[0-9]+$" }),
+        "f32" | "f64" => json!({ "type": "number" }),
+        "String" => json!({ "type": "string" }),
+        "Pubkey" | "PublicKey" => json!({
+            "type": "string",
+            "pattern": BASE58_PATTERN,
+            "minLength": 32,
+            "maxLength": 44,
+            "description": "Solana public key, base58",
+        }),
+        "Signature" => json!({
+            "type": "string",
+            "pattern": BASE58_PATTERN,
+            "minLength": 64,
+            "maxLength": 88,
+            "description": "Ed25519 signature, base58",
+        }),
+        // Keypairs and unknown primitives have no portable JSON form
+        _ => json!({ "description": format!("`{}` value", name) }),
+    }
+}
+
+fn integer(minimum: Value, maximum: Value) -> Value {
+    json!({ "type": "integer", "minimum": minimum, "maximum": maximum })
+}
+
+/// `value` as a JSON number; `#[range]` bounds fit the field's integer type
+fn integer_value(value: i128) -> Value {
+    i64::try_from(value)
+        .map(Value::from)
+        .or_else(|_| u64::try_from(value).map(Value::from))
+        .unwrap_or_else(|_| json!(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        /// A user's vault
+        #[solana]
+        #[account]
+        struct Vault {
+            /// Owner of the vault
+            owner: PublicKey,
+            #[range(1, 10000)]
+            fee_bps: u16,
+            total: u128,
+            seed: [u8; 32],
+            #[max(3)]
+            history: [Event],
+            #[max(32)]
+            nickname: Option<String>,
+            limits: HashMap<PublicKey, u64>,
+        }
+
+        enum Event { Opened, Deposit(u64), Closed { reason: Option<String> } }
+
+        struct Unrelated { x: u8 }
+    "#;
+
+    fn ir() -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn maps_struct_fields_with_bounds() {
+        let document = generate_document(&ir(), "Vaults", ByteEncoding::Base64);
+        assert_eq!(document["$schema"], DIALECT);
+        assert_eq!(document["title"], "Vaults");
+
+        let vault = &document["$defs"]["Vault"];
+        let properties = &vault["properties"];
+        assert_eq!(vault["description"], "A user's vault");
+        assert_eq!(
+            vault["required"],
+            json!(["owner", "fee_bps", "total", "seed", "history", "limits"])
+        );
+        assert_eq!(vault["additionalProperties"], false);
+        assert_eq!(properties["owner"]["pattern"], BASE58_PATTERN);
+        assert_eq!(properties["owner"]["description"], "Owner of the vault");
+        assert_eq!(
+            properties["fee_bps"],
+            json!({ "type": "integer", "minimum": 1, "maximum": 10000 })
+        );
+        assert_eq!(properties["total"]["pattern"], "^[0-9]+$");
+        assert_eq!(properties["seed"]["maxLength"], 44);
+        assert_eq!(properties["history"]["maxItems"], 3);
+        assert_eq!(properties["history"]["items"]["$ref"], "#/$defs/Event");
+        assert_eq!(properties["nickname"]["oneOf"][0]["maxLength"], 32);
+        assert_eq!(properties["nickname"]["oneOf"][1]["type"], "null");
+        assert_eq!(
+            properties["limits"]["propertyNames"]["pattern"],
+            BASE58_PATTERN
+        );
+
+        // No OpenAPI-only formats
+        assert!(!document.to_string().contains("\"format\""));
+    }
+
+    #[test]
+    fn maps_enums_to_kind_objects() {
+        let document = generate_document(&ir(), "Vaults", ByteEncoding::Base58);
+        let variants = document["$defs"]["Event"]["oneOf"].as_array().unwrap();
+
+        assert_eq!(variants.len(), 3);
+        assert_eq!(variants[0]["properties"]["kind"]["const"], "Opened");
+        assert_eq!(variants[0]["required"], json!(["kind"]));
+        assert_eq!(variants[1]["required"], json!(["kind", "field0"]));
+        assert_eq!(variants[1]["properties"]["field0"]["type"], "integer");
+        assert_eq!(variants[2]["required"], json!(["kind"]));
+        assert_eq!(variants[2]["title"], "Closed");
+    }
+
+    #[test]
+    fn type_documents_include_only_referenced_types() {
+        let vault = generate_type_document(&ir(), "Vault", ByteEncoding::Base64).unwrap();
+        assert_eq!(vault["$schema"], DIALECT);
+        assert_eq!(vault["title"], "Vault");
+        assert_eq!(vault["type"], "object");
+        let defs: Vec<&String> = vault["$defs"].as_object().unwrap().keys().collect();
+        assert_eq!(defs, ["Event"]);
+
+        let unrelated = generate_type_document(&ir(), "Unrelated", ByteEncoding::Base64).unwrap();
+        assert!(unrelated.get("$defs").is_none());
+        assert!(generate_type_document(&ir(), "Missing", ByteEncoding::Base64).is_none());
+    }
+}
@@ -0,0 +1,313 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! OpenAPI component schemas
+//!
+//! Emits an OpenAPI 3.1 document whose `components.schemas` has one entry per
+//! type, for services that mirror account data over REST. Values have the
+//! JSON shape of the generated TypeScript types and of `lumos mock`: enums
+//! are `{ kind, ... }` objects with tuple fields named `field0`, `field1`,
+//! and so on, and optional fields may be `null` or absent.
+//!
+//! Integers carry their range and a `format` such as `uint16`; 128-bit
+//! integers are decimal strings, since JSON numbers can't hold them exactly.
+//! Public keys and signatures are base58 strings, and byte arrays (`[u8]`)
+//! are base64 or base58 strings, per [`ByteEncoding`].
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::openapi::{self, ByteEncoding};
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "struct Vault { owner: PublicKey, amount: u64, memo: [u8] }",
+//! )?)?;
+//!
+//! let document = openapi::generate_document(&ir, "Vault API", "1.0.0", ByteEncoding::Base64);
+//! let vault = &document["components"]["schemas"]["Vault"];
+//! assert_eq!(vault["properties"]["memo"]["contentEncoding"], "base64");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::ir::{EnumDefinition, EnumVariantDefinition, FieldDefinition, StructDefinition};
+use crate::ir::{TypeDefinition, TypeInfo};
+use serde_json::{json, Map, Value};
+
+/// OpenAPI version of the generated document
+pub const OPENAPI_VERSION: &str = "3.1.0";
+
+/// String encoding of byte arrays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ByteEncoding {
+    /// Base64, OpenAPI's `byte` format
+    #[default]
+    Base64,
+
+    /// Base58, as used for Solana keys
+    Base58,
+}
+
+/// Generate an OpenAPI document with a component schema per type
+pub fn generate_document(
+    type_defs: &[TypeDefinition],
+    title: &str,
+    version: &str,
+    bytes: ByteEncoding,
+) -> Value {
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": { "title": title, "version": version },
+        "components": { "schemas": generate_schemas(type_defs, bytes) },
+    })
+}
+
+/// Generate the `components.schemas` object, keyed by type name
+pub fn generate_schemas(type_defs: &[TypeDefinition], bytes: ByteEncoding) -> Value {
+    let schemas: Map<String, Value> = type_defs
+        .iter()
+        .map(|type_def| {
+            let schema = match type_def {
+                TypeDefinition::Struct(s) => struct_schema(s, bytes),
+                TypeDefinition::Enum(e) => enum_schema(e, bytes),
+            };
+            (type_def.name().to_string(), schema)
+        })
+        .collect();
+    Value::Object(schemas)
+}
+
+fn struct_schema(struct_def: &StructDefinition, bytes: ByteEncoding) -> Value {
+    let mut schema = object_schema(&struct_def.fields, bytes);
+    if struct_def.metadata.is_account() {
+        schema.insert(
+            "description".to_string(),
+            json!(format!(
+                "`{}` account data, without the 8-byte Anchor discriminator",
+                struct_def.name
+            )),
+        );
+    }
+    Value::Object(schema)
+}
+
+/// `{ kind, ... }` object per variant
+fn enum_schema(enum_def: &EnumDefinition, bytes: ByteEncoding) -> Value {
+    let variants: Vec<Value> = enum_def
+        .variants
+        .iter()
+        .map(|variant| {
+            let mut properties = Map::new();
+            properties.insert("kind".to_string(), json!({ "const": variant.name() }));
+            let mut required = vec![json!("kind")];
+
+            let fields: Vec<(String, &TypeInfo)> = match variant {
+                EnumVariantDefinition::Unit { .. } => Vec::new(),
+                EnumVariantDefinition::Tuple { types, .. } => types
+                    .iter()
+                    .enumerate()
+                    .map(|(index, type_info)| (format!("field{}", index), type_info))
+                    .collect(),
+                EnumVariantDefinition::Struct { fields, .. } => fields
+                    .iter()
+                    .map(|field| (field.name.clone(), &field.type_info))
+                    .collect(),
+            };
+            for (name, type_info) in fields {
+                if !matches!(type_info, TypeInfo::Option(_)) {
+                    required.push(json!(name));
+                }
+                properties.insert(name, type_schema(type_info, bytes));
+            }
+
+            json!({
+                "type": "object",
+                "title": variant.name(),
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false,
+            })
+        })
+        .collect();
+
+    json!({ "oneOf": variants })
+}
+
+fn object_schema(fields: &[FieldDefinition], bytes: ByteEncoding) -> Map<String, Value> {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in fields {
+        let mut schema = type_schema(&field.type_info, bytes);
+        if let (Some(doc), Value::Object(map)) = (field.doc_comment(), &mut schema) {
+            map.insert("description".to_string(), json!(doc));
+        }
+        if !matches!(field.type_info, TypeInfo::Option(_)) {
+            required.push(json!(field.name));
+        }
+        properties.insert(field.name.clone(), schema);
+    }
+
+    let mut schema = Map::new();
+    schema.insert("type".to_string(), json!("object"));
+    schema.insert("properties".to_string(), Value::Object(properties));
+    schema.insert("required".to_string(), Value::Array(required));
+    schema.insert("additionalProperties".to_string(), json!(false));
+    schema
+}
+
+/// JSON Schema of a value of `type_info`
+fn type_schema(type_info: &TypeInfo, bytes: ByteEncoding) -> Value {
+    match type_info {
+        TypeInfo::Primitive(name) => primitive_schema(name),
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) if matches!(&**inner, TypeInfo::Primitive(t) if t == "u8") => {
+            match bytes {
+                ByteEncoding::Base64 => json!({
+                    "type": "string",
+                    "format": "byte",
+                    "contentEncoding": "base64",
+                }),
+                ByteEncoding::Base58 => json!({ "type": "string", "format": "base58" }),
+            }
+        }
+        TypeInfo::Array(inner) => json!({ "type": "array", "items": type_schema(inner, bytes) }),
+        TypeInfo::FixedArray(inner, len) => json!({
+            "type": "array",
+            "items": type_schema(inner, bytes),
+            "minItems": len,
+            "maxItems": len,
+        }),
+        // JSON object keys are strings: decimal integers, `true`/`false` or base58 keys
+        TypeInfo::Map(_, _, value) => json!({
+            "type": "object",
+            "additionalProperties": type_schema(value, bytes),
+        }),
+        TypeInfo::Option(inner) => {
+            json!({ "oneOf": [type_schema(inner, bytes), { "type": "null" }] })
+        }
+        TypeInfo::UserDefined(name) => json!({ "$ref": format!("#/components/schemas/{}", name) }),
+    }
+}
+
+fn primitive_schema(name: &str) -> Value {
+    match name {
+        "bool" => json!({ "type": "boolean" }),
+        "u8" => integer("uint8", u8::MIN.into(), u8::MAX.into()),
+        "i8" => integer("int8", i8::MIN.into(), i8::MAX.into()),
+        "u16" => integer("uint16", u16::MIN.into(), u16::MAX.into()),
+        "i16" => integer("int16", i16::MIN.into(), i16::MAX.into()),
+        "u32" => integer("uint32", u32::MIN.into(), u32::MAX.into()),
+        "i32" => integer("int32", i32::MIN.into(), i32::MAX.into()),
+        "u64" => integer("uint64", u64::MIN.into(), u64::MAX.into()),
+        "i64" => integer("int64", i64::MIN.into(), i64::MAX.into()),
+        "u128" => json!({ "type": "string", "format": "uint128", "pattern": "^[0-9]+$" }),
+        "i128" => json!({ "type": "string", "format": "int128", "pattern": "^-?[0-9]+$" }),
+        "f32" => json!({ "type": "number", "format": "float" }),
+        "f64" => json!({ "type": "number", "format": "double" }),
+        "String" => json!({ "type": "string" }),
+        "Pubkey" | "PublicKey" => json!({
+            "type": "string",
+            "format": "base58",
+            "minLength": 32,
+            "maxLength": 44,
+            "description": "Solana public key",
+        }),
+        "Signature" => json!({
+            "type": "string",
+            "format": "base58",
+            "minLength": 64,
+            "maxLength": 88,
+            "description": "Ed25519 signature",
+        }),
+        // Keypairs and unknown primitives have no portable JSON form
+        _ => json!({ "description": format!("`{}` value", name) }),
+    }
+}
+
+fn integer(format: &str, minimum: Value, maximum: Value) -> Value {
+    json!({
+        "type": "integer",
+        "format": format,
+        "minimum": minimum,
+        "maximum": maximum,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        #[solana]
+        #[account]
+        struct Vault {
+            /// Owner of the vault
+            owner: PublicKey,
+            balance: u64,
+            total: u128,
+            memo: [u8],
+            history: [Event],
+            nickname: Option<String>,
+        }
+
+        enum Event { Opened, Deposit(u64), Closed { reason: Option<String> } }
+    "#;
+
+    fn schemas(bytes: ByteEncoding) -> Value {
+        generate_schemas(
+            &transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap(),
+            bytes,
+        )
+    }
+
+    #[test]
+    fn maps_struct_fields() {
+        let vault = &schemas(ByteEncoding::Base64)["Vault"];
+
+        assert_eq!(vault["type"], "object");
+        assert_eq!(
+            vault["required"],
+            json!(["owner", "balance", "total", "memo", "history"])
+        );
+        assert_eq!(vault["properties"]["owner"]["format"], "base58");
+        assert_eq!(
+            vault["properties"]["owner"]["description"],
+            "Owner of the vault"
+        );
+        assert_eq!(vault["properties"]["balance"]["maximum"], json!(u64::MAX));
+        assert_eq!(vault["properties"]["total"]["type"], "string");
+        assert_eq!(vault["properties"]["memo"]["format"], "byte");
+        assert_eq!(
+            vault["properties"]["history"]["items"]["$ref"],
+            "#/components/schemas/Event"
+        );
+        assert_eq!(vault["properties"]["nickname"]["oneOf"][1]["type"], "null");
+        assert!(vault["description"]
+            .as_str()
+            .unwrap()
+            .contains("without the 8-byte Anchor discriminator"));
+    }
+
+    #[test]
+    fn maps_enums_to_kind_objects() {
+        let event = &schemas(ByteEncoding::Base64)["Event"];
+        let variants = event["oneOf"].as_array().unwrap();
+
+        assert_eq!(variants.len(), 3);
+        assert_eq!(variants[0]["properties"]["kind"]["const"], "Opened");
+        assert_eq!(variants[1]["required"], json!(["kind", "field0"]));
+        assert_eq!(variants[1]["properties"]["field0"]["format"], "uint64");
+        assert_eq!(variants[2]["required"], json!(["kind"]));
+    }
+
+    #[test]
+    fn encodes_bytes_as_requested() {
+        let memo = &schemas(ByteEncoding::Base58)["Vault"]["properties"]["memo"];
+        assert_eq!(memo, &json!({ "type": "string", "format": "base58" }));
+
+        let document = generate_document(&[], "API", "0.1.0", ByteEncoding::Base64);
+        assert_eq!(document["openapi"], OPENAPI_VERSION);
+        assert_eq!(document["info"]["title"], "API");
+    }
+}
@@ -0,0 +1,367 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Python dataclasses and `borsh-construct` layouts
+//!
+//! For Python services that read on-chain accounts, e.g. analytics
+//! pipelines. Every struct becomes a `@dataclass` and every enum a set of
+//! variant dataclasses with a `Union` alias; each type also gets a
+//! `<Name>Layout` that parses bytes straight into those classes and builds
+//! bytes from them:
+//!
+//! ```python
+//! player = Player.decode(data)
+//! assert player.encode() == data
+//! ```
+//!
+//! | LUMOS | Python | Layout |
+//! |-------|--------|--------|
+//! | integers | `int` | `U8` ... `I128` |
+//! | `f32`, `f64` | `float` | `F32`, `F64` |
+//! | `bool` | `bool` | `Bool` |
+//! | `String`, `Signature` | `str` | `String` |
+//! | `PublicKey` | `bytes` | `construct.Bytes(32)` |
+//! | `[T]` | `list[T]` | `Vec(T)` |
+//! | `[T; N]` | `list[T]` | `construct.Array(N, T)` |
+//! | `HashMap<K, V>`, `BTreeMap<K, V>` | `dict[K, V]` | `HashMap(K, V)` |
+//! | `Option<T>` | `Optional[T]` | `Option(T)` |
+//!
+//! Tuple variant fields are named `field_0`, `field_1`, ..., and names that
+//! are Python keywords get a trailing underscore. `#[account]` structs also
+//! get `decode_account`, which skips the 8-byte Anchor discriminator.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::python;
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "struct Deposit { owner: PublicKey, amount: u64 }",
+//! )?)?;
+//!
+//! let code = python::generate_module(&ir);
+//! assert!(code.contains("class Deposit:"));
+//! assert!(code.contains("    \"amount\" / U64,"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::ir::{
+    EnumDefinition, EnumVariantDefinition, FieldDefinition, StructDefinition, TypeDefinition,
+    TypeInfo,
+};
+
+/// Size of the Anchor discriminator in front of account data
+const DISCRIMINATOR_SIZE: usize = 8;
+
+/// Python keywords, which can't be attribute names
+const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Adapters turning parsed containers into the generated dataclasses
+const HELPERS: &str = r#"class _Dataclass(construct.Adapter):
+    """Parses a `CStruct` into a dataclass and builds one from it"""
+
+    def __init__(self, cls, subcon):
+        super().__init__(subcon)
+        self.cls = cls
+
+    def _decode(self, obj, context, path):
+        return self.cls(**{f.name: obj[f.name] for f in dataclasses.fields(self.cls)})
+
+    def _encode(self, obj, context, path):
+        return {f.name: getattr(obj, f.name) for f in dataclasses.fields(self.cls)}
+
+
+class _Enum(construct.Adapter):
+    """A `u8` variant index followed by the variant's fields"""
+
+    def __init__(self, name, variants):
+        super().__init__(
+            construct.Struct(
+                "index" / U8,
+                "value" / construct.Switch(construct.this.index, dict(enumerate(variants))),
+            )
+        )
+        self.name = name
+        self.variants = variants
+
+    def _decode(self, obj, context, path):
+        if obj.index >= len(self.variants):
+            raise construct.ExplicitError(
+                f"variant index {obj.index} is out of range for {self.name}", path=path
+            )
+        return obj.value
+
+    def _encode(self, obj, context, path):
+        for index, variant in enumerate(self.variants):
+            if isinstance(obj, variant.cls):
+                return {"index": index, "value": obj}
+        raise construct.ExplicitError(f"{obj!r} is not a {self.name}", path=path)
+"#;
+
+/// Generate the complete module for `type_defs`
+pub fn generate_module(type_defs: &[TypeDefinition]) -> String {
+    let mut output = String::new();
+    output.push_str("# Auto-generated by LUMOS\n");
+    output.push_str("# DO NOT EDIT - Changes will be overwritten\n\n");
+    output.push_str("from __future__ import annotations\n\n");
+    output.push_str("import dataclasses\nimport typing\nfrom dataclasses import dataclass\n\n");
+    output.push_str("import construct\n");
+    output.push_str("from borsh_construct import (\n");
+    for name in [
+        "Bool", "CStruct", "F32", "F64", "HashMap", "I128", "I16", "I32", "I64", "I8", "Option",
+        "String", "U128", "U16", "U32", "U64", "U8", "Vec",
+    ] {
+        output.push_str(&format!("    {},\n", name));
+    }
+    output.push_str(")\n\n\n");
+    output.push_str(HELPERS);
+
+    for type_def in type_defs {
+        output.push_str("\n\n");
+        match type_def {
+            TypeDefinition::Struct(s) => output.push_str(&generate_struct(s)),
+            TypeDefinition::Enum(e) => output.push_str(&generate_enum(e)),
+        }
+    }
+
+    output
+}
+
+fn generate_struct(struct_def: &StructDefinition) -> String {
+    let name = &struct_def.name;
+    let mut output = String::from("@dataclass\n");
+    output.push_str(&format!("class {}:\n", name));
+    output.push_str(&dataclass_fields(&struct_def.fields));
+    if !struct_def.fields.is_empty() {
+        output.push('\n');
+    }
+
+    let is_account = struct_def.metadata.solana && struct_def.metadata.is_account();
+    if is_account {
+        output.push_str(&format!(
+            "    DISCRIMINATOR_SIZE: typing.ClassVar[int] = {}\n\n",
+            DISCRIMINATOR_SIZE
+        ));
+    }
+    output.push_str(&format!(
+        "    @classmethod\n    def decode(cls, data: bytes) -> {name}:\n        return {name}Layout.parse(data)\n\n"
+    ));
+    if is_account {
+        output.push_str(&format!(
+            "    @classmethod\n    def decode_account(cls, data: bytes) -> {name}:\n        \"\"\"Decode account data, skipping the Anchor discriminator\"\"\"\n        return {name}Layout.parse(data[cls.DISCRIMINATOR_SIZE :])\n\n"
+        ));
+    }
+    output.push_str(&format!(
+        "    def encode(self) -> bytes:\n        return {name}Layout.build(self)\n\n\n"
+    ));
+
+    output.push_str(&format!(
+        "{}Layout = {}\n",
+        name,
+        dataclass_layout(name, &struct_def.fields, "")
+    ));
+    output
+}
+
+fn generate_enum(enum_def: &EnumDefinition) -> String {
+    let name = &enum_def.name;
+    let mut output = String::new();
+    let mut classes = Vec::new();
+
+    for variant in &enum_def.variants {
+        let class = format!("{}{}", name, variant.name());
+        let fields = variant_fields(variant);
+        output.push_str(&format!("@dataclass\nclass {}:\n", class));
+        if fields.is_empty() {
+            output.push_str("    pass\n");
+        } else {
+            output.push_str(&dataclass_fields(&fields));
+        }
+        output.push_str("\n\n");
+        classes.push((class, fields));
+    }
+
+    let members: Vec<&str> = classes.iter().map(|(class, _)| class.as_str()).collect();
+    output.push_str(&format!(
+        "{} = typing.Union[{}]\n\n",
+        name,
+        members.join(", ")
+    ));
+
+    output.push_str(&format!(
+        "{}Layout = _Enum(\n    \"{}\",\n    [\n",
+        name, name
+    ));
+    for (class, fields) in &classes {
+        output.push_str(&format!(
+            "        {},\n",
+            dataclass_layout(class, fields, "        ")
+        ));
+    }
+    output.push_str("    ],\n)\n");
+    output
+}
+
+/// Tuple variant fields become `field_0`, `field_1`, ...
+fn variant_fields(variant: &EnumVariantDefinition) -> Vec<FieldDefinition> {
+    match variant {
+        EnumVariantDefinition::Unit { .. } => Vec::new(),
+        EnumVariantDefinition::Tuple { types, .. } => types
+            .iter()
+            .enumerate()
+            .map(|(index, type_info)| FieldDefinition {
+                name: format!("field_{}", index),
+                type_info: type_info.clone(),
+                optional: false,
+                attributes: Vec::new(),
+            })
+            .collect(),
+        EnumVariantDefinition::Struct { fields, .. } => fields.clone(),
+    }
+}
+
+fn dataclass_fields(fields: &[FieldDefinition]) -> String {
+    let mut output = String::new();
+    for field in fields {
+        if let Some(doc) = field.doc_comment() {
+            output.push_str(&format!("    # {}\n", doc));
+        }
+        output.push_str(&format!(
+            "    {}: {}\n",
+            attribute_name(&field.name),
+            python_type(&field.type_info)
+        ));
+    }
+    output
+}
+
+/// `_Dataclass(<class>, CStruct(...))`, continuation lines indented by `indent`
+fn dataclass_layout(class: &str, fields: &[FieldDefinition], indent: &str) -> String {
+    if fields.is_empty() {
+        return format!("_Dataclass({}, CStruct())", class);
+    }
+    let mut output = format!("_Dataclass(\n{indent}    {class},\n{indent}    CStruct(\n");
+    for field in fields {
+        let layout = match field.codec("rust") {
+            Some(codec) => format!("construct.Error,  # layout owned by custom codec {}", codec),
+            None => format!("{},", layout(&field.type_info)),
+        };
+        output.push_str(&format!(
+            "{indent}        \"{}\" / {}\n",
+            attribute_name(&field.name),
+            layout
+        ));
+    }
+    output.push_str(&format!("{indent}    ),\n{indent})"));
+    output
+}
+
+/// Field name as a Python attribute, with a trailing `_` for keywords
+fn attribute_name(name: &str) -> String {
+    match KEYWORDS.contains(&name) {
+        true => format!("{}_", name),
+        false => name.to_string(),
+    }
+}
+
+/// Python type annotation for a field
+fn python_type(type_info: &TypeInfo) -> String {
+    match type_info {
+        TypeInfo::Primitive(name) => match name.as_str() {
+            "f32" | "f64" => "float",
+            "bool" => "bool",
+            "String" | "Signature" => "str",
+            "PublicKey" | "Pubkey" | "Keypair" => "bytes",
+            _ => "int",
+        }
+        .to_string(),
+        TypeInfo::UserDefined(name) => name.clone(),
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => {
+            format!("list[{}]", python_type(inner))
+        }
+        TypeInfo::Map(_, key, value) => {
+            format!("dict[{}, {}]", python_type(key), python_type(value))
+        }
+        TypeInfo::Option(inner) => format!("typing.Optional[{}]", python_type(inner)),
+    }
+}
+
+/// `borsh-construct` layout of a type
+fn layout(type_info: &TypeInfo) -> String {
+    match type_info {
+        TypeInfo::Primitive(name) => match name.as_str() {
+            "bool" => "Bool".to_string(),
+            "String" | "Signature" => "String".to_string(),
+            "PublicKey" | "Pubkey" => "construct.Bytes(32)".to_string(),
+            "Keypair" => "construct.Bytes(64)".to_string(),
+            other => other.to_uppercase(),
+        },
+        // Looked up when used, so types can refer to types declared later
+        TypeInfo::UserDefined(name) => format!("construct.LazyBound(lambda: {}Layout)", name),
+        TypeInfo::Array(inner) => format!("Vec({})", layout(inner)),
+        TypeInfo::FixedArray(inner, len) => format!("construct.Array({}, {})", len, layout(inner)),
+        TypeInfo::Map(_, key, value) => format!("HashMap({}, {})", layout(key), layout(value)),
+        TypeInfo::Option(inner) => format!("Option({})", layout(inner)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn generate(source: &str) -> String {
+        generate_module(&transform_to_ir(parse_lumos_file(source).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn generates_struct_dataclasses() {
+        let code = generate(
+            r#"
+            #[solana]
+            #[account]
+            struct Vault {
+                owner: PublicKey,
+                /// Deposited lamports
+                balance: u64,
+                tags: [String],
+                seed: [u8; 32],
+                limits: BTreeMap<PublicKey, u128>,
+                memo: Option<String>,
+                from: i16,
+            }
+            "#,
+        );
+
+        assert!(code.contains("@dataclass\nclass Vault:\n    owner: bytes\n    # Deposited lamports\n    balance: int\n    tags: list[str]\n"));
+        assert!(code.contains(
+            "    limits: dict[bytes, int]\n    memo: typing.Optional[str]\n    from_: int\n"
+        ));
+        assert!(code.contains("        return VaultLayout.parse(data[cls.DISCRIMINATOR_SIZE :])"));
+        assert!(code.contains("        \"owner\" / construct.Bytes(32),\n        \"balance\" / U64,\n        \"tags\" / Vec(String),\n        \"seed\" / construct.Array(32, U8),\n        \"limits\" / HashMap(construct.Bytes(32), U128),\n        \"memo\" / Option(String),\n        \"from_\" / I16,\n"));
+    }
+
+    #[test]
+    fn generates_enum_variants() {
+        let code = generate(
+            "struct Order { side: Side }\nenum Side { Bid, Ask(u8), Limit { price: u64 } }",
+        );
+
+        assert!(code.contains("\"side\" / construct.LazyBound(lambda: SideLayout),"));
+        assert!(code.contains("@dataclass\nclass SideBid:\n    pass\n"));
+        assert!(code.contains("@dataclass\nclass SideAsk:\n    field_0: int\n"));
+        assert!(code.contains("Side = typing.Union[SideBid, SideAsk, SideLimit]"));
+        assert!(code.contains(
+            "SideLayout = _Enum(\n    \"Side\",\n    [\n        _Dataclass(SideBid, CStruct()),\n"
+        ));
+        assert!(code.contains("                \"price\" / U64,\n"));
+        assert!(!code.contains("decode_account"));
+    }
+}
@@ -59,10 +59,138 @@
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 
+use crate::ast::SchemaMetadata;
+use crate::borsh_decoder::DecodedValue;
+use crate::generators::cache::{cached_snippet, snippet_key, NoCache, SnippetCache};
+use crate::generators::summary::SchemaSummary;
+use crate::instruction_decoder::to_snake_case;
 use crate::ir::{
-    EnumDefinition, EnumVariantDefinition, StructDefinition, TypeDefinition, TypeInfo,
+    self, EnumDefinition, EnumVariantDefinition, FieldDefinition, StructDefinition, TypeDefinition,
+    TypeInfo,
 };
-use std::collections::HashSet;
+use crate::migration_generator;
+use crate::test_vector;
+use crate::zero_copy;
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Write};
+
+/// How generated types get the `Debug` and `Clone` derives
+///
+/// On-chain programs rarely need either, and each adds code to the BPF
+/// binary. Serialization derives and Anchor's `#[account]` are unaffected.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClientDerives {
+    /// Always derive them (the default)
+    Always,
+
+    /// Derive them only with the named Cargo feature, via `cfg_attr`
+    Feature(String),
+
+    /// Never derive them
+    Skip,
+}
+
+/// Rust generation settings, from the `[rust]` section of a `lumos.toml`
+#[derive(Debug, Clone)]
+pub struct RustConfig {
+    /// `Debug`/`Clone` handling for types without an override
+    pub client_derives: ClientDerives,
+
+    /// Per-type `Debug`/`Clone` handling, by type name
+    pub type_overrides: BTreeMap<String, ClientDerives>,
+
+    /// `borsh::BorshSchema` derive on every type (skipped by default)
+    pub borsh_schema: ClientDerives,
+
+    /// `schema { ... }` block described in the file header; set from the
+    /// schema rather than `lumos.toml`
+    pub schema: Option<SchemaMetadata>,
+}
+
+impl Default for RustConfig {
+    fn default() -> Self {
+        Self {
+            client_derives: ClientDerives::Always,
+            type_overrides: BTreeMap::new(),
+            borsh_schema: ClientDerives::Skip,
+            schema: None,
+        }
+    }
+}
+
+impl RustConfig {
+    /// Cargo feature used by `debug-clone = "feature"` when none is configured
+    pub const DEFAULT_CLIENT_FEATURE: &'static str = "client";
+
+    /// `Debug`/`Clone` handling for a type
+    pub fn client_derives_for(&self, type_name: &str) -> &ClientDerives {
+        self.type_overrides
+            .get(type_name)
+            .unwrap_or(&self.client_derives)
+    }
+
+    /// Read the `[rust]` section of a `lumos.toml`
+    ///
+    /// ```toml
+    /// [rust]
+    /// debug-clone = "feature"   # "always" (default), "feature" or "skip"
+    /// client-feature = "client"
+    /// borsh-schema = "feature"  # BorshSchema derives, "skip" by default
+    ///
+    /// [rust.types.Order]
+    /// debug-clone = "always"
+    /// ```
+    pub fn from_lumos_toml(input: &str) -> Result<Self, toml::de::Error> {
+        use serde::de::Error;
+
+        let mut config = Self::default();
+        let value: toml::Value = toml::from_str(input)?;
+
+        let Some(rust) = value.get("rust") else {
+            return Ok(config);
+        };
+
+        let feature = match rust.get("client-feature") {
+            Some(name) => name
+                .as_str()
+                .ok_or_else(|| toml::de::Error::custom("rust.client-feature must be a string"))?,
+            None => Self::DEFAULT_CLIENT_FEATURE,
+        };
+        let parse_mode = |value: &toml::Value, key: &str| match value.as_str() {
+            Some("always") => Ok(ClientDerives::Always),
+            Some("feature") => Ok(ClientDerives::Feature(feature.to_string())),
+            Some("skip") => Ok(ClientDerives::Skip),
+            _ => Err(toml::de::Error::custom(format!(
+                "{} must be \"always\", \"feature\" or \"skip\"",
+                key
+            ))),
+        };
+
+        if let Some(mode) = rust.get("debug-clone") {
+            config.client_derives = parse_mode(mode, "rust.debug-clone")?;
+        }
+        if let Some(mode) = rust.get("borsh-schema") {
+            config.borsh_schema = parse_mode(mode, "rust.borsh-schema")?;
+        }
+
+        if let Some(types) = rust.get("types") {
+            let types = types
+                .as_table()
+                .ok_or_else(|| toml::de::Error::custom("rust.types must be a table"))?;
+
+            for (name, settings) in types {
+                if let Some(mode) = settings.get("debug-clone") {
+                    let key = format!("rust.types.{}.debug-clone", name);
+                    config
+                        .type_overrides
+                        .insert(name.clone(), parse_mode(mode, &key)?);
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
 
 /// Generate Rust code from a type definition
 pub fn generate(type_def: &TypeDefinition) -> String {
@@ -81,11 +209,7 @@ fn generate_struct(struct_def: &StructDefinition) -> String {
     output.push_str("// DO NOT EDIT - Changes will be overwritten\n\n");
 
     // Determine if this struct uses Anchor (#[account])
-    let use_anchor = struct_def.metadata.solana
-        && struct_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string());
+    let use_anchor = struct_def.metadata.solana && struct_def.metadata.is_account();
 
     // Collect required imports
     let imports = collect_struct_imports(struct_def);
@@ -96,28 +220,52 @@ fn generate_struct(struct_def: &StructDefinition) -> String {
         output.push('\n');
     }
 
+    push_docs(&mut output, &struct_def.metadata.docs, "");
+
     // Generate derives using context-aware function
-    let derives = generate_struct_derives_with_context(struct_def, use_anchor);
+    let mut derives = generate_struct_derives_with_context(struct_def, use_anchor);
+    let redact_debug = struct_def.fields.iter().any(|f| f.is_sensitive())
+        && derives.iter().any(|derive| derive == "Debug");
+    if redact_debug {
+        derives.retain(|derive| derive != "Debug");
+    }
+    let zero_copy = zero_copy::is_zero_copy(struct_def);
+    if zero_copy && !use_anchor {
+        derives.retain(|derive| derive != "Clone");
+    }
     if !derives.is_empty() {
         output.push_str(&format!("#[derive({})]\n", derives.join(", ")));
     }
+    if zero_copy && !use_anchor {
+        output.push_str("#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]\n#[repr(C)]\n");
+    }
 
     // Add Solana-specific attributes
     if use_anchor {
-        output.push_str("#[account]\n");
+        output.push_str(match zero_copy {
+            true => "#[account(zero_copy)]\n",
+            false => "#[account]\n",
+        });
     }
 
     // Generate struct definition
     output.push_str(&format!("pub struct {} {{\n", struct_def.name));
 
     // Generate fields
-    for field in &struct_def.fields {
-        let rust_type = map_type_to_rust(&field.type_info);
-        output.push_str(&format!("    pub {}: {},\n", field.name, rust_type));
+    for field in struct_def.data_fields() {
+        push_field(&mut output, field);
     }
 
     output.push_str("}\n");
 
+    if redact_debug {
+        output.push_str(&generate_redacted_debug(struct_def, &ClientDerives::Always));
+    }
+
+    if struct_def.metadata.has_display() {
+        output.push_str(&generate_struct_display(struct_def));
+    }
+
     output
 }
 
@@ -130,11 +278,7 @@ fn generate_enum(enum_def: &EnumDefinition) -> String {
     output.push_str("// DO NOT EDIT - Changes will be overwritten\n\n");
 
     // Determine if this enum uses Anchor (#[account])
-    let use_anchor = enum_def.metadata.solana
-        && enum_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string());
+    let use_anchor = enum_def.metadata.solana && enum_def.metadata.is_account();
 
     // Collect required imports
     let imports = collect_enum_imports(enum_def);
@@ -145,6 +289,8 @@ fn generate_enum(enum_def: &EnumDefinition) -> String {
         output.push('\n');
     }
 
+    push_docs(&mut output, &enum_def.metadata.docs, "");
+
     // Generate derives using context-aware function
     let derives = generate_enum_derives_with_context(enum_def, use_anchor);
     if !derives.is_empty() {
@@ -173,6 +319,7 @@ fn generate_enum(enum_def: &EnumDefinition) -> String {
                 output.push_str(&format!("    {} {{\n", name));
                 for field in fields {
                     let rust_type = map_type_to_rust(&field.type_info);
+                    push_docs(&mut output, &field.docs(), "        ");
                     output.push_str(&format!("        {}: {},\n", field.name, rust_type));
                 }
                 output.push_str("    },\n");
@@ -182,6 +329,14 @@ fn generate_enum(enum_def: &EnumDefinition) -> String {
 
     output.push_str("}\n");
 
+    if enum_def.is_unit_only() {
+        output.push_str(&generate_all_variants(enum_def));
+    }
+
+    if enum_def.metadata.has_display() {
+        output.push_str(&generate_enum_display(enum_def));
+    }
+
     output
 }
 
@@ -277,6 +432,12 @@ fn generate_enum(enum_def: &EnumDefinition) -> String {
 ///     pub losses: u32,
 /// }
 /// ```
+/// Import path of the Anchor prelude
+const ANCHOR_PRELUDE: &str = "anchor_lang::prelude::*";
+
+/// Path of the `BorshSchema` derive, spelled out so it works with Anchor's prelude too
+const BORSH_SCHEMA_DERIVE: &str = "borsh::BorshSchema";
+
 // Estimate output size for string capacity pre-allocation
 //
 // Reduces reallocations for large schemas by estimating the final size.
@@ -308,22 +469,83 @@ fn estimate_output_size(type_defs: &[TypeDefinition]) -> usize {
 
 pub fn generate_module(type_defs: &[TypeDefinition]) -> String {
     // Estimate output size to reduce allocations for large schemas
-    let estimated_capacity = estimate_output_size(type_defs);
-    let mut output = String::with_capacity(estimated_capacity);
+    let mut output = Vec::with_capacity(estimate_output_size(type_defs));
+    generate_to(type_defs, &mut output).expect("writing to a Vec cannot fail");
+    String::from_utf8(output).expect("generated code is valid UTF-8")
+}
 
-    // Add file header
-    output.push_str("// Auto-generated by LUMOS\n");
-    output.push_str("// DO NOT EDIT - Changes will be overwritten\n\n");
+/// Write the module for `type_defs` to `out`, one type at a time
+///
+/// Produces the same code as [`generate_module`] without holding the whole
+/// module in memory, which matters for schemas with thousands of types.
+pub fn generate_to<W: Write>(type_defs: &[TypeDefinition], out: &mut W) -> io::Result<()> {
+    generate_cached_to(type_defs, None, &mut NoCache, out)
+}
 
-    // Check if ANY struct or enum uses #[account]
-    let has_account_attr = type_defs.iter().any(|t| match t {
+/// Like [`generate_to`], adding `declare_id!(program_id)` to Anchor modules
+///
+/// The declaration follows the Anchor prelude import. Modules that do not use
+/// Anchor (see [`uses_anchor`]) are written unchanged.
+pub fn generate_program_to<W: Write>(
+    type_defs: &[TypeDefinition],
+    program_id: &str,
+    out: &mut W,
+) -> io::Result<()> {
+    generate_cached_to(type_defs, Some(program_id), &mut NoCache, out)
+}
+
+/// Whether the module for `type_defs` uses the Anchor prelude
+///
+/// True when any Solana type is marked `#[account]`, or any Solana struct
+/// `#[instruction]`, whose `Accounts` context needs Anchor.
+pub fn uses_anchor(type_defs: &[TypeDefinition]) -> bool {
+    type_defs.iter().any(|t| match t {
         TypeDefinition::Struct(s) => {
-            s.metadata.solana && s.metadata.attributes.contains(&"account".to_string())
-        }
-        TypeDefinition::Enum(e) => {
-            e.metadata.solana && e.metadata.attributes.contains(&"account".to_string())
+            s.metadata.solana && (s.metadata.is_account() || s.metadata.is_instruction())
         }
-    });
+        TypeDefinition::Enum(e) => e.metadata.solana && e.metadata.is_account(),
+    })
+}
+
+/// Like [`generate_program_to`], reusing unchanged types' code from `cache`
+///
+/// Pass `None` as `program_id` to omit `declare_id!`. Each type's snippet is
+/// looked up by [`snippet_key`] and only generated on a miss.
+pub fn generate_cached_to<W: Write>(
+    type_defs: &[TypeDefinition],
+    program_id: Option<&str>,
+    cache: &mut dyn SnippetCache,
+    out: &mut W,
+) -> io::Result<()> {
+    generate_configured_to(type_defs, program_id, &RustConfig::default(), cache, out)
+}
+
+/// Like [`generate_cached_to`], applying the settings in `config`
+pub fn generate_configured_to<W: Write>(
+    type_defs: &[TypeDefinition],
+    program_id: Option<&str>,
+    config: &RustConfig,
+    cache: &mut dyn SnippetCache,
+    out: &mut W,
+) -> io::Result<()> {
+    let type_defs = &*ir::for_target(type_defs, "rust");
+
+    // Add file header
+    out.write_all(b"// Auto-generated by LUMOS\n")?;
+    out.write_all(b"// DO NOT EDIT - Changes will be overwritten\n")?;
+    for line in config
+        .schema
+        .as_ref()
+        .map(SchemaMetadata::header_lines)
+        .unwrap_or_default()
+    {
+        writeln!(out, "// {}", line)?;
+    }
+    out.write_all(b"\n")?;
+
+    // Check if ANY struct or enum uses #[account]
+    let has_account_attr = uses_anchor(type_defs);
+    let validated = ir::validated_structs(type_defs);
 
     // Collect all imports needed
     let mut all_imports = HashSet::new();
@@ -331,7 +553,7 @@ pub fn generate_module(type_defs: &[TypeDefinition]) -> String {
     // Determine which import style to use
     if has_account_attr {
         // If any struct/enum uses #[account], use Anchor imports for everything
-        all_imports.insert("anchor_lang::prelude::*".to_string());
+        all_imports.insert(ANCHOR_PRELUDE.to_string());
     } else {
         // Otherwise collect individual imports
         for type_def in type_defs {
@@ -348,13 +570,17 @@ pub fn generate_module(type_defs: &[TypeDefinition]) -> String {
         }
     }
 
-    // Check for Solana-specific types
+    // Check for Solana-specific types, and map types to import
     let mut needs_pubkey = false;
+    let mut check = |type_info: &TypeInfo| {
+        check_needs_solana_types(type_info, &mut needs_pubkey);
+        collect_map_imports(type_info, &mut all_imports);
+    };
     for type_def in type_defs {
         match type_def {
             TypeDefinition::Struct(s) => {
                 for field in &s.fields {
-                    check_needs_solana_types(&field.type_info, &mut needs_pubkey);
+                    check(&field.type_info);
                 }
             }
             TypeDefinition::Enum(e) => {
@@ -363,12 +589,12 @@ pub fn generate_module(type_defs: &[TypeDefinition]) -> String {
                         EnumVariantDefinition::Unit { .. } => {}
                         EnumVariantDefinition::Tuple { types, .. } => {
                             for type_info in types {
-                                check_needs_solana_types(type_info, &mut needs_pubkey);
+                                check(type_info);
                             }
                         }
                         EnumVariantDefinition::Struct { fields, .. } => {
                             for field in fields {
-                                check_needs_solana_types(&field.type_info, &mut needs_pubkey);
+                                check(&field.type_info);
                             }
                         }
                     }
@@ -386,27 +612,282 @@ pub fn generate_module(type_defs: &[TypeDefinition]) -> String {
         let mut sorted_imports: Vec<_> = all_imports.into_iter().collect();
         sorted_imports.sort();
         for import in sorted_imports {
-            output.push_str(&format!("use {};\n", import));
+            writeln!(out, "use {};", import)?;
+            if import == ANCHOR_PRELUDE {
+                if let Some(program_id) = program_id {
+                    write!(out, "\ndeclare_id!(\"{}\");\n\n", program_id)?;
+                }
+            }
         }
-        output.push('\n');
+        out.write_all(b"\n")?;
     }
 
     // Generate each type definition
     for (i, type_def) in type_defs.iter().enumerate() {
         if i > 0 {
-            output.push('\n');
+            out.write_all(b"\n")?;
         }
 
-        match type_def {
-            TypeDefinition::Struct(s) => {
-                output.push_str(&generate_struct_with_context(s, has_account_attr));
-            }
+        let client_derives = config.client_derives_for(type_def.name());
+        let borsh_schema = &config.borsh_schema;
+        let key = snippet_key(
+            "rust",
+            (has_account_attr, client_derives, borsh_schema, &validated),
+            type_def,
+        );
+        let code = cached_snippet(cache, key, || match type_def {
+            TypeDefinition::Struct(s) => generate_struct_with_context(
+                s,
+                has_account_attr,
+                client_derives,
+                borsh_schema,
+                &validated,
+            ),
             TypeDefinition::Enum(e) => {
-                output.push_str(&generate_enum_with_context(e, has_account_attr));
+                generate_enum_with_context(e, has_account_attr, client_derives, borsh_schema)
+            }
+        });
+        out.write_all(code.as_bytes())?;
+
+        if let TypeDefinition::Struct(s) = type_def {
+            if s.metadata.solana && s.metadata.is_instruction() {
+                out.write_all(generate_accounts_context(s, type_defs).as_bytes())?;
             }
         }
     }
 
+    out.write_all(generate_test_vectors(type_defs).as_bytes())?;
+
+    write!(out, "\n{}", SchemaSummary::new(type_defs).footer())
+}
+
+/// Generate a test module checking the `#[test_vector]` values of Solana structs
+///
+/// Each test serializes the value and compares it to the bytes LUMOS
+/// computed from the schema, then decodes those bytes and serializes the
+/// result again. Empty when no struct declares vectors.
+fn generate_test_vectors(type_defs: &[TypeDefinition]) -> String {
+    let mut tests = String::new();
+    for type_def in type_defs {
+        let TypeDefinition::Struct(s) = type_def else {
+            continue;
+        };
+        if !s.metadata.solana {
+            continue;
+        }
+        // Checked when the schema was transformed
+        let vectors = test_vector::test_vectors(type_defs, s).unwrap_or_default();
+        for (index, vector) in vectors.iter().enumerate() {
+            let value = rust_literal(
+                type_defs,
+                &TypeInfo::UserDefined(s.name.clone()),
+                &vector.value,
+            );
+            let bytes: Vec<String> = vector.bytes.iter().map(u8::to_string).collect();
+
+            tests.push_str(&format!(
+                "\n    /// `#[test_vector]` {} of `{}`\n",
+                index + 1,
+                s.name
+            ));
+            tests.push_str("    #[test]\n");
+            tests.push_str(&format!(
+                "    fn {}_{}() {{\n",
+                to_snake_case(&s.name),
+                index + 1
+            ));
+            tests.push_str(&format!("        let value = {};\n", value));
+            tests.push_str(&format!(
+                "        let expected: &[u8] = &[{}];\n\n",
+                bytes.join(", ")
+            ));
+            tests.push_str("        let mut bytes = Vec::new();\n");
+            tests.push_str("        value.serialize(&mut bytes).unwrap();\n");
+            tests.push_str("        assert_eq!(bytes, expected);\n\n");
+            tests.push_str(&format!(
+                "        let decoded = {}::try_from_slice(expected).unwrap();\n",
+                s.name
+            ));
+            tests.push_str("        let mut again = Vec::new();\n");
+            tests.push_str("        decoded.serialize(&mut again).unwrap();\n");
+            tests.push_str("        assert_eq!(again, expected);\n");
+            tests.push_str("    }\n");
+        }
+    }
+
+    if tests.is_empty() {
+        return tests;
+    }
+    format!(
+        "\n#[cfg(test)]\nmod test_vectors {{\n    use super::*;\n{}}}\n",
+        tests
+    )
+}
+
+/// A Rust expression building `value`, a value of `type_info`
+fn rust_literal(
+    type_defs: &[TypeDefinition],
+    type_info: &TypeInfo,
+    value: &DecodedValue,
+) -> String {
+    let list = |items: Vec<String>| items.join(", ");
+    let fields = |fields: &[FieldDefinition], values: &[(String, DecodedValue)]| -> String {
+        fields
+            .iter()
+            .zip(values)
+            .map(|(field, (_, value))| {
+                format!(
+                    "{}: {}",
+                    field.name,
+                    rust_literal(type_defs, &field.type_info, value)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match (type_info, value) {
+        (TypeInfo::Option(_), DecodedValue::Option(None)) => "None".to_string(),
+        (TypeInfo::Option(inner), DecodedValue::Option(Some(value))) => {
+            format!("Some({})", rust_literal(type_defs, inner, value))
+        }
+        (TypeInfo::Array(inner), DecodedValue::Vec(items)) => format!(
+            "vec![{}]",
+            list(
+                items
+                    .iter()
+                    .map(|item| rust_literal(type_defs, inner, item))
+                    .collect()
+            )
+        ),
+        (TypeInfo::FixedArray(inner, _), DecodedValue::Vec(items)) => format!(
+            "[{}]",
+            list(
+                items
+                    .iter()
+                    .map(|item| rust_literal(type_defs, inner, item))
+                    .collect()
+            )
+        ),
+        (TypeInfo::Map(kind, key_type, value_type), DecodedValue::Map(entries)) => format!(
+            "{}::from([{}])",
+            kind.as_str(),
+            list(
+                entries
+                    .iter()
+                    .map(|(key, value)| format!(
+                        "({}, {})",
+                        rust_literal(type_defs, key_type, key),
+                        rust_literal(type_defs, value_type, value)
+                    ))
+                    .collect()
+            )
+        ),
+        (TypeInfo::UserDefined(name), _) => {
+            let type_def = type_defs.iter().find(|type_def| type_def.name() == name);
+            match (type_def, value) {
+                (Some(TypeDefinition::Struct(s)), DecodedValue::Struct(values)) => {
+                    format!("{} {{ {} }}", name, fields(&s.fields, values))
+                }
+                (
+                    Some(TypeDefinition::Enum(e)),
+                    DecodedValue::Variant {
+                        name: variant,
+                        fields: values,
+                    },
+                ) => match e.variants.iter().find(|v| v.name() == variant) {
+                    Some(EnumVariantDefinition::Tuple { types, .. }) => format!(
+                        "{}::{}({})",
+                        name,
+                        variant,
+                        list(
+                            types
+                                .iter()
+                                .zip(values)
+                                .map(|(type_info, (_, value))| rust_literal(
+                                    type_defs, type_info, value
+                                ))
+                                .collect()
+                        )
+                    ),
+                    Some(EnumVariantDefinition::Struct { fields: defs, .. }) => {
+                        format!("{}::{} {{ {} }}", name, variant, fields(defs, values))
+                    }
+                    _ => format!("{}::{}", name, variant),
+                },
+                _ => value.to_string(),
+            }
+        }
+        (_, DecodedValue::Bool(value)) => value.to_string(),
+        (_, DecodedValue::Unsigned(value)) => value.to_string(),
+        (_, DecodedValue::Signed(value)) => value.to_string(),
+        (_, DecodedValue::Float(value)) => format!("{:?}", value),
+        (_, DecodedValue::String(value)) => format!("{:?}.to_string()", value),
+        (_, DecodedValue::PublicKey(key)) => format!("{:?}.parse::<Pubkey>().unwrap()", key),
+        (_, value) => value.to_string(),
+    }
+}
+
+/// Generate the Anchor `#[derive(Accounts)]` context of an `#[instruction]`
+///
+/// `{Name}Accounts` lists the instruction's accounts: `#[signer]` fields
+/// become `Signer`, fields of an `#[account]` type `Account` (`AccountLoader`
+/// when zero-copy) and other `PublicKey`s `UncheckedAccount`. Their
+/// `#[account(...)]` constraints are copied over, and the arguments are
+/// declared with `#[instruction(...)]` so constraints can refer to them. The
+/// handler takes the arguments in field order, matching the Borsh layout of
+/// the `{Name}` struct.
+fn generate_accounts_context(
+    struct_def: &StructDefinition,
+    type_defs: &[TypeDefinition],
+) -> String {
+    let accounts: Vec<&FieldDefinition> = struct_def.instruction_accounts().collect();
+    let arguments: Vec<String> = struct_def
+        .data_fields()
+        .map(|field| format!("{}: {}", field.name, map_type_to_rust(&field.type_info)))
+        .collect();
+
+    let mut output = format!(
+        "\n/// Accounts of the `{}` instruction\n#[derive(Accounts)]\n",
+        to_snake_case(&struct_def.name)
+    );
+    if !arguments.is_empty() {
+        output.push_str(&format!("#[instruction({})]\n", arguments.join(", ")));
+    }
+    if accounts.is_empty() {
+        output.push_str(&format!("pub struct {}Accounts {{}}\n", struct_def.name));
+        return output;
+    }
+
+    output.push_str(&format!(
+        "pub struct {}Accounts<'info> {{\n",
+        struct_def.name
+    ));
+    for field in accounts {
+        let constraints = field.attribute_args("account");
+        if !constraints.is_empty() {
+            output.push_str(&format!("    #[account({})]\n", constraints.join(", ")));
+        }
+        let account_type = match &field.type_info {
+            TypeInfo::UserDefined(name) => {
+                let zero_copy = type_defs
+                    .iter()
+                    .any(|t| t.name() == name && t.metadata().zero_copy);
+                match zero_copy {
+                    true => format!("AccountLoader<'info, {}>", name),
+                    false => format!("Account<'info, {}>", name),
+                }
+            }
+            _ if field.has_attribute("signer") => "Signer<'info>".to_string(),
+            _ => {
+                output.push_str("    /// CHECK: left unchecked by the LUMOS schema\n");
+                "UncheckedAccount<'info>".to_string()
+            }
+        };
+        output.push_str(&format!("    pub {}: {},\n", field.name, account_type));
+    }
+    output.push_str("}\n");
+
     output
 }
 
@@ -418,33 +899,50 @@ fn check_needs_solana_types(type_info: &TypeInfo, needs_pubkey: &mut bool) {
                 *needs_pubkey = true;
             }
         }
-        TypeInfo::Array(inner) => {
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => {
             check_needs_solana_types(inner, needs_pubkey);
         }
         TypeInfo::Option(inner) => {
             check_needs_solana_types(inner, needs_pubkey);
         }
+        TypeInfo::Map(_, key, value) => {
+            check_needs_solana_types(key, needs_pubkey);
+            check_needs_solana_types(value, needs_pubkey);
+        }
         TypeInfo::UserDefined(_) => {}
     }
 }
 
+/// Add the `std::collections` import of each map type used
+fn collect_map_imports(type_info: &TypeInfo, imports: &mut HashSet<String>) {
+    match type_info {
+        TypeInfo::Map(kind, _, value) => {
+            imports.insert(format!("std::collections::{}", kind.as_str()));
+            collect_map_imports(value, imports);
+        }
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) | TypeInfo::Option(inner) => {
+            collect_map_imports(inner, imports);
+        }
+        TypeInfo::Primitive(_) | TypeInfo::UserDefined(_) => {}
+    }
+}
+
 /// Generate enum with context (e.g., whether module uses Anchor)
-fn generate_enum_with_context(enum_def: &EnumDefinition, use_anchor: bool) -> String {
+fn generate_enum_with_context(
+    enum_def: &EnumDefinition,
+    use_anchor: bool,
+    client_derives: &ClientDerives,
+    borsh_schema: &ClientDerives,
+) -> String {
     let mut output = String::new();
+    push_docs(&mut output, &enum_def.metadata.docs, "");
 
     // Generate derives (only if there are any)
     let derives = generate_enum_derives_with_context(enum_def, use_anchor);
-    if !derives.is_empty() {
-        output.push_str(&format!("#[derive({})]\n", derives.join(", ")));
-    }
+    push_derives(&mut output, derives, client_derives, borsh_schema);
 
     // Add Solana-specific attributes
-    if enum_def.metadata.solana
-        && enum_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-    {
+    if enum_def.metadata.solana && enum_def.metadata.is_account() {
         output.push_str("#[account]\n");
     }
 
@@ -465,6 +963,7 @@ fn generate_enum_with_context(enum_def: &EnumDefinition, use_anchor: bool) -> St
                 output.push_str(&format!("    {} {{\n", name));
                 for field in fields {
                     let rust_type = map_type_to_rust(&field.type_info);
+                    push_docs(&mut output, &field.docs(), "        ");
                     output.push_str(&format!("        {}: {},\n", field.name, rust_type));
                 }
                 output.push_str("    },\n");
@@ -474,38 +973,582 @@ fn generate_enum_with_context(enum_def: &EnumDefinition, use_anchor: bool) -> St
 
     output.push_str("}\n");
 
+    if enum_def.is_unit_only() {
+        output.push_str(&generate_all_variants(enum_def));
+    }
+
+    if enum_def.metadata.has_display() {
+        output.push_str(&generate_enum_display(enum_def));
+    }
+
+    if enum_def.metadata.has_state_hash() {
+        output.push_str(&generate_state_hash(&enum_def.name, use_anchor));
+    }
+
     output
 }
 
-/// Generate struct with context (e.g., whether module uses Anchor)
-fn generate_struct_with_context(struct_def: &StructDefinition, use_anchor: bool) -> String {
+/// Generate the `ALL` constant of a unit-only enum
+fn generate_all_variants(enum_def: &EnumDefinition) -> String {
+    let variants: Vec<String> = enum_def
+        .variants
+        .iter()
+        .map(|variant| format!("{}::{}", enum_def.name, variant.name()))
+        .collect();
+
     let mut output = String::new();
+    output.push_str(&format!("\nimpl {} {{\n", enum_def.name));
+    output.push_str("    /// Every variant, in declaration order\n");
+    output.push_str(&format!(
+        "    pub const ALL: [{}; {}] = [{}];\n",
+        enum_def.name,
+        variants.len(),
+        variants.join(", ")
+    ));
+    output.push_str("}\n");
 
-    // Generate derives (only if there are any)
-    let derives = generate_struct_derives_with_context(struct_def, use_anchor);
-    if !derives.is_empty() {
-        output.push_str(&format!("#[derive({})]\n", derives.join(", ")));
+    output
+}
+
+/// Generate `Display` and `FromStr` for a `#[display]` unit-only enum
+///
+/// Variants are written and parsed by name.
+fn generate_enum_display(enum_def: &EnumDefinition) -> String {
+    let name = &enum_def.name;
+    let mut output = String::new();
+
+    output.push_str(&format!("\nimpl core::fmt::Display for {} {{\n", name));
+    output.push_str("    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {\n");
+    output.push_str("        f.write_str(match self {\n");
+    for variant in &enum_def.variants {
+        output.push_str(&format!(
+            "            {}::{} => \"{}\",\n",
+            name,
+            variant.name(),
+            variant.name()
+        ));
+    }
+    output.push_str("        })\n");
+    output.push_str("    }\n");
+    output.push_str("}\n");
+
+    output.push_str(&format!("\nimpl core::str::FromStr for {} {{\n", name));
+    output.push_str("    type Err = String;\n\n");
+    output.push_str("    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {\n");
+    output.push_str("        match s {\n");
+    for variant in &enum_def.variants {
+        output.push_str(&format!(
+            "            \"{}\" => Ok({}::{}),\n",
+            variant.name(),
+            name,
+            variant.name()
+        ));
+    }
+    output.push_str(&format!(
+        "            _ => Err(format!(\"unknown {} variant: {{}}\", s)),\n",
+        name
+    ));
+    output.push_str("        }\n");
+    output.push_str("    }\n");
+    output.push_str("}\n");
+
+    output
+}
+
+/// Generate `Display` and `FromStr` for a `#[display]` single-field struct
+///
+/// Both delegate to the field's type, so `Lamports { value: 5 }` reads and
+/// writes as `5`.
+fn generate_struct_display(struct_def: &StructDefinition) -> String {
+    let [field] = struct_def.fields.as_slice() else {
+        return String::new();
+    };
+    let name = &struct_def.name;
+    let field_type = map_type_to_rust(&field.type_info);
+    let mut output = String::new();
+
+    output.push_str(&format!("\nimpl core::fmt::Display for {} {{\n", name));
+    output.push_str("    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {\n");
+    output.push_str(&format!(
+        "        core::fmt::Display::fmt(&self.{}, f)\n",
+        field.name
+    ));
+    output.push_str("    }\n");
+    output.push_str("}\n");
+
+    output.push_str(&format!("\nimpl core::str::FromStr for {} {{\n", name));
+    output.push_str(&format!(
+        "    type Err = <{} as core::str::FromStr>::Err;\n\n",
+        field_type
+    ));
+    output.push_str("    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {\n");
+    output.push_str(&format!(
+        "        Ok(Self {{ {}: s.parse()? }})\n",
+        field.name
+    ));
+    output.push_str("    }\n");
+    output.push_str("}\n");
+
+    output
+}
+
+/// Generate struct with context (e.g., whether module uses Anchor)
+///
+/// `validated` names the structs of the module that have a `validate`.
+fn generate_struct_with_context(
+    struct_def: &StructDefinition,
+    use_anchor: bool,
+    client_derives: &ClientDerives,
+    borsh_schema: &ClientDerives,
+    validated: &[&str],
+) -> String {
+    let mut output = String::new();
+    let zero_copy = zero_copy::is_zero_copy(struct_def);
+    push_docs(&mut output, &struct_def.metadata.docs, "");
+
+    // Generate derives (only if there are any); `#[sensitive]` fields get a
+    // hand-written `Debug` instead of the derived one
+    let mut derives = generate_struct_derives_with_context(struct_def, use_anchor);
+    if zero_copy && use_anchor {
+        // Anchor's zero-copy attributes provide the derives and `repr(C)`
+        derives.clear();
+    }
+    let redact_debug = struct_def.fields.iter().any(|f| f.is_sensitive())
+        && derives.iter().any(|derive| derive == "Debug");
+    if redact_debug {
+        derives.retain(|derive| derive != "Debug");
+    }
+    if zero_copy && !use_anchor {
+        // Pod needs Copy, and so Clone, whatever the client derive setting
+        derives.retain(|derive| derive != "Clone");
+    }
+    push_derives(&mut output, derives, client_derives, borsh_schema);
+    if zero_copy && !use_anchor {
+        output.push_str("#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]\n#[repr(C)]\n");
     }
 
     // Add Solana-specific attributes
-    if struct_def.metadata.solana
-        && struct_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-    {
-        output.push_str("#[account]\n");
+    let is_account = struct_def.metadata.solana && struct_def.metadata.is_account();
+    match (is_account, zero_copy && use_anchor) {
+        (true, true) => output.push_str("#[account(zero_copy)]\n"),
+        (true, false) => output.push_str("#[account]\n"),
+        (false, true) => output.push_str("#[zero_copy]\n"),
+        (false, false) => {}
+    }
+    if struct_def.metadata.solana && use_anchor && is_event(struct_def) {
+        output.push_str("#[event]\n");
     }
 
     // Generate struct definition
     output.push_str(&format!("pub struct {} {{\n", struct_def.name));
 
     // Generate fields
-    for field in &struct_def.fields {
-        let rust_type = map_type_to_rust(&field.type_info);
-        output.push_str(&format!("    pub {}: {},\n", field.name, rust_type));
+    for field in struct_def.data_fields() {
+        push_field(&mut output, field);
+    }
+
+    output.push_str("}\n");
+
+    if redact_debug {
+        output.push_str(&generate_redacted_debug(struct_def, client_derives));
+    }
+
+    if struct_def.metadata.has_display() {
+        output.push_str(&generate_struct_display(struct_def));
+    }
+
+    if struct_def.metadata.has_state_hash() {
+        output.push_str(&generate_state_hash(&struct_def.name, use_anchor));
+    }
+
+    output.push_str(&generate_validate(struct_def, validated));
+
+    if struct_def.metadata.has_version_shims() {
+        output.push_str(&generate_version_shims(
+            struct_def,
+            use_anchor,
+            client_derives,
+            borsh_schema,
+            validated,
+        ));
+    }
+
+    output
+}
+
+/// Write a struct field, delegating its Borsh encoding to its `#[codec]`, if any
+///
+/// `serialize_with`/`deserialize_with` need the Borsh 1.x derives.
+fn push_field(output: &mut String, field: &FieldDefinition) {
+    let docs = field.docs();
+    push_docs(output, &docs, "    ");
+    if let Some(codec) = field.codec("rust") {
+        if !docs.is_empty() {
+            output.push_str("    ///\n");
+        }
+        output.push_str(&format!(
+            "    /// Layout owned by `{0}`, not by the schema\n    #[borsh(serialize_with = \"{0}::serialize\", deserialize_with = \"{0}::deserialize\")]\n",
+            codec
+        ));
+    }
+    output.push_str(&format!(
+        "    pub {}: {},\n",
+        field.name,
+        map_type_to_rust(&field.type_info)
+    ));
+}
+
+/// Write the lines of a schema doc comment as `///` comments
+fn push_docs(output: &mut String, lines: &[String], indent: &str) {
+    for line in lines {
+        match line.is_empty() {
+            true => output.push_str(&format!("{}///\n", indent)),
+            false => output.push_str(&format!("{}/// {}\n", indent, line)),
+        }
+    }
+}
+
+/// Write the derive attributes, handling `Debug` and `Clone` per `client_derives`
+/// and `BorshSchema` per `borsh_schema`
+fn push_derives(
+    output: &mut String,
+    derives: Vec<String>,
+    client_derives: &ClientDerives,
+    borsh_schema: &ClientDerives,
+) {
+    let (client, mut derives): (Vec<String>, Vec<String>) = derives
+        .into_iter()
+        .partition(|derive| derive == "Debug" || derive == "Clone");
+
+    if *client_derives == ClientDerives::Always {
+        derives.extend(client.iter().cloned());
+    }
+    if *borsh_schema == ClientDerives::Always {
+        derives.push(BORSH_SCHEMA_DERIVE.to_string());
+    }
+    if !derives.is_empty() {
+        output.push_str(&format!("#[derive({})]\n", derives.join(", ")));
+    }
+    if let ClientDerives::Feature(feature) = client_derives {
+        if !client.is_empty() {
+            output.push_str(&format!(
+                "#[cfg_attr(feature = \"{}\", derive({}))]\n",
+                feature,
+                client.join(", ")
+            ));
+        }
+    }
+    if let ClientDerives::Feature(feature) = borsh_schema {
+        output.push_str(&format!(
+            "#[cfg_attr(feature = \"{}\", derive({}))]\n",
+            feature, BORSH_SCHEMA_DERIVE
+        ));
+    }
+}
+
+/// Generate earlier layouts of a `#[version(n)]` struct and `decode_any_version`
+///
+/// Each `{Name}V{k}` converts into the next version with `From`, filling in
+/// the fields added by that version with their defaults.
+fn generate_version_shims(
+    struct_def: &StructDefinition,
+    use_anchor: bool,
+    client_derives: &ClientDerives,
+    borsh_schema: &ClientDerives,
+    validated: &[&str],
+) -> String {
+    let current = struct_def.metadata.version.unwrap_or(1);
+    let name = &struct_def.name;
+    let version_name = |version: u32| {
+        if version == current {
+            name.clone()
+        } else {
+            format!("{}V{}", name, version)
+        }
+    };
+
+    let mut output = String::new();
+    for version in 1..current {
+        let old = struct_def.at_version(version);
+        output.push_str(&format!(
+            "\n/// `{}` as laid out in schema version {}\n",
+            name, version
+        ));
+        output.push_str(&generate_struct_with_context(
+            &old,
+            use_anchor,
+            client_derives,
+            borsh_schema,
+            validated,
+        ));
+
+        let next = version + 1;
+        output.push_str(&format!(
+            "\nimpl From<{}> for {} {{\n",
+            old.name,
+            version_name(next)
+        ));
+        output.push_str(&format!("    fn from(old: {}) -> Self {{\n", old.name));
+        output.push_str("        Self {\n");
+        for field in &struct_def.fields {
+            let since = field.since_version();
+            if since <= version {
+                output.push_str(&format!(
+                    "            {}: old.{},\n",
+                    field.name, field.name
+                ));
+            } else if since == next {
+                output.push_str(&format!(
+                    "            {}: Default::default(),\n",
+                    field.name
+                ));
+            }
+        }
+        output.push_str("        }\n");
+        output.push_str("    }\n");
+        output.push_str("}\n");
+    }
+
+    output.push_str(&format!("\nimpl {} {{\n", name));
+    output.push_str(
+        "    /// Decode Borsh data written under any schema version, upgrading it to this layout\n",
+    );
+    output.push_str("    ///\n");
+    output.push_str(
+        "    /// Layouts are tried newest first. For accounts, pass the data after the\n",
+    );
+    output.push_str("    /// 8-byte discriminator.\n");
+    output.push_str("    pub fn decode_any_version(data: &[u8]) -> std::io::Result<Self> {\n");
+    output.push_str("        if let Ok(value) = Self::deserialize(&mut &data[..]) {\n");
+    output.push_str("            return Ok(value);\n");
+    output.push_str("        }\n");
+    for version in (1..current).rev() {
+        // Upgrade one version at a time, ending in `Self`
+        let mut upgraded = "value".to_string();
+        for next in version + 1..current {
+            upgraded = format!("{}::from({})", version_name(next), upgraded);
+        }
+        upgraded = format!("Self::from({})", upgraded);
+
+        if version > 1 {
+            output.push_str(&format!(
+                "        if let Ok(value) = {}V{}::deserialize(&mut &data[..]) {{\n",
+                name, version
+            ));
+            output.push_str(&format!("            return Ok({});\n", upgraded));
+            output.push_str("        }\n");
+        } else {
+            output.push_str(&format!(
+                "        {}V1::deserialize(&mut &data[..])\n",
+                name
+            ));
+            if current == 2 {
+                output.push_str("            .map(Self::from)\n");
+            } else {
+                output.push_str(&format!("            .map(|value| {})\n", upgraded));
+            }
+        }
+    }
+    output.push_str("    }\n");
+    output.push_str("}\n");
+    output.push_str(&migration_generator::generate_rust(struct_def));
+
+    output
+}
+
+/// Generate `validate`, checking the `#[min]`, `#[max]` and `#[range]` bounds
+///
+/// Borsh doesn't enforce them, so programs call it before writing an account
+/// and clients before sending an instruction. Fields holding a struct in
+/// `validated` call its `validate` in turn. Empty when there is nothing to check.
+fn generate_validate(struct_def: &StructDefinition, validated: &[&str]) -> String {
+    let nested: Vec<String> = struct_def
+        .fields
+        .iter()
+        .map(|field| {
+            validate_nested(
+                &field.type_info,
+                &format!("&self.{}", field.name),
+                "        ",
+                validated,
+            )
+        })
+        .collect();
+    if !struct_def.is_validated(validated) {
+        return String::new();
+    }
+
+    let mut output = format!("\nimpl {} {{\n", struct_def.name);
+    output.push_str(
+        "    /// Check the `#[min]`, `#[max]` and `#[range]` bounds of the schema, returning the first violation\n",
+    );
+    // Spelled out: the Anchor prelude shadows `Result` with a one-parameter alias
+    output.push_str("    pub fn validate(&self) -> std::result::Result<(), String> {\n");
+    for (field, nested) in struct_def.fields.iter().zip(nested) {
+        let constraints = field.constraints();
+        if constraints.is_empty() {
+            output.push_str(&nested);
+            continue;
+        }
+        let location = format!("{}.{}", struct_def.name, field.name);
+        let (type_info, value, indent) = match &field.type_info {
+            TypeInfo::Option(inner) => {
+                output.push_str(&format!(
+                    "        if let Some(value) = &self.{} {{\n",
+                    field.name
+                ));
+                (inner.as_ref(), "value".to_string(), "            ")
+            }
+            type_info => (type_info, format!("&self.{}", field.name), "        "),
+        };
+
+        let unit = match type_info {
+            TypeInfo::Array(_) => "items",
+            _ => "bytes",
+        };
+        let length = format!("{}.len()", value.trim_start_matches('&'));
+        let checks = [
+            (
+                match (constraints.min_len, constraints.max_len) {
+                    (Some(min), Some(max)) => format!("!({}..={}).contains(&{})", min, max, length),
+                    (Some(min), None) => format!("{} < {}", length, min),
+                    (None, Some(max)) => format!("{} > {}", length, max),
+                    (None, None) => String::new(),
+                },
+                constraints.length_violation(&location, unit),
+            ),
+            (
+                constraints
+                    .range
+                    .map(|(min, max)| format!("!({}..={}).contains({})", min, max, value))
+                    .unwrap_or_default(),
+                constraints.range_violation(&location),
+            ),
+        ];
+        for (condition, message) in checks {
+            let Some(message) = message else {
+                continue;
+            };
+            output.push_str(&format!("{}if {} {{\n", indent, condition));
+            output.push_str(&format!(
+                "{}    return Err(\"{}\".to_string());\n",
+                indent, message
+            ));
+            output.push_str(&format!("{}}}\n", indent));
+        }
+
+        if matches!(field.type_info, TypeInfo::Option(_)) {
+            output.push_str("        }\n");
+        }
+        output.push_str(&nested);
     }
+    output.push_str("        Ok(())\n");
+    output.push_str("    }\n");
+    output.push_str("}\n");
+
+    output
+}
+
+/// Statements calling `validate` on the structs in `validated` that `value`,
+/// a reference to a `type_info`, holds
+fn validate_nested(type_info: &TypeInfo, value: &str, indent: &str, validated: &[&str]) -> String {
+    let inner_indent = format!("{}    ", indent);
+    let (header, inner) = match type_info {
+        TypeInfo::UserDefined(name) if validated.contains(&name.as_str()) => {
+            return format!("{}{}.validate()?;\n", indent, value.trim_start_matches('&'));
+        }
+        TypeInfo::Option(inner) => (format!("if let Some(item) = {}", value), inner),
+        // Skipping `None`s with `flatten`, as clippy prefers
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => match inner.as_ref() {
+            TypeInfo::Option(inner) => (
+                format!(
+                    "for item in {}.iter().flatten()",
+                    value.trim_start_matches('&')
+                ),
+                inner,
+            ),
+            _ => (format!("for item in {}", value), inner),
+        },
+        TypeInfo::Map(_, _, inner) => (
+            format!("for item in {}.values()", value.trim_start_matches('&')),
+            inner,
+        ),
+        _ => return String::new(),
+    };
 
+    let body = validate_nested(inner, "item", &inner_indent, validated);
+    if body.is_empty() {
+        return body;
+    }
+    format!("{}{} {{\n{}{}}}\n", indent, header, body, indent)
+}
+
+/// Generate `state_hash`, the SHA-256 of a value's Borsh bytes
+///
+/// Anchor's 8-byte account discriminator is not included, so the hash matches
+/// the TypeScript helper, which hashes the Borsh schema's output.
+fn generate_state_hash(type_name: &str, use_anchor: bool) -> String {
+    let hash = if use_anchor {
+        "anchor_lang::solana_program::hash::hash"
+    } else {
+        "solana_program::hash::hash"
+    };
+    let mut output = String::new();
+    output.push_str(&format!("\nimpl {} {{\n", type_name));
+    output.push_str(
+        "    /// SHA-256 of the canonical Borsh encoding, for off-chain integrity checks\n",
+    );
+    output.push_str("    pub fn state_hash(&self) -> [u8; 32] {\n");
+    output.push_str("        let mut bytes = Vec::new();\n");
+    output.push_str("        self.serialize(&mut bytes)\n");
+    output.push_str("            .expect(\"serializing to a Vec cannot fail\");\n");
+    output.push_str(&format!("        {}(&bytes).to_bytes()\n", hash));
+    output.push_str("    }\n");
+    output.push_str("}\n");
+
+    output
+}
+
+/// Generate a `Debug` impl that prints `#[sensitive]` fields as `[REDACTED]`
+///
+/// Follows `client_derives` like the derived `Debug` it replaces.
+fn generate_redacted_debug(
+    struct_def: &StructDefinition,
+    client_derives: &ClientDerives,
+) -> String {
+    let mut output = String::from("\n");
+    match client_derives {
+        ClientDerives::Always => {}
+        ClientDerives::Feature(feature) => {
+            output.push_str(&format!("#[cfg(feature = \"{}\")]\n", feature));
+        }
+        ClientDerives::Skip => return String::new(),
+    }
+
+    output.push_str(&format!(
+        "impl core::fmt::Debug for {} {{\n",
+        struct_def.name
+    ));
+    output.push_str("    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {\n");
+    output.push_str(&format!(
+        "        f.debug_struct(\"{}\")\n",
+        struct_def.name
+    ));
+    for field in struct_def.data_fields() {
+        if field.is_sensitive() {
+            output.push_str(&format!(
+                "            .field(\"{}\", &\"[REDACTED]\")\n",
+                field.name
+            ));
+        } else {
+            output.push_str(&format!(
+                "            .field(\"{0}\", &self.{0})\n",
+                field.name
+            ));
+        }
+    }
+    output.push_str("            .finish()\n");
+    output.push_str("    }\n");
     output.push_str("}\n");
 
     output
@@ -516,12 +1559,7 @@ fn generate_enum_derives_with_context(enum_def: &EnumDefinition, use_anchor: boo
     let mut derives = Vec::new();
 
     // If using #[account], no derives needed (Anchor provides them)
-    if enum_def.metadata.solana
-        && enum_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-    {
+    if enum_def.metadata.solana && enum_def.metadata.is_account() {
         return derives;
     }
 
@@ -546,6 +1584,11 @@ fn generate_enum_derives_with_context(enum_def: &EnumDefinition, use_anchor: boo
     derives
 }
 
+/// Whether the struct is an Anchor event emitted to program logs
+fn is_event(struct_def: &StructDefinition) -> bool {
+    struct_def.metadata.is_event()
+}
+
 /// Generate derives with context about whether we're using Anchor
 fn generate_struct_derives_with_context(
     struct_def: &StructDefinition,
@@ -554,12 +1597,14 @@ fn generate_struct_derives_with_context(
     let mut derives = Vec::new();
 
     // If using #[account], no derives needed (Anchor provides them)
-    if struct_def.metadata.solana
-        && struct_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-    {
+    if struct_def.metadata.solana && struct_def.metadata.is_account() {
+        return derives;
+    }
+
+    // Anchor's #[event] derives the serialization traits itself
+    if struct_def.metadata.solana && use_anchor && is_event(struct_def) {
+        derives.push("Debug".to_string());
+        derives.push("Clone".to_string());
         return derives;
     }
 
@@ -591,11 +1636,7 @@ fn collect_enum_imports(enum_def: &EnumDefinition) -> HashSet<String> {
     // Check if we need Borsh or Anchor imports
     if enum_def.metadata.solana {
         // If using #[account], use Anchor imports (includes Borsh)
-        if enum_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-        {
+        if enum_def.metadata.is_account() {
             imports.insert("anchor_lang::prelude::*".to_string());
         } else {
             // Otherwise use Borsh directly
@@ -630,11 +1671,7 @@ fn collect_struct_imports(struct_def: &StructDefinition) -> HashSet<String> {
     // Check if we need Borsh or Anchor imports
     if struct_def.metadata.solana {
         // If using #[account], use Anchor imports (includes Borsh)
-        if struct_def
-            .metadata
-            .attributes
-            .contains(&"account".to_string())
-        {
+        if struct_def.metadata.is_account() {
             imports.insert("anchor_lang::prelude::*".to_string());
         } else {
             // Otherwise use Borsh directly
@@ -660,12 +1697,16 @@ fn collect_imports_from_type(type_info: &TypeInfo, imports: &mut HashSet<String>
             }
             // Note: Signature is mapped to String in Rust (no special import needed)
         }
-        TypeInfo::Array(inner) => {
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => {
             collect_imports_from_type(inner, imports);
         }
         TypeInfo::Option(inner) => {
             collect_imports_from_type(inner, imports);
         }
+        TypeInfo::Map(_, key, value) => {
+            collect_imports_from_type(key, imports);
+            collect_imports_from_type(value, imports);
+        }
         TypeInfo::UserDefined(_) => {
             // User-defined types are assumed to be in the same module
         }
@@ -699,10 +1740,20 @@ fn map_type_to_rust(type_info: &TypeInfo) -> String {
             let inner_type = map_type_to_rust(inner);
             format!("Vec<{}>", inner_type)
         }
+        TypeInfo::FixedArray(inner, len) => {
+            let inner_type = map_type_to_rust(inner);
+            format!("[{}; {}]", inner_type, len)
+        }
         TypeInfo::Option(inner) => {
             let inner_type = map_type_to_rust(inner);
             format!("Option<{}>", inner_type)
         }
+        TypeInfo::Map(kind, key, value) => format!(
+            "{}<{}, {}>",
+            kind.as_str(),
+            map_type_to_rust(key),
+            map_type_to_rust(value)
+        ),
         TypeInfo::UserDefined(type_name) => type_name.clone(),
     }
 }
@@ -712,7 +1763,7 @@ mod tests {
     use super::*;
     use crate::ir::{
         EnumDefinition, EnumVariantDefinition, FieldDefinition, Metadata, StructDefinition,
-        TypeDefinition, TypeInfo,
+        TypeDefinition, TypeInfo, TypeRole,
     };
 
     #[test]
@@ -724,11 +1775,13 @@ mod tests {
                     name: "id".to_string(),
                     type_info: TypeInfo::Primitive("u64".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
                 FieldDefinition {
                     name: "name".to_string(),
                     type_info: TypeInfo::Primitive("String".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
             ],
             metadata: Metadata::default(),
@@ -750,16 +1803,25 @@ mod tests {
                     name: "wallet".to_string(),
                     type_info: TypeInfo::Primitive("Pubkey".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
                 FieldDefinition {
                     name: "balance".to_string(),
                     type_info: TypeInfo::Primitive("u64".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
             ],
             metadata: Metadata {
                 solana: true,
                 attributes: vec!["account".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         });
 
@@ -780,6 +1842,7 @@ mod tests {
                 name: "email".to_string(),
                 type_info: TypeInfo::Option(Box::new(TypeInfo::Primitive("String".to_string()))),
                 optional: true,
+                attributes: vec![],
             }],
             metadata: Metadata::default(),
         });
@@ -796,6 +1859,7 @@ mod tests {
                 name: "members".to_string(),
                 type_info: TypeInfo::Array(Box::new(TypeInfo::Primitive("u64".to_string()))),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata::default(),
         });
@@ -824,6 +1888,59 @@ mod tests {
         assert!(code.contains("pub struct Post"));
     }
 
+    #[test]
+    fn streams_same_module_and_declares_program_id() {
+        let type_defs = vec![
+            TypeDefinition::Struct(StructDefinition {
+                name: "Vault".to_string(),
+                fields: vec![FieldDefinition {
+                    name: "owner".to_string(),
+                    type_info: TypeInfo::Primitive("PublicKey".to_string()),
+                    optional: false,
+                    attributes: vec![],
+                }],
+                metadata: Metadata {
+                    solana: true,
+                    attributes: vec!["account".to_string()],
+                    role: TypeRole::Account,
+                    zero_copy: false,
+                    version: None,
+                    lifecycle: Default::default(),
+                    skip: Vec::new(),
+                    docs: Vec::new(),
+                    test_vectors: Vec::new(),
+                },
+            }),
+            TypeDefinition::Struct(StructDefinition {
+                name: "Config".to_string(),
+                fields: vec![],
+                metadata: Metadata::default(),
+            }),
+        ];
+
+        let mut streamed = Vec::new();
+        generate_to(&type_defs, &mut streamed).unwrap();
+        assert_eq!(
+            String::from_utf8(streamed).unwrap(),
+            generate_module(&type_defs)
+        );
+
+        assert!(uses_anchor(&type_defs));
+        let mut program = Vec::new();
+        generate_program_to(&type_defs, "Prog1111", &mut program).unwrap();
+        let program = String::from_utf8(program).unwrap();
+        assert!(program.contains("use anchor_lang::prelude::*;\n\ndeclare_id!(\"Prog1111\");\n\n"));
+
+        let plain = &type_defs[1..];
+        let mut without_anchor = Vec::new();
+        generate_program_to(plain, "Prog1111", &mut without_anchor).unwrap();
+        assert!(!uses_anchor(plain));
+        assert_eq!(
+            String::from_utf8(without_anchor).unwrap(),
+            generate_module(plain)
+        );
+    }
+
     #[test]
     fn maps_publickey_to_pubkey() {
         let type_def = TypeDefinition::Struct(StructDefinition {
@@ -832,10 +1949,18 @@ mod tests {
                 name: "key".to_string(),
                 type_info: TypeInfo::Primitive("PublicKey".to_string()),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata {
                 solana: true,
                 attributes: vec![],
+                role: TypeRole::Plain,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         });
 
@@ -861,6 +1986,13 @@ mod tests {
             metadata: Metadata {
                 solana: true,
                 attributes: vec![],
+                role: TypeRole::Plain,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         });
 
@@ -871,6 +2003,9 @@ mod tests {
         assert!(code.contains("Active,"));
         assert!(code.contains("Paused,"));
         assert!(code.contains("Finished,"));
+        assert!(code.contains(
+            "    pub const ALL: [GameState; 3] = [GameState::Active, GameState::Paused, GameState::Finished];\n"
+        ));
     }
 
     #[test]
@@ -893,6 +2028,13 @@ mod tests {
             metadata: Metadata {
                 solana: true,
                 attributes: vec![],
+                role: TypeRole::Plain,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         });
 
@@ -903,6 +2045,7 @@ mod tests {
         assert!(code.contains("pub enum GameEvent"));
         assert!(code.contains("PlayerJoined(Pubkey),"));
         assert!(code.contains("ScoreUpdated(Pubkey, u64),"));
+        assert!(!code.contains("pub const ALL"));
     }
 
     #[test]
@@ -917,11 +2060,13 @@ mod tests {
                             name: "authority".to_string(),
                             type_info: TypeInfo::Primitive("Pubkey".to_string()),
                             optional: false,
+                            attributes: vec![],
                         },
                         FieldDefinition {
                             name: "max_players".to_string(),
                             type_info: TypeInfo::Primitive("u32".to_string()),
                             optional: false,
+                            attributes: vec![],
                         },
                     ],
                 },
@@ -932,11 +2077,13 @@ mod tests {
                             name: "player".to_string(),
                             type_info: TypeInfo::Primitive("Pubkey".to_string()),
                             optional: false,
+                            attributes: vec![],
                         },
                         FieldDefinition {
                             name: "new_score".to_string(),
                             type_info: TypeInfo::Primitive("u64".to_string()),
                             optional: false,
+                            attributes: vec![],
                         },
                     ],
                 },
@@ -944,6 +2091,13 @@ mod tests {
             metadata: Metadata {
                 solana: true,
                 attributes: vec![],
+                role: TypeRole::Plain,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         });
 
@@ -959,4 +2113,612 @@ mod tests {
         assert!(code.contains("player: Pubkey,"));
         assert!(code.contains("new_score: u64,"));
     }
+
+    #[test]
+    fn generates_zero_copy_layouts() {
+        let parse = |source: &str| {
+            crate::transform::transform_to_ir(crate::parser::parse_lumos_file(source).unwrap())
+                .unwrap()
+        };
+
+        let anchor = generate_module(&parse(
+            r#"
+            #[zero_copy]
+            struct Level { price: u64, size: u64 }
+
+            #[solana]
+            #[account]
+            #[zero_copy]
+            struct Book { market: PublicKey, best: Level }
+            "#,
+        ));
+        assert!(anchor.contains("#[zero_copy]\npub struct Level {"));
+        assert!(anchor.contains("#[account(zero_copy)]\npub struct Book {"));
+
+        let plain = generate_module(&parse(
+            "#[solana]\n#[zero_copy]\nstruct Level { price: u64, size: u64 }",
+        ));
+        assert!(plain.contains(
+            "#[derive(BorshSerialize, BorshDeserialize, Debug)]\n#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]\n#[repr(C)]\npub struct Level {"
+        ));
+    }
+
+    #[test]
+    fn generates_anchor_events() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                #[solana]
+                #[account]
+                struct Vault { owner: PublicKey, amount: u64 }
+
+                #[solana]
+                #[event]
+                struct Deposited { vault: PublicKey, amount: u64 }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("#[derive(Debug, Clone)]\n#[event]\npub struct Deposited {"));
+    }
+
+    #[test]
+    fn generates_state_hash_for_opted_in_types() {
+        let account = |attributes: Vec<&str>| {
+            TypeDefinition::Struct(StructDefinition {
+                name: "Vault".to_string(),
+                fields: vec![FieldDefinition {
+                    name: "amount".to_string(),
+                    type_info: TypeInfo::Primitive("u64".to_string()),
+                    optional: false,
+                    attributes: vec![],
+                }],
+                metadata: Metadata {
+                    solana: true,
+                    role: match attributes.contains(&"account") {
+                        true => TypeRole::Account,
+                        false => TypeRole::Plain,
+                    },
+                    attributes: attributes.into_iter().map(String::from).collect(),
+                    zero_copy: false,
+                    version: None,
+                    lifecycle: Default::default(),
+                    skip: Vec::new(),
+                    docs: Vec::new(),
+                    test_vectors: Vec::new(),
+                },
+            })
+        };
+
+        let code = generate_module(&[account(vec!["account", "state_hash"])]);
+        assert!(code.contains("impl Vault {"));
+        assert!(code.contains("pub fn state_hash(&self) -> [u8; 32]"));
+        assert!(code.contains("anchor_lang::solana_program::hash::hash(&bytes).to_bytes()"));
+
+        let code = generate_module(&[account(vec!["state_hash"])]);
+        assert!(code.contains("solana_program::hash::hash(&bytes)"));
+        assert!(!code.contains("anchor_lang::solana_program"));
+
+        assert!(!generate_module(&[account(vec!["account"])]).contains("state_hash"));
+    }
+
+    #[test]
+    fn generates_shims_for_earlier_versions() {
+        let source = r#"
+            #[solana]
+            #[account]
+            #[version(3)]
+            struct Profile {
+                owner: PublicKey,
+                #[since(2)]
+                bio: Option<String>,
+                #[since(3)]
+                level: u16,
+            }
+        "#;
+        let type_defs =
+            crate::transform::transform_to_ir(crate::parser::parse_lumos_file(source).unwrap())
+                .unwrap();
+
+        let code = generate_module(&type_defs);
+        assert!(code.contains("pub struct ProfileV1 {\n    pub owner: Pubkey,\n}"));
+        assert!(code.contains("pub struct ProfileV2 {"));
+        assert_eq!(code.matches("#[account]").count(), 1);
+        assert!(code.contains("impl From<ProfileV1> for ProfileV2 {"));
+        assert!(code.contains("            bio: Default::default(),\n        }"));
+        assert!(code.contains("impl From<ProfileV2> for Profile {"));
+        assert!(code.contains("pub fn decode_any_version(data: &[u8]) -> std::io::Result<Self>"));
+        assert!(code.contains(".map(|value| Self::from(ProfileV2::from(value)))"));
+        assert!(code.contains("pub const V2_TO_V3_GROWTH: usize = 2;"));
+        assert!(code.contains("pub fn migrate_v1_to_v2(data: &[u8]) -> std::io::Result<Vec<u8>>"));
+    }
+
+    #[test]
+    fn reads_rust_config_from_lumos_toml() {
+        let config = RustConfig::from_lumos_toml(
+            r#"
+            [rust]
+            debug-clone = "feature"
+            client-feature = "off-chain"
+
+            [rust.types.Order]
+            debug-clone = "always"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.client_derives_for("Vault"),
+            &ClientDerives::Feature("off-chain".to_string())
+        );
+        assert_eq!(config.client_derives_for("Order"), &ClientDerives::Always);
+
+        let err = RustConfig::from_lumos_toml("[rust]\ndebug-clone = \"never\"").unwrap_err();
+        assert!(err.to_string().contains("rust.debug-clone must be"));
+    }
+
+    #[test]
+    fn gates_debug_and_clone_per_config() {
+        let type_defs = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                #[solana]
+                struct Vault { amount: u64 }
+
+                #[solana]
+                enum Side { Bid, Ask }
+
+                struct Note { text: String }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let generate = |config: &RustConfig| {
+            let mut out = Vec::new();
+            generate_configured_to(&type_defs, None, config, &mut NoCache, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        // The default keeps today's output
+        assert_eq!(
+            generate(&RustConfig::default()),
+            generate_module(&type_defs)
+        );
+
+        let mut config = RustConfig {
+            client_derives: ClientDerives::Feature("client".to_string()),
+            ..RustConfig::default()
+        };
+        config
+            .type_overrides
+            .insert("Side".to_string(), ClientDerives::Skip);
+        let code = generate(&config);
+        assert!(code.contains(
+            "#[derive(BorshSerialize, BorshDeserialize)]\n#[cfg_attr(feature = \"client\", derive(Debug, Clone))]\npub struct Vault"
+        ));
+        assert!(code.contains("#[derive(BorshSerialize, BorshDeserialize)]\npub enum Side"));
+        assert!(code
+            .contains("#[cfg_attr(feature = \"client\", derive(Debug, Clone))]\npub struct Note"));
+    }
+
+    #[test]
+    fn derives_borsh_schema_per_config() {
+        let type_defs = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                #[solana]
+                #[account]
+                struct Vault { amount: u64 }
+
+                #[solana]
+                enum Side { Bid, Ask }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let generate = |config: &RustConfig| {
+            let mut out = Vec::new();
+            generate_configured_to(&type_defs, None, config, &mut NoCache, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let config = RustConfig::from_lumos_toml("[rust]\nborsh-schema = \"always\"").unwrap();
+        let code = generate(&config);
+        assert!(code.contains("#[derive(borsh::BorshSchema)]\n#[account]\npub struct Vault"));
+        assert!(code.contains(
+            "#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, borsh::BorshSchema)]\npub enum Side"
+        ));
+
+        let config = RustConfig::from_lumos_toml("[rust]\nborsh-schema = \"feature\"").unwrap();
+        assert!(generate(&config).contains(
+            "#[cfg_attr(feature = \"client\", derive(borsh::BorshSchema))]\n#[account]\npub struct Vault"
+        ));
+        assert!(!generate(&RustConfig::default()).contains("BorshSchema"));
+    }
+
+    #[test]
+    fn redacts_sensitive_fields_in_debug() {
+        let type_defs = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                #[solana]
+                struct Member {
+                    wallet: PublicKey,
+                    #[sensitive]
+                    email: String,
+                }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&type_defs);
+        assert!(generate(&type_defs[0]).contains(".field(\"email\", &\"[REDACTED]\")"));
+        assert!(
+            code.contains("#[derive(BorshSerialize, BorshDeserialize, Clone)]\npub struct Member")
+        );
+        assert!(code.contains("impl core::fmt::Debug for Member {"));
+        assert!(code.contains(".field(\"wallet\", &self.wallet)"));
+        assert!(code.contains(".field(\"email\", &\"[REDACTED]\")"));
+        assert!(!code.contains("&self.email"));
+
+        let config = RustConfig {
+            client_derives: ClientDerives::Feature("client".to_string()),
+            ..RustConfig::default()
+        };
+        let mut out = Vec::new();
+        generate_configured_to(&type_defs, None, &config, &mut NoCache, &mut out).unwrap();
+        let code = String::from_utf8(out).unwrap();
+        assert!(code.contains("#[cfg_attr(feature = \"client\", derive(Clone))]"));
+        assert!(code.contains("#[cfg(feature = \"client\")]\nimpl core::fmt::Debug for Member"));
+    }
+
+    #[test]
+    fn describes_schema_block_in_header() {
+        let file = crate::parser::parse_lumos_file(
+            "schema { name = \"staking\", version = \"1.2.0\", license = \"MIT\" }\n\
+             struct Pool { staked: u64 }",
+        )
+        .unwrap();
+        let config = RustConfig {
+            schema: file.metadata.clone(),
+            ..RustConfig::default()
+        };
+        let type_defs = crate::transform::transform_to_ir(file).unwrap();
+
+        let mut out = Vec::new();
+        generate_configured_to(&type_defs, None, &config, &mut NoCache, &mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with(
+            "// Auto-generated by LUMOS\n// DO NOT EDIT - Changes will be overwritten\n\
+             // Schema: staking 1.2.0\n// SPDX-License-Identifier: MIT\n\n#[derive"
+        ));
+        assert!(generate_module(&type_defs).contains("overwritten\n\n#[derive"));
+    }
+
+    #[test]
+    fn generates_fixed_arrays() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "#[solana]\nstruct Seeds { hash: [u8; 32], owners: [PublicKey; 2], slots: [Option<u8>; 3] }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("pub hash: [u8; 32],"));
+        assert!(code.contains("pub owners: [Pubkey; 2],"));
+        assert!(code.contains("pub slots: [Option<u8>; 3],"));
+    }
+
+    #[test]
+    fn delegates_codec_fields() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file("#[solana]\nstruct Vault { #[codec(rust = \"crate::codecs::compact_u64\", ts = \"./codecs/compactU64\")] amount: u64, owner: PublicKey }").unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains(
+            "    /// Layout owned by `crate::codecs::compact_u64`, not by the schema\n    #[borsh(serialize_with = \"crate::codecs::compact_u64::serialize\", deserialize_with = \"crate::codecs::compact_u64::deserialize\")]\n    pub amount: u64,"
+        ));
+        assert!(code.contains("    pub owner: Pubkey,"));
+    }
+
+    #[test]
+    fn generates_maps() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "#[solana]\nstruct Pool { stakes: HashMap<PublicKey, u64>, tiers: BTreeMap<u8, String>, claims: HashMap<PublicKey, Option<u64>> }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("use std::collections::BTreeMap;"));
+        assert!(code.contains("use std::collections::HashMap;"));
+        assert!(code.contains("pub stakes: HashMap<Pubkey, u64>,"));
+        assert!(code.contains("pub tiers: BTreeMap<u8, String>,"));
+        assert!(code.contains("pub claims: HashMap<Pubkey, Option<u64>>,"));
+    }
+
+    #[test]
+    fn generates_display_and_from_str_on_request() {
+        let type_defs = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                #[solana]
+                #[display]
+                enum Side { Bid, Ask }
+
+                #[solana]
+                #[display]
+                struct Authority { key: PublicKey }
+
+                #[solana]
+                enum Plain { A, B }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&type_defs);
+        assert!(code.contains("impl core::fmt::Display for Side {"));
+        assert!(code.contains("            Side::Bid => \"Bid\",\n"));
+        assert!(code.contains("            \"Ask\" => Ok(Side::Ask),\n"));
+        assert!(code.contains("_ => Err(format!(\"unknown Side variant: {}\", s)),"));
+
+        assert!(code.contains("core::fmt::Display::fmt(&self.key, f)"));
+        assert!(code.contains("type Err = <Pubkey as core::str::FromStr>::Err;"));
+        assert!(code.contains("Ok(Self { key: s.parse()? })"));
+
+        assert!(!code.contains("impl core::fmt::Display for Plain"));
+        assert!(generate(&type_defs[0]).contains("impl core::str::FromStr for Side {"));
+    }
+
+    #[test]
+    fn from_str_does_not_use_the_anchor_result_alias() {
+        let type_defs = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                #[solana]
+                #[display]
+                enum Side { Bid, Ask }
+
+                #[solana]
+                #[display]
+                struct Authority { key: PublicKey }
+
+                #[solana]
+                #[account]
+                struct Order { side: Side, authority: Authority }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&type_defs);
+        assert!(code.contains("use anchor_lang::prelude::*;"));
+        assert_eq!(
+            code.matches("    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {\n")
+                .count(),
+            2
+        );
+        assert!(!code.contains("-> Result<Self, Self::Err>"));
+    }
+
+    #[test]
+    fn generates_accounts_context_for_instructions() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                #[solana]
+                #[account]
+                #[zero_copy]
+                struct Vault { balance: u64 }
+
+                #[solana]
+                #[instruction]
+                struct Deposit {
+                    #[account(mut, has_one = owner)]
+                    vault: Vault,
+                    #[signer]
+                    owner: PublicKey,
+                    #[account]
+                    mint: PublicKey,
+                    amount: u64,
+                }
+
+                #[solana]
+                #[instruction]
+                struct Ping {}
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("pub struct Deposit {\n    pub amount: u64,\n}\n"));
+        assert!(code.contains(
+            "/// Accounts of the `deposit` instruction\n#[derive(Accounts)]\n#[instruction(amount: u64)]\npub struct DepositAccounts<'info> {\n    #[account(mut, has_one = owner)]\n    pub vault: AccountLoader<'info, Vault>,\n    pub owner: Signer<'info>,\n    /// CHECK: left unchecked by the LUMOS schema\n    pub mint: UncheckedAccount<'info>,\n}\n"
+        ));
+        assert!(code.contains("#[derive(Accounts)]\npub struct PingAccounts {}\n"));
+    }
+
+    #[test]
+    fn generates_validate_for_constrained_fields() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "#[solana] struct Profile { #[min(3)] #[max(32)] name: String, #[max(8)] tags: Vec<u8>, #[range(0, 10000)] fee_bps: u16, #[range(-5, 5)] bias: Option<i64>, plain: u8 }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("    pub fn validate(&self) -> std::result::Result<(), String> {\n"));
+        assert!(code.contains(
+            "        if !(3..=32).contains(&self.name.len()) {\n            return Err(\"Profile.name must be 3 to 32 bytes long\".to_string());\n"
+        ));
+        assert!(code.contains("if self.tags.len() > 8 {"));
+        assert!(code.contains("if !(0..=10000).contains(&self.fee_bps) {"));
+        assert!(code.contains(
+            "        if let Some(value) = &self.bias {\n            if !(-5..=5).contains(value) {\n"
+        ));
+        assert!(!code.contains("self.plain"));
+
+        let plain = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file("#[solana] struct Plain { x: u8 }").unwrap(),
+        )
+        .unwrap();
+        assert!(!generate_module(&plain).contains("fn validate"));
+    }
+
+    #[test]
+    fn validates_nested_structs() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                struct Limits { #[range(1, 10)] level: u8 }
+                struct Wrapper { inner: Limits }
+                struct Plain { x: u8 }
+                struct Profile {
+                    #[max(8)]
+                    name: String,
+                    limits: Limits,
+                    history: Vec<Limits>,
+                    backup: Option<Limits>,
+                    slots: Vec<Option<Limits>>,
+                    by_id: BTreeMap<u8, Limits>,
+                    wrapper: Wrapper,
+                    plain: Plain,
+                }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains(
+            "        self.limits.validate()?;\n\
+             \x20       for item in &self.history {\n\
+             \x20           item.validate()?;\n\
+             \x20       }\n\
+             \x20       if let Some(item) = &self.backup {\n\
+             \x20           item.validate()?;\n\
+             \x20       }\n\
+             \x20       for item in self.slots.iter().flatten() {\n\
+             \x20           item.validate()?;\n\
+             \x20       }\n\
+             \x20       for item in self.by_id.values() {\n\
+             \x20           item.validate()?;\n\
+             \x20       }\n\
+             \x20       self.wrapper.validate()?;\n\
+             \x20       Ok(())\n"
+        ));
+        // Wrapper has no bounds of its own, but holds a struct with some
+        assert!(code.contains("impl Wrapper {"));
+        assert!(code.contains("        self.inner.validate()?;\n"));
+        assert!(!code.contains("impl Plain {"));
+        assert!(!code.contains("self.plain"));
+    }
+
+    #[test]
+    fn validate_does_not_use_the_anchor_result_alias() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "#[solana] #[account] struct Player { #[max(16)] name: String, level: u8 }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("use anchor_lang::prelude::*;"));
+        assert!(code.contains("    pub fn validate(&self) -> std::result::Result<(), String> {\n"));
+        assert!(!code.contains("-> Result<(), String>"));
+    }
+
+    #[test]
+    fn carries_doc_comments() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                /// A user's vault.
+                ///
+                /// Holds lamports.
+                #[solana]
+                struct Vault {
+                    /// Who may withdraw
+                    owner: PublicKey,
+                    /**
+                     * Lamports held
+                     */
+                    balance: u64,
+                }
+
+                /// Lifecycle of a vault
+                #[solana]
+                enum Status { Open, Closed { /// When it closed
+                    at: i64 } }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains(
+            "/// A user's vault.\n///\n/// Holds lamports.\n#[derive(BorshSerialize, BorshDeserialize"
+        ));
+        assert!(code.contains("    /// Who may withdraw\n    pub owner: Pubkey,\n"));
+        assert!(code.contains("    /// Lamports held\n    pub balance: u64,\n"));
+        assert!(code.contains("/// Lifecycle of a vault\n#[derive("));
+        assert!(code.contains("        /// When it closed\n        at: i64,\n"));
+    }
+
+    #[test]
+    fn generates_tests_for_test_vectors() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                #[solana]
+                enum State { Open, Frozen(u64) }
+
+                #[solana]
+                #[test_vector(owner = "11111111111111111111111111111111", tags = ["a"], state = {"Frozen": {"0": 3}}, memo = null)]
+                struct Vault { owner: PublicKey, tags: Vec<String>, state: State, memo: Option<u16> }
+
+                #[test_vector(x = 1)]
+                struct Plain { x: u8 }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("#[cfg(test)]\nmod test_vectors {\n    use super::*;\n"));
+        assert!(code.contains("    fn vault_1() {\n        let value = Vault { owner: \"11111111111111111111111111111111\".parse::<Pubkey>().unwrap(), tags: vec![\"a\".to_string()], state: State::Frozen(3), memo: None };\n"));
+        assert!(code.contains(&format!(
+            "        let expected: &[u8] = &[{}1, 0, 0, 0, 1, 0, 0, 0, 97, 1, 3, 0, 0, 0, 0, 0, 0, 0, 0];\n",
+            "0, ".repeat(32)
+        )));
+        assert!(code.contains("        let decoded = Vault::try_from_slice(expected).unwrap();\n"));
+        // Without Borsh derives, plain structs can't be checked
+        assert!(!code.contains("fn plain_1()"));
+    }
 }
@@ -0,0 +1,1157 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Solana Kit codec generator
+//!
+//! Alternative TypeScript backend for projects on `@solana/kit` (web3.js v2),
+//! selected with `codecs = "kit"` in the `[typescript]` section of
+//! `lumos.toml`. Instead of `@coral-xyz/borsh` schemas, every `#[solana]`
+//! type gets `get<Type>Encoder`, `get<Type>Decoder` and `get<Type>Codec`
+//! functions built from Kit's codecs, with the same Borsh wire format.
+//!
+//! Each type is declared twice: `Type` is what decoders return and
+//! `TypeArgs` is what encoders accept, since Kit decodes 64- and 128-bit
+//! integers as `bigint` but encodes `number` too. Enums keep the
+//! `{ kind: ... }` unions of the Borsh backend, options are `T | null`, and
+//! public keys are Kit `Address`es. `#[account]` structs also get the
+//! discriminator-checking `decode<Type>` of the Borsh backend.
+//!
+//! With `runtime = "react-native"`, the same code imports from the
+//! standalone `@solana/codecs` and `@solana/addresses` packages, and
+//! `#[state_hash]` helpers hash with a bundled SHA-256 instead of WebCrypto,
+//! so the module runs in React Native, Expo and edge runtimes without
+//! polyfills. Nothing in either variant uses Node's `Buffer`.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::{solana_kit, typescript::Runtime};
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "#[solana]\nstruct Vault { owner: PublicKey, amount: u64 }",
+//! )?)?;
+//!
+//! let ts_code = solana_kit::generate_module(&ir, Runtime::Node);
+//! assert!(ts_code.contains("export type VaultArgs = {\n  owner: Address;\n  amount: number | bigint;\n};"));
+//! assert!(ts_code.contains("export function getVaultDecoder(): Decoder<Vault> {"));
+//! assert!(ts_code.contains("['amount', getU64Decoder()],"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::ast::SchemaMetadata;
+use crate::generators::cache::{cached_snippet, snippet_key, NoCache, SnippetCache};
+use crate::generators::summary::SchemaSummary;
+use crate::generators::typescript::Runtime;
+use crate::generators::typescript::{
+    account_discriminator_helpers, codec_module, decodes_account, doc_block, function_prefix,
+    generate_account_decoder, generate_enum_display, generate_enum_values,
+    generate_sensitive_fields, generate_struct_validation, generate_test_vectors,
+    has_sensitive_fields, has_test_vectors, parse_integer, REDACT_HELPERS,
+};
+use crate::ir::{
+    self, EnumDefinition, EnumVariantDefinition, StructDefinition, TypeDefinition, TypeInfo,
+};
+use crate::migration_generator::{self, TypeScriptCodec};
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+/// Package every import comes from on Node
+const PACKAGE: &str = "@solana/kit";
+
+/// Standalone packages for React Native: addresses, and codecs for everything else
+const ADDRESSES_PACKAGE: &str = "@solana/addresses";
+const CODECS_PACKAGE: &str = "@solana/codecs";
+
+/// Names `@solana/addresses` exports
+const ADDRESS_NAMES: [&str; 5] = [
+    "Address",
+    "address",
+    "getAddressDecoder",
+    "getAddressEncoder",
+    "isAddress",
+];
+
+/// The all-zero address, `PublicKey.default` in web3.js v1
+const DEFAULT_ADDRESS: &str = "11111111111111111111111111111111";
+
+/// Which half of a codec to build
+#[derive(Clone, Copy)]
+enum Side {
+    Encoder,
+    Decoder,
+}
+
+impl Side {
+    fn name(self) -> &'static str {
+        match self {
+            Side::Encoder => "Encoder",
+            Side::Decoder => "Decoder",
+        }
+    }
+}
+
+/// Names imported from `@solana/kit`, values and types kept apart
+#[derive(Default)]
+struct Imports {
+    values: BTreeSet<String>,
+    types: BTreeSet<&'static str>,
+}
+
+impl Imports {
+    fn value(&mut self, name: String) -> String {
+        self.values.insert(name.clone());
+        name
+    }
+}
+
+/// Generate the complete module for `type_defs`
+pub fn generate_module(type_defs: &[TypeDefinition], runtime: Runtime) -> String {
+    let mut output = Vec::new();
+    generate_to(type_defs, runtime, &mut output).expect("writing to a Vec cannot fail");
+    String::from_utf8(output).expect("generated code is valid UTF-8")
+}
+
+/// Write the module for `type_defs` to `out`, one type at a time
+pub fn generate_to<W: Write>(
+    type_defs: &[TypeDefinition],
+    runtime: Runtime,
+    out: &mut W,
+) -> io::Result<()> {
+    generate_cached_to(type_defs, runtime, &mut NoCache, out)
+}
+
+/// Like [`generate_to`], reusing unchanged types' code from `cache`
+///
+/// Snippets don't depend on `runtime`, only the imports and shared helpers do.
+pub fn generate_cached_to<W: Write>(
+    type_defs: &[TypeDefinition],
+    runtime: Runtime,
+    cache: &mut dyn SnippetCache,
+    out: &mut W,
+) -> io::Result<()> {
+    write_module(type_defs, runtime, None, cache, out)
+}
+
+/// The module for `type_defs`, with `schema` described in the file header
+pub(crate) fn write_module<W: Write>(
+    type_defs: &[TypeDefinition],
+    runtime: Runtime,
+    schema: Option<&SchemaMetadata>,
+    cache: &mut dyn SnippetCache,
+    out: &mut W,
+) -> io::Result<()> {
+    let type_defs = &*ir::for_target(type_defs, "typescript");
+
+    out.write_all(b"// Auto-generated by LUMOS\n")?;
+    out.write_all(b"// DO NOT EDIT - Changes will be overwritten\n")?;
+    for line in schema.map(SchemaMetadata::header_lines).unwrap_or_default() {
+        writeln!(out, "// {}", line)?;
+    }
+    out.write_all(b"\n")?;
+
+    let mut imports = Imports::default();
+    for type_def in type_defs {
+        collect_imports(type_def, &mut imports);
+    }
+    match runtime {
+        Runtime::Node | Runtime::Deno | Runtime::Bun => {
+            write_imports(out, &imports, &runtime.package(PACKAGE), |_| true)?
+        }
+        Runtime::ReactNative => {
+            let from_addresses = |name: &str| ADDRESS_NAMES.contains(&name);
+            write_imports(out, &imports, ADDRESSES_PACKAGE, from_addresses)?;
+            write_imports(out, &imports, CODECS_PACKAGE, |name| !from_addresses(name))?;
+        }
+    }
+
+    // Hand-written codecs of `#[codec]` fields
+    let mut modules = BTreeSet::new();
+    for type_def in type_defs {
+        if let TypeDefinition::Struct(s) = type_def {
+            if s.metadata.solana {
+                modules.extend(s.fields.iter().filter_map(codec_module));
+            }
+        }
+    }
+    for (module, path) in &modules {
+        writeln!(
+            out,
+            "import * as {} from '{}';",
+            module,
+            runtime.local_module(path)
+        )?;
+    }
+    if !modules.is_empty() {
+        out.write_all(b"\n")?;
+    }
+
+    if type_defs.iter().any(|t| t.metadata().has_state_hash()) {
+        let helpers = match runtime {
+            Runtime::Node | Runtime::Deno | Runtime::Bun => STATE_HASH_HELPERS,
+            Runtime::ReactNative => PORTABLE_STATE_HASH_HELPERS,
+        };
+        out.write_all(helpers.as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+
+    if type_defs.iter().any(has_sensitive_fields) {
+        out.write_all(REDACT_HELPERS.as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+
+    if type_defs.iter().any(decodes_account) {
+        out.write_all(account_discriminator_helpers("ReadonlyUint8Array").as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+
+    if type_defs.iter().any(has_test_vectors) {
+        out.write_all(CHECK_TEST_VECTORS_HELPER.as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+
+    let validated = ir::validated_structs(type_defs);
+    for (i, type_def) in type_defs.iter().enumerate() {
+        if i > 0 {
+            out.write_all(b"\n")?;
+        }
+        let key = snippet_key("typescript-kit", &validated, type_def);
+        let code = cached_snippet(cache, key, || generate_type(type_def, &validated));
+        out.write_all(code.as_bytes())?;
+    }
+
+    // Vectors depend on the types they reference, so they aren't cached
+    for type_def in type_defs {
+        if let TypeDefinition::Struct(s) = type_def {
+            if has_test_vectors(type_def) {
+                out.write_all(b"\n")?;
+                out.write_all(
+                    generate_test_vectors(s, type_defs, TypeScriptCodec::SolanaKit).as_bytes(),
+                )?;
+            }
+        }
+    }
+
+    write!(out, "\n{}", SchemaSummary::new(type_defs).footer())
+}
+
+/// Import the names `from_package` selects from `package`, if there are any
+fn write_imports<W: Write>(
+    out: &mut W,
+    imports: &Imports,
+    package: &str,
+    from_package: impl Fn(&str) -> bool,
+) -> io::Result<()> {
+    let values: Vec<&str> = imports
+        .values
+        .iter()
+        .map(String::as_str)
+        .filter(|name| from_package(name))
+        .collect();
+    let types: Vec<&str> = imports
+        .types
+        .iter()
+        .copied()
+        .filter(|name| from_package(name))
+        .collect();
+    if values.is_empty() && types.is_empty() {
+        return Ok(());
+    }
+
+    out.write_all(b"import {\n")?;
+    for name in values {
+        writeln!(out, "  {},", name)?;
+    }
+    for name in types {
+        writeln!(out, "  type {},", name)?;
+    }
+    writeln!(out, "}} from '{}';\n", package)
+}
+
+/// Shared by the `check<Type>TestVectors` functions of `#[test_vector]` structs
+const CHECK_TEST_VECTORS_HELPER: &str = r#"function checkTestVectors<T>(
+  name: string,
+  encoder: Encoder<T>,
+  vectors: readonly { value: T; hex: string }[],
+): void {
+  vectors.forEach(({ value, hex }, index) => {
+    const actual = Array.from(encoder.encode(value), (byte) => byte.toString(16).padStart(2, '0')).join('');
+    if (actual !== hex) {
+      throw new Error(`#[test_vector] ${index + 1} of ${name} encodes to ${actual}, expected ${hex}`);
+    }
+  });
+}
+"#;
+
+/// Shared by the `<type>StateHash` helpers of `#[state_hash]` types
+const STATE_HASH_HELPERS: &str = r#"async function sha256(bytes: ReadonlyUint8Array): Promise<Uint8Array> {
+  return new Uint8Array(await globalThis.crypto.subtle.digest('SHA-256', new Uint8Array(bytes)));
+}
+"#;
+
+/// [`STATE_HASH_HELPERS`] without WebCrypto, which React Native lacks
+const PORTABLE_STATE_HASH_HELPERS: &str = r#"const SHA256_K = Uint32Array.of(
+  0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+  0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+  0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+  0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+  0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+  0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+  0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+  0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+);
+
+function rotr(x: number, n: number): number {
+  return (x >>> n) | (x << (32 - n));
+}
+
+async function sha256(bytes: ReadonlyUint8Array): Promise<Uint8Array> {
+  const length = bytes.length;
+  const padded = new Uint8Array(((length + 72) >> 6) << 6);
+  padded.set(bytes);
+  padded[length] = 0x80;
+  const view = new DataView(padded.buffer);
+  view.setUint32(padded.length - 8, Math.floor(length / 0x20000000));
+  view.setUint32(padded.length - 4, length << 3);
+
+  const h = Uint32Array.of(
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+  );
+  const w = new Uint32Array(64);
+  for (let offset = 0; offset < padded.length; offset += 64) {
+    for (let i = 0; i < 16; i++) w[i] = view.getUint32(offset + i * 4);
+    for (let i = 16; i < 64; i++) {
+      const s0 = rotr(w[i - 15], 7) ^ rotr(w[i - 15], 18) ^ (w[i - 15] >>> 3);
+      const s1 = rotr(w[i - 2], 17) ^ rotr(w[i - 2], 19) ^ (w[i - 2] >>> 10);
+      w[i] = w[i - 16] + s0 + w[i - 7] + s1;
+    }
+
+    let [a, b, c, d, e, f, g, k] = h;
+    for (let i = 0; i < 64; i++) {
+      const t1 = k + (rotr(e, 6) ^ rotr(e, 11) ^ rotr(e, 25)) + ((e & f) ^ (~e & g)) + SHA256_K[i] + w[i];
+      const t2 = (rotr(a, 2) ^ rotr(a, 13) ^ rotr(a, 22)) + ((a & b) ^ (a & c) ^ (b & c));
+      k = g;
+      g = f;
+      f = e;
+      e = (d + t1) >>> 0;
+      d = c;
+      c = b;
+      b = a;
+      a = (t1 + t2) >>> 0;
+    }
+    h[0] += a;
+    h[1] += b;
+    h[2] += c;
+    h[3] += d;
+    h[4] += e;
+    h[5] += f;
+    h[6] += g;
+    h[7] += k;
+  }
+
+  const digest = new Uint8Array(32);
+  const digestView = new DataView(digest.buffer);
+  h.forEach((word, i) => digestView.setUint32(i * 4, word));
+  return digest;
+}
+"#;
+
+/// Record everything the code for `type_def` imports
+fn collect_imports(type_def: &TypeDefinition, imports: &mut Imports) {
+    let metadata = type_def.metadata();
+    let mut types: Vec<&TypeInfo> = Vec::new();
+    // Types encoded by generated codecs, leaving out `#[codec]` fields
+    let mut encoded: Vec<&TypeInfo> = Vec::new();
+    match type_def {
+        TypeDefinition::Struct(s) => {
+            types.extend(s.fields.iter().map(|f| &f.type_info));
+            encoded.extend(
+                s.fields
+                    .iter()
+                    .filter(|f| codec_module(f).is_none())
+                    .map(|f| &f.type_info),
+            );
+            if s.metadata.has_version_shims() {
+                let defaults = s.fields.iter().filter(|f| f.since_version() > 1);
+                if defaults.clone().any(|f| is_public_key(&f.type_info)) {
+                    imports.values.insert("address".to_string());
+                }
+                if metadata.solana {
+                    imports.types.insert("ReadonlyUint8Array");
+                }
+            }
+            if s.metadata.has_display() && s.fields.iter().any(|f| is_public_key(&f.type_info)) {
+                imports.values.insert("isAddress".to_string());
+            }
+        }
+        TypeDefinition::Enum(e) => {
+            for variant in &e.variants {
+                match variant {
+                    EnumVariantDefinition::Unit { .. } => {}
+                    EnumVariantDefinition::Tuple { types: t, .. } => types.extend(t),
+                    EnumVariantDefinition::Struct { fields, .. } => {
+                        types.extend(fields.iter().map(|f| &f.type_info))
+                    }
+                }
+            }
+            encoded.extend(&types);
+        }
+    }
+
+    for type_info in &types {
+        ts_type(type_info, false, imports);
+    }
+    if metadata.has_state_hash() || decodes_account(type_def) {
+        imports.types.insert("ReadonlyUint8Array");
+    }
+    if !metadata.solana {
+        return;
+    }
+
+    imports.values.insert("combineCodec".to_string());
+    imports.types.extend(["Codec", "Decoder", "Encoder"]);
+    for side in [Side::Encoder, Side::Decoder] {
+        for type_info in &encoded {
+            codec(type_info, side, imports);
+        }
+        match type_def {
+            TypeDefinition::Struct(_) => {
+                imports.value(format!("getStruct{}", side.name()));
+            }
+            TypeDefinition::Enum(e) => {
+                imports.value(format!("getDiscriminatedUnion{}", side.name()));
+                for variant in &e.variants {
+                    let name = match variant {
+                        EnumVariantDefinition::Unit { .. } => "Unit",
+                        _ => "Struct",
+                    };
+                    imports.value(format!("get{}{}", name, side.name()));
+                }
+            }
+        }
+    }
+}
+
+/// Declarations and codecs of one type
+/// `validated` names the structs of the module that have a `validate{Name}`
+fn generate_type(type_def: &TypeDefinition, validated: &[&str]) -> String {
+    let mut code = match type_def {
+        TypeDefinition::Struct(s) => generate_struct(s, validated),
+        TypeDefinition::Enum(e) => generate_enum(e),
+    };
+    if type_def.metadata().has_state_hash() && type_def.metadata().solana {
+        code.push('\n');
+        code.push_str(&generate_state_hash(type_def.name()));
+    }
+    if let TypeDefinition::Struct(s) = type_def {
+        if has_sensitive_fields(type_def) {
+            code.push('\n');
+            code.push_str(&generate_sensitive_fields(s));
+        }
+        if s.metadata.has_version_shims() && s.metadata.solana {
+            code.push_str(&generate_version_shims(s, validated));
+        }
+        if decodes_account(type_def) {
+            let decode = match s.metadata.has_version_shims() {
+                true => format!("decode{}AnyVersion(data.subarray(8))", s.name),
+                false => format!("get{}Decoder().decode(data, 8)", s.name),
+            };
+            code.push('\n');
+            code.push_str(&generate_account_decoder(
+                type_def,
+                "ReadonlyUint8Array",
+                &decode,
+            ));
+        }
+    }
+    code
+}
+
+fn generate_struct(struct_def: &StructDefinition, validated: &[&str]) -> String {
+    let name = &struct_def.name;
+    let imports = &mut Imports::default();
+    let body = |args: bool, imports: &mut Imports| {
+        let mut body = String::from("{\n");
+        for field in struct_def.data_fields() {
+            body.push_str(&doc_block(&field.docs(), "  "));
+            body.push_str(&format!(
+                "  {}: {};\n",
+                field.name,
+                ts_type(&field.type_info, args, imports)
+            ));
+        }
+        body.push('}');
+        body
+    };
+
+    let mut output = doc_block(&struct_def.metadata.docs, "");
+    output.push_str(&type_alias(name, &body(false, imports)));
+    output.push('\n');
+    output.push_str(&args_type(
+        name,
+        &body(false, imports),
+        &body(true, imports),
+    ));
+
+    if struct_def.metadata.solana {
+        for side in [Side::Encoder, Side::Decoder] {
+            output.push('\n');
+            output.push_str(&side_signature(name, side));
+            output.push_str(&format!("  return getStruct{}([\n", side.name()));
+            for field in struct_def.data_fields() {
+                match codec_module(field) {
+                    Some((module, path)) => output.push_str(&format!(
+                        "    ['{}', {}.get{}()], // layout owned by {}\n",
+                        field.name,
+                        module,
+                        side.name(),
+                        path
+                    )),
+                    None => output.push_str(&format!(
+                        "    ['{}', {}],\n",
+                        field.name,
+                        codec(&field.type_info, side, imports)
+                    )),
+                }
+            }
+            output.push_str("  ]);\n}\n");
+        }
+        output.push('\n');
+        output.push_str(&doc_block(&struct_def.metadata.docs, ""));
+        output.push_str(&generate_codec(name));
+    }
+
+    if struct_def.metadata.has_display() {
+        output.push('\n');
+        output.push_str(&generate_struct_display(struct_def));
+    }
+
+    if struct_def.is_validated(validated) {
+        output.push('\n');
+        output.push_str(&generate_struct_validation(struct_def, true, validated));
+    }
+
+    output
+}
+
+fn generate_enum(enum_def: &EnumDefinition) -> String {
+    let name = &enum_def.name;
+    let imports = &mut Imports::default();
+    let union = |args: bool, imports: &mut Imports| {
+        let mut union = String::new();
+        for variant in &enum_def.variants {
+            union.push_str(&format!("\n  | {{ kind: '{}'", variant.name()));
+            for (field, type_info) in variant_fields(variant) {
+                union.push_str(&format!(
+                    "; {}: {}",
+                    field,
+                    ts_type(type_info, args, imports)
+                ));
+            }
+            union.push_str(" }");
+        }
+        union
+    };
+
+    let mut output = doc_block(&enum_def.metadata.docs, "");
+    output.push_str(&type_alias(name, &union(false, imports)));
+    output.push('\n');
+    output.push_str(&args_type(
+        name,
+        &union(false, imports),
+        &union(true, imports),
+    ));
+
+    if enum_def.metadata.solana {
+        for side in [Side::Encoder, Side::Decoder] {
+            output.push('\n');
+            output.push_str(&side_signature(name, side));
+            output.push_str(&format!(
+                "  return getDiscriminatedUnion{}(\n    [\n",
+                side.name()
+            ));
+            for variant in &enum_def.variants {
+                let variant_codec = match variant {
+                    EnumVariantDefinition::Unit { .. } => format!("getUnit{}()", side.name()),
+                    _ => {
+                        let fields: Vec<String> = variant_fields(variant)
+                            .into_iter()
+                            .map(|(field, type_info)| {
+                                format!("['{}', {}]", field, codec(type_info, side, imports))
+                            })
+                            .collect();
+                        format!("getStruct{}([{}])", side.name(), fields.join(", "))
+                    }
+                };
+                output.push_str(&format!(
+                    "      ['{}', {}],\n",
+                    variant.name(),
+                    variant_codec
+                ));
+            }
+            output.push_str("    ],\n    { discriminator: 'kind' },\n  );\n}\n");
+        }
+        output.push('\n');
+        output.push_str(&doc_block(&enum_def.metadata.docs, ""));
+        output.push_str(&generate_codec(name));
+    }
+
+    if enum_def.is_unit_only() {
+        output.push('\n');
+        output.push_str(&generate_enum_values(enum_def));
+    }
+    if enum_def.metadata.has_display() {
+        output.push('\n');
+        output.push_str(&generate_enum_display(enum_def));
+    }
+
+    output
+}
+
+/// `<name>Args`, an alias of `<name>` when encoding accepts nothing more
+fn args_type(name: &str, decoded: &str, args: &str) -> String {
+    if decoded == args {
+        format!("export type {}Args = {};\n", name, name)
+    } else {
+        type_alias(&format!("{}Args", name), args)
+    }
+}
+
+/// `export type <name> = <body>;`, with unions starting on the next line
+fn type_alias(name: &str, body: &str) -> String {
+    let separator = if body.starts_with('\n') { " =" } else { " = " };
+    format!("export type {}{}{};\n", name, separator, body)
+}
+
+/// Fields of a variant; tuple fields are named `field0`, `field1`, ...
+fn variant_fields(variant: &EnumVariantDefinition) -> Vec<(String, &TypeInfo)> {
+    match variant {
+        EnumVariantDefinition::Unit { .. } => Vec::new(),
+        EnumVariantDefinition::Tuple { types, .. } => types
+            .iter()
+            .enumerate()
+            .map(|(i, type_info)| (format!("field{}", i), type_info))
+            .collect(),
+        EnumVariantDefinition::Struct { fields, .. } => fields
+            .iter()
+            .map(|f| (f.name.clone(), &f.type_info))
+            .collect(),
+    }
+}
+
+fn side_signature(name: &str, side: Side) -> String {
+    let value = match side {
+        Side::Encoder => format!("{}Args", name),
+        Side::Decoder => name.to_string(),
+    };
+    format!(
+        "export function get{name}{side}(): {side}<{value}> {{\n",
+        side = side.name()
+    )
+}
+
+fn generate_codec(name: &str) -> String {
+    format!(
+        "export function get{name}Codec(): Codec<{name}Args, {name}> {{\n  return combineCodec(get{name}Encoder(), get{name}Decoder());\n}}\n"
+    )
+}
+
+/// TypeScript type of a value; `args` selects what encoders accept
+fn ts_type(type_info: &TypeInfo, args: bool, imports: &mut Imports) -> String {
+    match type_info {
+        TypeInfo::Primitive(type_name) => match type_name.as_str() {
+            "u64" | "i64" | "u128" | "i128" if args => "number | bigint".to_string(),
+            "u64" | "i64" | "u128" | "i128" => "bigint".to_string(),
+            "bool" => "boolean".to_string(),
+            "String" | "Signature" => "string".to_string(),
+            "Pubkey" | "PublicKey" => {
+                imports.types.insert("Address");
+                "Address".to_string()
+            }
+            "Keypair" => {
+                imports.types.insert("ReadonlyUint8Array");
+                "ReadonlyUint8Array".to_string()
+            }
+            _ => "number".to_string(),
+        },
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => {
+            format!("Array<{}>", ts_type(inner, args, imports))
+        }
+        TypeInfo::Option(inner) => format!("{} | null", ts_type(inner, args, imports)),
+        TypeInfo::Map(_, key, value) => format!(
+            "Map<{}, {}>",
+            ts_type(key, args, imports),
+            ts_type(value, args, imports)
+        ),
+        TypeInfo::UserDefined(name) if args => format!("{}Args", name),
+        TypeInfo::UserDefined(name) => name.clone(),
+    }
+}
+
+/// Expression building the encoder or decoder of a value
+fn codec(type_info: &TypeInfo, side: Side, imports: &mut Imports) -> String {
+    let s = side.name();
+    match type_info {
+        TypeInfo::Primitive(type_name) => match type_name.as_str() {
+            // Matches the Rust side, which stores signatures as base58 strings
+            "String" | "Signature" => format!(
+                "{}({}(), {}())",
+                imports.value(format!("add{}SizePrefix", s)),
+                imports.value(format!("getUtf8{}", s)),
+                imports.value(format!("getU32{}", s))
+            ),
+            "Pubkey" | "PublicKey" => format!("{}()", imports.value(format!("getAddress{}", s))),
+            "Keypair" => format!(
+                "{}({}(), 64)",
+                imports.value(format!("fix{}Size", s)),
+                imports.value(format!("getBytes{}", s))
+            ),
+            "bool" => format!("{}()", imports.value(format!("getBoolean{}", s))),
+            primitive => format!(
+                "{}()",
+                imports.value(format!("get{}{}", primitive.to_ascii_uppercase(), s))
+            ),
+        },
+        TypeInfo::Array(inner) => format!(
+            "{}({})",
+            imports.value(format!("getArray{}", s)),
+            codec(inner, side, imports)
+        ),
+        TypeInfo::FixedArray(inner, len) => format!(
+            "{}({}, {{ size: {} }})",
+            imports.value(format!("getArray{}", s)),
+            codec(inner, side, imports),
+            len
+        ),
+        TypeInfo::Option(inner) => format!(
+            "{}({})",
+            imports.value(format!("getNullable{}", s)),
+            codec(inner, side, imports)
+        ),
+        TypeInfo::Map(_, key, value) => format!(
+            "{}({}, {})",
+            imports.value(format!("getMap{}", s)),
+            codec(key, side, imports),
+            codec(value, side, imports)
+        ),
+        TypeInfo::UserDefined(name) => format!("get{}{}()", name, s),
+    }
+}
+
+fn is_public_key(type_info: &TypeInfo) -> bool {
+    matches!(type_info, TypeInfo::Primitive(t) if t == "Pubkey" || t == "PublicKey")
+}
+
+/// Async counterpart of the Rust `state_hash` method
+fn generate_state_hash(type_name: &str) -> String {
+    format!(
+        "/** SHA-256 of the canonical Borsh encoding, matching `{name}::state_hash` in Rust */\n\
+         export async function {function}StateHash(value: {name}Args): Promise<Uint8Array> {{\n  \
+         return sha256(get{name}Encoder().encode(value));\n}}\n",
+        name = type_name,
+        function = function_prefix(type_name)
+    )
+}
+
+/// Earlier layouts of a `#[version(n)]` struct, mirroring the Rust shims
+fn generate_version_shims(struct_def: &StructDefinition, validated: &[&str]) -> String {
+    let current = struct_def.metadata.version.unwrap_or(1);
+    let name = &struct_def.name;
+    let version_name = |version: u32| {
+        if version == current {
+            name.clone()
+        } else {
+            format!("{}V{}", name, version)
+        }
+    };
+
+    let mut output = String::new();
+    for version in 1..current {
+        let old = struct_def.at_version(version);
+        output.push_str(&format!(
+            "\n/** `{}` as laid out in schema version {} */\n",
+            name, version
+        ));
+        output.push_str(&generate_struct(&old, validated));
+
+        let next = version + 1;
+        let added: Vec<String> = struct_def
+            .fields
+            .iter()
+            .filter(|field| field.since_version() == next)
+            .map(|field| format!("{}: {}", field.name, default_value(&field.type_info)))
+            .collect();
+        output.push_str(&format!(
+            "\n/** Upgrade a version {} `{}` to version {} */\n",
+            version, name, next
+        ));
+        output.push_str(&format!(
+            "export function upgrade{}(value: {}): {} {{\n",
+            old.name,
+            old.name,
+            version_name(next)
+        ));
+        output.push_str(&format!("  return {{ ...value, {} }};\n", added.join(", ")));
+        output.push_str("}\n");
+    }
+
+    output.push_str(&format!(
+        "\n/** Decode Borsh data written under any schema version of `{}`, upgrading it to the current layout */\n",
+        name
+    ));
+    output.push_str(&format!(
+        "export function decode{}AnyVersion(data: ReadonlyUint8Array): {} {{\n",
+        name, name
+    ));
+    for version in (1..=current).rev() {
+        let mut decoded = format!("get{}Decoder().decode(data)", version_name(version));
+        for from in version..current {
+            decoded = format!("upgrade{}V{}({})", name, from, decoded);
+        }
+
+        if version > 1 {
+            output.push_str("  try {\n");
+            output.push_str(&format!("    return {};\n", decoded));
+            output.push_str("  } catch {\n");
+            output.push_str("    // Written under an earlier version\n");
+            output.push_str("  }\n");
+        } else {
+            output.push_str(&format!("  return {};\n", decoded));
+        }
+    }
+    output.push_str("}\n");
+    output.push_str(&migration_generator::generate_typescript(
+        struct_def,
+        TypeScriptCodec::SolanaKit,
+    ));
+
+    output
+}
+
+/// Decoded value given to a field added in a later schema version
+fn default_value(type_info: &TypeInfo) -> String {
+    match type_info {
+        TypeInfo::Option(_) => "null".to_string(),
+        TypeInfo::Array(_) => "[]".to_string(),
+        TypeInfo::Map(..) => "new Map()".to_string(),
+        TypeInfo::FixedArray(inner, len) => {
+            format!(
+                "Array.from({{ length: {} }}, () => {})",
+                len,
+                default_value(inner)
+            )
+        }
+        TypeInfo::Primitive(type_name) => match type_name.as_str() {
+            "u64" | "i64" | "u128" | "i128" => "0n".to_string(),
+            "bool" => "false".to_string(),
+            "String" | "Signature" => "''".to_string(),
+            "Pubkey" | "PublicKey" => format!("address('{}')", DEFAULT_ADDRESS),
+            "Keypair" => "new Uint8Array(64)".to_string(),
+            _ => "0".to_string(),
+        },
+        // Rejected by the transform, which only allows defaultable types
+        TypeInfo::UserDefined(_) => "undefined".to_string(),
+    }
+}
+
+/// `toString`/`parse` helpers of a `#[display]` single-field struct, matching
+/// Rust's `Display`/`FromStr`
+fn generate_struct_display(struct_def: &StructDefinition) -> String {
+    let [field] = struct_def.fields.as_slice() else {
+        return String::new();
+    };
+    let TypeInfo::Primitive(primitive) = &field.type_info else {
+        return String::new();
+    };
+    let name = &struct_def.name;
+    let field_name = &field.name;
+    let bigint = matches!(primitive.as_str(), "u64" | "i64" | "u128" | "i128");
+
+    let parse_body = match primitive.as_str() {
+        "u8" | "u16" | "u32" | "u64" | "u128" => parse_integer(field_name, r"/^\+?\d+$/", bigint),
+        "i8" | "i16" | "i32" | "i64" | "i128" => {
+            parse_integer(field_name, r"/^[+-]?\d+$/", bigint)
+        }
+        "f32" | "f64" => format!(
+            "  const parsed = Number(text);\n  if (text.trim() === '' || Number.isNaN(parsed)) return undefined;\n  return {{ {}: parsed }};\n",
+            field_name
+        ),
+        "bool" => format!(
+            "  if (text !== 'true' && text !== 'false') return undefined;\n  return {{ {}: text === 'true' }};\n",
+            field_name
+        ),
+        "Pubkey" | "PublicKey" => format!(
+            "  return isAddress(text) ? {{ {}: text }} : undefined;\n",
+            field_name
+        ),
+        _ => format!("  return {{ {}: text }};\n", field_name),
+    };
+
+    let function_name = function_prefix(name);
+    format!(
+        "/** `{name}` as text */\n\
+         export function {function_name}ToString(value: {name}): string {{\n  \
+         return value.{field_name}.toString();\n}}\n\n\
+         /** Parse a `{name}`, or `undefined` if `text` is not valid */\n\
+         export function parse{name}(text: string): {name} | undefined {{\n\
+         {parse_body}}}\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn generate(source: &str) -> String {
+        generate_module(
+            &transform_to_ir(parse_lumos_file(source).unwrap()).unwrap(),
+            Runtime::Node,
+        )
+    }
+
+    #[test]
+    fn generates_struct_codecs() {
+        let code = generate(
+            r#"
+            #[solana]
+            struct Position { x: i32, y: i32 }
+
+            #[solana]
+            #[account]
+            struct Player {
+                owner: PublicKey,
+                name: String,
+                lamports: u64,
+                position: Position,
+                items: [u16],
+                guild: Option<PublicKey>,
+            }
+            "#,
+        );
+
+        assert!(code.contains(
+            "import {\n  addDecoderSizePrefix,\n  addEncoderSizePrefix,\n  combineCodec,\n  getAddressDecoder,\n  getAddressEncoder,\n  getArrayDecoder,\n  getArrayEncoder,\n  getI32Decoder,\n  getI32Encoder,\n  getNullableDecoder,\n  getNullableEncoder,\n  getStructDecoder,\n  getStructEncoder,\n  getU16Decoder,\n  getU16Encoder,\n  getU32Decoder,\n  getU32Encoder,\n  getU64Decoder,\n  getU64Encoder,\n  getUtf8Decoder,\n  getUtf8Encoder,\n  type Address,\n  type Codec,\n  type Decoder,\n  type Encoder,\n  type ReadonlyUint8Array,\n} from '@solana/kit';\n"
+        ));
+        assert!(code.contains("export type PositionArgs = Position;\n"));
+        assert!(code.contains(
+            "export type Player = {\n  owner: Address;\n  name: string;\n  lamports: bigint;\n  position: Position;\n  items: Array<number>;\n  guild: Address | null;\n};\n"
+        ));
+        assert!(code.contains("  lamports: number | bigint;\n  position: PositionArgs;\n"));
+        assert!(code.contains(
+            "export function getPlayerEncoder(): Encoder<PlayerArgs> {\n  return getStructEncoder([\n    ['owner', getAddressEncoder()],\n    ['name', addEncoderSizePrefix(getUtf8Encoder(), getU32Encoder())],\n    ['lamports', getU64Encoder()],\n    ['position', getPositionEncoder()],\n    ['items', getArrayEncoder(getU16Encoder())],\n    ['guild', getNullableEncoder(getAddressEncoder())],\n  ]);\n}\n"
+        ));
+        assert!(code.contains("['items', getArrayDecoder(getU16Decoder())],"));
+        assert!(code.contains(
+            "export function getPlayerCodec(): Codec<PlayerArgs, Player> {\n  return combineCodec(getPlayerEncoder(), getPlayerDecoder());\n}\n"
+        ));
+        assert!(!code.contains("@coral-xyz/borsh"));
+    }
+
+    #[test]
+    fn generates_enum_codecs() {
+        let code = generate(
+            r#"
+            #[solana]
+            enum GameEvent {
+                Started,
+                Moved(u8, u64),
+                Finished { winner: PublicKey },
+            }
+
+            #[solana]
+            enum Phase { Lobby, Playing }
+            "#,
+        );
+
+        assert!(code.contains(
+            "export type GameEvent =\n  | { kind: 'Started' }\n  | { kind: 'Moved'; field0: number; field1: bigint }\n  | { kind: 'Finished'; winner: Address };\n"
+        ));
+        assert!(code.contains(
+            "export type GameEventArgs =\n  | { kind: 'Started' }\n  | { kind: 'Moved'; field0: number; field1: number | bigint }"
+        ));
+        assert!(code.contains(
+            "  return getDiscriminatedUnionDecoder(\n    [\n      ['Started', getUnitDecoder()],\n      ['Moved', getStructDecoder([['field0', getU8Decoder()], ['field1', getU64Decoder()]])],\n      ['Finished', getStructDecoder([['winner', getAddressDecoder()]])],\n    ],\n    { discriminator: 'kind' },\n  );\n"
+        ));
+        assert!(code.contains("export type PhaseArgs = Phase;\n"));
+        assert!(code.contains("export const PHASE_VALUES: readonly Phase[] = ["));
+    }
+
+    #[test]
+    fn generates_helpers() {
+        let code = generate(
+            r#"
+            #[solana]
+            #[account]
+            #[state_hash]
+            #[version(2)]
+            struct Profile {
+                owner: PublicKey,
+                #[since(2)]
+                delegate: PublicKey,
+            }
+
+            #[solana]
+            #[display]
+            struct Mint { address: PublicKey }
+            "#,
+        );
+
+        assert!(code.contains("return sha256(getProfileEncoder().encode(value));"));
+        assert!(code.contains("export function getProfileV1Decoder(): Decoder<ProfileV1> {"));
+        assert!(code.contains(
+            "return { ...value, delegate: address('11111111111111111111111111111111') };"
+        ));
+        assert!(code.contains(
+            "    return getProfileDecoder().decode(data);\n  } catch {\n    // Written under an earlier version\n  }\n  return upgradeProfileV1(getProfileV1Decoder().decode(data));\n"
+        ));
+        assert!(code.contains(
+            "export function migrateProfileV1ToV2(data: ReadonlyUint8Array): ReadonlyUint8Array {"
+        ));
+        assert!(code.contains("return isAddress(text) ? { address: text } : undefined;"));
+        assert!(code.contains("  address,\n"));
+        assert!(code.contains("  isAddress,\n"));
+        assert!(code.contains("  type ReadonlyUint8Array,\n"));
+    }
+
+    #[test]
+    fn react_native_imports_standalone_packages() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                "#[solana]\n#[state_hash]\nstruct Vault { owner: PublicKey, amount: u64 }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir, Runtime::ReactNative);
+        assert!(code.contains(
+            "import {\n  getAddressDecoder,\n  getAddressEncoder,\n  type Address,\n} from '@solana/addresses';\n\nimport {\n  combineCodec,\n"
+        ));
+        assert!(code.contains("  type ReadonlyUint8Array,\n} from '@solana/codecs';\n"));
+        assert!(code.contains("const SHA256_K = Uint32Array.of("));
+        for node_only in ["@solana/kit", "Buffer", "crypto.subtle"] {
+            assert!(!code.contains(node_only), "{}", node_only);
+        }
+
+        // Only the imports and helpers differ
+        let node = generate_module(&ir, Runtime::Node);
+        let types = |code: &str| code[code.find("export type Vault").unwrap()..].to_string();
+        assert_eq!(types(&code), types(&node));
+    }
+
+    #[test]
+    fn delegates_codec_fields() {
+        let code = generate("#[solana]\nstruct Vault { #[codec(rust = \"crate::codecs::compact_u64\", ts = \"./codecs/compactU64\")] amount: u64, owner: PublicKey }");
+        assert!(code.contains("import * as codecsCompactU64 from './codecs/compactU64';"));
+        assert!(code.contains(
+            "['amount', codecsCompactU64.getEncoder()], // layout owned by ./codecs/compactU64"
+        ));
+        assert!(code.contains("['amount', codecsCompactU64.getDecoder()],"));
+        assert!(!code.contains("getU64Encoder"));
+    }
+
+    #[test]
+    fn validates_64_bit_ranges_as_bigints() {
+        let code = generate("#[solana]\nstruct Order { #[range(1, 1000000)] amount: u64, #[range(0, 100)] pct: u8 }");
+        assert!(code.contains("export function validateOrder(value: Order): string | undefined {"));
+        assert!(code.contains("if (value.amount < 1n || value.amount > 1000000n) {"));
+        assert!(code.contains("if (value.pct < 0 || value.pct > 100) {"));
+    }
+
+    #[test]
+    fn validates_nested_structs() {
+        let code = generate(
+            "#[solana]\nstruct Order { #[range(1, 1000000)] amount: u64 }\n\
+             #[solana]\nstruct Book { orders: Vec<Vec<Order>> }",
+        );
+        assert!(code.contains("export function validateBook(value: Book): string | undefined {"));
+        assert!(code.contains(
+            "  for (const item of value.orders) {\n\
+             \x20   for (const item2 of item) {\n\
+             \x20     error = validateOrder(item2);\n"
+        ));
+    }
+
+    #[test]
+    fn carries_doc_comments() {
+        let code = generate(
+            "/// A vault\n#[solana]\nstruct Vault {\n    /// Who may withdraw\n    owner: PublicKey,\n}",
+        );
+        assert!(code.contains("/** A vault */\nexport type Vault = {\n  /** Who may withdraw */\n  owner: Address;\n};"));
+        assert!(code.contains("/** A vault */\nexport function getVaultCodec()"));
+    }
+
+    #[test]
+    fn generates_account_decoders_with_discriminator_check() {
+        let code = generate(
+            r#"
+            #[solana]
+            #[account]
+            struct PlayerAccount { owner: PublicKey }
+
+            #[solana]
+            #[account]
+            #[version(2)]
+            struct Profile { name: String, #[since(2)] bio: Option<String> }
+
+            #[solana]
+            #[account]
+            #[zero_copy]
+            struct Board { cells: [u8; 9] }
+            "#,
+        );
+
+        assert_eq!(
+            code.matches("export class AccountDiscriminatorError")
+                .count(),
+            1
+        );
+        assert!(code.contains(
+            "function checkAccountDiscriminator(account: string, expected: Uint8Array, data: ReadonlyUint8Array): void {"
+        ));
+        assert!(code.contains(
+            "export const PLAYER_ACCOUNT_DISCRIMINATOR = Uint8Array.from([224, 184, 224, 50, 98, 72, 48, 236]);\n"
+        ));
+        assert!(code.contains(
+            "export function decodePlayerAccount(data: ReadonlyUint8Array): PlayerAccount {\n  checkAccountDiscriminator('PlayerAccount', PLAYER_ACCOUNT_DISCRIMINATOR, data);\n  return getPlayerAccountDecoder().decode(data, 8);\n}\n"
+        ));
+        assert!(code.contains("  return decodeProfileAnyVersion(data.subarray(8));\n"));
+        assert!(!code.contains("decodeBoard("));
+        assert!(!code.contains("Buffer"));
+
+        assert!(!generate("#[solana] struct Move { x: u8 }").contains("AccountDiscriminatorError"));
+    }
+
+    #[test]
+    fn generates_test_vector_checks() {
+        let code = generate(
+            r#"
+            #[solana]
+            enum State { Open, Closed { at: i64 } }
+
+            #[solana]
+            #[test_vector(owner = "11111111111111111111111111111111", balance = 5, state = {"Closed": {"at": -1}}, memo = null)]
+            struct Vault { owner: PublicKey, balance: u64, state: State, memo: Option<String> }
+            "#,
+        );
+
+        assert!(
+            code.contains("function checkTestVectors<T>(\n  name: string,\n  encoder: Encoder<T>,")
+        );
+        assert!(code.contains(
+            "export const VAULT_TEST_VECTORS: readonly { value: VaultArgs; hex: string }[] = [\n"
+        ));
+        assert!(code.contains(&format!(
+            "  {{ value: {{ owner: '11111111111111111111111111111111' as Address, balance: 5n, state: {{ kind: 'Closed', at: -1n }}, memo: null }}, hex: '{}050000000000000001ffffffffffffffff00' }},\n",
+            "00".repeat(32)
+        )));
+        assert!(code.contains(
+            "export function checkVaultTestVectors(): void {\n  checkTestVectors('Vault', getVaultEncoder(), VAULT_TEST_VECTORS);\n}\n"
+        ));
+        assert!(!code.contains("Buffer"));
+
+        assert!(!generate("#[solana] struct Plain { x: u8 }").contains("checkTestVectors"));
+    }
+}
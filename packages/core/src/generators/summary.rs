@@ -0,0 +1,273 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Schema summary footer for generated modules
+//!
+//! The Rust and TypeScript modules end with a comment summarizing the schema
+//! they were generated from: the number of types, the total size of the
+//! fixed-size types, a schema hash, and one hash per type. Reviewers see
+//! which types a regeneration touched from the footer diff alone, and
+//! `lumos check` compares the footer of a stale file with the current schema
+//! to report which types changed, not just which files.
+//!
+//! Hashes are computed from the IR with a hash that is stable across Rust
+//! releases, so they only change when a type does.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::generators::summary::{changed_types, SchemaSummary, TypeChange};
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = |source| transform::transform_to_ir(parser::parse_lumos_file(source).unwrap()).unwrap();
+//! let old = SchemaSummary::new(&ir("struct A { x: u8 }\nstruct B { y: u8 }"));
+//! let new = SchemaSummary::new(&ir("struct A { x: u16 }\nstruct B { y: u8 }"));
+//!
+//! let parsed = SchemaSummary::parse(&old.footer()).unwrap();
+//! assert_eq!(changed_types(&parsed, &new), [TypeChange::Changed("A".to_string())]);
+//! ```
+
+use crate::generators::cache::StableHasher;
+use crate::ir::TypeDefinition;
+use crate::size_calculator::{SizeCalculator, SizeInfo};
+use std::hash::{Hash, Hasher};
+
+/// First line of the footer
+const MARKER: &str = "// LUMOS schema summary: ";
+
+/// Prefix of each per-type line
+const TYPE_PREFIX: &str = "//   ";
+
+/// Summary of the schema a module was generated from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaSummary {
+    /// Type names and their hashes, in schema order
+    pub types: Vec<(String, String)>,
+
+    /// Total size in bytes of the types with a fixed Borsh size
+    pub fixed_bytes: usize,
+
+    /// Hash of the whole schema
+    pub hash: String,
+}
+
+/// How a type differs between two summaries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeChange {
+    /// Only in the newer schema
+    Added(String),
+
+    /// Only in the older schema
+    Removed(String),
+
+    /// In both, with different definitions
+    Changed(String),
+}
+
+impl SchemaSummary {
+    /// Summarize `type_defs`
+    pub fn new(type_defs: &[TypeDefinition]) -> Self {
+        let calculator = SizeCalculator::new(type_defs);
+        let fixed_bytes = type_defs
+            .iter()
+            .filter_map(|t| calculator.account_size(t.name()))
+            .map(|size| match size.total_bytes {
+                SizeInfo::Fixed(bytes) => bytes,
+                SizeInfo::Variable { .. } => 0,
+            })
+            .sum();
+
+        let types: Vec<(String, String)> = type_defs
+            .iter()
+            .map(|t| (t.name().to_string(), hex_hash(t)))
+            .collect();
+        let hash = hex_hash(&types);
+
+        Self {
+            types,
+            fixed_bytes,
+            hash,
+        }
+    }
+
+    /// The footer, as `//` comment lines
+    pub fn footer(&self) -> String {
+        let mut footer = format!(
+            "{}{}, {}, hash {}\n",
+            MARKER,
+            counted(self.types.len(), "type"),
+            counted(self.fixed_bytes, "fixed byte"),
+            self.hash
+        );
+        for (name, hash) in &self.types {
+            footer.push_str(&format!("{}{} {}\n", TYPE_PREFIX, name, hash));
+        }
+        footer
+    }
+
+    /// Read the footer back from generated code, if it has one
+    pub fn parse(code: &str) -> Option<Self> {
+        let start = code.rfind(MARKER)?;
+        let mut lines = code[start..].lines();
+
+        let header = lines.next()?.strip_prefix(MARKER)?;
+        let mut parts = header.split(", ");
+        let count = parse_counted(parts.next()?, "type")?;
+        let fixed_bytes = parse_counted(parts.next()?, "fixed byte")?;
+        let hash = parts.next()?.strip_prefix("hash ")?.to_string();
+
+        let types = lines
+            .filter_map(|line| line.strip_prefix(TYPE_PREFIX)?.split_once(' '))
+            .map(|(name, hash)| (name.to_string(), hash.to_string()))
+            .collect::<Vec<_>>();
+        if types.len() != count {
+            return None;
+        }
+
+        Some(Self {
+            types,
+            fixed_bytes,
+            hash,
+        })
+    }
+}
+
+/// Types added, removed or changed from `old` to `new`, in schema order
+pub fn changed_types(old: &SchemaSummary, new: &SchemaSummary) -> Vec<TypeChange> {
+    let find = |summary: &SchemaSummary, name: &str| {
+        summary
+            .types
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, hash)| hash.clone())
+    };
+
+    let mut changes: Vec<TypeChange> = new
+        .types
+        .iter()
+        .filter_map(|(name, hash)| match find(old, name) {
+            None => Some(TypeChange::Added(name.clone())),
+            Some(old_hash) if old_hash != *hash => Some(TypeChange::Changed(name.clone())),
+            Some(_) => None,
+        })
+        .collect();
+    changes.extend(
+        old.types
+            .iter()
+            .filter(|(name, _)| find(new, name).is_none())
+            .map(|(name, _)| TypeChange::Removed(name.clone())),
+    );
+    changes
+}
+
+/// `count` followed by `noun`, plural unless `count` is 1
+fn counted(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        _ => format!("{} {}s", count, noun),
+    }
+}
+
+/// Read back a [`counted`] phrase; footers written before the singular form
+/// existed say `1 types`, so the plural is accepted for any count
+fn parse_counted(text: &str, noun: &str) -> Option<usize> {
+    let (count, rest) = text.split_once(' ')?;
+    let count = count.parse().ok()?;
+    match rest.strip_suffix('s') {
+        Some(plural) if plural == noun => Some(count),
+        None if rest == noun && count == 1 => Some(count),
+        _ => None,
+    }
+}
+
+fn hex_hash(value: impl Hash) -> String {
+    let mut hasher = StableHasher::default();
+    value.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn summary(source: &str) -> SchemaSummary {
+        SchemaSummary::new(&transform_to_ir(parse_lumos_file(source).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn summarizes_types_and_fixed_sizes() {
+        let summary = summary(
+            r#"
+            #[solana]
+            #[account]
+            struct Vault { owner: PublicKey, amount: u64 }
+
+            struct Note { text: String }
+
+            enum Side { Bid, Ask }
+            "#,
+        );
+
+        assert_eq!(summary.types.len(), 3);
+        // 8 + 32 + 8 for Vault and Side's discriminant, as `check-size` counts them;
+        // Note is variable
+        assert_eq!(summary.fixed_bytes, 52);
+
+        let footer = summary.footer();
+        assert!(footer.starts_with("// LUMOS schema summary: 3 types, 52 fixed bytes, hash "));
+        assert_eq!(footer.lines().count(), 4);
+        assert_eq!(SchemaSummary::parse(&footer), Some(summary));
+    }
+
+    #[test]
+    fn hashes_are_stable_and_per_type() {
+        let a = summary("struct A { x: u8 }\nstruct B { y: u8 }");
+        assert_eq!(a, summary("struct A { x: u8 }\n\n\nstruct B { y: u8 }"));
+
+        let b = summary("struct B { y: u8 }\nstruct C { z: u8 }\nstruct A { x: u64 }");
+        assert_ne!(a.hash, b.hash);
+        assert_eq!(
+            changed_types(&a, &b),
+            [
+                TypeChange::Added("C".to_string()),
+                TypeChange::Changed("A".to_string()),
+            ]
+        );
+        assert_eq!(
+            changed_types(&b, &a),
+            [
+                TypeChange::Changed("A".to_string()),
+                TypeChange::Removed("C".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_singular_for_one_type_or_byte() {
+        let summary = summary("struct Flag { on: bool }");
+        assert_eq!(summary.fixed_bytes, 1);
+
+        let footer = summary.footer();
+        assert!(footer.starts_with("// LUMOS schema summary: 1 type, 1 fixed byte, hash "));
+        assert_eq!(SchemaSummary::parse(&footer), Some(summary.clone()));
+
+        // Footers written by earlier versions still parse
+        let old = footer.replacen("1 type, 1 fixed byte", "1 types, 1 fixed bytes", 1);
+        assert_eq!(SchemaSummary::parse(&old), Some(summary));
+        assert_eq!(
+            SchemaSummary::parse(&footer.replacen("1 type,", "2 type,", 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn ignores_code_without_a_footer() {
+        assert_eq!(SchemaSummary::parse("// Auto-generated by LUMOS\n"), None);
+        assert_eq!(
+            SchemaSummary::parse("// LUMOS schema summary: 2 types, 0 fixed bytes, hash 00\n"),
+            None
+        );
+    }
+}
@@ -48,6 +48,8 @@
 //! | `bool` | `boolean` | `borsh.bool` | - |
 //! | `PublicKey` | `PublicKey` | `borsh.publicKey` | From `@solana/web3.js` |
 //! | `[T]` | `T[]` | `borsh.vec(...)` | Dynamic arrays |
+//! | `[T; N]` | `T[]` | `borsh.array(..., N)` | Fixed-size arrays |
+//! | `HashMap<K, V>`, `BTreeMap<K, V>` | `Map<K, V>` | `borsh.map(K, V)` | Maps |
 //! | `Option<T>` | `T \| undefined` | `borsh.option(...)` | Optional fields |
 //!
 //! ## Example
@@ -78,10 +80,160 @@
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 
+use crate::ast::SchemaMetadata;
+use crate::borsh_decoder::DecodedValue;
+use crate::encoding::encode_hex;
+use crate::generators::cache::{cached_snippet, snippet_key, NoCache, SnippetCache};
+use crate::generators::solana_kit;
+use crate::generators::summary::SchemaSummary;
+use crate::instruction_decoder::{instruction_discriminator, to_snake_case};
 use crate::ir::{
-    EnumDefinition, EnumVariantDefinition, StructDefinition, TypeDefinition, TypeInfo,
+    self, EnumDefinition, EnumVariantDefinition, FieldDefinition, StructDefinition, TypeDefinition,
+    TypeInfo,
 };
+use crate::migration_generator::{self, TypeScriptCodec};
+use crate::test_vector;
 use std::collections::HashSet;
+use std::io::{self, Write};
+
+/// Serialization library the generated TypeScript builds on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Codecs {
+    /// `@coral-xyz/borsh` schemas, for web3.js v1 projects
+    #[default]
+    Borsh,
+
+    /// `@solana/kit` codecs (see [`solana_kit`](super::solana_kit)), for web3.js v2 projects
+    Kit,
+}
+
+/// JavaScript runtime the generated module targets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Runtime {
+    /// Node.js and browsers
+    #[default]
+    Node,
+
+    /// React Native, Expo and edge runtimes: no `Buffer`, Node or WebCrypto APIs
+    ReactNative,
+
+    /// Deno: `npm:` package specifiers and explicit `node:` built-ins
+    Deno,
+
+    /// Bun: explicit `node:` built-ins
+    Bun,
+}
+
+impl Runtime {
+    /// Import specifier of an npm package, e.g. `npm:@solana/kit` on Deno
+    pub fn package(self, name: &str) -> String {
+        match self {
+            Runtime::Deno if !name.starts_with("node:") => format!("npm:{}", name),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Import specifier of a generated TypeScript file, with the `.ts`
+    /// extension Deno and Bun resolve by
+    pub fn local_module(self, path: &str) -> String {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        match self {
+            Runtime::Deno | Runtime::Bun if path.starts_with('.') && !file_name.contains('.') => {
+                format!("{}.ts", path)
+            }
+            _ => path.to_string(),
+        }
+    }
+
+    /// Whether `Buffer` must be imported rather than used as a global
+    fn imports_buffer(self) -> bool {
+        matches!(self, Runtime::Deno | Runtime::Bun)
+    }
+}
+
+/// TypeScript generation settings, from the `[typescript]` section of a `lumos.toml`
+#[derive(Debug, Clone, Default)]
+pub struct TypeScriptConfig {
+    /// Serialization library of the generated module
+    pub codecs: Codecs,
+
+    /// Runtime the module must run in; only [`Codecs::Kit`] supports [`Runtime::ReactNative`]
+    pub runtime: Runtime,
+
+    /// `schema { ... }` block described in the file header; set from the
+    /// schema rather than `lumos.toml`
+    pub schema: Option<SchemaMetadata>,
+}
+
+impl TypeScriptConfig {
+    /// Read the `[typescript]` section of a `lumos.toml`
+    ///
+    /// ```toml
+    /// [typescript]
+    /// codecs = "kit"              # "borsh" (default) or "kit"
+    /// runtime = "react-native"    # "node" (default), "react-native", "deno" or "bun"
+    /// ```
+    ///
+    /// `@coral-xyz/borsh` needs `Buffer`, so `runtime = "react-native"`
+    /// selects the Kit codecs and rejects `codecs = "borsh"`.
+    pub fn from_lumos_toml(input: &str) -> Result<Self, toml::de::Error> {
+        use serde::de::Error;
+
+        let mut config = Self::default();
+        let value: toml::Value = toml::from_str(input)?;
+
+        let Some(typescript) = value.get("typescript") else {
+            return Ok(config);
+        };
+
+        let codecs = match typescript.get("codecs").map(toml::Value::as_str) {
+            None => None,
+            Some(Some("borsh")) => Some(Codecs::Borsh),
+            Some(Some("kit")) => Some(Codecs::Kit),
+            Some(_) => {
+                return Err(toml::de::Error::custom(
+                    "typescript.codecs must be \"borsh\" or \"kit\"",
+                ))
+            }
+        };
+        config.runtime =
+            match typescript.get("runtime").map(toml::Value::as_str) {
+                None | Some(Some("node")) => Runtime::Node,
+                Some(Some("react-native")) => Runtime::ReactNative,
+                Some(Some("deno")) => Runtime::Deno,
+                Some(Some("bun")) => Runtime::Bun,
+                Some(_) => return Err(toml::de::Error::custom(
+                    "typescript.runtime must be \"node\", \"react-native\", \"deno\" or \"bun\"",
+                )),
+            };
+
+        config.codecs = match (codecs, config.runtime) {
+            (Some(Codecs::Borsh), Runtime::ReactNative) => {
+                return Err(toml::de::Error::custom(
+                    "typescript.runtime = \"react-native\" needs codecs = \"kit\"; @coral-xyz/borsh depends on Node's Buffer",
+                ))
+            }
+            (None, Runtime::ReactNative) => Codecs::Kit,
+            (codecs, _) => codecs.unwrap_or_default(),
+        };
+
+        Ok(config)
+    }
+}
+
+/// Like [`generate_cached_to`], with the backend `config` selects
+pub fn generate_configured_to<W: Write>(
+    type_defs: &[TypeDefinition],
+    config: &TypeScriptConfig,
+    cache: &mut dyn SnippetCache,
+    out: &mut W,
+) -> io::Result<()> {
+    let schema = config.schema.as_ref();
+    match config.codecs {
+        Codecs::Borsh => write_module(type_defs, config.runtime, schema, cache, out),
+        Codecs::Kit => solana_kit::write_module(type_defs, config.runtime, schema, cache, out),
+    }
+}
 
 /// Generate TypeScript code from a type definition
 pub fn generate(type_def: &TypeDefinition) -> String {
@@ -117,6 +269,16 @@ fn generate_struct(struct_def: &StructDefinition) -> String {
         output.push_str(&generate_struct_borsh_schema(struct_def));
     }
 
+    if struct_def.metadata.has_display() {
+        output.push('\n');
+        output.push_str(&generate_struct_display(struct_def));
+    }
+
+    if struct_def.has_constraints() {
+        output.push('\n');
+        output.push_str(&generate_struct_validation(struct_def, false, &[]));
+    }
+
     output
 }
 
@@ -146,6 +308,16 @@ fn generate_enum(enum_def: &EnumDefinition) -> String {
         output.push_str(&generate_enum_borsh_schema(enum_def));
     }
 
+    if enum_def.is_unit_only() {
+        output.push('\n');
+        output.push_str(&generate_enum_values(enum_def));
+    }
+
+    if enum_def.metadata.has_display() {
+        output.push('\n');
+        output.push_str(&generate_enum_display(enum_def));
+    }
+
     output
 }
 
@@ -286,12 +458,49 @@ fn estimate_output_size(type_defs: &[TypeDefinition]) -> usize {
 
 pub fn generate_module(type_defs: &[TypeDefinition]) -> String {
     // Estimate output size to reduce allocations for large schemas
-    let estimated_capacity = estimate_output_size(type_defs);
-    let mut output = String::with_capacity(estimated_capacity);
+    let mut output = Vec::with_capacity(estimate_output_size(type_defs));
+    generate_to(type_defs, &mut output).expect("writing to a Vec cannot fail");
+    String::from_utf8(output).expect("generated code is valid UTF-8")
+}
+
+/// Write the module for `type_defs` to `out`, one type at a time
+///
+/// Produces the same code as [`generate_module`] without holding the whole
+/// module in memory, which matters for schemas with thousands of types.
+pub fn generate_to<W: Write>(type_defs: &[TypeDefinition], out: &mut W) -> io::Result<()> {
+    generate_cached_to(type_defs, &mut NoCache, out)
+}
+
+/// Like [`generate_to`], reusing unchanged types' code from `cache`
+///
+/// Each type's snippet is looked up by [`snippet_key`] and only generated on
+/// a miss.
+pub fn generate_cached_to<W: Write>(
+    type_defs: &[TypeDefinition],
+    cache: &mut dyn SnippetCache,
+    out: &mut W,
+) -> io::Result<()> {
+    write_module(type_defs, Runtime::Node, None, cache, out)
+}
+
+/// The Borsh backend's module, with imports resolved for `runtime` and
+/// `schema` described in the file header
+fn write_module<W: Write>(
+    type_defs: &[TypeDefinition],
+    runtime: Runtime,
+    schema: Option<&SchemaMetadata>,
+    cache: &mut dyn SnippetCache,
+    out: &mut W,
+) -> io::Result<()> {
+    let type_defs = &*ir::for_target(type_defs, "typescript");
 
     // Add file header
-    output.push_str("// Auto-generated by LUMOS\n");
-    output.push_str("// DO NOT EDIT - Changes will be overwritten\n\n");
+    out.write_all(b"// Auto-generated by LUMOS\n")?;
+    out.write_all(b"// DO NOT EDIT - Changes will be overwritten\n")?;
+    for line in schema.map(SchemaMetadata::header_lines).unwrap_or_default() {
+        writeln!(out, "// {}", line)?;
+    }
+    out.write_all(b"\n")?;
 
     // Collect all imports needed
     let mut all_imports = HashSet::new();
@@ -308,88 +517,756 @@ pub fn generate_module(type_defs: &[TypeDefinition]) -> String {
         }
     }
 
+    // State hashes, version shims, instruction encoders, account decoders and
+    // test vector checks use `Buffer`, a Node global
+    let checks_vectors = type_defs.iter().any(has_test_vectors);
+    let decodes_accounts = type_defs.iter().any(decodes_account);
+    let uses_buffer = checks_vectors
+        || decodes_accounts
+        || type_defs.iter().any(|t| {
+            t.metadata().has_state_hash()
+                || t.metadata().has_version_shims()
+                || encodes_instruction(t)
+        });
+    if uses_buffer && runtime.imports_buffer() {
+        all_imports.insert("import { Buffer } from 'node:buffer'".to_string());
+    }
+
     // Write imports
     if !all_imports.is_empty() {
         let mut sorted_imports: Vec<_> = all_imports.into_iter().collect();
         sorted_imports.sort();
         for import in sorted_imports {
-            output.push_str(&format!("{};\n", import));
+            match import.split_once(" from '") {
+                Some((names, package)) => {
+                    let package = package.trim_end_matches('\'');
+                    let specifier = match package.starts_with('.') {
+                        true => runtime.local_module(package),
+                        false => runtime.package(package),
+                    };
+                    writeln!(out, "{} from '{}';", names, specifier)?
+                }
+                None => writeln!(out, "{};", import)?,
+            }
         }
-        output.push('\n');
+        out.write_all(b"\n")?;
+    }
+
+    let state_hash = type_defs.iter().any(|t| t.metadata().has_state_hash());
+    let migrates = type_defs.iter().any(|t| t.metadata().has_version_shims());
+    if state_hash || migrates || checks_vectors || type_defs.iter().any(encodes_instruction) {
+        out.write_all(ENCODE_BORSH_HELPER.as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+    if checks_vectors {
+        out.write_all(CHECK_TEST_VECTORS_HELPER.as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+    if decodes_accounts {
+        out.write_all(account_discriminator_helpers("Uint8Array").as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+    if state_hash {
+        out.write_all(STATE_HASH_HELPERS.as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+
+    if type_defs.iter().any(has_sensitive_fields) {
+        out.write_all(REDACT_HELPERS.as_bytes())?;
+        out.write_all(b"\n")?;
     }
 
     // Generate each type definition
+    let validated = ir::validated_structs(type_defs);
     for (i, type_def) in type_defs.iter().enumerate() {
         if i > 0 {
-            output.push('\n');
+            out.write_all(b"\n")?;
         }
 
-        match type_def {
-            TypeDefinition::Struct(s) => {
-                output.push_str(&generate_struct_interface(s));
-
-                // Add Borsh schema for Solana types
-                if s.metadata.solana {
-                    output.push('\n');
-                    output.push_str(&generate_struct_borsh_schema(s));
-                    if i < type_defs.len() - 1 {
-                        output.push('\n');
-                    }
-                }
+        let key = snippet_key("typescript", &validated, type_def);
+        let code = cached_snippet(cache, key, || {
+            generate_type_with_schema(type_def, &validated)
+        });
+        out.write_all(code.as_bytes())?;
+
+        // Separate a Borsh schema from the next type
+        if type_def.metadata().solana && i < type_defs.len() - 1 {
+            out.write_all(b"\n")?;
+        }
+    }
+
+    // Vectors depend on the types they reference, so they aren't cached
+    for type_def in type_defs {
+        if let TypeDefinition::Struct(s) = type_def {
+            if has_test_vectors(type_def) {
+                out.write_all(b"\n")?;
+                out.write_all(
+                    generate_test_vectors(s, type_defs, TypeScriptCodec::Borsh).as_bytes(),
+                )?;
             }
-            TypeDefinition::Enum(e) => {
-                output.push_str(&generate_enum_type(e));
-
-                // Add Borsh schema for Solana types
-                if e.metadata.solana {
-                    output.push('\n');
-                    output.push_str(&generate_enum_borsh_schema(e));
-                    if i < type_defs.len() - 1 {
-                        output.push('\n');
+        }
+    }
+
+    write!(out, "\n{}", SchemaSummary::new(type_defs).footer())
+}
+
+/// Shared by the `check<Type>TestVectors` functions of `#[test_vector]` structs
+const CHECK_TEST_VECTORS_HELPER: &str = r#"function checkTestVectors<T>(
+  name: string,
+  schema: borsh.Layout<T>,
+  vectors: readonly { value: T; hex: string }[],
+): void {
+  vectors.forEach(({ value, hex }, index) => {
+    const actual = Buffer.from(encodeBorsh(schema, value)).toString('hex');
+    if (actual !== hex) {
+      throw new Error(`#[test_vector] ${index + 1} of ${name} encodes to ${actual}, expected ${hex}`);
+    }
+  });
+}
+"#;
+
+/// Whether `type_def` is a Solana struct with `#[test_vector]` values
+pub(super) fn has_test_vectors(type_def: &TypeDefinition) -> bool {
+    matches!(type_def, TypeDefinition::Struct(s) if s.metadata.solana && !s.metadata.test_vectors.is_empty())
+}
+
+/// `<TYPE>_TEST_VECTORS` and the `check<Type>TestVectors` function checking them
+///
+/// The bytes are the ones the Rust tests expect, so a passing check in both
+/// languages means they agree on the encoding. `codec` selects the values'
+/// shape and the encoder handed to the module's `checkTestVectors`.
+pub(super) fn generate_test_vectors(
+    struct_def: &StructDefinition,
+    type_defs: &[TypeDefinition],
+    codec: TypeScriptCodec,
+) -> String {
+    let name = &struct_def.name;
+    let (value_type, encoder) = match codec {
+        TypeScriptCodec::Borsh => (name.clone(), format!("{}Schema", name)),
+        TypeScriptCodec::SolanaKit => (format!("{}Args", name), format!("get{}Encoder()", name)),
+    };
+    let constant = format!("{}_TEST_VECTORS", to_screaming_snake_case(name));
+    // Checked when the schema was transformed
+    let vectors = test_vector::test_vectors(type_defs, struct_def).unwrap_or_default();
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "/** `#[test_vector]` values of `{}` and their Borsh encodings, as in the Rust tests */\n",
+        name
+    ));
+    output.push_str(&format!(
+        "export const {}: readonly {{ value: {}; hex: string }}[] = [\n",
+        constant, value_type
+    ));
+    for vector in &vectors {
+        let value = ts_literal(
+            type_defs,
+            &TypeInfo::UserDefined(name.clone()),
+            &vector.value,
+            codec,
+        );
+        output.push_str(&format!(
+            "  {{ value: {}, hex: '{}' }},\n",
+            value,
+            encode_hex(&vector.bytes)
+        ));
+    }
+    output.push_str("];\n\n");
+
+    output.push_str(&format!(
+        "/** Throws unless every value in `{}` encodes to its bytes */\n",
+        constant
+    ));
+    output.push_str(&format!(
+        "export function check{}TestVectors(): void {{\n",
+        name
+    ));
+    output.push_str(&format!(
+        "  checkTestVectors('{}', {}, {});\n",
+        name, encoder, constant
+    ));
+    output.push_str("}\n");
+
+    output
+}
+
+/// A TypeScript expression for `value`, a value of `type_info`, in the
+/// shape of the types `codec` generates
+fn ts_literal(
+    type_defs: &[TypeDefinition],
+    type_info: &TypeInfo,
+    value: &DecodedValue,
+    codec: TypeScriptCodec,
+) -> String {
+    let list = |inner: &TypeInfo, items: &[DecodedValue]| -> Vec<String> {
+        items
+            .iter()
+            .map(|item| ts_literal(type_defs, inner, item, codec))
+            .collect()
+    };
+    let object = |entries: Vec<String>| match entries.is_empty() {
+        true => "{}".to_string(),
+        false => format!("{{ {} }}", entries.join(", ")),
+    };
+    let fields = |fields: &[FieldDefinition], values: &[(String, DecodedValue)]| -> Vec<String> {
+        fields
+            .iter()
+            .zip(values)
+            .map(|(field, (_, value))| {
+                format!(
+                    "{}: {}",
+                    field.name,
+                    ts_literal(type_defs, &field.type_info, value, codec)
+                )
+            })
+            .collect()
+    };
+
+    match (type_info, value) {
+        (TypeInfo::Option(_), DecodedValue::Option(None)) => match codec {
+            TypeScriptCodec::Borsh => "undefined".to_string(),
+            TypeScriptCodec::SolanaKit => "null".to_string(),
+        },
+        (TypeInfo::Option(inner), DecodedValue::Option(Some(value))) => {
+            ts_literal(type_defs, inner, value, codec)
+        }
+        (TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _), DecodedValue::Vec(items)) => {
+            format!("[{}]", list(inner, items).join(", "))
+        }
+        (TypeInfo::Map(_, key_type, value_type), DecodedValue::Map(entries)) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| {
+                    format!(
+                        "[{}, {}]",
+                        ts_literal(type_defs, key_type, key, codec),
+                        ts_literal(type_defs, value_type, value, codec)
+                    )
+                })
+                .collect();
+            format!("new Map([{}])", entries.join(", "))
+        }
+        (TypeInfo::UserDefined(name), _) => {
+            let type_def = type_defs.iter().find(|type_def| type_def.name() == name);
+            match (type_def, value) {
+                (Some(TypeDefinition::Struct(s)), DecodedValue::Struct(values)) => {
+                    object(fields(&s.fields, values))
+                }
+                (
+                    Some(TypeDefinition::Enum(e)),
+                    DecodedValue::Variant {
+                        name: variant,
+                        fields: values,
+                    },
+                ) => {
+                    let mut entries = vec![format!("kind: '{}'", variant)];
+                    match e.variants.iter().find(|v| v.name() == variant) {
+                        Some(EnumVariantDefinition::Tuple { types, .. }) => {
+                            entries.extend(types.iter().zip(values).enumerate().map(
+                                |(index, (type_info, (_, value)))| {
+                                    format!(
+                                        "field{}: {}",
+                                        index,
+                                        ts_literal(type_defs, type_info, value, codec)
+                                    )
+                                },
+                            ))
+                        }
+                        Some(EnumVariantDefinition::Struct {
+                            fields: variant_fields,
+                            ..
+                        }) => entries.extend(fields(variant_fields, values)),
+                        _ => {}
                     }
+                    object(entries)
                 }
+                _ => "undefined".to_string(),
             }
         }
+        // Kit encodes 64-bit `bigint`s, which stay exact above 2^53
+        (TypeInfo::Primitive(name), DecodedValue::Unsigned(n))
+            if name == "u128" || (name == "u64" && codec == TypeScriptCodec::SolanaKit) =>
+        {
+            format!("{}n", n)
+        }
+        (TypeInfo::Primitive(name), DecodedValue::Signed(n))
+            if name == "i128" || (name == "i64" && codec == TypeScriptCodec::SolanaKit) =>
+        {
+            format!("{}n", n)
+        }
+        (_, DecodedValue::Bool(value)) => value.to_string(),
+        (_, DecodedValue::Unsigned(n)) => n.to_string(),
+        (_, DecodedValue::Signed(n)) => n.to_string(),
+        (_, DecodedValue::Float(f)) => f.to_string(),
+        (_, DecodedValue::String(s)) => ts_string(s),
+        (_, DecodedValue::PublicKey(key)) => match codec {
+            TypeScriptCodec::Borsh => format!("new PublicKey({})", ts_string(key)),
+            TypeScriptCodec::SolanaKit => format!("{} as Address", ts_string(key)),
+        },
+        _ => "undefined".to_string(),
+    }
+}
+
+/// `s` as a single-quoted TypeScript string literal
+fn ts_string(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('\'', "\\'")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    format!("'{}'", escaped)
+}
+
+/// Shared by state hashes and instruction encoders
+///
+/// Encoding retries with a larger buffer up to Solana's 10 MiB account limit.
+const ENCODE_BORSH_HELPER: &str = r#"function encodeBorsh<T>(schema: borsh.Layout<T>, value: T): Uint8Array {
+  for (let size = 1024; ; size *= 2) {
+    const buffer = Buffer.alloc(size);
+    try {
+      return buffer.subarray(0, schema.encode(value, buffer));
+    } catch (e) {
+      if (!(e instanceof RangeError) || size >= 10 * 1024 * 1024) throw e;
+    }
+  }
+}
+"#;
+
+/// Shared by the `<type>StateHash` helpers of `#[state_hash]` types
+///
+/// Hashing uses WebCrypto, so no extra packages are needed.
+const STATE_HASH_HELPERS: &str = r#"async function sha256(bytes: Uint8Array): Promise<Uint8Array> {
+  return new Uint8Array(await globalThis.crypto.subtle.digest('SHA-256', bytes));
+}
+"#;
+
+/// Shared by the `<type>SensitiveFields` lists of types with `#[sensitive]` fields
+pub(super) const REDACT_HELPERS: &str = r#"/** Placeholder that `redact()` puts in place of sensitive fields */
+export const REDACTED = '[REDACTED]';
+
+/** Copy of `value` with `fields` replaced by `REDACTED`, for logs and off-chain indexes */
+export function redact<T extends object, K extends keyof T>(
+  value: T,
+  fields: readonly K[],
+): Omit<T, K> & Record<K, typeof REDACTED> {
+  const copy = { ...value } as Record<K, unknown>;
+  for (const field of fields) {
+    copy[field] = REDACTED;
+  }
+  return copy as unknown as Omit<T, K> & Record<K, typeof REDACTED>;
+}
+"#;
+
+pub(super) fn has_sensitive_fields(type_def: &TypeDefinition) -> bool {
+    matches!(type_def, TypeDefinition::Struct(s) if s.fields.iter().any(|f| f.is_sensitive()))
+}
+
+/// Type declaration, followed by its Borsh schema for Solana types
+/// `validated` names the structs of the module that have a `validate{Name}`
+fn generate_type_with_schema(type_def: &TypeDefinition, validated: &[&str]) -> String {
+    let mut code = generate_type_and_schema(type_def, validated);
+    if type_def.metadata().has_state_hash() {
+        code.push('\n');
+        code.push_str(&generate_state_hash(type_def.name()));
+    }
+    if let TypeDefinition::Struct(s) = type_def {
+        if has_sensitive_fields(type_def) {
+            code.push('\n');
+            code.push_str(&generate_sensitive_fields(s));
+        }
+        if s.metadata.has_version_shims() {
+            code.push_str(&generate_version_shims(s));
+        }
+        if encodes_instruction(type_def) {
+            code.push('\n');
+            code.push_str(&generate_instruction_encoder(s));
+        }
+        if decodes_account(type_def) {
+            let decode = match s.metadata.has_version_shims() {
+                true => format!("decode{}AnyVersion(data.subarray(8))", s.name),
+                false => format!("{}Schema.decode(data.subarray(8))", s.name),
+            };
+            code.push('\n');
+            code.push_str(&generate_account_decoder(type_def, "Buffer", &decode));
+        }
     }
+    code
+}
+
+/// Whether `type_def` is a Solana `#[account]` struct, which gets a decoder
+///
+/// Zero-copy accounts are laid out with `repr(C)` rather than Borsh, so the
+/// schema can't decode them.
+pub(super) fn decodes_account(type_def: &TypeDefinition) -> bool {
+    matches!(type_def, TypeDefinition::Struct(s) if s.metadata.solana && s.metadata.is_account() && !s.metadata.zero_copy)
+}
+
+/// Shared by the `decode<Type>` functions of `#[account]` structs, see
+/// [`account_discriminator_helpers`]
+const ACCOUNT_DISCRIMINATOR_HELPERS: &str = r#"/** Thrown when account data doesn't start with the discriminator of the expected account type */
+export class AccountDiscriminatorError extends Error {
+  constructor(
+    readonly account: string,
+    readonly expected: Uint8Array,
+    readonly actual: Uint8Array,
+  ) {
+    super(
+      `Account data is not a ${account}: expected discriminator ${toHex(expected)}, found ${toHex(actual) || 'no data'}`,
+    );
+    this.name = 'AccountDiscriminatorError';
+  }
+}
+
+function toHex(bytes: Uint8Array): string {
+  return Array.from(bytes, (byte) => byte.toString(16).padStart(2, '0')).join('');
+}
+
+function checkAccountDiscriminator(account: string, expected: Uint8Array, data: {data}): void {
+  const actual = data.subarray(0, expected.length);
+  if (actual.length !== expected.length || actual.some((byte, i) => byte !== expected[i])) {
+    throw new AccountDiscriminatorError(account, expected, Uint8Array.from(actual));
+  }
+}
+"#;
+
+/// [`ACCOUNT_DISCRIMINATOR_HELPERS`] checking account data of type `data_type`
+pub(super) fn account_discriminator_helpers(data_type: &str) -> String {
+    ACCOUNT_DISCRIMINATOR_HELPERS.replace("{data}", data_type)
+}
+
+/// `<TYPE>_DISCRIMINATOR` and `decode<Type>` of an `#[account]` struct
+///
+/// The decoder takes account data of type `data_type`, checks Anchor's
+/// 8-byte discriminator and returns `decode`, which decodes the rest.
+pub(super) fn generate_account_decoder(
+    type_def: &TypeDefinition,
+    data_type: &str,
+    decode: &str,
+) -> String {
+    let name = type_def.name();
+    let constant = format!("{}_DISCRIMINATOR", to_screaming_snake_case(name));
+    // Accounts always have one, see `decodes_account`
+    let discriminator: Vec<String> = type_def
+        .discriminator()
+        .unwrap_or_default()
+        .iter()
+        .map(u8::to_string)
+        .collect();
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "/** Anchor discriminator of `{0}` accounts, the first 8 bytes of `sha256(\"account:{0}\")` */\n",
+        name
+    ));
+    output.push_str(&format!(
+        "export const {} = Uint8Array.from([{}]);\n\n",
+        constant,
+        discriminator.join(", ")
+    ));
+    output.push_str(&format!(
+        "/** Decode `{}` account data, throwing `AccountDiscriminatorError` if it holds another type */\n",
+        name
+    ));
+    output.push_str(&format!(
+        "export function decode{}(data: {}): {} {{\n",
+        name, data_type, name
+    ));
+    output.push_str(&format!(
+        "  checkAccountDiscriminator('{}', {}, data);\n",
+        name, constant
+    ));
+    output.push_str(&format!("  return {};\n", decode));
+    output.push_str("}\n");
 
     output
 }
 
+/// Whether `type_def` is a Solana `#[instruction]` struct, which gets an encoder
+fn encodes_instruction(type_def: &TypeDefinition) -> bool {
+    matches!(type_def, TypeDefinition::Struct(s) if s.metadata.solana && s.metadata.is_instruction())
+}
+
+/// Instruction data encoder of an `#[instruction]`, mirroring the Rust
+/// `{Name}Accounts` context
+///
+/// The interface and Borsh schema of the struct hold its arguments; the
+/// accounts, if any, get a `{Name}Accounts` interface of their addresses.
+fn generate_instruction_encoder(struct_def: &StructDefinition) -> String {
+    let name = &struct_def.name;
+    let handler = to_snake_case(name);
+    let constant = to_screaming_snake_case(name);
+    let discriminator: Vec<String> = instruction_discriminator(name)
+        .iter()
+        .map(u8::to_string)
+        .collect();
+
+    let mut output = String::new();
+    let accounts: Vec<&FieldDefinition> = struct_def.instruction_accounts().collect();
+    if !accounts.is_empty() {
+        output.push_str(&format!(
+            "/** Accounts of the `{}` instruction, in the order the program expects them */\n",
+            handler
+        ));
+        output.push_str(&format!("export interface {}Accounts {{\n", name));
+        for field in accounts {
+            output.push_str(&format!("  {}: PublicKey;\n", field.name));
+        }
+        output.push_str("}\n\n");
+    }
+
+    output.push_str(&format!(
+        "/** Anchor discriminator of the `{}` instruction */\n",
+        handler
+    ));
+    output.push_str(&format!(
+        "export const {}_DISCRIMINATOR = Uint8Array.from([{}]);\n\n",
+        constant,
+        discriminator.join(", ")
+    ));
+    output.push_str(&format!(
+        "/** Data of a `{}` instruction: its discriminator, then the Borsh-encoded arguments */\n",
+        handler
+    ));
+    output.push_str(&format!(
+        "export function encode{}Instruction(args: {}): Uint8Array {{\n",
+        name, name
+    ));
+    output.push_str(&format!(
+        "  const encoded = encodeBorsh({}Schema, args);\n",
+        name
+    ));
+    output.push_str("  const data = new Uint8Array(8 + encoded.length);\n");
+    output.push_str(&format!("  data.set({}_DISCRIMINATOR);\n", constant));
+    output.push_str("  data.set(encoded, 8);\n");
+    output.push_str("  return data;\n");
+    output.push_str("}\n");
+
+    output
+}
+
+/// Earlier layouts of a `#[version(n)]` struct, mirroring the Rust shims
+///
+/// `upgrade{Name}V{k}` fills in the fields added by version `k + 1` with
+/// their defaults, and `decode{Name}AnyVersion` tries layouts newest first.
+fn generate_version_shims(struct_def: &StructDefinition) -> String {
+    let current = struct_def.metadata.version.unwrap_or(1);
+    let name = &struct_def.name;
+    let version_name = |version: u32| {
+        if version == current {
+            name.clone()
+        } else {
+            format!("{}V{}", name, version)
+        }
+    };
+
+    let mut output = String::new();
+    for version in 1..current {
+        let old = struct_def.at_version(version);
+        output.push_str(&format!(
+            "\n/** `{}` as laid out in schema version {} */\n",
+            name, version
+        ));
+        output.push_str(&generate_struct_interface(&old));
+        output.push('\n');
+        output.push_str(&generate_struct_borsh_schema(&old));
+
+        let next = version + 1;
+        let added: Vec<String> = struct_def
+            .fields
+            .iter()
+            .filter(|field| field.since_version() == next)
+            .map(|field| format!("{}: {}", field.name, default_value(&field.type_info)))
+            .collect();
+        output.push_str(&format!(
+            "\n/** Upgrade a version {} `{}` to version {} */\n",
+            version, name, next
+        ));
+        output.push_str(&format!(
+            "export function upgrade{}(value: {}): {} {{\n",
+            old.name,
+            old.name,
+            version_name(next)
+        ));
+        output.push_str(&format!("  return {{ ...value, {} }};\n", added.join(", ")));
+        output.push_str("}\n");
+    }
+
+    output.push_str(&format!(
+        "\n/** Decode Borsh data written under any schema version of `{}`, upgrading it to the current layout */\n",
+        name
+    ));
+    output.push_str(&format!(
+        "export function decode{}AnyVersion(data: Buffer): {} {{\n",
+        name, name
+    ));
+    for version in (1..=current).rev() {
+        let mut decoded = format!("{}Schema.decode(data)", version_name(version));
+        for from in version..current {
+            decoded = format!("upgrade{}V{}({})", name, from, decoded);
+        }
+
+        if version > 1 {
+            output.push_str("  try {\n");
+            output.push_str(&format!("    return {};\n", decoded));
+            output.push_str("  } catch {\n");
+            output.push_str("    // Written under an earlier version\n");
+            output.push_str("  }\n");
+        } else {
+            output.push_str(&format!("  return {};\n", decoded));
+        }
+    }
+    output.push_str("}\n");
+    output.push_str(&migration_generator::generate_typescript(
+        struct_def,
+        TypeScriptCodec::Borsh,
+    ));
+
+    output
+}
+
+/// Value given to a field added in a later schema version
+fn default_value(type_info: &TypeInfo) -> String {
+    match type_info {
+        TypeInfo::Option(_) => "undefined".to_string(),
+        TypeInfo::Array(_) => "[]".to_string(),
+        TypeInfo::Map(..) => "new Map()".to_string(),
+        TypeInfo::FixedArray(inner, len) => {
+            format!(
+                "Array.from({{ length: {} }}, () => {})",
+                len,
+                default_value(inner)
+            )
+        }
+        TypeInfo::Primitive(type_name) => match type_name.as_str() {
+            "u128" | "i128" => "0n",
+            "bool" => "false",
+            "String" | "Signature" => "''",
+            "Pubkey" | "PublicKey" => "PublicKey.default",
+            _ => "0",
+        }
+        .to_string(),
+        // Rejected by the transform, which only allows defaultable types
+        TypeInfo::UserDefined(_) => "undefined".to_string(),
+    }
+}
+
+/// Async counterpart of the Rust `state_hash` method
+fn generate_state_hash(type_name: &str) -> String {
+    let function_name = function_prefix(type_name);
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "/** SHA-256 of the canonical Borsh encoding, matching `{}::state_hash` in Rust */\n",
+        type_name
+    ));
+    output.push_str(&format!(
+        "export async function {}StateHash(value: {}): Promise<Uint8Array> {{\n",
+        function_name, type_name
+    ));
+    output.push_str(&format!(
+        "  return sha256(encodeBorsh({}Schema, value));\n",
+        type_name
+    ));
+    output.push_str("}\n");
+
+    output
+}
+
+/// The `#[sensitive]` fields of a struct, to pass to `redact()`
+pub(super) fn generate_sensitive_fields(struct_def: &StructDefinition) -> String {
+    let fields: Vec<String> = struct_def
+        .fields
+        .iter()
+        .filter(|f| f.is_sensitive())
+        .map(|f| format!("'{}'", f.name))
+        .collect();
+
+    format!(
+        "/** Fields of `{0}` marked `#[sensitive]`; use `redact(value, {0}SensitiveFields)` before logging */\nexport const {0}SensitiveFields = [{1}] as const;\n",
+        struct_def.name,
+        fields.join(", ")
+    )
+}
+
+fn generate_type_and_schema(type_def: &TypeDefinition, validated: &[&str]) -> String {
+    match type_def {
+        TypeDefinition::Struct(s) => {
+            let mut code = generate_struct_interface(s);
+            if s.metadata.solana {
+                code.push('\n');
+                code.push_str(&generate_struct_borsh_schema(s));
+            }
+            if s.metadata.has_display() {
+                code.push('\n');
+                code.push_str(&generate_struct_display(s));
+            }
+            if s.is_validated(validated) {
+                code.push('\n');
+                code.push_str(&generate_struct_validation(s, false, validated));
+            }
+            code
+        }
+        TypeDefinition::Enum(e) => {
+            let mut code = generate_enum_type(e);
+            if e.metadata.solana {
+                code.push('\n');
+                code.push_str(&generate_enum_borsh_schema(e));
+            }
+            if e.is_unit_only() {
+                code.push('\n');
+                code.push_str(&generate_enum_values(e));
+            }
+            if e.metadata.has_display() {
+                code.push('\n');
+                code.push_str(&generate_enum_display(e));
+            }
+            code
+        }
+    }
+}
+
 /// Check if a TypeInfo contains u64 or i64 types (which map to TypeScript 'number' with precision limits)
 fn contains_u64_or_i64(type_info: &TypeInfo) -> bool {
     match type_info {
         TypeInfo::Primitive(type_name) => {
             matches!(type_name.as_str(), "u64" | "i64")
         }
-        TypeInfo::Array(inner) | TypeInfo::Option(inner) => contains_u64_or_i64(inner),
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) | TypeInfo::Option(inner) => {
+            contains_u64_or_i64(inner)
+        }
+        TypeInfo::Map(_, key, value) => contains_u64_or_i64(key) || contains_u64_or_i64(value),
         TypeInfo::UserDefined(_) => false, // User-defined types are checked separately
     }
 }
 
 /// Generate TypeScript interface for struct
 fn generate_struct_interface(struct_def: &StructDefinition) -> String {
-    let mut output = String::new();
+    let mut output = doc_block(&struct_def.metadata.docs, "");
 
     // Generate interface
     output.push_str(&format!("export interface {} {{\n", struct_def.name));
 
     // Generate fields
-    for field in &struct_def.fields {
+    for field in struct_def.data_fields() {
         let ts_type = map_type_to_typescript(&field.type_info);
         let optional_marker = if field.optional { "?" } else { "" };
 
         // Add JSDoc warning for u64/i64 types (precision limitation)
+        let mut docs = field.docs();
         if contains_u64_or_i64(&field.type_info) {
-            output.push_str("  /**\n");
-            output.push_str("   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).\n");
-            output.push_str(
-                "   * For Solana lamports or large values, ensure they stay within safe range.\n",
-            );
-            output.push_str(
-                "   * Values exceeding this limit will lose precision during serialization.\n",
+            if !docs.is_empty() {
+                docs.push(String::new());
+            }
+            docs.extend(
+                [
+                    "WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).",
+                    "For Solana lamports or large values, ensure they stay within safe range.",
+                    "Values exceeding this limit will lose precision during serialization.",
+                ]
+                .map(String::from),
             );
-            output.push_str("   */\n");
         }
+        output.push_str(&doc_block(&docs, "  "));
 
         output.push_str(&format!(
             "  {}{}: {};\n",
@@ -404,7 +1281,7 @@ fn generate_struct_interface(struct_def: &StructDefinition) -> String {
 
 /// Generate Borsh schema for struct serialization
 fn generate_struct_borsh_schema(struct_def: &StructDefinition) -> String {
-    let mut output = String::new();
+    let mut output = doc_block(&struct_def.metadata.docs, "");
 
     output.push_str(&format!(
         "export const {}Schema = borsh.struct([\n",
@@ -412,14 +1289,43 @@ fn generate_struct_borsh_schema(struct_def: &StructDefinition) -> String {
     ));
 
     // Generate Borsh field definitions
-    for field in &struct_def.fields {
-        let borsh_type = map_type_to_borsh(&field.type_info);
-        output.push_str(&format!("  {}('{}'),\n", borsh_type, field.name));
+    for field in struct_def.data_fields() {
+        match codec_module(field) {
+            Some((module, path)) => output.push_str(&format!(
+                "  {}.layout('{}'), // layout owned by {}\n",
+                module, field.name, path
+            )),
+            None => {
+                let borsh_type = map_type_to_borsh(&field.type_info);
+                output.push_str(&format!("  {}('{}'),\n", borsh_type, field.name));
+            }
+        }
+    }
+
+    output.push_str("]);\n");
+
+    output
+}
+
+/// A TSDoc block with `lines` at `indent`, or nothing if there are none
+pub(super) fn doc_block(lines: &[String], indent: &str) -> String {
+    // `*/` inside a line would end the block early
+    let escape = |line: &String| line.replace("*/", "*\\/");
+    match lines {
+        [] => String::new(),
+        [line] => format!("{}/** {} */\n", indent, escape(line)),
+        _ => {
+            let mut output = format!("{}/**\n", indent);
+            for line in lines {
+                match line.is_empty() {
+                    true => output.push_str(&format!("{} *\n", indent)),
+                    false => output.push_str(&format!("{} * {}\n", indent, escape(line))),
+                }
+            }
+            output.push_str(&format!("{} */\n", indent));
+            output
+        }
     }
-
-    output.push_str("]);\n");
-
-    output
 }
 
 /// Generate TypeScript discriminated union type for enum
@@ -436,14 +1342,20 @@ fn generate_enum_type(enum_def: &EnumDefinition) -> String {
     });
 
     // Add JSDoc warning if enum contains u64/i64
+    let mut docs = enum_def.metadata.docs.clone();
     if has_u64_i64 {
-        output.push_str("/**\n");
-        output.push_str(" * WARNING: Some variants contain TypeScript 'number' types with precision limit of 2^53-1.\n");
-        output.push_str(
-            " * Large values (e.g., Solana lamports) may lose precision during serialization.\n",
+        if !docs.is_empty() {
+            docs.push(String::new());
+        }
+        docs.extend(
+            [
+                "WARNING: Some variants contain TypeScript 'number' types with precision limit of 2^53-1.",
+                "Large values (e.g., Solana lamports) may lose precision during serialization.",
+            ]
+            .map(String::from),
         );
-        output.push_str(" */\n");
     }
+    output.push_str(&doc_block(&docs, ""));
 
     // Generate discriminated union type
     output.push_str(&format!("export type {} =\n", enum_def.name));
@@ -479,9 +1391,336 @@ fn generate_enum_type(enum_def: &EnumDefinition) -> String {
     output
 }
 
+/// Every variant of a unit-only enum, with conversions to and from variant indexes
+///
+/// The index of a variant is its Borsh discriminant.
+pub(super) fn generate_enum_values(enum_def: &EnumDefinition) -> String {
+    let name = &enum_def.name;
+    let values = format!("{}_VALUES", to_screaming_snake_case(name));
+    let function_name = function_prefix(name);
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "/** Every `{}` variant, in declaration order */\n",
+        name
+    ));
+    output.push_str(&format!(
+        "export const {}: readonly {}[] = [\n",
+        values, name
+    ));
+    for variant in &enum_def.variants {
+        output.push_str(&format!("  {{ kind: '{}' }},\n", variant.name()));
+    }
+    output.push_str("];\n\n");
+
+    output.push_str(&format!(
+        "/** `{}` with variant index `value`, or `undefined` if there is none */\n",
+        name
+    ));
+    output.push_str(&format!(
+        "export function {}FromNumber(value: number): {} | undefined {{\n",
+        function_name, name
+    ));
+    output.push_str(&format!("  return {}[value];\n", values));
+    output.push_str("}\n\n");
+
+    output.push_str(&format!("/** Variant index of a `{}` */\n", name));
+    output.push_str(&format!(
+        "export function {}ToNumber(value: {}): number {{\n",
+        function_name, name
+    ));
+    output.push_str(&format!(
+        "  return {}.findIndex((v) => v.kind === value.kind);\n",
+        values
+    ));
+    output.push_str("}\n");
+
+    output
+}
+
+/// `toString`/`parse` helpers of a `#[display]` unit-only enum, matching Rust's
+/// `Display`/`FromStr`
+pub(super) fn generate_enum_display(enum_def: &EnumDefinition) -> String {
+    let name = &enum_def.name;
+    let function_name = function_prefix(name);
+
+    let mut output = String::new();
+    output.push_str(&format!("/** Name of a `{}` variant */\n", name));
+    output.push_str(&format!(
+        "export function {}ToString(value: {}): string {{\n",
+        function_name, name
+    ));
+    output.push_str("  return value.kind;\n");
+    output.push_str("}\n\n");
+
+    output.push_str(&format!(
+        "/** `{}` variant named `text`, or `undefined` if there is none */\n",
+        name
+    ));
+    output.push_str(&format!(
+        "export function parse{}(text: string): {} | undefined {{\n",
+        name, name
+    ));
+    output.push_str(&format!(
+        "  return {}_VALUES.find((v) => v.kind === text);\n",
+        to_screaming_snake_case(name)
+    ));
+    output.push_str("}\n");
+
+    output
+}
+
+/// `validate{Name}`, checking the `#[min]`, `#[max]` and `#[range]` bounds like
+/// Rust's `validate`
+///
+/// Strings are measured in UTF-8 bytes, as Borsh stores them. `bigint_64`
+/// is set when `u64`/`i64` values are bigints, as with `@solana/kit` codecs.
+/// Fields holding a struct in `validated` are checked with its `validate{Name}`.
+pub(crate) fn generate_struct_validation(
+    struct_def: &StructDefinition,
+    bigint_64: bool,
+    validated: &[&str],
+) -> String {
+    let name = &struct_def.name;
+    let mut output = format!(
+        "/** Check a `{}` against the `#[min]`, `#[max]` and `#[range]` bounds of the schema, returning the first violation */\n",
+        name
+    );
+    output.push_str(&format!(
+        "export function validate{}(value: {}): string | undefined {{\n",
+        name, name
+    ));
+    let measures_strings = struct_def.fields.iter().any(|field| {
+        let constraints = field.constraints();
+        let type_info = match &field.type_info {
+            TypeInfo::Option(inner) => inner,
+            type_info => type_info,
+        };
+        (constraints.min_len.is_some() || constraints.max_len.is_some())
+            && !matches!(type_info, TypeInfo::Array(_))
+    });
+    if measures_strings {
+        output.push_str(
+            "  const utf8Length = (text: string) => new TextEncoder().encode(text).length;\n",
+        );
+    }
+    let nested: Vec<String> = struct_def
+        .fields
+        .iter()
+        .map(|field| {
+            validate_nested(
+                &field.type_info,
+                &format!("value.{}", field.name),
+                "  ",
+                0,
+                validated,
+            )
+        })
+        .collect();
+    if nested.iter().any(|code| !code.is_empty()) {
+        output.push_str("  let error: string | undefined;\n");
+    }
+    for (field, nested) in struct_def.fields.iter().zip(nested) {
+        let constraints = field.constraints();
+        let value = format!("value.{}", field.name);
+        let (type_info, guard) = match &field.type_info {
+            TypeInfo::Option(inner) => (inner.as_ref(), format!("{} != null && ", value)),
+            type_info => (type_info, String::new()),
+        };
+
+        let (length, unit) = match type_info {
+            TypeInfo::Array(_) => (format!("{}.length", value), "items"),
+            _ => (format!("utf8Length({})", value), "bytes"),
+        };
+        let length_check = match (constraints.min_len, constraints.max_len) {
+            (Some(min), Some(max)) => Some(format!("{0} < {1} || {0} > {2}", length, min, max)),
+            (Some(min), None) => Some(format!("{} < {}", length, min)),
+            (None, Some(max)) => Some(format!("{} > {}", length, max)),
+            (None, None) => None,
+        };
+
+        let bigint = match type_info {
+            TypeInfo::Primitive(type_name) => match type_name.as_str() {
+                "u128" | "i128" => true,
+                "u64" | "i64" => bigint_64,
+                _ => false,
+            },
+            _ => false,
+        };
+        let literal = |bound: i128| match bigint {
+            true => format!("{}n", bound),
+            false => bound.to_string(),
+        };
+        let range_check = constraints
+            .range
+            .map(|(min, max)| format!("{0} < {1} || {0} > {2}", value, literal(min), literal(max)));
+
+        let location = format!("{}.{}", name, field.name);
+        let checks = [
+            (length_check, constraints.length_violation(&location, unit)),
+            (range_check, constraints.range_violation(&location)),
+        ];
+        for (condition, message) in checks {
+            if let (Some(condition), Some(message)) = (condition, message) {
+                let condition = match (guard.is_empty(), condition.contains("||")) {
+                    (false, true) => format!("{}({})", guard, condition),
+                    _ => format!("{}{}", guard, condition),
+                };
+                output.push_str(&format!("  if ({}) {{\n", condition));
+                output.push_str(&format!("    return '{}';\n", message));
+                output.push_str("  }\n");
+            }
+        }
+        output.push_str(&nested);
+    }
+    output.push_str("  return undefined;\n");
+    output.push_str("}\n");
+
+    output
+}
+
+/// Statements checking the structs in `validated` that `value` holds with
+/// their `validate{Name}`, returning the first violation
+///
+/// Loop variables are numbered by `depth` so nested loops don't shadow them.
+fn validate_nested(
+    type_info: &TypeInfo,
+    value: &str,
+    indent: &str,
+    depth: usize,
+    validated: &[&str],
+) -> String {
+    let inner_indent = format!("{}  ", indent);
+    let item = match depth {
+        0 => "item".to_string(),
+        _ => format!("item{}", depth + 1),
+    };
+    let (header, inner, item) = match type_info {
+        TypeInfo::UserDefined(name) if validated.contains(&name.as_str()) => {
+            return format!(
+                "{0}error = validate{1}({2});\n{0}if (error !== undefined) {{\n{0}  return error;\n{0}}}\n",
+                indent, name, value
+            );
+        }
+        TypeInfo::Option(inner) => (format!("if ({} != null)", value), inner, value.to_string()),
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => {
+            (format!("for (const {} of {})", item, value), inner, item)
+        }
+        TypeInfo::Map(_, _, inner) => (
+            format!("for (const {} of {}.values())", item, value),
+            inner,
+            item,
+        ),
+        _ => return String::new(),
+    };
+
+    let body = validate_nested(inner, &item, &inner_indent, depth + 1, validated);
+    if body.is_empty() {
+        return body;
+    }
+    format!("{}{} {{\n{}{}}}\n", indent, header, body, indent)
+}
+
+/// `toString`/`parse` helpers of a `#[display]` single-field struct, matching
+/// Rust's `Display`/`FromStr`
+///
+/// Integers are checked for digits only, not range.
+fn generate_struct_display(struct_def: &StructDefinition) -> String {
+    let [field] = struct_def.fields.as_slice() else {
+        return String::new();
+    };
+    let name = &struct_def.name;
+    let function_name = function_prefix(name);
+    let field_name = &field.name;
+
+    let parse_body = match &field.type_info {
+        TypeInfo::Primitive(t) => match t.as_str() {
+            "u8" | "u16" | "u32" | "u64" | "u128" => {
+                parse_integer(field_name, r"/^\+?\d+$/", t == "u128")
+            }
+            "i8" | "i16" | "i32" | "i64" | "i128" => {
+                parse_integer(field_name, r"/^[+-]?\d+$/", t == "i128")
+            }
+            "f32" | "f64" => format!(
+                "  const parsed = Number(text);\n  if (text.trim() === '' || Number.isNaN(parsed)) return undefined;\n  return {{ {}: parsed }};\n",
+                field_name
+            ),
+            "bool" => format!(
+                "  if (text !== 'true' && text !== 'false') return undefined;\n  return {{ {}: text === 'true' }};\n",
+                field_name
+            ),
+            "Pubkey" | "PublicKey" => format!(
+                "  try {{\n    return {{ {}: new PublicKey(text) }};\n  }} catch {{\n    return undefined;\n  }}\n",
+                field_name
+            ),
+            _ => format!("  return {{ {}: text }};\n", field_name),
+        },
+        _ => return String::new(),
+    };
+
+    let mut output = String::new();
+    output.push_str(&format!("/** `{}` as text */\n", name));
+    output.push_str(&format!(
+        "export function {}ToString(value: {}): string {{\n",
+        function_name, name
+    ));
+    output.push_str(&format!("  return value.{}.toString();\n", field_name));
+    output.push_str("}\n\n");
+
+    output.push_str(&format!(
+        "/** Parse a `{}`, or `undefined` if `text` is not valid */\n",
+        name
+    ));
+    output.push_str(&format!(
+        "export function parse{}(text: string): {} | undefined {{\n",
+        name, name
+    ));
+    output.push_str(&parse_body);
+    output.push_str("}\n");
+
+    output
+}
+
+/// Body of a `parse` helper for an integer field
+pub(super) fn parse_integer(field_name: &str, pattern: &str, bigint: bool) -> String {
+    let convert = if bigint { "BigInt" } else { "Number" };
+    format!(
+        "  if (!{}.test(text)) return undefined;\n  return {{ {}: {}(text) }};\n",
+        pattern, field_name, convert
+    )
+}
+
+/// `GameState` -> `gameState`, for helper function names
+pub(super) fn function_prefix(type_name: &str) -> String {
+    let mut prefix = type_name.to_string();
+    if let Some(first) = prefix.get_mut(0..1) {
+        first.make_ascii_lowercase();
+    }
+    prefix
+}
+
+/// `GameState` -> `GAME_STATE`, keeping acronyms together (`NFTKind` -> `NFT_KIND`)
+fn to_screaming_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::new();
+
+    for (i, &ch) in chars.iter().enumerate() {
+        let starts_word = ch.is_uppercase()
+            && i > 0
+            && (chars[i - 1].is_lowercase()
+                || chars.get(i + 1).is_some_and(|next| next.is_lowercase()));
+        if starts_word {
+            result.push('_');
+        }
+        result.push(ch.to_ascii_uppercase());
+    }
+
+    result
+}
+
 /// Generate Borsh schema for enum serialization
 fn generate_enum_borsh_schema(enum_def: &EnumDefinition) -> String {
-    let mut output = String::new();
+    let mut output = doc_block(&enum_def.metadata.docs, "");
 
     output.push_str(&format!(
         "export const {}Schema = borsh.rustEnum([\n",
@@ -553,6 +1792,31 @@ fn collect_enum_imports(enum_def: &EnumDefinition) -> HashSet<String> {
     imports
 }
 
+/// Namespace and path of the module a `#[codec]` field delegates to
+///
+/// `./codecs/compactU64` is imported as `codecsCompactU64`.
+pub(super) fn codec_module(field: &FieldDefinition) -> Option<(String, String)> {
+    let path = field.codec("typescript")?;
+    let mut module = String::new();
+    for word in path
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        let mut chars = word.chars();
+        let first = chars.next().expect("words are not empty");
+        if module.is_empty() {
+            module.push(first.to_ascii_lowercase());
+        } else {
+            module.push(first.to_ascii_uppercase());
+        }
+        module.extend(chars);
+    }
+    if !module.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        module.insert_str(0, "codec");
+    }
+    Some((module, path))
+}
+
 /// Collect required imports based on struct definition
 fn collect_struct_imports(struct_def: &StructDefinition) -> HashSet<String> {
     let mut imports = HashSet::new();
@@ -571,6 +1835,9 @@ fn collect_struct_imports(struct_def: &StructDefinition) -> HashSet<String> {
 
     if needs_borsh {
         imports.insert("import * as borsh from '@coral-xyz/borsh'".to_string());
+        for (module, path) in struct_def.fields.iter().filter_map(codec_module) {
+            imports.insert(format!("import * as {} from '{}'", module, path));
+        }
     }
 
     imports
@@ -584,12 +1851,16 @@ fn collect_imports_from_type(type_info: &TypeInfo, needs_publickey: &mut bool) {
                 *needs_publickey = true;
             }
         }
-        TypeInfo::Array(inner) => {
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => {
             collect_imports_from_type(inner, needs_publickey);
         }
         TypeInfo::Option(inner) => {
             collect_imports_from_type(inner, needs_publickey);
         }
+        TypeInfo::Map(_, key, value) => {
+            collect_imports_from_type(key, needs_publickey);
+            collect_imports_from_type(value, needs_publickey);
+        }
         TypeInfo::UserDefined(_) => {
             // User-defined types are in the same module
         }
@@ -623,14 +1894,22 @@ fn map_type_to_typescript(type_info: &TypeInfo) -> String {
                 _ => type_name.clone(),
             }
         }
-        TypeInfo::Array(inner) => {
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => {
             let inner_type = map_type_to_typescript(inner);
-            format!("{}[]", inner_type)
+            match inner.as_ref() {
+                TypeInfo::Option(_) => format!("({})[]", inner_type),
+                _ => format!("{}[]", inner_type),
+            }
         }
         TypeInfo::Option(inner) => {
             let inner_type = map_type_to_typescript(inner);
             format!("{} | undefined", inner_type)
         }
+        TypeInfo::Map(_, key, value) => format!(
+            "Map<{}, {}>",
+            map_type_to_typescript(key),
+            map_type_to_typescript(value)
+        ),
         TypeInfo::UserDefined(type_name) => type_name.clone(),
     }
 }
@@ -660,10 +1939,21 @@ fn map_type_to_borsh(type_info: &TypeInfo) -> String {
             let inner_borsh = map_type_to_borsh(inner);
             format!("borsh.vec({})", inner_borsh)
         }
+        TypeInfo::FixedArray(inner, len) => {
+            let inner_borsh = map_type_to_borsh(inner);
+            format!("borsh.array({}, {})", inner_borsh, len)
+        }
         TypeInfo::Option(inner) => {
             let inner_borsh = map_type_to_borsh(inner);
             format!("borsh.option({})", inner_borsh)
         }
+        TypeInfo::Map(_, key, value) => {
+            format!(
+                "borsh.map({}, {})",
+                map_type_to_borsh(key),
+                map_type_to_borsh(value)
+            )
+        }
         TypeInfo::UserDefined(type_name) => {
             // User-defined types need their schema
             format!("{}Schema", type_name)
@@ -676,9 +1966,73 @@ mod tests {
     use super::*;
     use crate::ir::{
         EnumDefinition, EnumVariantDefinition, FieldDefinition, Metadata, StructDefinition,
-        TypeDefinition, TypeInfo,
+        TypeDefinition, TypeInfo, TypeRole,
     };
 
+    #[test]
+    fn resolves_imports_for_deno_and_bun() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "#[solana]\n#[state_hash]\nstruct Vault { owner: PublicKey, amount: u64 }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let generate = |runtime| {
+            let config = TypeScriptConfig {
+                codecs: Codecs::Borsh,
+                runtime,
+                ..TypeScriptConfig::default()
+            };
+            let mut out = Vec::new();
+            generate_configured_to(&ir, &config, &mut NoCache, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let deno = generate(Runtime::Deno);
+        assert!(deno.contains(
+            "import * as borsh from 'npm:@coral-xyz/borsh';\nimport { Buffer } from 'node:buffer';\nimport { PublicKey } from 'npm:@solana/web3.js';\n"
+        ));
+        let bun = generate(Runtime::Bun);
+        assert!(bun.contains(
+            "import { Buffer } from 'node:buffer';\nimport { PublicKey } from '@solana/web3.js';\n"
+        ));
+        assert_eq!(generate(Runtime::Node), generate_module(&ir));
+
+        assert_eq!(Runtime::Deno.local_module("./generated"), "./generated.ts");
+        assert_eq!(
+            Runtime::Bun.local_module("../types/accounts.ts"),
+            "../types/accounts.ts"
+        );
+        assert_eq!(Runtime::Deno.local_module("@app/types"), "@app/types");
+        assert_eq!(Runtime::Node.local_module("./generated"), "./generated");
+    }
+
+    #[test]
+    fn reads_codecs_from_lumos_toml() {
+        let config = TypeScriptConfig::from_lumos_toml("[typescript]\ncodecs = \"kit\"\n").unwrap();
+        assert_eq!(config.codecs, Codecs::Kit);
+
+        let config = TypeScriptConfig::from_lumos_toml("[rust]\ndebug-clone = \"skip\"\n").unwrap();
+        assert_eq!(config.codecs, Codecs::Borsh);
+
+        assert!(TypeScriptConfig::from_lumos_toml("[typescript]\ncodecs = \"anchor\"\n").is_err());
+
+        let config =
+            TypeScriptConfig::from_lumos_toml("[typescript]\nruntime = \"react-native\"\n")
+                .unwrap();
+        assert_eq!(
+            (config.codecs, config.runtime),
+            (Codecs::Kit, Runtime::ReactNative)
+        );
+
+        let err = TypeScriptConfig::from_lumos_toml(
+            "[typescript]\ncodecs = \"borsh\"\nruntime = \"react-native\"\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("needs codecs = \"kit\""));
+    }
+
     #[test]
     fn generates_simple_interface() {
         let type_def = TypeDefinition::Struct(StructDefinition {
@@ -688,11 +2042,13 @@ mod tests {
                     name: "id".to_string(),
                     type_info: TypeInfo::Primitive("u64".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
                 FieldDefinition {
                     name: "name".to_string(),
                     type_info: TypeInfo::Primitive("String".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
             ],
             metadata: Metadata::default(),
@@ -713,16 +2069,25 @@ mod tests {
                     name: "wallet".to_string(),
                     type_info: TypeInfo::Primitive("PublicKey".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
                 FieldDefinition {
                     name: "balance".to_string(),
                     type_info: TypeInfo::Primitive("u64".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
             ],
             metadata: Metadata {
                 solana: true,
                 attributes: vec!["account".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         });
 
@@ -745,6 +2110,7 @@ mod tests {
                 name: "email".to_string(),
                 type_info: TypeInfo::Option(Box::new(TypeInfo::Primitive("String".to_string()))),
                 optional: true,
+                attributes: vec![],
             }],
             metadata: Metadata::default(),
         });
@@ -761,10 +2127,18 @@ mod tests {
                 name: "members".to_string(),
                 type_info: TypeInfo::Array(Box::new(TypeInfo::Primitive("u64".to_string()))),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata {
                 solana: true,
                 attributes: vec![],
+                role: TypeRole::Plain,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         });
 
@@ -793,6 +2167,54 @@ mod tests {
         assert!(code.contains("export interface Post"));
     }
 
+    #[test]
+    fn streams_same_module_as_generate_module() {
+        let type_defs = vec![
+            TypeDefinition::Struct(StructDefinition {
+                name: "Player".to_string(),
+                fields: vec![FieldDefinition {
+                    name: "wallet".to_string(),
+                    type_info: TypeInfo::Primitive("PublicKey".to_string()),
+                    optional: false,
+                    attributes: vec![],
+                }],
+                metadata: Metadata {
+                    solana: true,
+                    attributes: vec![],
+                    role: TypeRole::Plain,
+                    zero_copy: false,
+                    version: None,
+                    lifecycle: Default::default(),
+                    skip: Vec::new(),
+                    docs: Vec::new(),
+                    test_vectors: Vec::new(),
+                },
+            }),
+            TypeDefinition::Struct(StructDefinition {
+                name: "Stats".to_string(),
+                fields: vec![],
+                metadata: Metadata {
+                    solana: true,
+                    attributes: vec![],
+                    role: TypeRole::Plain,
+                    zero_copy: false,
+                    version: None,
+                    lifecycle: Default::default(),
+                    skip: Vec::new(),
+                    docs: Vec::new(),
+                    test_vectors: Vec::new(),
+                },
+            }),
+        ];
+
+        let mut streamed = Vec::new();
+        generate_to(&type_defs, &mut streamed).unwrap();
+        assert_eq!(
+            String::from_utf8(streamed).unwrap(),
+            generate_module(&type_defs)
+        );
+    }
+
     #[test]
     fn maps_bigint_types() {
         let type_def = TypeDefinition::Struct(StructDefinition {
@@ -802,11 +2224,13 @@ mod tests {
                     name: "big_unsigned".to_string(),
                     type_info: TypeInfo::Primitive("u128".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
                 FieldDefinition {
                     name: "big_signed".to_string(),
                     type_info: TypeInfo::Primitive("i128".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
             ],
             metadata: Metadata::default(),
@@ -835,6 +2259,13 @@ mod tests {
             metadata: Metadata {
                 solana: true,
                 attributes: vec![],
+                role: TypeRole::Plain,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         });
 
@@ -848,6 +2279,17 @@ mod tests {
         assert!(code.contains("borsh.unit('Active')"));
         assert!(code.contains("borsh.unit('Paused')"));
         assert!(code.contains("borsh.unit('Finished')"));
+        assert!(code.contains("export const GAME_STATE_VALUES: readonly GameState[] = [\n  { kind: 'Active' },\n  { kind: 'Paused' },\n  { kind: 'Finished' },\n];"));
+        assert!(code.contains(
+            "export function gameStateFromNumber(value: number): GameState | undefined {\n  return GAME_STATE_VALUES[value];\n}"
+        ));
+        assert!(code.contains("export function gameStateToNumber(value: GameState): number {"));
+        assert_eq!(
+            generate_module(&[type_def])
+                .matches("export const GAME_STATE_VALUES")
+                .count(),
+            1
+        );
     }
 
     #[test]
@@ -870,6 +2312,13 @@ mod tests {
             metadata: Metadata {
                 solana: true,
                 attributes: vec![],
+                role: TypeRole::Plain,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         });
 
@@ -882,6 +2331,7 @@ mod tests {
         assert!(code.contains("borsh.tuple(["));
         assert!(code.contains("borsh.publicKey"));
         assert!(code.contains("borsh.u64"));
+        assert!(!code.contains("_VALUES"));
     }
 
     #[test]
@@ -896,11 +2346,13 @@ mod tests {
                             name: "authority".to_string(),
                             type_info: TypeInfo::Primitive("Pubkey".to_string()),
                             optional: false,
+                            attributes: vec![],
                         },
                         FieldDefinition {
                             name: "max_players".to_string(),
                             type_info: TypeInfo::Primitive("u32".to_string()),
                             optional: false,
+                            attributes: vec![],
                         },
                     ],
                 },
@@ -911,11 +2363,13 @@ mod tests {
                             name: "player".to_string(),
                             type_info: TypeInfo::Primitive("Pubkey".to_string()),
                             optional: false,
+                            attributes: vec![],
                         },
                         FieldDefinition {
                             name: "new_score".to_string(),
                             type_info: TypeInfo::Primitive("u64".to_string()),
                             optional: false,
+                            attributes: vec![],
                         },
                     ],
                 },
@@ -923,6 +2377,13 @@ mod tests {
             metadata: Metadata {
                 solana: true,
                 attributes: vec![],
+                role: TypeRole::Plain,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         });
 
@@ -938,4 +2399,456 @@ mod tests {
         assert!(code.contains("borsh.publicKey('player')"));
         assert!(code.contains("borsh.u64('new_score')"));
     }
+
+    #[test]
+    fn generates_state_hash_helpers_for_opted_in_types() {
+        let vault = TypeDefinition::Struct(StructDefinition {
+            name: "Vault".to_string(),
+            fields: vec![FieldDefinition {
+                name: "amount".to_string(),
+                type_info: TypeInfo::Primitive("u64".to_string()),
+                optional: false,
+                attributes: vec![],
+            }],
+            metadata: Metadata {
+                solana: true,
+                attributes: vec!["account".to_string(), "state_hash".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
+            },
+        });
+
+        let code = generate_module(std::slice::from_ref(&vault));
+        assert_eq!(code.matches("function encodeBorsh<T>").count(), 1);
+        assert!(code.contains("crypto.subtle.digest('SHA-256', bytes)"));
+        assert!(code
+            .contains("export async function vaultStateHash(value: Vault): Promise<Uint8Array> {"));
+        assert!(code.contains("return sha256(encodeBorsh(VaultSchema, value));"));
+
+        let TypeDefinition::Struct(mut plain) = vault else {
+            unreachable!()
+        };
+        plain.metadata.attributes.pop();
+        let code = generate_module(&[TypeDefinition::Struct(plain)]);
+        assert!(!code.contains("encodeBorsh"));
+        assert!(!code.contains("StateHash"));
+    }
+
+    #[test]
+    fn generates_redact_helper_for_sensitive_fields() {
+        let source = r#"
+            #[solana]
+            struct Member {
+                wallet: PublicKey,
+                #[sensitive]
+                email: String,
+                #[sensitive]
+                phone: Option<String>,
+            }
+
+            struct Note { text: String }
+        "#;
+        let type_defs =
+            crate::transform::transform_to_ir(crate::parser::parse_lumos_file(source).unwrap())
+                .unwrap();
+
+        let code = generate_module(&type_defs);
+        assert_eq!(code.matches("export function redact<").count(), 1);
+        assert!(code.contains("export const REDACTED = '[REDACTED]';"));
+        assert!(code.contains("export const MemberSensitiveFields = ['email', 'phone'] as const;"));
+        assert!(!code.contains("NoteSensitiveFields"));
+
+        let code = generate_module(&type_defs[1..]);
+        assert!(!code.contains("redact"));
+    }
+
+    #[test]
+    fn generates_shims_for_earlier_versions() {
+        let source = r#"
+            #[solana]
+            #[account]
+            #[version(3)]
+            struct Profile {
+                owner: PublicKey,
+                #[since(2)]
+                bio: Option<String>,
+                #[since(3)]
+                level: u16,
+            }
+        "#;
+        let type_defs =
+            crate::transform::transform_to_ir(crate::parser::parse_lumos_file(source).unwrap())
+                .unwrap();
+
+        let code = generate_module(&type_defs);
+        assert!(code.contains("export interface ProfileV1 {\n  owner: PublicKey;\n}"));
+        assert!(code.contains("export const ProfileV2Schema = borsh.struct(["));
+        assert!(code.contains("return { ...value, bio: undefined };"));
+        assert!(code.contains("export function upgradeProfileV2(value: ProfileV2): Profile {"));
+        assert!(code.contains("export function decodeProfileAnyVersion(data: Buffer): Profile {"));
+        assert!(code.contains(
+            "  return upgradeProfileV2(upgradeProfileV1(ProfileV1Schema.decode(data)));"
+        ));
+        assert!(code.contains("function encodeBorsh<T>("));
+        assert!(code.contains("export function migrateProfileV2ToV3(data: Buffer): Uint8Array {"));
+    }
+
+    #[test]
+    fn generates_fixed_arrays() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "#[solana]\nstruct Seeds { hash: [u8; 32], owners: [PublicKey; 2] }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("hash: number[];"));
+        assert!(code.contains("borsh.array(borsh.u8, 32)('hash')"));
+        assert!(code.contains("borsh.array(borsh.publicKey, 2)('owners')"));
+    }
+
+    #[test]
+    fn delegates_codec_fields() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file("#[solana]\nstruct Vault { #[codec(rust = \"crate::codecs::compact_u64\", ts = \"./codecs/compactU64\")] amount: u64, owner: PublicKey }").unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("import * as codecsCompactU64 from './codecs/compactU64';"));
+        assert!(code
+            .contains("codecsCompactU64.layout('amount'), // layout owned by ./codecs/compactU64"));
+        assert!(code.contains("amount: number;"));
+    }
+
+    #[test]
+    fn keeps_options_nested_in_vecs() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "#[solana]\nstruct Votes { ballots: Vec<Option<u32>> }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("ballots: (number | undefined)[];"));
+        assert!(code.contains("borsh.vec(borsh.option(borsh.u32))('ballots')"));
+    }
+
+    #[test]
+    fn generates_maps() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "#[solana]\nstruct Pool { stakes: HashMap<PublicKey, u64>, claims: HashMap<PublicKey, Option<u64>> }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("stakes: Map<PublicKey, number>;"));
+        assert!(code.contains("borsh.map(borsh.publicKey, borsh.u64)('stakes')"));
+        assert!(code.contains("claims: Map<PublicKey, number | undefined>;"));
+        assert!(code.contains("borsh.map(borsh.publicKey, borsh.option(borsh.u64))('claims')"));
+    }
+
+    #[test]
+    fn leaves_out_skipped_types_and_fields() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "#[skip(typescript)]\nstruct Scratch { cursor: u64 }\n\
+                 struct Order { id: u64, #[skip(typescript)] scratch: Scratch }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(!code.contains("Scratch") && !code.contains("scratch"));
+        assert!(code.contains("export interface Order"));
+
+        let rust = crate::generators::rust::generate_module(&ir);
+        assert!(rust.contains("pub struct Scratch") && rust.contains("pub scratch: Scratch,"));
+    }
+
+    #[test]
+    fn converts_type_names_to_constant_case() {
+        assert_eq!(to_screaming_snake_case("GameState"), "GAME_STATE");
+        assert_eq!(to_screaming_snake_case("NFTKind"), "NFT_KIND");
+        assert_eq!(to_screaming_snake_case("Side"), "SIDE");
+        assert_eq!(to_screaming_snake_case("TierV2"), "TIER_V2");
+    }
+
+    #[test]
+    fn generates_to_string_and_parse_on_request() {
+        let source = r#"
+            #[solana]
+            #[display]
+            enum Side { Bid, Ask }
+
+            #[display]
+            struct Lamports { value: u64 }
+
+            #[display]
+            struct Authority { key: PublicKey }
+        "#;
+        let type_defs =
+            crate::transform::transform_to_ir(crate::parser::parse_lumos_file(source).unwrap())
+                .unwrap();
+
+        let code = generate_module(&type_defs);
+        assert!(code.contains(
+            "export function sideToString(value: Side): string {\n  return value.kind;\n}"
+        ));
+        assert!(code.contains(
+            "export function parseSide(text: string): Side | undefined {\n  return SIDE_VALUES.find((v) => v.kind === text);\n}"
+        ));
+        assert!(code.contains("export function lamportsToString(value: Lamports): string {\n  return value.value.toString();\n}"));
+        assert!(code.contains("  if (!/^\\+?\\d+$/.test(text)) return undefined;\n  return { value: Number(text) };\n"));
+        assert!(code.contains("    return { key: new PublicKey(text) };"));
+        assert!(generate(&type_defs[1]).contains("export function parseLamports("));
+    }
+
+    #[test]
+    fn generates_instruction_encoders() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "#[solana]\n#[account]\nstruct Vault { balance: u64 }\n\
+                 #[solana]\n#[instruction]\nstruct Initialize { #[account(mut)] vault: Vault, #[signer] payer: PublicKey, bump: u8 }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("export interface Initialize {\n  bump: number;\n}"));
+        assert!(code
+            .contains("export const InitializeSchema = borsh.struct([\n  borsh.u8('bump'),\n]);"));
+        assert!(code.contains(
+            "export interface InitializeAccounts {\n  vault: PublicKey;\n  payer: PublicKey;\n}"
+        ));
+        // sha256("global:initialize")[..8], as emitted by Anchor
+        assert!(code.contains(
+            "export const INITIALIZE_DISCRIMINATOR = Uint8Array.from([175, 175, 109, 31, 13, 152, 155, 237]);"
+        ));
+        assert!(code.contains(
+            "export function encodeInitializeInstruction(args: Initialize): Uint8Array {"
+        ));
+        assert!(code.contains("function encodeBorsh<T>("));
+        assert!(!code.contains("async function sha256"));
+    }
+
+    #[test]
+    fn generates_validation_for_constrained_fields() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "#[solana] struct Profile { #[min(3)] #[max(32)] name: String, #[max(8)] tags: Vec<u8>, #[range(0, 10000)] fee_bps: u16, #[range(-5, 5)] bias: Option<i64>, plain: u8 }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(
+            code.contains("export function validateProfile(value: Profile): string | undefined {")
+        );
+        assert!(code.contains(
+            "const utf8Length = (text: string) => new TextEncoder().encode(text).length;"
+        ));
+        assert!(code.contains("if (utf8Length(value.name) < 3 || utf8Length(value.name) > 32) {"));
+        assert!(code.contains(
+            "if (value.tags.length > 8) {\n    return 'Profile.tags must be at most 8 items long';"
+        ));
+        assert!(code.contains("if (value.fee_bps < 0 || value.fee_bps > 10000) {"));
+        assert!(code.contains("if (value.bias != null && (value.bias < -5 || value.bias > 5)) {"));
+        assert!(!code.contains("value.plain"));
+    }
+
+    #[test]
+    fn validates_nested_structs() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                struct Limits { #[range(1, 10)] level: u8 }
+                struct Wrapper { inner: Limits }
+                struct Plain { x: u8 }
+                struct Profile {
+                    #[max(8)]
+                    name: String,
+                    limits: Limits,
+                    history: Vec<Limits>,
+                    backup: Option<Limits>,
+                    slots: Vec<Option<Limits>>,
+                    by_id: BTreeMap<u8, Limits>,
+                    wrapper: Wrapper,
+                    plain: Plain,
+                }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains(
+            "export function validateProfile(value: Profile): string | undefined {\n\
+             \x20 const utf8Length = (text: string) => new TextEncoder().encode(text).length;\n\
+             \x20 let error: string | undefined;\n"
+        ));
+        assert!(code.contains(
+            "  error = validateLimits(value.limits);\n\
+             \x20 if (error !== undefined) {\n\
+             \x20   return error;\n\
+             \x20 }\n\
+             \x20 for (const item of value.history) {\n\
+             \x20   error = validateLimits(item);\n"
+        ));
+        assert!(code.contains(
+            "  if (value.backup != null) {\n    error = validateLimits(value.backup);\n"
+        ));
+        assert!(code.contains(
+            "  for (const item of value.slots) {\n    if (item != null) {\n      error = validateLimits(item);\n"
+        ));
+        assert!(code.contains("  for (const item of value.by_id.values()) {\n"));
+        assert!(code.contains("  error = validateWrapper(value.wrapper);\n"));
+        assert!(code.contains("export function validateWrapper(value: Wrapper)"));
+        assert!(!code.contains("validatePlain"));
+    }
+
+    #[test]
+    fn carries_doc_comments_into_tsdoc() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                /// A user's vault.
+                ///
+                /// Holds lamports.
+                #[solana]
+                struct Vault {
+                    /// Who may withdraw
+                    owner: PublicKey,
+                    /**
+                     * Lamports held
+                     */
+                    balance: u64,
+                }
+
+                /// Lifecycle of a vault
+                #[solana]
+                enum Status { Open, Closed { /// When it closed
+                    at: i64 } }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        let vault_docs = "/**\n * A user's vault.\n *\n * Holds lamports.\n */\n";
+        assert!(code.contains(&format!("{}export interface Vault {{", vault_docs)));
+        assert!(code.contains(&format!("{}export const VaultSchema", vault_docs)));
+        assert!(code.contains("  /** Who may withdraw */\n  owner: PublicKey;\n"));
+        assert!(code.contains("  /**\n   * Lamports held\n   *\n   * WARNING: TypeScript 'number'"));
+        assert!(code.contains("/**\n * Lifecycle of a vault\n *\n * WARNING: Some variants"));
+        assert!(code.contains("/** Lifecycle of a vault */\nexport const StatusSchema"));
+
+        assert_eq!(
+            doc_block(&["Ends */ early".to_string()], ""),
+            "/** Ends *\\/ early */\n"
+        );
+    }
+
+    #[test]
+    fn generates_test_vector_checks() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                #[solana]
+                enum State { Open, Closed { at: i64 } }
+
+                #[solana]
+                #[test_vector(owner = "11111111111111111111111111111111", supply = "340282366920938463463374607431768211455", state = {"Closed": {"at": -1}}, memo = "it's")]
+                struct Vault { owner: PublicKey, supply: u128, state: State, memo: String }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert!(code.contains("function encodeBorsh<T>"));
+        assert!(code.contains("function checkTestVectors<T>("));
+        assert!(code.contains(&format!(
+            "  {{ value: {{ owner: new PublicKey('11111111111111111111111111111111'), supply: 340282366920938463463374607431768211455n, state: {{ kind: 'Closed', at: -1 }}, memo: 'it\\'s' }}, hex: '{}{}01ffffffffffffffff0400000069742773' }},\n",
+            "00".repeat(32),
+            "ff".repeat(16)
+        )));
+        assert!(code.contains(
+            "export function checkVaultTestVectors(): void {\n  checkTestVectors('Vault', VaultSchema, VAULT_TEST_VECTORS);\n}\n"
+        ));
+
+        let plain = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file("#[solana] struct Plain { x: u8 }").unwrap(),
+        )
+        .unwrap();
+        assert!(!generate_module(&plain).contains("checkTestVectors"));
+    }
+
+    #[test]
+    fn generates_account_decoders_with_discriminator_check() {
+        let ir = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                r#"
+                #[solana]
+                #[account]
+                struct PlayerAccount { owner: PublicKey }
+
+                #[solana]
+                #[account]
+                #[version(2)]
+                struct Profile { name: String, #[since(2)] bio: Option<String> }
+
+                #[solana]
+                #[account]
+                #[zero_copy]
+                struct Board { cells: [u8; 9] }
+
+                #[solana]
+                struct Move { x: u8 }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let code = generate_module(&ir);
+        assert_eq!(
+            code.matches("export class AccountDiscriminatorError")
+                .count(),
+            1
+        );
+        assert!(code.contains(
+            "export const PLAYER_ACCOUNT_DISCRIMINATOR = Uint8Array.from([224, 184, 224, 50, 98, 72, 48, 236]);\n"
+        ));
+        assert!(code.contains(
+            "export function decodePlayerAccount(data: Buffer): PlayerAccount {\n  checkAccountDiscriminator('PlayerAccount', PLAYER_ACCOUNT_DISCRIMINATOR, data);\n  return PlayerAccountSchema.decode(data.subarray(8));\n}\n"
+        ));
+        assert!(code.contains("  return decodeProfileAnyVersion(data.subarray(8));\n"));
+        assert!(!code.contains("decodeBoard("));
+        assert!(!code.contains("decodeMove("));
+
+        let plain = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file("#[solana] struct Move { x: u8 }").unwrap(),
+        )
+        .unwrap();
+        assert!(!generate_module(&plain).contains("AccountDiscriminatorError"));
+    }
 }
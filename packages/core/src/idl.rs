@@ -0,0 +1,651 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Anchor IDL export and drift detection
+//!
+//! Exports the schema's `#[account]` types, and every type they reference,
+//! in the Anchor IDL format, and compares that export with the IDL of a
+//! deployed program. Drift means the program on-chain decodes accounts
+//! differently from what the repository describes.
+//!
+//! Both the current IDL format (Anchor 0.30+) and the legacy one are read:
+//! `publicKey` and `pubkey` are the same type, camelCase field names match
+//! their snake_case schema names, and legacy accounts carry their layout
+//! inline instead of in `types`. Account discriminators are derived from
+//! the account name, so comparing names covers them.
+//!
+//! Types that only exist in the deployed IDL are not reported; programs
+//! declare instruction argument types that a schema does not describe.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::{idl, parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "#[solana]\n#[account]\nstruct Vault { owner: PublicKey, amount: u64 }",
+//! )?)?;
+//! let local = idl::export(&ir);
+//!
+//! let deployed = serde_json::json!({
+//!     "accounts": [{ "name": "Vault", "discriminator": [211, 8, 232, 43, 2, 152, 117, 119] }],
+//!     "types": [{
+//!         "name": "Vault",
+//!         "type": { "kind": "struct", "fields": [
+//!             { "name": "owner", "type": "pubkey" },
+//!             { "name": "amount", "type": "u32" },
+//!         ] },
+//!     }],
+//! });
+//!
+//! let drift = idl::diff(&local, &deployed)?;
+//! assert_eq!(drift[0].location, "Vault.amount");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::{LumosError, Result};
+use crate::ir::{EnumVariantDefinition, FieldDefinition, MapKind, TypeDefinition, TypeInfo};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Kind of drift between the schema and a deployed program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriftKind {
+    /// Schema account the deployed program does not declare
+    MissingAccount,
+
+    /// Deployed account the schema does not declare
+    UnexpectedAccount,
+
+    /// Type referenced by a schema account but absent on-chain
+    MissingType,
+
+    /// Struct in one IDL, enum in the other
+    KindMismatch,
+
+    /// Fields or variants differ in name, type or position
+    LayoutMismatch,
+}
+
+impl DriftKind {
+    /// Stable identifier for tooling
+    pub fn id(&self) -> &'static str {
+        match self {
+            DriftKind::MissingAccount => "missing-account",
+            DriftKind::UnexpectedAccount => "unexpected-account",
+            DriftKind::MissingType => "missing-type",
+            DriftKind::KindMismatch => "kind-mismatch",
+            DriftKind::LayoutMismatch => "layout-mismatch",
+        }
+    }
+
+    /// Human-readable name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DriftKind::MissingAccount => "Missing Account",
+            DriftKind::UnexpectedAccount => "Unexpected Account",
+            DriftKind::MissingType => "Missing Type",
+            DriftKind::KindMismatch => "Kind Mismatch",
+            DriftKind::LayoutMismatch => "Layout Mismatch",
+        }
+    }
+}
+
+/// A difference between the schema and the deployed IDL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdlDrift {
+    /// Kind of drift
+    pub kind: DriftKind,
+
+    /// Location, e.g. `Vault.amount` or `Status::Active`
+    pub location: String,
+
+    /// What differs
+    pub message: String,
+}
+
+/// Export `#[account]` types and the types they reference as an Anchor IDL
+///
+/// Only `accounts` and `types` are emitted; the schema does not describe
+/// instructions.
+pub fn export(type_defs: &[TypeDefinition]) -> Value {
+    let accounts: Vec<&TypeDefinition> = type_defs.iter().filter(|t| t.is_account()).collect();
+
+    let mut reachable = BTreeSet::new();
+    let mut pending: Vec<&str> = accounts.iter().map(|t| t.name()).collect();
+    while let Some(name) = pending.pop() {
+        if !reachable.insert(name.to_string()) {
+            continue;
+        }
+        if let Some(type_def) = type_defs.iter().find(|t| t.name() == name) {
+            type_def.referenced_types(&mut pending);
+        }
+    }
+
+    let types: Vec<Value> = type_defs
+        .iter()
+        .filter(|t| reachable.contains(t.name()))
+        .map(export_type)
+        .collect();
+
+    json!({
+        "accounts": accounts
+            .iter()
+            .map(|t| json!({ "name": t.name() }))
+            .collect::<Vec<_>>(),
+        "types": types,
+    })
+}
+
+/// Compare a `local` IDL (usually from [`export`]) with a `deployed` one
+pub fn diff(local: &Value, deployed: &Value) -> Result<Vec<IdlDrift>> {
+    let local_idl = NormalizedIdl::read(local)?;
+    let deployed_idl = NormalizedIdl::read(deployed)?;
+    let mut drift = Vec::new();
+
+    for name in local_idl.accounts.difference(&deployed_idl.accounts) {
+        drift.push(IdlDrift {
+            kind: DriftKind::MissingAccount,
+            location: name.clone(),
+            message: "is an account in the schema but not in the deployed program".to_string(),
+        });
+    }
+    for name in deployed_idl.accounts.difference(&local_idl.accounts) {
+        drift.push(IdlDrift {
+            kind: DriftKind::UnexpectedAccount,
+            location: name.clone(),
+            message: "is an account in the deployed program but not in the schema".to_string(),
+        });
+    }
+
+    for (name, local_shape) in &local_idl.types {
+        match deployed_idl.types.get(name) {
+            Some(deployed_shape) => compare(name, local_shape, deployed_shape, &mut drift),
+            // Already reported as a missing account
+            None if !deployed_idl.accounts.contains(name) && local_idl.accounts.contains(name) => {}
+            None => drift.push(IdlDrift {
+                kind: DriftKind::MissingType,
+                location: name.clone(),
+                message: "is not defined in the deployed IDL".to_string(),
+            }),
+        }
+    }
+
+    Ok(drift)
+}
+
+pub(crate) fn export_type(type_def: &TypeDefinition) -> Value {
+    let ty = match type_def {
+        TypeDefinition::Struct(s) => json!({
+            "kind": "struct",
+            "fields": export_fields(&s.fields),
+        }),
+        TypeDefinition::Enum(e) => {
+            let variants: Vec<Value> = e
+                .variants
+                .iter()
+                .map(|variant| match variant {
+                    EnumVariantDefinition::Unit { name } => json!({ "name": name }),
+                    EnumVariantDefinition::Tuple { name, types } => json!({
+                        "name": name,
+                        "fields": types.iter().map(idl_type).collect::<Vec<_>>(),
+                    }),
+                    EnumVariantDefinition::Struct { name, fields } => json!({
+                        "name": name,
+                        "fields": export_fields(fields),
+                    }),
+                })
+                .collect();
+            json!({ "kind": "enum", "variants": variants })
+        }
+    };
+    json!({ "name": type_def.name(), "type": ty })
+}
+
+fn export_fields(fields: &[FieldDefinition]) -> Vec<Value> {
+    fields
+        .iter()
+        .map(|f| json!({ "name": f.name, "type": idl_type(&f.type_info) }))
+        .collect()
+}
+
+/// IDL type of a schema type, matching what the Rust generator emits
+pub(crate) fn idl_type(type_info: &TypeInfo) -> Value {
+    match type_info {
+        TypeInfo::Primitive(name) => match name.as_str() {
+            "PublicKey" | "Pubkey" => json!("pubkey"),
+            // Signatures are generated as base58 `String`s
+            "String" | "Signature" => json!("string"),
+            other => json!(other),
+        },
+        TypeInfo::UserDefined(name) => json!({ "defined": { "name": name } }),
+        TypeInfo::Array(inner) => json!({ "vec": idl_type(inner) }),
+        TypeInfo::FixedArray(inner, len) => json!({ "array": [idl_type(inner), len] }),
+        TypeInfo::Map(MapKind::HashMap, key, value) => {
+            json!({ "hashMap": [idl_type(key), idl_type(value)] })
+        }
+        TypeInfo::Map(MapKind::BTreeMap, key, value) => {
+            json!({ "bTreeMap": [idl_type(key), idl_type(value)] })
+        }
+        TypeInfo::Option(inner) => json!({ "option": idl_type(inner) }),
+    }
+}
+
+/// Layout of a type, with types in schema notation (see [`crate::evolution::signature`])
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Struct(Vec<(String, String)>),
+    Enum(Vec<(String, Vec<(String, String)>)>),
+    /// Aliases and other kinds, compared by kind only
+    Other(String),
+}
+
+impl Shape {
+    fn kind(&self) -> &str {
+        match self {
+            Shape::Struct(_) => "struct",
+            Shape::Enum(_) => "enum",
+            Shape::Other(kind) => kind,
+        }
+    }
+}
+
+struct NormalizedIdl {
+    accounts: BTreeSet<String>,
+    types: BTreeMap<String, Shape>,
+}
+
+impl NormalizedIdl {
+    fn read(idl: &Value) -> Result<Self> {
+        let mut accounts = BTreeSet::new();
+        let mut types = BTreeMap::new();
+
+        for account in entries(idl, "accounts")? {
+            let name = str_field(account, "name")?;
+            // Legacy IDLs declare account layouts inline
+            if let Some(ty) = account.get("type") {
+                types.insert(name.to_string(), read_shape(ty)?);
+            }
+            accounts.insert(name.to_string());
+        }
+        for type_def in entries(idl, "types")? {
+            let name = str_field(type_def, "name")?;
+            let ty = type_def
+                .get("type")
+                .ok_or_else(|| invalid(format!("type `{}` has no `type`", name)))?;
+            types.insert(name.to_string(), read_shape(ty)?);
+        }
+
+        Ok(NormalizedIdl { accounts, types })
+    }
+}
+
+fn entries<'a>(idl: &'a Value, key: &str) -> Result<&'a [Value]> {
+    match idl.get(key) {
+        None => Ok(&[]),
+        Some(Value::Array(items)) => Ok(items),
+        Some(_) => Err(invalid(format!("`{}` is not an array", key))),
+    }
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Result<&'a str> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid(format!("entry without a string `{}`: {}", key, value)))
+}
+
+fn read_shape(ty: &Value) -> Result<Shape> {
+    let kind = str_field(ty, "kind")?;
+    match kind {
+        "struct" => Ok(Shape::Struct(read_fields(ty.get("fields"))?)),
+        "enum" => {
+            let variants = ty
+                .get("variants")
+                .and_then(Value::as_array)
+                .ok_or_else(|| invalid("enum without `variants`".to_string()))?;
+            variants
+                .iter()
+                .map(|variant| {
+                    Ok((
+                        str_field(variant, "name")?.to_string(),
+                        read_fields(variant.get("fields"))?,
+                    ))
+                })
+                .collect::<Result<_>>()
+                .map(Shape::Enum)
+        }
+        other => Ok(Shape::Other(other.to_string())),
+    }
+}
+
+/// Named fields, or tuple fields named by position
+fn read_fields(fields: Option<&Value>) -> Result<Vec<(String, String)>> {
+    let Some(fields) = fields else {
+        return Ok(Vec::new());
+    };
+    let fields = fields
+        .as_array()
+        .ok_or_else(|| invalid("`fields` is not an array".to_string()))?;
+
+    fields
+        .iter()
+        .enumerate()
+        .map(
+            |(index, field)| match (field.get("name"), field.get("type")) {
+                (Some(Value::String(name)), Some(ty)) => Ok((to_snake_case(name), read_type(ty)?)),
+                _ => Ok((index.to_string(), read_type(field)?)),
+            },
+        )
+        .collect()
+}
+
+/// IDL type in schema notation, e.g. `[u8]` or `Option<PublicKey>`
+fn read_type(ty: &Value) -> Result<String> {
+    if let Some(name) = ty.as_str() {
+        return Ok(match name {
+            "publicKey" | "pubkey" => "PublicKey".to_string(),
+            "string" => "String".to_string(),
+            "bytes" => "[u8]".to_string(),
+            other => other.to_string(),
+        });
+    }
+
+    if let Some(inner) = ty.get("vec") {
+        return Ok(format!("[{}]", read_type(inner)?));
+    }
+    if let Some(inner) = ty.get("option").or_else(|| ty.get("coption")) {
+        return Ok(format!("Option<{}>", read_type(inner)?));
+    }
+    if let Some(Value::Array(array)) = ty.get("array") {
+        if let [inner, len] = array.as_slice() {
+            return Ok(format!("[{}; {}]", read_type(inner)?, len));
+        }
+    }
+    for (tag, kind) in [
+        ("hashMap", MapKind::HashMap),
+        ("bTreeMap", MapKind::BTreeMap),
+    ] {
+        if let Some(Value::Array(array)) = ty.get(tag) {
+            if let [key, value] = array.as_slice() {
+                return Ok(format!(
+                    "{}<{}, {}>",
+                    kind.as_str(),
+                    read_type(key)?,
+                    read_type(value)?
+                ));
+            }
+        }
+    }
+    match ty.get("defined") {
+        Some(Value::String(name)) => Ok(name.clone()),
+        Some(defined) => Ok(str_field(defined, "name")?.to_string()),
+        None => Err(invalid(format!("unsupported type: {}", ty))),
+    }
+}
+
+fn invalid(message: String) -> LumosError {
+    LumosError::SchemaParse(format!("Invalid IDL: {}", message), None)
+}
+
+/// `lastUpdated` -> `last_updated`; snake_case names are unchanged
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            result.push('_');
+        }
+        result.push(ch.to_ascii_lowercase());
+    }
+    result
+}
+
+fn compare(name: &str, local: &Shape, deployed: &Shape, drift: &mut Vec<IdlDrift>) {
+    match (local, deployed) {
+        (Shape::Struct(local), Shape::Struct(deployed)) => {
+            compare_fields(name, local, deployed, drift)
+        }
+        (Shape::Enum(local), Shape::Enum(deployed)) => {
+            for index in 0..local.len().max(deployed.len()) {
+                match (local.get(index), deployed.get(index)) {
+                    (Some((local_name, local_fields)), Some((deployed_name, deployed_fields))) => {
+                        let location = format!("{}::{}", name, local_name);
+                        if local_name != deployed_name {
+                            drift.push(layout(
+                                location,
+                                format!(
+                                    "is `{}` on-chain; variant order sets the Borsh tag",
+                                    deployed_name
+                                ),
+                            ));
+                        } else {
+                            compare_fields(&location, local_fields, deployed_fields, drift);
+                        }
+                    }
+                    (Some((local_name, _)), None) => drift.push(layout(
+                        format!("{}::{}", name, local_name),
+                        "is missing on-chain".to_string(),
+                    )),
+                    (None, Some((deployed_name, _))) => drift.push(layout(
+                        format!("{}::{}", name, deployed_name),
+                        "exists on-chain but not in the schema".to_string(),
+                    )),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        _ if local.kind() == deployed.kind() => {}
+        _ => drift.push(IdlDrift {
+            kind: DriftKind::KindMismatch,
+            location: name.to_string(),
+            message: format!(
+                "is {} {} in the schema but {} {} on-chain",
+                article(local.kind()),
+                local.kind(),
+                article(deployed.kind()),
+                deployed.kind()
+            ),
+        }),
+    }
+}
+
+fn compare_fields(
+    owner: &str,
+    local: &[(String, String)],
+    deployed: &[(String, String)],
+    drift: &mut Vec<IdlDrift>,
+) {
+    for index in 0..local.len().max(deployed.len()) {
+        match (local.get(index), deployed.get(index)) {
+            (Some((local_name, local_type)), Some((deployed_name, deployed_type))) => {
+                let location = format!("{}.{}", owner, local_name);
+                if local_name != deployed_name {
+                    drift.push(layout(
+                        location,
+                        format!("is `{}` on-chain at position {}", deployed_name, index),
+                    ));
+                } else if local_type != deployed_type {
+                    drift.push(layout(
+                        location,
+                        format!(
+                            "is {} on-chain but {} in the schema",
+                            deployed_type, local_type
+                        ),
+                    ));
+                }
+            }
+            (Some((local_name, _)), None) => drift.push(layout(
+                format!("{}.{}", owner, local_name),
+                "is missing on-chain".to_string(),
+            )),
+            (None, Some((deployed_name, _))) => drift.push(layout(
+                format!("{}.{}", owner, deployed_name),
+                "exists on-chain but not in the schema".to_string(),
+            )),
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+fn layout(location: String, message: String) -> IdlDrift {
+    IdlDrift {
+        kind: DriftKind::LayoutMismatch,
+        location,
+        message,
+    }
+}
+
+fn article(kind: &str) -> &'static str {
+    match kind.chars().next() {
+        Some('a' | 'e' | 'i' | 'o' | 'u') => "an",
+        _ => "a",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        #[solana]
+        #[account]
+        struct Vault {
+            owner: PublicKey,
+            amount: u64,
+            last_updated: i64,
+            status: Status,
+            tags: [String],
+        }
+
+        #[solana]
+        enum Status { Active, Frozen { until: Option<i64> } }
+
+        #[solana]
+        struct Unused { value: u8 }
+    "#;
+
+    fn local() -> Value {
+        export(&transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn exports_accounts_and_referenced_types() {
+        let idl = local();
+        assert_eq!(idl["accounts"], json!([{ "name": "Vault" }]));
+
+        let names: Vec<&str> = idl["types"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["Vault", "Status"]);
+        assert_eq!(
+            idl["types"][0]["type"]["fields"][3],
+            json!({ "name": "status", "type": { "defined": { "name": "Status" } } })
+        );
+        assert_eq!(
+            idl["types"][1]["type"]["variants"][1]["fields"][0]["type"],
+            json!({ "option": "i64" })
+        );
+    }
+
+    #[test]
+    fn matching_legacy_idl_has_no_drift() {
+        let deployed = json!({
+            "accounts": [{
+                "name": "Vault",
+                "type": { "kind": "struct", "fields": [
+                    { "name": "owner", "type": "publicKey" },
+                    { "name": "amount", "type": "u64" },
+                    { "name": "lastUpdated", "type": "i64" },
+                    { "name": "status", "type": { "defined": "Status" } },
+                    { "name": "tags", "type": { "vec": "string" } },
+                ] },
+            }],
+            "types": [{
+                "name": "Status",
+                "type": { "kind": "enum", "variants": [
+                    { "name": "Active" },
+                    { "name": "Frozen", "fields": [{ "name": "until", "type": { "option": "i64" } }] },
+                ] },
+            }],
+        });
+
+        assert_eq!(diff(&local(), &deployed).unwrap(), Vec::new());
+        assert_eq!(diff(&local(), &local()).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn reports_drift() {
+        let deployed = json!({
+            "accounts": [
+                { "name": "Vault", "discriminator": [211, 8, 232, 43, 2, 152, 117, 119] },
+                { "name": "Config", "discriminator": [155, 12, 170, 224, 30, 250, 204, 130] },
+            ],
+            "types": [
+                {
+                    "name": "Vault",
+                    "type": { "kind": "struct", "fields": [
+                        { "name": "owner", "type": "pubkey" },
+                        { "name": "amount", "type": "u32" },
+                        { "name": "created_at", "type": "i64" },
+                        { "name": "status", "type": { "defined": { "name": "Status" } } },
+                        { "name": "tags", "type": { "vec": "string" } },
+                        { "name": "bump", "type": "u8" },
+                    ] },
+                },
+                {
+                    "name": "Status",
+                    "type": { "kind": "enum", "variants": [{ "name": "Frozen" }, { "name": "Active" }] },
+                },
+                { "name": "Config", "type": { "kind": "struct", "fields": [] } },
+            ],
+        });
+
+        let drift = diff(&local(), &deployed).unwrap();
+        let found: Vec<(DriftKind, &str)> = drift
+            .iter()
+            .map(|d| (d.kind, d.location.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (DriftKind::UnexpectedAccount, "Config"),
+                (DriftKind::LayoutMismatch, "Status::Active"),
+                (DriftKind::LayoutMismatch, "Status::Frozen"),
+                (DriftKind::LayoutMismatch, "Vault.amount"),
+                (DriftKind::LayoutMismatch, "Vault.last_updated"),
+                (DriftKind::LayoutMismatch, "Vault.bump"),
+            ]
+        );
+        assert_eq!(drift[3].message, "is u32 on-chain but u64 in the schema");
+        assert_eq!(drift[4].message, "is `created_at` on-chain at position 2");
+        assert_eq!(drift[5].message, "exists on-chain but not in the schema");
+    }
+
+    #[test]
+    fn reports_missing_accounts_and_kind_changes() {
+        let deployed = json!({
+            "types": [{ "name": "Status", "type": { "kind": "struct", "fields": [] } }],
+        });
+
+        let drift = diff(&local(), &deployed).unwrap();
+        assert_eq!(drift.len(), 2);
+        assert_eq!(drift[0].kind, DriftKind::MissingAccount);
+        assert_eq!(drift[0].location, "Vault");
+        assert_eq!(drift[1].kind, DriftKind::KindMismatch);
+        assert_eq!(
+            drift[1].message,
+            "is an enum in the schema but a struct on-chain"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_idl() {
+        let err = diff(&local(), &json!({ "types": [{ "name": "Vault" }] })).unwrap_err();
+        assert!(err.to_string().contains("Invalid IDL"));
+    }
+}
@@ -0,0 +1,280 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Instruction data decoding for explorer-style debugging
+//!
+//! Raw instruction data from a failed transaction is matched against every
+//! layout the schema allows:
+//!
+//! - **Anchor**: an `#[instruction]` struct after the 8-byte discriminator
+//!   `sha256("global:<snake_case name>")[..8]`
+//! - **Dispatch**: a tag byte selecting an `#[instruction]` type in schema
+//!   order, then its arguments, as in the generated fuzz dispatcher
+//! - **Borsh**: any enum, whose variant tag selects the instruction
+//!
+//! A candidate is any layout whose prefix matches; it decodes when its
+//! arguments consume the data exactly.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::instruction_decoder::{decode_instruction, InstructionEncoding};
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "enum VaultInstruction { Deposit { amount: u64 }, Withdraw { amount: u64 } }",
+//! )?)?;
+//!
+//! let data = [1, 10, 0, 0, 0, 0, 0, 0, 0];
+//! let candidates = decode_instruction(&ir, &data);
+//! assert_eq!(candidates[0].encoding, InstructionEncoding::Borsh);
+//! assert_eq!(
+//!     candidates[0].result.as_ref().unwrap().to_string(),
+//!     "Withdraw {\n  amount: 10\n}"
+//! );
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::borsh_decoder::{BorshDecoder, DecodeError, DecodedValue};
+use crate::ir::{anchor_discriminator, TypeDefinition, TypeInfo};
+
+/// How the instruction data selected its type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionEncoding {
+    /// 8-byte Anchor discriminator
+    Anchor,
+
+    /// Tag byte in `#[instruction]` order
+    Dispatch,
+
+    /// Borsh enum variant tag
+    Borsh,
+}
+
+impl InstructionEncoding {
+    /// Stable identifier for tooling
+    pub fn id(&self) -> &'static str {
+        match self {
+            InstructionEncoding::Anchor => "anchor",
+            InstructionEncoding::Dispatch => "dispatch",
+            InstructionEncoding::Borsh => "borsh",
+        }
+    }
+
+    /// Human-readable description
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InstructionEncoding::Anchor => "Anchor instruction",
+            InstructionEncoding::Dispatch => "dispatch tag",
+            InstructionEncoding::Borsh => "Borsh enum",
+        }
+    }
+}
+
+/// A schema type the instruction data may encode
+#[derive(Debug, Clone)]
+pub struct InstructionCandidate {
+    /// Schema type
+    pub name: String,
+
+    /// How the data selected it
+    pub encoding: InstructionEncoding,
+
+    /// Decoded arguments, or why they failed to decode
+    pub result: Result<DecodedValue, DecodeError>,
+}
+
+/// Anchor discriminator of the instruction handler for type `name`
+pub fn instruction_discriminator(name: &str) -> [u8; 8] {
+    anchor_discriminator("global", &to_snake_case(name))
+}
+
+/// Every layout whose prefix matches `data`, in the order listed above
+pub fn decode_instruction(type_defs: &[TypeDefinition], data: &[u8]) -> Vec<InstructionCandidate> {
+    let decoder = BorshDecoder::new(type_defs);
+    let instructions: Vec<&TypeDefinition> =
+        type_defs.iter().filter(|t| t.is_instruction()).collect();
+    let mut candidates = Vec::new();
+
+    for type_def in &instructions {
+        if matches!(type_def, TypeDefinition::Struct(_))
+            && data.starts_with(&instruction_discriminator(type_def.name()))
+        {
+            candidates.push(candidate(
+                &decoder,
+                type_def.name(),
+                InstructionEncoding::Anchor,
+                data,
+                8,
+            ));
+        }
+    }
+
+    if let Some(type_def) = data.first().and_then(|&tag| instructions.get(tag as usize)) {
+        candidates.push(candidate(
+            &decoder,
+            type_def.name(),
+            InstructionEncoding::Dispatch,
+            data,
+            1,
+        ));
+    }
+
+    for type_def in type_defs {
+        if let TypeDefinition::Enum(e) = type_def {
+            if data
+                .first()
+                .is_some_and(|&tag| (tag as usize) < e.variants.len())
+            {
+                candidates.push(candidate(
+                    &decoder,
+                    &e.name,
+                    InstructionEncoding::Borsh,
+                    data,
+                    0,
+                ));
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Decode `data` as the type `name`, which must consume it exactly
+pub fn decode_as(
+    type_defs: &[TypeDefinition],
+    name: &str,
+    data: &[u8],
+) -> Result<DecodedValue, DecodeError> {
+    decode_exact(&BorshDecoder::new(type_defs), name, data, 0)
+}
+
+fn candidate(
+    decoder: &BorshDecoder,
+    name: &str,
+    encoding: InstructionEncoding,
+    data: &[u8],
+    offset: usize,
+) -> InstructionCandidate {
+    InstructionCandidate {
+        name: name.to_string(),
+        encoding,
+        result: decode_exact(decoder, name, data, offset),
+    }
+}
+
+fn decode_exact(
+    decoder: &BorshDecoder,
+    name: &str,
+    data: &[u8],
+    offset: usize,
+) -> Result<DecodedValue, DecodeError> {
+    let type_info = TypeInfo::UserDefined(name.to_string());
+    let (value, end) = decoder.decode(&type_info, data, offset, name)?;
+    if end < data.len() {
+        return Err(DecodeError {
+            offset: end,
+            path: name.to_string(),
+            message: format!("{} bytes left over after the arguments", data.len() - end),
+        });
+    }
+    Ok(value)
+}
+
+/// `CreateVault` -> `create_vault`, as Anchor names instruction handlers
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            result.push('_');
+        }
+        result.push(ch.to_ascii_lowercase());
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        #[solana]
+        #[instruction]
+        struct Initialize { bump: u8 }
+
+        #[solana]
+        #[account]
+        struct Vault { balance: u64 }
+
+        #[solana]
+        #[instruction]
+        struct Deposit { #[account(mut)] vault: Vault, #[signer] owner: PublicKey, amount: u64 }
+
+        enum Action { Pause, Resume }
+    "#;
+
+    fn ir() -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn computes_anchor_instruction_discriminators() {
+        // sha256("global:initialize")[..8], as emitted by Anchor
+        assert_eq!(
+            instruction_discriminator("Initialize"),
+            [175, 175, 109, 31, 13, 152, 155, 237]
+        );
+    }
+
+    #[test]
+    fn decodes_anchor_instructions() {
+        let mut data = instruction_discriminator("Deposit").to_vec();
+        data.extend_from_slice(&500u64.to_le_bytes());
+
+        let candidates = decode_instruction(&ir(), &data);
+        let decoded: Vec<_> = candidates.iter().filter(|c| c.result.is_ok()).collect();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].name, "Deposit");
+        assert_eq!(decoded[0].encoding, InstructionEncoding::Anchor);
+        assert_eq!(
+            decoded[0].result.as_ref().unwrap().to_string(),
+            "{\n  amount: 500\n}"
+        );
+    }
+
+    #[test]
+    fn tries_dispatch_tags_and_enums() {
+        let candidates = decode_instruction(&ir(), &[1, 7, 0, 0, 0, 0, 0, 0, 0]);
+        let found: Vec<_> = candidates
+            .iter()
+            .map(|c| (c.name.as_str(), c.encoding, c.result.is_ok()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("Deposit", InstructionEncoding::Dispatch, true),
+                ("Action", InstructionEncoding::Borsh, false),
+            ]
+        );
+        assert_eq!(
+            candidates[1].result.as_ref().unwrap_err().message,
+            "8 bytes left over after the arguments"
+        );
+
+        let candidates = decode_instruction(&ir(), &[1]);
+        assert_eq!(candidates[1].result.as_ref().unwrap().to_string(), "Resume");
+    }
+
+    #[test]
+    fn decodes_as_a_named_type() {
+        let ir = ir();
+        assert_eq!(
+            decode_as(&ir, "Initialize", &[254]).unwrap().to_string(),
+            "{\n  bump: 254\n}"
+        );
+        let err = decode_as(&ir, "Missing", &[0]).unwrap_err();
+        assert_eq!(err.message, "unknown type `Missing`");
+    }
+}
@@ -6,8 +6,13 @@
 //! The IR is a language-agnostic representation of type definitions
 //! that can be transformed into various target languages.
 
+use crate::ast::{Attribute, AttributeValue};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
 /// Intermediate representation of a type definition (struct or enum)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum TypeDefinition {
     /// Struct definition
     Struct(StructDefinition),
@@ -17,7 +22,7 @@ pub enum TypeDefinition {
 }
 
 /// Struct type definition
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct StructDefinition {
     /// Struct name
     pub name: String,
@@ -30,7 +35,7 @@ pub struct StructDefinition {
 }
 
 /// Enum type definition
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct EnumDefinition {
     /// Enum name
     pub name: String,
@@ -43,7 +48,8 @@ pub struct EnumDefinition {
 }
 
 /// Enum variant definition
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum EnumVariantDefinition {
     /// Unit variant (e.g., `Active`)
     Unit { name: String },
@@ -59,7 +65,7 @@ pub enum EnumVariantDefinition {
 }
 
 /// A field in a type definition
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct FieldDefinition {
     /// Field name
     pub name: String,
@@ -69,10 +75,54 @@ pub struct FieldDefinition {
 
     /// Whether this field is optional
     pub optional: bool,
+
+    /// Field-level attributes (e.g., `#[max(32)]`, `#[account(mut)]`)
+    pub attributes: Vec<Attribute>,
+}
+
+/// Bounds a field's values must stay within, checked by generated `validate` code
+///
+/// Lengths bound a `String` (in UTF-8 bytes) or `Vec`, the range an integer;
+/// on an `Option` field they apply to the value inside.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Constraints {
+    /// Shortest allowed length, from `#[min(n)]`
+    pub min_len: Option<u64>,
+
+    /// Longest allowed length, from `#[max(n)]`
+    pub max_len: Option<u64>,
+
+    /// Inclusive bounds of an integer, from `#[range(min, max)]`
+    pub range: Option<(i128, i128)>,
+}
+
+impl Constraints {
+    /// Whether there is nothing to check
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Message for a length out of bounds, counted in `unit` ("bytes" or "items")
+    pub(crate) fn length_violation(&self, location: &str, unit: &str) -> Option<String> {
+        let bounds = match (self.min_len, self.max_len) {
+            (Some(min), Some(max)) => format!("{} to {}", min, max),
+            (Some(min), None) => format!("at least {}", min),
+            (None, Some(max)) => format!("at most {}", max),
+            (None, None) => return None,
+        };
+        Some(format!("{} must be {} {} long", location, bounds, unit))
+    }
+
+    /// Message for an integer out of range
+    pub(crate) fn range_violation(&self, location: &str) -> Option<String> {
+        let (min, max) = self.range?;
+        Some(format!("{} must be between {} and {}", location, min, max))
+    }
 }
 
 /// Type information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "type", rename_all = "snake_case")]
 pub enum TypeInfo {
     /// Primitive types (u64, string, etc.)
     Primitive(String),
@@ -83,18 +133,307 @@ pub enum TypeInfo {
     /// Array types
     Array(Box<TypeInfo>),
 
+    /// Fixed-size array types, with their length
+    FixedArray(Box<TypeInfo>, usize),
+
+    /// Map types, with their key and value types
+    Map(MapKind, Box<TypeInfo>, Box<TypeInfo>),
+
     /// Option types
     Option(Box<TypeInfo>),
 }
 
+impl TypeInfo {
+    /// The type as written in schemas, e.g. `Option<Vec<PublicKey>>`
+    ///
+    /// `Pubkey` is spelled `PublicKey`, so equal types have equal signatures.
+    pub fn signature(&self) -> String {
+        match self {
+            TypeInfo::Primitive(name) if name == "Pubkey" => "PublicKey".to_string(),
+            TypeInfo::Primitive(name) | TypeInfo::UserDefined(name) => name.clone(),
+            TypeInfo::Array(inner) => format!("Vec<{}>", inner.signature()),
+            TypeInfo::FixedArray(inner, len) => format!("[{}; {}]", inner.signature(), len),
+            TypeInfo::Map(kind, key, value) => format!(
+                "{}<{}, {}>",
+                kind.as_str(),
+                key.signature(),
+                value.signature()
+            ),
+            TypeInfo::Option(inner) => format!("Option<{}>", inner.signature()),
+        }
+    }
+
+    /// The user-defined type a value of this type holds, directly or inside
+    /// an `Option`, `Vec`, array or map value
+    pub fn held_type(&self) -> Option<&str> {
+        match self {
+            TypeInfo::UserDefined(name) => Some(name),
+            TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) | TypeInfo::Option(inner) => {
+                inner.held_type()
+            }
+            TypeInfo::Map(_, _, value) => value.held_type(),
+            TypeInfo::Primitive(_) => None,
+        }
+    }
+}
+
+/// Collection a map type is declared as
+///
+/// Both encode the same in Borsh: a `u32` entry count, then the entries
+/// sorted by key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapKind {
+    /// `HashMap<K, V>`
+    HashMap,
+
+    /// `BTreeMap<K, V>`
+    BTreeMap,
+}
+
+impl MapKind {
+    /// Parse a collection name (`HashMap` or `BTreeMap`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "HashMap" => Some(Self::HashMap),
+            "BTreeMap" => Some(Self::BTreeMap),
+            _ => None,
+        }
+    }
+
+    /// Collection name as written in schemas and Rust
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::HashMap => "HashMap",
+            Self::BTreeMap => "BTreeMap",
+        }
+    }
+}
+
 /// Metadata about a type
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Hash, Serialize, Deserialize)]
 pub struct Metadata {
     /// Whether this is Solana-specific
     pub solana: bool,
 
-    /// Additional attributes
+    /// Names of every attribute on the type, including those promoted to
+    /// typed fields below, for extensions LUMOS doesn't model
     pub attributes: Vec<String>,
+
+    /// What the type is on-chain, from `#[account]`, `#[event]` or `#[instruction]`
+    #[serde(default, skip_serializing_if = "TypeRole::is_plain")]
+    pub role: TypeRole,
+
+    /// Whether the struct is `#[zero_copy]`
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub zero_copy: bool,
+
+    /// Current schema version from `#[version(n)]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+
+    /// Lifecycle intents of an account
+    #[serde(default, skip_serializing_if = "Lifecycle::is_empty")]
+    pub lifecycle: Lifecycle,
+
+    /// Targets the type is not generated for, from `#[skip(typescript)]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip: Vec<String>,
+
+    /// Lines of the type's doc comment, carried into generated code
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub docs: Vec<String>,
+
+    /// Argument lists of `#[test_vector(...)]`, e.g. `balance = 5`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub test_vectors: Vec<String>,
+}
+
+/// What a type is on-chain
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeRole {
+    /// Data embedded in other types
+    #[default]
+    Plain,
+
+    /// Account state (`#[account]`), stored after an 8-byte discriminator
+    Account,
+
+    /// Anchor event (`#[event]`), logged after an 8-byte discriminator
+    Event,
+
+    /// Instruction arguments (`#[instruction]`)
+    Instruction,
+}
+
+impl TypeRole {
+    /// Role declared by the attribute `name`, if it declares one
+    pub fn from_attribute(name: &str) -> Option<Self> {
+        match name {
+            "account" => Some(TypeRole::Account),
+            "event" => Some(TypeRole::Event),
+            "instruction" => Some(TypeRole::Instruction),
+            _ => None,
+        }
+    }
+
+    /// Whether the type is plain data
+    pub fn is_plain(&self) -> bool {
+        *self == TypeRole::Plain
+    }
+}
+
+/// Structs that get a generated `validate`: those with `#[min]`, `#[max]` or
+/// `#[range]` bounds, and those holding one of them in a field
+pub fn validated_structs(type_defs: &[TypeDefinition]) -> Vec<&str> {
+    let structs: Vec<&StructDefinition> = type_defs
+        .iter()
+        .filter_map(|type_def| match type_def {
+            TypeDefinition::Struct(s) => Some(s),
+            TypeDefinition::Enum(_) => None,
+        })
+        .collect();
+    let mut validated: Vec<&str> = structs
+        .iter()
+        .filter(|s| s.has_constraints())
+        .map(|s| s.name.as_str())
+        .collect();
+
+    // Holding a validated struct makes a struct validated in turn
+    loop {
+        let count = validated.len();
+        for s in &structs {
+            if !validated.contains(&s.name.as_str()) && s.is_validated(&validated) {
+                validated.push(&s.name);
+            }
+        }
+        if validated.len() == count {
+            return validated;
+        }
+    }
+}
+
+/// Targets `#[skip(..)]` can exclude a type or field from
+pub const TARGETS: [&str; 2] = ["rust", "typescript"];
+
+/// The types and fields generated for `target`, without those marked `#[skip(target)]`
+///
+/// Borrows `type_defs` unchanged when nothing in the schema is skipped.
+pub fn for_target<'a>(type_defs: &'a [TypeDefinition], target: &str) -> Cow<'a, [TypeDefinition]> {
+    let skips_anything = type_defs.iter().any(|type_def| match type_def {
+        TypeDefinition::Struct(s) => {
+            s.metadata.skips(target) || s.fields.iter().any(|f| f.skips(target))
+        }
+        TypeDefinition::Enum(e) => e.metadata.skips(target),
+    });
+    if !skips_anything {
+        return Cow::Borrowed(type_defs);
+    }
+
+    type_defs
+        .iter()
+        .filter(|type_def| !type_def.metadata().skips(target))
+        .map(|type_def| match type_def {
+            TypeDefinition::Struct(s) => TypeDefinition::Struct(StructDefinition {
+                fields: s
+                    .fields
+                    .iter()
+                    .filter(|field| !field.skips(target))
+                    .cloned()
+                    .collect(),
+                ..s.clone()
+            }),
+            TypeDefinition::Enum(_) => type_def.clone(),
+        })
+        .collect()
+}
+
+/// How an account is created, resized and closed
+///
+/// Declared with `#[init(..)]`, `#[init_space]`, `#[close]` and `#[realloc]`
+/// on `#[account]` structs. Generators ignore it; the audit checklist turns
+/// it into targeted lifecycle checks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Lifecycle {
+    /// Instructions that create the account, from `#[init(create_vault)]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub initialized_by: Vec<String>,
+
+    /// Whether the account is allocated at its schema size (`#[init_space]`)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub init_space: bool,
+
+    /// Whether the account can be closed (`#[close]` or `#[close(..)]`)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub closable: bool,
+
+    /// Instructions that close the account, from `#[close(close_vault)]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub closed_by: Vec<String>,
+
+    /// Whether the account can be resized after creation (`#[realloc]`)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub reallocable: bool,
+}
+
+impl Lifecycle {
+    /// Whether no lifecycle intent is declared
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// First 8 bytes of `sha256("<namespace>:<name>")`, as Anchor derives discriminators
+pub(crate) fn anchor_discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let hash = crate::borsh_decoder::sha256(format!("{}:{}", namespace, name).as_bytes());
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+impl Metadata {
+    /// Whether the type is an `#[account]`
+    pub fn is_account(&self) -> bool {
+        self.role == TypeRole::Account
+    }
+
+    /// Whether the type is an `#[event]`
+    pub fn is_event(&self) -> bool {
+        self.role == TypeRole::Event
+    }
+
+    /// Whether the type is an `#[instruction]`
+    pub fn is_instruction(&self) -> bool {
+        self.role == TypeRole::Instruction
+    }
+
+    /// Whether generated code includes a `state_hash` helper (`#[state_hash]` on a Solana type)
+    pub fn has_state_hash(&self) -> bool {
+        self.solana && self.attributes.iter().any(|attr| attr == "state_hash")
+    }
+
+    /// Whether generated code includes string conversions (`#[display]`)
+    ///
+    /// Rust gets `Display` and `FromStr`, TypeScript `toString`/`parse`
+    /// helpers. Only unit-only enums and single-field structs accept it.
+    pub fn has_display(&self) -> bool {
+        self.attributes.iter().any(|attr| attr == "display")
+    }
+
+    /// Whether generated code includes decoding shims for earlier schema versions
+    pub fn has_version_shims(&self) -> bool {
+        self.solana && self.version.is_some_and(|version| version > 1)
+    }
+
+    /// Whether the type is left out of `target` (`#[skip(target)]`)
+    pub fn skips(&self, target: &str) -> bool {
+        self.skip.iter().any(|skipped| skipped == target)
+    }
 }
 
 impl TypeDefinition {
@@ -118,6 +457,138 @@ impl TypeDefinition {
     pub fn is_solana(&self) -> bool {
         self.metadata().solana
     }
+
+    /// Check if this type is marked `#[account]`
+    pub fn is_account(&self) -> bool {
+        self.metadata().is_account()
+    }
+
+    /// Check if this type is marked `#[instruction]`
+    pub fn is_instruction(&self) -> bool {
+        self.metadata().is_instruction()
+    }
+
+    /// Check if this type is marked `#[event]`
+    pub fn is_event(&self) -> bool {
+        self.metadata().is_event()
+    }
+
+    /// Anchor discriminator in front of the type's data, for accounts and events
+    ///
+    /// Instruction discriminators come from the handler name instead, see
+    /// [`crate::instruction_decoder::instruction_discriminator`].
+    pub fn discriminator(&self) -> Option<[u8; 8]> {
+        let namespace = match self.metadata().role {
+            TypeRole::Account => "account",
+            TypeRole::Event => "event",
+            TypeRole::Plain | TypeRole::Instruction => return None,
+        };
+        Some(anchor_discriminator(namespace, self.name()))
+    }
+
+    /// Push the names of the user-defined types this type's fields refer to,
+    /// in field order
+    pub fn referenced_types<'a>(&'a self, names: &mut Vec<&'a str>) {
+        fn walk<'a>(type_info: &'a TypeInfo, names: &mut Vec<&'a str>) {
+            match type_info {
+                TypeInfo::Primitive(_) => {}
+                TypeInfo::UserDefined(name) => names.push(name),
+                TypeInfo::Array(inner)
+                | TypeInfo::FixedArray(inner, _)
+                | TypeInfo::Option(inner) => walk(inner, names),
+                TypeInfo::Map(_, key, value) => {
+                    walk(key, names);
+                    walk(value, names);
+                }
+            }
+        }
+
+        match self {
+            TypeDefinition::Struct(s) => s.fields.iter().for_each(|f| walk(&f.type_info, names)),
+            TypeDefinition::Enum(e) => {
+                for variant in &e.variants {
+                    match variant {
+                        EnumVariantDefinition::Unit { .. } => {}
+                        EnumVariantDefinition::Tuple { types, .. } => {
+                            types.iter().for_each(|t| walk(t, names))
+                        }
+                        EnumVariantDefinition::Struct { fields, .. } => {
+                            fields.iter().for_each(|f| walk(&f.type_info, names))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl StructDefinition {
+    /// Whether any field has `#[min]`, `#[max]` or `#[range]` bounds to check
+    pub fn has_constraints(&self) -> bool {
+        self.fields
+            .iter()
+            .any(|field| !field.constraints().is_empty())
+    }
+
+    /// Whether the struct gets a generated `validate`: it has bounds of its
+    /// own or holds one of the `validated` structs
+    pub fn is_validated(&self, validated: &[&str]) -> bool {
+        self.has_constraints()
+            || self.fields.iter().any(|field| {
+                field
+                    .type_info
+                    .held_type()
+                    .is_some_and(|name| validated.contains(&name))
+            })
+    }
+
+    /// The struct as laid out in an earlier schema version
+    ///
+    /// Only fields added at or before `version` are kept, and the type is
+    /// named `{name}V{version}`. It carries no attributes, so generators emit
+    /// a plain Borsh type rather than another account.
+    pub fn at_version(&self, version: u32) -> StructDefinition {
+        StructDefinition {
+            name: format!("{}V{}", self.name, version),
+            fields: self
+                .fields
+                .iter()
+                .filter(|field| field.since_version() <= version)
+                .cloned()
+                .collect(),
+            metadata: Metadata {
+                solana: self.metadata.solana,
+                attributes: Vec::new(),
+                role: TypeRole::Plain,
+                zero_copy: false,
+                version: None,
+                lifecycle: Lifecycle::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
+            },
+        }
+    }
+
+    /// Fields carried in the type's Borsh data
+    ///
+    /// That is every field, except that the accounts an `#[instruction]`
+    /// takes (see [`FieldDefinition::is_instruction_account`]) are passed
+    /// alongside the instruction data rather than inside it.
+    pub fn data_fields(&self) -> impl Iterator<Item = &FieldDefinition> {
+        let instruction = self.metadata.is_instruction();
+        self.fields
+            .iter()
+            .filter(move |field| !(instruction && field.is_instruction_account()))
+    }
+
+    /// Accounts an `#[instruction]` struct takes, in field order
+    pub fn instruction_accounts(&self) -> impl Iterator<Item = &FieldDefinition> {
+        let instruction = self.metadata.is_instruction();
+        self.fields
+            .iter()
+            .filter(move |field| instruction && field.is_instruction_account())
+    }
 }
 
 impl EnumDefinition {
@@ -143,6 +614,179 @@ impl EnumDefinition {
     }
 }
 
+/// Lines of the doc comments among `attributes`, without leading or trailing blank lines
+///
+/// Each `///` line is one `doc` attribute; a `/** */` block is one attribute
+/// spanning several lines.
+pub(crate) fn doc_lines(attributes: &[Attribute]) -> Vec<String> {
+    let mut lines: Vec<String> = attributes
+        .iter()
+        .filter(|attr| attr.name == "doc")
+        .filter_map(|attr| match &attr.value {
+            Some(AttributeValue::String(text)) => Some(text),
+            _ => None,
+        })
+        .flat_map(|text| text.split('\n').map(doc_line))
+        .skip_while(|line| line.is_empty())
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// A doc comment line without its indentation, or the ` * ` margin of a block comment
+fn doc_line(line: &str) -> String {
+    let line = line.trim();
+    match line {
+        "*" => String::new(),
+        _ => line.strip_prefix("* ").unwrap_or(line).to_string(),
+    }
+}
+
+impl FieldDefinition {
+    /// Lines of the field's doc comment, as written
+    pub fn docs(&self) -> Vec<String> {
+        doc_lines(&self.attributes)
+    }
+
+    /// Get the field's doc comment, with lines joined by spaces
+    pub fn doc_comment(&self) -> Option<String> {
+        let lines: Vec<&str> = self
+            .attributes
+            .iter()
+            .filter(|attr| attr.name == "doc")
+            .filter_map(|attr| match &attr.value {
+                Some(AttributeValue::String(line)) if !line.is_empty() => Some(line.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        (!lines.is_empty()).then(|| lines.join(" "))
+    }
+
+    /// Check if field has a specific attribute
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes.iter().any(|attr| attr.name == name)
+    }
+
+    /// Get attribute by name
+    pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
+        self.attributes.iter().find(|attr| attr.name == name)
+    }
+
+    /// Whether the field is marked `#[sensitive]`
+    ///
+    /// Sensitive fields are redacted from generated `Debug` output and by the
+    /// TypeScript `redact()` helper, so they stay out of logs and indexes.
+    pub fn is_sensitive(&self) -> bool {
+        self.has_attribute("sensitive")
+    }
+
+    /// Whether this field of an `#[instruction]` struct is an account the
+    /// instruction takes, marked `#[signer]` or `#[account(...)]`, rather
+    /// than an argument
+    pub fn is_instruction_account(&self) -> bool {
+        self.has_attribute("signer") || self.has_attribute("account")
+    }
+
+    /// Whether the field is left out of `target` (`#[skip(target)]`)
+    pub fn skips(&self, target: &str) -> bool {
+        self.attribute_args("skip")
+            .iter()
+            .any(|skipped| skipped == target)
+    }
+
+    /// Module of the hand-written codec that encodes this field for `target`,
+    /// from `#[codec(rust = "...", ts = "...")]`
+    ///
+    /// The codec owns the field's layout: generated code delegates to it
+    /// instead of encoding the declared type as Borsh.
+    pub fn codec(&self, target: &str) -> Option<String> {
+        let key = match target {
+            "typescript" => "ts",
+            other => other,
+        };
+        self.attribute_args("codec").iter().find_map(|arg| {
+            let (name, path) = arg.split_once('=')?;
+            (name.trim() == key).then(|| path.trim().trim_matches('"').to_string())
+        })
+    }
+
+    /// Schema version that added this field, from `#[since(n)]` (1 if absent)
+    pub fn since_version(&self) -> u32 {
+        match self
+            .get_attribute("since")
+            .and_then(|attr| attr.value.as_ref())
+        {
+            Some(AttributeValue::Integer(n)) => u32::try_from(*n).unwrap_or(u32::MAX),
+            _ => 1,
+        }
+    }
+
+    /// Length and value bounds from `#[min]`, `#[max]` and `#[range]`
+    ///
+    /// Attribute values are folded to literals during transform, which also
+    /// checks that each bound suits the field's type.
+    pub fn constraints(&self) -> Constraints {
+        let integer = |name: &str| match self.get_attribute(name).and_then(|a| a.value.as_ref()) {
+            Some(AttributeValue::Integer(n)) => Some(*n),
+            _ => None,
+        };
+        let range = match self.attribute_args("range").as_slice() {
+            [min, max] => min.parse().ok().zip(max.parse().ok()),
+            _ => None,
+        };
+        Constraints {
+            min_len: integer("min"),
+            max_len: integer("max"),
+            range,
+        }
+    }
+
+    /// Get the comma-separated arguments of a list attribute
+    ///
+    /// For `#[account(mut, has_one = authority)]` this returns
+    /// `["mut", "has_one = authority"]`. Commas nested inside parentheses,
+    /// brackets or braces are not treated as separators.
+    pub fn attribute_args(&self, name: &str) -> Vec<String> {
+        let Some(AttributeValue::String(value)) = self
+            .get_attribute(name)
+            .and_then(|attr| attr.value.as_ref())
+        else {
+            return Vec::new();
+        };
+
+        let mut args = Vec::new();
+        let mut current = String::new();
+        let mut depth = 0usize;
+
+        for ch in value.chars() {
+            match ch {
+                '(' | '[' | '{' => {
+                    depth += 1;
+                    current.push(ch);
+                }
+                ')' | ']' | '}' => {
+                    depth = depth.saturating_sub(1);
+                    current.push(ch);
+                }
+                ',' if depth == 0 => {
+                    args.push(current.trim().to_string());
+                    current.clear();
+                }
+                _ => current.push(ch),
+            }
+        }
+
+        if !current.trim().is_empty() {
+            args.push(current.trim().to_string());
+        }
+
+        args
+    }
+}
+
 impl EnumVariantDefinition {
     /// Get the variant name
     pub fn name(&self) -> &str {
@@ -0,0 +1,509 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Field reorderings that reduce padding and keep filter fields at fixed offsets
+//!
+//! Two layout properties depend only on field order:
+//!
+//! - **Padding.** A `#[zero_copy]` (`repr(C)`) struct needs no padding when
+//!   fields are ordered from the largest alignment to the smallest. Borsh
+//!   structs don't pad, but a struct that may become zero-copy later is
+//!   cheaper to convert when its order is already padding-free.
+//! - **Fixed offsets.** RPC `memcmp` filters match bytes at a fixed offset,
+//!   so a field is only filterable when every field before it has a fixed
+//!   Borsh size. Strings, vectors, options and enums with data push every
+//!   later field to a data-dependent offset.
+//!
+//! [`suggest`] orders fixed-size fields before variable-size ones, by
+//! alignment, largest first, and filter fields (`#[key]` and `PublicKey`
+//! fields) first within an alignment. It only suggests a new order when that
+//! removes padding or gives more fields a fixed offset. [`reorder_fields`]
+//! applies suggestions to the schema source. Since
+//! [`crate::transform::transform_to_ir`] rejects `#[key]` fields at variable
+//! offsets, schemas that need reordering are loaded with
+//! [`crate::transform::transform_for_layout`].
+//!
+//! Reordering is a breaking layout change: accounts already on-chain,
+//! serialized instruction data and deployed clients keep the old order.
+//! Versioned structs (with `#[since]` fields) are never reordered, since
+//! their fields must stay in the order they were added.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::layout_optimizer::{reorder_fields, suggest};
+//! use lumos_core::{parser, transform};
+//!
+//! let source = "struct Pool {\n    bump: u8,\n    amount: u64,\n    side: u8,\n}\n";
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(source).unwrap()).unwrap();
+//!
+//! let suggestions = suggest(&ir);
+//! assert_eq!(suggestions[0].suggested, ["amount", "bump", "side"]);
+//! assert_eq!(suggestions[0].padding, Some((14, 6)));
+//!
+//! let reordered = reorder_fields(source, &suggestions).unwrap();
+//! assert_eq!(reordered, "struct Pool {\n    amount: u64,\n    bump: u8,\n    side: u8,\n}\n");
+//! ```
+
+use crate::ast::Item;
+use crate::error::{LumosError, Result};
+use crate::ir::{EnumVariantDefinition, StructDefinition, TypeDefinition, TypeInfo};
+use crate::parser::parse_lumos_file;
+use crate::zero_copy::primitive_layout;
+use std::cmp::Reverse;
+
+/// Warning shown with every suggestion
+pub const BREAKING_WARNING: &str = "reordering fields is a breaking layout change: accounts already on-chain, serialized instruction data and deployed clients keep the old order and stop decoding until they are migrated";
+
+/// A suggested field order for one struct
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// Struct name
+    pub name: String,
+
+    /// Field names in declaration order
+    pub current: Vec<String>,
+
+    /// Field names in the suggested order
+    pub suggested: Vec<String>,
+
+    /// `repr(C)` padding in bytes of the current and suggested orders, when every
+    /// field has a `repr(C)` layout
+    pub padding: Option<(usize, usize)>,
+
+    /// Borsh offsets of the filter fields
+    pub filters: Vec<FilterOffset>,
+}
+
+/// Borsh offset of a field commonly used in `memcmp` filters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterOffset {
+    /// Field name
+    pub field: String,
+
+    /// Offset in the current order, if fixed; includes the account discriminator
+    pub current: Option<usize>,
+
+    /// Offset in the suggested order, if fixed
+    pub suggested: Option<usize>,
+}
+
+/// Suggest field orders for every struct whose layout can improve
+pub fn suggest(type_defs: &[TypeDefinition]) -> Vec<Suggestion> {
+    type_defs
+        .iter()
+        .filter_map(|t| match t {
+            TypeDefinition::Struct(s) => suggest_struct(type_defs, s),
+            TypeDefinition::Enum(_) => None,
+        })
+        .collect()
+}
+
+fn suggest_struct(
+    type_defs: &[TypeDefinition],
+    struct_def: &StructDefinition,
+) -> Option<Suggestion> {
+    if struct_def.fields.len() < 2 || struct_def.fields.iter().any(|f| f.has_attribute("since")) {
+        return None;
+    }
+
+    let fields: Vec<Field> = struct_def
+        .fields
+        .iter()
+        .map(|f| Field {
+            name: f.name.clone(),
+            size: borsh_size(type_defs, &f.type_info, &mut Vec::new()),
+            c_layout: c_layout(type_defs, &f.type_info, &mut Vec::new()),
+            filter: f.has_attribute("key")
+                || matches!(&f.type_info, TypeInfo::Primitive(p) if p == "PublicKey" || p == "Pubkey"),
+        })
+        .collect();
+
+    let mut ordered: Vec<&Field> = fields.iter().collect();
+    ordered.sort_by_key(|f| match (f.size, f.c_layout) {
+        (None, _) => (1, Reverse(0), false),
+        (Some(_), layout) => (0, Reverse(layout.map_or(1, |(_, align)| align)), !f.filter),
+    });
+    let current: Vec<&Field> = fields.iter().collect();
+
+    let padding = padding(&current).zip(padding(&ordered));
+    let fixed_before = fixed_offsets(&current, 0).iter().flatten().count();
+    let fixed_after = fixed_offsets(&ordered, 0).iter().flatten().count();
+    let removes_padding = padding.is_some_and(|(before, after)| after < before);
+    if !removes_padding && fixed_after <= fixed_before {
+        return None;
+    }
+
+    let discriminator = if struct_def.metadata.is_account() {
+        8
+    } else {
+        0
+    };
+    let offset_of = |order: &[&Field], name: &str| {
+        let position = order.iter().position(|f| f.name == name)?;
+        fixed_offsets(order, discriminator)[position]
+    };
+    let filters = fields
+        .iter()
+        .filter(|f| f.filter)
+        .map(|f| FilterOffset {
+            field: f.name.clone(),
+            current: offset_of(&current, &f.name),
+            suggested: offset_of(&ordered, &f.name),
+        })
+        .collect();
+
+    Some(Suggestion {
+        name: struct_def.name.clone(),
+        current: current.iter().map(|f| f.name.clone()).collect(),
+        suggested: ordered.iter().map(|f| f.name.clone()).collect(),
+        padding,
+        filters,
+    })
+}
+
+struct Field {
+    name: String,
+    size: Option<usize>,
+    c_layout: Option<(usize, usize)>,
+    filter: bool,
+}
+
+/// Offset of each field, while every field before it has a fixed size
+fn fixed_offsets(order: &[&Field], start: usize) -> Vec<Option<usize>> {
+    let mut offset = Some(start);
+    order
+        .iter()
+        .map(|f| {
+            let this = offset;
+            offset = offset.zip(f.size).map(|(offset, size)| offset + size);
+            this
+        })
+        .collect()
+}
+
+/// Bytes of `repr(C)` padding, including trailing padding
+fn padding(order: &[&Field]) -> Option<usize> {
+    let layouts = order
+        .iter()
+        .map(|f| f.c_layout)
+        .collect::<Option<Vec<_>>>()?;
+    let (size, _) = c_struct_layout(&layouts);
+    Some(size - layouts.iter().map(|(size, _)| size).sum::<usize>())
+}
+
+/// Size and alignment of a `repr(C)` struct with fields of these layouts
+fn c_struct_layout(fields: &[(usize, usize)]) -> (usize, usize) {
+    let mut offset = 0usize;
+    let mut align = 1;
+    for &(size, field_align) in fields {
+        offset = offset.next_multiple_of(field_align) + size;
+        align = align.max(field_align);
+    }
+    (offset.next_multiple_of(align), align)
+}
+
+/// `repr(C)` size and alignment of a type, if it has a Pod layout
+fn c_layout(
+    type_defs: &[TypeDefinition],
+    type_info: &TypeInfo,
+    path: &mut Vec<String>,
+) -> Option<(usize, usize)> {
+    match type_info {
+        TypeInfo::Primitive(name) => primitive_layout(name),
+        TypeInfo::UserDefined(name) => {
+            let Some(TypeDefinition::Struct(s)) = type_defs.iter().find(|t| t.name() == name)
+            else {
+                return None;
+            };
+            if path.contains(name) {
+                return None;
+            }
+            path.push(name.clone());
+            let fields = s
+                .fields
+                .iter()
+                .map(|f| c_layout(type_defs, &f.type_info, path))
+                .collect::<Option<Vec<_>>>();
+            path.pop();
+            Some(c_struct_layout(&fields?))
+        }
+        TypeInfo::FixedArray(inner, len) => {
+            c_layout(type_defs, inner, path).map(|(size, align)| (size * len, align))
+        }
+        TypeInfo::Array(_) | TypeInfo::Option(_) | TypeInfo::Map(..) => None,
+    }
+}
+
+/// Borsh size of a type, if every value has the same size
+pub(crate) fn borsh_size(
+    type_defs: &[TypeDefinition],
+    type_info: &TypeInfo,
+    path: &mut Vec<String>,
+) -> Option<usize> {
+    match type_info {
+        // `String` and `Signature`, a base58 `String`, vary in size
+        TypeInfo::Primitive(name) => match name.as_str() {
+            "bool" => Some(1),
+            other => primitive_layout(other).map(|(size, _)| size),
+        },
+        TypeInfo::UserDefined(name) => {
+            if path.contains(name) {
+                return None;
+            }
+            path.push(name.clone());
+            let size = match type_defs.iter().find(|t| t.name() == name)? {
+                TypeDefinition::Struct(s) => s
+                    .fields
+                    .iter()
+                    .map(|f| borsh_size(type_defs, &f.type_info, path))
+                    .sum(),
+                TypeDefinition::Enum(e) => e
+                    .variants
+                    .iter()
+                    .all(|v| matches!(v, EnumVariantDefinition::Unit { .. }))
+                    .then_some(1),
+            };
+            path.pop();
+            size
+        }
+        TypeInfo::FixedArray(inner, len) => {
+            borsh_size(type_defs, inner, path).map(|size| size * len)
+        }
+        TypeInfo::Array(_) | TypeInfo::Option(_) | TypeInfo::Map(..) => None,
+    }
+}
+
+/// Rewrite `source` so each suggested struct lists its fields in the suggested order
+///
+/// Fields move as whole lines, together with their attributes, doc comments
+/// and the `//` comments above them, so each field must start on its own
+/// line. A comma is added after a field that moves away from the end of the
+/// struct.
+pub fn reorder_fields(source: &str, suggestions: &[Suggestion]) -> Result<String> {
+    let ast = parse_lumos_file(source)?;
+    let mut lines: Vec<String> = source.lines().map(str::to_string).collect();
+
+    for suggestion in suggestions {
+        let struct_def = ast
+            .items
+            .iter()
+            .find_map(|item| match item {
+                Item::Struct(s) if s.name == suggestion.name => Some(s),
+                _ => None,
+            })
+            .ok_or_else(|| error(format!("struct '{}' is not in the source", suggestion.name)))?;
+        let by_hand = |reason: &str| {
+            error(format!(
+                "can't reorder '{}' automatically: {}; reorder its fields by hand",
+                suggestion.name, reason
+            ))
+        };
+
+        // 0-based index of the first line of each field, attributes included
+        let mut starts = Vec::new();
+        for field in &struct_def.fields {
+            let start = field
+                .attributes
+                .iter()
+                .filter_map(|a| a.span)
+                .chain(field.span)
+                .map(|span| span.line - 1)
+                .min()
+                .ok_or_else(|| by_hand("field positions are unknown"))?;
+            starts.push(start);
+        }
+        let header = struct_def.span.map_or(0, |span| span.line - 1);
+        if starts[0] <= header || starts.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(by_hand("some fields don't start on their own line"));
+        }
+        let end = (starts[starts.len() - 1]..lines.len())
+            .find(|&i| lines[i].trim_start().starts_with('}'))
+            .ok_or_else(|| by_hand("its closing brace isn't on its own line"))?;
+
+        // Comments directly above a field move with it
+        for i in 0..starts.len() {
+            let floor = if i == 0 {
+                header + 1
+            } else {
+                starts[i - 1] + 1
+            };
+            while starts[i] > floor && lines[starts[i] - 1].trim_start().starts_with("//") {
+                starts[i] -= 1;
+            }
+        }
+
+        let mut chunks: Vec<(String, Vec<String>)> = Vec::new();
+        for (i, field) in struct_def.fields.iter().enumerate() {
+            let chunk_end = starts.get(i + 1).copied().unwrap_or(end);
+            let mut chunk = lines[starts[i]..chunk_end].to_vec();
+            if chunk.iter().any(|line| code(line).contains(['{', '}'])) {
+                return Err(by_hand("its closing brace isn't on its own line"));
+            }
+            if let Some(last) = chunk.iter_mut().rev().find(|line| !code(line).is_empty()) {
+                let code_end = last[..last.find("//").unwrap_or(last.len())]
+                    .trim_end()
+                    .len();
+                if !last[..code_end].ends_with(',') {
+                    last.insert(code_end, ',');
+                }
+            }
+            chunks.push((field.name.clone(), chunk));
+        }
+
+        let mut reordered = Vec::new();
+        for name in &suggestion.suggested {
+            let position = chunks
+                .iter()
+                .position(|(field, _)| field == name)
+                .ok_or_else(|| by_hand(&format!("it has no field '{}'", name)))?;
+            reordered.extend(chunks.remove(position).1);
+        }
+        if !chunks.is_empty() {
+            return Err(by_hand("the suggestion doesn't list every field"));
+        }
+        lines.splice(starts[0]..end, reordered);
+    }
+
+    let mut output = lines.join("\n");
+    if source.ends_with('\n') {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// A line without its trailing `//` comment
+fn code(line: &str) -> &str {
+    line.find("//").map_or(line, |i| &line[..i]).trim()
+}
+
+fn error(message: String) -> LumosError {
+    LumosError::SchemaParse(message, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::transform_for_layout;
+
+    fn suggest_for(source: &str) -> Vec<Suggestion> {
+        suggest(&transform_for_layout(parse_lumos_file(source).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn suggests_orders_without_padding() {
+        let suggestions = suggest_for(
+            "struct Pool { bump: u8, amount: u64, fee_bps: u16, tick: u32 }\n\
+             struct Sorted { amount: u64, tick: u32, fee_bps: u16, bump: u8 }",
+        );
+
+        assert_eq!(suggestions.len(), 1);
+        let pool = &suggestions[0];
+        assert_eq!(pool.suggested, ["amount", "tick", "fee_bps", "bump"]);
+        // 7 bytes before `amount` and 2 after `tick`, then 1 byte of trailing padding
+        assert_eq!(pool.padding, Some((9, 1)));
+    }
+
+    #[test]
+    fn moves_filter_fields_to_fixed_offsets() {
+        let suggestions = suggest_for(
+            r#"
+            #[solana]
+            #[account]
+            struct Listing { title: String, tags: [String], #[key] seller: PublicKey, price: u64, bump: u8 }
+            "#,
+        );
+
+        let listing = &suggestions[0];
+        assert_eq!(
+            listing.suggested,
+            ["price", "seller", "bump", "title", "tags"]
+        );
+        assert_eq!(listing.padding, None);
+        assert_eq!(
+            listing.filters,
+            [FilterOffset {
+                field: "seller".to_string(),
+                current: None,
+                suggested: Some(16),
+            }]
+        );
+    }
+
+    #[test]
+    fn treats_signatures_as_variable_size() {
+        let suggestions = suggest_for(
+            r#"
+            #[solana]
+            #[account]
+            struct Receipt { sig: Signature, amount: u64, #[key] payer: PublicKey }
+            "#,
+        );
+
+        let receipt = &suggestions[0];
+        assert_eq!(receipt.suggested, ["amount", "payer", "sig"]);
+        assert_eq!(
+            receipt.filters,
+            [FilterOffset {
+                field: "payer".to_string(),
+                current: None,
+                suggested: Some(16),
+            }]
+        );
+    }
+
+    #[test]
+    fn skips_versioned_and_optimal_structs() {
+        assert!(suggest_for(
+            "#[version(2)]\nstruct Config { bump: u8, admin: PublicKey, #[since(2)] fee: u64 }"
+        )
+        .is_empty());
+        assert!(suggest_for("struct Note { owner: PublicKey, text: String, bump: u8 }").len() == 1);
+        assert!(suggest_for("struct Note { owner: PublicKey, bump: u8, text: String }").is_empty());
+    }
+
+    #[test]
+    fn reorders_field_lines_with_attributes_and_comments() {
+        let source = "\
+#[solana]
+struct Listing {
+    /// Listing title
+    title: String,
+    // Seller wallet
+    #[key]
+    seller: PublicKey,
+    price: u64 // lamports
+}
+
+struct Other { a: u8 }
+";
+        let suggestions = suggest_for(source);
+        assert_eq!(suggestions[0].suggested, ["price", "seller", "title"]);
+
+        let reordered = reorder_fields(source, &suggestions).unwrap();
+        assert_eq!(
+            reordered,
+            "\
+#[solana]
+struct Listing {
+    price: u64, // lamports
+    // Seller wallet
+    #[key]
+    seller: PublicKey,
+    /// Listing title
+    title: String,
+}
+
+struct Other { a: u8 }
+"
+        );
+        assert!(suggest_for(&reordered).is_empty());
+    }
+
+    #[test]
+    fn refuses_fields_sharing_a_line() {
+        let source = "struct Pool { bump: u8, amount: u64, side: u8 }";
+        let err = reorder_fields(source, &suggest_for(source)).unwrap_err();
+        assert!(err.to_string().contains("reorder its fields by hand"));
+    }
+}
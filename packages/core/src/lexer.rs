@@ -0,0 +1,392 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Tokenizer for `.lumos` sources
+//!
+//! Shared by the hand-written parser and the formatter. Tokens follow
+//! `proc_macro2`: identifiers, single-character punctuation marked joint when
+//! more punctuation follows, literals as written, and matched delimiters.
+//! Plain comments are dropped unless the formatter asks for them.
+
+use crate::ast::Span;
+use crate::error::{LumosError, Result, SourceLocation};
+use std::ops::Range;
+
+/// Characters lexed as punctuation, as in `proc_macro2`
+pub(crate) const PUNCT: &str = "~!@#$%^&*-=+|;:,<.>/?'";
+
+/// A syntax error at `span`, worded like the syn path's
+pub(crate) fn syntax_error(span: Span, message: impl std::fmt::Display) -> LumosError {
+    LumosError::SchemaParse(
+        format!("Failed to parse .lumos file: {}", message),
+        Some(SourceLocation::new(span.line, span.column + 1)),
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Kind {
+    Ident(String),
+
+    /// Punctuation, and whether the next character is punctuation too
+    Punct(char, bool),
+
+    /// Literal as written, quotes and suffix included
+    Literal(String),
+
+    /// Opening delimiter, with the index of its closing token
+    Open(char, usize),
+
+    Close(char),
+
+    /// Doc comment text, and whether it is an inner (`//!`) comment
+    Doc(String, bool),
+
+    /// Plain comment as written, only kept by [`Lexer::with_comments`]
+    Comment(String),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Token {
+    pub(crate) kind: Kind,
+    pub(crate) span: Span,
+
+    /// Character offsets in the source
+    pub(crate) range: Range<usize>,
+
+    /// Line of the last character
+    pub(crate) end_line: usize,
+}
+
+pub(crate) struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+    comments: bool,
+}
+
+impl Lexer {
+    pub(crate) fn new(source: &str) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            pos: 0,
+            line: 1,
+            column: 0,
+            comments: false,
+        }
+    }
+
+    /// Keep plain comments as [`Kind::Comment`] tokens
+    pub(crate) fn with_comments(mut self) -> Self {
+        self.comments = true;
+        self
+    }
+
+    fn peek(&self, ahead: usize) -> Option<char> {
+        self.chars.get(self.pos + ahead).copied()
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        text.chars()
+            .enumerate()
+            .all(|(i, c)| self.peek(i) == Some(c))
+    }
+
+    fn span(&self) -> Span {
+        Span {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn bump(&mut self) -> char {
+        let c = self.chars[self.pos];
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.column += 1;
+        }
+        c
+    }
+
+    fn bump_while(&mut self, keep: impl Fn(char) -> bool) -> String {
+        let mut text = String::new();
+        while let Some(c) = self.peek(0).filter(|c| keep(*c)) {
+            text.push(c);
+            self.bump();
+        }
+        text
+    }
+
+    /// Tokens of the whole source, with delimiters matched up
+    pub(crate) fn tokenize(mut self) -> Result<Vec<Token>> {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut open: Vec<usize> = Vec::new();
+
+        while let Some(token) = self.next_token()? {
+            match token.kind {
+                Kind::Open(..) => open.push(tokens.len()),
+                Kind::Close(close) => {
+                    let Some(start) = open.pop() else {
+                        return Err(syntax_error(token.span, "unexpected closing delimiter"));
+                    };
+                    let end = tokens.len();
+                    let Kind::Open(opening, partner) = &mut tokens[start].kind else {
+                        unreachable!("only opening delimiters are pushed");
+                    };
+                    if closing(*opening) != close {
+                        return Err(syntax_error(token.span, "mismatched closing delimiter"));
+                    }
+                    *partner = end;
+                }
+                _ => {}
+            }
+            tokens.push(token);
+        }
+
+        if let Some(start) = open.pop() {
+            return Err(syntax_error(tokens[start].span, "unclosed delimiter"));
+        }
+        Ok(tokens)
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>> {
+        loop {
+            while self.peek(0).is_some_and(char::is_whitespace) {
+                self.bump();
+            }
+            let span = self.span();
+            let Some(c) = self.peek(0) else {
+                return Ok(None);
+            };
+
+            let start = self.pos;
+            let comment = |lexer: &Self| {
+                let text: String = lexer.chars[start..lexer.pos].iter().collect();
+                Kind::Comment(text.trim_end().to_string())
+            };
+
+            let kind = if self.starts_with("//") {
+                match self.line_comment() {
+                    Some(doc) => doc,
+                    None if self.comments => comment(self),
+                    None => continue,
+                }
+            } else if self.starts_with("/*") {
+                match self.block_comment(span)? {
+                    Some(doc) => doc,
+                    None if self.comments => comment(self),
+                    None => continue,
+                }
+            } else if self.is_literal_prefix() {
+                Kind::Literal(self.literal(span)?)
+            } else if c == '\'' {
+                match self.peek(1) == Some('\\') || self.peek(2) == Some('\'') {
+                    true => Kind::Literal(self.quoted(span)?),
+                    // A lifetime: the quote is joined to the name after it
+                    false => {
+                        self.bump();
+                        Kind::Punct('\'', true)
+                    }
+                }
+            } else if c == '"' {
+                Kind::Literal(self.quoted(span)?)
+            } else if c.is_ascii_digit() {
+                Kind::Literal(self.number())
+            } else if is_ident_start(c) {
+                let mut name = String::new();
+                if self.starts_with("r#") && self.peek(2).is_some_and(is_ident_start) {
+                    name.push_str("r#");
+                    self.bump();
+                    self.bump();
+                }
+                name.push_str(&self.bump_while(is_ident_continue));
+                Kind::Ident(name)
+            } else if matches!(c, '(' | '[' | '{') {
+                self.bump();
+                Kind::Open(c, 0)
+            } else if matches!(c, ')' | ']' | '}') {
+                self.bump();
+                Kind::Close(c)
+            } else if PUNCT.contains(c) {
+                self.bump();
+                let joint = self.peek(0).is_some_and(|next| PUNCT.contains(next))
+                    && !self.starts_with("//")
+                    && !self.starts_with("/*");
+                Kind::Punct(c, joint)
+            } else {
+                return Err(syntax_error(span, format!("unexpected character `{}`", c)));
+            };
+            return Ok(Some(Token {
+                kind,
+                span,
+                range: start..self.pos,
+                end_line: self.line,
+            }));
+        }
+    }
+
+    /// A `//` comment; `///` and `//!` are doc comments, `////` is not
+    fn line_comment(&mut self) -> Option<Kind> {
+        let doc = match (self.peek(2), self.peek(3)) {
+            (Some('/'), next) if next != Some('/') => Some(false),
+            (Some('!'), _) => Some(true),
+            _ => None,
+        };
+        self.bump();
+        self.bump();
+        if doc.is_some() {
+            self.bump();
+        }
+        let text = self.bump_while(|c| c != '\n');
+        let text = text.strip_suffix('\r').unwrap_or(&text).to_string();
+        doc.map(|inner| Kind::Doc(text, inner))
+    }
+
+    /// A nested `/* */` comment; `/** */` and `/*! */` are doc comments
+    fn block_comment(&mut self, span: Span) -> Result<Option<Kind>> {
+        let doc = match (self.peek(2), self.peek(3)) {
+            (Some('*'), Some(next)) if next != '*' && next != '/' => Some(false),
+            (Some('!'), _) => Some(true),
+            _ => None,
+        };
+        self.bump();
+        self.bump();
+        if doc.is_some() {
+            self.bump();
+        }
+
+        let mut text = String::new();
+        let mut depth = 1;
+        loop {
+            if self.starts_with("*/") {
+                depth -= 1;
+                if depth == 0 {
+                    self.bump();
+                    self.bump();
+                    return Ok(doc.map(|inner| Kind::Doc(text, inner)));
+                }
+            } else if self.starts_with("/*") {
+                depth += 1;
+            }
+            match self.peek(0) {
+                Some(_) => text.push(self.bump()),
+                None => return Err(syntax_error(span, "unterminated block comment")),
+            }
+        }
+    }
+
+    /// Whether a prefixed string or byte literal starts here (`r"`, `b'`, `br#"`, ...)
+    fn is_literal_prefix(&self) -> bool {
+        let raw = |at: usize| match self.peek(at) {
+            Some('"') => true,
+            Some('#') => {
+                let hashes = (at..).take_while(|&i| self.peek(i) == Some('#')).count();
+                self.peek(at + hashes) == Some('"')
+            }
+            _ => false,
+        };
+        match self.peek(0) {
+            Some('r') => raw(1),
+            Some('b') => {
+                matches!(self.peek(1), Some('"' | '\'')) || self.peek(1) == Some('r') && raw(2)
+            }
+            Some('c') => self.peek(1) == Some('"') || self.peek(1) == Some('r') && raw(2),
+            _ => false,
+        }
+    }
+
+    fn literal(&mut self, span: Span) -> Result<String> {
+        let mut text = self.bump_while(|c| matches!(c, 'b' | 'c' | 'r'));
+        if text.ends_with('r') {
+            let hashes = self.bump_while(|c| c == '#');
+            text.push_str(&hashes);
+            text.push(self.bump());
+            let terminator = format!("\"{}", hashes);
+            loop {
+                if self.starts_with(&terminator) {
+                    for _ in 0..terminator.chars().count() {
+                        text.push(self.bump());
+                    }
+                    return Ok(text);
+                }
+                match self.peek(0) {
+                    Some(_) => text.push(self.bump()),
+                    None => return Err(syntax_error(span, "unterminated raw string")),
+                }
+            }
+        }
+        text.push_str(&self.quoted(span)?);
+        Ok(text)
+    }
+
+    /// A `"..."` or `'...'` literal, escapes left as written
+    fn quoted(&mut self, span: Span) -> Result<String> {
+        let quote = self.bump();
+        let mut text = quote.to_string();
+        loop {
+            match self.peek(0) {
+                Some('\\') => {
+                    text.push(self.bump());
+                    if self.peek(0).is_some() {
+                        text.push(self.bump());
+                    }
+                }
+                Some(c) => {
+                    text.push(self.bump());
+                    if c == quote {
+                        text.push_str(&self.bump_while(is_ident_continue));
+                        return Ok(text);
+                    }
+                }
+                None => return Err(syntax_error(span, "unterminated literal")),
+            }
+        }
+    }
+
+    /// An integer or float literal with its suffix
+    fn number(&mut self) -> String {
+        let mut text = String::new();
+        let mut fraction = false;
+        loop {
+            match self.peek(0) {
+                Some(c) if is_ident_continue(c) => {
+                    text.push(self.bump());
+                    let exponent = matches!(c, 'e' | 'E') && !text.starts_with("0x");
+                    if exponent && matches!(self.peek(0), Some('+' | '-')) {
+                        text.push(self.bump());
+                    }
+                }
+                // `1.5` and `1.`, but not `1..2` or `1.max(2)`
+                Some('.')
+                    if !fraction
+                        && !text.starts_with("0x")
+                        && self.peek(1) != Some('.')
+                        && !self.peek(1).is_some_and(is_ident_start) =>
+                {
+                    fraction = true;
+                    text.push(self.bump());
+                }
+                _ => return text,
+            }
+        }
+    }
+}
+
+pub(crate) fn is_ident_start(c: char) -> bool {
+    c == '_' || c.is_alphabetic()
+}
+
+pub(crate) fn is_ident_continue(c: char) -> bool {
+    c == '_' || c.is_alphanumeric()
+}
+
+pub(crate) fn closing(open: char) -> char {
+    match open {
+        '(' => ')',
+        '[' => ']',
+        _ => '}',
+    }
+}
@@ -65,6 +65,16 @@ pub mod ast;
 /// Parser for .lumos files (builds AST from source code)
 pub mod parser;
 
+/// Hand-written parser for .lumos files, without `syn`
+#[cfg(feature = "fast-parser")]
+pub mod fast_parser;
+
+/// Tokenizer shared by the hand-written parser and the formatter
+mod lexer;
+
+/// Canonical formatting of .lumos files
+pub mod formatter;
+
 /// Schema parsing and validation (TOML format - legacy)
 pub mod schema;
 
@@ -74,6 +84,33 @@ pub mod ir;
 /// Transform AST into IR
 pub mod transform;
 
+/// Loading of multi-file schemas joined with `import` statements
+pub mod resolver;
+
+/// Constant folding of `const` items and numeric attribute expressions
+pub mod constants;
+
+/// Deterministic profile rejecting types that are not byte-stable
+pub mod determinism;
+
+/// Trailing-append check between schema versions
+pub mod evolution;
+
+/// Schema diff classifying changes as breaking or not for Borsh layouts
+pub mod schema_diff;
+
+/// Padding-free `repr(C)` layouts of `#[zero_copy]` structs
+pub mod zero_copy;
+
+/// Field reorderings that reduce padding and keep filter fields at fixed offsets
+pub mod layout_optimizer;
+
+/// Anchor IDL export and drift detection against deployed programs
+pub mod idl;
+
+/// Type dependency graph for blast-radius queries
+pub mod type_graph;
+
 /// Rust code generator
 pub mod generators {
     /// Generate Rust code from IR
@@ -81,6 +118,45 @@ pub mod generators {
 
     /// Generate TypeScript code from IR
     pub mod typescript;
+
+    /// Generate `@solana/kit` codecs as an alternative TypeScript backend
+    pub mod solana_kit;
+
+    /// Per-type snippet caching shared by the generators
+    pub mod cache;
+
+    /// Schema summary footer appended to generated modules
+    pub mod summary;
+
+    /// Generate account update decoders for indexers
+    pub mod indexer;
+
+    /// Generate account reference documentation
+    pub mod docs;
+
+    /// Generate OpenAPI component schemas
+    pub mod openapi;
+
+    /// Generate JSON Schema (draft 2020-12) documents
+    pub mod json_schema;
+
+    /// Generate Apache Arrow schemas for analytics
+    pub mod arrow;
+
+    /// Generate Borsh schema containers
+    pub mod borsh_schema;
+
+    /// Generate C headers for zero-copy structs
+    pub mod c_header;
+
+    /// Generate Python dataclasses and borsh-construct layouts
+    pub mod python;
+
+    /// Generate Go structs with Borsh encode and decode methods
+    pub mod go;
+
+    /// Generate Anchor IDL documents
+    pub mod idl;
 }
 
 /// Error types for LUMOS core
@@ -92,6 +168,45 @@ pub mod size_calculator;
 /// Security analyzer for detecting common Solana vulnerabilities
 pub mod security_analyzer;
 
+/// Cross-checks of Anchor `Accounts` contexts against the schema
+pub mod anchor_accounts;
+
+/// Style and best-practice lints for schemas
+pub mod linter;
+
+/// Field search over schemas, by name, type and attributes
+pub mod search;
+
+/// Custom validation passes and the manager running them
+pub mod validation;
+
+/// Hex, base64 and base58 encodings of raw bytes
+pub mod encoding;
+
+/// Borsh decoding of raw bytes with the schema
+pub mod borsh_decoder;
+
+/// Borsh encoding of JSON values with the schema
+pub mod borsh_encoder;
+
+/// Canonical example values from `#[test_vector(...)]` and their encodings
+pub mod test_vector;
+
+/// Decoding of sampled on-chain account data against the schema
+pub mod sampling;
+
+/// Instruction data decoding for explorer-style debugging
+pub mod instruction_decoder;
+
+/// Anchor event decoding from program logs
+pub mod event_decoder;
+
+/// Schema import from existing Rust account structs
+pub mod rust_importer;
+
+/// Best-effort schema import from TypeScript Borsh layouts and interfaces
+pub mod ts_importer;
+
 /// Security audit checklist generator
 pub mod audit_generator;
 
@@ -101,6 +216,27 @@ pub mod fuzz_generator;
 /// Corpus generator for fuzz testing
 pub mod corpus_generator;
 
+/// Schema-level coverage of fuzz corpora
+pub mod fuzz_coverage;
+
+/// Seeded mock data for frontend development
+pub mod mock_generator;
+
+/// Account migration functions between the versions of `#[version(n)]` structs
+pub mod migration_generator;
+
+/// Shared criticality scale for security findings and audit checklist items
+pub mod criticality;
+
+/// Thread-safe compiler with interned compilations for long-running hosts
+pub mod compiler;
+
+/// Panic-to-error conversion with bug reports for embedding hosts
+pub mod panic_guard;
+
+/// Name redaction of schemas shared in bug reports
+pub mod redaction;
+
 /// WASM bindings for browser playground
 #[cfg(feature = "wasm")]
 pub mod wasm;
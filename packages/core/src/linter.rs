@@ -0,0 +1,566 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Schema style and best-practice lints (`lumos lint`)
+//!
+//! Unlike the security analyzer, lints flag schemas that work but read
+//! badly or are likely mistakes:
+//!
+//! - `type-name-case`: type and enum variant names are not PascalCase
+//! - `field-name-case`: field names are not snake_case
+//! - `large-vec-in-account`: an `#[account]` has a `Vec` without `#[max]`,
+//!   or one that can hold more than 10 KiB, the most an account can grow by
+//!   in one instruction
+//! - `missing-account`: a `#[solana]` struct that no other type uses is not
+//!   marked `#[account]`
+//!
+//! Every rule warns by default. The `[lint]` section of `lumos.toml` sets a
+//! rule to `"allow"`, `"warn"` or `"deny"`:
+//!
+//! ```toml
+//! [lint]
+//! field-name-case = "deny"
+//! missing-account = "allow"
+//! ```
+
+use crate::ir::{EnumVariantDefinition, FieldDefinition, TypeDefinition, TypeInfo};
+use crate::security_analyzer::Location;
+use crate::size_calculator::SizeCalculator;
+use std::collections::BTreeMap;
+
+/// Largest `Vec` an account may hold, in bytes
+///
+/// Programs can only grow an account by 10 KiB per instruction
+/// (`MAX_PERMITTED_DATA_INCREASE`), so bigger vectors cannot be filled
+/// without reallocating over several transactions.
+pub const MAX_VEC_BYTES: usize = 10 * 1024;
+
+/// What a rule does when it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Not reported
+    Allow,
+
+    /// Reported as a warning
+    Warn,
+
+    /// Reported as an error, failing `lumos lint`
+    Deny,
+}
+
+impl Level {
+    /// Parse a `lumos.toml` value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "allow" => Some(Level::Allow),
+            "warn" => Some(Level::Warn),
+            "deny" => Some(Level::Deny),
+            _ => None,
+        }
+    }
+
+    /// Get string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Allow => "allow",
+            Level::Warn => "warn",
+            Level::Deny => "deny",
+        }
+    }
+}
+
+/// A lint rule
+#[derive(Debug)]
+pub struct Rule {
+    /// Stable identifier, also the `[lint]` key (e.g. "field-name-case")
+    pub id: &'static str,
+
+    /// What the rule checks
+    pub description: &'static str,
+}
+
+/// Every rule, in the order findings are reported
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "type-name-case",
+        description: "Type and enum variant names are PascalCase",
+    },
+    Rule {
+        id: "field-name-case",
+        description: "Field names are snake_case",
+    },
+    Rule {
+        id: "large-vec-in-account",
+        description: "Vec fields of accounts have a #[max] that fits in 10 KiB",
+    },
+    Rule {
+        id: "missing-account",
+        description: "Top-level #[solana] structs are marked #[account]",
+    },
+];
+
+/// Rule levels from the `[lint]` section of `lumos.toml`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintConfig {
+    levels: BTreeMap<String, Level>,
+}
+
+impl LintConfig {
+    /// Level of the rule `id`
+    pub fn level(&self, id: &str) -> Level {
+        self.levels.get(id).copied().unwrap_or(Level::Warn)
+    }
+
+    /// Set the level of the rule `id`
+    pub fn set(&mut self, id: &str, level: Level) {
+        self.levels.insert(id.to_string(), level);
+    }
+
+    /// Parse the `[lint]` section of `lumos.toml` content
+    ///
+    /// Unknown rules are skipped, so settings for a rule added in a newer
+    /// release do not break an older one.
+    ///
+    /// ```toml
+    /// [lint]
+    /// type-name-case = "deny"
+    /// ```
+    pub fn from_lumos_toml(input: &str) -> Result<Self, toml::de::Error> {
+        use serde::de::Error;
+
+        let mut config = Self::default();
+        let value: toml::Value = toml::from_str(input)?;
+        let Some(lint) = value.get("lint") else {
+            return Ok(config);
+        };
+        let lint = lint
+            .as_table()
+            .ok_or_else(|| toml::de::Error::custom("lint must be a table"))?;
+
+        for (id, level) in lint {
+            if !RULES.iter().any(|rule| rule.id == id) {
+                continue;
+            }
+            let level = level.as_str().and_then(Level::parse).ok_or_else(|| {
+                toml::de::Error::custom(format!(
+                    "lint.{} must be \"allow\", \"warn\" or \"deny\"",
+                    id
+                ))
+            })?;
+            config.set(id, level);
+        }
+        Ok(config)
+    }
+}
+
+/// A rule matched by the schema
+#[derive(Debug, Clone)]
+pub struct Lint {
+    /// Rule identifier
+    pub rule: &'static str,
+
+    /// Warning or error
+    pub level: Level,
+
+    /// Type and field the lint is about
+    pub location: Location,
+
+    /// What is wrong
+    pub message: String,
+
+    /// How to fix it
+    pub suggestion: String,
+}
+
+/// Runs the lint rules over a schema
+pub struct Linter<'a> {
+    type_defs: &'a [TypeDefinition],
+    config: LintConfig,
+}
+
+impl<'a> Linter<'a> {
+    /// Lint `type_defs` with every rule at its default level
+    pub fn new(type_defs: &'a [TypeDefinition]) -> Self {
+        Self {
+            type_defs,
+            config: LintConfig::default(),
+        }
+    }
+
+    /// Use the rule levels of `config`
+    pub fn with_config(mut self, config: LintConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Lints of every rule that is not allowed, by rule then type order
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        for rule in RULES {
+            let level = self.config.level(rule.id);
+            if level == Level::Allow {
+                continue;
+            }
+            let mut report = |location: Location, message: String, suggestion: String| {
+                lints.push(Lint {
+                    rule: rule.id,
+                    level,
+                    location,
+                    message,
+                    suggestion,
+                })
+            };
+            match rule.id {
+                "type-name-case" => self.check_type_names(&mut report),
+                "field-name-case" => self.check_field_names(&mut report),
+                "large-vec-in-account" => self.check_account_vecs(&mut report),
+                "missing-account" => self.check_missing_accounts(&mut report),
+                _ => unreachable!("rule without a check: {}", rule.id),
+            }
+        }
+        lints
+    }
+
+    fn check_type_names(&self, report: &mut impl FnMut(Location, String, String)) {
+        for type_def in self.type_defs {
+            let name = type_def.name();
+            if !is_pascal_case(name) {
+                report(
+                    location(name, None),
+                    format!("Type '{}' is not PascalCase", name),
+                    format!("Rename it to '{}'", to_pascal_case(name)),
+                );
+            }
+
+            let TypeDefinition::Enum(enum_def) = type_def else {
+                continue;
+            };
+            for variant in &enum_def.variants {
+                let variant = variant.name();
+                if !is_pascal_case(variant) {
+                    report(
+                        location(name, Some(variant)),
+                        format!("Variant '{}::{}' is not PascalCase", name, variant),
+                        format!("Rename it to '{}'", to_pascal_case(variant)),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_field_names(&self, report: &mut impl FnMut(Location, String, String)) {
+        for type_def in self.type_defs {
+            let name = type_def.name();
+            for field in fields(type_def) {
+                if !is_snake_case(&field.name) {
+                    report(
+                        location(name, Some(&field.name)),
+                        format!("Field '{}.{}' is not snake_case", name, field.name),
+                        format!("Rename it to '{}'", to_snake_case(&field.name)),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_account_vecs(&self, report: &mut impl FnMut(Location, String, String)) {
+        let calculator = SizeCalculator::new(self.type_defs);
+        for type_def in self.type_defs {
+            let TypeDefinition::Struct(struct_def) = type_def else {
+                continue;
+            };
+            if !struct_def.metadata.is_account() {
+                continue;
+            }
+
+            for field in &struct_def.fields {
+                let type_info = match &field.type_info {
+                    TypeInfo::Option(inner) => inner,
+                    type_info => type_info,
+                };
+                let TypeInfo::Array(element) = type_info else {
+                    continue;
+                };
+                let at = location(&struct_def.name, Some(&field.name));
+                let Some(max) = field.constraints().max_len else {
+                    report(
+                        at,
+                        format!(
+                            "Vec field '{}.{}' has no #[max], so the account can grow without bound",
+                            struct_def.name, field.name
+                        ),
+                        "Add #[max(n)] with the most items the account will hold".to_string(),
+                    );
+                    continue;
+                };
+
+                let element_bytes = calculator.type_size(element).min_bytes().max(1);
+                let bytes = usize::try_from(max)
+                    .unwrap_or(usize::MAX)
+                    .saturating_mul(element_bytes);
+                if bytes > MAX_VEC_BYTES {
+                    report(
+                        at,
+                        format!(
+                            "Vec field '{}.{}' can hold {} bytes ({} items of {} bytes), over the {} bytes an account can grow by at once",
+                            struct_def.name, field.name, bytes, max, element_bytes, MAX_VEC_BYTES
+                        ),
+                        format!(
+                            "Lower #[max] to {} or move the items into their own accounts",
+                            MAX_VEC_BYTES / element_bytes
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_missing_accounts(&self, report: &mut impl FnMut(Location, String, String)) {
+        let mut referenced = Vec::new();
+        for type_def in self.type_defs {
+            type_def.referenced_types(&mut referenced);
+        }
+
+        for type_def in self.type_defs {
+            let TypeDefinition::Struct(struct_def) = type_def else {
+                continue;
+            };
+            let metadata = &struct_def.metadata;
+            // Instructions and events are top-level by design
+            if !metadata.solana || !metadata.role.is_plain() {
+                continue;
+            }
+            if referenced.contains(&struct_def.name.as_str()) {
+                continue;
+            }
+            report(
+                location(&struct_def.name, None),
+                format!(
+                    "#[solana] struct '{}' is not an #[account] and no other type uses it",
+                    struct_def.name
+                ),
+                "Add #[account] if it is stored on-chain, or drop #[solana]".to_string(),
+            );
+        }
+    }
+}
+
+fn location(type_name: &str, field_name: Option<&str>) -> Location {
+    Location {
+        type_name: type_name.to_string(),
+        field_name: field_name.map(str::to_string),
+    }
+}
+
+/// Fields of a struct, or of every struct variant of an enum
+fn fields(type_def: &TypeDefinition) -> Vec<&FieldDefinition> {
+    match type_def {
+        TypeDefinition::Struct(struct_def) => struct_def.fields.iter().collect(),
+        TypeDefinition::Enum(enum_def) => enum_def
+            .variants
+            .iter()
+            .flat_map(|variant| match variant {
+                EnumVariantDefinition::Struct { fields, .. } => fields.iter().collect(),
+                _ => Vec::new(),
+            })
+            .collect(),
+    }
+}
+
+/// `PlayerAccount`: starts uppercase, letters and digits only
+fn is_pascal_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// `total_supply` or `_reserved`: lowercase letters, digits and underscores
+fn is_snake_case(name: &str) -> bool {
+    !name.starts_with(|c: char| c.is_ascii_digit())
+        && !name.contains("__")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Words of an identifier, split at underscores and lower-to-upper changes
+fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            words.push(std::mem::take(&mut word));
+            continue;
+        }
+        // `ownerKey` -> owner|Key, and `NFTListing` -> NFT|Listing
+        let previous = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_ascii_uppercase()
+            && previous.is_some_and(|p| {
+                p.is_ascii_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_ascii_uppercase() && next.is_some_and(char::is_ascii_lowercase))
+            });
+        if boundary {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+    }
+    words.push(word);
+    words.retain(|word| !word.is_empty());
+    words
+}
+
+fn to_pascal_case(name: &str) -> String {
+    words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => {
+                    first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+                }
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn to_snake_case(name: &str) -> String {
+    let snake = words(name)
+        .iter()
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    match name.starts_with('_') {
+        true => format!("_{}", snake),
+        false => snake,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        #[solana]
+        #[account]
+        struct player_account {
+            owner: PublicKey,
+            totalScore: u64,
+            _reserved: u8,
+            #[max(100)]
+            badges: Vec<u8>,
+            #[max(1000)]
+            friends: Vec<PublicKey>,
+            history: Vec<u64>,
+        }
+
+        #[solana]
+        struct Settings {
+            fee_bps: u16,
+        }
+
+        #[solana]
+        struct Stats {
+            wins: u32,
+        }
+
+        #[solana]
+        #[account]
+        struct Season {
+            stats: Stats,
+        }
+
+        #[solana]
+        enum GameState {
+            waiting_for_players,
+            Finished { WinnerIndex: u8 },
+        }
+    "#;
+
+    fn lint(config: LintConfig) -> Vec<Lint> {
+        let ir = transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap();
+        Linter::new(&ir).with_config(config).lint()
+    }
+
+    #[test]
+    fn reports_each_rule() {
+        let lints = lint(LintConfig::default());
+        let found: Vec<_> = lints
+            .iter()
+            .map(|lint| (lint.rule, lint.location.to_string()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("type-name-case", "player_account".to_string()),
+                (
+                    "type-name-case",
+                    "GameState::waiting_for_players".to_string()
+                ),
+                ("field-name-case", "player_account::totalScore".to_string()),
+                ("field-name-case", "GameState::WinnerIndex".to_string()),
+                (
+                    "large-vec-in-account",
+                    "player_account::friends".to_string()
+                ),
+                (
+                    "large-vec-in-account",
+                    "player_account::history".to_string()
+                ),
+                ("missing-account", "Settings".to_string()),
+            ]
+        );
+        assert!(lints.iter().all(|lint| lint.level == Level::Warn));
+        assert_eq!(lints[0].suggestion, "Rename it to 'PlayerAccount'");
+        assert_eq!(lints[1].suggestion, "Rename it to 'WaitingForPlayers'");
+        assert_eq!(lints[2].suggestion, "Rename it to 'total_score'");
+        assert!(lints[4].message.contains("32000 bytes"));
+        assert_eq!(
+            lints[4].suggestion,
+            "Lower #[max] to 320 or move the items into their own accounts"
+        );
+    }
+
+    #[test]
+    fn applies_configured_levels() {
+        let config = LintConfig::from_lumos_toml(
+            "[lint]\nfield-name-case = \"deny\"\ntype-name-case = \"allow\"\nlarge-vec-in-account = \"allow\"\n",
+        )
+        .unwrap();
+        let lints = lint(config);
+        let rules: Vec<_> = lints.iter().map(|lint| (lint.rule, lint.level)).collect();
+        assert_eq!(
+            rules,
+            [
+                ("field-name-case", Level::Deny),
+                ("field-name-case", Level::Deny),
+                ("missing-account", Level::Warn),
+            ]
+        );
+
+        let unknown = LintConfig::from_lumos_toml("[lint]\nfield-case = \"deny\"\n").unwrap();
+        assert_eq!(unknown, LintConfig::default());
+        let invalid =
+            LintConfig::from_lumos_toml("[lint]\nfield-name-case = \"error\"\n").unwrap_err();
+        assert!(invalid
+            .to_string()
+            .contains("must be \"allow\", \"warn\" or \"deny\""));
+    }
+
+    #[test]
+    fn converts_identifier_case() {
+        assert!(is_pascal_case("PlayerAccount"));
+        assert!(!is_pascal_case("Player_Account"));
+        assert!(is_snake_case("total_supply_v2"));
+        assert!(is_snake_case("_reserved"));
+        assert!(!is_snake_case("total__supply"));
+        assert_eq!(to_pascal_case("nft_listing"), "NftListing");
+        assert_eq!(to_pascal_case("NFTListing"), "NftListing");
+        assert_eq!(to_snake_case("ownerPubkey"), "owner_pubkey");
+        assert_eq!(to_snake_case("_Padding"), "_padding");
+    }
+}
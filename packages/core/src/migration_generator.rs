@@ -0,0 +1,291 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Account migration helpers between the versions of a `#[version(n)]` struct
+//!
+//! Each field marked `#[since(k + 1)]` is what version `k + 1` adds to
+//! version `k`. [`steps`] lists those field-level diffs, and the generators
+//! emit one migration per step: it decodes data written under version `k`,
+//! fills the added fields with their defaults and re-encodes it under version
+//! `k + 1`. Added fields always have a default-encoded size known up front, so
+//! a program knows exactly how far to `realloc` the account before writing the
+//! migrated bytes back.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::ir::TypeDefinition;
+//! use lumos_core::migration_generator::steps;
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "#[solana] #[version(2)] struct Vault { owner: PublicKey, #[since(2)] fee: Option<u16> }",
+//! )?)?;
+//! let TypeDefinition::Struct(vault) = &ir[0] else { unreachable!() };
+//!
+//! let steps = steps(vault);
+//! assert_eq!((steps[0].from, steps[0].to), (1, 2));
+//! assert_eq!(steps[0].added, ["fee"]);
+//! assert_eq!(steps[0].growth, 1);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::ir::{StructDefinition, TypeInfo};
+use crate::layout_optimizer::borsh_size;
+
+/// One migration between consecutive versions of a struct
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStep {
+    /// Version the data is written under
+    pub from: u32,
+
+    /// Version the data is migrated to, `from + 1`
+    pub to: u32,
+
+    /// Fields added by version `to`, in layout order
+    pub added: Vec<String>,
+
+    /// Bytes the Borsh data grows by when the added fields hold their defaults
+    pub growth: usize,
+}
+
+/// TypeScript runtime the migrations are generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeScriptCodec {
+    /// `@coral-xyz/borsh` schemas (`{Name}Schema`)
+    Borsh,
+
+    /// `@solana/kit` codecs (`get{Name}Encoder`/`get{Name}Decoder`)
+    SolanaKit,
+}
+
+/// Field-level diffs between each version of `struct_def` and the next
+///
+/// Empty unless the struct is at version 2 or later.
+pub fn steps(struct_def: &StructDefinition) -> Vec<MigrationStep> {
+    let current = struct_def.metadata.version.unwrap_or(1);
+    (1..current)
+        .map(|from| {
+            let added: Vec<_> = struct_def
+                .fields
+                .iter()
+                .filter(|field| field.since_version() == from + 1)
+                .collect();
+            MigrationStep {
+                from,
+                to: from + 1,
+                added: added.iter().map(|field| field.name.clone()).collect(),
+                growth: added
+                    .iter()
+                    .map(|field| default_size(&field.type_info))
+                    .sum(),
+            }
+        })
+        .collect()
+}
+
+/// Rust `migrate_v{k}_to_v{k+1}` functions and growth constants
+///
+/// Relies on the `{Name}V{k}` structs and `From` impls of the version shims.
+pub fn generate_rust(struct_def: &StructDefinition) -> String {
+    let steps = steps(struct_def);
+    if steps.is_empty() {
+        return String::new();
+    }
+    let name = &struct_def.name;
+
+    let mut output = format!("\nimpl {} {{\n", name);
+    for (i, step) in steps.iter().enumerate() {
+        if i > 0 {
+            output.push('\n');
+        }
+        output.push_str(&format!(
+            "    /// Bytes a version {} `{}` grows by when migrated to version {}\n",
+            step.from, name, step.to
+        ));
+        output.push_str(&format!(
+            "    pub const V{}_TO_V{}_GROWTH: usize = {};\n\n",
+            step.from, step.to, step.growth
+        ));
+        output.push_str(&format!(
+            "    /// Re-encode version {} data under version {}, adding {}\n",
+            step.from,
+            step.to,
+            field_list(&step.added)
+        ));
+        output.push_str("    ///\n");
+        output.push_str(&format!(
+            "    /// For accounts, pass the data after the 8-byte discriminator and realloc\n    /// the account by `V{}_TO_V{}_GROWTH` bytes before writing the result back.\n",
+            step.from, step.to
+        ));
+        output.push_str(&format!(
+            "    pub fn migrate_v{}_to_v{}(data: &[u8]) -> std::io::Result<Vec<u8>> {{\n",
+            step.from, step.to
+        ));
+        output.push_str(&format!(
+            "        let old = {}V{}::deserialize(&mut &data[..])?;\n",
+            name, step.from
+        ));
+        output.push_str("        let mut bytes = Vec::new();\n");
+        output.push_str(&format!(
+            "        {}::from(old).serialize(&mut bytes)?;\n",
+            version_name(struct_def, step.to)
+        ));
+        output.push_str("        Ok(bytes)\n");
+        output.push_str("    }\n");
+    }
+    output.push_str("}\n");
+
+    output
+}
+
+/// TypeScript `migrate{Name}V{k}ToV{k+1}` functions
+///
+/// Relies on the `upgrade{Name}V{k}` functions of the version shims, and for
+/// [`TypeScriptCodec::Borsh`] on the generated `encodeBorsh` helper.
+pub fn generate_typescript(struct_def: &StructDefinition, codec: TypeScriptCodec) -> String {
+    let name = &struct_def.name;
+
+    let mut output = String::new();
+    for step in steps(struct_def) {
+        let next = version_name(struct_def, step.to);
+        output.push_str(&format!(
+            "\n/** Re-encode version {} `{}` data under version {}, adding {} ({} {} longer) */\n",
+            step.from,
+            name,
+            step.to,
+            field_list(&step.added),
+            step.growth,
+            if step.growth == 1 { "byte" } else { "bytes" }
+        ));
+        let (data, bytes, body) = match codec {
+            TypeScriptCodec::Borsh => (
+                "Buffer",
+                "Uint8Array",
+                format!(
+                    "encodeBorsh({}Schema, upgrade{}V{}({}V{}Schema.decode(data)))",
+                    next, name, step.from, name, step.from
+                ),
+            ),
+            TypeScriptCodec::SolanaKit => (
+                "ReadonlyUint8Array",
+                "ReadonlyUint8Array",
+                format!(
+                    "get{}Encoder().encode(upgrade{}V{}(get{}V{}Decoder().decode(data)))",
+                    next, name, step.from, name, step.from
+                ),
+            ),
+        };
+        output.push_str(&format!(
+            "export function migrate{}V{}ToV{}(data: {}): {} {{\n",
+            name, step.from, step.to, data, bytes
+        ));
+        output.push_str(&format!("  return {};\n", body));
+        output.push_str("}\n");
+    }
+
+    output
+}
+
+/// Name of the generated type holding `version` of the struct
+fn version_name(struct_def: &StructDefinition, version: u32) -> String {
+    if struct_def.metadata.version == Some(version) {
+        struct_def.name.clone()
+    } else {
+        format!("{}V{}", struct_def.name, version)
+    }
+}
+
+fn field_list(fields: &[String]) -> String {
+    let fields: Vec<_> = fields.iter().map(|field| format!("`{}`", field)).collect();
+    match fields.as_slice() {
+        [] => "no fields".to_string(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+/// Borsh size of a field's upgrade default
+///
+/// The transform only lets fields with such a default be added in a later
+/// version: `None`, empty collections and strings carry their tag or length
+/// prefix, everything else is fixed-size.
+fn default_size(type_info: &TypeInfo) -> usize {
+    match type_info {
+        TypeInfo::Option(_) => 1,
+        TypeInfo::Array(_) | TypeInfo::Map(..) => 4,
+        TypeInfo::Primitive(name) if name == "String" || name == "Signature" => 4,
+        TypeInfo::FixedArray(inner, len) => default_size(inner) * len,
+        other => borsh_size(&[], other, &mut Vec::new()).unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::TypeDefinition;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn vault() -> StructDefinition {
+        let source = r#"
+            #[solana]
+            #[account]
+            #[version(3)]
+            struct Vault {
+                owner: PublicKey,
+                #[since(2)]
+                label: String,
+                #[since(2)]
+                fee: Option<u16>,
+                #[since(3)]
+                limits: [u64; 4],
+                #[since(3)]
+                paused: bool,
+            }
+        "#;
+        let ir = transform_to_ir(parse_lumos_file(source).unwrap()).unwrap();
+        let TypeDefinition::Struct(vault) = ir.into_iter().next().unwrap() else {
+            unreachable!()
+        };
+        vault
+    }
+
+    #[test]
+    fn steps_follow_since_versions() {
+        let steps = steps(&vault());
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].added, ["label", "fee"]);
+        assert_eq!(steps[0].growth, 4 + 1);
+        assert_eq!((steps[1].from, steps[1].to), (2, 3));
+        assert_eq!(steps[1].added, ["limits", "paused"]);
+        assert_eq!(steps[1].growth, 4 * 8 + 1);
+    }
+
+    #[test]
+    fn generates_migrations_between_consecutive_versions() {
+        let rust = generate_rust(&vault());
+        assert!(rust.contains("pub const V1_TO_V2_GROWTH: usize = 5;"));
+        assert!(rust.contains("pub fn migrate_v1_to_v2(data: &[u8]) -> std::io::Result<Vec<u8>>"));
+        assert!(rust.contains("VaultV2::from(old).serialize(&mut bytes)?;"));
+        assert!(rust.contains("let old = VaultV2::deserialize(&mut &data[..])?;"));
+        assert!(rust.contains("Vault::from(old).serialize(&mut bytes)?;"));
+        assert!(rust.contains("adding `limits` and `paused`"));
+
+        let ts = generate_typescript(&vault(), TypeScriptCodec::Borsh);
+        assert!(ts.contains(
+            "export function migrateVaultV1ToV2(data: Buffer): Uint8Array {\n  \
+             return encodeBorsh(VaultV2Schema, upgradeVaultV1(VaultV1Schema.decode(data)));"
+        ));
+        assert!(ts.contains("(33 bytes longer)"));
+
+        let kit = generate_typescript(&vault(), TypeScriptCodec::SolanaKit);
+        assert!(kit.contains(
+            "return getVaultEncoder().encode(upgradeVaultV2(getVaultV2Decoder().decode(data)));"
+        ));
+
+        let mut unversioned = vault();
+        unversioned.metadata.version = None;
+        assert!(generate_rust(&unversioned).is_empty());
+    }
+}
@@ -0,0 +1,981 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Mock data generator for frontends
+//!
+//! Generates plausible, seeded instances of schema types for UI development
+//! and storybook fixtures. Values follow the shapes of the generated
+//! TypeScript types (enums are `{ kind: ... }` unions, `u128` is a `bigint`),
+//! and field names steer the values the way they do for the fuzz corpus:
+//! `created_at` gets a recent timestamp, `name` a person's name, `bump` a
+//! valid bump seed, and so on. Values stay within the `#[min]`, `#[max]` and
+//! `#[range]` bounds, so fixtures pass the generated `validate` code.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::mock_generator::{self, MockGenerator};
+//!
+//! let ir = lumos_core::transform::transform_to_ir(lumos_core::parser::parse_lumos_file(
+//!     "struct Player { name: String, created_at: i64 }",
+//! )?)?;
+//!
+//! let players = MockGenerator::new(&ir).with_seed(7).generate("Player", 3).unwrap();
+//! assert_eq!(players.len(), 3);
+//!
+//! let json = mock_generator::to_json(&players);
+//! assert!(json.contains("\"created_at\""));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::corpus_generator::{looks_like_timestamp, SeededRng, TIMESTAMP_RANGE};
+use crate::encoding::{decode_base58, encode_base58};
+use crate::generators::typescript::Runtime;
+use crate::ir::{Constraints, EnumDefinition, EnumVariantDefinition, TypeDefinition, TypeInfo};
+
+/// Largest integer a TypeScript `number` holds exactly (2^53 - 1)
+const MAX_SAFE_INTEGER: i64 = 9_007_199_254_740_991;
+
+/// Nesting depth after which arrays are empty and options are absent
+const MAX_DEPTH: usize = 4;
+
+const FIRST_NAMES: &[&str] = &[
+    "Alice", "Bob", "Carol", "Dave", "Erin", "Frank", "Grace", "Heidi", "Ivan", "Judy", "Mallory",
+    "Niaj", "Olivia", "Peggy", "Rupert", "Sybil", "Trent", "Victor", "Walter", "Yuki",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Nakamoto", "Lovelace", "Hopper", "Turing", "Shannon", "Hamilton", "Ritchie", "Liskov",
+    "Knuth", "Torvalds",
+];
+
+const WORDS: &[&str] = &[
+    "amber", "bright", "cobalt", "crystal", "ember", "falcon", "golden", "harbor", "lunar",
+    "meadow", "nebula", "orbit", "quiet", "river", "silver", "solar", "summit", "velvet", "violet",
+    "willow",
+];
+
+const SYMBOLS: &[&str] = &["SOL", "USDC", "USDT", "BONK", "JUP", "RAY", "ORCA", "MNDE"];
+
+/// A generated value, shaped like the generated TypeScript types
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockValue {
+    /// `boolean`
+    Bool(bool),
+
+    /// Integer that maps to `number`, always within the safe integer range
+    Number(i64),
+
+    /// Floating point `number`
+    Float(f64),
+
+    /// `u128`/`i128`, which map to `bigint`
+    BigInt(i128),
+
+    /// `string`
+    String(String),
+
+    /// Base58-encoded `PublicKey`
+    PublicKey(String),
+
+    /// Array of values
+    Array(Vec<MockValue>),
+
+    /// Optional value; `None` is rendered as `null` / `undefined`
+    Option(Option<Box<MockValue>>),
+
+    /// Map entries with distinct keys, in Borsh (key) order
+    Map(Vec<(MockValue, MockValue)>),
+
+    /// Struct, or enum variant with its `kind` first, in declaration order
+    Object(Vec<(String, MockValue)>),
+}
+
+/// Seeded generator of mock instances
+pub struct MockGenerator<'a> {
+    /// All type definitions
+    type_defs: &'a [TypeDefinition],
+
+    /// Seed; the same seed always produces the same instances
+    seed: u64,
+}
+
+impl<'a> MockGenerator<'a> {
+    /// Create a new mock generator with seed 0
+    pub fn new(type_defs: &'a [TypeDefinition]) -> Self {
+        Self { type_defs, seed: 0 }
+    }
+
+    /// Use `seed` for the generated values
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Generate `count` instances of `type_name`, or `None` if the type doesn't exist
+    ///
+    /// Each type gets its own random stream, so a larger `count` extends the
+    /// instances of a smaller one instead of replacing them.
+    pub fn generate(&self, type_name: &str, count: usize) -> Option<Vec<MockValue>> {
+        self.find(type_name)?;
+
+        let mut rng = SeededRng::for_type(self.seed, type_name);
+        let type_info = TypeInfo::UserDefined(type_name.to_string());
+        Some(
+            (0..count)
+                .map(|_| self.value(&type_info, "", Constraints::default(), &mut rng, 0))
+                .collect(),
+        )
+    }
+
+    fn find(&self, type_name: &str) -> Option<&'a TypeDefinition> {
+        self.type_defs.iter().find(|t| t.name() == type_name)
+    }
+
+    /// Random value for a type; `field_name` hints at a realistic value
+    /// and `constraints` are the field's bounds
+    fn value(
+        &self,
+        type_info: &TypeInfo,
+        field_name: &str,
+        constraints: Constraints,
+        rng: &mut SeededRng,
+        depth: usize,
+    ) -> MockValue {
+        let element = |rng: &mut SeededRng, inner: &TypeInfo| {
+            self.value(inner, field_name, Constraints::default(), rng, depth + 1)
+        };
+
+        match type_info {
+            TypeInfo::Primitive(name) => primitive(name, field_name, constraints, rng),
+            TypeInfo::Array(inner) => {
+                let min = constraints.min_len.unwrap_or(0);
+                let len = if depth >= MAX_DEPTH {
+                    min
+                } else {
+                    let max = constraints.max_len.unwrap_or(u64::MAX).min(min + 3);
+                    rng.range(min, max + 1)
+                };
+                MockValue::Array((0..len).map(|_| element(rng, inner)).collect())
+            }
+            TypeInfo::FixedArray(inner, len) => {
+                MockValue::Array((0..*len).map(|_| element(rng, inner)).collect())
+            }
+            TypeInfo::Map(_, key, value) => {
+                let len = if depth >= MAX_DEPTH {
+                    0
+                } else {
+                    rng.range(0, 4)
+                };
+                let mut entries: Vec<(MockValue, MockValue)> = Vec::new();
+                for _ in 0..len {
+                    let entry_key = element(rng, key);
+                    let entry_value = element(rng, value);
+                    if !entries.iter().any(|(k, _)| *k == entry_key) {
+                        entries.push((entry_key, entry_value));
+                    }
+                }
+                entries.sort_by(|(a, _), (b, _)| key_order(a, b));
+                MockValue::Map(entries)
+            }
+            TypeInfo::Option(inner) => {
+                // Mostly present, so UIs render populated states by default
+                if depth >= MAX_DEPTH || rng.range(0, 4) == 0 {
+                    MockValue::Option(None)
+                } else {
+                    let value = self.value(inner, field_name, constraints, rng, depth + 1);
+                    MockValue::Option(Some(Box::new(value)))
+                }
+            }
+            TypeInfo::UserDefined(type_name) => match self.find(type_name) {
+                Some(TypeDefinition::Struct(s)) => MockValue::Object(
+                    s.fields
+                        .iter()
+                        .map(|field| {
+                            let value = self.value(
+                                &field.type_info,
+                                &field.name,
+                                field.constraints(),
+                                rng,
+                                depth + 1,
+                            );
+                            (field.name.clone(), value)
+                        })
+                        .collect(),
+                ),
+                Some(TypeDefinition::Enum(e)) => self.variant(e, rng, depth + 1),
+                // Unknown type - leave the value out
+                None => MockValue::Option(None),
+            },
+        }
+    }
+
+    /// Random variant of an enum as a `{ kind, ... }` object
+    fn variant(&self, enum_def: &EnumDefinition, rng: &mut SeededRng, depth: usize) -> MockValue {
+        if enum_def.variants.is_empty() {
+            return MockValue::Option(None);
+        }
+
+        let index = rng.range(0, enum_def.variants.len() as u64) as usize;
+        let variant = &enum_def.variants[index];
+        let mut fields = vec![(
+            "kind".to_string(),
+            MockValue::String(variant.name().to_string()),
+        )];
+
+        match variant {
+            EnumVariantDefinition::Unit { .. } => {}
+            EnumVariantDefinition::Tuple { types, .. } => {
+                for (idx, type_info) in types.iter().enumerate() {
+                    let name = format!("field{}", idx);
+                    let value = self.value(type_info, &name, Constraints::default(), rng, depth);
+                    fields.push((name, value));
+                }
+            }
+            EnumVariantDefinition::Struct {
+                fields: variant_fields,
+                ..
+            } => {
+                for field in variant_fields {
+                    let value = self.value(
+                        &field.type_info,
+                        &field.name,
+                        field.constraints(),
+                        rng,
+                        depth,
+                    );
+                    fields.push((field.name.clone(), value));
+                }
+            }
+        }
+
+        MockValue::Object(fields)
+    }
+
+    /// Borsh encoding of an instance of `type_name`, without an account discriminator
+    ///
+    /// Values left out for unknown types encode to nothing.
+    pub fn to_borsh(&self, type_name: &str, value: &MockValue) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(
+            &TypeInfo::UserDefined(type_name.to_string()),
+            value,
+            &mut out,
+        );
+        out
+    }
+
+    fn encode(&self, type_info: &TypeInfo, value: &MockValue, out: &mut Vec<u8>) {
+        match (type_info, value) {
+            (TypeInfo::Primitive(name), value) => encode_primitive(name, value, out),
+            (TypeInfo::Array(inner), MockValue::Array(items)) => {
+                out.extend((items.len() as u32).to_le_bytes());
+                for item in items {
+                    self.encode(inner, item, out);
+                }
+            }
+            (TypeInfo::FixedArray(inner, _), MockValue::Array(items)) => {
+                for item in items {
+                    self.encode(inner, item, out);
+                }
+            }
+            (TypeInfo::Map(_, key, value), MockValue::Map(entries)) => {
+                out.extend((entries.len() as u32).to_le_bytes());
+                for (entry_key, entry_value) in entries {
+                    self.encode(key, entry_key, out);
+                    self.encode(value, entry_value, out);
+                }
+            }
+            (TypeInfo::Option(_), MockValue::Option(None)) => out.push(0),
+            (TypeInfo::Option(inner), MockValue::Option(Some(value))) => {
+                out.push(1);
+                self.encode(inner, value, out);
+            }
+            (TypeInfo::UserDefined(type_name), MockValue::Object(values)) => {
+                match self.find(type_name) {
+                    Some(TypeDefinition::Struct(s)) => {
+                        for (field, (_, value)) in s.fields.iter().zip(values) {
+                            self.encode(&field.type_info, value, out);
+                        }
+                    }
+                    Some(TypeDefinition::Enum(e)) => {
+                        let Some((_, MockValue::String(kind))) = values.first() else {
+                            return;
+                        };
+                        let Some(index) = e.variants.iter().position(|v| v.name() == kind) else {
+                            return;
+                        };
+                        out.push(index as u8);
+
+                        let types: Vec<&TypeInfo> = match &e.variants[index] {
+                            EnumVariantDefinition::Unit { .. } => Vec::new(),
+                            EnumVariantDefinition::Tuple { types, .. } => types.iter().collect(),
+                            EnumVariantDefinition::Struct { fields, .. } => {
+                                fields.iter().map(|f| &f.type_info).collect()
+                            }
+                        };
+                        for (type_info, (_, value)) in types.into_iter().zip(&values[1..]) {
+                            self.encode(type_info, value, out);
+                        }
+                    }
+                    None => {}
+                }
+            }
+            // Values left out for unknown types
+            _ => {}
+        }
+    }
+}
+
+/// Borsh encoding of a primitive value
+fn encode_primitive(type_name: &str, value: &MockValue, out: &mut Vec<u8>) {
+    match (type_name, value) {
+        ("bool", MockValue::Bool(b)) => out.push(u8::from(*b)),
+        ("u8" | "i8", MockValue::Number(n)) => out.push(*n as u8),
+        ("u16" | "i16", MockValue::Number(n)) => out.extend((*n as u16).to_le_bytes()),
+        ("u32" | "i32", MockValue::Number(n)) => out.extend((*n as u32).to_le_bytes()),
+        ("u64" | "i64", MockValue::Number(n)) => out.extend((*n as u64).to_le_bytes()),
+        ("u128" | "i128", MockValue::BigInt(n)) => out.extend(n.to_le_bytes()),
+        ("f32", MockValue::Float(f)) => out.extend((*f as f32).to_le_bytes()),
+        ("f64", MockValue::Float(f)) => out.extend(f.to_le_bytes()),
+        ("String", MockValue::String(s)) => {
+            out.extend((s.len() as u32).to_le_bytes());
+            out.extend(s.as_bytes());
+        }
+        ("Pubkey" | "PublicKey", MockValue::PublicKey(encoded))
+        | ("Signature", MockValue::String(encoded)) => {
+            out.extend(decode_base58(encoded).unwrap_or_default())
+        }
+        _ => {}
+    }
+}
+
+/// Random primitive value, steered by the field name and kept within `constraints`
+fn primitive(
+    type_name: &str,
+    field_name: &str,
+    constraints: Constraints,
+    rng: &mut SeededRng,
+) -> MockValue {
+    match type_name {
+        "bool" => MockValue::Bool(rng.next_u64() % 2 == 0),
+        "String" => MockValue::String(fit_text(text(field_name, rng), constraints, rng)),
+        "Pubkey" | "PublicKey" => MockValue::PublicKey(encode_base58(&rng.bytes(32))),
+        "Signature" => MockValue::String(encode_base58(&rng.bytes(64))),
+        "f32" | "f64" => MockValue::Float(rng.range(0, 100_000) as f64 / 100.0),
+        "u128" => MockValue::BigInt(i128::from(integer(
+            field_name,
+            within((0, MAX_SAFE_INTEGER), constraints),
+            rng,
+        ))),
+        "i128" => MockValue::BigInt(i128::from(integer(
+            field_name,
+            within((-MAX_SAFE_INTEGER, MAX_SAFE_INTEGER), constraints),
+            rng,
+        ))),
+        _ => match integer_bounds(type_name) {
+            Some(bounds) => {
+                MockValue::Number(integer(field_name, within(bounds, constraints), rng))
+            }
+            // Unknown primitive - leave the value out
+            None => MockValue::Option(None),
+        },
+    }
+}
+
+/// Narrow an integer type's bounds to a `#[range]`
+///
+/// A range entirely outside what a `number` holds exactly gets its lowest
+/// value, which `validate` accepts even if TypeScript rounds it.
+fn within((min, max): (i64, i64), constraints: Constraints) -> (i64, i64) {
+    let Some((low, high)) = constraints.range else {
+        return (min, max);
+    };
+    let low = low.clamp(i64::MIN.into(), i64::MAX.into()) as i64;
+    let high = high.clamp(i64::MIN.into(), i64::MAX.into()) as i64;
+    match (min.max(low), max.min(high)) {
+        (min, max) if min <= max => (min, max),
+        _ => (low, low),
+    }
+}
+
+/// Order of map keys as the Rust key types compare, public keys by their bytes
+fn key_order(a: &MockValue, b: &MockValue) -> std::cmp::Ordering {
+    match (a, b) {
+        (MockValue::Number(a), MockValue::Number(b)) => a.cmp(b),
+        (MockValue::BigInt(a), MockValue::BigInt(b)) => a.cmp(b),
+        (MockValue::Bool(a), MockValue::Bool(b)) => a.cmp(b),
+        (MockValue::PublicKey(a), MockValue::PublicKey(b)) => {
+            decode_base58(a).ok().cmp(&decode_base58(b).ok())
+        }
+        (MockValue::String(a), MockValue::String(b)) => a.cmp(b),
+        _ => std::cmp::Ordering::Equal,
+    }
+}
+
+/// Range of an integer type, capped to what a TypeScript `number` holds exactly
+fn integer_bounds(type_name: &str) -> Option<(i64, i64)> {
+    Some(match type_name {
+        "u8" => (0, u8::MAX.into()),
+        "i8" => (i8::MIN.into(), i8::MAX.into()),
+        "u16" => (0, u16::MAX.into()),
+        "i16" => (i16::MIN.into(), i16::MAX.into()),
+        "u32" => (0, u32::MAX.into()),
+        "i32" => (i32::MIN.into(), i32::MAX.into()),
+        "u64" => (0, MAX_SAFE_INTEGER),
+        "i64" => (-MAX_SAFE_INTEGER, MAX_SAFE_INTEGER),
+        _ => return None,
+    })
+}
+
+/// Plausible integer for a field, clamped to the type's range
+fn integer(field_name: &str, (min, max): (i64, i64), rng: &mut SeededRng) -> i64 {
+    let hint = field_name.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|word| hint.contains(word));
+
+    let (low, high) = if max >= TIMESTAMP_RANGE.1 as i64 && looks_like_timestamp(field_name) {
+        (TIMESTAMP_RANGE.0, TIMESTAMP_RANGE.1)
+    } else if has(&["bump"]) {
+        (250, 256)
+    } else if has(&["bps", "basis_points"]) {
+        (0, 10_001)
+    } else if has(&["percent", "pct"]) {
+        (0, 101)
+    } else if has(&["decimals"]) {
+        (0, 10)
+    } else if has(&[
+        "amount", "balance", "lamports", "price", "supply", "stake", "reward", "fee", "deposit",
+        "total", "value",
+    ]) {
+        (1_000, 1_000_000_000_000)
+    } else {
+        (0, 1_000)
+    };
+
+    // Narrow types (a `u8` balance) and `#[range]`s the hint misses fall
+    // back to their own range
+    let (low, high) = (i128::from(low), i128::from(high));
+    let (min_wide, max_wide) = (i128::from(min), i128::from(max));
+    let (low, high) = if low > max_wide || high <= min_wide {
+        (min_wide, max_wide + 1)
+    } else {
+        (low.max(min_wide), high.min(max_wide + 1))
+    };
+
+    let value = (low + i128::from(rng.range(0, (high - low) as u64))) as i64;
+    // Signed fields without a hint are sometimes negative
+    let value = if min < 0 && high == 1_000 && rng.next_u64() % 4 == 0 {
+        -value
+    } else {
+        value
+    };
+    value.clamp(min, max)
+}
+
+/// Pad or cut `text` to the `#[min]`/`#[max]` byte lengths
+fn fit_text(mut text: String, constraints: Constraints, rng: &mut SeededRng) -> String {
+    let min = constraints.min_len.unwrap_or(0) as usize;
+    while text.len() < min {
+        text.push('-');
+        text.push_str(WORDS[rng.range(0, WORDS.len() as u64) as usize]);
+    }
+    if let Some(max) = constraints.max_len {
+        let mut end = (max as usize).min(text.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    text
+}
+
+/// Plausible text for a string field
+fn text(field_name: &str, rng: &mut SeededRng) -> String {
+    let hint = field_name.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|word| hint.contains(word));
+    let mut pick = |list: &[&'static str]| list[rng.range(0, list.len() as u64) as usize];
+
+    if has(&["email"]) {
+        format!("{}@example.com", pick(FIRST_NAMES).to_lowercase())
+    } else if has(&["image", "avatar", "icon", "logo"]) {
+        format!("https://example.com/images/{}.png", pick(WORDS))
+    } else if has(&["url", "uri", "link", "website"]) {
+        format!("https://example.com/{}/{}", pick(WORDS), pick(WORDS))
+    } else if has(&["symbol", "ticker"]) {
+        pick(SYMBOLS).to_string()
+    } else if has(&["username", "handle"]) {
+        format!("{}_{}", pick(FIRST_NAMES).to_lowercase(), pick(WORDS))
+    } else if has(&["name", "owner", "author", "creator"]) {
+        format!("{} {}", pick(FIRST_NAMES), pick(LAST_NAMES))
+    } else if has(&["description", "bio", "memo", "note", "message", "comment"]) {
+        let words: Vec<&str> = (0..6).map(|_| pick(WORDS)).collect();
+        let sentence = words.join(" ");
+        format!("{}{}.", sentence[..1].to_uppercase(), &sentence[1..])
+    } else {
+        format!("{}-{}", pick(WORDS), pick(WORDS))
+    }
+}
+
+/// Render one instance as pretty-printed JSON
+pub fn instance_to_json(value: &MockValue) -> String {
+    let mut out = String::new();
+    render(value, Syntax::Json, 0, &mut out);
+    out.push('\n');
+    out
+}
+
+/// Render instances as a pretty-printed JSON array
+///
+/// `bigint` values become strings, since JSON numbers can't hold them exactly.
+pub fn to_json(values: &[MockValue]) -> String {
+    let mut out = String::new();
+    render_list(values, Syntax::Json, 0, &mut out);
+    out.push('\n');
+    out
+}
+
+/// Render instances as a TypeScript module exporting `<typeName>Mocks`
+///
+/// The type is imported from `import_from`, usually the generated module,
+/// with the specifiers `runtime` resolves.
+pub fn to_typescript(
+    type_name: &str,
+    values: &[MockValue],
+    import_from: &str,
+    runtime: Runtime,
+) -> String {
+    let mut out = String::new();
+    out.push_str("// Auto-generated by LUMOS\n");
+    out.push_str("// DO NOT EDIT - Changes will be overwritten\n\n");
+
+    if values.iter().any(contains_public_key) {
+        out.push_str(&format!(
+            "import {{ PublicKey }} from '{}';\n",
+            runtime.package("@solana/web3.js")
+        ));
+    }
+    out.push_str(&format!(
+        "import type {{ {} }} from '{}';\n\n",
+        type_name,
+        runtime.local_module(import_from)
+    ));
+
+    out.push_str(&format!(
+        "export const {}Mocks: {}[] = ",
+        lower_camel_case(type_name),
+        type_name
+    ));
+    render_list(values, Syntax::TypeScript, 0, &mut out);
+    out.push_str(";\n");
+    out
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Syntax {
+    Json,
+    TypeScript,
+}
+
+fn render(value: &MockValue, syntax: Syntax, indent: usize, out: &mut String) {
+    match value {
+        MockValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        MockValue::Number(n) => out.push_str(&n.to_string()),
+        MockValue::Float(f) => out.push_str(&f.to_string()),
+        MockValue::BigInt(n) => match syntax {
+            Syntax::Json => out.push_str(&format!("\"{}\"", n)),
+            Syntax::TypeScript => out.push_str(&format!("{}n", n)),
+        },
+        MockValue::String(s) => out.push_str(&quote(s, syntax)),
+        MockValue::PublicKey(key) => match syntax {
+            Syntax::Json => out.push_str(&quote(key, syntax)),
+            Syntax::TypeScript => out.push_str(&format!("new PublicKey({})", quote(key, syntax))),
+        },
+        MockValue::Array(items) => render_list(items, syntax, indent, out),
+        MockValue::Option(Some(value)) => render(value, syntax, indent, out),
+        MockValue::Option(None) => out.push_str(match syntax {
+            Syntax::Json => "null",
+            Syntax::TypeScript => "undefined",
+        }),
+        MockValue::Map(entries) => match syntax {
+            Syntax::Json => {
+                let fields: Vec<_> = entries
+                    .iter()
+                    .map(|(key, value)| (key_string(key), value.clone()))
+                    .collect();
+                render(&MockValue::Object(fields), syntax, indent, out);
+            }
+            Syntax::TypeScript => {
+                let pairs: Vec<_> = entries
+                    .iter()
+                    .map(|(key, value)| MockValue::Array(vec![key.clone(), value.clone()]))
+                    .collect();
+                out.push_str("new Map(");
+                render_list(&pairs, syntax, indent, out);
+                out.push(')');
+            }
+        },
+        MockValue::Object(fields) => {
+            if fields.is_empty() {
+                out.push_str("{}");
+                return;
+            }
+            out.push_str("{\n");
+            for (index, (name, value)) in fields.iter().enumerate() {
+                out.push_str(&"  ".repeat(indent + 1));
+                match syntax {
+                    Syntax::Json => out.push_str(&quote(name, syntax)),
+                    Syntax::TypeScript => out.push_str(name),
+                }
+                out.push_str(": ");
+                render(value, syntax, indent + 1, out);
+                push_separator(index + 1 == fields.len(), syntax, out);
+            }
+            out.push_str(&"  ".repeat(indent));
+            out.push('}');
+        }
+    }
+}
+
+/// Map key as a JSON object key
+fn key_string(key: &MockValue) -> String {
+    match key {
+        MockValue::Bool(b) => b.to_string(),
+        MockValue::Number(n) => n.to_string(),
+        MockValue::BigInt(n) => n.to_string(),
+        MockValue::String(s) | MockValue::PublicKey(s) => s.clone(),
+        _ => String::new(),
+    }
+}
+
+fn render_list(items: &[MockValue], syntax: Syntax, indent: usize, out: &mut String) {
+    if items.is_empty() {
+        out.push_str("[]");
+        return;
+    }
+    out.push_str("[\n");
+    for (index, item) in items.iter().enumerate() {
+        out.push_str(&"  ".repeat(indent + 1));
+        render(item, syntax, indent + 1, out);
+        push_separator(index + 1 == items.len(), syntax, out);
+    }
+    out.push_str(&"  ".repeat(indent));
+    out.push(']');
+}
+
+/// JSON forbids trailing commas; TypeScript style keeps them
+fn push_separator(last: bool, syntax: Syntax, out: &mut String) {
+    if !last || syntax == Syntax::TypeScript {
+        out.push(',');
+    }
+    out.push('\n');
+}
+
+fn quote(s: &str, syntax: Syntax) -> String {
+    match syntax {
+        Syntax::Json => serde_json::to_string(s).expect("strings serialize"),
+        Syntax::TypeScript => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+    }
+}
+
+fn contains_public_key(value: &MockValue) -> bool {
+    match value {
+        MockValue::PublicKey(_) => true,
+        MockValue::Array(items) => items.iter().any(contains_public_key),
+        MockValue::Option(Some(value)) => contains_public_key(value),
+        MockValue::Map(entries) => entries
+            .iter()
+            .any(|(key, value)| contains_public_key(key) || contains_public_key(value)),
+        MockValue::Object(fields) => fields.iter().any(|(_, value)| contains_public_key(value)),
+        _ => false,
+    }
+}
+
+/// `UserAccount` → `userAccount`, `NFTListing` → `nftListing`
+fn lower_camel_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let upper_run = chars.iter().take_while(|c| c.is_uppercase()).count();
+    // Keep the capital that starts the next word, as in `NFTListing`
+    let lower = match upper_run {
+        n if n > 1 && n < chars.len() => n - 1,
+        n => n.max(1),
+    };
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            if i < lower {
+                c.to_ascii_lowercase()
+            } else {
+                *c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        #[solana]
+        #[account]
+        struct UserAccount {
+            wallet: PublicKey,
+            name: String,
+            created_at: i64,
+            bump: u8,
+            fee_bps: u16,
+            balance: u64,
+            total_supply: u128,
+            nickname: Option<String>,
+            tags: [String],
+            status: Status,
+        }
+
+        enum Status {
+            Active,
+            Suspended(i64),
+            Closed { reason: String },
+        }
+    "#;
+
+    fn ir() -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap()
+    }
+
+    fn field<'v>(value: &'v MockValue, name: &str) -> &'v MockValue {
+        match value {
+            MockValue::Object(fields) => &fields.iter().find(|(n, _)| n == name).unwrap().1,
+            _ => panic!("not an object: {:?}", value),
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_instances() {
+        let ir = ir();
+        let first = MockGenerator::new(&ir)
+            .with_seed(42)
+            .generate("UserAccount", 5);
+        let again = MockGenerator::new(&ir)
+            .with_seed(42)
+            .generate("UserAccount", 5);
+        let other = MockGenerator::new(&ir)
+            .with_seed(43)
+            .generate("UserAccount", 5);
+
+        assert_eq!(first, again);
+        assert_ne!(first, other);
+
+        let longer = MockGenerator::new(&ir)
+            .with_seed(42)
+            .generate("UserAccount", 8)
+            .unwrap();
+        assert_eq!(first.unwrap()[..], longer[..5]);
+    }
+
+    #[test]
+    fn field_names_steer_values() {
+        let ir = ir();
+        for user in MockGenerator::new(&ir).generate("UserAccount", 20).unwrap() {
+            let MockValue::Number(created_at) = field(&user, "created_at") else {
+                panic!("created_at is not a number");
+            };
+            assert!((TIMESTAMP_RANGE.0 as i64..TIMESTAMP_RANGE.1 as i64).contains(created_at));
+
+            let MockValue::Number(bump) = field(&user, "bump") else {
+                panic!("bump is not a number");
+            };
+            assert!((250..=255).contains(bump));
+
+            let MockValue::Number(fee) = field(&user, "fee_bps") else {
+                panic!("fee_bps is not a number");
+            };
+            assert!((0..=10_000).contains(fee));
+
+            let MockValue::String(name) = field(&user, "name") else {
+                panic!("name is not a string");
+            };
+            assert!(name.contains(' '));
+
+            let MockValue::PublicKey(wallet) = field(&user, "wallet") else {
+                panic!("wallet is not a public key");
+            };
+            assert!((32..=44).contains(&wallet.len()));
+
+            let MockValue::Object(status) = field(&user, "status") else {
+                panic!("status is not an object");
+            };
+            assert_eq!(status[0].0, "kind");
+        }
+    }
+
+    /// First value outside its field's bounds, as the generated `validate` reports it
+    fn violation(ir: &[TypeDefinition], type_name: &str, value: &MockValue) -> Option<String> {
+        let Some(TypeDefinition::Struct(s)) = ir.iter().find(|t| t.name() == type_name) else {
+            return None;
+        };
+        for definition in &s.fields {
+            let constraints = definition.constraints();
+            let location = format!("{}.{}", s.name, definition.name);
+            let value = match field(value, &definition.name) {
+                MockValue::Option(Some(inner)) => inner.as_ref(),
+                MockValue::Option(None) => continue,
+                value => value,
+            };
+            let (length, unit) = match value {
+                MockValue::String(text) => (Some(text.len() as u64), "bytes"),
+                MockValue::Array(items) => (Some(items.len() as u64), "items"),
+                _ => (None, ""),
+            };
+            if let Some(length) = length {
+                if constraints.min_len.is_some_and(|min| length < min)
+                    || constraints.max_len.is_some_and(|max| length > max)
+                {
+                    return constraints.length_violation(&location, unit);
+                }
+            }
+            let number = match value {
+                MockValue::Number(n) => Some(i128::from(*n)),
+                MockValue::BigInt(n) => Some(*n),
+                _ => None,
+            };
+            if let (Some(n), Some((min, max))) = (number, constraints.range) {
+                if !(min..=max).contains(&n) {
+                    return constraints.range_violation(&location);
+                }
+            }
+            if let TypeInfo::UserDefined(inner) = &definition.type_info {
+                if let Some(message) = violation(ir, inner, value) {
+                    return Some(message);
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn mocks_stay_within_constraints() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                r#"
+                struct Profile {
+                    #[max(10)]
+                    name: String,
+                    #[min(40)]
+                    #[max(48)]
+                    bio: String,
+                    #[max(4)]
+                    handle: Option<String>,
+                    #[min(5)]
+                    #[max(6)]
+                    tags: [u8],
+                    #[range(18, 21)]
+                    age: u8,
+                    #[range(-10, -5)]
+                    offset: i32,
+                    #[range(2000000000000, 2000000000100)]
+                    balance: u64,
+                    #[range(1, 3)]
+                    total_supply: u128,
+                    limits: Limits,
+                }
+
+                struct Limits {
+                    #[range(1, 2)]
+                    level: u16,
+                    #[min(3)]
+                    code: String,
+                }
+            "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        for seed in 0..5 {
+            for profile in MockGenerator::new(&ir)
+                .with_seed(seed)
+                .generate("Profile", 50)
+                .unwrap()
+            {
+                assert_eq!(violation(&ir, "Profile", &profile), None, "{:?}", profile);
+            }
+        }
+    }
+
+    #[test]
+    fn renders_valid_json() {
+        let ir = ir();
+        let users = MockGenerator::new(&ir).generate("UserAccount", 3).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&to_json(&users)).unwrap();
+
+        assert_eq!(json.as_array().unwrap().len(), 3);
+        assert!(json[0]["total_supply"].is_string());
+        assert!(json[0]["wallet"].is_string());
+    }
+
+    #[test]
+    fn renders_typescript_module() {
+        let ir = ir();
+        let users = MockGenerator::new(&ir).generate("UserAccount", 2).unwrap();
+        let ts = to_typescript("UserAccount", &users, "./generated", Runtime::Node);
+
+        assert!(ts.contains("import { PublicKey } from '@solana/web3.js';"));
+        assert!(ts.contains("import type { UserAccount } from './generated';"));
+        assert!(ts.contains("export const userAccountMocks: UserAccount[] = ["));
+        assert!(ts.contains("wallet: new PublicKey('"));
+        assert!(ts.contains("kind: '"));
+        assert!(ts.trim_end().ends_with("];"));
+
+        let deno = to_typescript("UserAccount", &users, "./generated", Runtime::Deno);
+        assert!(deno.contains("import { PublicKey } from 'npm:@solana/web3.js';"));
+        assert!(deno.contains("import type { UserAccount } from './generated.ts';"));
+    }
+
+    #[test]
+    fn unknown_type_is_none() {
+        assert!(MockGenerator::new(&ir()).generate("Missing", 1).is_none());
+    }
+
+    #[test]
+    fn encodes_base58() {
+        assert_eq!(encode_base58(&[0u8; 32]), "1".repeat(32));
+        assert_eq!(encode_base58(b"hello world"), "StV1DL6CwTryKyV");
+        assert_eq!(decode_base58("StV1DL6CwTryKyV").unwrap(), b"hello world");
+        assert_eq!(decode_base58(&"1".repeat(32)).unwrap(), [0u8; 32]);
+    }
+
+    #[test]
+    fn encodes_instances_as_borsh() {
+        let ir = ir();
+        let generator = MockGenerator::new(&ir).with_seed(3);
+        let user = &generator.generate("UserAccount", 1).unwrap()[0];
+        let bytes = generator.to_borsh("UserAccount", user);
+
+        let MockValue::PublicKey(wallet) = field(user, "wallet") else {
+            panic!("wallet is not a public key");
+        };
+        assert_eq!(encode_base58(&bytes[..32]), *wallet);
+
+        let MockValue::String(name) = field(user, "name") else {
+            panic!("name is not a string");
+        };
+        assert_eq!(bytes[32..36], (name.len() as u32).to_le_bytes());
+        assert_eq!(&bytes[36..36 + name.len()], name.as_bytes());
+
+        let MockValue::Number(created_at) = field(user, "created_at") else {
+            panic!("created_at is not a number");
+        };
+        let offset = 36 + name.len();
+        assert_eq!(bytes[offset..offset + 8], created_at.to_le_bytes());
+    }
+
+    #[test]
+    fn lower_camel_cases_type_names() {
+        assert_eq!(lower_camel_case("UserAccount"), "userAccount");
+        assert_eq!(lower_camel_case("NFTListing"), "nftListing");
+        assert_eq!(lower_camel_case("DAO"), "dao");
+    }
+}
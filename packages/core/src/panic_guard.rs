@@ -0,0 +1,193 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Panic-free entry points for embedding hosts
+//!
+//! The playground, editors and `lumos daemon` keep one process or WASM
+//! instance alive across many schemas, so a bug that panics in LUMOS must
+//! not take the host down with it. [`guarded`] runs an entry point and turns
+//! a panic into [`LumosError::Internal`], whose [`InternalError::bug_report`]
+//! names the entry point, the LUMOS version and a hash of the schema text:
+//! enough to match reports without the schema leaving the user's machine.
+//!
+//! On `wasm32` a panic aborts the instance instead of unwinding, unless the
+//! module is built with `-C panic=unwind`. [`record_panics`] installs a hook
+//! that keeps the report of the last panic, so the page can still show it
+//! after catching the trap and recreate the instance.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::error::LumosError;
+//! use lumos_core::panic_guard::guarded;
+//!
+//! let result: Result<(), _> = guarded("example", "struct A { x: u8 }", || panic!("boom"));
+//! let Err(LumosError::Internal(error)) = result else { unreachable!() };
+//! assert_eq!(error.message, "boom");
+//! assert!(error.bug_report().contains("Entry point: example"));
+//! ```
+
+use crate::error::{LumosError, Result};
+use crate::generators::cache::StableHasher;
+use std::any::Any;
+use std::cell::RefCell;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Mutex, Once};
+
+/// Where bugs are reported
+pub const ISSUES_URL: &str = "https://github.com/getlumos/lumos/issues/new";
+
+/// A panic caught at an entry point
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InternalError {
+    /// Entry point that panicked, e.g. `generateCode`
+    pub entry: String,
+
+    /// Panic message
+    pub message: String,
+
+    /// Stable hash of the schema text being processed
+    pub schema_hash: String,
+}
+
+impl InternalError {
+    /// Report for an issue, without the schema itself
+    pub fn bug_report(&self) -> String {
+        format!(
+            "LUMOS hit an internal error. Please report it at {}\n\
+             \n\
+             ### Bug report\n\
+             - LUMOS version: {}\n\
+             - Entry point: {}\n\
+             - Schema hash: {}\n\
+             - Panic: {}\n\
+             \n\
+             If you can share it, attach the smallest schema that still fails.",
+            ISSUES_URL,
+            env!("CARGO_PKG_VERSION"),
+            self.entry,
+            self.schema_hash,
+            self.message
+        )
+    }
+}
+
+thread_local! {
+    /// Entry point and schema hash of the guarded call in progress
+    static CURRENT: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// Report of the last panic seen by the [`record_panics`] hook
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+/// Run `entry` on `source`, turning a panic into [`LumosError::Internal`]
+///
+/// State `run` was mutating when it panicked may be left half-updated;
+/// hosts should drop caches that `run` touches when this returns an
+/// internal error.
+pub fn guarded<T>(entry: &str, source: &str, run: impl FnOnce() -> Result<T>) -> Result<T> {
+    let schema_hash = hash_source(source);
+    let previous = CURRENT.with(|current| {
+        current
+            .borrow_mut()
+            .replace((entry.to_string(), schema_hash.clone()))
+    });
+    let result = panic::catch_unwind(AssertUnwindSafe(run));
+    CURRENT.with(|current| *current.borrow_mut() = previous);
+
+    result.unwrap_or_else(|payload| {
+        Err(LumosError::Internal(Box::new(InternalError {
+            entry: entry.to_string(),
+            message: panic_message(payload.as_ref()),
+            schema_hash,
+        })))
+    })
+}
+
+/// Keep a bug report for every panic, for [`last_panic_report`]
+///
+/// Installed once; the previous hook still runs afterwards.
+pub fn record_panics() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let (entry, schema_hash) = CURRENT
+                .with(|current| current.borrow().clone())
+                .unwrap_or_else(|| ("unknown".to_string(), hash_source("")));
+            let mut message = panic_message(info.payload());
+            if let Some(location) = info.location() {
+                message.push_str(&format!(" ({}:{})", location.file(), location.line()));
+            }
+            let report = InternalError {
+                entry,
+                message,
+                schema_hash,
+            }
+            .bug_report();
+            if let Ok(mut last) = LAST_PANIC.lock() {
+                *last = Some(report);
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// Bug report of the last panic recorded since [`record_panics`]
+pub fn last_panic_report() -> Option<String> {
+    LAST_PANIC.lock().ok().and_then(|last| last.clone())
+}
+
+/// Stable hex hash of a schema's text
+fn hash_source(source: &str) -> String {
+    let mut hasher = StableHasher::default();
+    source.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "panic with a non-string payload".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_and_records_panics() {
+        let ok = guarded("compile", "struct A { x: u8 }", || Ok(1));
+        assert_eq!(ok.unwrap(), 1);
+
+        let error = guarded::<()>("compile", "struct A { x: u8 }", || {
+            panic!("index {} out of range", 3)
+        })
+        .unwrap_err();
+        let LumosError::Internal(internal) = &error else {
+            panic!("expected an internal error, got {:?}", error)
+        };
+        assert_eq!(internal.message, "index 3 out of range");
+        assert_eq!(internal.schema_hash, hash_source("struct A { x: u8 }"));
+        assert_ne!(internal.schema_hash, hash_source("struct B { x: u8 }"));
+        assert!(error.to_string().contains("internal error in compile"));
+
+        let report = internal.bug_report();
+        assert!(report.contains(&format!("LUMOS version: {}", env!("CARGO_PKG_VERSION"))));
+        assert!(report.contains(&format!("Schema hash: {}", internal.schema_hash)));
+        assert!(!report.contains("struct A"));
+
+        // The hook keeps the report, with the panic's location
+        record_panics();
+        let _ = guarded::<()>("generateCode", "struct A { x: u8 }", || panic!("recorded"));
+        let report = last_panic_report().unwrap();
+        assert!(report.contains("Entry point: generateCode"));
+        assert!(report.contains("Panic: recorded (") && report.contains("panic_guard.rs:"));
+    }
+}
@@ -12,9 +12,14 @@
 //!
 //! - Struct definitions with `#[account]`, `#[solana]` attributes
 //! - Enum definitions (unit, tuple, and struct variants)
+//! - Constant definitions (`const MAX_LEN: u32 = 32;`)
 //! - Field types (primitives, arrays, options, user-defined)
 //! - Attribute parsing (`#[max(n)]`, `#[key]`, etc.)
 //!
+//! With the `fast-parser` feature, [`parse_lumos_file`](crate::parser::parse_lumos_file)
+//! uses the hand-written parser in [`crate::fast_parser`] instead, which
+//! builds the same AST.
+//!
 //! ## Example
 //!
 //! ```rust
@@ -34,11 +39,15 @@
 //! ```
 
 use crate::ast::{
-    Attribute, AttributeValue, EnumDef, EnumVariant, FieldDef, Item as AstItem, LumosFile,
-    StructDef, TypeSpec,
+    Attribute, AttributeValue, ConstDef, EnumDef, EnumVariant, FieldDef, Import, Item as AstItem,
+    LumosFile, SchemaMetadata, StructDef, TypeSpec,
 };
-use crate::error::{LumosError, Result};
-use syn::{Item, Meta, Type};
+use crate::error::{LumosError, Result, SourceLocation};
+use quote::ToTokens;
+use std::borrow::Cow;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, Item, Lit, Meta, MetaNameValue, Token, Type};
 
 /// Parse a `.lumos` file into an Abstract Syntax Tree.
 ///
@@ -58,6 +67,11 @@ use syn::{Item, Meta, Type};
 ///
 /// - **Structs**: `struct Name { field: Type, ... }`
 /// - **Enums**: `enum Name { Variant, Variant(Type), Variant { field: Type } }`
+/// - **Constants**: `const NAME: u32 = 32;` (unsigned integer types only)
+/// - **Imports**: `import "tokens.lumos";`, recorded in [`LumosFile::imports`]
+///   and loaded by [`crate::resolver`]
+/// - **Schema block**: `schema { name = "staking", version = "1.2.0" }` before
+///   the first item, recorded in [`LumosFile::metadata`]
 /// - **Attributes**: `#[solana]`, `#[account]`, `#[max(n)]`, `#[key]`
 /// - **Types**: Primitives (`u64`, `String`), Solana types (`PublicKey`), arrays `[T]`, `Option<T>`
 ///
@@ -92,14 +106,32 @@ use syn::{Item, Meta, Type};
 ///
 /// Returns [`LumosError::SchemaParse`] if:
 /// - Syntax is invalid (not valid Rust-style code)
-/// - No struct or enum definitions found
+/// - No struct or enum definitions found, and nothing is imported
 /// - Unsupported type syntax encountered
 pub fn parse_lumos_file(input: &str) -> Result<LumosFile> {
+    parse_source(input)
+}
+
+#[cfg(feature = "fast-parser")]
+use crate::fast_parser::parse_lumos_file as parse_source;
+#[cfg(not(feature = "fast-parser"))]
+use parse_with_syn as parse_source;
+
+/// Parse a `.lumos` file with `syn`
+///
+/// This is what [`parse_lumos_file`] uses unless the `fast-parser` feature
+/// is enabled; it stays available to compare the two parsers against.
+pub fn parse_with_syn(input: &str) -> Result<LumosFile> {
     let mut items = Vec::new();
+    let (imports, input) = extract_imports(input)?;
 
     // Parse the file as Rust code using syn
-    let file = syn::parse_file(input).map_err(|e| {
-        LumosError::SchemaParse(format!("Failed to parse .lumos file: {}", e), None)
+    let file = syn::parse_str::<Source>(input.trim_start_matches('\u{feff}')).map_err(|e| {
+        let start = e.span().start();
+        LumosError::SchemaParse(
+            format!("Failed to parse .lumos file: {}", e),
+            Some(SourceLocation::new(start.line, start.column + 1)),
+        )
     })?;
 
     // Extract struct and enum definitions
@@ -113,26 +145,222 @@ pub fn parse_lumos_file(input: &str) -> Result<LumosFile> {
                 let enum_def = parse_enum(item_enum)?;
                 items.push(AstItem::Enum(enum_def));
             }
+            Item::Const(item_const) => {
+                let const_def = parse_const(item_const)?;
+                items.push(AstItem::Const(const_def));
+            }
             _ => {
                 // Ignore other items (functions, impls, etc.)
             }
         }
     }
 
-    if items.is_empty() {
+    lumos_file(file.metadata, imports, items)
+}
+
+/// Assemble a parsed file, which must define a type or import one
+pub(crate) fn lumos_file(
+    metadata: Option<SchemaMetadata>,
+    imports: Vec<Import>,
+    items: Vec<AstItem>,
+) -> Result<LumosFile> {
+    if imports.is_empty() && items.iter().all(|item| matches!(item, AstItem::Const(_))) {
         return Err(LumosError::SchemaParse(
             "No type definitions found in .lumos file".to_string(),
             None,
         ));
     }
 
-    Ok(LumosFile { items })
+    Ok(LumosFile {
+        metadata,
+        imports,
+        items,
+        origins: Default::default(),
+    })
+}
+
+/// Keys of the `schema { ... }` block
+pub(crate) const SCHEMA_KEYS: [&str; 5] = ["name", "version", "description", "license", "authors"];
+
+/// A `.lumos` file as `syn` sees it: Rust items, optionally preceded by a
+/// `schema { ... }` block
+struct Source {
+    metadata: Option<SchemaMetadata>,
+    items: Vec<Item>,
+}
+
+impl Parse for Source {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.call(syn::Attribute::parse_inner)?;
+
+        let is_schema_block = input.peek(syn::Ident)
+            && input.peek2(syn::token::Brace)
+            && input.fork().parse::<syn::Ident>()? == "schema";
+        let metadata = match is_schema_block {
+            true => Some(parse_schema_block(input)?),
+            false => None,
+        };
+
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            items.push(input.parse()?);
+        }
+        Ok(Self { metadata, items })
+    }
+}
+
+/// Parse `schema { name = "...", version = "...", authors = ["..."], ... }`
+fn parse_schema_block(input: ParseStream) -> syn::Result<SchemaMetadata> {
+    let keyword: syn::Ident = input.parse()?;
+    let content;
+    syn::braced!(content in input);
+    let entries = Punctuated::<MetaNameValue, Token![,]>::parse_terminated(&content)?;
+
+    let mut metadata = SchemaMetadata::default();
+    let mut seen = Vec::new();
+    for entry in &entries {
+        let key = entry
+            .path
+            .get_ident()
+            .map(|ident| ident.to_string())
+            .filter(|key| SCHEMA_KEYS.contains(&key.as_str()))
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    &entry.path,
+                    format!(
+                        "unknown schema key (expected one of {})",
+                        SCHEMA_KEYS.join(", ")
+                    ),
+                )
+            })?;
+        if seen.contains(&key) {
+            return Err(syn::Error::new_spanned(
+                &entry.path,
+                format!("duplicate schema key `{}`", key),
+            ));
+        }
+        match key.as_str() {
+            "name" => metadata.name = string_literal(&entry.value)?,
+            "version" => metadata.version = Some(string_literal(&entry.value)?),
+            "description" => metadata.description = Some(string_literal(&entry.value)?),
+            "license" => metadata.license = Some(string_literal(&entry.value)?),
+            _ => {
+                let Expr::Array(authors) = &entry.value else {
+                    return Err(syn::Error::new_spanned(
+                        &entry.value,
+                        "`authors` must be a list of strings",
+                    ));
+                };
+                metadata.authors = authors
+                    .elems
+                    .iter()
+                    .map(string_literal)
+                    .collect::<syn::Result<_>>()?;
+            }
+        }
+        seen.push(key);
+    }
+
+    if metadata.name.is_empty() {
+        return Err(syn::Error::new_spanned(
+            keyword,
+            "schema block needs a non-empty `name`",
+        ));
+    }
+    Ok(metadata)
+}
+
+fn string_literal(expr: &Expr) -> syn::Result<String> {
+    match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(text),
+            ..
+        }) => Ok(text.value()),
+        _ => Err(syn::Error::new_spanned(expr, "expected a string literal")),
+    }
 }
 
+/// Path of `import "path";`, optionally followed by a `//` comment
+fn import_path(line: &str) -> Option<String> {
+    let statement = line.trim().strip_prefix("import")?.trim_start();
+    let (path, tail) = statement.strip_prefix('"')?.split_once('"')?;
+    let comment = tail.trim_start().strip_prefix(';')?.trim();
+    (!path.is_empty() && (comment.is_empty() || comment.starts_with("//")))
+        .then(|| path.to_string())
+}
+
+/// Take `import "path";` statements out of `input`
+///
+/// They aren't Rust syntax, so each is replaced by an empty line before
+/// `syn` sees the source, keeping the line numbers of everything else.
+pub(crate) fn extract_imports(input: &str) -> Result<(Vec<Import>, Cow<'_, str>)> {
+    let is_import = |line: &str| {
+        line.trim_start()
+            .strip_prefix("import")
+            .is_some_and(|rest| rest.trim_start().starts_with('"'))
+    };
+    if !input.lines().any(is_import) {
+        return Ok((Vec::new(), Cow::Borrowed(input)));
+    }
+
+    let mut imports = Vec::new();
+    let mut rest = String::with_capacity(input.len());
+    for (index, line) in input.lines().enumerate() {
+        if is_import(line) {
+            let Some(path) = import_path(line) else {
+                return Err(LumosError::SchemaParse(
+                    "expected `import \"file.lumos\";`".to_string(),
+                    Some(SourceLocation::new(index + 1, 1)),
+                ));
+            };
+            imports.push(Import {
+                path,
+                line: index + 1,
+            });
+        } else {
+            rest.push_str(line);
+        }
+        rest.push('\n');
+    }
+    Ok((imports, Cow::Owned(rest)))
+}
+
+/// Parse a constant definition; its value is folded during transform
+fn parse_const(item: syn::ItemConst) -> Result<ConstDef> {
+    let name = item.ident.to_string();
+    let type_name = match item.ty.as_ref() {
+        Type::Path(type_path) => type_path
+            .path
+            .get_ident()
+            .map(|ident| ident.to_string())
+            .filter(|ty| CONST_TYPES.contains(&ty.as_str())),
+        _ => None,
+    };
+    let Some(type_name) = type_name else {
+        return Err(LumosError::SchemaParse(
+            format!(
+                "const '{}' must have an unsigned integer type (u8, u16, u32 or u64)",
+                name
+            ),
+            None,
+        ));
+    };
+
+    Ok(ConstDef {
+        span: Some(item.ident.span().into()),
+        name,
+        type_name,
+        value: item.expr.to_token_stream().to_string(),
+    })
+}
+
+/// Types a `const` item may declare
+pub(crate) const CONST_TYPES: [&str; 4] = ["u8", "u16", "u32", "u64"];
+
 /// Parse a struct definition
 fn parse_struct(item: syn::ItemStruct) -> Result<StructDef> {
     let name = item.ident.to_string();
-    let span = Some(item.ident.span());
+    let span = Some(item.ident.span().into());
 
     // Extract attributes
     let attributes = parse_attributes(&item.attrs)?;
@@ -166,7 +394,7 @@ fn parse_struct(item: syn::ItemStruct) -> Result<StructDef> {
 /// Parse an enum definition
 fn parse_enum(item: syn::ItemEnum) -> Result<EnumDef> {
     let name = item.ident.to_string();
-    let span = Some(item.ident.span());
+    let span = Some(item.ident.span().into());
 
     // Extract attributes
     let attributes = parse_attributes(&item.attrs)?;
@@ -196,7 +424,7 @@ fn parse_enum(item: syn::ItemEnum) -> Result<EnumDef> {
 /// Parse an enum variant
 fn parse_enum_variant(variant: syn::Variant) -> Result<EnumVariant> {
     let name = variant.ident.to_string();
-    let span = Some(variant.ident.span());
+    let span = Some(variant.ident.span().into());
 
     match variant.fields {
         // Unit variant: `Active`
@@ -232,7 +460,7 @@ fn parse_field(field: syn::Field) -> Result<FieldDef> {
         .ok_or_else(|| LumosError::SchemaParse("Field must have a name".to_string(), None))?
         .to_string();
 
-    let span = field.ident.as_ref().map(|i| i.span());
+    let span = field.ident.as_ref().map(|i| i.span().into());
 
     // Extract field attributes
     let attributes = parse_attributes(&field.attrs)?;
@@ -264,7 +492,7 @@ fn parse_attributes(attrs: &[syn::Attribute]) -> Result<Vec<Attribute>> {
                     attributes.push(Attribute {
                         name: ident.to_string(),
                         value: None,
-                        span: Some(ident.span()),
+                        span: Some(ident.span().into()),
                     });
                 }
             }
@@ -283,13 +511,23 @@ fn parse_attributes(attrs: &[syn::Attribute]) -> Result<Vec<Attribute>> {
                 attributes.push(Attribute {
                     name,
                     value: Some(value),
-                    span: Some(meta_list.path.get_ident().unwrap().span()),
+                    span: Some(meta_list.path.get_ident().unwrap().span().into()),
                 });
             }
 
             // Name-value attribute: #[key = "value"]
-            Meta::NameValue(_) => {
-                // Not commonly used in LUMOS, but we could support it
+            // Doc comments (`/// text`) arrive as #[doc = "text"], one per line
+            Meta::NameValue(name_value) => {
+                let is_doc = name_value.path.is_ident("doc");
+                if let (true, syn::Expr::Lit(expr)) = (is_doc, &name_value.value) {
+                    if let syn::Lit::Str(text) = &expr.lit {
+                        attributes.push(Attribute {
+                            name: "doc".to_string(),
+                            value: Some(AttributeValue::String(text.value().trim().to_string())),
+                            span: Some(text.span().into()),
+                        });
+                    }
+                }
             }
         }
     }
@@ -298,7 +536,7 @@ fn parse_attributes(attrs: &[syn::Attribute]) -> Result<Vec<Attribute>> {
 }
 
 /// Parse attribute value from token stream
-fn parse_attribute_value(tokens: &str) -> Result<AttributeValue> {
+pub(crate) fn parse_attribute_value(tokens: &str) -> Result<AttributeValue> {
     let tokens_trimmed = tokens.trim();
 
     // Try parsing as integer
@@ -343,26 +581,90 @@ fn parse_type(ty: &Type) -> Result<(TypeSpec, bool)> {
                 if let Some(segment) = type_path.path.segments.last() {
                     if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
                         if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
-                            let (inner_type_spec, _) = parse_type(inner_ty)?;
+                            let inner_type_spec = parse_nested_type(inner_ty)?;
                             return Ok((inner_type_spec, true)); // optional = true
                         }
                     }
                 }
             }
 
+            // Vec<T>, the Rust spelling of [T]
+            if type_name == "Vec" {
+                let segment = type_path.path.segments.last();
+                if let Some(syn::PathArguments::AngleBracketed(args)) =
+                    segment.map(|s| &s.arguments)
+                {
+                    if args.args.len() > 1 {
+                        return Err(LumosError::SchemaParse(
+                            format!("Vec takes a single element type, found {}", args.args.len()),
+                            None,
+                        ));
+                    }
+                    if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
+                        let inner_type_spec = parse_nested_type(inner_ty)?;
+                        return Ok((TypeSpec::Array(Box::new(inner_type_spec)), false));
+                    }
+                }
+                return Err(LumosError::SchemaParse(
+                    "Vec needs an element type, e.g. Vec<u64>".to_string(),
+                    None,
+                ));
+            }
+
+            // HashMap<K, V> and BTreeMap<K, V>
+            if type_name == "HashMap" || type_name == "BTreeMap" {
+                let segment = type_path.path.segments.last();
+                if let Some(syn::PathArguments::AngleBracketed(args)) =
+                    segment.map(|s| &s.arguments)
+                {
+                    if let [syn::GenericArgument::Type(key_ty), syn::GenericArgument::Type(value_ty)] =
+                        args.args.iter().collect::<Vec<_>>()[..]
+                    {
+                        let key = parse_nested_type(key_ty)?;
+                        let value = parse_nested_type(value_ty)?;
+                        return Ok((
+                            TypeSpec::Map(type_name, Box::new(key), Box::new(value)),
+                            false,
+                        ));
+                    }
+                }
+                return Err(LumosError::SchemaParse(
+                    format!(
+                        "{} needs key and value types, e.g. {}<PublicKey, u64>",
+                        type_name, type_name
+                    ),
+                    None,
+                ));
+            }
+
             // Regular type
             Ok((TypeSpec::Primitive(type_name), false))
         }
 
-        // Array type: [T]
+        // Fixed-size array type: [T; N]
         Type::Array(type_array) => {
-            let (inner_type_spec, _) = parse_type(&type_array.elem)?;
-            Ok((TypeSpec::Array(Box::new(inner_type_spec)), false))
+            let inner_type_spec = parse_nested_type(&type_array.elem)?;
+            let len = match &type_array.len {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(len),
+                    ..
+                }) => len.base10_parse::<usize>().ok(),
+                _ => None,
+            }
+            .filter(|&len| len > 0)
+            .ok_or_else(|| {
+                LumosError::SchemaParse(
+                    "Fixed array length must be a positive integer literal, e.g. [u8; 32]"
+                        .to_string(),
+                    None,
+                )
+            })?;
+            Ok((TypeSpec::FixedArray(Box::new(inner_type_spec), len), false))
         }
 
         // Slice type: [T] (also treated as array)
         Type::Slice(type_slice) => {
-            let (inner_type_spec, _) = parse_type(&type_slice.elem)?;
+            let inner_type_spec = parse_nested_type(&type_slice.elem)?;
             Ok((TypeSpec::Array(Box::new(inner_type_spec)), false))
         }
 
@@ -373,6 +675,16 @@ fn parse_type(ty: &Type) -> Result<(TypeSpec, bool)> {
     }
 }
 
+/// Parse a type nested in another one, keeping its `Option` as [`TypeSpec::Option`]
+fn parse_nested_type(ty: &Type) -> Result<TypeSpec> {
+    let (type_spec, optional) = parse_type(ty)?;
+    if optional {
+        Ok(TypeSpec::Option(Box::new(type_spec)))
+    } else {
+        Ok(type_spec)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -403,6 +715,108 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_imports() {
+        let source = "import \"tokens.lumos\";\n  import \"ids.lumos\"; // ids\n\nstruct A {\n    x: Bad,\n}\n";
+        let file = parse_lumos_file(source).unwrap();
+        let paths: Vec<&str> = file.imports.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, ["tokens.lumos", "ids.lumos"]);
+        assert_eq!(file.imports[1].line, 2);
+        assert_eq!(file.items.len(), 1);
+
+        // A file of imports alone is a valid index
+        assert!(parse_lumos_file("import \"a.lumos\";\n").is_ok());
+
+        let error = parse_lumos_file("struct A { x: u8 }\nimport \"a.lumos\"\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected `import \"file.lumos\";` at line 2, column 1"
+        );
+    }
+
+    #[test]
+    fn test_parse_schema_block() {
+        let source = r#"
+            import "tokens.lumos";
+
+            schema {
+                name = "staking",
+                version = "1.2.0",
+                license = "MIT OR Apache-2.0",
+                authors = ["Ada <ada@example.com>", "Grace"],
+            }
+
+            struct Pool { staked: u64 }
+        "#;
+        let file = parse_lumos_file(source).unwrap();
+        let metadata = file.metadata.unwrap();
+        assert_eq!(metadata.name, "staking");
+        assert_eq!(metadata.version.as_deref(), Some("1.2.0"));
+        assert_eq!(metadata.description, None);
+        assert_eq!(metadata.authors, ["Ada <ada@example.com>", "Grace"]);
+        assert_eq!(file.items.len(), 1);
+
+        assert!(parse_lumos_file("struct Pool { staked: u64 }")
+            .unwrap()
+            .metadata
+            .is_none());
+
+        let error =
+            parse_lumos_file("schema {\n    name = \"a\",\n    owner = \"b\",\n}\nstruct A {}")
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to parse .lumos file: unknown schema key (expected one of name, version, \
+             description, license, authors) at line 3, column 5"
+        );
+        for (source, message) in [
+            ("schema { version = \"1\" }", "needs a non-empty `name`"),
+            (
+                "schema { name = \"a\", name = \"b\" }",
+                "duplicate schema key `name`",
+            ),
+            ("schema { name = 1 }", "expected a string literal"),
+            (
+                "schema { name = \"a\", authors = \"b\" }",
+                "must be a list of strings",
+            ),
+            // The block must come before the first item
+            ("struct A {}\nschema { name = \"a\" }", "expected"),
+        ] {
+            let error = parse_lumos_file(&format!("{}\nstruct B {{}}", source)).unwrap_err();
+            assert!(error.to_string().contains(message), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_parse_field_doc_comments() {
+        let input = r#"
+            struct Vault {
+                /// Signer allowed to withdraw.
+                /// Set once at initialization.
+                authority: PublicKey,
+            }
+        "#;
+
+        let file = parse_lumos_file(input).unwrap();
+        match &file.items[0] {
+            AstItem::Struct(struct_def) => {
+                let docs: Vec<_> = struct_def.fields[0]
+                    .attributes
+                    .iter()
+                    .filter(|attr| attr.name == "doc")
+                    .map(|attr| attr.value.clone())
+                    .collect();
+                assert_eq!(docs.len(), 2);
+                assert!(matches!(
+                    &docs[0],
+                    Some(AttributeValue::String(line)) if line == "Signer allowed to withdraw."
+                ));
+            }
+            _ => panic!("Expected struct item"),
+        }
+    }
+
     #[test]
     fn test_parse_with_attributes() {
         let input = r#"
@@ -473,4 +887,134 @@ mod tests {
             _ => panic!("Expected struct item"),
         }
     }
+
+    #[test]
+    fn test_parse_vec_type() {
+        let input = r#"
+            struct Team {
+                members: Vec<PublicKey>,
+                slices: [PublicKey],
+                grid: Vec<Vec<u8>>,
+                scores: Option<Vec<u64>>,
+            }
+        "#;
+
+        let file = parse_lumos_file(input).unwrap();
+        match &file.items[0] {
+            AstItem::Struct(struct_def) => {
+                let fields = &struct_def.fields;
+                assert!(fields[0].type_spec.is_array());
+                assert_eq!(
+                    format!("{:?}", fields[0].type_spec),
+                    format!("{:?}", fields[1].type_spec)
+                );
+                assert!(matches!(
+                    &fields[2].type_spec,
+                    TypeSpec::Array(inner) if inner.is_array()
+                ));
+                assert!(fields[3].type_spec.is_array() && fields[3].optional);
+            }
+            _ => panic!("Expected struct item"),
+        }
+
+        let err = parse_lumos_file("struct Team { members: Vec }").unwrap_err();
+        assert!(err.to_string().contains("Vec needs an element type"));
+
+        let err = parse_lumos_file("struct Team { members: Vec<u8, u16> }").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Vec takes a single element type, found 2"));
+    }
+
+    #[test]
+    fn test_parse_nested_option_type() {
+        let file = parse_lumos_file(
+            "struct Votes { ballots: Vec<Option<u32>>, raw: [Option<PublicKey>], maybe: Option<Option<u8>> }",
+        )
+        .unwrap();
+        match &file.items[0] {
+            AstItem::Struct(struct_def) => {
+                let fields = &struct_def.fields;
+                assert_eq!(fields[0].type_spec.to_string(), "[Option<u32>]");
+                assert!(!fields[0].optional);
+                assert_eq!(fields[1].type_spec.to_string(), "[Option<PublicKey>]");
+                assert_eq!(fields[2].type_spec.to_string(), "Option<u8>");
+                assert!(fields[2].optional);
+            }
+            _ => panic!("Expected struct item"),
+        }
+    }
+
+    #[test]
+    fn test_parse_fixed_array_type() {
+        let input = r#"
+            struct Seeds {
+                hash: [u8; 32],
+                grid: [[u16; 4]; 2],
+            }
+        "#;
+
+        let file = parse_lumos_file(input).unwrap();
+        match &file.items[0] {
+            AstItem::Struct(struct_def) => {
+                assert_eq!(struct_def.fields[0].type_spec.to_string(), "[u8; 32]");
+                assert_eq!(struct_def.fields[1].type_spec.to_string(), "[[u16; 4]; 2]");
+                assert!(!struct_def.fields[0].type_spec.is_array());
+            }
+            _ => panic!("Expected struct item"),
+        }
+
+        let file = parse_lumos_file("struct Slots { taken: [Option<u8>; 3] }").unwrap();
+        match &file.items[0] {
+            AstItem::Struct(struct_def) => {
+                assert_eq!(
+                    struct_def.fields[0].type_spec.to_string(),
+                    "[Option<u8>; 3]"
+                );
+                assert!(!struct_def.fields[0].optional);
+            }
+            _ => panic!("Expected struct item"),
+        }
+
+        for input in ["struct S { x: [u8; N] }", "struct S { x: [u8; 0] }"] {
+            let err = parse_lumos_file(input).unwrap_err();
+            assert!(err.to_string().contains("positive integer literal"));
+        }
+    }
+
+    #[test]
+    fn test_parse_map_types() {
+        let file = parse_lumos_file(
+            "struct Pool { stakes: HashMap<PublicKey, u64>, tiers: BTreeMap<u8, [String]> }",
+        )
+        .unwrap();
+        match &file.items[0] {
+            AstItem::Struct(struct_def) => {
+                assert_eq!(
+                    struct_def.fields[0].type_spec.to_string(),
+                    "HashMap<PublicKey, u64>"
+                );
+                assert_eq!(
+                    struct_def.fields[1].type_spec.to_string(),
+                    "BTreeMap<u8, [String]>"
+                );
+            }
+            _ => panic!("Expected struct item"),
+        }
+
+        let file =
+            parse_lumos_file("struct Pool { stakes: HashMap<PublicKey, Option<u64>> }").unwrap();
+        match &file.items[0] {
+            AstItem::Struct(struct_def) => assert_eq!(
+                struct_def.fields[0].type_spec.to_string(),
+                "HashMap<PublicKey, Option<u64>>"
+            ),
+            _ => panic!("Expected struct item"),
+        }
+
+        let err = parse_lumos_file("struct Pool { stakes: HashMap<u64> }").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("HashMap needs key and value types"));
+    }
 }
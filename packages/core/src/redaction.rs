@@ -0,0 +1,283 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Name redaction of schemas shared in bug reports
+//!
+//! A schema that triggers a bug is the best reproduction, but its type and
+//! field names can say more about an unreleased program than its author
+//! wants to publish. [`Redactor`] renames every type, field, variant and
+//! constant the schema defines (`Type1`, `field_1`, `Variant1`, `CONST_1`)
+//! and drops comments and doc comments. Attributes, types, layouts and
+//! literals are kept, so the redacted schema still takes the same path
+//! through LUMOS.
+//!
+//! Names are shared across files, so a redacted schema's imports still line
+//! up, and [`Redactor::redact_text`] applies the same renaming to command
+//! output or settings that mention them.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::redaction::Redactor;
+//!
+//! let source = "/// Player state\nstruct Player { wallet: PublicKey, #[max(16)] name: String }";
+//! let redactor = Redactor::new(&[source])?;
+//!
+//! assert_eq!(
+//!     redactor.redact(source)?,
+//!     "struct Type1 { field_1: PublicKey, #[max(16)] field_2: String }"
+//! );
+//! assert_eq!(redactor.redact_text("error in Player.name"), "error in Type1.field_2");
+//! # Ok::<(), lumos_core::error::LumosError>(())
+//! ```
+
+use crate::ast::{EnumVariant, FieldDef, Item};
+use crate::error::Result;
+use crate::lexer::{is_ident_continue, Kind, Lexer};
+use crate::parser::parse_lumos_file;
+use std::collections::HashMap;
+
+/// Consistent renaming of the names a set of schema files defines
+#[derive(Debug, Default)]
+pub struct Redactor {
+    /// Original name to its replacement
+    names: HashMap<String, String>,
+
+    /// Names given out per prefix
+    counts: HashMap<&'static str, usize>,
+}
+
+impl Redactor {
+    /// Collect the names defined by every file of a schema
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::LumosError::SchemaParse`] if a file doesn't parse.
+    pub fn new(sources: &[&str]) -> Result<Self> {
+        let mut redactor = Self::default();
+        let files = sources
+            .iter()
+            .map(|source| parse_lumos_file(source))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Types and constants first, so their numbering doesn't depend on fields
+        for item in files.iter().flat_map(|file| &file.items) {
+            match item {
+                Item::Struct(s) => redactor.rename(&s.name, "Type"),
+                Item::Enum(e) => redactor.rename(&e.name, "Type"),
+                Item::Const(c) => redactor.rename(&c.name, "CONST_"),
+            }
+        }
+        for item in files.iter().flat_map(|file| &file.items) {
+            match item {
+                Item::Struct(s) => redactor.rename_fields(&s.fields),
+                Item::Enum(e) => {
+                    for variant in &e.variants {
+                        let (name, fields) = match variant {
+                            EnumVariant::Unit { name, .. } | EnumVariant::Tuple { name, .. } => {
+                                (name, &[][..])
+                            }
+                            EnumVariant::Struct { name, fields, .. } => (name, &fields[..]),
+                        };
+                        redactor.rename(name, "Variant");
+                        redactor.rename_fields(fields);
+                    }
+                }
+                Item::Const(_) => {}
+            }
+        }
+        Ok(redactor)
+    }
+
+    /// `source` with the collected names replaced and comments removed
+    ///
+    /// Names in the `schema { ... }` block and attribute names are left
+    /// alone, so a field called `version` doesn't rename `#[version(2)]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::error::LumosError::SchemaParse`] if `source` doesn't tokenize.
+    pub fn redact(&self, source: &str) -> Result<String> {
+        let tokens = Lexer::new(source).with_comments().tokenize()?;
+        let chars: Vec<char> = source.chars().collect();
+
+        // Token ranges to replace, in source order
+        let mut edits: Vec<(std::ops::Range<usize>, &str)> = Vec::new();
+        let mut skip_until = 0;
+        let mut attribute_end = 0;
+        for (i, token) in tokens.iter().enumerate() {
+            if i < skip_until {
+                continue;
+            }
+            let next = tokens.get(i + 1).map(|token| &token.kind);
+            match &token.kind {
+                Kind::Doc(..) | Kind::Comment(_) => edits.push((token.range.clone(), "")),
+                Kind::Ident(name) if name == "schema" && i < attribute_end => {}
+                Kind::Ident(name) if name == "schema" => {
+                    if let Some(Kind::Open('{', close)) = next {
+                        skip_until = close + 1;
+                    }
+                }
+                Kind::Punct('#', _) => {
+                    if let Some(Kind::Open('[', close)) = next {
+                        attribute_end = *close;
+                    }
+                }
+                Kind::Ident(name) => {
+                    let in_attribute = i < attribute_end;
+                    // Inside attributes, only types and constants are references
+                    let renamed = self.names.get(name).filter(|renamed| {
+                        !in_attribute
+                            || renamed.starts_with("Type")
+                            || renamed.starts_with("CONST_")
+                    });
+                    if let Some(renamed) = renamed {
+                        edits.push((token.range.clone(), renamed));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut output = String::new();
+        let mut pos = 0;
+        for (mut range, replacement) in edits {
+            if replacement.is_empty() {
+                // Take the comment's indentation with it, and its line if
+                // nothing else is on it
+                while range.start > pos && matches!(chars[range.start - 1], ' ' | '\t') {
+                    range.start -= 1;
+                }
+                let rest = chars[range.end..]
+                    .iter()
+                    .take_while(|c| **c != '\n')
+                    .count();
+                let line_start = range.start == 0 || chars[range.start - 1] == '\n';
+                if chars[range.end..range.end + rest]
+                    .iter()
+                    .all(|c| c.is_whitespace())
+                {
+                    range.end += rest;
+                    if line_start && range.end < chars.len() {
+                        range.end += 1;
+                    }
+                }
+            }
+            output.extend(&chars[pos..range.start]);
+            output.push_str(replacement);
+            pos = range.end;
+        }
+        output.extend(&chars[pos..]);
+        Ok(output)
+    }
+
+    /// `text` with every collected name replaced where it appears as a whole word
+    pub fn redact_text(&self, text: &str) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut word = String::new();
+        for c in text.chars().chain(std::iter::once('\0')) {
+            if is_ident_continue(c) {
+                word.push(c);
+                continue;
+            }
+            match self.names.get(&word) {
+                Some(renamed) => output.push_str(renamed),
+                None => output.push_str(&word),
+            }
+            word.clear();
+            if c != '\0' {
+                output.push(c);
+            }
+        }
+        output
+    }
+
+    fn rename(&mut self, name: &str, prefix: &'static str) {
+        if self.names.contains_key(name) {
+            return;
+        }
+        let count = self.counts.entry(prefix).or_default();
+        *count += 1;
+        self.names
+            .insert(name.to_string(), format!("{}{}", prefix, count));
+    }
+
+    fn rename_fields(&mut self, fields: &[FieldDef]) {
+        for field in fields {
+            self.rename(&field.name, "field_");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::transform_to_ir;
+
+    #[test]
+    fn redacted_schemas_keep_their_layout() {
+        let tokens = r#"
+            // Shared amounts
+            const MAX_HOLDERS: u32 = 8;
+
+            /// A token amount
+            #[solana]
+            struct TokenAmount { raw: u64 }
+        "#;
+        let vault = r#"import "tokens.lumos";
+
+            schema {
+                name = "vaults",
+                version = "1.0.0",
+            }
+
+            #[solana]
+            #[account]
+            #[version(2)]
+            struct Vault {
+                balance: TokenAmount, // in lamports
+                #[max(MAX_HOLDERS)]
+                holders: Vec<PublicKey>,
+                #[since(2)]
+                version: Option<Status>,
+            }
+
+            #[solana]
+            enum Status { Open, Closed { at: i64 } }
+        "#;
+        let redactor = Redactor::new(&[tokens, vault]).unwrap();
+        let tokens = redactor.redact(tokens).unwrap();
+        let vault = redactor.redact(vault).unwrap();
+
+        assert!(tokens.contains("const CONST_1: u32 = 8;"));
+        assert!(tokens.contains("struct Type1 { field_1: u64 }"));
+        assert!(!tokens.contains("//"));
+        assert!(vault.starts_with("import \"tokens.lumos\";"));
+        assert!(vault.contains("version = \"1.0.0\","));
+        assert!(vault.contains("#[version(2)]"));
+        assert!(vault.contains("field_2: Type1,\n"));
+        assert!(vault.contains("#[max(CONST_1)]"));
+        assert!(vault.contains("enum Type3 { Variant1, Variant2 { field_5: i64 } }"));
+        for name in [
+            "Vault",
+            "TokenAmount",
+            "MAX_HOLDERS",
+            "balance",
+            "holders",
+            "lamports",
+        ] {
+            assert!(!tokens.contains(name) && !vault.contains(name), "{}", name);
+        }
+
+        // The redacted files still make the same schema, up to names
+        let mut ast = parse_lumos_file(&tokens).unwrap();
+        ast.items.extend(parse_lumos_file(&vault).unwrap().items);
+        let ir = transform_to_ir(ast).unwrap();
+        assert_eq!(ir.len(), 3);
+
+        assert_eq!(
+            redactor.redact_text("Vault.holders exceeds MAX_HOLDERS (Vaults)"),
+            "Type2.field_3 exceeds CONST_1 (Vaults)"
+        );
+    }
+}
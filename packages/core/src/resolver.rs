@@ -0,0 +1,304 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Multi-file schemas joined with `import` statements
+//!
+//! A schema can import other files, relative to its own directory:
+//!
+//! ```text
+//! import "tokens.lumos";
+//!
+//! #[solana]
+//! #[account]
+//! struct Vault { balance: TokenAmount }
+//! ```
+//!
+//! [`load`] reads a schema and everything it imports, directly or through
+//! other files, and merges them into one [`LumosFile`] ready for
+//! [`crate::transform::transform_to_ir`]. Imported items come before the
+//! importing file's own, and a file imported twice is only read once. Import
+//! cycles and types or constants defined in more than one file are errors.
+//! The merged file keeps the `schema { ... }` block of the schema passed to
+//! [`load`]; imported files' blocks are ignored.
+
+use crate::ast::{Item, LumosFile, SchemaMetadata};
+use crate::error::{LumosError, Result};
+use crate::parser::parse_lumos_file;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Read the schema at `path` with everything it imports
+///
+/// # Errors
+///
+/// Returns [`LumosError::SchemaParse`] if a file can't be read or parsed, if
+/// imports form a cycle, or if a type or constant name is defined in more
+/// than one file.
+pub fn load(path: &Path) -> Result<LumosFile> {
+    let mut loader = Loader::default();
+    loader.visit(path, None)?;
+    Ok(LumosFile {
+        metadata: loader.metadata,
+        imports: Vec::new(),
+        items: loader.items,
+        origins: loader.origins,
+    })
+}
+
+/// The schema at `path` and every file it imports, directly or through other files
+///
+/// Unlike [`load`], this never fails: files that are missing or don't parse
+/// are listed without their imports. Watch mode uses it to know which files
+/// a schema depends on even while one of them is broken.
+pub fn dependencies(path: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(file) = pending.pop() {
+        if !seen.insert(file.canonicalize().unwrap_or_else(|_| file.clone())) {
+            continue;
+        }
+        let imports = fs::read_to_string(&file)
+            .ok()
+            .and_then(|source| parse_lumos_file(&source).ok())
+            .map(|parsed| parsed.imports)
+            .unwrap_or_default();
+        let dir = file.parent().unwrap_or_else(|| Path::new(""));
+        pending.extend(imports.iter().rev().map(|import| dir.join(&import.path)));
+        files.push(file);
+    }
+    files
+}
+
+#[derive(Default)]
+struct Loader {
+    /// Files being loaded, importer first
+    stack: Vec<PathBuf>,
+
+    /// Files already merged
+    loaded: HashSet<PathBuf>,
+
+    /// `schema { ... }` block of the loaded schema
+    metadata: Option<SchemaMetadata>,
+
+    /// Merged items
+    items: Vec<Item>,
+
+    /// File defining each type and constant name
+    origins: HashMap<String, PathBuf>,
+}
+
+impl Loader {
+    fn visit(&mut self, path: &Path, importer: Option<&Path>) -> Result<()> {
+        let failed = |message: String| match importer {
+            Some(importer) => LumosError::SchemaParse(
+                format!("{} (imported by {})", message, importer.display()),
+                None,
+            ),
+            None => LumosError::SchemaParse(message, None),
+        };
+
+        let key = path
+            .canonicalize()
+            .map_err(|e| failed(format!("Failed to read {}: {}", path.display(), e)))?;
+        if let Some(start) = self.stack.iter().position(|p| *p == key) {
+            let cycle: Vec<String> = self.stack[start..]
+                .iter()
+                .chain([&key])
+                .map(|p| file_name(p))
+                .collect();
+            return Err(LumosError::SchemaParse(
+                format!("Import cycle: {}", cycle.join(" -> ")),
+                None,
+            ));
+        }
+        if self.loaded.contains(&key) {
+            return Ok(());
+        }
+
+        let source = fs::read_to_string(path)
+            .map_err(|e| failed(format!("Failed to read {}: {}", path.display(), e)))?;
+        let file = parse_lumos_file(&source).map_err(|e| match (e, importer) {
+            (LumosError::SchemaParse(message, Some(location)), Some(importer)) => {
+                LumosError::SchemaParse(
+                    format!("{} (imported by {})", message, importer.display()),
+                    Some(location.in_file(path)),
+                )
+            }
+            (e, Some(_)) => failed(format!("{}: {}", path.display(), e)),
+            (e, None) => e.in_file(path),
+        })?;
+
+        if importer.is_none() {
+            self.metadata = file.metadata;
+        }
+
+        self.stack.push(key.clone());
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        for import in &file.imports {
+            self.visit(&dir.join(&import.path), Some(path))?;
+        }
+        self.stack.pop();
+
+        for item in file.items {
+            let name = item.name();
+            if let Some(origin) = self.origins.get(name) {
+                return Err(LumosError::SchemaParse(
+                    format!(
+                        "'{}' is defined in both {} and {}",
+                        name,
+                        origin.display(),
+                        path.display()
+                    ),
+                    item.span().map(|span| span.location(Some(path))),
+                ));
+            }
+            self.origins.insert(name.to_string(), path.to_path_buf());
+            self.items.push(item);
+        }
+        self.loaded.insert(key);
+        Ok(())
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::transform_to_ir;
+
+    fn write(dir: &Path, name: &str, source: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn merges_imports_once_in_dependency_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        write(
+            dir,
+            "common/ids.lumos",
+            "const MAX: u32 = 4;\nstruct Id { raw: u64 }\n",
+        );
+        write(
+            dir,
+            "common/tokens.lumos",
+            "import \"ids.lumos\";\nstruct Token { id: Id, #[max(MAX)] tags: Vec<u8> }\n",
+        );
+        let root = write(
+            dir,
+            "schema.lumos",
+            "import \"common/tokens.lumos\"; // shared types\nimport \"common/ids.lumos\";\n\nstruct Vault { token: Token, owner: Id }\n",
+        );
+
+        let file = load(&root).unwrap();
+        assert!(file.imports.is_empty());
+        let ir = transform_to_ir(file).unwrap();
+        let names: Vec<&str> = ir.iter().map(|t| t.name()).collect();
+        assert_eq!(names, ["Id", "Token", "Vault"]);
+
+        assert_eq!(
+            dependencies(&root),
+            [
+                root.clone(),
+                dir.join("common/tokens.lumos"),
+                dir.join("common/ids.lumos"),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_schema_block_of_loaded_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        write(
+            dir,
+            "tokens.lumos",
+            "schema { name = \"tokens\" }\nstruct Token { raw: u64 }\n",
+        );
+        let root = write(
+            dir,
+            "schema.lumos",
+            "import \"tokens.lumos\";\nschema { name = \"vault\" }\nstruct Vault { token: Token }\n",
+        );
+
+        assert_eq!(load(&root).unwrap().metadata.unwrap().name, "vault");
+        assert_eq!(
+            load(&dir.join("tokens.lumos"))
+                .unwrap()
+                .metadata
+                .unwrap()
+                .name,
+            "tokens"
+        );
+    }
+
+    #[test]
+    fn locates_errors_in_imported_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let tokens = write(
+            dir,
+            "tokens.lumos",
+            "struct Token {\n    raw: u64\n    id: u8,\n}\n",
+        );
+        let root = write(
+            dir,
+            "schema.lumos",
+            "import \"tokens.lumos\";\nstruct Vault {\n    token: Tokn,\n}\n",
+        );
+
+        let error = load(&root).unwrap_err();
+        let location = error.location().unwrap();
+        assert_eq!((location.line, location.column), (3, 5));
+        assert_eq!(location.file.as_deref(), Some(tokens.as_path()));
+        assert!(error.to_string().contains("(imported by "), "{}", error);
+
+        write(dir, "tokens.lumos", "struct Token { raw: u64 }\n");
+        let error = transform_to_ir(load(&root).unwrap()).unwrap_err();
+        let location = error.location().unwrap();
+        assert_eq!((location.line, location.column), (3, 5));
+        assert_eq!(location.file.as_deref(), Some(root.as_path()));
+    }
+
+    #[test]
+    fn rejects_cycles_duplicates_and_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        write(dir, "a.lumos", "import \"b.lumos\";\nstruct A { x: u8 }\n");
+        write(dir, "b.lumos", "import \"a.lumos\";\nstruct B { x: u8 }\n");
+        let error = load(&dir.join("a.lumos")).unwrap_err().to_string();
+        assert_eq!(error, "Import cycle: a.lumos -> b.lumos -> a.lumos");
+        assert_eq!(
+            dependencies(&dir.join("a.lumos")),
+            [dir.join("a.lumos"), dir.join("b.lumos")]
+        );
+
+        write(dir, "one.lumos", "struct Shared { x: u8 }\n");
+        let two = write(
+            dir,
+            "two.lumos",
+            "import \"one.lumos\";\nstruct Shared { y: u8 }\n",
+        );
+        let error = load(&two).unwrap_err().to_string();
+        assert!(error.contains("'Shared' is defined in both"), "{}", error);
+
+        let missing = write(dir, "missing.lumos", "import \"nope.lumos\";\n");
+        assert_eq!(
+            dependencies(&missing),
+            [missing.clone(), dir.join("nope.lumos")]
+        );
+        let error = load(&missing).unwrap_err().to_string();
+        assert!(error.contains("nope.lumos"), "{}", error);
+        assert!(error.contains("(imported by"), "{}", error);
+    }
+}
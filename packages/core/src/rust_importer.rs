@@ -0,0 +1,524 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Rust source import
+//!
+//! Derives a `.lumos` schema from the state structs of an existing program, so
+//! projects can adopt LUMOS without transcribing their accounts by hand. Every
+//! `#[account]` struct is imported, together with the structs and enums it
+//! references that are defined in the same source. Doc comments carry over,
+//! and Anchor's `#[max_len(n)]` becomes `#[max(n)]`.
+//!
+//! Types the schema language cannot express are rejected with an error, so an
+//! imported schema never silently describes a different layout. The one
+//! exception is arrays whose length is a constant rather than a literal,
+//! which are imported as dynamic arrays and reported as warnings.
+
+use crate::anchor_accounts::has_account_attribute;
+use crate::error::{LumosError, Result};
+use quote::ToTokens;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+/// Result of importing Rust source
+#[derive(Debug, Clone)]
+pub struct ImportedSchema {
+    /// Generated `.lumos` source
+    pub source: String,
+
+    /// Names of the imported types, in source order
+    pub types: Vec<String>,
+
+    /// Differences between the Rust types and the imported schema
+    pub warnings: Vec<String>,
+}
+
+/// Struct or enum found in the Rust source
+enum RustItem {
+    Struct(syn::ItemStruct),
+    Enum(syn::ItemEnum),
+}
+
+impl RustItem {
+    fn attrs(&self) -> &[syn::Attribute] {
+        match self {
+            RustItem::Struct(item) => &item.attrs,
+            RustItem::Enum(item) => &item.attrs,
+        }
+    }
+
+    fn generics(&self) -> &syn::Generics {
+        match self {
+            RustItem::Struct(item) => &item.generics,
+            RustItem::Enum(item) => &item.generics,
+        }
+    }
+
+    fn is_account(&self) -> bool {
+        matches!(self, RustItem::Struct(item) if has_account_attribute(&item.attrs))
+    }
+
+    /// Field types, including those of enum variants
+    fn field_types(&self) -> Vec<&syn::Type> {
+        match self {
+            RustItem::Struct(item) => item.fields.iter().map(|field| &field.ty).collect(),
+            RustItem::Enum(item) => item
+                .variants
+                .iter()
+                .flat_map(|variant| variant.fields.iter().map(|field| &field.ty))
+                .collect(),
+        }
+    }
+}
+
+/// Import the `#[account]` structs of a Rust source file as a `.lumos` schema
+///
+/// # Errors
+///
+/// Returns [`LumosError::SchemaParse`] if the source is not valid Rust, has no
+/// `#[account]` structs, or an imported type uses a type the schema language
+/// cannot express (generics, tuples, references, maps, ...).
+pub fn import_rust(source: &str) -> Result<ImportedSchema> {
+    let file = syn::parse_file(source).map_err(|e| {
+        LumosError::SchemaParse(format!("Failed to parse Rust source: {}", e), None)
+    })?;
+
+    let mut items = Vec::new();
+    collect(&file.items, &mut items);
+
+    let names: HashMap<String, usize> = items
+        .iter()
+        .enumerate()
+        .map(|(index, (name, _))| (name.clone(), index))
+        .collect();
+
+    // Accounts and everything they reference within the source
+    let mut selected = BTreeSet::new();
+    let mut pending: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, item))| item.is_account())
+        .map(|(index, _)| index)
+        .collect();
+    if pending.is_empty() {
+        return Err(LumosError::SchemaParse(
+            "No #[account] structs found in Rust source".to_string(),
+            None,
+        ));
+    }
+
+    let mut external = BTreeSet::new();
+    while let Some(index) = pending.pop() {
+        if !selected.insert(index) {
+            continue;
+        }
+        for ty in items[index].1.field_types() {
+            let mut referenced = Vec::new();
+            referenced_types(ty, &mut referenced);
+            for name in referenced {
+                match names.get(&name) {
+                    Some(&index) => pending.push(index),
+                    None => {
+                        external.insert(name);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut importer = Importer::default();
+    for name in &external {
+        importer.warnings.push(format!(
+            "Type '{}' is not defined in the source; add it to the schema",
+            name
+        ));
+    }
+    for &index in &selected {
+        let (name, item) = &items[index];
+        importer.item(name, item)?;
+    }
+
+    Ok(ImportedSchema {
+        source: importer.output,
+        types: selected
+            .iter()
+            .map(|&index| items[index].0.clone())
+            .collect(),
+        warnings: importer.warnings,
+    })
+}
+
+/// Collect structs and enums, descending into inline modules
+fn collect(items: &[syn::Item], out: &mut Vec<(String, RustItem)>) {
+    for item in items {
+        match item {
+            syn::Item::Struct(item) => {
+                out.push((item.ident.to_string(), RustItem::Struct(item.clone())));
+            }
+            syn::Item::Enum(item) => {
+                out.push((item.ident.to_string(), RustItem::Enum(item.clone())));
+            }
+            syn::Item::Mod(module) => {
+                if let Some((_, items)) = &module.content {
+                    collect(items, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Names of non-primitive types referenced by a Rust type
+fn referenced_types(ty: &syn::Type, out: &mut Vec<String>) {
+    match ty {
+        syn::Type::Path(path) => {
+            let Some(segment) = path.path.segments.last() else {
+                return;
+            };
+            match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => {
+                    for arg in &args.args {
+                        if let syn::GenericArgument::Type(inner) = arg {
+                            referenced_types(inner, out);
+                        }
+                    }
+                }
+                _ if primitive(&segment.ident.to_string()).is_none() => {
+                    out.push(segment.ident.to_string());
+                }
+                _ => {}
+            }
+        }
+        syn::Type::Array(array) => referenced_types(&array.elem, out),
+        _ => {}
+    }
+}
+
+/// Schema name of a Rust primitive or Solana type
+pub(crate) fn primitive(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "u8" => "u8",
+        "u16" => "u16",
+        "u32" => "u32",
+        "u64" => "u64",
+        "u128" => "u128",
+        "i8" => "i8",
+        "i16" => "i16",
+        "i32" => "i32",
+        "i64" => "i64",
+        "i128" => "i128",
+        "f32" => "f32",
+        "f64" => "f64",
+        "bool" => "bool",
+        "String" => "String",
+        "Pubkey" | "PublicKey" => "PublicKey",
+        "Signature" => "Signature",
+        _ => return None,
+    })
+}
+
+/// Writes `.lumos` source for the selected items
+#[derive(Default)]
+struct Importer {
+    output: String,
+    warnings: Vec<String>,
+}
+
+impl Importer {
+    fn item(&mut self, name: &str, item: &RustItem) -> Result<()> {
+        if !item.generics().params.is_empty() {
+            return Err(LumosError::SchemaParse(
+                format!("Cannot import {}: generic types are not supported", name),
+                None,
+            ));
+        }
+
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        write_docs(&mut self.output, item.attrs(), "");
+        self.output.push_str("#[solana]\n");
+        if item.is_account() {
+            self.output.push_str("#[account]\n");
+        }
+
+        match item {
+            RustItem::Struct(item) => {
+                let syn::Fields::Named(fields) = &item.fields else {
+                    return Err(LumosError::SchemaParse(
+                        format!("Cannot import {}: struct must have named fields", name),
+                        None,
+                    ));
+                };
+                let _ = writeln!(self.output, "struct {} {{", name);
+                self.fields(name, fields, "    ")?;
+                self.output.push_str("}\n");
+            }
+            RustItem::Enum(item) => {
+                let _ = writeln!(self.output, "enum {} {{", name);
+                for variant in &item.variants {
+                    let path = format!("{}::{}", name, variant.ident);
+                    if variant.discriminant.is_some() {
+                        self.warnings.push(format!(
+                            "{}: explicit discriminant dropped; variants are numbered by position",
+                            path
+                        ));
+                    }
+                    write_docs(&mut self.output, &variant.attrs, "    ");
+                    match &variant.fields {
+                        syn::Fields::Unit => {
+                            let _ = writeln!(self.output, "    {},", variant.ident);
+                        }
+                        syn::Fields::Unnamed(fields) => {
+                            let types = fields
+                                .unnamed
+                                .iter()
+                                .map(|field| self.field_type(&path, &field.ty))
+                                .collect::<Result<Vec<_>>>()?;
+                            let _ = writeln!(
+                                self.output,
+                                "    {}({}),",
+                                variant.ident,
+                                types.join(", ")
+                            );
+                        }
+                        syn::Fields::Named(fields) => {
+                            let _ = writeln!(self.output, "    {} {{", variant.ident);
+                            self.fields(&path, fields, "        ")?;
+                            self.output.push_str("    },\n");
+                        }
+                    }
+                }
+                self.output.push_str("}\n");
+            }
+        }
+        Ok(())
+    }
+
+    fn fields(&mut self, owner: &str, fields: &syn::FieldsNamed, indent: &str) -> Result<()> {
+        for field in &fields.named {
+            let Some(ident) = &field.ident else {
+                continue;
+            };
+            let path = format!("{}.{}", owner, ident);
+            let ty = self.field_type(&path, &field.ty)?;
+
+            write_docs(&mut self.output, &field.attrs, indent);
+            if let Some(max) = max_len(&field.attrs) {
+                let _ = writeln!(self.output, "{}#[max({})]", indent, max);
+            }
+            let _ = writeln!(self.output, "{}{}: {},", indent, ident, ty);
+        }
+        Ok(())
+    }
+
+    /// Schema syntax for a Rust field type
+    fn field_type(&mut self, path: &str, ty: &syn::Type) -> Result<String> {
+        let type_text = || ty.to_token_stream().to_string();
+        match ty {
+            syn::Type::Path(type_path) if type_path.qself.is_none() => {
+                let Some(segment) = type_path.path.segments.last() else {
+                    return Err(unsupported(path, &type_text()));
+                };
+                let name = segment.ident.to_string();
+                match &segment.arguments {
+                    syn::PathArguments::None => {
+                        Ok(primitive(&name).map(str::to_string).unwrap_or(name))
+                    }
+                    syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                        let Some(syn::GenericArgument::Type(inner)) = args.args.first() else {
+                            return Err(unsupported(path, &type_text()));
+                        };
+                        match name.as_str() {
+                            "Vec" => Ok(format!("[{}]", self.field_type(path, inner)?)),
+                            "Option" => Ok(format!("Option<{}>", self.field_type(path, inner)?)),
+                            // Borsh serializes a box as its contents
+                            "Box" => self.field_type(path, inner),
+                            _ => Err(unsupported(path, &type_text())),
+                        }
+                    }
+                    _ => Err(unsupported(path, &type_text())),
+                }
+            }
+            syn::Type::Array(array) => {
+                let elements = self.field_type(path, &array.elem)?;
+                match &array.len {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Int(len),
+                        ..
+                    }) if len.base10_parse::<usize>().is_ok_and(|len| len > 0) => {
+                        Ok(format!("[{}; {}]", elements, len.base10_digits()))
+                    }
+                    len => {
+                        self.warnings.push(format!(
+                            "{}: array `[{}; {}]` has no literal length; imported as a dynamic array, which adds a 4-byte length prefix",
+                            path,
+                            array.elem.to_token_stream(),
+                            len.to_token_stream()
+                        ));
+                        Ok(format!("[{}]", elements))
+                    }
+                }
+            }
+            _ => Err(unsupported(path, &type_text())),
+        }
+    }
+}
+
+fn unsupported(path: &str, what: &str) -> LumosError {
+    LumosError::SchemaParse(
+        format!("Cannot import {}: unsupported type `{}`", path, what),
+        None,
+    )
+}
+
+/// Copy `///` doc comments
+fn write_docs(output: &mut String, attrs: &[syn::Attribute], indent: &str) {
+    for attr in attrs {
+        let syn::Meta::NameValue(name_value) = &attr.meta else {
+            continue;
+        };
+        if !name_value.path.is_ident("doc") {
+            continue;
+        }
+        if let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(text),
+            ..
+        }) = &name_value.value
+        {
+            let _ = writeln!(output, "{}/// {}", indent, text.value().trim());
+        }
+    }
+}
+
+/// Value of Anchor's `#[max_len(n)]`, the first length for nested vectors
+fn max_len(attrs: &[syn::Attribute]) -> Option<u64> {
+    let list = attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("max_len"))?
+        .meta
+        .require_list()
+        .ok()?;
+    let lengths = list
+        .parse_args_with(
+            syn::punctuated::Punctuated::<syn::LitInt, syn::Token![,]>::parse_terminated,
+        )
+        .ok()?;
+    lengths.first()?.base10_parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{TypeDefinition, TypeInfo};
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const STATE: &str = r#"
+        use anchor_lang::prelude::*;
+
+        pub mod state {
+            use super::*;
+
+            /// A registered player
+            #[account]
+            #[derive(InitSpace)]
+            pub struct Player {
+                pub authority: Pubkey,
+                /// Display name
+                #[max_len(32)]
+                pub name: String,
+                pub class: Class,
+                pub guild: Option<Pubkey>,
+                #[max_len(10)]
+                pub items: Vec<Item>,
+                pub bump: u8,
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+        pub struct Item {
+            pub id: u64,
+            pub power: Box<u16>,
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+        pub enum Class {
+            Warrior,
+            Mage { mana: u32 },
+            Rogue(u8, i64),
+        }
+
+        #[derive(Accounts)]
+        pub struct CreatePlayer<'info> {
+            #[account(mut)]
+            pub authority: Signer<'info>,
+        }
+
+        pub struct Unrelated {
+            pub map: std::collections::HashMap<u8, u8>,
+        }
+    "#;
+
+    fn round_trip(source: &str) -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn imports_accounts_and_referenced_types() {
+        let imported = import_rust(STATE).unwrap();
+        assert_eq!(imported.types, ["Player", "Item", "Class"]);
+        assert!(imported.warnings.is_empty(), "{:?}", imported.warnings);
+        assert!(imported
+            .source
+            .contains("/// A registered player\n#[solana]\n#[account]\nstruct Player {"));
+        assert!(imported
+            .source
+            .contains("    /// Display name\n    #[max(32)]\n    name: String,"));
+        assert!(imported.source.contains("items: [Item],"));
+        assert!(imported.source.contains("Rogue(u8, i64),"));
+        assert!(!imported.source.contains("Unrelated"));
+
+        let ir = round_trip(&imported.source);
+        let TypeDefinition::Struct(player) = &ir[0] else {
+            panic!("expected struct");
+        };
+        assert!(player.metadata.attributes.contains(&"account".to_string()));
+        assert!(
+            matches!(&player.fields[0].type_info, TypeInfo::Primitive(name) if name == "PublicKey")
+        );
+        assert!(matches!(&player.fields[3].type_info, TypeInfo::Option(_)));
+        assert!(matches!(&ir[2], TypeDefinition::Enum(_)));
+    }
+
+    #[test]
+    fn warns_about_layout_changes() {
+        let imported = import_rust(
+            r#"
+            #[account]
+            pub struct Vault {
+                pub seed: [u8; 32],
+                pub slots: [u64; SLOTS],
+                pub mint: Mint,
+            }
+
+            pub enum Mint { A = 1 }
+            "#,
+        )
+        .unwrap();
+        assert!(imported.source.contains("seed: [u8; 32],"));
+        assert!(imported.source.contains("slots: [u64],"));
+        assert_eq!(imported.warnings.len(), 2);
+        assert!(imported.warnings[0].contains("Vault.slots"));
+        assert!(imported.warnings[1].contains("Mint::A"));
+    }
+
+    #[test]
+    fn rejects_inexpressible_types() {
+        let error = import_rust("#[account] pub struct Pool { pub pair: (u64, u64) }").unwrap_err();
+        assert!(error.to_string().contains("Pool.pair"));
+
+        assert!(import_rust("#[account] pub struct Wrapper<T> { pub inner: T }").is_err());
+        assert!(import_rust("pub struct NotAnAccount { pub x: u8 }").is_err());
+    }
+}
@@ -0,0 +1,304 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Decoding sampled on-chain accounts against the schema
+//!
+//! `lumos audit sample` fetches real account data for each `#[account]`
+//! type and decodes it here, byte for byte, the way the generated Borsh code
+//! would. Decode failures and trailing non-zero bytes are layout drift the
+//! schema does not know about; the spread of data lengths shows how accounts
+//! are actually allocated compared with the calculated size.
+//!
+//! Accounts are matched by their Anchor discriminator, the first 8 bytes of
+//! `sha256("account:<Name>")`.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::sampling::{discriminator, AccountSample};
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "#[solana]\n#[account]\nstruct Counter { count: u64 }",
+//! )?)?;
+//!
+//! let mut data = discriminator("Counter").to_vec();
+//! data.extend_from_slice(&7u64.to_le_bytes());
+//!
+//! let mut sample = AccountSample::new(&ir, "Counter");
+//! sample.add("Counter1111", &data);
+//! assert!(sample.failures.is_empty());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::borsh_decoder::BorshDecoder;
+use crate::ir::{anchor_discriminator, TypeDefinition, TypeInfo};
+use crate::size_calculator::{SizeCalculator, SizeInfo};
+use std::collections::BTreeMap;
+
+pub use crate::borsh_decoder::DecodeError;
+
+/// Anchor account discriminator size in bytes
+const DISCRIMINATOR: usize = 8;
+
+/// Anchor discriminator of the account type `name`
+pub fn discriminator(name: &str) -> [u8; 8] {
+    anchor_discriminator("account", name)
+}
+
+/// An account that failed to decode
+#[derive(Debug, Clone)]
+pub struct DecodeFailure {
+    /// Account address
+    pub address: String,
+
+    /// First error found
+    pub error: DecodeError,
+}
+
+/// Decoding results for the sampled accounts of one type
+#[derive(Debug, Clone)]
+pub struct AccountSample<'a> {
+    /// Account type
+    pub name: String,
+
+    /// Size calculated from the schema, discriminator included
+    pub expected: SizeInfo,
+
+    /// Number of accounts added
+    pub sampled: usize,
+
+    /// Accounts that failed to decode
+    pub failures: Vec<DecodeFailure>,
+
+    /// Addresses of decoded accounts with non-zero bytes after the data
+    pub trailing: Vec<String>,
+
+    /// Number of accounts by data length in bytes
+    pub sizes: BTreeMap<usize, usize>,
+
+    decoder: AccountDecoder<'a>,
+}
+
+impl<'a> AccountSample<'a> {
+    /// Start sampling the account type `name`
+    pub fn new(type_defs: &'a [TypeDefinition], name: &str) -> Self {
+        let expected = SizeCalculator::new(type_defs)
+            .account_size(name)
+            .map_or(SizeInfo::Fixed(DISCRIMINATOR), |size| {
+                size.total_bytes.clone()
+            });
+
+        Self {
+            name: name.to_string(),
+            expected,
+            sampled: 0,
+            failures: Vec::new(),
+            trailing: Vec::new(),
+            sizes: BTreeMap::new(),
+            decoder: AccountDecoder::new(type_defs),
+        }
+    }
+
+    /// Decode the data of the account at `address` and record the outcome
+    pub fn add(&mut self, address: &str, data: &[u8]) {
+        self.sampled += 1;
+        *self.sizes.entry(data.len()).or_default() += 1;
+
+        match self.decoder.decode(&self.name, data) {
+            Ok(end) if data[end..].iter().any(|&b| b != 0) => {
+                self.trailing.push(address.to_string())
+            }
+            Ok(_) => {}
+            Err(error) => self.failures.push(DecodeFailure {
+                address: address.to_string(),
+                error,
+            }),
+        }
+    }
+
+    /// Share of sampled accounts that failed to decode, from 0.0 to 1.0
+    pub fn failure_rate(&self) -> f64 {
+        if self.sampled == 0 {
+            0.0
+        } else {
+            self.failures.len() as f64 / self.sampled as f64
+        }
+    }
+
+    /// Smallest, median and largest data length, if anything was sampled
+    pub fn size_range(&self) -> Option<(usize, usize, usize)> {
+        let min = *self.sizes.keys().next()?;
+        let max = *self.sizes.keys().next_back()?;
+
+        let middle = self.sampled / 2;
+        let mut seen = 0;
+        let median = self
+            .sizes
+            .iter()
+            .find(|(_, &count)| {
+                seen += count;
+                seen > middle
+            })
+            .map_or(max, |(&size, _)| size);
+
+        Some((min, median, max))
+    }
+}
+
+/// Decoder for account data laid out as the schema describes
+#[derive(Debug, Clone, Copy)]
+pub struct AccountDecoder<'a> {
+    decoder: BorshDecoder<'a>,
+}
+
+impl<'a> AccountDecoder<'a> {
+    /// Create a decoder for the schema's types
+    pub fn new(type_defs: &'a [TypeDefinition]) -> Self {
+        Self {
+            decoder: BorshDecoder::new(type_defs),
+        }
+    }
+
+    /// Decode an account of type `name`, returning where its data ends
+    ///
+    /// Bytes after the end are allocated space the layout does not use.
+    pub fn decode(&self, name: &str, data: &[u8]) -> Result<usize, DecodeError> {
+        if !data.starts_with(&discriminator(name)) {
+            return Err(DecodeError {
+                offset: 0,
+                path: name.to_string(),
+                message: "discriminator does not match".to_string(),
+            });
+        }
+        let type_info = TypeInfo::UserDefined(name.to_string());
+        let (_, end) = self.decoder.decode(&type_info, data, DISCRIMINATOR, name)?;
+        Ok(end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::borsh_decoder::sha256;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn ir() -> Vec<TypeDefinition> {
+        transform_to_ir(
+            parse_lumos_file(
+                r#"
+                #[solana]
+                #[account]
+                struct Vault {
+                    owner: PublicKey,
+                    active: bool,
+                    label: String,
+                    status: Status,
+                    limits: [u16],
+                    closed_at: Option<i64>,
+                }
+
+                #[solana]
+                enum Status { Open, Frozen { until: i64 } }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn vault(label: &[u8], status: &[u8]) -> Vec<u8> {
+        let mut data = discriminator("Vault").to_vec();
+        data.extend_from_slice(&[7; 32]);
+        data.push(1);
+        data.extend_from_slice(&(label.len() as u32).to_le_bytes());
+        data.extend_from_slice(label);
+        data.extend_from_slice(status);
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&[1, 0, 2, 0]);
+        data.push(0);
+        data
+    }
+
+    #[test]
+    fn computes_anchor_discriminators() {
+        // sha256("account:NewAccount")[..8], as emitted by Anchor
+        assert_eq!(
+            discriminator("NewAccount"),
+            [176, 95, 4, 118, 91, 177, 125, 232]
+        );
+        assert_eq!(
+            sha256(b"")[..4],
+            [0xe3, 0xb0, 0xc4, 0x42],
+            "sha256 of the empty string"
+        );
+    }
+
+    #[test]
+    fn decodes_valid_accounts_up_to_their_padding() {
+        let ir = ir();
+        let mut data = vault(b"main", &[1, 5, 0, 0, 0, 0, 0, 0, 0]);
+        let end = data.len();
+        data.extend_from_slice(&[0; 16]);
+
+        assert_eq!(AccountDecoder::new(&ir).decode("Vault", &data), Ok(end));
+    }
+
+    #[test]
+    fn reports_where_decoding_fails() {
+        let ir = ir();
+        let decoder = AccountDecoder::new(&ir);
+
+        let err = decoder.decode("Vault", &vault(b"main", &[2])).unwrap_err();
+        assert_eq!(err.path, "Vault.status");
+        assert_eq!(err.offset, 49);
+        assert_eq!(err.message, "variant tag 2 is out of range (2 variants)");
+
+        let err = decoder
+            .decode("Vault", &vault(&[0xff, 0xfe], &[0]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Vault.label at byte 45: string is not valid UTF-8"
+        );
+
+        let mut data = vault(b"x", &[0]);
+        data.truncate(data.len() - 3);
+        let err = decoder.decode("Vault", &data).unwrap_err();
+        assert_eq!(err.path, "Vault.limits[1]");
+
+        let err = decoder.decode("Vault", &[0; 64]).unwrap_err();
+        assert_eq!(
+            (err.offset, err.message.as_str()),
+            (0, "discriminator does not match")
+        );
+    }
+
+    #[test]
+    fn summarizes_samples() {
+        let ir = ir();
+        let mut sample = AccountSample::new(&ir, "Vault");
+        assert!(!sample.expected.is_fixed());
+
+        let good = vault(b"main", &[0]);
+        let mut padded = good.clone();
+        padded.extend_from_slice(&[0; 8]);
+        let mut trailing = good.clone();
+        trailing.extend_from_slice(&[9; 8]);
+
+        sample.add("A", &good);
+        sample.add("B", &padded);
+        sample.add("C", &trailing);
+        sample.add("D", &vault(b"main", &[3]));
+
+        assert_eq!(sample.sampled, 4);
+        assert_eq!(sample.failure_rate(), 0.25);
+        assert_eq!(sample.failures[0].address, "D");
+        assert_eq!(sample.trailing, ["C"]);
+        assert_eq!(
+            sample.size_range(),
+            Some((good.len(), good.len() + 8, good.len() + 8))
+        );
+    }
+}
@@ -0,0 +1,439 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Schema diff with Borsh compatibility (`lumos diff`)
+//!
+//! Borsh data carries no names or tags: struct fields are read in order and
+//! enum variants by position. [`diff`] lists what changed between two
+//! schemas and marks the changes that leave data written under the old
+//! schema unreadable, or generated clients without a type they used.
+//!
+//! A field appended to the end of a struct only keeps existing accounts
+//! readable when it is an `Option`, the same rule as
+//! [`crate::evolution::check_append_only`].
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::schema_diff::{diff, ChangeKind};
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = |source| transform::transform_to_ir(parser::parse_lumos_file(source)?);
+//! let old = ir("struct Player { wallet: PublicKey, level: u16 }")?;
+//! let new = ir("struct Player { level: u16, wallet: PublicKey }")?;
+//!
+//! let changes = diff(&old, &new);
+//! assert_eq!(changes[0].kind, ChangeKind::FieldsReordered);
+//! assert!(changes[0].breaking);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::evolution::signature;
+use crate::ir::{
+    EnumDefinition, EnumVariantDefinition, StructDefinition, TypeDefinition, TypeInfo,
+};
+use serde::Serialize;
+use std::fmt;
+
+/// What kind of change a [`SchemaChange`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// A type was added
+    TypeAdded,
+
+    /// A type was removed
+    TypeRemoved,
+
+    /// A type changed between struct and enum
+    KindChanged,
+
+    /// A struct field was added
+    FieldAdded,
+
+    /// A struct field was removed
+    FieldRemoved,
+
+    /// A struct field changed type
+    FieldTypeChanged,
+
+    /// Fields on both sides are in a different order
+    FieldsReordered,
+
+    /// An enum variant was appended
+    VariantAdded,
+
+    /// An enum variant was removed from the end
+    VariantRemoved,
+
+    /// Another variant now has this variant's position
+    VariantReplaced,
+
+    /// An enum variant's payload changed
+    VariantChanged,
+}
+
+/// One difference between two schemas
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaChange {
+    /// What changed
+    pub kind: ChangeKind,
+
+    /// `Type`, `Type.field` or `Enum::Variant`
+    pub location: String,
+
+    /// Before the change: a type, variant payload, kind or field order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<String>,
+
+    /// After the change, in the same notation as `old`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<String>,
+
+    /// Whether data or clients from the old schema break
+    pub breaking: bool,
+
+    /// Why the change breaks, when it does
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl SchemaChange {
+    fn new(kind: ChangeKind, location: String) -> Self {
+        Self {
+            kind,
+            location,
+            old: None,
+            new: None,
+            breaking: false,
+            reason: None,
+        }
+    }
+
+    fn old(mut self, old: String) -> Self {
+        self.old = Some(old);
+        self
+    }
+
+    fn new_value(mut self, new: String) -> Self {
+        self.new = Some(new);
+        self
+    }
+
+    fn breaking(mut self, reason: &str) -> Self {
+        self.breaking = true;
+        self.reason = Some(reason.to_string());
+        self
+    }
+}
+
+impl fmt::Display for SchemaChange {
+    /// One line, e.g. `~ Player.level: u16 → u32 (breaking: its encoding changes)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let old = self.old.as_deref().unwrap_or_default();
+        let new = self.new.as_deref().unwrap_or_default();
+        match self.kind {
+            ChangeKind::TypeAdded => write!(f, "+ {} added", self.location)?,
+            ChangeKind::TypeRemoved => write!(f, "- {} removed", self.location)?,
+            ChangeKind::FieldAdded => write!(f, "+ {}: {}", self.location, new)?,
+            ChangeKind::FieldRemoved => write!(f, "- {}: {}", self.location, old)?,
+            ChangeKind::VariantAdded => write!(f, "+ {}", self.location)?,
+            ChangeKind::VariantRemoved => write!(f, "- {} removed", self.location)?,
+            ChangeKind::FieldTypeChanged => write!(f, "~ {}: {} → {}", self.location, old, new)?,
+            ChangeKind::FieldsReordered => {
+                write!(f, "~ {} fields reordered: {} → {}", self.location, old, new)?
+            }
+            ChangeKind::VariantReplaced => write!(f, "~ {} replaced by `{}`", self.location, new)?,
+            ChangeKind::KindChanged | ChangeKind::VariantChanged => {
+                write!(f, "~ {} changed from {} to {}", self.location, old, new)?
+            }
+        }
+        match &self.reason {
+            Some(reason) => write!(f, " (breaking: {})", reason),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Every change from `old` to `new`
+///
+/// Removed types come first, then changes in `new`'s type order.
+pub fn diff(old: &[TypeDefinition], new: &[TypeDefinition]) -> Vec<SchemaChange> {
+    let mut changes = Vec::new();
+
+    for old_type in old {
+        if !new.iter().any(|t| t.name() == old_type.name()) {
+            changes.push(
+                SchemaChange::new(ChangeKind::TypeRemoved, old_type.name().to_string())
+                    .breaking("generated clients lose the type"),
+            );
+        }
+    }
+
+    for new_type in new {
+        let Some(old_type) = old.iter().find(|t| t.name() == new_type.name()) else {
+            changes.push(SchemaChange::new(
+                ChangeKind::TypeAdded,
+                new_type.name().to_string(),
+            ));
+            continue;
+        };
+        match (old_type, new_type) {
+            (TypeDefinition::Struct(old), TypeDefinition::Struct(new)) => {
+                diff_struct(old, new, &mut changes)
+            }
+            (TypeDefinition::Enum(old), TypeDefinition::Enum(new)) => {
+                diff_enum(old, new, &mut changes)
+            }
+            _ => changes.push(
+                SchemaChange::new(ChangeKind::KindChanged, new_type.name().to_string())
+                    .old(kind(old_type).to_string())
+                    .new_value(kind(new_type).to_string())
+                    .breaking("existing data decodes as the other kind"),
+            ),
+        }
+    }
+
+    changes
+}
+
+/// Whether any of `changes` breaks data or clients from the old schema
+pub fn has_breaking(changes: &[SchemaChange]) -> bool {
+    changes.iter().any(|change| change.breaking)
+}
+
+fn kind(type_def: &TypeDefinition) -> &'static str {
+    match type_def {
+        TypeDefinition::Struct(_) => "struct",
+        TypeDefinition::Enum(_) => "enum",
+    }
+}
+
+/// Field changes, matched by name
+fn diff_struct(old: &StructDefinition, new: &StructDefinition, changes: &mut Vec<SchemaChange>) {
+    let kept = |fields: &StructDefinition, other: &StructDefinition| -> Vec<String> {
+        fields
+            .fields
+            .iter()
+            .filter(|f| other.fields.iter().any(|o| o.name == f.name))
+            .map(|f| f.name.clone())
+            .collect()
+    };
+    let (old_order, new_order) = (kept(old, new), kept(new, old));
+    if old_order != new_order {
+        changes.push(
+            SchemaChange::new(ChangeKind::FieldsReordered, new.name.clone())
+                .old(old_order.join(", "))
+                .new_value(new_order.join(", "))
+                .breaking("fields are read in order"),
+        );
+    }
+
+    // Fields after the last kept one are appended
+    let appended_from = new
+        .fields
+        .iter()
+        .rposition(|f| old.fields.iter().any(|o| o.name == f.name))
+        .map_or(0, |index| index + 1);
+
+    for (index, field) in new.fields.iter().enumerate() {
+        let location = format!("{}.{}", new.name, field.name);
+        match old.fields.iter().find(|f| f.name == field.name) {
+            None => {
+                let change = SchemaChange::new(ChangeKind::FieldAdded, location)
+                    .new_value(signature(&field.type_info));
+                changes.push(if index < appended_from {
+                    change.breaking("the fields after it move")
+                } else if !matches!(field.type_info, TypeInfo::Option(_)) {
+                    change.breaking("existing accounts end before it; append it as an Option")
+                } else {
+                    change
+                });
+            }
+            Some(previous) => {
+                let (before, after) = (signature(&previous.type_info), signature(&field.type_info));
+                if before != after {
+                    changes.push(
+                        SchemaChange::new(ChangeKind::FieldTypeChanged, location)
+                            .old(before)
+                            .new_value(after)
+                            .breaking("its encoding changes"),
+                    );
+                }
+            }
+        }
+    }
+
+    for field in &old.fields {
+        if !new.fields.iter().any(|f| f.name == field.name) {
+            changes.push(
+                SchemaChange::new(
+                    ChangeKind::FieldRemoved,
+                    format!("{}.{}", old.name, field.name),
+                )
+                .old(signature(&field.type_info))
+                .breaking("existing data still holds its bytes"),
+            );
+        }
+    }
+}
+
+/// Variant changes, matched by position as Borsh encodes them
+fn diff_enum(old: &EnumDefinition, new: &EnumDefinition, changes: &mut Vec<SchemaChange>) {
+    for (index, variant) in old.variants.iter().enumerate() {
+        let location = format!("{}::{}", old.name, variant.name());
+        match new.variants.get(index) {
+            None => changes.push(
+                SchemaChange::new(ChangeKind::VariantRemoved, location)
+                    .old(variant_signature(variant))
+                    .breaking("existing data may hold it"),
+            ),
+            Some(current) if current.name() != variant.name() => changes.push(
+                SchemaChange::new(ChangeKind::VariantReplaced, location)
+                    .old(variant.name().to_string())
+                    .new_value(current.name().to_string())
+                    .breaking("variants are encoded by position"),
+            ),
+            Some(current) if variant_signature(current) != variant_signature(variant) => changes
+                .push(
+                    SchemaChange::new(ChangeKind::VariantChanged, location)
+                        .old(variant_signature(variant))
+                        .new_value(variant_signature(current))
+                        .breaking("its payload decodes differently"),
+                ),
+            Some(_) => {}
+        }
+    }
+
+    for variant in new.variants.iter().skip(old.variants.len()) {
+        changes.push(
+            SchemaChange::new(
+                ChangeKind::VariantAdded,
+                format!("{}::{}", new.name, variant.name()),
+            )
+            .new_value(variant_signature(variant)),
+        );
+    }
+}
+
+/// Payload in schema notation, e.g. `(PublicKey, u64)` or `{ amount: u64 }`
+fn variant_signature(variant: &EnumVariantDefinition) -> String {
+    match variant {
+        EnumVariantDefinition::Unit { .. } => "unit".to_string(),
+        EnumVariantDefinition::Tuple { types, .. } => format!(
+            "({})",
+            types.iter().map(signature).collect::<Vec<_>>().join(", ")
+        ),
+        EnumVariantDefinition::Struct { fields, .. } => format!(
+            "{{ {} }}",
+            fields
+                .iter()
+                .map(|f| format!("{}: {}", f.name, signature(&f.type_info)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn ir(source: &str) -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(source).unwrap()).unwrap()
+    }
+
+    fn lines(changes: &[SchemaChange]) -> Vec<String> {
+        changes.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn compatible_changes_are_not_breaking() {
+        let changes = diff(
+            &ir("struct Player { wallet: PublicKey }\nenum Status { Active }"),
+            &ir(r#"
+                struct Player { wallet: PublicKey, nickname: Option<String> }
+                enum Status { Active, Paused(u64) }
+                struct Guild { name: String }
+            "#),
+        );
+
+        assert!(!has_breaking(&changes));
+        assert_eq!(
+            lines(&changes),
+            [
+                "+ Player.nickname: Option<String>",
+                "+ Status::Paused",
+                "+ Guild added"
+            ]
+        );
+        assert!(diff(&ir("struct A { b: u8 }"), &ir("struct A { b: u8 }")).is_empty());
+    }
+
+    #[test]
+    fn classifies_breaking_struct_changes() {
+        let old = ir("struct Vault { owner: PublicKey, amount: u64, bump: u8, memo: String }");
+        let new =
+            ir("struct Vault { amount: u32, flags: u8, owner: PublicKey, bump: u8, extra: u16 }");
+
+        let changes = diff(&old, &new);
+        assert!(changes.iter().all(|change| change.breaking));
+        assert_eq!(
+            lines(&changes),
+            [
+                "~ Vault fields reordered: owner, amount, bump → amount, owner, bump (breaking: fields are read in order)",
+                "~ Vault.amount: u64 → u32 (breaking: its encoding changes)",
+                "+ Vault.flags: u8 (breaking: the fields after it move)",
+                "+ Vault.extra: u16 (breaking: existing accounts end before it; append it as an Option)",
+                "- Vault.memo: String (breaking: existing data still holds its bytes)",
+            ]
+        );
+    }
+
+    #[test]
+    fn classifies_type_and_variant_changes() {
+        let old = ir(r#"
+            enum Status { Active, Paused, Closed(u8) }
+            struct Legacy { id: u8 }
+            struct Shape { id: u8 }
+        "#);
+        let new = ir(r#"
+            enum Status { Active, Closed(u8) }
+            enum Shape { Round }
+        "#);
+
+        let changes = diff(&old, &new);
+        assert_eq!(
+            lines(&changes),
+            [
+                "- Legacy removed (breaking: generated clients lose the type)",
+                "~ Status::Paused replaced by `Closed` (breaking: variants are encoded by position)",
+                "- Status::Closed removed (breaking: existing data may hold it)",
+                "~ Shape changed from struct to enum (breaking: existing data decodes as the other kind)",
+            ]
+        );
+
+        let changes = diff(&ir("enum E { A(u8) }"), &ir("enum E { A { value: u16 } }"));
+        assert_eq!(changes[0].kind, ChangeKind::VariantChanged);
+        assert_eq!(changes[0].old.as_deref(), Some("(u8)"));
+        assert_eq!(changes[0].new.as_deref(), Some("{ value: u16 }"));
+    }
+
+    #[test]
+    fn serializes_for_automation() {
+        let changes = diff(&ir("struct A { b: u8 }"), &ir("struct A { b: u16 }"));
+        assert_eq!(
+            serde_json::to_value(&changes).unwrap(),
+            serde_json::json!([{
+                "kind": "field_type_changed",
+                "location": "A.b",
+                "old": "u8",
+                "new": "u16",
+                "breaking": true,
+                "reason": "its encoding changes",
+            }])
+        );
+    }
+}
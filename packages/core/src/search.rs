@@ -0,0 +1,320 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Field search over schemas (`lumos grep`)
+//!
+//! A query is a list of space-separated terms that a field must all match:
+//!
+//! - `name:<pattern>`: the field name
+//! - `type:<pattern>`: the field type or any type inside it, so
+//!   `type:PublicKey` also finds `Option<PublicKey>` and `Vec<PublicKey>`
+//! - `in:<pattern>`: the type defining the field
+//! - `attr:<name>`: the field has the attribute, e.g. `attr:max`
+//! - `is:<kind>`: the defining type is an `account`, `event`,
+//!   `instruction`, `struct` or `enum`
+//! - a bare pattern is the same as `name:<pattern>`
+//!
+//! Patterns ignore case; `*` stands for any run of characters and `?` for
+//! one character.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::parser::parse_lumos_file;
+//! use lumos_core::search::Query;
+//! use lumos_core::transform::transform_to_ir;
+//!
+//! let source = "#[solana]\n#[account]\nstruct Vault { authority: PublicKey, backup_authority: Option<PublicKey>, balance: u64 }";
+//! let ir = transform_to_ir(parse_lumos_file(source)?)?;
+//!
+//! let query = Query::parse("type:PublicKey name:*authority*").unwrap();
+//! let names: Vec<_> = query.search(&ir).into_iter().map(|m| m.field_name).collect();
+//! assert_eq!(names, ["authority", "backup_authority"]);
+//! # Ok::<(), lumos_core::error::LumosError>(())
+//! ```
+
+use crate::ir::{EnumVariantDefinition, FieldDefinition, TypeDefinition, TypeInfo};
+
+/// Kinds of type `is:` accepts
+pub const KINDS: [&str; 5] = ["account", "event", "instruction", "struct", "enum"];
+
+/// One term of a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    Name(String),
+    Type(String),
+    In(String),
+    Attr(String),
+    Is(String),
+}
+
+/// A parsed search query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+/// A field matching a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldMatch {
+    /// Type defining the field
+    pub type_name: String,
+
+    /// Enum variant defining the field, for fields of struct variants
+    pub variant: Option<String>,
+
+    /// Field name
+    pub field_name: String,
+
+    /// Field type as written, e.g. `Option<PublicKey>`
+    pub type_signature: String,
+}
+
+impl FieldMatch {
+    /// `Vault.authority`, or `Action::Transfer.amount` for a variant field
+    pub fn path(&self) -> String {
+        match &self.variant {
+            Some(variant) => format!("{}::{}.{}", self.type_name, variant, self.field_name),
+            None => format!("{}.{}", self.type_name, self.field_name),
+        }
+    }
+}
+
+impl Query {
+    /// Parse a query such as `type:PublicKey name:*authority*`
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let mut terms = Vec::new();
+        for term in query.split_whitespace() {
+            let (key, value) = term.split_once(':').unwrap_or(("name", term));
+            if value.is_empty() {
+                return Err(format!("'{}' needs a value, e.g. {}:authority", term, key));
+            }
+            let value = value.to_lowercase();
+            terms.push(match key {
+                "name" => Term::Name(value),
+                "type" => Term::Type(value),
+                "in" => Term::In(value),
+                "attr" => Term::Attr(value),
+                "is" if KINDS.contains(&value.as_str()) => Term::Is(value),
+                "is" => {
+                    return Err(format!(
+                        "'{}' is not a kind of type; use one of: {}",
+                        value,
+                        KINDS.join(", ")
+                    ))
+                }
+                _ => {
+                    return Err(format!(
+                        "Unknown search term '{}:'; use name:, type:, in:, attr: or is:",
+                        key
+                    ))
+                }
+            });
+        }
+        if terms.is_empty() {
+            return Err("Empty query; search for e.g. \"type:PublicKey name:*authority*\"".into());
+        }
+        Ok(Self { terms })
+    }
+
+    /// Fields of `type_defs` matching every term, in definition order
+    pub fn search(&self, type_defs: &[TypeDefinition]) -> Vec<FieldMatch> {
+        let mut matches = Vec::new();
+        for type_def in type_defs {
+            for (variant, field) in fields(type_def) {
+                if self
+                    .terms
+                    .iter()
+                    .all(|term| matches_term(term, type_def, field))
+                {
+                    matches.push(FieldMatch {
+                        type_name: type_def.name().to_string(),
+                        variant: variant.map(str::to_string),
+                        field_name: field.name.clone(),
+                        type_signature: field.type_info.signature(),
+                    });
+                }
+            }
+        }
+        matches
+    }
+}
+
+fn matches_term(term: &Term, type_def: &TypeDefinition, field: &FieldDefinition) -> bool {
+    match term {
+        Term::Name(pattern) => glob_match(pattern, &field.name),
+        Term::Type(pattern) => {
+            let mut types = Vec::new();
+            nested_types(&field.type_info, &mut types);
+            types
+                .iter()
+                .any(|type_info| glob_match(pattern, &type_info.signature()))
+        }
+        Term::In(pattern) => glob_match(pattern, type_def.name()),
+        Term::Attr(name) => field
+            .attributes
+            .iter()
+            .any(|attribute| attribute.name.eq_ignore_ascii_case(name)),
+        Term::Is(kind) => match kind.as_str() {
+            "account" => type_def.is_account(),
+            "event" => type_def.is_event(),
+            "instruction" => type_def.is_instruction(),
+            "struct" => matches!(type_def, TypeDefinition::Struct(_)),
+            _ => matches!(type_def, TypeDefinition::Enum(_)),
+        },
+    }
+}
+
+/// Fields of a struct, or of every struct variant of an enum with the variant name
+fn fields(type_def: &TypeDefinition) -> Vec<(Option<&str>, &FieldDefinition)> {
+    match type_def {
+        TypeDefinition::Struct(struct_def) => struct_def
+            .fields
+            .iter()
+            .map(|field| (None, field))
+            .collect(),
+        TypeDefinition::Enum(enum_def) => enum_def
+            .variants
+            .iter()
+            .flat_map(|variant| match variant {
+                EnumVariantDefinition::Struct { name, fields } => fields
+                    .iter()
+                    .map(|field| (Some(name.as_str()), field))
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect(),
+    }
+}
+
+/// `type_info` and every type inside it
+fn nested_types<'a>(type_info: &'a TypeInfo, types: &mut Vec<&'a TypeInfo>) {
+    types.push(type_info);
+    match type_info {
+        TypeInfo::Primitive(_) | TypeInfo::UserDefined(_) => {}
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) | TypeInfo::Option(inner) => {
+            nested_types(inner, types)
+        }
+        TypeInfo::Map(_, key, value) => {
+            nested_types(key, types);
+            nested_types(value, types);
+        }
+    }
+}
+
+/// Whether `text` matches the lowercase `pattern`, ignoring case
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    // Backtrack to the last `*` on a mismatch
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        #[solana]
+        #[account]
+        struct Vault {
+            authority: PublicKey,
+            #[max(4)]
+            signers: Vec<PublicKey>,
+            balance: u64,
+        }
+
+        #[solana]
+        #[event]
+        struct Withdrawn {
+            vault_authority: PublicKey,
+            amount: u64,
+        }
+
+        #[solana]
+        enum Action {
+            Transfer { new_authority: PublicKey, amount: u64 },
+            Close,
+        }
+    "#;
+
+    fn search(query: &str) -> Vec<String> {
+        let ir = transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap();
+        Query::parse(query)
+            .unwrap()
+            .search(&ir)
+            .iter()
+            .map(FieldMatch::path)
+            .collect()
+    }
+
+    #[test]
+    fn finds_fields_matching_every_term() {
+        assert_eq!(
+            search("type:PublicKey name:*authority*"),
+            [
+                "Vault.authority",
+                "Withdrawn.vault_authority",
+                "Action::Transfer.new_authority"
+            ]
+        );
+        assert_eq!(
+            search("type:publickey is:account"),
+            ["Vault.authority", "Vault.signers"]
+        );
+        assert_eq!(search("type:Vec<*>"), ["Vault.signers"]);
+        assert_eq!(search("attr:max"), ["Vault.signers"]);
+        assert_eq!(search("amount in:W*"), ["Withdrawn.amount"]);
+        assert_eq!(
+            search("is:enum"),
+            ["Action::Transfer.new_authority", "Action::Transfer.amount"]
+        );
+        assert!(search("name:bal?nce is:event").is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_queries() {
+        assert!(Query::parse("  ").unwrap_err().contains("Empty query"));
+        assert!(Query::parse("name:").unwrap_err().contains("needs a value"));
+        assert!(Query::parse("is:vault")
+            .unwrap_err()
+            .contains("account, event"));
+        assert!(Query::parse("kind:account")
+            .unwrap_err()
+            .contains("Unknown search term 'kind:'"));
+    }
+
+    #[test]
+    fn matches_globs() {
+        assert!(glob_match("*authority*", "backup_Authority_key"));
+        assert!(glob_match("a*b?d", "aXXbcd"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("a*b", "acd"));
+        assert!(!glob_match("owner", "owners"));
+    }
+}
@@ -6,7 +6,8 @@
 //! Performs static analysis on LUMOS schemas to identify potential security
 //! issues before code generation and deployment.
 
-use crate::ir::{StructDefinition, TypeDefinition, TypeInfo};
+use crate::ir::{FieldDefinition, StructDefinition, TypeDefinition, TypeInfo};
+use crate::size_calculator::{SizeCalculator, SizeInfo};
 
 /// Severity level of a security finding
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -47,6 +48,15 @@ pub enum VulnerabilityType {
 
     /// Arithmetic-prone fields without checked math
     UncheckedArithmetic,
+
+    /// Same account type passed twice as mutable without a distinguishing constraint
+    DuplicateMutableAccount,
+
+    /// Closable account without explicit close handling
+    UnsafeAccountClosure,
+
+    /// Discriminator-less accounts with identical size and layout
+    TypeConfusion,
 }
 
 /// A security finding from analysis
@@ -78,6 +88,70 @@ pub struct Location {
     pub field_name: Option<String>,
 }
 
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.field_name {
+            Some(field) => write!(f, "{}::{}", self.type_name, field),
+            None => write!(f, "{}", self.type_name),
+        }
+    }
+}
+
+/// The location of a whole type
+impl From<&str> for Location {
+    fn from(type_name: &str) -> Self {
+        Self {
+            type_name: type_name.to_string(),
+            field_name: None,
+        }
+    }
+}
+
+/// Findings reported by the same rule, grouped together
+#[derive(Debug, Clone)]
+pub struct FindingGroup {
+    /// Severity level shared by all members
+    pub severity: Severity,
+
+    /// Rule that produced the findings
+    pub vulnerability: VulnerabilityType,
+
+    /// Suggested fix (taken from the first member)
+    pub suggestion: String,
+
+    /// Individual findings in this group
+    pub members: Vec<SecurityFinding>,
+}
+
+impl FindingGroup {
+    /// Number of findings in this group
+    pub fn count(&self) -> usize {
+        self.members.len()
+    }
+}
+
+/// Group findings by rule and severity, preserving the order of first occurrence
+pub fn group_findings(findings: &[SecurityFinding]) -> Vec<FindingGroup> {
+    let mut groups: Vec<FindingGroup> = Vec::new();
+
+    for finding in findings {
+        match groups
+            .iter_mut()
+            .find(|g| g.vulnerability == finding.vulnerability && g.severity == finding.severity)
+        {
+            Some(group) => group.members.push(finding.clone()),
+            None => groups.push(FindingGroup {
+                severity: finding.severity.clone(),
+                vulnerability: finding.vulnerability.clone(),
+                suggestion: finding.suggestion.clone(),
+                members: vec![finding.clone()],
+            }),
+        }
+    }
+
+    groups
+}
+
 /// Security analyzer
 pub struct SecurityAnalyzer<'a> {
     /// All type definitions
@@ -110,6 +184,10 @@ impl<'a> SecurityAnalyzer<'a> {
             match type_def {
                 TypeDefinition::Struct(s) => {
                     findings.extend(self.analyze_struct(s));
+
+                    if s.metadata.is_instruction() {
+                        findings.extend(self.analyze_instruction(s));
+                    }
                 }
                 TypeDefinition::Enum(_) => {
                     // Enums have fewer security concerns
@@ -118,18 +196,41 @@ impl<'a> SecurityAnalyzer<'a> {
             }
         }
 
+        findings.extend(self.analyze_type_confusion());
+
+        // Show the developer's documented intent next to the automated check
+        for finding in &mut findings {
+            if let Some(doc) = self.field_doc(&finding.location) {
+                finding.message = format!("{} (documented as: \"{}\")", finding.message, doc);
+            }
+        }
+
         // Sort by severity (Critical first)
         findings.sort_by(|a, b| b.severity.cmp(&a.severity));
 
         findings
     }
 
+    /// Doc comment of the field a finding points at, if any
+    fn field_doc(&self, location: &Location) -> Option<String> {
+        let field_name = location.field_name.as_deref()?;
+
+        self.type_defs.iter().find_map(|type_def| match type_def {
+            TypeDefinition::Struct(s) if s.name == location.type_name => s
+                .fields
+                .iter()
+                .find(|f| f.name == field_name)
+                .and_then(|f| f.doc_comment()),
+            _ => None,
+        })
+    }
+
     /// Analyze a struct for vulnerabilities
     fn analyze_struct(&self, struct_def: &StructDefinition) -> Vec<SecurityFinding> {
         let mut findings = Vec::new();
 
         // Check if this is an Anchor account
-        let is_account = struct_def.metadata.attributes.contains(&"account".to_string());
+        let is_account = struct_def.metadata.is_account();
 
         // Check for missing discriminator
         if struct_def.metadata.solana && !is_account {
@@ -168,9 +269,11 @@ impl<'a> SecurityAnalyzer<'a> {
             }
 
             // Check for owner validation
-            if field.name == "owner" && matches!(field.type_info, TypeInfo::Primitive(ref t) if t == "PublicKey" || t == "Pubkey") {
-                if self.strict_mode {
-                    findings.push(SecurityFinding {
+            if field.name == "owner"
+                && matches!(field.type_info, TypeInfo::Primitive(ref t) if t == "PublicKey" || t == "Pubkey")
+                && self.strict_mode
+            {
+                findings.push(SecurityFinding {
                         severity: Severity::Warning,
                         vulnerability: VulnerabilityType::MissingOwnerValidation,
                         location: Location {
@@ -180,7 +283,6 @@ impl<'a> SecurityAnalyzer<'a> {
                         message: "Owner field requires validation to prevent unauthorized access".to_string(),
                         suggestion: "Validate that msg.sender or transaction signer matches the owner field before state mutations".to_string(),
                     });
-                }
             }
 
             // Check for arithmetic-prone fields
@@ -201,9 +303,8 @@ impl<'a> SecurityAnalyzer<'a> {
             }
 
             // Check for integer overflow in large numeric types
-            if self.is_large_integer(&field.type_info) {
-                if self.strict_mode {
-                    findings.push(SecurityFinding {
+            if self.is_large_integer(&field.type_info) && self.strict_mode {
+                findings.push(SecurityFinding {
                         severity: Severity::Info,
                         vulnerability: VulnerabilityType::IntegerOverflow,
                         location: Location {
@@ -216,14 +317,46 @@ impl<'a> SecurityAnalyzer<'a> {
                         ),
                         suggestion: "Ensure arithmetic operations on this field use checked math or saturating operations".to_string(),
                     });
-                }
             }
         }
 
-        // Check for re-initialization risks
-        if is_account && !self.has_initialized_flag(struct_def) {
-            if self.strict_mode {
+        // Check for closable accounts
+        if is_account {
+            if let Some(field) = struct_def
+                .fields
+                .iter()
+                .find(|f| self.is_closable_field(&f.name))
+            {
+                let has_close_authority = struct_def
+                    .fields
+                    .iter()
+                    .any(|f| f.name.to_lowercase() == "close_authority");
+
+                let suggestion = if has_close_authority {
+                    "Close the account with Anchor's `close = destination` constraint together with `has_one = close_authority`, and verify the refund destination before lamports are moved".to_string()
+                } else {
+                    "Close the account with Anchor's `close = destination` constraint so lamports go to a verified refund destination and the data is zeroed, instead of relying on a flag".to_string()
+                };
+
                 findings.push(SecurityFinding {
+                    severity: Severity::Warning,
+                    vulnerability: VulnerabilityType::UnsafeAccountClosure,
+                    location: Location {
+                        type_name: struct_def.name.clone(),
+                        field_name: Some(field.name.clone()),
+                    },
+                    message: format!(
+                        "Account '{}' appears closable (field '{}') - improper closing can drain lamports to the wrong destination or leave a revivable account",
+                        struct_def.name, field.name
+                    ),
+                    suggestion,
+                });
+            }
+        }
+
+        // Check for re-initialization risks
+        if is_account && !self.has_initialized_flag(struct_def) && self.strict_mode {
+            findings.push(SecurityFinding {
                     severity: Severity::Warning,
                     vulnerability: VulnerabilityType::ReInitialization,
                     location: Location {
@@ -233,12 +366,179 @@ impl<'a> SecurityAnalyzer<'a> {
                     message: "Account lacks explicit initialization flag - vulnerable to re-initialization attacks".to_string(),
                     suggestion: "Add an 'is_initialized' boolean field or use Anchor's init constraint to prevent re-initialization".to_string(),
                 });
+        }
+
+        findings
+    }
+
+    /// Analyze an `#[instruction]` struct for duplicate mutable accounts
+    ///
+    /// Fields whose type is an `#[account]` struct are treated as the accounts
+    /// the instruction takes; `#[account(mut)]` marks them as mutable. When two
+    /// or more mutable accounts of one type lack a distinguishing constraint,
+    /// the same account can be passed for each of them. A `has_one` check
+    /// doesn't count: both accounts can satisfy it at once.
+    fn analyze_instruction(&self, struct_def: &StructDefinition) -> Vec<SecurityFinding> {
+        let mut findings = Vec::new();
+        let mut by_type: Vec<(&str, Vec<&FieldDefinition>)> = Vec::new();
+
+        for field in &struct_def.fields {
+            let TypeInfo::UserDefined(type_name) = &field.type_info else {
+                continue;
+            };
+
+            if !self.is_account_type(type_name) || !self.is_mutable_account_field(field) {
+                continue;
+            }
+
+            match by_type.iter_mut().find(|(name, _)| name == type_name) {
+                Some((_, fields)) => fields.push(field),
+                None => by_type.push((type_name, vec![field])),
+            }
+        }
+
+        for (type_name, fields) in by_type {
+            let fields: Vec<&FieldDefinition> = fields
+                .into_iter()
+                .filter(|f| !self.has_distinguishing_constraint(f))
+                .collect();
+            if fields.len() < 2 {
+                continue;
+            }
+
+            let names: Vec<String> = fields.iter().map(|f| format!("'{}'", f.name)).collect();
+
+            findings.push(SecurityFinding {
+                severity: Severity::Critical,
+                vulnerability: VulnerabilityType::DuplicateMutableAccount,
+                location: Location {
+                    type_name: struct_def.name.clone(),
+                    field_name: Some(fields[0].name.clone()),
+                },
+                message: format!(
+                    "Instruction '{}' takes {} mutable '{}' accounts ({}) with nothing preventing the same account from being passed twice",
+                    struct_def.name,
+                    fields.len(),
+                    type_name,
+                    names.join(", ")
+                ),
+                suggestion: format!(
+                    "Add a constraint that tells the accounts apart, e.g. #[account(mut, constraint = {}.key() != {}.key())]",
+                    fields[0].name, fields[1].name
+                ),
+            });
+        }
+
+        findings
+    }
+
+    /// Analyze discriminator-less account structs for type confusion
+    ///
+    /// Two structs without a discriminator that serialize to the same fixed
+    /// size and start with the same field types can be deserialized as each
+    /// other, so a manual program can't tell them apart.
+    fn analyze_type_confusion(&self) -> Vec<SecurityFinding> {
+        let mut findings = Vec::new();
+
+        let sizes: Vec<(String, usize)> = SizeCalculator::new(self.type_defs)
+            .calculate_all()
+            .into_iter()
+            .filter_map(|size| match size.total_bytes {
+                SizeInfo::Fixed(bytes) => Some((size.name, bytes)),
+                SizeInfo::Variable { .. } => None,
+            })
+            .collect();
+
+        let candidates: Vec<(&StructDefinition, usize)> = self
+            .type_defs
+            .iter()
+            .filter_map(|type_def| match type_def {
+                TypeDefinition::Struct(s) if self.lacks_discriminator(s) => sizes
+                    .iter()
+                    .find(|(name, _)| name == &s.name)
+                    .map(|(_, bytes)| (s, *bytes)),
+                _ => None,
+            })
+            .collect();
+
+        for (i, (first, first_size)) in candidates.iter().enumerate() {
+            for (second, second_size) in candidates.iter().skip(i + 1) {
+                if first_size != second_size || !self.has_similar_layout(first, second) {
+                    continue;
+                }
+
+                findings.push(SecurityFinding {
+                    severity: Severity::Warning,
+                    vulnerability: VulnerabilityType::TypeConfusion,
+                    location: Location {
+                        type_name: second.name.clone(),
+                        field_name: None,
+                    },
+                    message: format!(
+                        "Accounts '{}' and '{}' are both {} bytes with the same leading field layout and no discriminator - one can be deserialized as the other",
+                        first.name, second.name, first_size
+                    ),
+                    suggestion: "Add #[account] to get Anchor's 8-byte discriminator, or store an explicit account type tag as the first field and check it on deserialization".to_string(),
+                });
             }
         }
 
         findings
     }
 
+    /// Check if a struct is stored as account data without a discriminator
+    fn lacks_discriminator(&self, struct_def: &StructDefinition) -> bool {
+        let is_account = struct_def.metadata.is_account();
+
+        if struct_def.metadata.is_instruction() {
+            return false;
+        }
+
+        (struct_def.metadata.solana || is_account) && !(struct_def.metadata.solana && is_account)
+    }
+
+    /// Check if two structs start with the same field types
+    fn has_similar_layout(&self, first: &StructDefinition, second: &StructDefinition) -> bool {
+        const LEADING_FIELDS: usize = 2;
+
+        if first.fields.is_empty() || second.fields.is_empty() {
+            return false;
+        }
+
+        first
+            .fields
+            .iter()
+            .zip(&second.fields)
+            .take(LEADING_FIELDS)
+            .all(|(a, b)| a.type_info.signature() == b.type_info.signature())
+    }
+
+    /// Check if a type name refers to an `#[account]` struct in the schema
+    fn is_account_type(&self, type_name: &str) -> bool {
+        self.type_defs
+            .iter()
+            .any(|t| t.name() == type_name && t.is_account())
+    }
+
+    /// Check if an instruction field is declared mutable via `#[account(mut)]`
+    fn is_mutable_account_field(&self, field: &FieldDefinition) -> bool {
+        field
+            .attribute_args("account")
+            .iter()
+            .any(|arg| arg == "mut")
+    }
+
+    /// Check if an instruction field carries a constraint that distinguishes it
+    /// from other accounts of the same type
+    fn has_distinguishing_constraint(&self, field: &FieldDefinition) -> bool {
+        const DISTINGUISHING: [&str; 3] = ["constraint", "seeds", "address"];
+
+        field.attribute_args("account").iter().any(|arg| {
+            let key = arg.split('=').next().unwrap_or("").trim();
+            DISTINGUISHING.contains(&key)
+        })
+    }
+
     /// Check if a field name suggests it's an authority/signer
     fn is_authority_field(&self, field_name: &str) -> bool {
         let authority_keywords = [
@@ -280,25 +580,26 @@ impl<'a> SecurityAnalyzer<'a> {
         })
     }
 
+    /// Check if a field name suggests the account can be closed
+    fn is_closable_field(&self, field_name: &str) -> bool {
+        let closable_keywords = ["closed", "is_closed", "close_authority", "closer"];
+
+        let lower = field_name.to_lowercase();
+        closable_keywords.contains(&lower.as_str())
+    }
+
     /// Check if a field is used for arithmetic operations
     fn is_arithmetic_field(&self, field_name: &str, type_info: &TypeInfo) -> bool {
         // Common field names that involve arithmetic
         let arithmetic_keywords = [
-            "balance",
-            "amount",
-            "supply",
-            "total",
-            "count",
-            "price",
-            "value",
-            "reward",
-            "stake",
-            "fee",
-            "lamport",
+            "balance", "amount", "supply", "total", "count", "price", "value", "reward", "stake",
+            "fee", "lamport",
         ];
 
         let lower = field_name.to_lowercase();
-        let is_arithmetic_name = arithmetic_keywords.iter().any(|keyword| lower.contains(keyword));
+        let is_arithmetic_name = arithmetic_keywords
+            .iter()
+            .any(|keyword| lower.contains(keyword));
 
         // Must be a numeric type
         let is_numeric = matches!(type_info, TypeInfo::Primitive(ref t) if
@@ -320,8 +621,8 @@ impl<'a> SecurityAnalyzer<'a> {
     fn has_initialized_flag(&self, struct_def: &StructDefinition) -> bool {
         struct_def.fields.iter().any(|f| {
             let lower = f.name.to_lowercase();
-            (lower.contains("initialized") || lower.contains("init")) &&
-            matches!(f.type_info, TypeInfo::Primitive(ref t) if t == "bool")
+            (lower.contains("initialized") || lower.contains("init"))
+                && matches!(f.type_info, TypeInfo::Primitive(ref t) if t == "bool")
         })
     }
 }
@@ -347,6 +648,23 @@ impl Severity {
 }
 
 impl VulnerabilityType {
+    /// Stable identifier (e.g. "missing-signer")
+    pub fn id(&self) -> &'static str {
+        match self {
+            VulnerabilityType::MissingSigner => "missing-signer",
+            VulnerabilityType::IntegerOverflow => "integer-overflow",
+            VulnerabilityType::MissingOwnerValidation => "missing-owner-validation",
+            VulnerabilityType::UninitializedAccount => "uninitialized-account",
+            VulnerabilityType::ReInitialization => "re-initialization",
+            VulnerabilityType::UncheckedAccountData => "unchecked-account-data",
+            VulnerabilityType::NoDiscriminator => "no-discriminator",
+            VulnerabilityType::UncheckedArithmetic => "unchecked-arithmetic",
+            VulnerabilityType::DuplicateMutableAccount => "duplicate-mutable-account",
+            VulnerabilityType::UnsafeAccountClosure => "unsafe-account-closure",
+            VulnerabilityType::TypeConfusion => "type-confusion",
+        }
+    }
+
     /// Get string representation
     pub fn as_str(&self) -> &str {
         match self {
@@ -358,6 +676,9 @@ impl VulnerabilityType {
             VulnerabilityType::UncheckedAccountData => "Unchecked Account Data",
             VulnerabilityType::NoDiscriminator => "No Discriminator",
             VulnerabilityType::UncheckedArithmetic => "Unchecked Arithmetic",
+            VulnerabilityType::DuplicateMutableAccount => "Duplicate Mutable Account",
+            VulnerabilityType::UnsafeAccountClosure => "Unsafe Account Closure",
+            VulnerabilityType::TypeConfusion => "Type Confusion Risk",
         }
     }
 }
@@ -365,7 +686,7 @@ impl VulnerabilityType {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{FieldDefinition, Metadata};
+    use crate::ir::{FieldDefinition, Metadata, TypeRole};
 
     #[test]
     fn test_detects_missing_signer() {
@@ -375,6 +696,7 @@ mod tests {
                 name: "authority".to_string(),
                 type_info: TypeInfo::Primitive("PublicKey".to_string()),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata::default(),
         })];
@@ -382,10 +704,10 @@ mod tests {
         let analyzer = SecurityAnalyzer::new(&type_defs);
         let findings = analyzer.analyze();
 
-        assert!(findings.iter().any(|f|
-            matches!(f.vulnerability, VulnerabilityType::MissingSigner) &&
-            matches!(f.severity, Severity::Critical)
-        ));
+        assert!(findings.iter().any(|f| matches!(
+            f.vulnerability,
+            VulnerabilityType::MissingSigner
+        ) && matches!(f.severity, Severity::Critical)));
     }
 
     #[test]
@@ -396,6 +718,7 @@ mod tests {
                 name: "balance".to_string(),
                 type_info: TypeInfo::Primitive("u64".to_string()),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata::default(),
         })];
@@ -403,9 +726,9 @@ mod tests {
         let analyzer = SecurityAnalyzer::new(&type_defs);
         let findings = analyzer.analyze();
 
-        assert!(findings.iter().any(|f|
-            matches!(f.vulnerability, VulnerabilityType::UncheckedArithmetic)
-        ));
+        assert!(findings
+            .iter()
+            .any(|f| matches!(f.vulnerability, VulnerabilityType::UncheckedArithmetic)));
     }
 
     #[test]
@@ -416,15 +739,22 @@ mod tests {
             metadata: Metadata {
                 solana: true,
                 attributes: vec![], // Missing #[account]
+                role: TypeRole::Plain,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         })];
 
         let analyzer = SecurityAnalyzer::new(&type_defs);
         let findings = analyzer.analyze();
 
-        assert!(findings.iter().any(|f|
-            matches!(f.vulnerability, VulnerabilityType::NoDiscriminator)
-        ));
+        assert!(findings
+            .iter()
+            .any(|f| matches!(f.vulnerability, VulnerabilityType::NoDiscriminator)));
     }
 
     #[test]
@@ -435,10 +765,18 @@ mod tests {
                 name: "owner".to_string(),
                 type_info: TypeInfo::Primitive("PublicKey".to_string()),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata {
                 solana: true,
                 attributes: vec!["account".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         })];
 
@@ -462,16 +800,25 @@ mod tests {
                     name: "id".to_string(),
                     type_info: TypeInfo::Primitive("u32".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
                 FieldDefinition {
                     name: "name".to_string(),
                     type_info: TypeInfo::Primitive("String".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
             ],
             metadata: Metadata {
                 solana: true,
                 attributes: vec!["account".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         })];
 
@@ -479,6 +826,273 @@ mod tests {
         let findings = analyzer.analyze();
 
         // Should have no critical findings
-        assert!(!findings.iter().any(|f| matches!(f.severity, Severity::Critical)));
+        assert!(!findings
+            .iter()
+            .any(|f| matches!(f.severity, Severity::Critical)));
+    }
+
+    fn analyze_source(source: &str) -> Vec<SecurityFinding> {
+        let ast = crate::parser::parse_lumos_file(source).unwrap();
+        let type_defs = crate::transform::transform_to_ir(ast).unwrap();
+        SecurityAnalyzer::new(&type_defs).analyze()
+    }
+
+    #[test]
+    fn test_detects_duplicate_mutable_accounts() {
+        let findings = analyze_source(
+            r#"
+            #[solana]
+            #[account]
+            struct Vault {
+                balance: u64,
+            }
+
+            #[solana]
+            #[instruction]
+            struct Transfer {
+                #[account(mut)]
+                from: Vault,
+                #[account(mut)]
+                to: Vault,
+                amount: u64,
+            }
+            "#,
+        );
+
+        let finding = findings
+            .iter()
+            .find(|f| matches!(f.vulnerability, VulnerabilityType::DuplicateMutableAccount))
+            .expect("duplicate mutable account finding");
+
+        assert_eq!(finding.severity, Severity::Critical);
+        assert_eq!(finding.location.type_name, "Transfer");
+        assert!(finding.message.contains("'from', 'to'"));
+    }
+
+    #[test]
+    fn test_duplicate_mutable_accounts_with_constraint_is_safe() {
+        let findings = analyze_source(
+            r#"
+            #[solana]
+            #[account]
+            struct Vault {
+                balance: u64,
+            }
+
+            #[solana]
+            #[instruction]
+            struct Transfer {
+                #[account(mut)]
+                from: Vault,
+                #[account(mut, constraint = to.key() != from.key())]
+                to: Vault,
+            }
+
+            #[solana]
+            #[instruction]
+            struct Inspect {
+                #[account(mut)]
+                primary: Vault,
+                secondary: Vault,
+            }
+            "#,
+        );
+
+        assert!(!findings
+            .iter()
+            .any(|f| matches!(f.vulnerability, VulnerabilityType::DuplicateMutableAccount)));
+    }
+
+    #[test]
+    fn test_duplicate_mutable_accounts_need_all_but_one_distinguished() {
+        let findings = analyze_source(
+            r#"
+            #[solana]
+            #[account]
+            struct Vault {
+                owner: PublicKey,
+                balance: u64,
+            }
+
+            #[solana]
+            #[instruction]
+            struct Settle {
+                #[account(mut)]
+                first: Vault,
+                #[account(mut)]
+                second: Vault,
+                #[account(mut, constraint = third.key() != first.key())]
+                third: Vault,
+            }
+
+            #[solana]
+            #[instruction]
+            struct Transfer {
+                #[account(mut, has_one = owner)]
+                from: Vault,
+                #[account(mut, has_one = owner)]
+                to: Vault,
+            }
+            "#,
+        );
+
+        let messages: Vec<&str> = findings
+            .iter()
+            .filter(|f| matches!(f.vulnerability, VulnerabilityType::DuplicateMutableAccount))
+            .map(|f| f.message.as_str())
+            .collect();
+
+        assert_eq!(messages.len(), 2);
+        assert!(
+            messages[0].contains("'Settle' takes 2 mutable 'Vault' accounts ('first', 'second')")
+        );
+        assert!(messages[1].contains("'Transfer' takes 2 mutable 'Vault' accounts ('from', 'to')"));
+    }
+
+    #[test]
+    fn test_detects_closable_account() {
+        let findings = analyze_source(
+            r#"
+            #[solana]
+            #[account]
+            struct Escrow {
+                close_authority: PublicKey,
+                closed: bool,
+            }
+
+            #[solana]
+            #[account]
+            struct Config {
+                admin_count: u8,
+            }
+            "#,
+        );
+
+        let closure: Vec<_> = findings
+            .iter()
+            .filter(|f| matches!(f.vulnerability, VulnerabilityType::UnsafeAccountClosure))
+            .collect();
+
+        assert_eq!(closure.len(), 1);
+        assert_eq!(closure[0].severity, Severity::Warning);
+        assert_eq!(closure[0].location.type_name, "Escrow");
+        assert!(closure[0].suggestion.contains("close = destination"));
+        assert!(closure[0].suggestion.contains("has_one = close_authority"));
+    }
+
+    #[test]
+    fn test_detects_type_confusion_between_same_size_accounts() {
+        let findings = analyze_source(
+            r#"
+            #[solana]
+            struct UserStats {
+                owner: PublicKey,
+                points: u64,
+            }
+
+            #[solana]
+            struct AdminStats {
+                owner: PublicKey,
+                level: u64,
+            }
+
+            #[solana]
+            struct Counter {
+                value: u64,
+                owner: PublicKey,
+            }
+            "#,
+        );
+
+        let confusion: Vec<_> = findings
+            .iter()
+            .filter(|f| matches!(f.vulnerability, VulnerabilityType::TypeConfusion))
+            .collect();
+
+        assert_eq!(confusion.len(), 1);
+        assert_eq!(confusion[0].location.type_name, "AdminStats");
+        assert!(confusion[0]
+            .message
+            .contains("'UserStats' and 'AdminStats'"));
+    }
+
+    #[test]
+    fn test_no_type_confusion_with_discriminators() {
+        let findings = analyze_source(
+            r#"
+            #[solana]
+            #[account]
+            struct UserStats {
+                owner: PublicKey,
+                points: u64,
+            }
+
+            #[solana]
+            #[account]
+            struct AdminStats {
+                owner: PublicKey,
+                level: u64,
+            }
+            "#,
+        );
+
+        assert!(!findings
+            .iter()
+            .any(|f| matches!(f.vulnerability, VulnerabilityType::TypeConfusion)));
+    }
+
+    #[test]
+    fn test_group_findings_by_rule() {
+        let findings = analyze_source(
+            r#"
+            #[solana]
+            #[account]
+            struct Alice {
+                balance: u64,
+            }
+
+            #[solana]
+            #[account]
+            struct Bob {
+                balance: u64,
+                authority: PublicKey,
+            }
+            "#,
+        );
+
+        let groups = group_findings(&findings);
+        let arithmetic = groups
+            .iter()
+            .find(|g| matches!(g.vulnerability, VulnerabilityType::UncheckedArithmetic))
+            .expect("arithmetic group");
+
+        assert_eq!(arithmetic.count(), 2);
+        assert_eq!(arithmetic.members[0].location.to_string(), "Alice::balance");
+        assert_eq!(
+            groups.iter().map(FindingGroup::count).sum::<usize>(),
+            findings.len()
+        );
+    }
+
+    #[test]
+    fn test_finding_message_includes_field_docs() {
+        let findings = analyze_source(
+            r#"
+            #[solana]
+            #[account]
+            struct Vault {
+                /// Signer allowed to withdraw
+                authority: PublicKey,
+            }
+            "#,
+        );
+
+        let finding = findings
+            .iter()
+            .find(|f| f.location.field_name.as_deref() == Some("authority"))
+            .unwrap();
+        assert!(finding
+            .message
+            .ends_with("(documented as: \"Signer allowed to withdraw\")"));
     }
 }
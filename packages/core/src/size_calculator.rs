@@ -5,11 +5,16 @@
 //!
 //! Calculates the exact byte size of account data structures based on
 //! Borsh serialization format.
+//!
+//! [`SizeCalculator`] works through `&self` and caches each type's size
+//! behind a lock, so one calculator can be shared across threads and nested
+//! types are only sized once.
 
 use crate::ir::{
     EnumDefinition, EnumVariantDefinition, StructDefinition, TypeDefinition, TypeInfo,
 };
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
 /// Result of size calculation for an account
 #[derive(Debug, Clone)]
@@ -56,13 +61,131 @@ pub struct FieldSize {
     pub description: String,
 }
 
+/// An account that grew beyond the allowed delta compared to a baseline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeRegression {
+    /// Account name
+    pub name: String,
+
+    /// Size recorded in the baseline (minimum bytes)
+    pub baseline_bytes: usize,
+
+    /// Size calculated from the current schema (minimum bytes)
+    pub current_bytes: usize,
+}
+
+impl SizeRegression {
+    /// Number of bytes the account grew by
+    pub fn growth(&self) -> usize {
+        self.current_bytes - self.baseline_bytes
+    }
+}
+
+/// Compare calculated sizes against a baseline of minimum byte counts
+///
+/// Accounts that grew by more than `max_growth` bytes are reported. Accounts
+/// missing from the baseline are new and never count as regressions.
+pub fn find_size_regressions(
+    sizes: &[AccountSize],
+    baseline: &HashMap<String, usize>,
+    max_growth: usize,
+) -> Vec<SizeRegression> {
+    sizes
+        .iter()
+        .filter_map(|account| {
+            let baseline_bytes = *baseline.get(&account.name)?;
+            let current_bytes = account.total_bytes.min_bytes();
+
+            (current_bytes > baseline_bytes.saturating_add(max_growth)).then(|| SizeRegression {
+                name: account.name.clone(),
+                baseline_bytes,
+                current_bytes,
+            })
+        })
+        .collect()
+}
+
+/// Lamports charged per byte-year of account storage (Solana's default rent)
+const LAMPORTS_PER_BYTE_YEAR: u64 = 3_480;
+
+/// Years of rent an account must hold to be rent-exempt
+const EXEMPTION_THRESHOLD_YEARS: u64 = 2;
+
+/// Bytes of account metadata that are charged on top of the data
+const ACCOUNT_STORAGE_OVERHEAD: usize = 128;
+
+/// Minimum balance in lamports for an account with `data_len` bytes of data to be rent-exempt
+pub fn rent_exempt_lamports(data_len: usize) -> u64 {
+    (data_len + ACCOUNT_STORAGE_OVERHEAD) as u64
+        * LAMPORTS_PER_BYTE_YEAR
+        * EXEMPTION_THRESHOLD_YEARS
+}
+
+/// Projected rent for an expected number of accounts of one type
+#[derive(Debug, Clone)]
+pub struct RentProjection {
+    /// Account name
+    pub name: String,
+
+    /// Expected number of accounts
+    pub count: u64,
+
+    /// Size of a single account (minimum bytes)
+    pub bytes_each: usize,
+
+    /// Rent-exempt balance of a single account in SOL
+    pub rent_sol_each: f64,
+}
+
+impl RentProjection {
+    /// Total storage for all accounts in bytes
+    pub fn total_bytes(&self) -> u64 {
+        self.bytes_each as u64 * self.count
+    }
+
+    /// Total rent-exempt balance for all accounts in SOL
+    pub fn total_rent_sol(&self) -> f64 {
+        self.rent_sol_each * self.count as f64
+    }
+}
+
+/// Project total rent for expected account counts per type
+///
+/// Returns one projection per entry in `counts`, in the same order. Names
+/// that don't match a calculated type are returned in the error list.
+pub fn project_rent(
+    sizes: &[AccountSize],
+    counts: &[(String, u64)],
+) -> Result<Vec<RentProjection>, Vec<String>> {
+    let mut projections = Vec::new();
+    let mut unknown = Vec::new();
+
+    for (name, count) in counts {
+        match sizes.iter().find(|s| &s.name == name) {
+            Some(account) => projections.push(RentProjection {
+                name: name.clone(),
+                count: *count,
+                bytes_each: account.total_bytes.min_bytes(),
+                rent_sol_each: account.rent_sol,
+            }),
+            None => unknown.push(name.clone()),
+        }
+    }
+
+    if unknown.is_empty() {
+        Ok(projections)
+    } else {
+        Err(unknown)
+    }
+}
+
 /// Size calculator
 pub struct SizeCalculator<'a> {
     /// All type definitions for resolving user-defined types
     type_defs: &'a [TypeDefinition],
 
-    /// Cache of calculated sizes for user-defined types
-    size_cache: HashMap<String, SizeInfo>,
+    /// Sizes calculated so far, by type name
+    cache: RwLock<HashMap<String, Arc<AccountSize>>>,
 }
 
 impl<'a> SizeCalculator<'a> {
@@ -70,23 +193,65 @@ impl<'a> SizeCalculator<'a> {
     pub fn new(type_defs: &'a [TypeDefinition]) -> Self {
         Self {
             type_defs,
-            size_cache: HashMap::new(),
+            cache: RwLock::new(HashMap::new()),
         }
     }
 
     /// Calculate sizes for all accounts
-    pub fn calculate_all(&mut self) -> Vec<AccountSize> {
+    pub fn calculate_all(&self) -> Vec<AccountSize> {
         self.type_defs
             .iter()
-            .filter_map(|type_def| match type_def {
-                TypeDefinition::Struct(s) => Some(self.calculate_struct_size(s)),
-                TypeDefinition::Enum(e) => Some(self.calculate_enum_size(e)),
-            })
+            .map(|type_def| self.size_of(type_def, &mut Vec::new()).as_ref().clone())
             .collect()
     }
 
+    /// Size of the type named `name`, if the schema defines it
+    pub fn account_size(&self, name: &str) -> Option<Arc<AccountSize>> {
+        self.type_defs
+            .iter()
+            .find(|t| t.name() == name)
+            .map(|type_def| self.size_of(type_def, &mut Vec::new()))
+    }
+
+    /// Encoded size of a value of `type_info`
+    pub fn type_size(&self, type_info: &TypeInfo) -> SizeInfo {
+        self.calculate_type_size(type_info, &mut Vec::new())
+    }
+
+    /// Cached size of a type definition, calculated on first use
+    ///
+    /// The lock is not held while calculating, since nested types recurse
+    /// into the cache; threads racing on the same type compute equal sizes.
+    /// `sizing` holds the types being calculated further up, so a type that
+    /// reaches itself through a map stops there instead of recursing forever.
+    fn size_of(&self, type_def: &TypeDefinition, sizing: &mut Vec<String>) -> Arc<AccountSize> {
+        let cached = self
+            .cache
+            .read()
+            .ok()
+            .and_then(|cache| cache.get(type_def.name()).cloned());
+        if let Some(size) = cached {
+            return size;
+        }
+
+        sizing.push(type_def.name().to_string());
+        let size = Arc::new(match type_def {
+            TypeDefinition::Struct(s) => self.calculate_struct_size(s, sizing),
+            TypeDefinition::Enum(e) => self.calculate_enum_size(e, sizing),
+        });
+        sizing.pop();
+        if let Ok(mut cache) = self.cache.write() {
+            cache.insert(type_def.name().to_string(), Arc::clone(&size));
+        }
+        size
+    }
+
     /// Calculate size for a struct
-    fn calculate_struct_size(&mut self, struct_def: &StructDefinition) -> AccountSize {
+    fn calculate_struct_size(
+        &self,
+        struct_def: &StructDefinition,
+        sizing: &mut Vec<String>,
+    ) -> AccountSize {
         let mut field_breakdown = Vec::new();
         let mut total_size = 0;
         let mut is_variable = false;
@@ -94,7 +259,7 @@ impl<'a> SizeCalculator<'a> {
         let mut warnings = Vec::new();
 
         // Add discriminator for Anchor accounts
-        let is_account = struct_def.metadata.attributes.contains(&"account".to_string());
+        let is_account = struct_def.metadata.is_account();
         if is_account {
             field_breakdown.push(FieldSize {
                 name: "discriminator".to_string(),
@@ -106,8 +271,24 @@ impl<'a> SizeCalculator<'a> {
 
         // Calculate size for each field
         for field in &struct_def.fields {
-            let size = self.calculate_type_size(&field.type_info);
-            let description = self.describe_type(&field.type_info);
+            let (size, description) = match field.codec("rust") {
+                // The codec owns the layout, so nothing is known about its size
+                Some(codec) => (
+                    SizeInfo::Variable {
+                        min: 0,
+                        reason: format!("encoded by custom codec `{}`", codec),
+                    },
+                    format!(
+                        "{} (custom codec `{}`)",
+                        self.describe_type(&field.type_info),
+                        codec
+                    ),
+                ),
+                None => (
+                    self.calculate_type_size(&field.type_info, sizing),
+                    self.describe_type(&field.type_info),
+                ),
+            };
 
             match &size {
                 SizeInfo::Fixed(bytes) => {
@@ -130,14 +311,12 @@ impl<'a> SizeCalculator<'a> {
             });
         }
 
-        // Calculate rent (using Solana rent formula: ~0.00000348 SOL per byte per year)
-        // Minimum rent-exempt balance = (size + 128) * 6.96 lamports/byte
-        let rent_lamports = (total_size + 128) as f64 * 6.96;
-        let rent_sol = rent_lamports / 1_000_000_000.0;
+        // Minimum rent-exempt balance = (size + 128) * 3480 lamports/byte-year * 2 years
+        let rent_sol = rent_exempt_lamports(total_size) as f64 / 1_000_000_000.0;
 
         // Generate warnings
         const MAX_ACCOUNT_SIZE: usize = 10 * 1024 * 1024; // 10MB Solana limit
-        const WARNING_THRESHOLD: usize = 1 * 1024 * 1024; // Warn at 1MB
+        const WARNING_THRESHOLD: usize = 1024 * 1024; // Warn at 1MB
 
         if total_size > MAX_ACCOUNT_SIZE {
             warnings.push(format!(
@@ -171,7 +350,11 @@ impl<'a> SizeCalculator<'a> {
     }
 
     /// Calculate size for an enum
-    fn calculate_enum_size(&mut self, enum_def: &EnumDefinition) -> AccountSize {
+    fn calculate_enum_size(
+        &self,
+        enum_def: &EnumDefinition,
+        sizing: &mut Vec<String>,
+    ) -> AccountSize {
         let mut field_breakdown = Vec::new();
         let mut max_variant_size = 0;
         let mut warnings = Vec::new();
@@ -199,7 +382,7 @@ impl<'a> SizeCalculator<'a> {
                 EnumVariantDefinition::Tuple { name, types } => {
                     let mut tuple_size = 0;
                     for (i, type_info) in types.iter().enumerate() {
-                        let size = self.calculate_type_size(type_info);
+                        let size = self.calculate_type_size(type_info, sizing);
                         if let SizeInfo::Fixed(bytes) = size {
                             tuple_size += bytes;
                         }
@@ -214,7 +397,7 @@ impl<'a> SizeCalculator<'a> {
                 EnumVariantDefinition::Struct { name, fields } => {
                     let mut struct_size = 0;
                     for field in fields {
-                        let size = self.calculate_type_size(&field.type_info);
+                        let size = self.calculate_type_size(&field.type_info, sizing);
                         if let SizeInfo::Fixed(bytes) = size {
                             struct_size += bytes;
                         }
@@ -234,8 +417,7 @@ impl<'a> SizeCalculator<'a> {
         let total_size = discriminant_size + max_variant_size;
 
         // Calculate rent
-        let rent_lamports = (total_size + 128) as f64 * 6.96;
-        let rent_sol = rent_lamports / 1_000_000_000.0;
+        let rent_sol = rent_exempt_lamports(total_size) as f64 / 1_000_000_000.0;
 
         // Warnings
         if total_size > 10 * 1024 * 1024 {
@@ -256,29 +438,17 @@ impl<'a> SizeCalculator<'a> {
     }
 
     /// Calculate size for a type
-    fn calculate_type_size(&mut self, type_info: &TypeInfo) -> SizeInfo {
+    fn calculate_type_size(&self, type_info: &TypeInfo, sizing: &mut Vec<String>) -> SizeInfo {
         match type_info {
             TypeInfo::Primitive(type_name) => self.calculate_primitive_size(type_name),
+            TypeInfo::UserDefined(type_name) if sizing.contains(type_name) => SizeInfo::Variable {
+                min: 0,
+                reason: format!("Recursive type '{}'", type_name),
+            },
             TypeInfo::UserDefined(type_name) => {
-                // Check cache first
-                if let Some(cached) = self.size_cache.get(type_name) {
-                    return cached.clone();
-                }
-
-                // Find type definition and calculate
-                if let Some(type_def) = self.type_defs.iter().find(|t| t.name() == type_name) {
-                    let size = match type_def {
-                        TypeDefinition::Struct(s) => {
-                            let account_size = self.calculate_struct_size(s);
-                            account_size.total_bytes
-                        }
-                        TypeDefinition::Enum(e) => {
-                            let account_size = self.calculate_enum_size(e);
-                            account_size.total_bytes
-                        }
-                    };
-                    self.size_cache.insert(type_name.clone(), size.clone());
-                    size
+                let type_def = self.type_defs.iter().find(|t| t.name() == type_name);
+                if let Some(type_def) = type_def {
+                    self.size_of(type_def, sizing).total_bytes.clone()
                 } else {
                     // Unknown user-defined type, assume reasonable size
                     SizeInfo::Variable {
@@ -291,12 +461,43 @@ impl<'a> SizeCalculator<'a> {
                 // Vec<T> = 4 bytes (length) + variable data
                 SizeInfo::Variable {
                     min: 4,
-                    reason: format!("Vec length prefix + elements ({})", self.describe_type(inner)),
+                    reason: format!(
+                        "Vec length prefix + elements ({})",
+                        self.describe_type(inner)
+                    ),
+                }
+            }
+            TypeInfo::FixedArray(inner, len) => {
+                // [T; N] = N elements, no length prefix
+                match self.calculate_type_size(inner, sizing) {
+                    SizeInfo::Fixed(bytes) => SizeInfo::Fixed(bytes * len),
+                    SizeInfo::Variable { min, reason } => SizeInfo::Variable {
+                        min: min * len,
+                        reason,
+                    },
+                }
+            }
+            TypeInfo::Map(_, key, value) => {
+                // Map<K, V> = 4 bytes (entry count) + (K, V) per entry
+                let entry = match (
+                    self.calculate_type_size(key, sizing),
+                    self.calculate_type_size(value, sizing),
+                ) {
+                    (SizeInfo::Fixed(k), SizeInfo::Fixed(v)) => format!("{} bytes", k + v),
+                    (k, v) => format!("at least {} bytes", k.min_bytes() + v.min_bytes()),
+                };
+                SizeInfo::Variable {
+                    min: 4,
+                    reason: format!(
+                        "Map length prefix + {} per entry ({})",
+                        entry,
+                        self.describe_type(type_info)
+                    ),
                 }
             }
             TypeInfo::Option(inner) => {
                 // Option<T> = 1 byte (discriminant) + T
-                let inner_size = self.calculate_type_size(inner);
+                let inner_size = self.calculate_type_size(inner, sizing);
                 match inner_size {
                     SizeInfo::Fixed(bytes) => SizeInfo::Fixed(1 + bytes),
                     SizeInfo::Variable { min, reason } => SizeInfo::Variable {
@@ -347,6 +548,15 @@ impl<'a> SizeCalculator<'a> {
             },
             TypeInfo::UserDefined(name) => name.clone(),
             TypeInfo::Array(inner) => format!("Vec<{}>", self.describe_type(inner)),
+            TypeInfo::FixedArray(inner, len) => {
+                format!("[{}; {}]", self.describe_type(inner), len)
+            }
+            TypeInfo::Map(kind, key, value) => format!(
+                "{}<{}, {}>",
+                kind.as_str(),
+                self.describe_type(key),
+                self.describe_type(value)
+            ),
             TypeInfo::Option(inner) => format!("Option<{}>", self.describe_type(inner)),
         }
     }
@@ -370,7 +580,7 @@ impl SizeInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{FieldDefinition, Metadata, StructDefinition};
+    use crate::ir::{FieldDefinition, Metadata, StructDefinition, TypeRole};
 
     #[test]
     fn test_primitive_sizes() {
@@ -395,17 +605,19 @@ mod tests {
                     name: "wallet".to_string(),
                     type_info: TypeInfo::Primitive("PublicKey".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
                 FieldDefinition {
                     name: "score".to_string(),
                     type_info: TypeInfo::Primitive("u64".to_string()),
                     optional: false,
+                    attributes: vec![],
                 },
             ],
             metadata: Metadata::default(),
         })];
 
-        let mut calc = SizeCalculator::new(&type_defs);
+        let calc = SizeCalculator::new(&type_defs);
         let sizes = calc.calculate_all();
 
         assert_eq!(sizes.len(), 1);
@@ -421,14 +633,22 @@ mod tests {
                 name: "score".to_string(),
                 type_info: TypeInfo::Primitive("u64".to_string()),
                 optional: false,
+                attributes: vec![],
             }],
             metadata: Metadata {
                 solana: true,
                 attributes: vec!["account".to_string()],
+                role: TypeRole::Account,
+                zero_copy: false,
+                version: None,
+                lifecycle: Default::default(),
+                skip: Vec::new(),
+                docs: Vec::new(),
+                test_vectors: Vec::new(),
             },
         })];
 
-        let mut calc = SizeCalculator::new(&type_defs);
+        let calc = SizeCalculator::new(&type_defs);
         let sizes = calc.calculate_all();
 
         assert_eq!(sizes.len(), 1);
@@ -444,14 +664,161 @@ mod tests {
                 name: "maybe_value".to_string(),
                 type_info: TypeInfo::Option(Box::new(TypeInfo::Primitive("u64".to_string()))),
                 optional: true,
+                attributes: vec![],
             }],
             metadata: Metadata::default(),
         })];
 
-        let mut calc = SizeCalculator::new(&type_defs);
+        let calc = SizeCalculator::new(&type_defs);
         let sizes = calc.calculate_all();
 
         assert_eq!(sizes.len(), 1);
         assert_eq!(sizes[0].total_bytes.min_bytes(), 1 + 8); // discriminant + u64
     }
+
+    #[test]
+    fn test_fixed_array_size() {
+        let type_defs = vec![TypeDefinition::Struct(StructDefinition {
+            name: "Seeds".to_string(),
+            fields: vec![
+                FieldDefinition {
+                    name: "hash".to_string(),
+                    type_info: TypeInfo::FixedArray(
+                        Box::new(TypeInfo::Primitive("u8".to_string())),
+                        32,
+                    ),
+                    optional: false,
+                    attributes: vec![],
+                },
+                FieldDefinition {
+                    name: "labels".to_string(),
+                    type_info: TypeInfo::FixedArray(
+                        Box::new(TypeInfo::Primitive("String".to_string())),
+                        2,
+                    ),
+                    optional: false,
+                    attributes: vec![],
+                },
+            ],
+            metadata: Metadata::default(),
+        })];
+
+        let calc = SizeCalculator::new(&type_defs);
+        let sizes = calc.calculate_all();
+
+        assert!(matches!(
+            sizes[0].field_breakdown[0].size,
+            SizeInfo::Fixed(32)
+        ));
+        assert_eq!(sizes[0].field_breakdown[0].description, "[u8; 32]");
+        // Two strings, each with a 4-byte length prefix
+        assert!(!sizes[0].field_breakdown[1].size.is_fixed());
+        assert_eq!(sizes[0].total_bytes.min_bytes(), 32 + 2 * 4);
+    }
+
+    #[test]
+    fn test_map_size() {
+        let type_defs = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file(
+                "struct Ledger { balances: HashMap<PublicKey, u64>, names: BTreeMap<u32, String> }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let calc = SizeCalculator::new(&type_defs);
+        let sizes = calc.calculate_all();
+
+        let SizeInfo::Variable { min, reason } = &sizes[0].field_breakdown[0].size else {
+            panic!("maps are variable-sized")
+        };
+        assert_eq!(*min, 4);
+        assert!(reason.contains("40 bytes per entry"), "{}", reason);
+        let SizeInfo::Variable { reason, .. } = &sizes[0].field_breakdown[1].size else {
+            panic!("maps are variable-sized")
+        };
+        assert!(reason.contains("at least 8 bytes per entry"), "{}", reason);
+        assert_eq!(
+            sizes[0].field_breakdown[1].description,
+            "BTreeMap<u32, String (variable)>"
+        );
+    }
+
+    #[test]
+    fn test_recursive_map_size() {
+        let type_defs = crate::transform::transform_to_ir(
+            crate::parser::parse_lumos_file("struct Node { id: u64, children: HashMap<u8, Node> }")
+                .unwrap(),
+        )
+        .unwrap();
+
+        let sizes = SizeCalculator::new(&type_defs).calculate_all();
+        let SizeInfo::Variable { min, reason } = &sizes[0].field_breakdown[1].size else {
+            panic!("maps are variable-sized")
+        };
+        assert_eq!(*min, 4);
+        assert!(reason.contains("at least 1 bytes per entry"), "{}", reason);
+        assert_eq!(sizes[0].total_bytes.min_bytes(), 8 + 4);
+    }
+
+    #[test]
+    fn test_find_size_regressions() {
+        let type_defs = vec![
+            TypeDefinition::Struct(StructDefinition {
+                name: "Grown".to_string(),
+                fields: vec![FieldDefinition {
+                    name: "value".to_string(),
+                    type_info: TypeInfo::Primitive("u64".to_string()),
+                    optional: false,
+                    attributes: vec![],
+                }],
+                metadata: Metadata::default(),
+            }),
+            TypeDefinition::Struct(StructDefinition {
+                name: "New".to_string(),
+                fields: vec![],
+                metadata: Metadata::default(),
+            }),
+        ];
+
+        let sizes = SizeCalculator::new(&type_defs).calculate_all();
+        let baseline = HashMap::from([("Grown".to_string(), 4)]);
+
+        let regressions = find_size_regressions(&sizes, &baseline, 0);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].name, "Grown");
+        assert_eq!(regressions[0].growth(), 4);
+
+        assert!(find_size_regressions(&sizes, &baseline, 4).is_empty());
+    }
+
+    #[test]
+    fn test_rent_exempt_lamports() {
+        assert_eq!(rent_exempt_lamports(0), 890_880);
+        assert_eq!(rent_exempt_lamports(110), 1_656_480);
+    }
+
+    #[test]
+    fn test_project_rent() {
+        let type_defs = vec![TypeDefinition::Struct(StructDefinition {
+            name: "UserAccount".to_string(),
+            fields: vec![FieldDefinition {
+                name: "balance".to_string(),
+                type_info: TypeInfo::Primitive("u64".to_string()),
+                optional: false,
+                attributes: vec![],
+            }],
+            metadata: Metadata::default(),
+        })];
+
+        let sizes = SizeCalculator::new(&type_defs).calculate_all();
+
+        let projections = project_rent(&sizes, &[("UserAccount".to_string(), 100_000)]).unwrap();
+        assert_eq!(projections[0].total_bytes(), 800_000);
+        assert!((sizes[0].rent_sol - 0.00094656).abs() < 1e-12);
+        assert!((projections[0].total_rent_sol() - 94.656).abs() < 1e-6);
+
+        let unknown = project_rent(&sizes, &[("Missing".to_string(), 1)]).unwrap_err();
+        assert_eq!(unknown, vec!["Missing".to_string()]);
+    }
 }
@@ -0,0 +1,232 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Canonical example values from `#[test_vector(...)]`
+//!
+//! A struct can declare values it must encode to, one attribute per value:
+//!
+//! ```text
+//! #[solana]
+//! #[test_vector(owner = "11111111111111111111111111111111", balance = 5)]
+//! struct Vault { owner: PublicKey, balance: u64 }
+//! ```
+//!
+//! Each argument is `field = value`, with every field given and values
+//! written as JSON in the shapes [`crate::borsh_encoder`] accepts. The bytes
+//! are computed here, once: the Rust and TypeScript generators emit tests
+//! asserting the generated code serializes each value to exactly these
+//! bytes, and the docs generator shows them as examples.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::ir::TypeDefinition;
+//! use lumos_core::test_vector::test_vectors;
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "#[test_vector(amount = 5, memo = null)]\nstruct Deposit { amount: u16, memo: Option<String> }",
+//! )?)?;
+//!
+//! let TypeDefinition::Struct(deposit) = &ir[0] else { unreachable!() };
+//! let vectors = test_vectors(&ir, deposit)?;
+//! assert_eq!(vectors[0].bytes, [5, 0, 0]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::borsh_decoder::DecodedValue;
+use crate::borsh_encoder::BorshEncoder;
+use crate::ir::{StructDefinition, TypeDefinition, TypeInfo};
+use serde_json::{Map, Value};
+
+/// A value declared with `#[test_vector(...)]` and its Borsh encoding
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestVector {
+    /// The value as JSON
+    pub json: Value,
+
+    /// The value checked against the schema
+    pub value: DecodedValue,
+
+    /// Borsh encoding of the value
+    pub bytes: Vec<u8>,
+}
+
+/// The test vectors of `struct_def`, in declaration order
+///
+/// Errors name the vector (1-based) and what is wrong with it.
+pub fn test_vectors(
+    type_defs: &[TypeDefinition],
+    struct_def: &StructDefinition,
+) -> Result<Vec<TestVector>, String> {
+    let encoder = BorshEncoder::new(type_defs);
+    let type_info = TypeInfo::UserDefined(struct_def.name.clone());
+
+    struct_def
+        .metadata
+        .test_vectors
+        .iter()
+        .enumerate()
+        .map(|(index, args)| {
+            let fail = |message: String| {
+                format!(
+                    "#[test_vector] {} on '{}': {}",
+                    index + 1,
+                    struct_def.name,
+                    message
+                )
+            };
+            let json = parse_args(args).map_err(fail)?;
+            let value = encoder
+                .from_json(&type_info, &json, &struct_def.name)
+                .map_err(|e| fail(e.to_string()))?;
+            let bytes = encoder
+                .encode(&type_info, &value, &struct_def.name)
+                .map_err(|e| fail(e.to_string()))?;
+            Ok(TestVector { json, value, bytes })
+        })
+        .collect()
+}
+
+/// Parse `field = value, ...` into a JSON object
+///
+/// Values are JSON; `-` may be separated from the number it negates, as the
+/// attribute's tokens are rendered that way.
+pub fn parse_args(args: &str) -> Result<Value, String> {
+    let mut object = Map::new();
+    for arg in split_top_level(args, ',') {
+        let arg = arg.trim();
+        if arg.is_empty() {
+            continue;
+        }
+        let Some((name, value)) = arg.split_once('=') else {
+            return Err(format!("expected `field = value`, found `{}`", arg));
+        };
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!("`{}` is not a field name", name));
+        }
+        let value = join_signs(value.trim());
+        let value: Value = serde_json::from_str(&value)
+            .map_err(|_| format!("value of `{}` is not JSON: `{}`", name, value))?;
+        if object.insert(name.to_string(), value).is_some() {
+            return Err(format!("`{}` is given twice", name));
+        }
+    }
+    if object.is_empty() {
+        return Err("lists no fields, e.g. #[test_vector(balance = 5)]".to_string());
+    }
+    Ok(Value::Object(object))
+}
+
+/// Split `text` on `separator` outside brackets and string literals
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '[' | '{' | '(' => depth += 1,
+            ']' | '}' | ')' => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// `value` with whitespace after a `-` outside string literals removed
+fn join_signs(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut after_sign = false;
+
+    for c in value.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if after_sign && c.is_whitespace() {
+            continue;
+        } else if c == '"' {
+            in_string = true;
+        }
+        after_sign = !in_string && c == '-';
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+    use serde_json::json;
+
+    #[test]
+    fn parses_arguments_as_rendered_tokens() {
+        let args = r#"owner = "1111, 2" , balance = - 5 , tags = [1 , 2] , state = { "Frozen" : { "until" : 3 } }"#;
+        assert_eq!(
+            parse_args(args).unwrap(),
+            json!({
+                "owner": "1111, 2",
+                "balance": -5,
+                "tags": [1, 2],
+                "state": { "Frozen": { "until": 3 } }
+            })
+        );
+
+        assert!(parse_args("balance = 5, balance = 6")
+            .unwrap_err()
+            .contains("given twice"));
+        assert!(parse_args("balance").unwrap_err().contains("field = value"));
+        assert!(parse_args("memo = hello").unwrap_err().contains("not JSON"));
+        assert!(parse_args("").unwrap_err().contains("lists no fields"));
+    }
+
+    #[test]
+    fn encodes_vectors_with_the_schema() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                r#"
+                #[test_vector(owner = "11111111111111111111111111111111", balance = 5, delta = -1)]
+                #[test_vector(owner = "11111111111111111111111111111111", balance = 256, delta = 2)]
+                struct Vault { owner: PublicKey, balance: u64, delta: i8 }
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let TypeDefinition::Struct(vault) = &ir[0] else {
+            unreachable!()
+        };
+
+        let vectors = test_vectors(&ir, vault).unwrap();
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors[0].bytes[32..], [5, 0, 0, 0, 0, 0, 0, 0, 0xff]);
+        assert_eq!(vectors[1].bytes[32..], [0, 1, 0, 0, 0, 0, 0, 0, 2]);
+        assert_eq!(vectors[1].json["balance"], 256);
+    }
+}
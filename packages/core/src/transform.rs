@@ -64,14 +64,16 @@
 //! ```
 
 use crate::ast::{
-    EnumDef as AstEnum, EnumVariant as AstEnumVariant, FieldDef as AstField, Item as AstItem,
-    LumosFile, StructDef as AstStruct, TypeSpec as AstType,
+    Attribute, AttributeValue, EnumDef as AstEnum, EnumVariant as AstEnumVariant,
+    FieldDef as AstField, Item as AstItem, LumosFile, StructDef as AstStruct, TypeSpec as AstType,
 };
-use crate::error::Result;
+use crate::error::{LumosError, Result, SourceLocation};
 use crate::ir::{
-    EnumDefinition, EnumVariantDefinition, FieldDefinition, Metadata, StructDefinition,
-    TypeDefinition, TypeInfo,
+    self, EnumDefinition, EnumVariantDefinition, FieldDefinition, Lifecycle, MapKind, Metadata,
+    StructDefinition, TypeDefinition, TypeInfo, TypeRole, TARGETS,
 };
+use crate::{constants, evolution, layout_optimizer, test_vector, zero_copy};
+use std::collections::{HashMap, HashSet};
 
 /// Transform a parsed LUMOS file (AST) into Intermediate Representation (IR).
 ///
@@ -122,31 +124,133 @@ use crate::ir::{
 ///
 /// Returns [`crate::error::LumosError`] if transformation fails (rare, most validation happens in parser).
 pub fn transform_to_ir(file: LumosFile) -> Result<Vec<TypeDefinition>> {
+    let locations = Locations::new(&file);
+    let type_defs = transform_file(file, &locations)?;
+    validate_key_fields(&type_defs, &locations)?;
+    Ok(type_defs)
+}
+
+/// Like [`transform_to_ir`], but accepting `#[key]` fields at variable offsets
+///
+/// For [`crate::layout_optimizer`], whose suggestions move them to fixed ones.
+pub fn transform_for_layout(file: LumosFile) -> Result<Vec<TypeDefinition>> {
+    let locations = Locations::new(&file);
+    transform_file(file, &locations)
+}
+
+/// Where each type, field and variant of a file is defined
+///
+/// The IR carries no spans, so errors found in it are located by the path
+/// they name: `Type`, `Type.field`, `Enum::Variant` or `Enum::Variant.field`.
+struct Locations(HashMap<String, SourceLocation>);
+
+impl Locations {
+    fn new(file: &LumosFile) -> Self {
+        let mut locations = HashMap::new();
+        for item in &file.items {
+            let name = item.name();
+            let mut add = |path: String, span| {
+                if let Some(location) = file.location(name, span) {
+                    locations.insert(path, location);
+                }
+            };
+            add(name.to_string(), item.span());
+            match item {
+                AstItem::Struct(s) => {
+                    for field in &s.fields {
+                        add(format!("{}.{}", name, field.name), field.span);
+                    }
+                }
+                AstItem::Enum(e) => {
+                    for variant in &e.variants {
+                        let path = format!("{}::{}", name, variant.name());
+                        if let AstEnumVariant::Struct { fields, .. } = variant {
+                            for field in fields {
+                                add(format!("{}.{}", path, field.name), field.span);
+                            }
+                        }
+                        add(path, variant.span());
+                    }
+                }
+                AstItem::Const(_) => {}
+            }
+        }
+        Self(locations)
+    }
+
+    /// Location of `path`, or of the closest enclosing definition
+    fn of(&self, path: &str) -> Option<SourceLocation> {
+        let mut path = path;
+        loop {
+            if let Some(location) = self.0.get(path) {
+                return Some(location.clone());
+            }
+            path = &path[..path.rfind(['.', ':'])?];
+            path = path.trim_end_matches(':');
+        }
+    }
+
+    /// `error`, located at `path` unless it already has a location
+    fn locate(&self, error: LumosError, path: &str) -> LumosError {
+        error.or_located(self.of(path))
+    }
+}
+
+fn transform_file(mut file: LumosFile, locations: &Locations) -> Result<Vec<TypeDefinition>> {
+    if let Some(import) = file.imports.first() {
+        return Err(LumosError::SchemaParse(
+            format!(
+                "import \"{}\" can only be resolved when the schema is loaded from a file",
+                import.path
+            ),
+            Some(SourceLocation::new(import.line, 1)),
+        ));
+    }
+
     let mut type_defs = Vec::new();
 
+    constants::fold(&mut file)?;
+
     for item in file.items {
         match item {
             AstItem::Struct(struct_def) => {
-                let type_def = transform_struct(struct_def)?;
+                let name = struct_def.name.clone();
+                let type_def = transform_struct(struct_def, locations)
+                    .map_err(|e| locations.locate(e, &name))?;
                 type_defs.push(TypeDefinition::Struct(type_def));
             }
             AstItem::Enum(enum_def) => {
-                let type_def = transform_enum(enum_def)?;
+                let name = enum_def.name.clone();
+                let type_def =
+                    transform_enum(enum_def, locations).map_err(|e| locations.locate(e, &name))?;
                 type_defs.push(TypeDefinition::Enum(type_def));
             }
+            AstItem::Const(_) => {}
         }
     }
 
     // Validate user-defined type references
-    validate_user_defined_types(&type_defs)?;
+    validate_user_defined_types(&type_defs, locations)?;
+    validate_recursive_types(&type_defs, locations)?;
+    validate_instruction_accounts(&type_defs, locations)?;
+    validate_skipped_references(&type_defs, locations)?;
+    for type_def in &type_defs {
+        if let TypeDefinition::Struct(s) = type_def {
+            if zero_copy::is_zero_copy(s) {
+                zero_copy::layout(&type_defs, &s.name).map_err(|e| locations.locate(e, &s.name))?;
+            }
+            test_vector::test_vectors(&type_defs, s)
+                .map_err(|message| LumosError::TypeValidation(message, locations.of(&s.name)))?;
+        }
+    }
 
     Ok(type_defs)
 }
 
 /// Transform a single struct definition
-fn transform_struct(struct_def: AstStruct) -> Result<StructDefinition> {
+fn transform_struct(struct_def: AstStruct, locations: &Locations) -> Result<StructDefinition> {
     // Extract metadata from attributes BEFORE consuming struct
-    let metadata = extract_struct_metadata(&struct_def);
+    let metadata = extract_struct_metadata(&struct_def)?;
 
     let name = struct_def.name;
 
@@ -154,9 +258,85 @@ fn transform_struct(struct_def: AstStruct) -> Result<StructDefinition> {
     let fields = struct_def
         .fields
         .into_iter()
-        .map(transform_field)
+        .map(|field| {
+            let path = format!("{}.{}", name, field.name);
+            transform_field(field).map_err(|e| locations.locate(e, &path))
+        })
         .collect::<Result<Vec<_>>>()?;
 
+    validate_field_versions(&name, metadata.version, &fields)?;
+
+    let is_account = metadata.solana && metadata.is_account();
+    for field in &fields {
+        let location = format!("{}.{}", name, field.name);
+        let targets = skip_targets(field.get_attribute("skip"), &location)
+            .map_err(|e| locations.locate(e, &location))?;
+        if !targets.is_empty() && is_account {
+            return Err(LumosError::TypeValidation(
+                format!(
+                    "#[skip] on '{}' would give account '{}' a different layout in {}; skip the whole type instead",
+                    location,
+                    name,
+                    targets.join(" and ")
+                ),
+                locations.of(&location),
+            ));
+        }
+    }
+
+    for field in &fields {
+        let location = format!("{}.{}", name, field.name);
+        validate_codec(field, &location, metadata.zero_copy)
+            .map_err(|e| locations.locate(e, &location))?;
+        validate_constraints(field, &location)?;
+    }
+
+    if metadata.version.is_some() && metadata.zero_copy {
+        return Err(LumosError::TypeValidation(
+            format!(
+                "#[version] decoding shims need Borsh, so '{}' can't also be #[zero_copy]",
+                name
+            ),
+            None,
+        ));
+    }
+
+    if !metadata.test_vectors.is_empty() {
+        let skipped = fields
+            .iter()
+            .find(|field| TARGETS.iter().any(|target| field.skips(target)));
+        let conflict = match (metadata.zero_copy, skipped) {
+            (true, _) => Some("can't also be #[zero_copy]; vectors are Borsh bytes".to_string()),
+            (false, Some(field)) => Some(format!(
+                "can't #[skip] field '{}'; vectors are checked in every target",
+                field.name
+            )),
+            (false, None) => None,
+        };
+        if let Some(conflict) = conflict {
+            return Err(LumosError::TypeValidation(
+                format!("'{}' has #[test_vector] values, so it {}", name, conflict),
+                None,
+            ));
+        }
+    }
+
+    if metadata.has_display() {
+        let displayable = match fields.as_slice() {
+            [field] => matches!(&field.type_info, TypeInfo::Primitive(t) if t != "Keypair"),
+            _ => false,
+        };
+        if !displayable {
+            return Err(LumosError::TypeValidation(
+                format!(
+                    "#[display] on '{}' needs exactly one field of a primitive type (not Keypair)",
+                    name
+                ),
+                None,
+            ));
+        }
+    }
+
     Ok(StructDefinition {
         name,
         fields,
@@ -165,9 +345,35 @@ fn transform_struct(struct_def: AstStruct) -> Result<StructDefinition> {
 }
 
 /// Transform a single enum definition
-fn transform_enum(enum_def: AstEnum) -> Result<EnumDefinition> {
+fn transform_enum(enum_def: AstEnum, locations: &Locations) -> Result<EnumDefinition> {
+    for attribute in ["account", "version", zero_copy::ATTRIBUTE] {
+        if let Some(attr) = enum_def.get_attribute(attribute) {
+            return Err(LumosError::TypeValidation(
+                format!(
+                    "#[{}] applies to structs only, found on enum '{}'",
+                    attribute, enum_def.name
+                ),
+                attribute_location(attr),
+            ));
+        }
+    }
+
+    if let Some(attr) = enum_def
+        .attributes
+        .iter()
+        .find(|attr| LIFECYCLE_ATTRIBUTES.contains(&attr.name.as_str()))
+    {
+        return Err(LumosError::TypeValidation(
+            format!(
+                "#[{}] applies to #[account] structs only, found on enum '{}'",
+                attr.name, enum_def.name
+            ),
+            attribute_location(attr),
+        ));
+    }
+
     // Extract metadata from attributes BEFORE consuming enum
-    let metadata = extract_enum_metadata(&enum_def);
+    let metadata = extract_enum_metadata(&enum_def)?;
 
     let name = enum_def.name;
 
@@ -175,9 +381,46 @@ fn transform_enum(enum_def: AstEnum) -> Result<EnumDefinition> {
     let variants = enum_def
         .variants
         .into_iter()
-        .map(transform_enum_variant)
+        .map(|variant| {
+            let path = format!("{}::{}", name, variant.name());
+            transform_enum_variant(variant).map_err(|e| locations.locate(e, &path))
+        })
         .collect::<Result<Vec<_>>>()?;
 
+    if metadata.has_display()
+        && !variants
+            .iter()
+            .all(|v| matches!(v, EnumVariantDefinition::Unit { .. }))
+    {
+        return Err(LumosError::TypeValidation(
+            format!("#[display] on enum '{}' needs unit variants only", name),
+            None,
+        ));
+    }
+
+    for variant in &variants {
+        if let EnumVariantDefinition::Struct {
+            name: variant,
+            fields,
+        } = variant
+        {
+            for field in fields {
+                for attribute in ["sensitive", "skip", "codec", "key"] {
+                    if let Some(attr) = field.get_attribute(attribute) {
+                        return Err(LumosError::TypeValidation(
+                            format!(
+                                "#[{}] applies to struct fields only, found on '{}::{}.{}'",
+                                attribute, name, variant, field.name
+                            ),
+                            attribute_location(attr),
+                        ));
+                    }
+                }
+                validate_constraints(field, &format!("{}::{}.{}", name, variant, field.name))?;
+            }
+        }
+    }
+
     Ok(EnumDefinition {
         name,
         variants,
@@ -220,6 +463,16 @@ fn transform_enum_variant(variant: AstEnumVariant) -> Result<EnumVariantDefiniti
 fn transform_field(field: AstField) -> Result<FieldDefinition> {
     let name = field.name;
     let optional = field.optional;
+    let attributes = field.attributes;
+
+    if let Some(attr) = attributes.iter().find(|attr| attr.name == "sensitive") {
+        if attr.value.is_some() {
+            return Err(LumosError::TypeValidation(
+                format!("#[sensitive] on '{}' takes no arguments", name),
+                None,
+            ));
+        }
+    }
 
     // Transform type
     let type_info = transform_type(field.type_spec, optional)?;
@@ -228,6 +481,7 @@ fn transform_field(field: AstField) -> Result<FieldDefinition> {
         name,
         type_info,
         optional,
+        attributes,
     })
 }
 
@@ -252,11 +506,41 @@ fn transform_type(type_spec: AstType, optional: bool) -> Result<TypeInfo> {
             TypeInfo::Array(Box::new(inner_type))
         }
 
+        AstType::FixedArray(inner, len) => {
+            let inner_type = transform_type(*inner, false)?;
+            TypeInfo::FixedArray(Box::new(inner_type), len)
+        }
+
+        AstType::Map(kind, key, value) => {
+            let kind = MapKind::parse(&kind).ok_or_else(|| {
+                LumosError::TypeValidation(format!("Unknown map type '{}'", kind), None)
+            })?;
+            let key_name = key.as_string();
+            let key_type = transform_type(*key, false)?;
+            if !matches!(&key_type, TypeInfo::Primitive(name) if is_map_key(name)) {
+                return Err(LumosError::TypeValidation(
+                    format!(
+                        "{} keys must be integers, bool, String or PublicKey, found '{}'",
+                        kind.as_str(),
+                        key_name
+                    ),
+                    None,
+                ));
+            }
+            let value_type = transform_type(*value, false)?;
+            TypeInfo::Map(kind, Box::new(key_type), Box::new(value_type))
+        }
+
         AstType::UserDefined(name) => {
             // User-defined types are validated after full transformation
             // See validate_user_defined_types() called in transform_to_ir()
             TypeInfo::UserDefined(name)
         }
+
+        AstType::Option(inner) => {
+            let inner_type = transform_type(*inner, false)?;
+            TypeInfo::Option(Box::new(inner_type))
+        }
     };
 
     // Wrap in Option if optional
@@ -267,6 +551,11 @@ fn transform_type(type_spec: AstType, optional: bool) -> Result<TypeInfo> {
     }
 }
 
+/// Whether a primitive can key a map: hashable and totally ordered in Rust
+fn is_map_key(name: &str) -> bool {
+    !matches!(name, "f32" | "f64" | "Signature" | "Keypair")
+}
+
 /// Check if a type name is a valid primitive type
 fn is_valid_primitive_type(name: &str) -> bool {
     matches!(
@@ -305,26 +594,370 @@ fn map_type_alias(name: &str) -> String {
 }
 
 /// Extract metadata from struct attributes
-fn extract_struct_metadata(struct_def: &AstStruct) -> Metadata {
-    Metadata {
+fn extract_struct_metadata(struct_def: &AstStruct) -> Result<Metadata> {
+    let version = match struct_def.get_attribute("version") {
+        None => None,
+        Some(attr) => match &attr.value {
+            Some(AttributeValue::Integer(n)) if *n >= 1 && *n <= u32::MAX as u64 => Some(*n as u32),
+            _ => {
+                return Err(LumosError::TypeValidation(
+                    format!(
+                        "#[version] on '{}' must be a positive integer, e.g. #[version(2)]",
+                        struct_def.name
+                    ),
+                    None,
+                ))
+            }
+        },
+    };
+
+    let attributes: Vec<String> = struct_def
+        .attributes
+        .iter()
+        .map(|attr| attr.name.clone())
+        .collect();
+    Ok(Metadata {
         solana: struct_def.has_attribute("solana"),
-        attributes: struct_def
-            .attributes
-            .iter()
-            .map(|attr| attr.name.clone())
-            .collect(),
+        role: role(&attributes, &struct_def.name)?,
+        zero_copy: struct_def.has_attribute(zero_copy::ATTRIBUTE),
+        attributes,
+        version,
+        lifecycle: extract_lifecycle(struct_def)?,
+        skip: skip_targets(struct_def.get_attribute("skip"), &struct_def.name)?,
+        docs: ir::doc_lines(&struct_def.attributes),
+        test_vectors: test_vector_args(struct_def)?,
+    })
+}
+
+/// Argument lists of `#[test_vector(...)]`, checked against the schema in [`transform_file`]
+fn test_vector_args(struct_def: &AstStruct) -> Result<Vec<String>> {
+    struct_def
+        .attributes
+        .iter()
+        .filter(|attr| attr.name == "test_vector")
+        .map(|attr| match &attr.value {
+            Some(AttributeValue::String(args)) => Ok(args.clone()),
+            _ => Err(LumosError::TypeValidation(
+                format!(
+                    "#[test_vector] on '{}' lists field values, e.g. #[test_vector(balance = 5)]",
+                    struct_def.name
+                ),
+                None,
+            )),
+        })
+        .collect()
+}
+
+/// Type attributes declaring an account's lifecycle
+const LIFECYCLE_ATTRIBUTES: [&str; 4] = ["init", "init_space", "close", "realloc"];
+
+/// Extract `#[init(..)]`, `#[init_space]`, `#[close]` and `#[realloc]`
+fn extract_lifecycle(struct_def: &AstStruct) -> Result<Lifecycle> {
+    let name = &struct_def.name;
+    let mut lifecycle = Lifecycle::default();
+
+    for attr in &struct_def.attributes {
+        match (attr.name.as_str(), &attr.value) {
+            ("init", Some(AttributeValue::String(list))) => {
+                lifecycle.initialized_by = instruction_names("init", name, list)?;
+            }
+            ("init", _) => {
+                return Err(LumosError::TypeValidation(
+                    format!(
+                        "#[init] on '{}' names the instructions that create it, e.g. #[init(create_vault)]",
+                        name
+                    ),
+                    None,
+                ))
+            }
+            ("init_space", None) => lifecycle.init_space = true,
+            ("close", None) => lifecycle.closable = true,
+            ("close", Some(AttributeValue::String(list))) => {
+                lifecycle.closable = true;
+                lifecycle.closed_by = instruction_names("close", name, list)?;
+            }
+            ("realloc", None) => lifecycle.reallocable = true,
+            ("init_space" | "close" | "realloc", Some(_)) => {
+                return Err(LumosError::TypeValidation(
+                    format!("#[{}] on '{}' takes no arguments", attr.name, name),
+                    None,
+                ))
+            }
+            _ => {}
+        }
+    }
+
+    let is_account = struct_def.has_attribute("solana") && struct_def.has_attribute("account");
+    if !lifecycle.is_empty() && !is_account {
+        return Err(LumosError::TypeValidation(
+            format!(
+                "lifecycle attributes (#[init], #[init_space], #[close], #[realloc]) apply to #[solana] #[account] structs, found on '{}'",
+                name
+            ),
+            None,
+        ));
     }
+
+    Ok(lifecycle)
+}
+
+/// Parse the comma-separated instruction names of `#[init(..)]` or `#[close(..)]`
+fn instruction_names(attribute: &str, type_name: &str, list: &str) -> Result<Vec<String>> {
+    list.split(',')
+        .map(|name| {
+            let name = name.trim();
+            let valid = name
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if valid {
+                Ok(name.to_string())
+            } else {
+                Err(LumosError::TypeValidation(
+                    format!(
+                        "#[{}] on '{}' expects instruction names, found '{}'",
+                        attribute, type_name, name
+                    ),
+                    None,
+                ))
+            }
+        })
+        .collect()
 }
 
 /// Extract metadata from enum attributes
-fn extract_enum_metadata(enum_def: &AstEnum) -> Metadata {
-    Metadata {
+fn extract_enum_metadata(enum_def: &AstEnum) -> Result<Metadata> {
+    if enum_def.has_attribute("test_vector") {
+        return Err(LumosError::TypeValidation(
+            format!(
+                "#[test_vector] on '{}' only applies to structs; declare values of the enum in a struct field",
+                enum_def.name
+            ),
+            None,
+        ));
+    }
+    let attributes: Vec<String> = enum_def
+        .attributes
+        .iter()
+        .map(|attr| attr.name.clone())
+        .collect();
+    Ok(Metadata {
         solana: enum_def.has_attribute("solana"),
-        attributes: enum_def
-            .attributes
-            .iter()
-            .map(|attr| attr.name.clone())
+        role: role(&attributes, &enum_def.name)?,
+        zero_copy: false,
+        attributes,
+        version: None,
+        lifecycle: Lifecycle::default(),
+        skip: skip_targets(enum_def.get_attribute("skip"), &enum_def.name)?,
+        docs: ir::doc_lines(&enum_def.attributes),
+        test_vectors: Vec::new(),
+    })
+}
+
+/// The role declared by `#[account]`, `#[event]` or `#[instruction]`, at most one of them
+fn role(attributes: &[String], name: &str) -> Result<TypeRole> {
+    let mut declared: Vec<&str> = attributes
+        .iter()
+        .map(String::as_str)
+        .filter(|attr| TypeRole::from_attribute(attr).is_some())
+        .collect();
+    declared.dedup();
+    match declared.as_slice() {
+        [] => Ok(TypeRole::Plain),
+        [attr] => Ok(TypeRole::from_attribute(attr).unwrap_or_default()),
+        _ => Err(LumosError::TypeValidation(
+            format!(
+                "'{}' can't be both #[{}] and #[{}]",
+                name, declared[0], declared[1]
+            ),
+            None,
+        )),
+    }
+}
+
+/// Targets listed by a `#[skip(..)]` attribute on `location`, if it has one
+fn skip_targets(attr: Option<&Attribute>, location: &str) -> Result<Vec<String>> {
+    let Some(attr) = attr else {
+        return Ok(Vec::new());
+    };
+    let targets: Vec<String> = match &attr.value {
+        Some(AttributeValue::String(list)) => list
+            .split(',')
+            .map(|target| target.trim().to_string())
+            .filter(|target| !target.is_empty())
             .collect(),
+        _ => Vec::new(),
+    };
+
+    let fail = |message: String| Err(LumosError::TypeValidation(message, None));
+    if targets.is_empty() {
+        return fail(format!(
+            "#[skip] on '{}' names the targets to leave it out of, e.g. #[skip(typescript)]",
+            location
+        ));
+    }
+    if let Some(unknown) = targets.iter().find(|t| !TARGETS.contains(&t.as_str())) {
+        return fail(format!(
+            "#[skip] on '{}' names unknown target '{}' (expected {})",
+            location,
+            unknown,
+            TARGETS.join(" or ")
+        ));
+    }
+    if TARGETS
+        .iter()
+        .all(|target| targets.iter().any(|t| t == target))
+    {
+        return fail(format!(
+            "#[skip] on '{}' leaves it out of every target; remove it from the schema instead",
+            location
+        ));
+    }
+    Ok(targets)
+}
+
+/// Validate `#[codec(rust = "...", ts = "...")]` on the field at `location`
+///
+/// Every target the field is generated for needs a codec, or the targets
+/// would disagree on its layout.
+fn validate_codec(field: &FieldDefinition, location: &str, zero_copy: bool) -> Result<()> {
+    if !field.has_attribute("codec") {
+        return Ok(());
+    }
+    let fail = |message: String| Err(LumosError::TypeValidation(message, None));
+
+    let args = field.attribute_args("codec");
+    if args.is_empty() {
+        return fail(format!(
+            "#[codec] on '{}' names the hand-written codecs, e.g. #[codec(rust = \"crate::codecs::compact_u64\", ts = \"./codecs/compactU64\")]",
+            location
+        ));
+    }
+    if zero_copy {
+        return fail(format!(
+            "#[codec] on '{}' can't change the layout of a #[zero_copy] struct",
+            location
+        ));
+    }
+
+    let mut keys: Vec<&str> = Vec::new();
+    for arg in &args {
+        let parsed = arg.split_once('=').and_then(|(key, path)| {
+            let path = path.trim().strip_prefix('"')?.strip_suffix('"')?;
+            (!path.is_empty()).then_some((key.trim(), path))
+        });
+        let Some((key, path)) = parsed else {
+            return fail(format!(
+                "#[codec] on '{}': expected rust = \"path\" or ts = \"path\", found '{}'",
+                location, arg
+            ));
+        };
+        if key != "rust" && key != "ts" {
+            return fail(format!(
+                "#[codec] on '{}' names unknown target '{}' (expected rust or ts)",
+                location, key
+            ));
+        }
+        if keys.contains(&key) {
+            return fail(format!(
+                "#[codec] on '{}' names '{}' more than once",
+                location, key
+            ));
+        }
+        if key == "rust" && syn::parse_str::<syn::Path>(path).is_err() {
+            return fail(format!(
+                "#[codec] on '{}': '{}' is not a Rust module path",
+                location, path
+            ));
+        }
+        keys.push(key);
+    }
+
+    for target in TARGETS {
+        if !field.skips(target) && field.codec(target).is_none() {
+            return fail(format!(
+                "#[codec] on '{}' has no {} codec; every generated target must agree on the layout",
+                location,
+                if target == "typescript" { "ts" } else { target }
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validate `#[since(n)]` on the fields of a struct at schema version `version`
+///
+/// Borsh data carries no field names, so older accounts only stay readable
+/// when new fields are appended. Fields added after version 1 also need a
+/// value to fill in when an old account is upgraded, so they must be optional
+/// or of a type with an obvious default (a primitive or an array).
+fn validate_field_versions(
+    struct_name: &str,
+    version: Option<u32>,
+    fields: &[FieldDefinition],
+) -> Result<()> {
+    let mut previous = 1;
+
+    for field in fields {
+        if let Some(attr) = field.get_attribute("since") {
+            let Some(version) = version else {
+                return Err(LumosError::TypeValidation(
+                    format!(
+                        "'{}.{}' uses #[since] but struct '{}' has no #[version(n)]",
+                        struct_name, field.name, struct_name
+                    ),
+                    None,
+                ));
+            };
+            let valid = matches!(
+                attr.value,
+                Some(AttributeValue::Integer(n)) if n >= 2 && n <= version as u64
+            );
+            if !valid {
+                return Err(LumosError::TypeValidation(
+                    format!(
+                        "#[since] on '{}.{}' must be a version from 2 to {}",
+                        struct_name, field.name, version
+                    ),
+                    None,
+                ));
+            }
+        }
+
+        let since = field.since_version();
+        if since < previous {
+            return Err(LumosError::TypeValidation(
+                format!(
+                    "'{}.{}' (version {}) follows a field added in version {}; append new fields at the end so older accounts still decode",
+                    struct_name, field.name, since, previous
+                ),
+                None,
+            ));
+        }
+        if since > 1 && !has_upgrade_default(&field.type_info) {
+            return Err(LumosError::TypeValidation(
+                format!(
+                    "'{}.{}' was added in version {} but has no default for upgraded accounts; make it optional",
+                    struct_name, field.name, since
+                ),
+                None,
+            ));
+        }
+        previous = since;
+    }
+
+    Ok(())
+}
+
+/// Whether a field of this type can be filled in when upgrading an old account
+fn has_upgrade_default(type_info: &TypeInfo) -> bool {
+    match type_info {
+        TypeInfo::Option(_) | TypeInfo::Array(_) | TypeInfo::Map(..) => true,
+        // `Default` is only implemented for arrays of up to 32 elements
+        TypeInfo::FixedArray(inner, len) => *len <= 32 && has_upgrade_default(inner),
+        TypeInfo::Primitive(name) => name != "Keypair",
+        TypeInfo::UserDefined(_) => false,
     }
 }
 
@@ -350,7 +983,7 @@ fn extract_enum_metadata(enum_def: &AstEnum) -> Metadata {
 ///     inventory: UndefinedType  // Error: UndefinedType not found
 /// }
 /// ```
-fn validate_user_defined_types(type_defs: &[TypeDefinition]) -> Result<()> {
+fn validate_user_defined_types(type_defs: &[TypeDefinition], locations: &Locations) -> Result<()> {
     use std::collections::HashSet;
 
     // Collect all defined type names
@@ -362,7 +995,8 @@ fn validate_user_defined_types(type_defs: &[TypeDefinition]) -> Result<()> {
             TypeDefinition::Struct(s) => {
                 // Validate struct fields
                 for field in &s.fields {
-                    validate_type_info(&field.type_info, &defined_types, &s.name, &field.name)?;
+                    validate_type_info(&field.type_info, &defined_types, &s.name, &field.name)
+                        .map_err(|e| locations.locate(e, &format!("{}.{}", s.name, field.name)))?;
                 }
             }
             TypeDefinition::Enum(e) => {
@@ -376,7 +1010,10 @@ fn validate_user_defined_types(type_defs: &[TypeDefinition]) -> Result<()> {
                             // Validate tuple variant types
                             for (idx, type_info) in types.iter().enumerate() {
                                 let context = format!("{}.{}[{}]", e.name, name, idx);
-                                validate_type_info(type_info, &defined_types, &context, "")?;
+                                validate_type_info(type_info, &defined_types, &context, "")
+                                    .map_err(|err| {
+                                        locations.locate(err, &format!("{}::{}", e.name, name))
+                                    })?;
                             }
                         }
                         EnumVariantDefinition::Struct { name, fields } => {
@@ -388,18 +1025,376 @@ fn validate_user_defined_types(type_defs: &[TypeDefinition]) -> Result<()> {
                                     &defined_types,
                                     &context,
                                     &field.name,
-                                )?;
+                                )
+                                .map_err(|err| {
+                                    let path = format!("{}::{}.{}", e.name, name, field.name);
+                                    locations.locate(err, &path)
+                                })?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Source location of `attr`, for errors about where it is used
+fn attribute_location(attr: &Attribute) -> Option<SourceLocation> {
+    attr.span
+        .map(|span| SourceLocation::new(span.line, span.column + 1))
+}
+
+/// Check that `#[min]`/`#[max]` on the field at `location` bound a `String` or
+/// `Vec`, and `#[range]` an integer that can hold both ends
+fn validate_constraints(field: &FieldDefinition, location: &str) -> Result<()> {
+    fn bounded(type_info: &TypeInfo) -> bool {
+        match type_info {
+            TypeInfo::Primitive(name) => name == "String",
+            TypeInfo::Array(_) => true,
+            TypeInfo::Option(inner) => bounded(inner),
+            _ => false,
+        }
+    }
+
+    /// Values an integer type can hold
+    fn integer_bounds(type_info: &TypeInfo) -> Option<(i128, i128)> {
+        match type_info {
+            TypeInfo::Primitive(name) => Some(match name.as_str() {
+                "u8" => (0, u8::MAX.into()),
+                "u16" => (0, u16::MAX.into()),
+                "u32" => (0, u32::MAX.into()),
+                "u64" => (0, u64::MAX.into()),
+                "u128" => (0, i128::MAX),
+                "i8" => (i8::MIN.into(), i8::MAX.into()),
+                "i16" => (i16::MIN.into(), i16::MAX.into()),
+                "i32" => (i32::MIN.into(), i32::MAX.into()),
+                "i64" => (i64::MIN.into(), i64::MAX.into()),
+                "i128" => (i128::MIN, i128::MAX),
+                _ => return None,
+            }),
+            TypeInfo::Option(inner) => integer_bounds(inner),
+            _ => None,
+        }
+    }
+
+    let error = |attr: &Attribute, message: String| {
+        Err(LumosError::TypeValidation(
+            message,
+            attribute_location(attr),
+        ))
+    };
+    let signature = evolution::signature(&field.type_info);
+    let constraints = field.constraints();
+
+    for name in ["min", "max"] {
+        if let Some(attr) = field.get_attribute(name) {
+            if !bounded(&field.type_info) {
+                return error(
+                    attr,
+                    format!(
+                        "#[{}] on '{}' bounds String and Vec fields, found {}{}",
+                        name,
+                        location,
+                        signature,
+                        match integer_bounds(&field.type_info) {
+                            Some(_) => "; bound integers with #[range(min, max)]",
+                            None => "",
+                        }
+                    ),
+                );
+            }
+        }
+    }
+    if let (Some(min), Some(max)) = (constraints.min_len, constraints.max_len) {
+        if min > max {
+            return error(
+                field.get_attribute("min").expect("min is set"),
+                format!(
+                    "#[min({})] on '{}' is above its #[max({})]",
+                    min, location, max
+                ),
+            );
+        }
+    }
+
+    if let Some(attr) = field.get_attribute("range") {
+        let Some((lowest, highest)) = integer_bounds(&field.type_info) else {
+            return error(
+                attr,
+                format!(
+                    "#[range] on '{}' bounds integer fields, found {}{}",
+                    location,
+                    signature,
+                    match bounded(&field.type_info) {
+                        true => "; bound lengths with #[min(n)] and #[max(n)]",
+                        false => "",
+                    }
+                ),
+            );
+        };
+        let Some((min, max)) = constraints.range else {
+            return error(
+                attr,
+                format!("#[range] on '{}' expects #[range(min, max)]", location),
+            );
+        };
+        if min > max {
+            return error(
+                attr,
+                format!("#[range({}, {})] on '{}' is empty", min, max, location),
+            );
+        }
+        if min < lowest || max > highest {
+            return error(
+                attr,
+                format!(
+                    "#[range({}, {})] on '{}' goes beyond what {} holds",
+                    min, max, location, signature
+                ),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Check that every `#[key]` field is at a fixed offset
+///
+/// Key fields are what `memcmp` filters match on, so every field before them
+/// must have the same size in every value.
+fn validate_key_fields(type_defs: &[TypeDefinition], locations: &Locations) -> Result<()> {
+    for type_def in type_defs {
+        let TypeDefinition::Struct(s) = type_def else {
+            continue;
+        };
+        let mut variable: Option<&FieldDefinition> = None;
+        for field in s.data_fields() {
+            if let (Some(attr), Some(before)) = (field.get_attribute("key"), variable) {
+                return Err(LumosError::TypeValidation(
+                    format!(
+                        "#[key] on '{}.{}' needs a fixed offset, but '{}' before it is {}, which varies in size; move the key field before it",
+                        s.name,
+                        field.name,
+                        before.name,
+                        evolution::signature(&before.type_info)
+                    ),
+                    attribute_location(attr),
+                )
+                .or_located(locations.of(&s.name)));
+            }
+            if variable.is_none()
+                && layout_optimizer::borsh_size(type_defs, &field.type_info, &mut Vec::new())
+                    .is_none()
+            {
+                variable = Some(field);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the accounts `#[instruction]` structs take
+///
+/// A `#[signer]` is a `PublicKey`; an `#[account(...)]` is either a
+/// `PublicKey`, left unchecked, or an `#[account]` type.
+fn validate_instruction_accounts(
+    type_defs: &[TypeDefinition],
+    locations: &Locations,
+) -> Result<()> {
+    for type_def in type_defs {
+        let TypeDefinition::Struct(s) = type_def else {
+            continue;
+        };
+        for field in s.instruction_accounts() {
+            let location = format!("{}.{}", s.name, field.name);
+            let valid = match &field.type_info {
+                TypeInfo::Primitive(name) => name == "PublicKey",
+                TypeInfo::UserDefined(name) => {
+                    !field.has_attribute("signer")
+                        && type_defs.iter().any(|t| t.name() == name && t.is_account())
+                }
+                _ => false,
+            };
+            if !valid {
+                let expected = if field.has_attribute("signer") {
+                    "a PublicKey"
+                } else {
+                    "a PublicKey or an #[account] type"
+                };
+                return Err(LumosError::TypeValidation(
+                    format!(
+                        "Instruction account '{}' must be {}, found {}",
+                        location,
+                        expected,
+                        evolution::signature(&field.type_info)
+                    ),
+                    locations.of(&location),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that nothing generated for a target uses a type `#[skip]`ped from it
+fn validate_skipped_references(type_defs: &[TypeDefinition], locations: &Locations) -> Result<()> {
+    for target in TARGETS {
+        let skipped: Vec<&str> = type_defs
+            .iter()
+            .filter(|t| t.metadata().skips(target))
+            .map(|t| t.name())
+            .collect();
+        if skipped.is_empty() {
+            continue;
+        }
+
+        for type_def in ir::for_target(type_defs, target).iter() {
+            // (where the type is used, what to skip to fix it)
+            let mut uses: Vec<(String, String, &TypeInfo)> = Vec::new();
+            match type_def {
+                TypeDefinition::Struct(s) => {
+                    for field in &s.fields {
+                        let location = format!("{}.{}", s.name, field.name);
+                        uses.push((location.clone(), location, &field.type_info));
+                    }
+                }
+                TypeDefinition::Enum(e) => {
+                    for variant in &e.variants {
+                        let prefix = format!("{}::{}", e.name, variant.name());
+                        match variant {
+                            EnumVariantDefinition::Unit { .. } => {}
+                            EnumVariantDefinition::Tuple { types, .. } => {
+                                for (index, type_info) in types.iter().enumerate() {
+                                    let location = format!("{}.{}", prefix, index);
+                                    uses.push((location, e.name.clone(), type_info));
+                                }
+                            }
+                            EnumVariantDefinition::Struct { fields, .. } => {
+                                for field in fields {
+                                    let location = format!("{}.{}", prefix, field.name);
+                                    uses.push((location, e.name.clone(), &field.type_info));
+                                }
                             }
                         }
                     }
                 }
             }
+
+            for (location, fix, type_info) in uses {
+                if let Some(used) = user_type(type_info).filter(|used| skipped.contains(used)) {
+                    return Err(LumosError::TypeValidation(
+                        format!(
+                            "'{}' uses '{}', which is #[skip({})]; skip '{}' for {} too, or generate '{}' there",
+                            location, used, target, fix, target, used
+                        ),
+                        locations.of(&location),
+                    ));
+                }
+            }
         }
     }
 
     Ok(())
 }
 
+/// Check that no type contains itself without a `Vec` or map in between
+///
+/// `Option` and fixed arrays store their values inline, so a type reaching
+/// itself through them alone would have infinite size.
+fn validate_recursive_types(type_defs: &[TypeDefinition], locations: &Locations) -> Result<()> {
+    fn inline<'a>(type_info: &'a TypeInfo, names: &mut Vec<&'a str>) {
+        match type_info {
+            TypeInfo::UserDefined(name) => names.push(name),
+            TypeInfo::FixedArray(inner, _) | TypeInfo::Option(inner) => inline(inner, names),
+            TypeInfo::Primitive(_) | TypeInfo::Array(_) | TypeInfo::Map(..) => {}
+        }
+    }
+
+    // (where the type is used, the type used), for each type
+    let mut contains: HashMap<&str, Vec<(String, &str)>> = HashMap::new();
+    for type_def in type_defs {
+        let mut uses = |location: String, type_info| {
+            let mut names = Vec::new();
+            inline(type_info, &mut names);
+            for name in names {
+                contains
+                    .entry(type_def.name())
+                    .or_default()
+                    .push((location.clone(), name));
+            }
+        };
+        match type_def {
+            TypeDefinition::Struct(s) => {
+                for field in &s.fields {
+                    uses(format!("{}.{}", s.name, field.name), &field.type_info);
+                }
+            }
+            TypeDefinition::Enum(e) => {
+                for variant in &e.variants {
+                    let prefix = format!("{}::{}", e.name, variant.name());
+                    match variant {
+                        EnumVariantDefinition::Unit { .. } => {}
+                        EnumVariantDefinition::Tuple { types, .. } => {
+                            for (index, type_info) in types.iter().enumerate() {
+                                uses(format!("{}.{}", prefix, index), type_info);
+                            }
+                        }
+                        EnumVariantDefinition::Struct { fields, .. } => {
+                            for field in fields {
+                                uses(format!("{}.{}", prefix, field.name), &field.type_info);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    for type_def in type_defs {
+        let start = type_def.name();
+        // Depth-first, keeping the locations that led to each type
+        let mut stack: Vec<(&str, Vec<&str>)> = vec![(start, Vec::new())];
+        let mut seen: HashSet<&str> = HashSet::new();
+        while let Some((name, via)) = stack.pop() {
+            for (location, used) in contains.get(name).into_iter().flatten() {
+                let mut via = via.clone();
+                via.push(location);
+                if *used == start {
+                    return Err(LumosError::TypeValidation(
+                        format!(
+                            "'{}' contains itself ({}) with no Vec or map in between, so it would have infinite size; make one of these fields a Vec",
+                            start,
+                            via.join(" -> ")
+                        ),
+                        locations.of(via[0]),
+                    ));
+                }
+                if seen.insert(used) {
+                    stack.push((used, via));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Name of the user-defined type `type_info` holds, if any
+fn user_type(type_info: &TypeInfo) -> Option<&str> {
+    match type_info {
+        TypeInfo::Primitive(_) => None,
+        TypeInfo::UserDefined(name) => Some(name),
+        TypeInfo::Array(inner)
+        | TypeInfo::FixedArray(inner, _)
+        | TypeInfo::Option(inner)
+        | TypeInfo::Map(_, _, inner) => user_type(inner),
+    }
+}
+
 /// Recursively validate a TypeInfo against defined types
 ///
 /// # Arguments
@@ -414,8 +1409,6 @@ fn validate_type_info(
     parent_context: &str,
     field_name: &str,
 ) -> Result<()> {
-    use crate::error::LumosError;
-
     match type_info {
         TypeInfo::Primitive(_) => {
             // Primitive types are always valid
@@ -434,15 +1427,19 @@ fn validate_type_info(
                         "Undefined type '{}' referenced in '{}'",
                         type_name, location
                     ),
-                    None, // TODO: Add actual source location from AST spans
+                    None,
                 ));
             }
             Ok(())
         }
-        TypeInfo::Array(inner) => {
+        TypeInfo::Array(inner) | TypeInfo::FixedArray(inner, _) => {
             // Recursively validate array element type
             validate_type_info(inner, defined_types, parent_context, field_name)
         }
+        TypeInfo::Map(_, _, value) => {
+            // Keys are primitives; validate the value type
+            validate_type_info(value, defined_types, parent_context, field_name)
+        }
         TypeInfo::Option(inner) => {
             // Recursively validate optional type
             validate_type_info(inner, defined_types, parent_context, field_name)
@@ -454,6 +1451,7 @@ fn validate_type_info(
 mod tests {
     use super::*;
     use crate::parser::parse_lumos_file;
+    use std::borrow::Cow;
 
     #[test]
     fn test_transform_simple_struct() {
@@ -802,6 +1800,31 @@ mod tests {
         assert_eq!(ir.len(), 3);
     }
 
+    #[test]
+    fn test_validate_recursive_types() {
+        let err = transform_to_ir(
+            parse_lumos_file("struct Node {\n    id: u64,\n    next: Option<Node>,\n}").unwrap(),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("'Node' contains itself (Node.next) with no Vec or map in between"));
+        assert_eq!(err.location().map(|loc| loc.line), Some(3));
+
+        let err = transform_to_ir(
+            parse_lumos_file("struct A { b: [B; 2] }\nenum B { Leaf, Branch { a: A } }").unwrap(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("(A.b -> B::Branch.a)"));
+
+        // A Vec or map in between keeps the size finite
+        assert!(transform_to_ir(
+            parse_lumos_file("struct Tree { children: Vec<Tree>, index: HashMap<u8, Tree> }",)
+                .unwrap()
+        )
+        .is_ok());
+    }
+
     #[test]
     fn test_validate_nested_user_defined_types() {
         let input = r#"
@@ -852,4 +1875,574 @@ mod tests {
         // Should succeed - all primitive types
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_transform_versioned_struct() {
+        let input = r#"
+            #[solana]
+            #[version(2)]
+            struct Profile {
+                owner: PublicKey,
+                #[since(2)]
+                bio: Option<String>,
+            }
+        "#;
+
+        let ir = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap();
+        let TypeDefinition::Struct(profile) = &ir[0] else {
+            panic!("Expected struct");
+        };
+        assert_eq!(profile.metadata.version, Some(2));
+        assert_eq!(profile.fields[1].since_version(), 2);
+        assert_eq!(profile.at_version(1).fields.len(), 1);
+    }
+
+    #[test]
+    fn test_validate_field_versions() {
+        let errors = [
+            ("struct A { #[since(2)] x: u8 }", "has no #[version(n)]"),
+            (
+                "#[version(2)] struct A { #[since(3)] x: u8 }",
+                "must be a version from 2 to 2",
+            ),
+            (
+                "#[version(2)] struct A { #[since(2)] x: u8, y: u8 }",
+                "append new fields at the end",
+            ),
+            (
+                "struct B { x: u8 } #[version(2)] struct A { #[since(2)] b: B }",
+                "make it optional",
+            ),
+            ("#[version(0)] struct A { x: u8 }", "positive integer"),
+            ("#[version(2)] enum E { A }", "structs only"),
+        ];
+
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", input, err);
+        }
+    }
+
+    #[test]
+    fn test_validate_sensitive_fields() {
+        let ir =
+            transform_to_ir(parse_lumos_file("struct A { #[sensitive] email: String }").unwrap())
+                .unwrap();
+        let TypeDefinition::Struct(a) = &ir[0] else {
+            panic!("expected a struct")
+        };
+        assert!(a.fields[0].is_sensitive());
+
+        let errors = [
+            ("struct A { #[sensitive(pii)] x: u8 }", "takes no arguments"),
+            (
+                "enum E { Contact { #[sensitive] email: String } }",
+                "found on 'E::Contact.email'",
+            ),
+        ];
+
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", input, err);
+        }
+    }
+
+    #[test]
+    fn test_validate_skip_targets() {
+        let source = r#"
+            #[skip(typescript)]
+            struct Scratch { cursor: u64 }
+
+            struct Order {
+                id: u64,
+                #[skip(typescript)]
+                scratch: Scratch,
+            }
+        "#;
+        let ir = transform_to_ir(parse_lumos_file(source).unwrap()).unwrap();
+        assert_eq!(ir[0].metadata().skip, vec!["typescript".to_string()]);
+
+        let typescript = ir::for_target(&ir, "typescript");
+        assert_eq!(typescript.len(), 1);
+        let TypeDefinition::Struct(order) = &typescript[0] else {
+            panic!("expected a struct")
+        };
+        assert_eq!(order.fields.len(), 1);
+        assert!(matches!(ir::for_target(&ir, "rust"), Cow::Borrowed(_)));
+
+        let errors = [
+            (
+                "#[skip(typescript)] struct S { x: u8 } struct O { s: [S] }",
+                "'O.s' uses 'S', which is #[skip(typescript)]; skip 'O.s' for typescript too",
+            ),
+            (
+                "#[skip(rust)] struct S { x: u8 } enum E { A(Option<S>) }",
+                "'E::A.0' uses 'S', which is #[skip(rust)]; skip 'E' for rust too",
+            ),
+            ("#[skip] struct S { x: u8 }", "names the targets"),
+            (
+                "#[skip(python)] struct S { x: u8 }",
+                "unknown target 'python'",
+            ),
+            (
+                "#[skip(rust, typescript)] struct S { x: u8 }",
+                "every target",
+            ),
+            (
+                "#[solana] #[account] struct V { #[skip(typescript)] x: u8 }",
+                "skip the whole type instead",
+            ),
+            (
+                "enum E { A { #[skip(rust)] x: u8 } }",
+                "#[skip] applies to struct fields only",
+            ),
+        ];
+
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", input, err);
+        }
+    }
+
+    #[test]
+    fn test_validate_codecs() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                r#"struct A { #[codec(rust = "crate::codecs::varint", ts = "./codecs/varint")] x: u64 }
+                struct B { #[skip(typescript)] #[codec(rust = "codecs::varint")] x: u64 }"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let TypeDefinition::Struct(a) = &ir[0] else {
+            panic!("expected a struct");
+        };
+        assert_eq!(
+            a.fields[0].codec("rust").as_deref(),
+            Some("crate::codecs::varint")
+        );
+        assert_eq!(
+            a.fields[0].codec("typescript").as_deref(),
+            Some("./codecs/varint")
+        );
+
+        let errors = [
+            (
+                "struct A { #[codec] x: u64 }",
+                "names the hand-written codecs",
+            ),
+            (
+                r#"struct A { #[codec(rust = "a::b")] x: u64 }"#,
+                "#[codec] on 'A.x' has no ts codec",
+            ),
+            (
+                r#"struct A { #[codec(rust = "a::b", ts = "./b", py = "b")] x: u64 }"#,
+                "unknown target 'py'",
+            ),
+            (
+                r#"struct A { #[codec(rust = "not a path", ts = "./b")] x: u64 }"#,
+                "'not a path' is not a Rust module path",
+            ),
+            (
+                r#"struct A { #[codec(rust = a::b, ts = "./b")] x: u64 }"#,
+                "expected rust = \"path\"",
+            ),
+            (
+                r#"#[zero_copy] struct A { #[codec(rust = "a::b", ts = "./b")] x: u64 }"#,
+                "#[zero_copy]",
+            ),
+            (
+                r#"enum E { V { #[codec(rust = "a::b", ts = "./b")] x: u64 } }"#,
+                "#[codec] applies to struct fields only",
+            ),
+        ];
+
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", input, err);
+        }
+    }
+
+    #[test]
+    fn test_errors_report_source_locations() {
+        let errors = [
+            (
+                "struct A {\n    owner: Owner,\n}",
+                "Undefined type 'Owner' referenced in 'A.owner' (at 2:5)",
+            ),
+            (
+                "enum E {\n    V(u8),\n    W { inner: Missing },\n}",
+                "Undefined type 'Missing' referenced in 'E.W.inner' (at 3:9)",
+            ),
+            (
+                "enum E {\n    V(Missing),\n}",
+                "Undefined type 'Missing' referenced in 'E.V[0]' (at 2:5)",
+            ),
+            (
+                "struct A {\n    #[sensitive(yes)]\n    pin: u32,\n}",
+                "#[sensitive] on 'pin' takes no arguments (at 3:5)",
+            ),
+            (
+                "#[solana]\n#[init]\nstruct Vault { balance: u64 }",
+                "(at 3:8)",
+            ),
+            (
+                "#[zero_copy]\nstruct Pod {\n    a: u8,\n    b: u64,\n}",
+                "(at 2:8)",
+            ),
+            (
+                "const LEN: u8 = 300;\nstruct A { #[max(LEN)] name: String }",
+                "const 'LEN' = 300 overflows u8 (at 1:7)",
+            ),
+        ];
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().ends_with(expected), "{}: {}", input, err);
+        }
+    }
+
+    #[test]
+    fn test_validate_attribute_placement() {
+        transform_to_ir(
+            parse_lumos_file(
+                "struct Ok { #[key] id: u64, #[key] owner: PublicKey, #[max(32)] name: String, #[max(8)] tags: [u8], #[max(4)] note: Option<String> }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let errors = [
+            (
+                "#[solana]\n#[account]\nenum Side { Bid, Ask }",
+                "#[account] applies to structs only, found on enum 'Side' (at 2:3)",
+            ),
+            (
+                "struct A {\n    #[max(8)] count: u32,\n}",
+                "#[max] on 'A.count' bounds String and Vec fields, found u32; bound integers with #[range(min, max)] (at 2:7)",
+            ),
+            (
+                "enum E { V { #[max(8)] ids: [u8; 4] } }",
+                "#[max] on 'E::V.ids' bounds String and Vec fields, found [u8; 4]",
+            ),
+            (
+                "struct A { name: String, #[key] owner: PublicKey }",
+                "#[key] on 'A.owner' needs a fixed offset, but 'name' before it is String",
+            ),
+            (
+                "enum Tag { A(u8), B }\nstruct A { tag: Tag, #[key] owner: PublicKey }",
+                "'tag' before it is Tag",
+            ),
+            (
+                "enum E { V { #[key] id: u64 } }",
+                "#[key] applies to struct fields only, found on 'E::V.id'",
+            ),
+        ];
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", input, err);
+        }
+
+        let misplaced_key = parse_lumos_file("struct A { name: String, #[key] owner: PublicKey }");
+        assert!(transform_for_layout(misplaced_key.unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_constraints() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                "const LIMIT: u16 = 100;\n\
+                 struct A { #[min(1)] #[max(LIMIT)] name: String, #[range(-LIMIT, LIMIT * 2)] delta: Option<i16>, plain: u8 }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let TypeDefinition::Struct(a) = &ir[0] else {
+            panic!("expected struct");
+        };
+        assert!(a.has_constraints());
+        let constraints = a.fields[0].constraints();
+        assert_eq!(
+            (constraints.min_len, constraints.max_len),
+            (Some(1), Some(100))
+        );
+        assert_eq!(a.fields[1].constraints().range, Some((-100, 200)));
+        assert!(a.fields[2].constraints().is_empty());
+
+        let errors = [
+            (
+                "struct A { #[range(1, 10)] name: String }",
+                "#[range] on 'A.name' bounds integer fields, found String; bound lengths with #[min(n)] and #[max(n)]",
+            ),
+            (
+                "struct A { #[min(2)] flag: bool }",
+                "#[min] on 'A.flag' bounds String and Vec fields, found bool",
+            ),
+            (
+                "struct A { #[min(8)] #[max(4)] tags: Vec<u8> }",
+                "#[min(8)] on 'A.tags' is above its #[max(4)]",
+            ),
+            (
+                "struct A { #[range(10, 1)] level: u8 }",
+                "#[range(10, 1)] on 'A.level' is empty",
+            ),
+            (
+                "struct A { #[range(-1, 300)] level: u8 }",
+                "#[range(-1, 300)] on 'A.level' goes beyond what u8 holds",
+            ),
+            (
+                "struct A { #[range(5)] level: u8 }",
+                "#[range] on 'A.level' expects #[range(min, max)]",
+            ),
+        ];
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", input, err);
+        }
+    }
+
+    #[test]
+    fn test_validate_instruction_accounts() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                r#"#[solana] #[account] struct Vault { balance: u64 }
+                #[solana] #[instruction]
+                struct Deposit { #[account(mut)] vault: Vault, #[signer] owner: PublicKey, #[account] mint: PublicKey, amount: u64 }"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let TypeDefinition::Struct(deposit) = &ir[1] else {
+            panic!("expected struct");
+        };
+        let accounts: Vec<&str> = deposit
+            .instruction_accounts()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(accounts, ["vault", "owner", "mint"]);
+        let data: Vec<&str> = deposit.data_fields().map(|f| f.name.as_str()).collect();
+        assert_eq!(data, ["amount"]);
+
+        let errors = [
+            (
+                "#[instruction] struct A { #[signer] owner: u64 }",
+                "Instruction account 'A.owner' must be a PublicKey, found u64",
+            ),
+            (
+                "struct B { x: u8 }\n#[instruction] struct A { #[account(mut)] b: B }",
+                "must be a PublicKey or an #[account] type, found B",
+            ),
+        ];
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", input, err);
+        }
+    }
+
+    #[test]
+    fn test_validate_map_keys() {
+        assert!(transform_to_ir(
+            parse_lumos_file(
+                "struct A { x: HashMap<String, [u8]>, y: BTreeMap<i64, Option<bool>> }"
+            )
+            .unwrap()
+        )
+        .is_ok());
+
+        let errors = [
+            (
+                "struct A { x: HashMap<f64, u8> }",
+                "keys must be integers, bool, String or PublicKey, found 'f64'",
+            ),
+            (
+                "struct B { y: u8 }\nstruct A { x: BTreeMap<B, u8> }",
+                "found 'B'",
+            ),
+            (
+                "struct A { x: HashMap<Option<u64>, u8> }",
+                "found 'Option<u64>'",
+            ),
+            ("struct A { x: HashMap<u8, Missing> }", "Missing"),
+        ];
+
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", input, err);
+        }
+    }
+
+    #[test]
+    fn test_validate_display_types() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                "#[display] struct Lamports { value: u64 } #[display] enum Side { Bid, Ask }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(ir.iter().all(|t| t.metadata().has_display()));
+
+        let errors = [
+            ("#[display] struct A { x: u8, y: u8 }", "exactly one field"),
+            ("#[display] struct A { x: [u8] }", "exactly one field"),
+            ("#[display] struct A { x: Option<u8> }", "exactly one field"),
+            ("#[display] enum E { A, B(u8) }", "unit variants only"),
+        ];
+
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", input, err);
+        }
+    }
+
+    #[test]
+    fn test_extract_roles() {
+        let source = r#"
+            #[solana]
+            #[account]
+            struct Vault { owner: PublicKey }
+
+            #[solana]
+            #[event]
+            struct Deposited { amount: u64 }
+
+            #[instruction]
+            enum Command { Start }
+
+            #[solana]
+            #[account]
+            #[zero_copy]
+            struct Book { head: u64 }
+
+            struct Plain { id: u64 }
+        "#;
+        let ir = transform_to_ir(parse_lumos_file(source).unwrap()).unwrap();
+        let roles: Vec<TypeRole> = ir.iter().map(|t| t.metadata().role).collect();
+        assert_eq!(
+            roles,
+            [
+                TypeRole::Account,
+                TypeRole::Event,
+                TypeRole::Instruction,
+                TypeRole::Account,
+                TypeRole::Plain,
+            ]
+        );
+        assert!(ir[3].metadata().zero_copy && !ir[0].metadata().zero_copy);
+        assert_eq!(
+            ir[0].discriminator(),
+            Some([211, 8, 232, 43, 2, 152, 117, 119])
+        );
+        assert!(ir[1].discriminator().is_some());
+        assert_eq!(ir[2].discriminator(), None);
+        assert_eq!(ir[0].metadata().attributes, ["solana", "account"]);
+
+        let result = transform_to_ir(
+            parse_lumos_file("#[solana] #[account] #[event] struct A { x: u8 }").unwrap(),
+        );
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("'A' can't be both #[account] and #[event]"));
+    }
+
+    #[test]
+    fn test_extract_lifecycle() {
+        let source = r#"
+            #[solana]
+            #[account]
+            #[init(create_vault, migrate_vault)]
+            #[init_space]
+            #[close(close_vault)]
+            #[realloc]
+            struct Vault { owner: PublicKey }
+
+            #[solana]
+            #[account]
+            #[close]
+            struct Ticket { owner: PublicKey }
+        "#;
+        let ir = transform_to_ir(parse_lumos_file(source).unwrap()).unwrap();
+
+        assert_eq!(
+            ir[0].metadata().lifecycle,
+            Lifecycle {
+                initialized_by: vec!["create_vault".to_string(), "migrate_vault".to_string()],
+                init_space: true,
+                closable: true,
+                closed_by: vec!["close_vault".to_string()],
+                reallocable: true,
+            }
+        );
+        assert!(ir[1].metadata().lifecycle.closable);
+        assert!(ir[1].metadata().lifecycle.closed_by.is_empty());
+
+        let errors = [
+            (
+                "#[solana] #[close] struct A { x: u8 }",
+                "apply to #[solana] #[account] structs",
+            ),
+            (
+                "#[solana] #[account] #[init] struct A { x: u8 }",
+                "names the instructions",
+            ),
+            (
+                "#[solana] #[account] #[init(\"1st\")] struct A { x: u8 }",
+                "found '1st'",
+            ),
+            (
+                "#[solana] #[account] #[realloc(8)] struct A { x: u8 }",
+                "takes no arguments",
+            ),
+            (
+                "#[solana] #[account] #[close] enum E { A }",
+                "found on enum 'E'",
+            ),
+        ];
+
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", input, err);
+        }
+    }
+
+    #[test]
+    fn test_validate_test_vectors() {
+        let ir = transform_to_ir(
+            parse_lumos_file(
+                "#[test_vector(x = 1, memo = \"a, b\")]\n#[test_vector(x = 2, memo = \"\")]\nstruct S { x: u8, memo: String }",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(ir[0].metadata().test_vectors.len(), 2);
+
+        let errors = [
+            ("#[test_vector] struct S { x: u8 }", "lists field values"),
+            (
+                "#[test_vector(x = 1)] struct S { x: u8, y: u8 }",
+                "#[test_vector] 1 on 'S': S.y: field is missing",
+            ),
+            (
+                "#[test_vector(x = 256)] struct S { x: u8 }",
+                "S.x: expected a u8",
+            ),
+            (
+                "#[test_vector(x = 1)] enum E { A }",
+                "only applies to structs",
+            ),
+            (
+                "#[zero_copy] #[test_vector(x = 1)] struct S { x: u64 }",
+                "can't also be #[zero_copy]",
+            ),
+            (
+                "#[test_vector(x = 1)] struct S { #[skip(typescript)] x: u8 }",
+                "can't #[skip] field 'x'",
+            ),
+        ];
+
+        for (input, expected) in errors {
+            let err = transform_to_ir(parse_lumos_file(input).unwrap()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", input, err);
+        }
+    }
 }
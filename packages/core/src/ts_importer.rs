@@ -0,0 +1,1351 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! TypeScript source import
+//!
+//! Proposes a `.lumos` schema from an existing TypeScript client, so teams
+//! with hand-written Borsh layouts can converge on a single schema. Two kinds
+//! of declarations are read:
+//!
+//! - `@coral-xyz/borsh` layouts (`const XSchema = borsh.struct([...])` and
+//!   `borsh.rustEnum([...])`), which fix the exact field types and order
+//! - interfaces and discriminated unions (`{ kind: 'Name'; ... }`), used for
+//!   types without a layout
+//!
+//! TypeScript types do not say how a value is serialized (`number` may be any
+//! integer width), so inferred types get a `// TODO:` comment in the output
+//! and a matching warning. Types with a layout are preferred over inference.
+//!
+//! This is a best-effort reader, not a TypeScript parser: declarations it does
+//! not understand are skipped.
+
+use crate::error::{LumosError, Result};
+use crate::rust_importer::{primitive, ImportedSchema};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::{self, Write};
+
+/// Type used for fields whose layout could not be read
+const PLACEHOLDER: &str = "u8";
+
+/// Import the Borsh layouts and interfaces of a TypeScript source file as a `.lumos` schema
+///
+/// # Errors
+///
+/// Returns [`LumosError::SchemaParse`] if the source contains no layouts,
+/// interfaces or discriminated unions.
+pub fn import_typescript(source: &str) -> Result<ImportedSchema> {
+    let decls = Parser::new(tokenize(source)).declarations();
+    let mut importer = Importer::default();
+
+    // Types described by a layout, by layout constant
+    for decl in &decls {
+        if let Decl::Const { name, expr } = decl {
+            if matches!(callee_name(expr), Some("struct" | "rustEnum")) {
+                importer.layouts.insert(name.clone(), type_name(name));
+            }
+        }
+    }
+    let layout_types: HashSet<String> = importer.layouts.values().cloned().collect();
+
+    let mut interfaces = Vec::new();
+    for decl in decls {
+        match decl {
+            Decl::Const { name, expr } => {
+                if let Some(type_name) = importer.layouts.get(&name).cloned() {
+                    importer.layout_def(type_name, &expr);
+                }
+            }
+            Decl::Interface { ref name, .. } | Decl::Alias { ref name, .. }
+                if layout_types.contains(name) =>
+            {
+                interfaces.push(decl);
+            }
+            Decl::Interface {
+                name,
+                members,
+                extends,
+            } => {
+                if !extends.is_empty() {
+                    importer.warnings.push(format!(
+                        "{}: fields inherited from {} are not imported",
+                        name,
+                        extends.join(", ")
+                    ));
+                }
+                importer.interface_def(name, &members);
+            }
+            Decl::Alias { name, ty } => importer.alias_def(name, &ty),
+            Decl::Generic { name } => importer
+                .warnings
+                .push(format!("{}: generic types are not imported", name)),
+        }
+    }
+
+    // Layouts win over interfaces; report where the two disagree
+    for decl in interfaces {
+        let Decl::Interface { name, members, .. } = decl else {
+            continue;
+        };
+        let Some(Body::Struct(fields)) = importer
+            .defs
+            .iter()
+            .find(|def| def.name == name)
+            .map(|def| &def.body)
+        else {
+            continue;
+        };
+        let declared: Vec<&str> = members.iter().map(|m| m.name.as_str()).collect();
+        let laid_out: Vec<&str> = fields.iter().map(|f| f.source_name.as_str()).collect();
+        if declared != laid_out {
+            importer.warnings.push(format!(
+                "{}: interface fields ({}) differ from the layout ({}); the layout was used",
+                name,
+                declared.join(", "),
+                laid_out.join(", ")
+            ));
+        }
+    }
+
+    if importer.defs.is_empty() {
+        return Err(LumosError::SchemaParse(
+            "No Borsh layouts, interfaces or discriminated unions found in TypeScript source"
+                .to_string(),
+            None,
+        ));
+    }
+
+    Ok(importer.finish())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(String),
+    Punct(char),
+}
+
+/// Split source into tokens, dropping whitespace and comments
+fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if matches!(c, '\'' | '"' | '`') {
+            let mut value = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                if let Some(&ch) = chars.get(i) {
+                    value.push(ch);
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Str(value));
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '$')) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(if c.is_ascii_digit() {
+                Token::Num(word)
+            } else {
+                Token::Ident(word)
+            });
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// TypeScript type expression
+#[derive(Debug, Clone)]
+enum TsType {
+    Named { name: String, args: Vec<TsType> },
+    Array(Box<TsType>),
+    Union(Vec<TsType>),
+    Literal(String),
+    Object(Vec<Member>),
+    Unknown,
+}
+
+/// Property of an interface or object type
+#[derive(Debug, Clone)]
+struct Member {
+    name: String,
+    optional: bool,
+    ty: TsType,
+}
+
+/// JavaScript expression, enough for layout calls
+#[derive(Debug, Clone)]
+enum Expr {
+    Ident(String),
+    Str(String),
+    Num(String),
+    Array(Vec<Expr>),
+    Member(Box<Expr>, String),
+    Call(Box<Expr>, Vec<Expr>),
+}
+
+/// Top-level declaration of interest
+#[derive(Debug)]
+enum Decl {
+    Interface {
+        name: String,
+        members: Vec<Member>,
+        extends: Vec<String>,
+    },
+    Alias {
+        name: String,
+        ty: TsType,
+    },
+    Const {
+        name: String,
+        expr: Expr,
+    },
+    Generic {
+        name: String,
+    },
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn is(&self, c: char) -> bool {
+        self.peek_at(0) == Some(&Token::Punct(c))
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.is(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        match self.peek_at(0) {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Skip a balanced `<...>`, `{...}`, `(...)` or `[...]` group
+    fn skip_group(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Punct('<' | '{' | '(' | '[') => depth += 1,
+                Token::Punct('>' | '}' | ')' | ']') => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn declarations(&mut self) -> Vec<Decl> {
+        let mut decls = Vec::new();
+        while let Some(token) = self.peek_at(0).cloned() {
+            let Token::Ident(keyword) = token else {
+                self.pos += 1;
+                continue;
+            };
+            let named = matches!(self.peek_at(1), Some(Token::Ident(_)));
+            self.pos += 1;
+            if !named {
+                continue;
+            }
+
+            let decl = match keyword.as_str() {
+                "interface" => self.interface(),
+                "type" => self.alias(),
+                "const" | "let" | "var" => self.constant(),
+                _ => None,
+            };
+            decls.extend(decl);
+        }
+        decls
+    }
+
+    fn interface(&mut self) -> Option<Decl> {
+        let name = self.ident()?;
+        if self.is('<') {
+            self.skip_group();
+            return Some(Decl::Generic { name });
+        }
+
+        let mut extends = Vec::new();
+        while !self.is('{') {
+            match self.next()? {
+                Token::Ident(parent) if parent != "extends" => extends.push(parent),
+                _ => {}
+            }
+        }
+        self.pos += 1;
+
+        let members = self.members();
+        Some(Decl::Interface {
+            name,
+            members,
+            extends,
+        })
+    }
+
+    fn alias(&mut self) -> Option<Decl> {
+        let name = self.ident()?;
+        if self.is('<') {
+            self.skip_group();
+            return Some(Decl::Generic { name });
+        }
+        if !self.eat('=') {
+            return None;
+        }
+        let ty = self.ty();
+        Some(Decl::Alias { name, ty })
+    }
+
+    fn constant(&mut self) -> Option<Decl> {
+        let name = self.ident()?;
+        if self.eat(':') {
+            while !self.is('=') {
+                if matches!(self.peek_at(0), Some(Token::Punct('<' | '{' | '(' | '['))) {
+                    self.skip_group();
+                } else {
+                    self.next()?;
+                }
+            }
+        }
+        if !self.eat('=') {
+            return None;
+        }
+        let expr = self.expr()?;
+        matches!(expr, Expr::Call(..)).then_some(Decl::Const { name, expr })
+    }
+
+    /// Members of an object type, after its `{`
+    fn members(&mut self) -> Vec<Member> {
+        let mut members = Vec::new();
+        loop {
+            if self.eat('}') || self.peek_at(0).is_none() {
+                return members;
+            }
+            if self.eat(';') || self.eat(',') {
+                continue;
+            }
+
+            let mut name = match self.next() {
+                Some(Token::Ident(name) | Token::Str(name)) => name,
+                _ => {
+                    self.skip_member();
+                    continue;
+                }
+            };
+            if name == "readonly" {
+                if let Some(Token::Ident(real) | Token::Str(real)) = self.peek_at(0).cloned() {
+                    self.pos += 1;
+                    name = real;
+                }
+            }
+
+            let optional = self.eat('?');
+            if !self.eat(':') {
+                // Methods, index signatures and the like
+                self.skip_member();
+                continue;
+            }
+            let ty = self.ty();
+            members.push(Member { name, optional, ty });
+        }
+    }
+
+    fn skip_member(&mut self) {
+        while let Some(token) = self.peek_at(0) {
+            match token {
+                Token::Punct(';' | ',' | '}') => return,
+                Token::Punct('<' | '{' | '(' | '[') => self.skip_group(),
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn ty(&mut self) -> TsType {
+        self.eat('|');
+        let mut options = vec![self.ty_postfix()];
+        while self.eat('|') {
+            options.push(self.ty_postfix());
+        }
+        if options.len() == 1 {
+            options.pop().unwrap_or(TsType::Unknown)
+        } else {
+            TsType::Union(options)
+        }
+    }
+
+    fn ty_postfix(&mut self) -> TsType {
+        let mut ty = self.ty_primary();
+        while self.eat('&') {
+            self.ty_primary();
+            ty = TsType::Unknown;
+        }
+        while self.is('[') && self.peek_at(1) == Some(&Token::Punct(']')) {
+            self.pos += 2;
+            ty = TsType::Array(Box::new(ty));
+        }
+        ty
+    }
+
+    fn ty_primary(&mut self) -> TsType {
+        match self.next() {
+            Some(Token::Ident(mut name)) => {
+                while self.eat('.') {
+                    if let Some(segment) = self.ident() {
+                        name = segment;
+                    }
+                }
+                let mut args = Vec::new();
+                if self.eat('<') {
+                    loop {
+                        args.push(self.ty());
+                        if !self.eat(',') {
+                            break;
+                        }
+                    }
+                    self.eat('>');
+                }
+                TsType::Named { name, args }
+            }
+            Some(Token::Str(value) | Token::Num(value)) => TsType::Literal(value),
+            Some(Token::Punct('{')) => TsType::Object(self.members()),
+            Some(Token::Punct('(')) => {
+                let ty = self.ty();
+                self.eat(')');
+                ty
+            }
+            Some(Token::Punct('[')) => {
+                while !self.eat(']') && self.peek_at(0).is_some() {
+                    self.ty();
+                    self.eat(',');
+                }
+                TsType::Unknown
+            }
+            _ => TsType::Unknown,
+        }
+    }
+
+    fn expr(&mut self) -> Option<Expr> {
+        let mut expr = match self.next()? {
+            Token::Ident(name) => Expr::Ident(name),
+            Token::Str(value) => Expr::Str(value),
+            Token::Num(value) => Expr::Num(value),
+            Token::Punct('[') => Expr::Array(self.list(']')?),
+            Token::Punct('(') => {
+                let expr = self.expr()?;
+                self.eat(')');
+                expr
+            }
+            _ => return None,
+        };
+        loop {
+            if self.eat('.') {
+                expr = Expr::Member(Box::new(expr), self.ident()?);
+            } else if self.eat('(') {
+                expr = Expr::Call(Box::new(expr), self.list(')')?);
+            } else {
+                return Some(expr);
+            }
+        }
+    }
+
+    fn list(&mut self, close: char) -> Option<Vec<Expr>> {
+        let mut items = Vec::new();
+        while !self.eat(close) {
+            items.push(self.expr()?);
+            self.eat(',');
+        }
+        Some(items)
+    }
+}
+
+/// Type in schema syntax
+#[derive(Debug, Clone, PartialEq)]
+enum Ty {
+    Named(String),
+    Vec(Box<Ty>),
+    Array(Box<Ty>, usize),
+    Option(Box<Ty>),
+}
+
+impl Ty {
+    fn named(name: &str) -> Self {
+        Ty::Named(name.to_string())
+    }
+
+    fn names<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Ty::Named(name) => out.push(name),
+            Ty::Vec(inner) | Ty::Array(inner, _) | Ty::Option(inner) => inner.names(out),
+        }
+    }
+}
+
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ty::Named(name) => f.write_str(name),
+            Ty::Vec(inner) => write!(f, "[{}]", inner),
+            Ty::Array(inner, len) => write!(f, "[{}; {}]", inner, len),
+            Ty::Option(inner) => write!(f, "Option<{}>", inner),
+        }
+    }
+}
+
+/// Field or tuple element with an optional TODO note
+struct Field {
+    name: String,
+    source_name: String,
+    ty: Ty,
+    todo: Option<String>,
+}
+
+enum VariantBody {
+    Unit,
+    Tuple(Vec<Field>),
+    Struct(Vec<Field>),
+}
+
+struct Variant {
+    name: String,
+    body: VariantBody,
+}
+
+enum Body {
+    Struct(Vec<Field>),
+    Enum(Vec<Variant>),
+}
+
+struct Def {
+    name: String,
+    body: Body,
+    todo: Option<String>,
+}
+
+/// Result of reading one layout expression
+struct Layout {
+    property: Option<String>,
+    kind: LayoutKind,
+}
+
+enum LayoutKind {
+    Type(Ty, Option<String>),
+    Struct(Vec<Expr>),
+    Enum(Vec<Expr>),
+    Tuple(Vec<Expr>),
+    Unit,
+    Unknown(String),
+}
+
+#[derive(Default)]
+struct Importer {
+    /// Type names by layout constant
+    layouts: HashMap<String, String>,
+    defs: Vec<Def>,
+    warnings: Vec<String>,
+}
+
+impl Importer {
+    fn layout_def(&mut self, name: String, expr: &Expr) {
+        let body = match self.layout(expr).kind {
+            LayoutKind::Struct(items) => Body::Struct(self.layout_fields(&items)),
+            LayoutKind::Enum(items) => Body::Enum(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| self.layout_variant(index, item))
+                    .collect(),
+            ),
+            _ => return,
+        };
+        self.defs.push(Def {
+            name,
+            body,
+            todo: None,
+        });
+    }
+
+    fn layout_fields(&self, items: &[Expr]) -> Vec<Field> {
+        items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let layout = self.layout(item);
+                let (ty, todo) = self.layout_type(layout.kind);
+                match layout.property {
+                    Some(name) => field(name, ty, todo),
+                    None => field(
+                        format!("field{}", index),
+                        ty,
+                        Some(note(todo, "layout has no property name")),
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    fn layout_variant(&self, index: usize, item: &Expr) -> Variant {
+        let layout = self.layout(item);
+        let name = layout
+            .property
+            .unwrap_or_else(|| format!("Variant{}", index));
+        let body = match layout.kind {
+            LayoutKind::Unit => VariantBody::Unit,
+            LayoutKind::Struct(items) if items.is_empty() => VariantBody::Unit,
+            LayoutKind::Struct(items) => VariantBody::Struct(self.layout_fields(&items)),
+            LayoutKind::Tuple(items) => VariantBody::Tuple(
+                items
+                    .iter()
+                    .map(|item| {
+                        let (ty, todo) = self.layout_type(self.layout(item).kind);
+                        element(ty, todo)
+                    })
+                    .collect(),
+            ),
+            kind => {
+                let (ty, todo) = self.layout_type(kind);
+                VariantBody::Tuple(vec![element(ty, todo)])
+            }
+        };
+        Variant { name, body }
+    }
+
+    /// Read a layout expression such as `borsh.vec(borsh.u8())('data')`
+    fn layout(&self, expr: &Expr) -> Layout {
+        let unknown = || Layout {
+            property: None,
+            kind: LayoutKind::Unknown(describe(expr)),
+        };
+
+        let (callee, mut args): (&Expr, Vec<&Expr>) = match expr {
+            // Curried property name: `layout('name')`
+            Expr::Call(callee, args) if matches!(**callee, Expr::Call(..)) => {
+                let mut layout = self.layout(callee);
+                if let [Expr::Str(property)] = args.as_slice() {
+                    layout.property = Some(property.clone());
+                }
+                return layout;
+            }
+            Expr::Call(callee, args) => (callee, args.iter().collect()),
+            Expr::Ident(_) | Expr::Member(..) => (expr, Vec::new()),
+            _ => return unknown(),
+        };
+        let Some(name) = callee_name(callee) else {
+            return unknown();
+        };
+
+        let property = match args.last() {
+            Some(Expr::Str(property)) if name != "unit" || args.len() == 1 => {
+                let property = property.clone();
+                args.pop();
+                Some(property)
+            }
+            _ => None,
+        };
+        let list = |expr: Option<&&Expr>| match expr {
+            Some(Expr::Array(items)) => Some(items.clone()),
+            _ => None,
+        };
+        let inner = |expr: Option<&&Expr>| match expr.map(|expr| self.layout(expr).kind) {
+            Some(LayoutKind::Type(ty, todo)) => Some((ty, todo)),
+            _ => None,
+        };
+
+        let kind = if let Some(ty) = primitive_layout(name) {
+            LayoutKind::Type(ty, None)
+        } else if let Some(type_name) = self.layouts.get(name) {
+            LayoutKind::Type(Ty::Named(type_name.clone()), None)
+        } else {
+            match name {
+                "vec" => match inner(args.first()) {
+                    Some((ty, todo)) => LayoutKind::Type(Ty::Vec(Box::new(ty)), todo),
+                    None => LayoutKind::Unknown(describe(expr)),
+                },
+                "option" => match inner(args.first()) {
+                    Some((ty, todo)) => LayoutKind::Type(Ty::Option(Box::new(ty)), todo),
+                    None => LayoutKind::Unknown(describe(expr)),
+                },
+                "array" => match inner(args.first()) {
+                    Some((ty, todo)) => match args.get(1) {
+                        Some(Expr::Num(len)) if len.parse::<usize>().is_ok_and(|len| len > 0) => {
+                            let len = len.parse().unwrap_or_default();
+                            LayoutKind::Type(Ty::Array(Box::new(ty), len), todo)
+                        }
+                        _ => {
+                            let todo = format!(
+                                "array [{}; N] has no literal length; imported as a dynamic array, which adds a 4-byte length prefix",
+                                ty
+                            );
+                            LayoutKind::Type(Ty::Vec(Box::new(ty)), Some(todo))
+                        }
+                    },
+                    None => LayoutKind::Unknown(describe(expr)),
+                },
+                "struct" => list(args.first())
+                    .map_or(LayoutKind::Unknown(describe(expr)), LayoutKind::Struct),
+                "rustEnum" => {
+                    list(args.first()).map_or(LayoutKind::Unknown(describe(expr)), LayoutKind::Enum)
+                }
+                "tuple" => list(args.first())
+                    .map_or(LayoutKind::Unknown(describe(expr)), LayoutKind::Tuple),
+                "unit" => LayoutKind::Unit,
+                _ => LayoutKind::Unknown(describe(expr)),
+            }
+        };
+
+        Layout { property, kind }
+    }
+
+    fn layout_type(&self, kind: LayoutKind) -> (Ty, Option<String>) {
+        match kind {
+            LayoutKind::Type(ty, todo) => (ty, todo),
+            LayoutKind::Struct(_) | LayoutKind::Enum(_) | LayoutKind::Tuple(_) => (
+                Ty::named(PLACEHOLDER),
+                Some(
+                    "inline layout; move it into its own named layout and reference it".to_string(),
+                ),
+            ),
+            LayoutKind::Unit => (
+                Ty::named(PLACEHOLDER),
+                Some("unexpected unit layout".to_string()),
+            ),
+            LayoutKind::Unknown(text) => (
+                Ty::named(PLACEHOLDER),
+                Some(format!(
+                    "unrecognized layout `{}`; {} is a placeholder",
+                    text, PLACEHOLDER
+                )),
+            ),
+        }
+    }
+
+    fn interface_def(&mut self, name: String, members: &[Member]) {
+        let fields = self.member_fields(members);
+        self.defs.push(Def {
+            name,
+            body: Body::Struct(fields),
+            todo: None,
+        });
+    }
+
+    fn member_fields(&self, members: &[Member]) -> Vec<Field> {
+        members
+            .iter()
+            .map(|member| {
+                let (mut ty, todo) = infer(&member.ty);
+                if member.optional && !matches!(ty, Ty::Option(_)) {
+                    ty = Ty::Option(Box::new(ty));
+                }
+                field(member.name.clone(), ty, todo)
+            })
+            .collect()
+    }
+
+    fn alias_def(&mut self, name: String, ty: &TsType) {
+        let options = match ty {
+            TsType::Union(options) => options.as_slice(),
+            TsType::Object(members) => {
+                self.interface_def(name, members);
+                return;
+            }
+            _ => return,
+        };
+
+        // String literal unions
+        if options
+            .iter()
+            .all(|option| matches!(option, TsType::Literal(_)))
+        {
+            let variants = options
+                .iter()
+                .filter_map(|option| match option {
+                    TsType::Literal(value) => Some(Variant {
+                        name: value.clone(),
+                        body: VariantBody::Unit,
+                    }),
+                    _ => None,
+                })
+                .collect();
+            self.defs.push(Def {
+                name,
+                body: Body::Enum(variants),
+                todo: Some(
+                    "string literal union imported as an enum; check that it is serialized as a Borsh enum"
+                        .to_string(),
+                ),
+            });
+            return;
+        }
+
+        // Discriminated unions, as generated by LUMOS
+        let mut variants = Vec::new();
+        for option in options {
+            let TsType::Object(members) = option else {
+                return;
+            };
+            let Some(variant_name) = members.iter().find_map(|member| match &member.ty {
+                TsType::Literal(value) if member.name == "kind" => Some(value.clone()),
+                _ => None,
+            }) else {
+                return;
+            };
+            let rest: Vec<Member> = members
+                .iter()
+                .filter(|member| member.name != "kind")
+                .cloned()
+                .collect();
+            let is_tuple = rest
+                .iter()
+                .enumerate()
+                .all(|(index, member)| member.name == format!("field{}", index));
+
+            let body = if rest.is_empty() {
+                VariantBody::Unit
+            } else if is_tuple {
+                VariantBody::Tuple(self.member_fields(&rest))
+            } else {
+                VariantBody::Struct(self.member_fields(&rest))
+            };
+            variants.push(Variant {
+                name: variant_name,
+                body,
+            });
+        }
+        self.defs.push(Def {
+            name,
+            body: Body::Enum(variants),
+            todo: None,
+        });
+    }
+
+    fn finish(mut self) -> ImportedSchema {
+        let defined: HashSet<&str> = self.defs.iter().map(|def| def.name.as_str()).collect();
+        let mut external = BTreeSet::new();
+        let mut todos = Vec::new();
+        let mut source = String::new();
+
+        for def in &self.defs {
+            if !source.is_empty() {
+                source.push('\n');
+            }
+            if let Some(todo) = &def.todo {
+                todos.push(format!("{}: {}", def.name, todo));
+                let _ = writeln!(source, "// TODO: {}", todo);
+            }
+            source.push_str("#[solana]\n");
+
+            let mut fields: Vec<(String, &Field)> = Vec::new();
+            match &def.body {
+                Body::Struct(struct_fields) => {
+                    let _ = writeln!(source, "struct {} {{", def.name);
+                    write_fields(&mut source, struct_fields, "    ");
+                    source.push_str("}\n");
+                    fields.extend(
+                        struct_fields
+                            .iter()
+                            .map(|f| (format!("{}.{}", def.name, f.source_name), f)),
+                    );
+                }
+                Body::Enum(variants) => {
+                    let _ = writeln!(source, "enum {} {{", def.name);
+                    for variant in variants {
+                        let (name, todo) = ident(&variant.name);
+                        let path = format!("{}::{}", def.name, variant.name);
+                        if let Some(todo) = todo {
+                            todos.push(format!("{}: {}", path, todo));
+                            let _ = writeln!(source, "    // TODO: {}", todo);
+                        }
+                        match &variant.body {
+                            VariantBody::Unit => {
+                                let _ = writeln!(source, "    {},", name);
+                            }
+                            VariantBody::Tuple(elements) => {
+                                for element in elements {
+                                    if let Some(todo) = &element.todo {
+                                        let _ = writeln!(source, "    // TODO: {}", todo);
+                                    }
+                                }
+                                let types: Vec<String> =
+                                    elements.iter().map(|e| e.ty.to_string()).collect();
+                                let _ = writeln!(source, "    {}({}),", name, types.join(", "));
+                                fields.extend(elements.iter().map(|e| (path.clone(), e)));
+                            }
+                            VariantBody::Struct(variant_fields) => {
+                                let _ = writeln!(source, "    {} {{", name);
+                                write_fields(&mut source, variant_fields, "        ");
+                                source.push_str("    },\n");
+                                fields.extend(
+                                    variant_fields
+                                        .iter()
+                                        .map(|f| (format!("{}.{}", path, f.source_name), f)),
+                                );
+                            }
+                        }
+                    }
+                    source.push_str("}\n");
+                }
+            }
+
+            for (path, field) in fields {
+                if let Some(todo) = &field.todo {
+                    todos.push(format!("{}: {}", path, todo));
+                }
+                let mut names = Vec::new();
+                field.ty.names(&mut names);
+                external.extend(
+                    names
+                        .into_iter()
+                        .filter(|name| primitive(name).is_none() && !defined.contains(name))
+                        .map(str::to_string),
+                );
+            }
+        }
+
+        for name in external {
+            self.warnings.push(format!(
+                "Type '{}' is not defined in the source; add it to the schema",
+                name
+            ));
+        }
+        self.warnings.extend(todos);
+
+        ImportedSchema {
+            source,
+            types: self.defs.into_iter().map(|def| def.name).collect(),
+            warnings: self.warnings,
+        }
+    }
+}
+
+fn field(source_name: String, ty: Ty, todo: Option<String>) -> Field {
+    let (name, rename) = ident(&source_name);
+    let todo = match rename {
+        Some(rename) => Some(note(todo, &rename)),
+        None => todo,
+    };
+    Field {
+        name,
+        source_name,
+        ty,
+        todo,
+    }
+}
+
+/// Tuple element, which has no name
+fn element(ty: Ty, todo: Option<String>) -> Field {
+    Field {
+        name: String::new(),
+        source_name: String::new(),
+        ty,
+        todo,
+    }
+}
+
+/// Join a TODO note onto an existing one
+fn note(todo: Option<String>, extra: &str) -> String {
+    match todo {
+        Some(todo) => format!("{}; {}", todo, extra),
+        None => extra.to_string(),
+    }
+}
+
+/// A valid schema identifier for a TypeScript name
+fn ident(name: &str) -> (String, Option<String>) {
+    if syn::parse_str::<syn::Ident>(name).is_ok() {
+        return (name.to_string(), None);
+    }
+    let mut ident: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if syn::parse_str::<syn::Ident>(&ident).is_err() {
+        ident.push('_');
+    }
+    let todo = format!("renamed from `{}`, which is not a valid identifier", name);
+    (ident, Some(todo))
+}
+
+fn write_fields(source: &mut String, fields: &[Field], indent: &str) {
+    for field in fields {
+        if let Some(todo) = &field.todo {
+            let _ = writeln!(source, "{}// TODO: {}", indent, todo);
+        }
+        let _ = writeln!(source, "{}{}: {},", indent, field.name, field.ty);
+    }
+}
+
+/// Schema type for a layout function such as `u64` or `publicKey`
+fn primitive_layout(name: &str) -> Option<Ty> {
+    match name {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128" | "f32"
+        | "f64" | "bool" => Some(Ty::named(name)),
+        "publicKey" => Some(Ty::named("PublicKey")),
+        "str" | "string" => Some(Ty::named("String")),
+        "vecU8" => Some(Ty::Vec(Box::new(Ty::named("u8")))),
+        _ => None,
+    }
+}
+
+/// Infer a schema type from a TypeScript type
+fn infer(ty: &TsType) -> (Ty, Option<String>) {
+    match ty {
+        TsType::Named { name, args } => match (name.as_str(), args.as_slice()) {
+            ("number", _) => (
+                Ty::named("u64"),
+                Some("`number` may be any integer width or f64; u64 was chosen".to_string()),
+            ),
+            ("bigint" | "BN" | "BigInt", _) => (
+                Ty::named("u64"),
+                Some(format!(
+                    "`{}` may be u64, u128, i64 or i128; u64 was chosen",
+                    name
+                )),
+            ),
+            ("Date", _) => (
+                Ty::named("i64"),
+                Some("`Date` imported as an i64 Unix timestamp".to_string()),
+            ),
+            ("string", _) => (Ty::named("String"), None),
+            ("boolean", _) => (Ty::named("bool"), None),
+            ("PublicKey" | "Pubkey", _) => (Ty::named("PublicKey"), None),
+            ("Uint8Array" | "Buffer", _) => (Ty::Vec(Box::new(Ty::named("u8"))), None),
+            ("Array" | "ReadonlyArray", [inner]) => {
+                let (inner, todo) = infer(inner);
+                (Ty::Vec(Box::new(inner)), todo)
+            }
+            (name, []) if name.starts_with(|c: char| c.is_ascii_uppercase()) => {
+                (Ty::named(name), None)
+            }
+            _ => placeholder(ty),
+        },
+        TsType::Array(inner) => {
+            let (inner, todo) = infer(inner);
+            (Ty::Vec(Box::new(inner)), todo)
+        }
+        TsType::Union(options) => {
+            let present: Vec<&TsType> = options
+                .iter()
+                .filter(|option| {
+                    !matches!(option, TsType::Named { name, .. } if name == "null" || name == "undefined")
+                })
+                .collect();
+            match present.as_slice() {
+                [inner] if present.len() < options.len() => {
+                    let (inner, todo) = infer(inner);
+                    (Ty::Option(Box::new(inner)), todo)
+                }
+                _ => placeholder(ty),
+            }
+        }
+        _ => placeholder(ty),
+    }
+}
+
+fn placeholder(ty: &TsType) -> (Ty, Option<String>) {
+    let what = match ty {
+        TsType::Union(_) => "union type",
+        TsType::Literal(_) => "literal type",
+        TsType::Object(_) => "inline object type",
+        _ => "type",
+    };
+    (
+        Ty::named(PLACEHOLDER),
+        Some(format!(
+            "cannot infer a Borsh type from this {}; {} is a placeholder",
+            what, PLACEHOLDER
+        )),
+    )
+}
+
+/// Last identifier of a callee such as `borsh.struct`
+fn callee_name(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Ident(name) | Expr::Member(_, name) => Some(name),
+        Expr::Call(callee, _) => callee_name(callee),
+        _ => None,
+    }
+}
+
+/// Short source-like rendering of an expression for notes
+fn describe(expr: &Expr) -> String {
+    match expr {
+        Expr::Ident(name) | Expr::Num(name) => name.clone(),
+        Expr::Str(value) => format!("'{}'", value),
+        Expr::Array(_) => "[...]".to_string(),
+        Expr::Member(object, name) => format!("{}.{}", describe(object), name),
+        Expr::Call(callee, _) => format!("{}(...)", describe(callee)),
+    }
+}
+
+/// Type name for a layout constant: `PlayerSchema`, `PLAYER_LAYOUT` -> `Player`
+fn type_name(constant: &str) -> String {
+    let stem = ["Schema", "Layout", "_SCHEMA", "_LAYOUT"]
+        .iter()
+        .find_map(|suffix| constant.strip_suffix(suffix))
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or(constant);
+
+    if stem.chars().any(|c| c.is_ascii_lowercase()) {
+        let mut chars = stem.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    } else {
+        stem.split('_')
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let lower = word.to_lowercase();
+                let mut chars = lower.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{EnumVariantDefinition, TypeDefinition, TypeInfo};
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn round_trip(source: &str) -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn imports_layouts_generated_by_lumos() {
+        let source = r#"
+            import * as borsh from '@coral-xyz/borsh';
+            import { PublicKey } from '@solana/web3.js';
+
+            export interface Vault {
+              owner: PublicKey;
+              label: string;
+              seed: number[];
+              status: Status;
+            }
+
+            export const VaultSchema = borsh.struct([
+              borsh.publicKey('owner'),
+              borsh.string('label'),
+              borsh.vec(borsh.u8)('seed'),
+              StatusSchema('status'),
+            ]);
+
+            export type Status =
+              | { kind: 'Open' }
+              | { kind: 'Moved'; field0: number }
+              | { kind: 'Closed'; at: number }
+            ;
+
+            export const StatusSchema = borsh.rustEnum([
+              borsh.unit('Open'),
+              borsh.tuple([
+                borsh.u8,
+              ], 'Moved'),
+              borsh.struct([
+                borsh.i64('at'),
+              ], 'Closed'),
+            ]);
+        "#;
+
+        let imported = import_typescript(source).unwrap();
+        assert_eq!(imported.types, ["Vault", "Status"]);
+        assert!(imported.warnings.is_empty(), "{:?}", imported.warnings);
+        assert!(!imported.source.contains("TODO"));
+
+        let ir = round_trip(&imported.source);
+        let TypeDefinition::Struct(vault) = &ir[0] else {
+            panic!("expected struct");
+        };
+        assert!(matches!(&vault.fields[2].type_info, TypeInfo::Array(inner)
+            if matches!(&**inner, TypeInfo::Primitive(name) if name == "u8")));
+        assert!(
+            matches!(&vault.fields[3].type_info, TypeInfo::UserDefined(name) if name == "Status")
+        );
+
+        let TypeDefinition::Enum(status) = &ir[1] else {
+            panic!("expected enum");
+        };
+        assert!(matches!(
+            &status.variants[0],
+            EnumVariantDefinition::Unit { .. }
+        ));
+        assert!(matches!(
+            &status.variants[1],
+            EnumVariantDefinition::Tuple { .. }
+        ));
+        assert!(matches!(
+            &status.variants[2],
+            EnumVariantDefinition::Struct { .. }
+        ));
+    }
+
+    #[test]
+    fn reads_hand_written_layouts() {
+        let source = r#"
+            import { struct, u64, publicKey, option, array, u8, vec } from '@coral-xyz/borsh';
+
+            // Pool state
+            export const POOL_LAYOUT: Layout<Pool> = struct([
+              publicKey('mint'),
+              u64('reserve'),
+              option(publicKey(), 'admin'),
+              array(u8(), 32, 'seed'),
+              vec(MEMBER_LAYOUT, 'members'),
+              u256('liquidity'),
+            ]);
+
+            const MEMBER_LAYOUT = struct([publicKey('wallet'), u8('type')]);
+        "#;
+
+        let imported = import_typescript(source).unwrap();
+        assert_eq!(imported.types, ["Pool", "Member"]);
+        assert!(imported.source.contains("    admin: Option<PublicKey>,\n"));
+        assert!(imported.source.contains("    members: [Member],\n"));
+        assert!(imported.source.contains("    seed: [u8; 32],\n"));
+        assert!(imported.source.contains("    type_: u8,\n"));
+        assert!(imported
+            .source
+            .contains("    // TODO: unrecognized layout `u256(...)`"));
+
+        let todos: Vec<&str> = imported
+            .warnings
+            .iter()
+            .map(|w| w.split(':').next().unwrap())
+            .collect();
+        assert_eq!(todos, ["Pool.liquidity", "Member.type"]);
+        round_trip(&imported.source);
+    }
+
+    #[test]
+    fn infers_interfaces_with_todos() {
+        let source = r#"
+            /** Escrow terms */
+            export interface Escrow {
+              readonly maker: web3.PublicKey;
+              amount: BN;
+              expiresAt?: number;
+              memo: string | null;
+              tags: Array<string>;
+              kind: Side;
+              extra: Record<string, number>;
+              handler(): void;
+            }
+
+            export type Side = 'Buy' | 'Sell';
+            type Id = string;
+            interface Box<T> { value: T }
+        "#;
+
+        let imported = import_typescript(source).unwrap();
+        assert_eq!(imported.types, ["Escrow", "Side"]);
+        assert!(imported.source.contains("    maker: PublicKey,\n"));
+        assert!(imported.source.contains("    expiresAt: Option<u64>,\n"));
+        assert!(imported.source.contains("    memo: Option<String>,\n"));
+        assert!(imported.source.contains("    tags: [String],\n"));
+        assert!(!imported.source.contains("handler"));
+
+        let flagged: Vec<&str> = imported
+            .warnings
+            .iter()
+            .map(|w| w.split(':').next().unwrap())
+            .collect();
+        assert_eq!(
+            flagged,
+            [
+                "Box",
+                "Escrow.amount",
+                "Escrow.expiresAt",
+                "Escrow.extra",
+                "Side"
+            ]
+        );
+        round_trip(&imported.source);
+    }
+
+    #[test]
+    fn reports_interface_and_layout_disagreements() {
+        let source = r#"
+            interface Counter { count: number; owner: PublicKey }
+            const CounterSchema = borsh.struct([borsh.u32('count')]);
+        "#;
+        let imported = import_typescript(source).unwrap();
+        assert!(imported.source.contains("count: u32,"));
+        assert_eq!(imported.warnings.len(), 1);
+        assert!(imported.warnings[0].contains("differ from the layout"));
+
+        assert!(import_typescript("export function noop() {}").is_err());
+    }
+
+    #[test]
+    fn names_types_after_layout_constants() {
+        assert_eq!(type_name("PlayerSchema"), "Player");
+        assert_eq!(type_name("gameStateLayout"), "GameState");
+        assert_eq!(type_name("TOKEN_ACCOUNT_LAYOUT"), "TokenAccount");
+        assert_eq!(type_name("Schema"), "Schema");
+    }
+}
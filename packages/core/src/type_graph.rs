@@ -0,0 +1,260 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Type dependency graph for blast-radius queries
+//!
+//! A type uses another when one of its fields (or variant fields) names it,
+//! directly or inside a `Vec`, `Option`, array or map. [`TypeGraph`] answers
+//! which types a change to a type can affect ([`TypeGraph::users_of`]), what a
+//! type is built from ([`TypeGraph::dependencies_of`]), and which types
+//! nothing else uses ([`TypeGraph::roots`]).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::type_graph::TypeGraph;
+//! use lumos_core::{parser, transform};
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "struct Item { id: u64 }
+//!      struct Inventory { items: [Item] }
+//!      struct Player { inventory: Inventory }",
+//! )?)?;
+//!
+//! let graph = TypeGraph::new(&ir);
+//! let users: Vec<&str> = graph.users_of("Item").iter().map(|r| r.name).collect();
+//! assert_eq!(users, ["Inventory", "Player"]);
+//! assert_eq!(graph.roots(), ["Player"]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::ir::TypeDefinition;
+use std::collections::VecDeque;
+
+/// Type dependency graph of a schema, in schema order
+#[derive(Debug, Clone)]
+pub struct TypeGraph<'a> {
+    /// Type names
+    names: Vec<&'a str>,
+
+    /// Indices of the types each type uses, without duplicates
+    uses: Vec<Vec<usize>>,
+
+    /// Indices of the types using each type, without duplicates
+    used_by: Vec<Vec<usize>>,
+}
+
+/// A type reached by following edges from the queried type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reached<'a> {
+    /// Type name
+    pub name: &'a str,
+
+    /// Types on the shortest path between the queried type and this one,
+    /// nearest to the queried type first
+    pub via: Vec<&'a str>,
+}
+
+impl Reached<'_> {
+    /// Number of edges from the queried type, 1 for direct neighbours
+    pub fn depth(&self) -> usize {
+        self.via.len() + 1
+    }
+}
+
+impl<'a> TypeGraph<'a> {
+    /// Build the graph of `type_defs`
+    ///
+    /// References to types not defined in `type_defs` are ignored.
+    pub fn new(type_defs: &'a [TypeDefinition]) -> Self {
+        let names: Vec<&str> = type_defs.iter().map(TypeDefinition::name).collect();
+        let mut uses = vec![Vec::new(); names.len()];
+        let mut used_by = vec![Vec::new(); names.len()];
+
+        for (user, type_def) in type_defs.iter().enumerate() {
+            let mut referenced = Vec::new();
+            type_def.referenced_types(&mut referenced);
+            for name in referenced {
+                let Some(used) = names.iter().position(|n| *n == name) else {
+                    continue;
+                };
+                if !uses[user].contains(&used) {
+                    uses[user].push(used);
+                    used_by[used].push(user);
+                }
+            }
+        }
+
+        Self {
+            names,
+            uses,
+            used_by,
+        }
+    }
+
+    /// Type names, in schema order
+    pub fn names(&self) -> &[&'a str] {
+        &self.names
+    }
+
+    /// Whether the schema defines `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.index(name).is_some()
+    }
+
+    /// Types `name` uses directly, in field order
+    pub fn uses(&self, name: &str) -> Vec<&'a str> {
+        self.index(name)
+            .map(|index| self.uses[index].iter().map(|&i| self.names[i]).collect())
+            .unwrap_or_default()
+    }
+
+    /// Every type using `name`, directly or through other types, nearest first
+    pub fn users_of(&self, name: &str) -> Vec<Reached<'a>> {
+        self.reach(name, &self.used_by)
+    }
+
+    /// Every type `name` uses, directly or through other types, nearest first
+    pub fn dependencies_of(&self, name: &str) -> Vec<Reached<'a>> {
+        self.reach(name, &self.uses)
+    }
+
+    /// Types no other type uses, in schema order
+    ///
+    /// These are the top-level types, usually accounts, instructions and
+    /// events. A type that only uses itself is still a root.
+    pub fn roots(&self) -> Vec<&'a str> {
+        (0..self.names.len())
+            .filter(|&index| self.used_by[index].iter().all(|&user| user == index))
+            .map(|index| self.names[index])
+            .collect()
+    }
+
+    /// Graphviz DOT of the types in `names` and the edges between them,
+    /// pointing from each type to the types it uses
+    pub fn to_dot(&self, names: &[&str]) -> String {
+        let selected: Vec<usize> = (0..self.names.len())
+            .filter(|&index| names.contains(&self.names[index]))
+            .collect();
+
+        let mut output = String::from("digraph types {\n    rankdir=LR;\n    node [shape=box];\n");
+        for &index in &selected {
+            output.push_str(&format!("    \"{}\";\n", self.names[index]));
+        }
+        for &user in &selected {
+            for used in self.uses[user].iter().filter(|i| selected.contains(i)) {
+                output.push_str(&format!(
+                    "    \"{}\" -> \"{}\";\n",
+                    self.names[user], self.names[*used]
+                ));
+            }
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| *n == name)
+    }
+
+    /// Breadth-first walk from `name` along `edges`
+    fn reach(&self, name: &str, edges: &[Vec<usize>]) -> Vec<Reached<'a>> {
+        let Some(start) = self.index(name) else {
+            return Vec::new();
+        };
+
+        let mut previous: Vec<Option<usize>> = vec![None; self.names.len()];
+        let mut seen = vec![false; self.names.len()];
+        seen[start] = true;
+        let mut queue = VecDeque::from([start]);
+        let mut order = Vec::new();
+
+        while let Some(current) = queue.pop_front() {
+            for &next in &edges[current] {
+                if !seen[next] {
+                    seen[next] = true;
+                    previous[next] = Some(current);
+                    order.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        order
+            .into_iter()
+            .map(|index| {
+                let mut via = Vec::new();
+                let mut step = previous[index];
+                while let Some(current) = step.filter(|&i| i != start) {
+                    via.push(self.names[current]);
+                    step = previous[current];
+                }
+                via.reverse();
+                Reached {
+                    name: self.names[index],
+                    via,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    const SCHEMA: &str = r#"
+        struct Item { id: u64 }
+        enum Slot { Empty, Filled(Item) }
+        struct Inventory { slots: [Slot], favourite: Option<Item> }
+        struct Player { inventory: Inventory, friends: [Player] }
+        struct Shop { stock: HashMap<u64, Item> }
+    "#;
+
+    fn ir() -> Vec<TypeDefinition> {
+        transform_to_ir(parse_lumos_file(SCHEMA).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn finds_users_with_shortest_paths() {
+        let ir = ir();
+        let graph = TypeGraph::new(&ir);
+
+        let users = graph.users_of("Item");
+        let names: Vec<&str> = users.iter().map(|r| r.name).collect();
+        assert_eq!(names, ["Slot", "Inventory", "Shop", "Player"]);
+
+        let player = users.iter().find(|r| r.name == "Player").unwrap();
+        assert_eq!(player.via, ["Inventory"]);
+        assert_eq!(player.depth(), 2);
+        assert!(graph.users_of("Unknown").is_empty());
+    }
+
+    #[test]
+    fn finds_dependencies_and_roots() {
+        let ir = ir();
+        let graph = TypeGraph::new(&ir);
+
+        assert_eq!(graph.uses("Inventory"), ["Slot", "Item"]);
+        let deps: Vec<&str> = graph
+            .dependencies_of("Player")
+            .iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(deps, ["Inventory", "Slot", "Item"]);
+        assert_eq!(graph.roots(), ["Player", "Shop"]);
+    }
+
+    #[test]
+    fn renders_dot_for_selected_types() {
+        let ir = ir();
+        let graph = TypeGraph::new(&ir);
+
+        let dot = graph.to_dot(&["Inventory", "Slot", "Item"]);
+        assert!(dot.contains("    \"Inventory\" -> \"Slot\";\n"));
+        assert!(dot.contains("    \"Slot\" -> \"Item\";\n"));
+        assert!(!dot.contains("Player"));
+    }
+}
@@ -0,0 +1,278 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Custom validation passes
+//!
+//! [`crate::transform`] rejects schemas no generator can handle; projects
+//! often have rules of their own on top, such as "every account has a
+//! `version` field". A [`ValidationPass`] checks the IR for one such rule
+//! and reports what it finds into [`Diagnostics`], and a [`PassManager`]
+//! runs a set of passes in the order they were added. Tools that build the
+//! same manager, such as `lumos validate` and `lumos generate`, then apply
+//! exactly the same checks.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lumos_core::ir::TypeDefinition;
+//! use lumos_core::validation::{Diagnostics, PassManager, ValidationPass};
+//! use lumos_core::{parser, transform};
+//!
+//! struct Versioned;
+//!
+//! impl ValidationPass for Versioned {
+//!     fn name(&self) -> &str {
+//!         "versioned-accounts"
+//!     }
+//!
+//!     fn run(&self, ir: &[TypeDefinition], ctx: &mut Diagnostics) {
+//!         for account in ir.iter().filter(|type_def| type_def.is_account()) {
+//!             let TypeDefinition::Struct(def) = account else { continue };
+//!             if !def.fields.iter().any(|field| field.name == "version") {
+//!                 ctx.error(Some(account.name().into()), "Accounts need a `version` field");
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let ir = transform::transform_to_ir(parser::parse_lumos_file(
+//!     "#[solana]\n#[account]\nstruct Vault { balance: u64 }",
+//! )?)?;
+//!
+//! let diagnostics = PassManager::new().with_pass(Versioned).run(&ir);
+//! assert!(diagnostics.has_errors());
+//! assert_eq!(diagnostics.iter().next().unwrap().pass, "versioned-accounts");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::ir::TypeDefinition;
+pub use crate::security_analyzer::Location;
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Reported, but the schema is still accepted
+    Warning,
+
+    /// The schema is rejected
+    Error,
+}
+
+impl Level {
+    /// Parse a level name (`warning` or `error`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "warning" => Some(Self::Warning),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    /// The level's name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A problem reported by a pass
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// Name of the pass that reported it
+    pub pass: String,
+
+    /// How serious it is
+    pub level: Level,
+
+    /// Type or field it is about, if any
+    pub location: Option<Location>,
+
+    /// What is wrong
+    pub message: String,
+}
+
+/// Diagnostics reported by the passes of a run, in the order they were reported
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    /// Pass currently running
+    pass: String,
+
+    items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// An empty set of diagnostics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report a problem of the running pass
+    pub fn push(&mut self, level: Level, location: Option<Location>, message: impl Into<String>) {
+        self.items.push(Diagnostic {
+            pass: self.pass.clone(),
+            level,
+            location,
+            message: message.into(),
+        });
+    }
+
+    /// Report a problem that rejects the schema
+    pub fn error(&mut self, location: Option<Location>, message: impl Into<String>) {
+        self.push(Level::Error, location, message);
+    }
+
+    /// Report a problem that doesn't reject the schema
+    pub fn warning(&mut self, location: Option<Location>, message: impl Into<String>) {
+        self.push(Level::Warning, location, message);
+    }
+
+    /// Whether any diagnostic rejects the schema
+    pub fn has_errors(&self) -> bool {
+        self.count(Level::Error) > 0
+    }
+
+    /// Number of diagnostics at `level`
+    pub fn count(&self, level: Level) -> usize {
+        self.items.iter().filter(|d| d.level == level).count()
+    }
+
+    /// Whether nothing was reported
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The diagnostics, in the order they were reported
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.items.iter()
+    }
+
+    /// Take the diagnostics
+    pub fn into_vec(self) -> Vec<Diagnostic> {
+        self.items
+    }
+}
+
+/// A check of the IR, reporting what it finds into [`Diagnostics`]
+pub trait ValidationPass {
+    /// Name diagnostics of the pass are attributed to, e.g. `versioned-accounts`
+    fn name(&self) -> &str;
+
+    /// Check `ir`, reporting problems into `ctx`
+    fn run(&self, ir: &[TypeDefinition], ctx: &mut Diagnostics);
+}
+
+/// Runs validation passes in the order they were added
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Box<dyn ValidationPass>>,
+}
+
+impl PassManager {
+    /// A manager without passes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `pass` after the passes added so far
+    pub fn add(&mut self, pass: impl ValidationPass + 'static) -> &mut Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Add `pass` after the passes added so far
+    pub fn with_pass(mut self, pass: impl ValidationPass + 'static) -> Self {
+        self.add(pass);
+        self
+    }
+
+    /// Names of the passes, in the order they run
+    pub fn names(&self) -> Vec<&str> {
+        self.passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    /// Whether there are no passes to run
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Run every pass over `ir`, collecting their diagnostics
+    ///
+    /// Every pass runs even when an earlier one reported errors, so a
+    /// single run shows all problems.
+    pub fn run(&self, ir: &[TypeDefinition]) -> Diagnostics {
+        let mut diagnostics = Diagnostics::new();
+        for pass in &self.passes {
+            diagnostics.pass = pass.name().to_string();
+            pass.run(ir, &mut diagnostics);
+        }
+        diagnostics.pass.clear();
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    /// Reports every type at `level`
+    struct EveryType(&'static str, Level);
+
+    impl ValidationPass for EveryType {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn run(&self, ir: &[TypeDefinition], ctx: &mut Diagnostics) {
+            for type_def in ir {
+                ctx.push(self.1, Some(type_def.name().into()), "seen");
+            }
+        }
+    }
+
+    #[test]
+    fn runs_passes_in_order_and_attributes_diagnostics() {
+        let ir =
+            transform_to_ir(parse_lumos_file("struct A { x: u8 }\nstruct B { y: u8 }").unwrap())
+                .unwrap();
+
+        let mut manager = PassManager::new();
+        manager
+            .add(EveryType("first", Level::Warning))
+            .add(EveryType("second", Level::Error));
+        assert_eq!(manager.names(), ["first", "second"]);
+
+        let diagnostics = manager.run(&ir);
+        let seen: Vec<String> = diagnostics
+            .iter()
+            .map(|d| {
+                format!(
+                    "{} {} {}",
+                    d.pass,
+                    d.level.as_str(),
+                    d.location.as_ref().unwrap()
+                )
+            })
+            .collect();
+        assert_eq!(
+            seen,
+            [
+                "first warning A",
+                "first warning B",
+                "second error A",
+                "second error B"
+            ]
+        );
+        assert!(diagnostics.has_errors());
+        assert_eq!(diagnostics.count(Level::Warning), 2);
+
+        let warnings = PassManager::new()
+            .with_pass(EveryType("only", Level::Warning))
+            .run(&ir);
+        assert!(!warnings.has_errors());
+        assert!(PassManager::new().run(&ir).is_empty());
+    }
+}
@@ -5,10 +5,17 @@
 //!
 //! This module provides WebAssembly bindings that allow the LUMOS code generator
 //! to run in the browser for the interactive playground.
+//!
+//! Every export runs through [`crate::panic_guard`]: a panic becomes a
+//! JavaScript Error carrying a bug report, and when the instance aborts
+//! instead, `lastPanicReport()` returns the same report.
 
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+use crate::encoding::Encoding;
+use crate::error::LumosError;
+use crate::panic_guard;
 use crate::{generators, parser, transform};
 
 /// Result of code generation containing both Rust and TypeScript outputs
@@ -56,6 +63,10 @@ pub struct GeneratedCode {
 /// ```
 #[wasm_bindgen(js_name = generateCode)]
 pub fn generate_code(source: &str) -> Result<GeneratedCode, JsValue> {
+    export("generateCode", source, || generate(source))
+}
+
+fn generate(source: &str) -> Result<GeneratedCode, JsValue> {
     // Parse the .lumos file into AST
     let ast = parser::parse_lumos_file(source)
         .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
@@ -90,6 +101,10 @@ pub fn generate_code(source: &str) -> Result<GeneratedCode, JsValue> {
 /// `Ok(())` if the schema is valid, or a JavaScript Error with the validation message
 #[wasm_bindgen(js_name = validateSchema)]
 pub fn validate_schema(source: &str) -> Result<(), JsValue> {
+    export("validateSchema", source, || validate(source))
+}
+
+fn validate(source: &str) -> Result<(), JsValue> {
     // Parse the .lumos file
     let ast = parser::parse_lumos_file(source)
         .map_err(|e| JsValue::from_str(&format!("Validation error: {}", e)))?;
@@ -101,6 +116,91 @@ pub fn validate_schema(source: &str) -> Result<(), JsValue> {
     Ok(())
 }
 
+/// Encode bytes as hex, base64 or base58 text
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to encode
+/// * `encoding` - `"hex"`, `"base64"` or `"base58"`
+///
+/// # Example (JavaScript)
+///
+/// ```js
+/// import { encodeBytes } from 'lumos-wasm';
+///
+/// encodeBytes(new Uint8Array([104, 105]), 'hex'); // '6869'
+/// ```
+#[wasm_bindgen(js_name = encodeBytes)]
+pub fn encode_bytes(bytes: &[u8], encoding: &str) -> Result<String, JsValue> {
+    export("encodeBytes", "", || {
+        Ok(parse_encoding(encoding)?.encode(bytes))
+    })
+}
+
+/// Decode hex, base64 or base58 text into bytes
+///
+/// # Arguments
+///
+/// * `text` - The encoded text; surrounding whitespace is ignored
+/// * `encoding` - `"hex"`, `"base64"` or `"base58"`
+///
+/// # Returns
+///
+/// A `Uint8Array`, or a JavaScript Error naming the offending character
+#[wasm_bindgen(js_name = decodeBytes)]
+pub fn decode_bytes(text: &str, encoding: &str) -> Result<Vec<u8>, JsValue> {
+    export("decodeBytes", "", || {
+        parse_encoding(encoding)?
+            .decode(text)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    })
+}
+
+/// Bug report of the last panic, if any export has panicked
+///
+/// When a panic aborts the instance, the call throws a `RuntimeError`
+/// instead of an Error with the report; the page can show this report and
+/// load a fresh instance.
+///
+/// # Example (JavaScript)
+///
+/// ```js
+/// import { generateCode, lastPanicReport } from 'lumos-wasm';
+///
+/// try {
+///     generateCode(schema);
+/// } catch (error) {
+///     console.error(lastPanicReport() ?? error.message);
+/// }
+/// ```
+#[wasm_bindgen(js_name = lastPanicReport)]
+pub fn last_panic_report() -> Option<String> {
+    panic_guard::last_panic_report()
+}
+
+/// Run the export `name`, turning a panic into an Error with a bug report
+fn export<T>(
+    name: &str,
+    source: &str,
+    run: impl FnOnce() -> Result<T, JsValue>,
+) -> Result<T, JsValue> {
+    panic_guard::record_panics();
+    match panic_guard::guarded(name, source, || Ok(run())) {
+        Ok(result) => result,
+        Err(LumosError::Internal(error)) => Err(JsValue::from_str(&error.bug_report())),
+        Err(error) => Err(JsValue::from_str(&error.to_string())),
+    }
+}
+
+fn parse_encoding(encoding: &str) -> Result<Encoding, JsValue> {
+    Encoding::parse(encoding).ok_or_else(|| {
+        JsValue::from_str(&format!(
+            "Unsupported encoding '{}' (expected hex, base64 or base58)",
+            encoding
+        ))
+    })
+}
+
 #[cfg(all(test, target_arch = "wasm32"))]
 mod tests {
     use super::*;
@@ -171,4 +271,13 @@ mod tests {
         let result = validate_schema(source);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_round_trip_bytes() {
+        let encoded = encode_bytes(b"hello world", "base58").unwrap();
+        assert_eq!(encoded, "StV1DL6CwTryKyV");
+        assert_eq!(decode_bytes(&encoded, "base58").unwrap(), b"hello world");
+        assert!(decode_bytes("0OIl", "base58").is_err());
+        assert!(encode_bytes(b"", "utf8").is_err());
+    }
 }
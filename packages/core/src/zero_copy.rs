@@ -0,0 +1,300 @@
+// Licensed under either of Apache License, Version 2.0 or MIT license at your option.
+// Copyright 2025 RECTOR-LABS
+
+//! Memory layouts of `#[zero_copy]` structs
+//!
+//! A `#[zero_copy]` struct is generated as `#[repr(C)]` and `bytemuck::Pod`
+//! (Anchor's `#[account(zero_copy)]` for accounts), so programs and native
+//! clients read it in place instead of deserializing. Pod types can't have
+//! padding, so every field must start right where the previous one ends:
+//! order fields from the largest alignment to the smallest, or add `u8`
+//! padding fields by hand.
+//!
+//! Fields may be integers, floats, `PublicKey`, other `#[zero_copy]` structs,
+//! or fixed-size arrays of these. `bool`, strings, vectors, maps, options and enums
+//! have no Pod form.
+//! Without padding, the in-memory layout is byte-for-byte the Borsh layout.
+
+use crate::error::{LumosError, Result};
+use crate::ir::{StructDefinition, TypeDefinition, TypeInfo};
+
+/// Attribute marking a struct as zero-copy
+pub const ATTRIBUTE: &str = "zero_copy";
+
+/// `repr(C)` layout of a `#[zero_copy]` struct
+#[derive(Debug, Clone)]
+pub struct Layout {
+    /// Struct name
+    pub name: String,
+
+    /// Size in bytes
+    pub size: usize,
+
+    /// Alignment in bytes
+    pub align: usize,
+
+    /// Fields in declaration order
+    pub fields: Vec<FieldLayout>,
+}
+
+/// Placement of one field within a [`Layout`]
+#[derive(Debug, Clone)]
+pub struct FieldLayout {
+    /// Field name
+    pub name: String,
+
+    /// Field type
+    pub type_info: TypeInfo,
+
+    /// Offset from the start of the struct
+    pub offset: usize,
+
+    /// Size in bytes
+    pub size: usize,
+}
+
+/// Whether `struct_def` is marked `#[zero_copy]`
+pub fn is_zero_copy(struct_def: &StructDefinition) -> bool {
+    struct_def.metadata.zero_copy
+}
+
+/// Layout of the `#[zero_copy]` struct named `name`
+///
+/// Fails unless the struct has a padding-free Pod layout, which
+/// [`crate::transform`] checks for every `#[zero_copy]` struct.
+pub fn layout(type_defs: &[TypeDefinition], name: &str) -> Result<Layout> {
+    layout_nested(type_defs, name, &mut Vec::new())
+}
+
+/// `#[zero_copy]` structs in dependency order, nested types before the types containing them
+pub fn ordered_layouts(type_defs: &[TypeDefinition]) -> Result<Vec<Layout>> {
+    let mut ordered: Vec<Layout> = Vec::new();
+    for type_def in type_defs {
+        if let TypeDefinition::Struct(s) = type_def {
+            if is_zero_copy(s) {
+                push_with_dependencies(type_defs, &s.name, &mut ordered)?;
+            }
+        }
+    }
+    Ok(ordered)
+}
+
+fn push_with_dependencies(
+    type_defs: &[TypeDefinition],
+    name: &str,
+    ordered: &mut Vec<Layout>,
+) -> Result<()> {
+    if ordered.iter().any(|l| l.name == name) {
+        return Ok(());
+    }
+    let layout = layout(type_defs, name)?;
+    for field in &layout.fields {
+        if let TypeInfo::UserDefined(nested) = &field.type_info {
+            push_with_dependencies(type_defs, nested, ordered)?;
+        }
+    }
+    ordered.push(layout);
+    Ok(())
+}
+
+fn layout_nested(
+    type_defs: &[TypeDefinition],
+    name: &str,
+    path: &mut Vec<String>,
+) -> Result<Layout> {
+    if path.iter().any(|p| p == name) {
+        return Err(error(format!(
+            "#[zero_copy] struct '{}' contains itself by value",
+            name
+        )));
+    }
+    let struct_def = match type_defs.iter().find(|t| t.name() == name) {
+        Some(TypeDefinition::Struct(s)) if is_zero_copy(s) => s,
+        _ => return Err(error(format!("'{}' is not a #[zero_copy] struct", name))),
+    };
+    path.push(name.to_string());
+
+    let mut offset = 0;
+    let mut align = 1;
+    let mut fields = Vec::new();
+    for field in &struct_def.fields {
+        let (size, field_align) = type_layout(type_defs, &field.type_info, path)
+            .map_err(|reason| error(format!("'{}.{}'{}", name, field.name, reason)))?;
+
+        if offset % field_align != 0 {
+            let padding = field_align - offset % field_align;
+            return Err(error(format!(
+                "#[zero_copy] struct '{}' needs {} byte(s) of padding before '{}' (offset {}, alignment {}); order fields by alignment, largest first, or add u8 padding fields",
+                name, padding, field.name, offset, field_align
+            )));
+        }
+        fields.push(FieldLayout {
+            name: field.name.clone(),
+            type_info: field.type_info.clone(),
+            offset,
+            size,
+        });
+        offset += size;
+        align = align.max(field_align);
+    }
+
+    if offset % align != 0 {
+        return Err(error(format!(
+            "#[zero_copy] struct '{}' needs {} byte(s) of trailing padding to reach its {}-byte alignment; add u8 padding fields",
+            name,
+            align - offset % align,
+            align
+        )));
+    }
+
+    path.pop();
+    Ok(Layout {
+        name: name.to_string(),
+        size: offset,
+        align,
+        fields,
+    })
+}
+
+/// Size and alignment of a field type, or why it has no zero-copy layout
+fn type_layout(
+    type_defs: &[TypeDefinition],
+    type_info: &TypeInfo,
+    path: &mut Vec<String>,
+) -> std::result::Result<(usize, usize), String> {
+    match type_info {
+        TypeInfo::Primitive(primitive) => primitive_layout(primitive).ok_or_else(|| {
+            format!(
+                " is {}, which has no zero-copy layout; use integers, floats, PublicKey or #[zero_copy] structs",
+                primitive
+            )
+        }),
+        TypeInfo::UserDefined(nested) => {
+            let nested =
+                layout_nested(type_defs, nested, path).map_err(|e| format!(": {}", message(&e)))?;
+            Ok((nested.size, nested.align))
+        }
+        TypeInfo::FixedArray(inner, len) => {
+            let (size, align) = type_layout(type_defs, inner, path)?;
+            Ok((size * len, align))
+        }
+        TypeInfo::Array(_) | TypeInfo::Option(_) => {
+            Err(" is a vector or option, which has no zero-copy layout".to_string())
+        }
+        TypeInfo::Map(..) => Err(" is a map, which has no zero-copy layout".to_string()),
+    }
+}
+
+/// Size and `repr(C)` alignment of a Pod primitive
+pub(crate) fn primitive_layout(name: &str) -> Option<(usize, usize)> {
+    Some(match name {
+        "u8" | "i8" => (1, 1),
+        "u16" | "i16" => (2, 2),
+        "u32" | "i32" | "f32" => (4, 4),
+        "u64" | "i64" | "f64" => (8, 8),
+        "u128" | "i128" => (16, 16),
+        "Pubkey" | "PublicKey" => (32, 1),
+        _ => return None,
+    })
+}
+
+fn error(message: String) -> LumosError {
+    LumosError::TypeValidation(message, None)
+}
+
+fn message(error: &LumosError) -> String {
+    match error {
+        LumosError::TypeValidation(message, _) => message.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_lumos_file;
+    use crate::transform::transform_to_ir;
+
+    fn ir(source: &str) -> Result<Vec<TypeDefinition>> {
+        transform_to_ir(parse_lumos_file(source).unwrap())
+    }
+
+    #[test]
+    fn lays_out_fields_in_order() {
+        let ir = ir(r#"
+            #[zero_copy]
+            struct Position { x: i64, y: i64 }
+
+            #[solana]
+            #[account]
+            #[zero_copy]
+            struct Market { owner: PublicKey, best: Position, volume: u64, fee_bps: u16, side: u8, bump: u8, tick: u32 }
+        "#)
+        .unwrap();
+
+        let market = layout(&ir, "Market").unwrap();
+        let offsets: Vec<_> = market
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.offset))
+            .collect();
+        assert_eq!(
+            offsets,
+            [
+                ("owner", 0),
+                ("best", 32),
+                ("volume", 48),
+                ("fee_bps", 56),
+                ("side", 58),
+                ("bump", 59),
+                ("tick", 60)
+            ]
+        );
+        assert_eq!((market.size, market.align), (64, 8));
+
+        let names: Vec<_> = ordered_layouts(&ir)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.name)
+            .collect();
+        assert_eq!(names, ["Position", "Market"]);
+    }
+
+    #[test]
+    fn rejects_padding() {
+        let err = ir("#[zero_copy]\nstruct Pool { bump: u8, amount: u64 }").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("needs 7 byte(s) of padding before 'amount'"));
+
+        let err = ir("#[zero_copy]\nstruct Pool { amount: u64, bump: u8 }").unwrap_err();
+        assert!(err.to_string().contains("7 byte(s) of trailing padding"));
+    }
+
+    #[test]
+    fn rejects_non_pod_fields() {
+        let err = ir("#[zero_copy]\nstruct Pool { open: bool }").unwrap_err();
+        assert!(err.to_string().contains("'Pool.open' is bool"));
+
+        let err = ir("#[zero_copy]\nstruct Pool { fees: [u64] }").unwrap_err();
+        assert!(err.to_string().contains("vector or option"));
+
+        let err =
+            ir("struct Inner { x: u64 }\n#[zero_copy]\nstruct Pool { inner: Inner }").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("'Pool.inner': 'Inner' is not a #[zero_copy] struct"));
+    }
+
+    #[test]
+    fn lays_out_fixed_arrays() {
+        let oracle_ir =
+            ir("#[zero_copy]\nstruct Oracle { prices: [u64; 4], seed: [u8; 32] }").unwrap();
+        let oracle = layout(&oracle_ir, "Oracle").unwrap();
+        assert_eq!((oracle.size, oracle.align), (64, 8));
+        assert_eq!(oracle.fields[1].offset, 32);
+
+        let err = ir("#[zero_copy]\nstruct Oracle { flags: [bool; 2] }").unwrap_err();
+        assert!(err.to_string().contains("'Oracle.flags' is bool"));
+    }
+}
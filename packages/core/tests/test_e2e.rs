@@ -13,8 +13,18 @@ use std::path::PathBuf;
 use std::process::Command;
 use tempfile::TempDir;
 
+/// A valid program id for compile checks of generated Anchor code
+const TEST_PROGRAM_ID: &str = "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS";
+
 /// Test helper to create a temporary Rust project
-fn create_temp_rust_project(name: &str, code: &str) -> (TempDir, PathBuf) {
+///
+/// Anchor code gets `declare_id!` with `program_id`, or with the sentinel
+/// placeholder when none is given.
+fn create_temp_rust_project(
+    name: &str,
+    code: &str,
+    program_id: Option<&str>,
+) -> (TempDir, PathBuf) {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let project_dir = temp_dir.path().join(name);
     fs::create_dir(&project_dir).expect("Failed to create project dir");
@@ -43,12 +53,9 @@ anchor-lang = "0.30"
     // Write lib.rs with declare_id! for Anchor
     // Use a clearly-invalid placeholder by default so generated code does not
     // accidentally compile and get deployed with a junk program id.
-    // Tests that need to compile can provide a valid program id via
-    // the `LUMOS_TEST_ANCHOR_PROGRAM_ID` environment variable.
+    // Tests that need to compile pass a valid program id.
     let lib_code = if code.contains("anchor_lang::prelude") {
-        // Allow tests/CI to override with a real program id for compile checks
-        let program_id = std::env::var("LUMOS_TEST_ANCHOR_PROGRAM_ID")
-            .unwrap_or_else(|_| "REPLACE_WITH_YOUR_PROGRAM_ID".to_string());
+        let program_id = program_id.unwrap_or("REPLACE_WITH_YOUR_PROGRAM_ID");
 
         format!(
             "use anchor_lang::prelude::*;\n\ndeclare_id!(\"{}\");\n\n{}",
@@ -117,17 +124,9 @@ fn test_e2e_gaming_schema_rust_compiles() {
 
     println!("Generated Rust code:\n{}\n", rust_code);
 
-    // When compiling generated Anchor code in tests, provide a valid
-    // program id so compilation succeeds. In normal generation runs the
-    // generated code will contain a sentinel that fails compilation unless
-    // the user provides a real program id.
-    std::env::set_var(
-        "LUMOS_TEST_ANCHOR_PROGRAM_ID",
-        "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS",
-    );
-
     // Create temporary Rust project and try to compile
-    let (_temp_dir, project_dir) = create_temp_rust_project("gaming_schema", &rust_code);
+    let (_temp_dir, project_dir) =
+        create_temp_rust_project("gaming_schema", &rust_code, Some(TEST_PROGRAM_ID));
 
     // Try to compile with cargo check (faster than full build)
     let output = Command::new("cargo")
@@ -149,15 +148,11 @@ fn test_e2e_gaming_schema_rust_compiles() {
 
 #[test]
 fn test_anchor_placeholder_is_sentinel() {
-
-    // Ensure env var is not set so helper uses sentinel
-    std::env::remove_var("LUMOS_TEST_ANCHOR_PROGRAM_ID");
-
     // Minimal code that uses anchor imports
     let code = "use anchor_lang::prelude::*;\n\n#[account]\npub struct X { pub a: u64, }";
 
     // Create temp project via helper
-    let (temp_dir, project_dir) = create_temp_rust_project("anchor_placeholder", code);
+    let (temp_dir, project_dir) = create_temp_rust_project("anchor_placeholder", code, None);
 
     let lib_path = project_dir.join("src").join("lib.rs");
     let lib_contents = std::fs::read_to_string(&lib_path).expect("Failed to read lib.rs");
@@ -184,13 +179,8 @@ fn test_e2e_nft_marketplace_rust_compiles() {
 
     println!("Generated Rust code:\n{}\n", rust_code);
 
-    // Provide a valid program id for CI compilation of Anchor code
-    std::env::set_var(
-        "LUMOS_TEST_ANCHOR_PROGRAM_ID",
-        "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS",
-    );
-
-    let (_temp_dir, project_dir) = create_temp_rust_project("nft_marketplace", &rust_code);
+    let (_temp_dir, project_dir) =
+        create_temp_rust_project("nft_marketplace", &rust_code, Some(TEST_PROGRAM_ID));
 
     let output = Command::new("cargo")
         .arg("check")
@@ -223,7 +213,8 @@ fn test_e2e_defi_staking_rust_compiles() {
 
     let rust_code = rust::generate_module(&ir);
 
-    let (_temp_dir, project_dir) = create_temp_rust_project("defi_staking", &rust_code);
+    let (_temp_dir, project_dir) =
+        create_temp_rust_project("defi_staking", &rust_code, Some(TEST_PROGRAM_ID));
 
     let output = Command::new("cargo")
         .arg("check")
@@ -256,7 +247,8 @@ fn test_e2e_dao_governance_rust_compiles() {
 
     let rust_code = rust::generate_module(&ir);
 
-    let (_temp_dir, project_dir) = create_temp_rust_project("dao_governance", &rust_code);
+    let (_temp_dir, project_dir) =
+        create_temp_rust_project("dao_governance", &rust_code, Some(TEST_PROGRAM_ID));
 
     let output = Command::new("cargo")
         .arg("check")
@@ -361,7 +353,8 @@ fn test_e2e_complete_pipeline() {
     // Note: #[account] provides derives automatically, so we don't generate them
 
     // Compile Rust
-    let (_temp_dir, project_dir) = create_temp_rust_project("test_account", &rust_code);
+    let (_temp_dir, project_dir) =
+        create_temp_rust_project("test_account", &rust_code, Some(TEST_PROGRAM_ID));
     let output = Command::new("cargo")
         .arg("check")
         .arg("--quiet")